@@ -13,7 +13,7 @@ fn main() -> Result<(), GitError> {
 
     let address = format!("{}:{}", config.ip, config.port_daemon);
 
-    let client = Client::new(
+    let mut client = Client::new(
         config.name,
         config.email,
         config.ip,
@@ -22,6 +22,7 @@ fn main() -> Result<(), GitError> {
         config.src,
         config.path_log,
     );
+    client.set_default_branch(config.default_branch);
 
     // let init = is_git_initialized(client.get_directory_path())?;
     // if init.0 {
@@ -1,11 +1,15 @@
 use git::errors::GitError;
 use git::servers::daemon_server::handle_client_daemon;
 use git::servers::http_server::http_connection::handle_client_http;
+use git::servers::http_server::rate_limiter::init_rate_limiter;
 use git::servers::http_server::utils::create_pr_folder;
+use git::servers::http_server::webhook::init_webhooks;
 use git::servers::server::{
     create_listener, initialize_config, start_logging, start_server_thread, wait_for_threads,
 };
+use git::servers::server_config::ServerConfig;
 use std::sync::Arc;
+use std::time::Duration;
 
 use git::consts::DAEMON_SIGNATURE;
 use git::consts::HTPP_SIGNATURE;
@@ -26,23 +30,29 @@ fn main() -> Result<(), GitError> {
     let listener_daemon = create_listener(&config.ip, &config.port_daemon)?;
     let listener_http = create_listener(&config.ip, &config.port_http)?;
 
+    let server_config = Arc::new(ServerConfig::from_config(&config));
     let (shared_tx, log_handle) = start_logging(config.path_log)?;
+    let network_timeout = Duration::from_secs(config.network_timeout);
 
     let clients_daemon_handle = start_server_thread(
         listener_daemon,
         DAEMON_SIGNATURE.to_string(),
         Arc::clone(&shared_tx),
-        config.src.clone(),
+        Arc::clone(&server_config),
         handle_client_daemon,
+        network_timeout,
     )?;
 
     create_pr_folder(&config.src)?;
+    init_rate_limiter(config.rate_limit_refill, config.rate_limit_burst);
+    init_webhooks(config.webhook_urls);
     let clients_http_handle = start_server_thread(
         listener_http,
         HTPP_SIGNATURE.to_string(),
         shared_tx,
-        config.src.clone(),
+        server_config,
         handle_client_http,
+        network_timeout,
     )?;
 
     wait_for_threads(log_handle, clients_daemon_handle, clients_http_handle);
@@ -10,6 +10,9 @@
 
 /// Importa submódulos específicos para los comandos Git.
 pub mod add;
+pub mod apply;
+pub mod archive;
+pub mod blame;
 pub mod branch;
 pub mod cat_file;
 pub mod check_ignore;
@@ -17,20 +20,31 @@ pub mod checkout;
 pub mod clone;
 pub mod commit;
 pub mod config;
+pub mod describe;
 pub mod errors;
 pub mod fetch;
 pub mod fetch_head;
+pub mod gc;
 pub mod hash_object;
 pub mod init;
 pub mod log;
 pub mod ls_files;
+pub mod ls_remote;
 pub mod ls_tree;
 pub mod merge;
+pub mod merge_base;
+pub mod mv;
+pub mod notes;
 pub mod pull;
 pub mod push;
 pub mod rebase;
+pub mod reflog;
 pub mod remote;
+pub mod rev_parse;
 pub mod rm;
+pub mod show;
 pub mod show_ref;
 pub mod status;
+pub mod symbolic_ref;
 pub mod tag;
+pub mod worktree;
@@ -1,12 +1,16 @@
 use crate::consts::*;
 use crate::models::client::Client;
-use crate::util::files::{create_file_replace, open_file, read_file, read_file_string};
+use crate::util::files::{get_file_mode, get_git_dir, read_working_file};
+use crate::util::index::{open_index, parse_index_line, write_index};
+use crate::util::object_format::get_object_format;
 use crate::util::objects::builder_object_blob;
+use crate::util::validation::normalize_path;
 use std::ffi::OsString;
 use std::fs;
 use std::path::Path;
 
-use super::check_ignore::{check_gitignore, get_gitignore_content};
+use super::check_ignore::{check_gitignore, get_gitignore_content_for_path};
+use super::config::{is_ignore_case, same_path};
 use super::errors::CommandsError;
 use super::rm::remove_from_index_with_filename;
 use super::status::is_files_to_delete;
@@ -47,7 +51,7 @@ pub fn git_add_all(directory: &Path, repo_parts: usize) -> Result<String, Comman
         let file_name = entry.file_name();
         let full_path = entry.path();
 
-        if full_path.is_file() {
+        if full_path.is_file() || full_path.is_symlink() {
             add_file(&full_path, &file_name, repo_parts)?;
         } else if full_path.is_dir() {
             let path_str = file_name.to_str().ok_or(CommandsError::PathToStringError)?;
@@ -69,7 +73,7 @@ fn add_file(
     file_name: &OsString,
     repo_parts: usize,
 ) -> Result<(), CommandsError> {
-    let full_path_str = full_path.to_str().ok_or(CommandsError::PathToStringError)?;
+    let full_path_str = normalize_path(full_path.to_str().ok_or(CommandsError::PathToStringError)?);
     let parts: Vec<&str> = full_path_str.split('/').collect();
     let mut directory = String::new();
     let mut count = 0;
@@ -102,24 +106,25 @@ fn add_file(
 /// 'directory': directorio donde estará inicializado el repositorio
 /// 'file_name': Nombre del archivo del cual se leera el contenido para luego comprimirlo y generar el objeto
 pub fn git_add(directory: &str, file_name: &str) -> Result<String, CommandsError> {
+    let file_name = &normalize_path(file_name);
     if !is_files_to_delete(directory, file_name)? {
         let file_path = format!("{}/{}", directory, file_name);
         let mut ignored_files = Vec::<String>::new();
-        let gitignore_content = get_gitignore_content(directory)?;
+        let gitignore_content = get_gitignore_content_for_path(directory, file_name)?;
         check_gitignore(file_name, &mut ignored_files, &gitignore_content)?;
         if !ignored_files.is_empty() {
             let error_format = format!("This file {} is in .gitignore", file_name);
             return Ok(error_format);
         }
-        let file = open_file(&file_path)?;
-        let content = read_file(file)?;
+        let mode = get_file_mode(&file_path);
+        let content = read_working_file(&file_path)?;
 
-        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let git_dir = get_git_dir(directory);
 
-        let hash_object = builder_object_blob(content, &git_dir)?;
+        let hash_object = builder_object_blob(content, &git_dir, get_object_format(&git_dir))?;
 
         // Se actualiza el index.
-        add_to_index(git_dir, file_name, hash_object)?;
+        add_to_index_with_mode(git_dir, file_name, hash_object, mode)?;
     } else {
         remove_from_index_with_filename(directory, file_name)?;
     }
@@ -138,28 +143,63 @@ pub fn add_to_index(
     file_name: &str,
     hash_object: String,
 ) -> Result<(), CommandsError> {
-    let index_path = format!("{}/{}", &git_dir, INDEX);
+    add_to_index_with_mode(git_dir, file_name, hash_object, FILE)
+}
 
-    let index_file = open_file(index_path.as_str())?;
-    let index_content = read_file_string(index_file)?;
+/// Esta función actualiza el index con el archivo indicado guardando también su modo.
+///
+/// Los archivos regulares se guardan con el tipo `blob`; los ejecutables y los links simbólicos
+/// se guardan con su modo numérico (`100755` o `120000`).
+/// ###Parametros:
+/// 'git_dir': directorio donde esta el directory/.git
+/// 'file_name': Nombre del archivo que se le hizo add
+/// 'hash_object': Hash del objeto que se creó al hacer add
+/// 'mode': Modo del archivo, por ejemplo `100644`
+pub fn add_to_index_with_mode(
+    git_dir: String,
+    file_name: &str,
+    hash_object: String,
+    mode: &str,
+) -> Result<(), CommandsError> {
+    let mode = match mode == FILE {
+        true => BLOB,
+        false => mode,
+    };
+    let index_content = open_index(&git_dir)?;
 
-    let mut lines: Vec<String> = index_content.lines().map(String::from).collect();
+    let file_name = &normalize_path(file_name);
+    let mut lines: Vec<String> = index_content.lines().map(normalize_path).collect();
     let mut updated = false;
 
+    // Con core.ignorecase se conserva el nombre con el que el archivo se agregó por primera vez
+    let ignore_case = match Path::new(&git_dir).parent() {
+        Some(directory) => is_ignore_case(&directory.to_string_lossy()),
+        None => false,
+    };
     for line in &mut lines {
-        if line.starts_with(file_name) {
-            *line = format!("{} {} {}", file_name, BLOB, hash_object);
+        let entry_name = match parse_index_line(line) {
+            Some((entry_name, _, _)) => entry_name.to_string(),
+            None => continue,
+        };
+        let same_entry = if ignore_case {
+            same_path(&entry_name, file_name, true)
+        } else {
+            entry_name == *file_name
+        };
+        if same_entry {
+            let entry_name = if ignore_case { &entry_name } else { file_name };
+            *line = format!("{} {} {}", entry_name, mode, hash_object);
             updated = true;
             break;
         }
     }
 
     if !updated {
-        lines.push(format!("{} {} {}", file_name, BLOB, hash_object));
+        lines.push(format!("{} {} {}", file_name, mode, hash_object));
     }
 
     let updated_index_content = lines.join("\n");
-    create_file_replace(index_path.as_str(), updated_index_content.as_str())?;
+    write_index(&git_dir, &updated_index_content)?;
 
     Ok(())
 }
@@ -169,6 +209,7 @@ mod tests {
     use std::io::Write;
 
     use crate::commands::{init::git_init, status::get_index_content};
+    use crate::util::files::create_file_replace;
 
     use super::*;
 
@@ -0,0 +1,477 @@
+use super::errors::CommandsError;
+use crate::models::client::Client;
+use crate::util::diff::DiffLine;
+use crate::util::files::{
+    create_file_replace, delete_file, file_exists, open_file, read_file_string,
+};
+use std::path::{Component, Path};
+
+/// Path que se usa en un diff unificado como origen de los archivos nuevos y como destino de los
+/// eliminados.
+const DEV_NULL: &str = "/dev/null";
+
+/// Hunk de un diff unificado.
+///
+/// - `old_start`: primera línea del hunk en el archivo original, empezando en 1. Si el hunk
+///   no tiene líneas del original, es la línea después de la cual se insertan las nuevas.
+/// - `old_len`/`new_len`: cantidad de líneas del hunk en el archivo original y en el nuevo.
+/// - `lines`: líneas de contexto, eliminadas y agregadas, en orden.
+/// - `old_missing_newline`/`new_missing_newline`: si la última línea del hunk en el archivo
+///   original o en el nuevo no termina en salto de línea (`\ No newline at end of file`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new_len: usize,
+    lines: Vec<DiffLine>,
+    old_missing_newline: bool,
+    new_missing_newline: bool,
+}
+
+/// Cambios de un archivo dentro de un patch.
+///
+/// - `old_path`: path del archivo original, o `None` si el archivo es nuevo.
+/// - `new_path`: path del archivo resultante, o `None` si el archivo se elimina.
+/// - `hunks`: hunks a aplicar, en el orden en que aparecen en el patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FilePatch {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// Path del archivo del working tree al que se aplican los cambios.
+    fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Esta función se encarga de llamar al comando apply con los parametros necesarios.
+///
+/// * `git apply <patch>`: aplica al working tree el diff unificado guardado en el archivo.
+/// * `git apply --check <patch>`: solo verifica que el patch se pueda aplicar, sin escribir.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función apply
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_apply(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    let (patch_path, check_only) = match args.as_slice() {
+        ["--check", patch_path] | [patch_path, "--check"] => (patch_path, true),
+        [patch_path] if !patch_path.starts_with('-') => (patch_path, false),
+        _ => return Err(CommandsError::InvalidArgumentCountApplyError),
+    };
+    let patch = read_file_string(open_file(patch_path)?)?;
+    git_apply(directory, &patch, check_only)?;
+    Ok(String::new())
+}
+
+/// Aplica un diff unificado a los archivos del working tree. Los archivos con origen
+/// `/dev/null` se crean y los que tienen destino `/dev/null` se eliminan.
+///
+/// Cada hunk se aplica en la posición indicada en su encabezado, y sus líneas de contexto y
+/// eliminadas deben coincidir exactamente con las del archivo. Antes de escribir se verifica
+/// el patch completo, por lo que si algún hunk no se puede aplicar no se modifica ningún
+/// archivo.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'patch': contenido del patch
+/// 'check_only': si es true solo se verifica que el patch se pueda aplicar
+///
+/// Devuelve los paths de los archivos modificados, creados o eliminados.
+pub fn git_apply(
+    directory: &str,
+    patch: &str,
+    check_only: bool,
+) -> Result<Vec<String>, CommandsError> {
+    let file_patches = parse_patch(patch)?;
+    let mut results = Vec::new();
+    for file_patch in file_patches.iter() {
+        let path = file_patch.path();
+        let file_path = format!("{}/{}", directory, path);
+        let exists = file_exists(&file_path);
+        // Un archivo nuevo no puede existir y uno modificado o eliminado tiene que existir
+        if exists == file_patch.old_path.is_none() {
+            return Err(CommandsError::PatchDoesNotApply {
+                file: path.to_string(),
+                hunk: 1,
+            });
+        }
+        let content = match exists {
+            true => read_file_string(open_file(&file_path)?)?,
+            false => String::new(),
+        };
+        let new_content = apply_hunks(path, &content, &file_patch.hunks)?;
+        if file_patch.new_path.is_none() && !new_content.is_empty() {
+            return Err(CommandsError::PatchDoesNotApply {
+                file: path.to_string(),
+                hunk: file_patch.hunks.len(),
+            });
+        }
+        results.push((file_path, file_patch, new_content));
+    }
+    let paths = file_patches
+        .iter()
+        .map(|file_patch| file_patch.path().to_string())
+        .collect();
+    if check_only {
+        return Ok(paths);
+    }
+    for (file_path, file_patch, new_content) in results {
+        match file_patch.new_path {
+            Some(_) => create_file_replace(&file_path, &new_content)?,
+            None => delete_file(&file_path)?,
+        }
+    }
+    Ok(paths)
+}
+
+/// Aplica los hunks de un archivo a su contenido.
+/// ###Parametros:
+/// 'path': path del archivo, para informar el error
+/// 'content': contenido original del archivo
+/// 'hunks': hunks a aplicar, ordenados por su posición en el archivo
+///
+/// Devuelve el contenido resultante o `PatchDoesNotApply` con el primer hunk que no coincide.
+/// El resultado termina en salto de línea salvo que el hunk que llega al final del archivo
+/// indique lo contrario; si ningún hunk llega al final se conserva el del archivo original.
+fn apply_hunks(path: &str, content: &str, hunks: &[Hunk]) -> Result<String, CommandsError> {
+    let old_lines: Vec<&str> = content.lines().collect();
+    let old_missing_newline = !content.is_empty() && !content.ends_with('\n');
+    let mut missing_newline = old_missing_newline;
+    let mut new_lines: Vec<&str> = Vec::new();
+    let mut cursor = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let does_not_apply = CommandsError::PatchDoesNotApply {
+            file: path.to_string(),
+            hunk: index + 1,
+        };
+        let start = match hunk.old_len {
+            0 => hunk.old_start,
+            _ => hunk.old_start.saturating_sub(1),
+        };
+        if start < cursor || start + hunk.old_len > old_lines.len() {
+            return Err(does_not_apply);
+        }
+        new_lines.extend_from_slice(&old_lines[cursor..start]);
+        cursor = start;
+        for line in hunk.lines.iter() {
+            match line {
+                DiffLine::Equal(expected) | DiffLine::Removed(expected) => {
+                    if old_lines[cursor] != expected {
+                        return Err(does_not_apply);
+                    }
+                    if let DiffLine::Equal(_) = line {
+                        new_lines.push(old_lines[cursor]);
+                    }
+                    cursor += 1;
+                }
+                DiffLine::Added(added) => new_lines.push(added),
+            }
+        }
+        let reaches_end = cursor == old_lines.len();
+        // El original sólo puede no tener salto de línea al final del archivo
+        if hunk.old_missing_newline && (!reaches_end || !old_missing_newline) {
+            return Err(does_not_apply);
+        }
+        if reaches_end {
+            missing_newline = hunk.new_missing_newline;
+        }
+    }
+    new_lines.extend_from_slice(&old_lines[cursor..]);
+
+    let mut new_content = new_lines.join("\n");
+    if !new_content.is_empty() && !missing_newline {
+        new_content.push('\n');
+    }
+    Ok(new_content)
+}
+
+/// Lee los archivos y hunks de un diff unificado. Las líneas anteriores a cada encabezado
+/// `---`/`+++` (como `diff --git` o `index`) se ignoran.
+/// ###Parametros:
+/// 'patch': contenido del patch
+fn parse_patch(patch: &str) -> Result<Vec<FilePatch>, CommandsError> {
+    let mut file_patches: Vec<FilePatch> = Vec::new();
+    let mut lines = patch.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(old_path) = line.strip_prefix("--- ") {
+            let new_path = match lines.next().and_then(|line| line.strip_prefix("+++ ")) {
+                Some(new_path) => new_path,
+                None => return Err(CommandsError::InvalidPatch(line.to_string())),
+            };
+            file_patches.push(FilePatch {
+                old_path: parse_patch_path(old_path, "a/")?,
+                new_path: parse_patch_path(new_path, "b/")?,
+                hunks: Vec::new(),
+            });
+        } else if line.starts_with("@@ ") {
+            let file_patch = match file_patches.last_mut() {
+                Some(file_patch) => file_patch,
+                None => return Err(CommandsError::InvalidPatch(line.to_string())),
+            };
+            let mut hunk = parse_hunk_header(line)?;
+            let (mut old_remaining, mut new_remaining) = (hunk.old_len, hunk.new_len);
+            while old_remaining > 0 || new_remaining > 0 {
+                let hunk_line = match lines.next() {
+                    Some(hunk_line) => hunk_line,
+                    None => return Err(CommandsError::InvalidPatch(line.to_string())),
+                };
+                // Algunos editores eliminan el espacio de las líneas de contexto vacías
+                let (kind, text) = match hunk_line.chars().next() {
+                    Some(kind) => (kind, &hunk_line[kind.len_utf8()..]),
+                    None => (' ', ""),
+                };
+                let (diff_line, old_count, new_count) = match kind {
+                    ' ' => (DiffLine::Equal(text.to_string()), 1, 1),
+                    '-' => (DiffLine::Removed(text.to_string()), 1, 0),
+                    '+' => (DiffLine::Added(text.to_string()), 0, 1),
+                    '\\' => {
+                        mark_missing_newline(&mut hunk);
+                        continue;
+                    }
+                    _ => return Err(CommandsError::InvalidPatch(hunk_line.to_string())),
+                };
+                if old_remaining < old_count || new_remaining < new_count {
+                    return Err(CommandsError::InvalidPatch(line.to_string()));
+                }
+                old_remaining -= old_count;
+                new_remaining -= new_count;
+                hunk.lines.push(diff_line);
+            }
+            // `\ No newline at end of file` después de la última línea del hunk
+            if lines.peek().is_some_and(|next| next.starts_with('\\')) {
+                lines.next();
+                mark_missing_newline(&mut hunk);
+            }
+            file_patch.hunks.push(hunk);
+        }
+    }
+    if file_patches.is_empty() {
+        return Err(CommandsError::InvalidPatch(
+            patch.lines().next().unwrap_or_default().to_string(),
+        ));
+    }
+    Ok(file_patches)
+}
+
+/// Registra un `\ No newline at end of file`, que se refiere a la línea anterior del hunk: si
+/// es una línea eliminada falta el salto en el archivo original, si es agregada en el nuevo y
+/// si es de contexto en ambos.
+/// ###Parametros:
+/// 'hunk': hunk que se está leyendo
+fn mark_missing_newline(hunk: &mut Hunk) {
+    match hunk.lines.last() {
+        Some(DiffLine::Removed(_)) => hunk.old_missing_newline = true,
+        Some(DiffLine::Added(_)) => hunk.new_missing_newline = true,
+        Some(DiffLine::Equal(_)) => {
+            hunk.old_missing_newline = true;
+            hunk.new_missing_newline = true;
+        }
+        None => {}
+    }
+}
+
+/// Obtiene el path de un encabezado `---`/`+++`, sin el prefijo `a/` o `b/` ni la fecha que
+/// agregan algunas herramientas después de un tab.
+/// ###Parametros:
+/// 'path': path del encabezado
+/// 'prefix': prefijo a quitar
+///
+/// Devuelve `None` si el path es `/dev/null`. Los paths absolutos o con componentes `..`
+/// saldrían del working tree, por lo que se rechazan con `InvalidPatchPath`.
+fn parse_patch_path(path: &str, prefix: &str) -> Result<Option<String>, CommandsError> {
+    let path = path.split('\t').next().unwrap_or_default().trim_end();
+    if path == DEV_NULL {
+        return Ok(None);
+    }
+    let path = path.strip_prefix(prefix).unwrap_or(path);
+    let only_names = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if path.is_empty() || !only_names {
+        return Err(CommandsError::InvalidPatchPath(path.to_string()));
+    }
+    Ok(Some(path.to_string()))
+}
+
+/// Lee el encabezado `@@ -inicio,largo +inicio,largo @@` de un hunk. Si se omite el largo,
+/// es 1.
+/// ###Parametros:
+/// 'header': línea del encabezado
+fn parse_hunk_header(header: &str) -> Result<Hunk, CommandsError> {
+    let invalid = || CommandsError::InvalidPatch(header.to_string());
+    let mut ranges = header.split_whitespace().skip(1);
+    let parse_range = |range: Option<&str>, sign: char| -> Option<(usize, usize)> {
+        let range = range?.strip_prefix(sign)?;
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = parse_range(ranges.next(), '-').ok_or_else(invalid)?;
+    let (_, new_len) = parse_range(ranges.next(), '+').ok_or_else(invalid)?;
+    Ok(Hunk {
+        old_start,
+        old_len,
+        new_len,
+        lines: Vec::new(),
+        old_missing_newline: false,
+        new_missing_newline: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::diff::format_unified_diff;
+    use std::fs;
+
+    fn read(path: &str) -> String {
+        fs::read_to_string(path).expect("Falló al leer el archivo")
+    }
+
+    #[test]
+    fn test_git_apply_modifies_and_deletes_files() {
+        let directory = "./test_apply";
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\nuno\n3\n4\n5\n6\n7\n8\n9\n10\n11\ndoce\n";
+        create_file_replace(&format!("{}/src/n.txt", directory), old)
+            .expect("Falló al crear el archivo");
+        create_file_replace(&format!("{}/viejo.txt", directory), "chau\n")
+            .expect("Falló al crear el archivo");
+        let patch = format!(
+            "diff --git a/src/n.txt b/src/n.txt\nindex 1234567..89abcde 100644\n{}{}",
+            format_unified_diff("a/src/n.txt", "b/src/n.txt", old, new),
+            format_unified_diff("a/viejo.txt", DEV_NULL, "chau\n", "")
+        );
+
+        let result = git_apply(directory, &patch, true);
+        let unchanged = read(&format!("{}/src/n.txt", directory));
+        let result_apply = git_apply(directory, &patch, false);
+        let applied = read(&format!("{}/src/n.txt", directory));
+        let deleted = file_exists(&format!("{}/viejo.txt", directory));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let paths = vec!["src/n.txt".to_string(), "viejo.txt".to_string()];
+        assert_eq!(result, Ok(paths.clone()));
+        assert_eq!(unchanged, old, "--check no debe escribir");
+        assert_eq!(result_apply, Ok(paths));
+        assert_eq!(applied, new);
+        assert!(!deleted);
+    }
+
+    #[test]
+    fn test_git_apply_context_mismatch() {
+        let directory = "./test_apply_mismatch";
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\nuno\n3\n4\n5\n6\n7\n8\n9\n10\n11\ndoce\n";
+        let patch = format!(
+            "{}{}",
+            format_unified_diff(DEV_NULL, "b/nuevo.txt", "", "hola\n"),
+            format_unified_diff("a/n.txt", "b/n.txt", old, new)
+        );
+        // El segundo hunk espera `10` como contexto
+        let current = old.replace("10\n", "diez\n");
+        create_file_replace(&format!("{}/n.txt", directory), &current)
+            .expect("Falló al crear el archivo");
+
+        let result = git_apply(directory, &patch, false);
+        let content = read(&format!("{}/n.txt", directory));
+        let created = file_exists(&format!("{}/nuevo.txt", directory));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            result,
+            Err(CommandsError::PatchDoesNotApply {
+                file: "n.txt".to_string(),
+                hunk: 2
+            })
+        );
+        assert_eq!(content, current);
+        assert!(!created, "No se debe modificar ningún archivo");
+        assert!(git_apply(directory, "no es un patch\n", true).is_err());
+    }
+
+    #[test]
+    fn test_git_apply_creates_file() {
+        let directory = "./test_apply_create";
+        fs::create_dir_all(directory).expect("Falló al crear el directorio");
+        let patch = "--- /dev/null\n+++ b/docs/nuevo.txt\t2024-01-01 00:00:00\n\
+                     @@ -0,0 +1,3 @@\n+hola\n+\n+mundo\n\\ No newline at end of file\n";
+
+        let result = git_apply(directory, patch, false);
+        let content = read(&format!("{}/docs/nuevo.txt", directory));
+        let result_again = git_apply(directory, patch, true);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(result, Ok(vec!["docs/nuevo.txt".to_string()]));
+        assert_eq!(content, "hola\n\nmundo");
+        assert_eq!(
+            result_again,
+            Err(CommandsError::PatchDoesNotApply {
+                file: "docs/nuevo.txt".to_string(),
+                hunk: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_git_apply_end_of_file_newline() {
+        let directory = "./test_apply_newline";
+        fs::create_dir_all(directory).expect("Falló al crear el directorio");
+        let file = format!("{}/a.txt", directory);
+        fs::write(&file, "uno\ndos").expect("Falló al escribir el archivo");
+        let add_newline = "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n uno\n-dos\n\
+                           \\ No newline at end of file\n+tres\n";
+        let remove_newline = "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n uno\n-tres\n+dos\n\
+                              \\ No newline at end of file\n";
+
+        let result_add = git_apply(directory, add_newline, false);
+        let content_add = read(&file);
+        let result_again = git_apply(directory, add_newline, true);
+        let result_remove = git_apply(directory, remove_newline, false);
+        let content_remove = read(&file);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert!(result_add.is_ok());
+        assert_eq!(content_add, "uno\ntres\n");
+        assert!(result_again.is_err());
+        assert!(result_remove.is_ok());
+        assert_eq!(content_remove, "uno\ndos");
+    }
+
+    #[test]
+    fn test_git_apply_rejects_paths_outside_working_tree() {
+        let directory = "./test_apply_outside";
+        fs::create_dir_all(directory).expect("Falló al crear el directorio");
+        let create = |path: &str| format!("--- /dev/null\n+++ {}\n@@ -0,0 +1 @@\n+hola\n", path);
+
+        let parent = git_apply(directory, &create("b/../escape.txt"), false);
+        let absolute = git_apply(directory, &create("/tmp/test_apply_outside.txt"), false);
+        let escaped = file_exists("./escape.txt");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            parent,
+            Err(CommandsError::InvalidPatchPath("../escape.txt".to_string()))
+        );
+        assert_eq!(
+            absolute,
+            Err(CommandsError::InvalidPatchPath(
+                "/tmp/test_apply_outside.txt".to_string()
+            ))
+        );
+        assert!(!escaped);
+        assert!(!file_exists("/tmp/test_apply_outside.txt"));
+    }
+}
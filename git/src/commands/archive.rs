@@ -0,0 +1,428 @@
+use super::cat_file::git_cat_file;
+use super::checkout::get_tree_hash;
+use super::errors::CommandsError;
+use crate::consts::{
+    COMMIT, DIRECTORY, EXECUTABLE_FILE, GIT_DIR, NULL, REFS_HEADS, REFS_TAGS, SPACE, SUBMODULE,
+    SYMLINK, TAR_BLOCK_SIZE, TREE,
+};
+use crate::git_transport::references::{peel_tag, Reference};
+use crate::models::client::Client;
+use crate::util::files::get_git_dir;
+use crate::util::formats::decompression_object_content;
+use crate::util::object_format::get_object_format;
+use crate::util::objects::get_object_path;
+use crate::util::validation::{is_valid_obj_id, is_valid_ref_name};
+use std::fs;
+
+/// Tipos de entrada de un archivo tar.
+const TAR_TYPE_FILE: u8 = b'0';
+const TAR_TYPE_SYMLINK: u8 = b'2';
+const TAR_TYPE_DIRECTORY: u8 = b'5';
+
+/// Esta función se encarga de llamar al comando archive con los parametros necesarios.
+///
+/// * `git archive -o <archivo> <tree-ish>`: exporta el tree indicado a un archivo tar.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función archive
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_archive(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        ["-o", out_path, treeish] | [treeish, "-o", out_path] => {
+            git_archive(directory, treeish, out_path)
+        }
+        _ => Err(CommandsError::InvalidArgumentCountArchiveError),
+    }
+}
+
+/// Exporta el contenido de un tree a un archivo tar en disco.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'treeish': un tree hash, un commit hash, una branch, un tag o HEAD.
+/// 'out_path': ruta del archivo tar a generar.
+pub fn git_archive(
+    directory: &str,
+    treeish: &str,
+    out_path: &str,
+) -> Result<String, CommandsError> {
+    let archive = build_archive(directory, treeish)?;
+    if fs::write(out_path, archive).is_err() {
+        return Err(CommandsError::WriteFileError);
+    }
+    Ok(format!("Archivo {} creado", out_path))
+}
+
+/// Genera en memoria un archivo tar (formato ustar) con el contenido de un tree.
+///
+/// Los subdirectorios se agregan como entradas de directorio, los archivos conservan el bit de
+/// ejecución y los links simbólicos se exportan como tales. Si el tree-ish es un commit, la fecha
+/// de modificación de las entradas es la fecha del commit.
+/// ###Parametros:
+/// 'directory': directorio del repositorio.
+/// 'treeish': un tree hash, un commit hash, una branch, un tag o HEAD.
+pub fn build_archive(directory: &str, treeish: &str) -> Result<Vec<u8>, CommandsError> {
+    let (tree_hash, mtime) = resolve_treeish(directory, treeish)?;
+    let mut archive = Vec::new();
+    append_tree(directory, &tree_hash, "", mtime, &mut archive)?;
+    // El archivo tar termina con dos bloques vacíos
+    archive.extend_from_slice(&[0u8; TAR_BLOCK_SIZE * 2]);
+    Ok(archive)
+}
+
+/// Obtiene el tree al que apunta un tree-ish y la fecha a utilizar en las entradas del tar.
+/// ###Parametros:
+/// 'directory': directorio del repositorio.
+/// 'treeish': un tree hash, un commit hash, una branch, un tag o HEAD.
+fn resolve_treeish(directory: &str, treeish: &str) -> Result<(String, u64), CommandsError> {
+    let hash = if is_valid_obj_id(treeish, get_object_format(&get_git_dir(directory))) {
+        peel_tag(directory, treeish)?
+    } else if !is_valid_ref_name(treeish) {
+        // El nombre se usa como ruta dentro de '.git', por lo que no puede salir de ella
+        return Err(CommandsError::InvalidTreeHashError);
+    } else {
+        let candidates = [
+            treeish.to_string(),
+            format!("{}/{}", REFS_HEADS, treeish),
+            format!("{}/{}", REFS_TAGS, treeish),
+        ];
+        let resolved = candidates.iter().find_map(|ref_path| {
+            let reference = Reference::new("", ref_path).ok()?;
+            reference.resolve(directory).ok()
+        });
+        match resolved {
+            Some(resolved) => resolved.peeled,
+            None => return Err(CommandsError::InvalidTreeHashError),
+        }
+    };
+
+    match git_cat_file(directory, &hash, "-t")?.as_str() {
+        COMMIT => {
+            let content = git_cat_file(directory, &hash, "-p")?;
+            let tree_hash = match get_tree_hash(&content) {
+                Some(tree_hash) => tree_hash.to_string(),
+                None => return Err(CommandsError::InvalidTreeHashError),
+            };
+            Ok((tree_hash, get_commit_time(&content)))
+        }
+        TREE => Ok((hash, 0)),
+        _ => Err(CommandsError::InvalidTreeHashError),
+    }
+}
+
+/// Obtiene la fecha del committer de un commit, o 0 si no se puede leer.
+/// ###Parametros:
+/// 'content': contenido de un commit.
+fn get_commit_time(content: &str) -> u64 {
+    content
+        .lines()
+        .find(|line| line.starts_with("committer "))
+        .and_then(|line| line.split_whitespace().rev().nth(1))
+        .and_then(|time| time.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Agrega al archivo tar las entradas de un tree, recorriendo los sub-tree recursivamente.
+/// ###Parametros:
+/// 'directory': directorio del repositorio.
+/// 'tree_hash': hash del tree a recorrer.
+/// 'base': ruta del tree dentro del archivo, vacía para el tree raíz.
+/// 'mtime': fecha de modificación de las entradas.
+/// 'archive': buffer donde se escribe el archivo tar.
+fn append_tree(
+    directory: &str,
+    tree_hash: &str,
+    base: &str,
+    mtime: u64,
+    archive: &mut Vec<u8>,
+) -> Result<(), CommandsError> {
+    let tree = read_object_data(directory, tree_hash)?;
+    for (mode, name, hash) in parse_tree_entries(&tree)? {
+        let path = format!("{}{}", base, name);
+        match mode.as_str() {
+            DIRECTORY => {
+                let path = format!("{}/", path);
+                let header = tar_header(&path, 0o755, 0, mtime, TAR_TYPE_DIRECTORY, "")?;
+                archive.extend_from_slice(&header);
+                append_tree(directory, &hash, &path, mtime, archive)?;
+            }
+            SYMLINK => {
+                let target = read_object_data(directory, &hash)?;
+                let target = String::from_utf8_lossy(&target);
+                let header = tar_header(&path, 0o777, 0, mtime, TAR_TYPE_SYMLINK, &target)?;
+                archive.extend_from_slice(&header);
+            }
+            // Los submódulos no tienen contenido en este repositorio
+            SUBMODULE => {}
+            _ => {
+                let perm = if mode == EXECUTABLE_FILE {
+                    0o755
+                } else {
+                    0o644
+                };
+                let content = read_object_data(directory, &hash)?;
+                let header = tar_header(&path, perm, content.len(), mtime, TAR_TYPE_FILE, "")?;
+                archive.extend_from_slice(&header);
+                archive.extend_from_slice(&content);
+                let padding = (TAR_BLOCK_SIZE - content.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+                archive.resize(archive.len() + padding, 0);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Lee el contenido de un objeto sin su header, conservando los bytes tal cual están guardados.
+/// ###Parametros:
+/// 'directory': directorio del repositorio.
+/// 'hash': hash del objeto.
+fn read_object_data(directory: &str, hash: &str) -> Result<Vec<u8>, CommandsError> {
+    let path = get_object_path(&format!("{}/{}", directory, GIT_DIR), hash)?;
+    Ok(decompression_object_content(&path)?)
+}
+
+/// Interpreta el contenido de un tree, devolviendo el modo, el nombre y el hash de cada entrada.
+/// ###Parametros:
+/// 'tree': contenido del tree sin su header.
+fn parse_tree_entries(tree: &[u8]) -> Result<Vec<(String, String, String)>, CommandsError> {
+    let mut entries = Vec::new();
+    let mut index = 0;
+    while index < tree.len() {
+        let mode_end = match tree[index..].iter().position(|&byte| byte == SPACE) {
+            Some(pos) => index + pos,
+            None => return Err(CommandsError::InvalidTreeHashError),
+        };
+        let name_end = match tree[mode_end..].iter().position(|&byte| byte == NULL) {
+            Some(pos) => mode_end + pos,
+            None => return Err(CommandsError::InvalidTreeHashError),
+        };
+        if name_end + 21 > tree.len() {
+            return Err(CommandsError::InvalidTreeHashError);
+        }
+        let mode = String::from_utf8_lossy(&tree[index..mode_end]).to_string();
+        let name = String::from_utf8_lossy(&tree[mode_end + 1..name_end]).to_string();
+        let hash: String = tree[name_end + 1..name_end + 21]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        entries.push((mode, name, hash));
+        index = name_end + 21;
+    }
+    Ok(entries)
+}
+
+/// Construye el header ustar de una entrada del archivo tar.
+///
+/// Las rutas de más de 100 bytes se dividen entre los campos `prefix` y `name`.
+/// ###Parametros:
+/// 'path': ruta de la entrada dentro del archivo.
+/// 'mode': permisos de la entrada.
+/// 'size': tamaño del contenido de la entrada.
+/// 'mtime': fecha de modificación.
+/// 'typeflag': tipo de la entrada.
+/// 'linkname': destino de la entrada si es un link simbólico.
+fn tar_header(
+    path: &str,
+    mode: u32,
+    size: usize,
+    mtime: u64,
+    typeflag: u8,
+    linkname: &str,
+) -> Result<[u8; TAR_BLOCK_SIZE], CommandsError> {
+    let (prefix, name) = split_tar_path(path)?;
+    if linkname.len() > 100 {
+        return Err(CommandsError::ArchivePathTooLong(linkname.to_string()));
+    }
+    let mut header = [0u8; TAR_BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], mode as u64);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size as u64);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = typeflag;
+    header[157..157 + linkname.len()].copy_from_slice(linkname.as_bytes());
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[265..269].copy_from_slice(b"root");
+    header[297..301].copy_from_slice(b"root");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // El checksum se calcula con su propio campo lleno de espacios
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+/// Divide una ruta en los campos `prefix` (hasta 155 bytes) y `name` (hasta 100 bytes) del header.
+/// ###Parametros:
+/// 'path': ruta de la entrada dentro del archivo.
+fn split_tar_path(path: &str) -> Result<(&str, &str), CommandsError> {
+    if path.len() <= 100 {
+        return Ok(("", path));
+    }
+    // Se ignora la barra final de los directorios al buscar dónde dividir
+    let trimmed = path.trim_end_matches('/');
+    for (pos, _) in trimmed.match_indices('/') {
+        let (prefix, name) = (&path[..pos], &path[pos + 1..]);
+        if prefix.len() <= 155 && name.len() <= 100 {
+            return Ok((prefix, name));
+        }
+    }
+    Err(CommandsError::ArchivePathTooLong(path.to_string()))
+}
+
+/// Escribe un número en octal en un campo del header, terminado en NUL.
+/// ###Parametros:
+/// 'field': campo del header.
+/// 'value': número a escribir.
+fn write_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{:0width$o}", value, width = width);
+    let start = digits.len().saturating_sub(width);
+    field[..width].copy_from_slice(&digits.as_bytes()[start..]);
+    field[width] = NULL;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{add::git_add, commit::git_commit, init::git_init};
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+    use crate::util::object_format::ObjectFormat;
+    use crate::util::objects::{builder_object_blob, builder_object_tree};
+
+    /// Lee las entradas de un archivo tar: ruta, modo, tipo y contenido.
+    fn read_tar(archive: &[u8]) -> Vec<(String, u32, u8, Vec<u8>)> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while archive[offset..offset + TAR_BLOCK_SIZE]
+            .iter()
+            .any(|&b| b != 0)
+        {
+            let header = &archive[offset..offset + TAR_BLOCK_SIZE];
+            let checksum: u32 = header
+                .iter()
+                .enumerate()
+                .map(|(i, &b)| {
+                    if (148..156).contains(&i) {
+                        32
+                    } else {
+                        b as u32
+                    }
+                })
+                .sum();
+            assert_eq!(read_octal(&header[148..156]), checksum);
+            let field = |range: std::ops::Range<usize>| {
+                let bytes = &header[range];
+                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                String::from_utf8_lossy(&bytes[..end]).to_string()
+            };
+            let (prefix, name) = (field(345..500), field(0..100));
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let size = read_octal(&header[124..136]) as usize;
+            offset += TAR_BLOCK_SIZE;
+            let content = archive[offset..offset + size].to_vec();
+            offset += size.div_ceil(TAR_BLOCK_SIZE) * TAR_BLOCK_SIZE;
+            entries.push((path, read_octal(&header[100..108]), header[156], content));
+        }
+        entries
+    }
+
+    fn read_octal(field: &[u8]) -> u32 {
+        let digits = String::from_utf8_lossy(field);
+        u32::from_str_radix(digits.trim_matches(|c| c == '\0' || c == ' '), 8).unwrap_or(0)
+    }
+
+    #[test]
+    fn test_git_archive_commit() {
+        let directory = "./test_archive_commit";
+        git_init(directory).expect("Error al ejecutar git init");
+        create_file_replace(&format!("{}/README.md", directory), "Hola Mundo")
+            .expect("Falló al crear el archivo");
+        fs::create_dir_all(format!("{}/src/bin", directory)).expect("Falló al crear el directorio");
+        create_file_replace(&format!("{}/src/bin/main.rs", directory), "fn main() {}")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "README.md").expect("Error al ejecutar git add");
+        git_add(directory, "src/bin/main.rs").expect("Error al ejecutar git add");
+        git_commit(directory, test_commit("prueba")).expect("Error al commitear");
+
+        let out_path = format!("{}/export.tar", directory);
+        git_archive(directory, "master", &out_path).expect("Error al ejecutar git archive");
+        let archive = fs::read(&out_path).expect("Falló al leer el archivo");
+        assert_eq!(archive.len() % TAR_BLOCK_SIZE, 0);
+
+        let entries = read_tar(&archive);
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.0.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["README.md", "src/", "src/bin/", "src/bin/main.rs"]
+        );
+        assert_eq!(entries[0].1, 0o644);
+        assert_eq!(entries[0].3, b"Hola Mundo");
+        assert_eq!(entries[1].2, TAR_TYPE_DIRECTORY);
+        assert_eq!(entries[3].3, b"fn main() {}");
+
+        assert!(git_archive(directory, "no-existe", &out_path).is_err());
+        // refs/heads/../../HEAD apuntaría a HEAD desde fuera de refs
+        assert_eq!(
+            build_archive(directory, "../../HEAD"),
+            Err(CommandsError::InvalidTreeHashError)
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_build_archive_modes_and_long_paths() {
+        let directory = "./test_archive_modes";
+        git_init(directory).expect("Error al ejecutar git init");
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+
+        let script = builder_object_blob(
+            b"#!/bin/sh\necho hola\n".to_vec(),
+            &git_dir,
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el blob");
+        let link = builder_object_blob(b"run.sh".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        let long_name = "a".repeat(90);
+        let sub_tree = builder_object_tree(
+            &git_dir,
+            &format!("100644 {} {}\n", long_name, script),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el tree");
+        let long_dir = "b".repeat(60);
+        let tree = builder_object_tree(
+            &git_dir,
+            &format!(
+                "40000 {} {}\n100755 run.sh {}\n120000 start {}\n",
+                long_dir, sub_tree, script, link
+            ),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el tree");
+
+        let archive = build_archive(directory, &tree).expect("Error al generar el archivo");
+        let entries = read_tar(&archive);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].0, format!("{}/", long_dir));
+        assert_eq!(entries[1].0, format!("{}/{}", long_dir, long_name));
+        assert_eq!(entries[1].1, 0o644);
+        assert_eq!(entries[2].0, "run.sh");
+        assert_eq!(entries[2].1, 0o755);
+        assert_eq!(entries[2].3, b"#!/bin/sh\necho hola\n");
+        assert_eq!(entries[3].0, "start");
+        assert_eq!(entries[3].2, TAR_TYPE_SYMLINK);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
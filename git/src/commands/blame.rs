@@ -0,0 +1,242 @@
+use super::branch::get_current_branch;
+use super::cat_file::git_cat_file;
+use super::commit::{get_commits, parse_signature_date, Commit};
+use super::errors::CommandsError;
+use super::show::get_commit_files;
+use crate::consts::GIT_DIR;
+use crate::models::client::Client;
+use crate::util::diff::{diff_lines, DiffLine};
+use crate::util::objects::{abbreviate_hash, get_object_hashes};
+use crate::util::validation::normalize_path;
+use std::collections::BTreeMap;
+
+/// Esta función se encarga de llamar al comando blame con los parametros necesarios.
+///
+/// * `git blame <archivo>`: muestra, para cada línea del archivo, el commit que la introdujo.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función blame
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_blame(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    if args.len() != 1 {
+        return Err(CommandsError::InvalidArgumentCountBlameError);
+    }
+    let directory = client.get_directory_path();
+    git_blame(directory, args[0])
+}
+
+/// Versión de un archivo en un commit y la línea final a la que corresponde cada una de sus
+/// líneas (`None` si la línea no sobrevive hasta la versión de HEAD).
+struct BlameState {
+    hash: String,
+    commit: Commit,
+    path: String,
+    blob: String,
+    lines: Vec<Option<usize>>,
+}
+
+/// Muestra, para cada línea del archivo en HEAD, el commit que la introdujo con el formato
+/// `<hash abreviado> (<autor> <fecha>) <línea>`.
+///
+/// Se recorre la historia de la branch actual del commit más nuevo al más viejo siguiendo los
+/// primeros parents. En cada paso se compara el archivo con su versión en el parent: las líneas
+/// agregadas se atribuyen al commit y las que se mantienen siguen buscándose en el parent. Si el
+/// archivo no existe en el parent con el mismo path, se busca un archivo con el mismo blob para
+/// seguir los renombres.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'path': ruta del archivo, relativa al repositorio
+pub fn git_blame(directory: &str, path: &str) -> Result<String, CommandsError> {
+    let path = normalize_path(path).trim_start_matches("./").to_string();
+    let current_branch = get_current_branch(directory)?;
+    let commits = get_commits(directory, &current_branch)?;
+    let no_such_path = || CommandsError::BlameNoSuchPath(path.to_string());
+
+    let head = commits.first().ok_or_else(no_such_path)?;
+    let head_content = git_cat_file(directory, head, "-p")?;
+    let blob = get_commit_files(directory, &head_content)?
+        .remove(&path)
+        .ok_or_else(no_such_path)?;
+    let final_lines: Vec<String> = git_cat_file(directory, &blob, "-p")?
+        .lines()
+        .map(String::from)
+        .collect();
+
+    let mut state = BlameState {
+        hash: head.to_string(),
+        commit: Commit::from_object(&head_content)?,
+        path,
+        blob,
+        lines: (0..final_lines.len()).map(Some).collect(),
+    };
+    let mut owners: Vec<Option<(String, Commit)>> = vec![None; final_lines.len()];
+
+    // get_commits devuelve la historia en orden: la cadena de primeros parents aparece en el
+    // mismo orden en que se recorre, por lo que el resto de los commits se saltea
+    for hash in commits.iter().skip(1) {
+        if state.commit.get_parents().first() != Some(hash) {
+            continue;
+        }
+        let content = git_cat_file(directory, hash, "-p")?;
+        let files = get_commit_files(directory, &content)?;
+        let parent_path = match files.get(&state.path) {
+            Some(_) => Some(state.path.to_string()),
+            None => find_renamed_path(&files, &state.blob),
+        };
+        let parent_path = match parent_path {
+            Some(parent_path) => parent_path,
+            None => break,
+        };
+        let parent_blob = files[&parent_path].to_string();
+        let parent_lines = if parent_blob == state.blob {
+            state.lines.clone()
+        } else {
+            let old = git_cat_file(directory, &parent_blob, "-p")?;
+            let new = git_cat_file(directory, &state.blob, "-p")?;
+            assign_changed_lines(&old, &new, &state, &mut owners)
+        };
+        state = BlameState {
+            hash: hash.to_string(),
+            commit: Commit::from_object(&content)?,
+            path: parent_path,
+            blob: parent_blob,
+            lines: parent_lines,
+        };
+        if state.lines.iter().all(Option::is_none) {
+            break;
+        }
+    }
+    // Las líneas que siguen pendientes las introdujo el último commit visitado
+    for line in state.lines.iter().flatten() {
+        owners[*line] = Some((state.hash.to_string(), state.commit.clone()));
+    }
+
+    let hashes = get_object_hashes(&format!("{}/{}", directory, GIT_DIR))?;
+    let mut formatted_result = String::new();
+    for (line, owner) in final_lines.iter().zip(owners) {
+        if let Some((hash, commit)) = owner {
+            formatted_result.push_str(&format!(
+                "{} ({} {}) {}\n",
+                abbreviate_hash(&hash, &hashes),
+                commit.get_author_name(),
+                format_blame_date(commit.get_author_date()),
+                line
+            ));
+        }
+    }
+    Ok(formatted_result)
+}
+
+/// Compara la versión del archivo en el parent con la del commit actual. Las líneas agregadas
+/// se atribuyen al commit actual; devuelve, para cada línea del parent, la línea final a la que
+/// corresponde.
+/// ###Parametros:
+/// 'old': contenido del archivo en el parent
+/// 'new': contenido del archivo en el commit actual
+/// 'state': commit actual y líneas finales de cada una de sus líneas
+/// 'owners': commit al que se atribuye cada línea final
+fn assign_changed_lines(
+    old: &str,
+    new: &str,
+    state: &BlameState,
+    owners: &mut [Option<(String, Commit)>],
+) -> Vec<Option<usize>> {
+    let mut parent_lines = Vec::new();
+    let mut new_index = 0;
+    for line in diff_lines(old, new) {
+        match line {
+            DiffLine::Equal(_) => {
+                parent_lines.push(state.lines[new_index]);
+                new_index += 1;
+            }
+            DiffLine::Added(_) => {
+                if let Some(final_line) = state.lines[new_index] {
+                    owners[final_line] = Some((state.hash.to_string(), state.commit.clone()));
+                }
+                new_index += 1;
+            }
+            DiffLine::Removed(_) => parent_lines.push(None),
+        }
+    }
+    parent_lines
+}
+
+/// Busca en los archivos del parent uno con el mismo blob, es decir, el path anterior de un
+/// archivo renombrado sin cambios.
+/// ###Parametros:
+/// 'files': archivos del commit parent con el hash de su blob
+/// 'blob': hash del blob del archivo en el commit actual
+fn find_renamed_path(files: &BTreeMap<String, String>, blob: &str) -> Option<String> {
+    files
+        .iter()
+        .find(|(_, file_blob)| file_blob.as_str() == blob)
+        .map(|(path, _)| path.to_string())
+}
+
+/// Formatea la fecha de una firma (`<timestamp> <zona>`) como `AAAA-MM-DD HH:MM:SS <zona>`
+/// en la zona horaria del autor. Si la fecha no es válida devuelve `unknown`.
+/// ###Parametros:
+/// 'date': fecha con el formato del objeto commit
+fn format_blame_date(date: &str) -> String {
+    match parse_signature_date(date) {
+        Some(date) => date.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{add::git_add, init::git_init, mv::git_mv};
+    use crate::test_utils::{commit_file, commit_index, test_commit};
+    use crate::util::files::create_file_replace;
+    use std::fs;
+
+    #[test]
+    fn test_git_blame() {
+        let directory = "./test_blame";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "uno\ndos\ntres\n");
+        // El segundo commit es de otro autor
+        create_file_replace(&format!("{}/a.txt", directory), "uno\nDOS\ntres\ncuatro\n")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let author = Commit::new(
+            "cambios de Juan".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        let second = commit_index(directory, author);
+        git_mv(directory, "a.txt", "b.txt", false).expect("Falló al mover el archivo");
+        commit_index(directory, test_commit("mover a b.txt"));
+
+        let blame = git_blame(directory, "b.txt").expect("Falló al ejecutar blame");
+        let lines: Vec<&str> = blame.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with(&format!("{} (Valen ", &first[..7])));
+        assert!(lines[0].ends_with(" -0300) uno"));
+        assert!(lines[1].starts_with(&format!("{} (Juan ", &second[..7])));
+        assert!(lines[1].ends_with(") DOS"));
+        assert!(lines[2].starts_with(&first[..7]));
+        assert!(lines[3].starts_with(&second[..7]));
+        assert!(lines[3].ends_with(") cuatro"));
+
+        assert_eq!(
+            git_blame(directory, "a.txt"),
+            Err(CommandsError::BlameNoSuchPath("a.txt".to_string()))
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_format_blame_date() {
+        assert_eq!(
+            format_blame_date("1701632634 -0300"),
+            "2023-12-03 16:43:54 -0300"
+        );
+        assert_eq!(format_blame_date("invalida -0300"), "unknown");
+    }
+}
@@ -1,20 +1,37 @@
+use super::cat_file::git_cat_file;
+use super::commit::Commit;
+use super::config::GitConfig;
 use super::errors::CommandsError;
+use super::reflog::{append_reflog, delete_reflog};
+use super::show_ref::get_all_references;
+use super::symbolic_ref::{read_head, SymbolicRef};
 use crate::consts::*;
+use crate::git_transport::references::{read_packed_refs, ReferenceType};
 use crate::models::client::Client;
-use crate::util::files::{create_file, open_file, read_file, read_file_string};
+use crate::util::files::{create_file, get_common_git_dir, open_file, read_file, read_file_string};
+use crate::util::validation::valid_ref_name;
 use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 /// Esta función se encarga de llamar al comando branch con los parametros necesarios
+///
+/// * `git branch` o `git branch -l`: lista las branches locales.
+/// * `git branch -r`: lista las branches remotas.
+/// * `git branch -a`: lista las branches locales y las remotas.
+/// * `git branch <nombre>`: crea una branch.
+/// * `git branch -d <nombre>`: elimina una branch.
+///
 /// ###Parametros:
 /// 'args': Vector de Strings que contiene los argumentos que se le pasaran al comando branch
 /// 'client': Cliente que contiene el directorio del repositorio local
 pub fn handle_branch(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
     let directory = client.get_directory_path();
-    if !args.is_empty() && args[0] == "-l" {
+    if args.is_empty() || args[0] == "-l" || args[0] == "--list" {
         git_branch_list(directory)
+    } else if args == ["-r"] {
+        git_branch_list_remotes(directory)
+    } else if args == ["-a"] {
+        git_branch_list_all(directory)
     } else if args.len() == 1 && args[0] != "-d" {
         git_branch_create(directory, args[0])
     } else if (args.len() == 2 && args[0] == "-d") || (args.len() == 2 && args[0] == "-D") {
@@ -24,25 +41,6 @@ pub fn handle_branch(args: Vec<&str>, client: Client) -> Result<String, Commands
     }
 }
 
-/// Obtiene el hash parent del commit
-/// ###Parametros:
-/// 'commit': Commit a buscar el parent
-pub fn get_doble_parent_hashes(commit: String) -> String {
-    let mut hash_parent = PARENT_INITIAL;
-    let mut x = 0;
-    for line in commit.lines() {
-        if line.starts_with("parent ") {
-            x += 1;
-            if x == 1 {
-                if let Some(hash) = line.strip_prefix("parent ") {
-                    hash_parent = hash;
-                }
-            }
-        }
-    }
-    hash_parent.to_string()
-}
-
 /// Obtiene el hash parent del commit
 /// ###Parametros:
 /// 'commit': Commit a buscar el parent
@@ -76,13 +74,25 @@ pub fn get_branch_remote_current_hash(
     Ok(hash)
 }
 
-/// Devuelve el hash de la branch recibida por parametro.
+/// Devuelve el hash de la branch recibida por parametro. Si la branch no está suelta en
+/// .git/refs/heads se busca en `packed-refs`.
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 /// 'branch': nombre de la branch a obtener el hash.
 pub fn get_branch_current_hash(directory: &str, branch: String) -> Result<String, CommandsError> {
     let dir_branch = format!("{}/{}/{}/heads/{}", directory, GIT_DIR, REFS, branch);
-    let file = open_file(&dir_branch)?;
+    let file = match open_file(&dir_branch) {
+        Ok(file) => file,
+        Err(error) => {
+            let git_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+            let ref_path = format!("{}/{}", REFS_HEADS, branch);
+            return read_packed_refs(&git_dir)
+                .into_iter()
+                .find(|packed| *packed.reference.get_ref_path() == ref_path)
+                .map(|packed| packed.reference.get_hash().to_string())
+                .ok_or(error.into());
+        }
+    };
     let hash = read_file_string(file)?;
 
     Ok(hash)
@@ -92,24 +102,8 @@ pub fn get_branch_current_hash(directory: &str, branch: String) -> Result<String
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 pub fn get_current_branch(directory: &str) -> Result<String, CommandsError> {
-    let head_path = format!("{}/{}/HEAD", directory, GIT_DIR);
-    let head_file = match File::open(head_path) {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::BranchDirectoryOpenError),
-    };
-
-    let reader = BufReader::new(head_file);
-    let mut branch = String::new();
-    for line in reader.lines() {
-        let line = match line {
-            Ok(line) => line,
-            Err(_) => return Err(CommandsError::BranchFileReadError),
-        };
-        let line_split: Vec<&str> = line.split('/').collect();
-        branch = line_split[line_split.len() - 1].to_string();
-    }
-
-    Ok(branch)
+    let head = read_head(directory)?;
+    Ok(head.get_branch_name()?.to_string())
 }
 
 /// Muestra en una etiqueta las branches existentes.
@@ -127,44 +121,185 @@ pub fn git_branch_list_display(directory: &str) -> Result<String, CommandsError>
     Ok(formatted_branches)
 }
 
-/// Muestra por pantalla las branches existentes.
+/// Muestra por pantalla las branches locales ordenadas por nombre, marcando la actual con
+/// `*`. Cada branch se muestra con el hash abreviado de su último commit, su branch remota
+/// configurada (si tiene) y la primera línea del mensaje del commit.
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 pub fn git_branch_list(directory: &str) -> Result<String, CommandsError> {
-    let branches = get_branch(directory)?;
-    let current_branch = get_current_branch(directory)?;
+    let head = read_head(directory)?;
     let mut formatted_branches = String::new();
-    for branch in branches {
-        if branch == current_branch {
-            formatted_branches.push_str(&format!(" * {}\n", branch))
-        } else {
-            formatted_branches.push_str(&format!("    {}\n", branch))
-        }
+    if let SymbolicRef::Detached(hash) = &head {
+        formatted_branches.push_str(&format!(" * (HEAD detached at {})\n", &hash[..7]));
     }
+    let lines = get_local_branch_lines(directory, &head, "")?;
+    formatted_branches.push_str(&format_branch_lines(directory, &lines));
+    Ok(formatted_branches)
+}
 
+/// Muestra por pantalla las branches remotas (`refs/remotes`) ordenadas por nombre, con el
+/// hash abreviado y la primera línea del mensaje de su último commit.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+pub fn git_branch_list_remotes(directory: &str) -> Result<String, CommandsError> {
+    let lines = get_remote_branch_lines(directory, "")?;
+    Ok(format_branch_lines(directory, &lines))
+}
+
+/// Muestra por pantalla las branches locales seguidas de las remotas, estas últimas con el
+/// prefijo `remotes/`.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+pub fn git_branch_list_all(directory: &str) -> Result<String, CommandsError> {
+    let head = read_head(directory)?;
+    let mut formatted_branches = String::new();
+    if let SymbolicRef::Detached(hash) = &head {
+        formatted_branches.push_str(&format!(" * (HEAD detached at {})\n", &hash[..7]));
+    }
+    let mut lines = get_local_branch_lines(directory, &head, "")?;
+    lines.extend(get_remote_branch_lines(directory, "remotes/")?);
+    formatted_branches.push_str(&format_branch_lines(directory, &lines));
     Ok(formatted_branches)
 }
 
-/// Copia el log de la branch actual a la nueva branch.
+/// Branch a mostrar en el listado de `git branch`.
+///
+/// - `name`: nombre con el que se muestra la branch.
+/// - `hash`: hash del último commit, o la branch a la que apunta si es simbólica.
+/// - `current`: si es la branch actual.
+/// - `upstream`: branch remota configurada, sin el prefijo `refs/remotes/`.
+/// - `symbolic`: si la branch es una referencia simbólica, como `origin/HEAD`.
+struct BranchLine {
+    name: String,
+    hash: String,
+    current: bool,
+    upstream: Option<String>,
+    symbolic: bool,
+}
+
+/// Devuelve las branches locales ordenadas por nombre.
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
-/// 'current_branch': Nombre de la branch actual.
-/// 'branch_name': Nombre de la branch a crear.
-pub fn copy_log(
+/// 'head': HEAD del repositorio, para marcar la branch actual; en detached HEAD no se marca
+/// ninguna.
+/// 'prefix': prefijo con el que se muestra el nombre de cada branch.
+fn get_local_branch_lines(
     directory: &str,
-    current_branch: &str,
-    branch_name: &str,
-) -> Result<(), CommandsError> {
-    let current_branch_log_path = format!(
-        "{}/{}/logs/refs/heads/{}",
-        directory, GIT_DIR, current_branch
-    );
-    let new_branch_log_path = format!("{}/{}/logs/refs/heads/{}", directory, GIT_DIR, branch_name);
-    let file_log_branch = open_file(&current_branch_log_path)?;
-    let content_log_current_branch = read_file_string(file_log_branch)?;
-    create_file(&new_branch_log_path, &content_log_current_branch)?;
+    head: &SymbolicRef,
+    prefix: &str,
+) -> Result<Vec<BranchLine>, CommandsError> {
+    let current_branch = head.get_branch_name().unwrap_or_default();
+    let config = GitConfig::new_from_file(directory).ok();
+    let mut branches = get_branch(directory)?;
+    branches.sort();
+    Ok(branches
+        .into_iter()
+        .map(|branch| {
+            let hash = get_branch_current_hash(directory, branch.to_string()).unwrap_or_default();
+            let upstream = config
+                .as_ref()
+                .and_then(|config| config.get_remote_branch_ref(&branch))
+                .map(|upstream| {
+                    let remote_prefix = format!("{}/", REFS_REMOTES);
+                    upstream
+                        .strip_prefix(&remote_prefix)
+                        .unwrap_or(&upstream)
+                        .to_string()
+                });
+            BranchLine {
+                name: format!("{}{}", prefix, branch),
+                hash: hash.trim().to_string(),
+                current: branch == current_branch,
+                upstream,
+                symbolic: false,
+            }
+        })
+        .collect())
+}
+
+/// Devuelve las branches remotas, sueltas y empaquetadas, ordenadas por nombre.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'prefix': prefijo con el que se muestra el nombre de cada branch.
+fn get_remote_branch_lines(
+    directory: &str,
+    prefix: &str,
+) -> Result<Vec<BranchLine>, CommandsError> {
+    let remote_prefix = format!("{}/", REFS_REMOTES);
+    Ok(get_all_references(directory)?
+        .into_iter()
+        .filter(|reference| reference.get_type() == ReferenceType::Remote)
+        .map(|reference| {
+            let name = reference.get_ref_path();
+            let name = name.strip_prefix(&remote_prefix).unwrap_or(name);
+            let hash = reference.get_hash();
+            // `refs/remotes/origin/HEAD` suele apuntar a otra branch remota
+            let (hash, symbolic) = match SymbolicRef::parse(hash) {
+                Ok(SymbolicRef::Ref(target)) => {
+                    let target = target.strip_prefix(&remote_prefix).unwrap_or(&target);
+                    (format!("{}{}", prefix, target), true)
+                }
+                _ => (hash.trim().to_string(), false),
+            };
+            BranchLine {
+                name: format!("{}{}", prefix, name),
+                hash,
+                current: false,
+                upstream: None,
+                symbolic,
+            }
+        })
+        .collect())
+}
+
+/// Formatea el listado de branches, alineando los hashes según el nombre más largo.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'lines': branches a mostrar.
+fn format_branch_lines(directory: &str, lines: &[BranchLine]) -> String {
+    let width = lines.iter().map(|line| line.name.len()).max().unwrap_or(0);
+    let mut formatted_branches = String::new();
+    for line in lines {
+        let marker = if line.current { " * " } else { "    " };
+        if line.symbolic {
+            formatted_branches.push_str(&format!("{}{} -> {}\n", marker, line.name, line.hash));
+            continue;
+        }
+        let short_hash = line.hash.get(..7).unwrap_or(&line.hash);
+        let mut details = short_hash.to_string();
+        if let Some(upstream) = &line.upstream {
+            details.push_str(&format!(" [{}]", upstream));
+        }
+        let subject = get_commit_subject(directory, &line.hash);
+        if !subject.is_empty() {
+            details.push_str(&format!(" {}", subject));
+        }
+        formatted_branches.push_str(&format!(
+            "{}{:width$} {}\n",
+            marker,
+            line.name,
+            details,
+            width = width
+        ));
+    }
+    formatted_branches
+}
 
-    Ok(())
+/// Devuelve la primera línea del mensaje de un commit, o un string vacío si el commit no se
+/// puede leer.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'hash': hash del commit.
+fn get_commit_subject(directory: &str, hash: &str) -> String {
+    match git_cat_file(directory, hash, "-p").and_then(|content| Commit::from_object(&content)) {
+        Ok(commit) => commit
+            .get_message()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        Err(_) => String::new(),
+    }
 }
 
 /// Crea una nueva branch si no existe.
@@ -173,28 +308,43 @@ pub fn copy_log(
 /// 'branch_name': Nombre de la branch a crear.
 /// 'commit_hash': Contiene el hash del ultimo commit.
 pub fn git_branch_create(directory: &str, branch_name: &str) -> Result<String, CommandsError> {
+    valid_ref_name(branch_name)?;
     let branches = get_branch(directory)?;
     if branches.contains(&branch_name.to_string()) {
         return Err(CommandsError::BranchAlreadyExistsError);
     }
-    let current_branch = get_current_branch(directory)?;
-    let branch_current_path = format!("{}/{}/{}/{}", directory, GIT_DIR, REF_HEADS, current_branch);
-    if fs::metadata(&branch_current_path).is_err() {
-        return Err(CommandsError::BranchNotFoundError);
-    }
-    let file_current_branch = open_file(&branch_current_path)?;
-    let hash_current_branch = read_file(file_current_branch)?;
+    // En estado detached HEAD la branch se crea desde el commit al que apunta HEAD
+    let (current_branch, commit_current_branch) = match read_head(directory)? {
+        SymbolicRef::Detached(hash) => (HEAD.to_string(), hash),
+        head => {
+            let current_branch = head.get_branch_name()?.to_string();
+            let branch_current_path =
+                format!("{}/{}/{}/{}", directory, GIT_DIR, REF_HEADS, current_branch);
+            if fs::metadata(&branch_current_path).is_err() {
+                return Err(CommandsError::BranchNotFoundError);
+            }
+            let file_current_branch = open_file(&branch_current_path)?;
+            let hash_current_branch = read_file(file_current_branch)?;
 
-    let commit_current_branch = match String::from_utf8(hash_current_branch) {
-        Ok(commit_current_branch) => commit_current_branch,
-        Err(_) => return Err(CommandsError::GenericError),
+            match String::from_utf8(hash_current_branch) {
+                Ok(commit_current_branch) => (current_branch, commit_current_branch),
+                Err(_) => return Err(CommandsError::GenericError),
+            }
+        }
     };
     // Crear un nuevo archivo en .git/refs/heads/ con el nombre de la rama y el contenido es el hash del commit actual.
     let branch_path = format!("{}/{}/{}/{}", directory, GIT_DIR, REF_HEADS, branch_name);
 
     create_file(branch_path.as_str(), commit_current_branch.as_str())?;
 
-    copy_log(directory, &current_branch, branch_name)?;
+    append_reflog(
+        directory,
+        &format!("{}/{}", REF_HEADS, branch_name),
+        ZERO_ID,
+        &commit_current_branch,
+        REFLOG_DEFAULT_IDENTITY,
+        &format!("branch: Created from {}", current_branch),
+    )?;
 
     let response = format!("Branch {} created", branch_name);
 
@@ -283,6 +433,7 @@ pub fn git_branch_delete(directory: &str, branch_name: &str) -> Result<String, C
     if fs::remove_file(branch_path).is_err() {
         return Err(CommandsError::DeleteBranchError);
     }
+    delete_reflog(directory, &format!("{}/{}", REF_HEADS, branch_name))?;
 
     let response = format!("Branch {} deleted", branch_name);
     Ok(response)
@@ -291,7 +442,10 @@ pub fn git_branch_delete(directory: &str, branch_name: &str) -> Result<String, C
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::init::git_init;
+    use crate::commands::{
+        add::git_add, checkout::git_checkout, commit::git_commit, init::git_init,
+        rev_parse::git_rev_parse,
+    };
     use crate::util::files::create_file_replace;
     use std::fs;
 
@@ -309,14 +463,85 @@ mod tests {
             .expect("Falló al crear el archivo que contiene la branch");
 
         let result = git_branch_list(directory);
-        let test_branch = "    test_branch\n";
-        let master_branch = " * master\n";
 
         fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
 
-        assert!(result.is_ok());
-        assert!(result.as_ref().unwrap().contains(test_branch));
-        assert!(result.unwrap().contains(master_branch));
+        assert_eq!(
+            result,
+            Ok(" * master      12345\n    test_branch 54321\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_git_branch_list_remotes_and_upstream() {
+        let directory = "./test_git_branch_list_remotes";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "uno").expect("Falló al crear");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let commit = Commit::new(
+            "primer commit\n\ndetalle".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(directory, commit).expect("Falló el commit");
+        let hash = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        let short = &hash[..7];
+        git_branch_create(directory, "zeta").expect("Falló al crear la rama");
+        git_branch_create(directory, "alpha").expect("Falló al crear la rama");
+
+        let remotes = format!("{}/{}/{}/origin", directory, GIT_DIR, REFS_REMOTES);
+        fs::create_dir_all(&remotes).expect("Falló al crear el directorio");
+        create_file_replace(&format!("{}/master", remotes), &hash).expect("Falló al crear");
+        create_file_replace(
+            &format!("{}/HEAD", remotes),
+            "ref: refs/remotes/origin/master\n",
+        )
+        .expect("Falló al crear");
+        let mut config = GitConfig::new_from_file(directory).expect("Falló la configuración");
+        config
+            .add_remote("origin", "git://127.0.0.1:9418/repo")
+            .expect("Falló al agregar el remoto");
+        config
+            .add_branch("master", "origin", "refs/heads/master")
+            .expect("Falló al agregar la branch");
+        config
+            .write_to_file(&format!("{}/{}/config", directory, GIT_DIR))
+            .expect("Falló al escribir la configuración");
+
+        let local = git_branch_list(directory);
+        let remote = git_branch_list_remotes(directory);
+        let all = git_branch_list_all(directory);
+        git_checkout(directory, &hash).expect("Falló el checkout");
+        let detached = git_branch_list(directory);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            local,
+            Ok(format!(
+                "    alpha  {0} primer commit\n * master {0} [origin/master] primer commit\n    zeta   {0} primer commit\n",
+                short
+            ))
+        );
+        assert_eq!(
+            remote,
+            Ok(format!(
+                "    origin/HEAD -> origin/master\n    origin/master {} primer commit\n",
+                short
+            ))
+        );
+        assert_eq!(
+            all,
+            Ok(format!(
+                "    alpha                 {0} primer commit\n * master                {0} [origin/master] primer commit\n    zeta                  {0} primer commit\n    remotes/origin/HEAD -> remotes/origin/master\n    remotes/origin/master {0} primer commit\n",
+                short
+            ))
+        );
+        let detached = detached.expect("Falló al listar las branches");
+        assert!(detached.starts_with(&format!(" * (HEAD detached at {})\n    alpha", short)));
+        assert!(!detached.contains(" * master"));
     }
 
     #[test]
@@ -328,21 +553,22 @@ mod tests {
         create_file(current_branch_path.as_str(), "12345")
             .expect("Falló al crear el archivo que contiene la branch");
 
-        let logs_dir = format!("{}/{}/logs/refs/heads", directory, GIT_DIR);
-        fs::create_dir_all(logs_dir).expect("Falló al crear el directorio de logs");
-
-        let current_branch_log_path =
-            format!("{}/{}/logs/refs/heads/{}", directory, GIT_DIR, "master");
-        create_file(current_branch_log_path.as_str(), "12345")
-            .expect("Falló al crear el archivo que contiene la branch");
-
         let result = git_branch_create(directory, "test_new_branch");
         let result_branch = format!("Branch {} created", "test_new_branch");
+        let result_invalid = git_branch_create(directory, "../../config");
+        let new_branch_log_path = format!(
+            "{}/{}/logs/refs/heads/{}",
+            directory, GIT_DIR, "test_new_branch"
+        );
+        let reflog = fs::read_to_string(new_branch_log_path).unwrap_or_default();
 
         fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), result_branch);
+        assert!(result_invalid.is_err());
+        assert!(reflog.starts_with(&format!("{} 12345 {}", ZERO_ID, REFLOG_DEFAULT_IDENTITY)));
+        assert!(reflog.ends_with("\tbranch: Created from master\n"));
     }
 
     #[test]
@@ -376,14 +602,6 @@ mod tests {
         create_file(current_branch_path.as_str(), "12345")
             .expect("Falló al crear el archivo que contiene la branch");
 
-        let logs_dir = format!("{}/{}/logs/refs/heads", directory, GIT_DIR);
-        fs::create_dir_all(logs_dir).expect("Falló al crear el directorio de logs");
-
-        let current_branch_log_path =
-            format!("{}/{}/logs/refs/heads/{}", directory, GIT_DIR, "master");
-        create_file(current_branch_log_path.as_str(), "12345")
-            .expect("Falló al crear el archivo que contiene la branch");
-
         git_branch_create(directory, "test_branch3").expect("Falló al crear la rama");
         let head_file = format!("{}/{}/HEAD", directory, GIT_DIR);
         create_file_replace(head_file.as_str(), "ref: /refs/heads/test_branch3")
@@ -396,6 +614,30 @@ mod tests {
         assert_eq!(result, Ok("test_branch3".to_string()));
     }
 
+    #[test]
+    fn test_get_branch_current_hash_packed() {
+        let directory = "./test_get_branch_current_hash_packed";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        let hash = "ebc52673798d1baf34d9c8b13022c745bac28880";
+        let packed_refs = format!("{}/{}/{}", directory, GIT_DIR, PACKED_REFS);
+        create_file(
+            &packed_refs,
+            &format!(
+                "# pack-refs with: peeled fully-peeled sorted \n{} refs/heads/feature/x\n",
+                hash
+            ),
+        )
+        .expect("Falló al crear el archivo packed-refs");
+
+        let packed = get_branch_current_hash(directory, "feature/x".to_string());
+        let missing = get_branch_current_hash(directory, "otra".to_string());
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(packed, Ok(hash.to_string()));
+        assert!(missing.is_err());
+    }
+
     #[test]
     fn test_parent() {
         let commit = "tree 4abc6b1e60a965f4dfefbc673322f1c6e98c8b08\nparent ebc52673798d1baf34d9c8b13022c745bac28880\nauthor S2JuanS2 <juansdelrio@hotmail.com> 1701632634 -0300\ncommitter S2JuanS2 <juansdelrio@hotmail.com> 1701632634 -0300\n\ncarpeta\n";
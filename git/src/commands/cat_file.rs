@@ -1,15 +1,35 @@
 use crate::consts::*;
+use crate::errors::GitError;
 use crate::models::client::Client;
-use crate::util::formats::decompression_object;
+use crate::util::errors::UtilError;
+use crate::util::formats::{decompression_object, decompression_object_prefix};
+use crate::util::object_format::{get_object_format, ObjectFormat};
 use crate::util::objects::*;
 
 use super::errors::CommandsError;
+use std::io::{self, BufRead};
+use std::path::Path;
 
 /// Esta función se encarga de llamar a al comando cat-file con los parametros necesarios
+///
+/// * `git cat-file -t <hash>`: muestra el tipo del objeto.
+/// * `git cat-file -s <hash>`: muestra el tamaño del contenido del objeto, sin el header.
+/// * `git cat-file -p <hash>`: muestra el contenido del objeto.
+/// * `git cat-file --batch-check [<hash>...]`: muestra `<hash> <tipo> <tamaño>` por cada hash
+///   recibido, o `<hash> missing` si el objeto no existe. Sin hashes, los lee de stdin.
+///
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función cat-file
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_cat_file(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    if args.first() == Some(&"--batch-check") {
+        let directory = client.get_directory_path();
+        if args.len() > 1 {
+            let hashes = args[1..].join("\n");
+            return git_cat_file_batch_check(directory, hashes.as_bytes());
+        }
+        return git_cat_file_batch_check(directory, io::stdin().lock());
+    }
     if args.len() != 2 {
         return Err(CommandsError::InvalidArgumentCountCatFileError);
     }
@@ -25,7 +45,12 @@ pub fn handle_cat_file(args: Vec<&str>, client: Client) -> Result<String, Comman
 /// ###Parametros:
 /// 'bytes': Vector de bytes que contiene el contenido del objeto
 /// 'type_object': Tipo de objeto que se va a leer
-pub fn git_cat_file_p(bytes: Vec<u8>, type_object: String) -> Result<String, CommandsError> {
+/// 'object_format': Formato de los objetos del repositorio, para leer los hashes de un tree
+pub fn git_cat_file_p(
+    bytes: Vec<u8>,
+    type_object: String,
+    object_format: ObjectFormat,
+) -> Result<String, CommandsError> {
     let mut content = String::new();
 
     if type_object == BLOB {
@@ -33,7 +58,7 @@ pub fn git_cat_file_p(bytes: Vec<u8>, type_object: String) -> Result<String, Com
     } else if type_object == COMMIT {
         content = read_commit(&bytes)?;
     } else if type_object == TREE {
-        content = read_tree(&bytes)?;
+        content = read_tree(&bytes, object_format)?;
     } else if type_object == TAG {
         content = read_tag(&bytes)?;
     }
@@ -44,26 +69,61 @@ pub fn git_cat_file_p(bytes: Vec<u8>, type_object: String) -> Result<String, Com
 /// Esta función se utiliza para mostrar el contenido o información sobre los objetos (archivos, commits, etc.)
 /// ###Parametros:
 /// 'directory': dirección donde se encuentra inicializado el repositorio.
-/// 'object_hash': Valor hash de 40 caracteres (SHA-1) del objeto a leer.
+/// 'object_hash': Valor hash del objeto a leer, de 40 caracteres (SHA-1) o 64 (SHA-256).
 pub fn git_cat_file(
     directory: &str,
     object_hash: &str,
     flag: &str,
 ) -> Result<String, CommandsError> {
-    if object_hash.len() != 40 {
+    git_cat_file_typed(directory, object_hash, flag, None)
+}
+
+/// Igual que `git_cat_file`, pero si se indica el tipo esperado verifica que el objeto sea de
+/// ese tipo antes de leerlo. Si no lo es devuelve `GitError::ObjectTypeMismatch`, así quien
+/// espera un commit o un tree no interpreta el contenido de otro tipo de objeto.
+/// ###Parametros:
+/// 'directory': dirección donde se encuentra inicializado el repositorio.
+/// 'object_hash': Valor hash del objeto a leer, de 40 caracteres (SHA-1) o 64 (SHA-256).
+/// 'flag': `-t`, `-s` o `-p`.
+/// 'expected_type': Tipo de objeto esperado (`commit`, `tree`, `blob` o `tag`), si se conoce.
+pub fn git_cat_file_typed(
+    directory: &str,
+    object_hash: &str,
+    flag: &str,
+    expected_type: Option<&str>,
+) -> Result<String, CommandsError> {
+    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    let object_format = get_object_format(&git_dir);
+    if object_hash.len() != object_format.hex_len() {
         return Err(CommandsError::HashObjectInvalid);
     }
-    //Lee los primeros 2 digitos del hash contenidos en el nombre de la carpeta.
-    let path = format!("{}/{}/objects/{}", directory, GIT_DIR, &object_hash[..2]);
-    //Lee los demás digitos del hash contenidos en el nombre del archivo.
-    let file_path = format!("{}/{}", path, &object_hash[2..]);
+    //Busca el objeto en el repositorio o en sus alternates.
+    let file_path = get_object_path(&git_dir, object_hash)?;
+    // En un clone parcial los objetos omitidos se descargan más adelante
+    if !Path::new(&file_path).is_file() && is_promisor_repository(directory) {
+        return Err(UtilError::MissingPromisorObject(object_hash.to_string()).into());
+    }
 
-    let content = decompression_object(&file_path)?;
+    // Para el tipo y el tamaño alcanza con descomprimir el header
+    let content = match flag {
+        "-p" => decompression_object(&file_path)?,
+        _ => decompression_object_prefix(&file_path, OBJECT_HEADER_MAX_LEN)?,
+    };
 
     let mut result = read_type(&content)?;
+    if let Some(expected_type) = expected_type {
+        if result != expected_type {
+            return Err(GitError::ObjectTypeMismatch {
+                expected: expected_type.to_string(),
+                actual: result,
+                hash: object_hash.to_string(),
+            }
+            .into());
+        }
+    }
 
     if flag == "-p" {
-        result = git_cat_file_p(content, result)?;
+        result = git_cat_file_p(content, result, object_format)?;
     } else if flag == "-s" {
         result = read_size(&content)?;
     }
@@ -71,9 +131,52 @@ pub fn git_cat_file(
     Ok(result)
 }
 
+/// Lee un hash por línea y devuelve, por cada uno, una línea `<hash> <tipo> <tamaño>`, o
+/// `<hash> missing` si el objeto no existe en el repositorio.
+/// ###Parametros:
+/// 'directory': dirección donde se encuentra inicializado el repositorio.
+/// 'reader': entrada desde la que se leen los hashes, uno por línea.
+pub fn git_cat_file_batch_check(
+    directory: &str,
+    reader: impl BufRead,
+) -> Result<String, CommandsError> {
+    let mut formatted_result = String::new();
+    for line in reader.lines() {
+        let line = line.map_err(|_| CommandsError::ReadFileError)?;
+        let object_hash = line.trim();
+        if object_hash.is_empty() {
+            continue;
+        }
+        match read_type_and_size(directory, object_hash) {
+            Some((object_type, object_size)) => formatted_result.push_str(&format!(
+                "{} {} {}\n",
+                object_hash, object_type, object_size
+            )),
+            None => formatted_result.push_str(&format!("{} missing\n", object_hash)),
+        }
+    }
+    Ok(formatted_result)
+}
+
+/// Obtiene el tipo y el tamaño de un objeto descomprimiéndolo una única vez. Devuelve `None`
+/// si el hash no es válido o el objeto no existe.
+/// ###Parametros:
+/// 'directory': dirección donde se encuentra inicializado el repositorio.
+/// 'object_hash': Valor hash del objeto a leer, de 40 caracteres (SHA-1) o 64 (SHA-256).
+fn read_type_and_size(directory: &str, object_hash: &str) -> Option<(String, String)> {
+    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    if object_hash.len() != get_object_format(&git_dir).hex_len() {
+        return None;
+    }
+    let file_path = get_object_path(&git_dir, object_hash).ok()?;
+    let content = decompression_object_prefix(&file_path, OBJECT_HEADER_MAX_LEN).ok()?;
+    Some((read_type(&content).ok()?, read_size(&content).ok()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::init::git_init;
     use crate::util::formats::compressor_object;
     use std::fs;
     use std::fs::File;
@@ -104,4 +207,88 @@ mod tests {
 
         fs::remove_dir_all(TEST_DIRECTORY).expect("Falló al remover el directorio temporal");
     }
+
+    #[test]
+    fn test_git_cat_file_size_and_batch_check() {
+        let directory = "./test_cat_file_batch";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let blob = builder_object_blob(b"hola mundo\n".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        let tree = builder_object_tree(
+            &git_dir,
+            &format!("a.txt blob {}", blob),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el tree");
+        let commit_content = format!(
+            "tree {}\nauthor Valen <vlanzillotta@fi.uba.ar> 0 +0000\ncommitter Valen <vlanzillotta@fi.uba.ar> 0 +0000\n\nprueba\n",
+            tree
+        );
+        let commit = builder_object_commit(&commit_content, &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el commit");
+
+        assert_eq!(git_cat_file(directory, &blob, "-s"), Ok("11".to_string()));
+        // Un tree con una entrada: "a.txt 100644\0" (13 bytes) más los 20 bytes del hash
+        assert_eq!(git_cat_file(directory, &tree, "-s"), Ok("33".to_string()));
+        assert_eq!(
+            git_cat_file(directory, &commit, "-s"),
+            Ok(commit_content.len().to_string())
+        );
+
+        let missing = "0123456789abcdef0123456789abcdef01234567";
+        let input = format!("{}\n{}\n\n{}\nabc\n", blob, tree, missing);
+        let output =
+            git_cat_file_batch_check(directory, input.as_bytes()).expect("Falló el batch-check");
+        assert_eq!(
+            output,
+            format!(
+                "{} blob 11\n{} tree 33\n{} missing\nabc missing\n",
+                blob, tree, missing
+            )
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_git_cat_file_typed_mismatch() {
+        let directory = "./test_cat_file_typed";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let blob = builder_object_blob(b"hola\n".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        let tree = builder_object_tree(
+            &git_dir,
+            &format!("a.txt blob {}", blob),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el tree");
+
+        let result = git_cat_file_typed(directory, &blob, "-p", Some(TREE));
+        let result_tree = git_cat_file_typed(directory, &tree, "-t", Some(TREE));
+        let result_unchecked = git_cat_file_typed(directory, &blob, "-p", None);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            result,
+            Err(CommandsError::CommandsFromGit(
+                GitError::ObjectTypeMismatch {
+                    expected: TREE.to_string(),
+                    actual: BLOB.to_string(),
+                    hash: blob.clone(),
+                }
+            ))
+        );
+        assert_eq!(
+            result.map_err(|error| error.to_string()),
+            Err(format!(
+                "fatal: el objeto {} es de tipo blob, se esperaba tree",
+                blob
+            ))
+        );
+        assert_eq!(result_tree, Ok(TREE.to_string()));
+        assert_eq!(result_unchecked, Ok("hola\n".to_string()));
+    }
 }
@@ -1,9 +1,12 @@
 use std::fs;
 use std::io::BufRead;
 
+use super::config::GitConfig;
 use super::errors::CommandsError;
+use crate::consts::{CORE_EXCLUDES_FILE, GITIGNORE, GIT_DIR, INFO_EXCLUDE};
 use crate::models::client::Client;
 use crate::util::files::{open_file, read_file_string};
+use crate::util::validation::normalize_path;
 
 /// Esta función se encarga de llamar a al comando check-ignore con los parametros necesarios
 /// ###Parametros:
@@ -28,14 +31,13 @@ pub fn git_check_ignore(directory: &str, paths: Vec<&str>) -> Result<String, Com
     let mut ignored_files = Vec::<String>::new();
     let mut formatted_result = String::new();
 
-    let gitignore_content = get_gitignore_content(directory)?;
-
     if paths.len() == 1 && paths[0] == "--stdin" {
         let stdin = std::io::stdin();
         let lines = stdin.lock().lines();
-        lines.map_while(Result::ok).for_each(|line| {
-            check_gitignore(&line, &mut ignored_files, &gitignore_content).unwrap();
-        });
+        for line in lines.map_while(Result::ok) {
+            let gitignore_content = get_gitignore_content_for_path(directory, &line)?;
+            check_gitignore(&line, &mut ignored_files, &gitignore_content)?;
+        }
         for ignored_file in ignored_files {
             formatted_result.push_str(format!("{}\n", ignored_file).as_str());
         }
@@ -43,6 +45,7 @@ pub fn git_check_ignore(directory: &str, paths: Vec<&str>) -> Result<String, Com
     }
 
     for path in paths {
+        let gitignore_content = get_gitignore_content_for_path(directory, path)?;
         check_gitignore(path, &mut ignored_files, &gitignore_content)?;
     }
 
@@ -52,36 +55,189 @@ pub fn git_check_ignore(directory: &str, paths: Vec<&str>) -> Result<String, Com
     Ok(formatted_result)
 }
 
-/// Obtiene el contenido del archivo .gitignore.
+/// Obtiene los patrones que se aplican a todo el repositorio, en orden de precedencia
+/// creciente: el archivo global indicado en `core.excludesFile`, `.git/info/exclude` y el
+/// `.gitignore` de la raíz. Como gana el último patrón que coincide, los `.gitignore` de los
+/// subdirectorios deben agregarse a continuación (ver `get_nested_gitignore_content`).
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
 pub fn get_gitignore_content(directory: &str) -> Result<String, CommandsError> {
-    let gitignore_path = format!("{}/.gitignore", directory);
-    if fs::metadata(&gitignore_path).is_err() {
-        return Ok(String::new());
+    let mut gitignore_content = String::new();
+    if let Some(excludes_file) = get_global_excludes_file(directory) {
+        gitignore_content.push_str(&read_ignore_file(&excludes_file)?);
     }
-    let gitignore = open_file(&gitignore_path)?;
-    let gitignore_content = read_file_string(gitignore)?;
+    let exclude_path = format!("{}/{}/{}", directory, GIT_DIR, INFO_EXCLUDE);
+    gitignore_content.push_str(&read_ignore_file(&exclude_path)?);
+    let gitignore_path = format!("{}/{}", directory, GITIGNORE);
+    gitignore_content.push_str(&read_ignore_file(&gitignore_path)?);
     Ok(gitignore_content)
 }
 
+/// Obtiene los patrones del `.gitignore` de un subdirectorio, reescritos para que sean relativos
+/// a la raíz del repositorio y solo se apliquen dentro de ese subdirectorio.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'relative_dir': subdirectorio, relativo a la raíz del repositorio.
+pub fn get_nested_gitignore_content(
+    directory: &str,
+    relative_dir: &str,
+) -> Result<String, CommandsError> {
+    let gitignore_path = format!("{}/{}/{}", directory, relative_dir, GITIGNORE);
+    let content = read_ignore_file(&gitignore_path)?;
+    let mut scoped_content = String::new();
+    for line in content.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (negation, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => ("!", pattern),
+            None => ("", line),
+        };
+        // Un patrón sin barras (salvo la final) coincide en cualquier nivel del subdirectorio
+        let anchored = pattern.trim_end_matches('/').contains('/');
+        let pattern = pattern.trim_start_matches('/');
+        if anchored {
+            scoped_content.push_str(&format!("{}{}/{}\n", negation, relative_dir, pattern));
+        } else {
+            scoped_content.push_str(&format!("{}{}/**/{}\n", negation, relative_dir, pattern));
+        }
+    }
+    Ok(scoped_content)
+}
+
+/// Obtiene los patrones que se aplican a un path: los de todo el repositorio y los de los
+/// `.gitignore` de cada uno de los directorios que lo contienen.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'path': path relativo a la raíz del repositorio.
+pub fn get_gitignore_content_for_path(
+    directory: &str,
+    path: &str,
+) -> Result<String, CommandsError> {
+    let mut gitignore_content = get_gitignore_content(directory)?;
+    let path = normalize_path(path);
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    for i in 1..parts.len() {
+        let relative_dir = parts[..i].join("/");
+        gitignore_content.push_str(&get_nested_gitignore_content(directory, &relative_dir)?);
+    }
+    Ok(gitignore_content)
+}
+
+/// Devuelve la ruta del archivo global de exclusiones configurado en `core.excludesFile`.
+fn get_global_excludes_file(directory: &str) -> Option<String> {
+    let git_config = GitConfig::new_from_file(directory).ok()?;
+    let excludes_file = git_config
+        .get_value("core", CORE_EXCLUDES_FILE)
+        .or_else(|| git_config.get_value("core", "excludesFile"))?;
+    match (excludes_file.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(path), Ok(home)) => Some(format!("{}/{}", home, path)),
+        _ => Some(excludes_file.to_string()),
+    }
+}
+
+/// Lee un archivo de patrones, devolviendo un string vacío si no existe.
+fn read_ignore_file(path: &str) -> Result<String, CommandsError> {
+    if fs::metadata(path).is_err() {
+        return Ok(String::new());
+    }
+    let file = open_file(path)?;
+    let mut content = read_file_string(file)?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    Ok(content)
+}
+
 /// Verifica si un path esta incluido en .gitignore.
+///
+/// Los patrones se evalúan en orden y gana el último que coincide, por lo que un patrón
+/// negado (`!patron`) vuelve a incluir un path excluido antes. Un path cuyo directorio padre
+/// está excluido no puede volver a incluirse. Los paths que terminan en `/` se consideran
+/// directorios.
 /// ###Parametros:
-/// 'path_to_check': path a verificar.
+/// 'path_to_check': path a verificar, relativo a la raíz del repositorio.
 /// 'ignored_files': resultado del git check-ignore.
-/// 'directory': directorio del repositorio local.
+/// 'gitignore_content': patrones a aplicar.
 pub fn check_gitignore(
     path_to_check: &str,
     ignored_files: &mut Vec<String>,
     gitignore_content: &str,
 ) -> Result<(), CommandsError> {
-    let gitignore_lines: Vec<&str> = gitignore_content.lines().collect();
+    let path = normalize_path(path_to_check);
+    let is_dir = path.ends_with('/');
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    for i in 1..=parts.len() {
+        let current_path = parts[..i].join("/");
+        let current_is_dir = i < parts.len() || is_dir;
+        if is_ignored(&current_path, current_is_dir, gitignore_content) {
+            ignored_files.push(path_to_check.to_string());
+            break;
+        }
+    }
+    Ok(())
+}
 
-    if gitignore_lines.contains(&path_to_check) {
-        ignored_files.push(path_to_check.to_string());
+/// Aplica los patrones a un path y devuelve si queda excluido según el último patrón que coincide.
+fn is_ignored(path: &str, is_dir: bool, gitignore_content: &str) -> bool {
+    let mut ignored = false;
+    for line in gitignore_content.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (negation, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line),
+        };
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        if dir_only && !is_dir {
+            continue;
+        }
+        let matches = if pattern.contains('/') {
+            glob_match(pattern.trim_start_matches('/').as_bytes(), path.as_bytes())
+        } else {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            glob_match(pattern.as_bytes(), file_name.as_bytes())
+        };
+        if matches {
+            ignored = !negation;
+        }
     }
+    ignored
+}
 
-    Ok(())
+/// Compara un path con un patrón glob: `*` y `?` no coinciden con `/`, mientras que `**`
+/// coincide con cualquier cantidad de directorios.
+pub fn glob_match(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let mut rest = &pattern[2..];
+            if let Some(after_slash) = rest.strip_prefix(b"/") {
+                // `**/` también coincide con ningún directorio
+                if glob_match(after_slash, path) {
+                    return true;
+                }
+                rest = after_slash;
+                return (0..path.len())
+                    .filter(|i| path[*i] == b'/')
+                    .any(|i| glob_match(rest, &path[i + 1..]));
+            }
+            (0..=path.len()).any(|i| glob_match(rest, &path[i..]))
+        }
+        Some(b'*') => {
+            let segment_len = path.iter().position(|c| *c == b'/').unwrap_or(path.len());
+            (0..=segment_len).any(|i| glob_match(&pattern[1..], &path[i..]))
+        }
+        Some(b'?') => match path.first() {
+            Some(c) if *c != b'/' => glob_match(&pattern[1..], &path[1..]),
+            _ => false,
+        },
+        Some(c) => path.first() == Some(c) && glob_match(&pattern[1..], &path[1..]),
+    }
 }
 
 #[cfg(test)]
@@ -108,4 +264,63 @@ mod tests {
 
         fs::remove_dir_all(directory).expect("Error al eliminar el directorio");
     }
+
+    #[test]
+    fn test_check_gitignore_patterns() {
+        let content = "*.log\n!keep.log\nbuild/\n/docs/*.md\nsrc/**/gen\n";
+        let is_ignored = |path: &str| {
+            let mut ignored_files = Vec::new();
+            check_gitignore(path, &mut ignored_files, content).expect("Falló al verificar");
+            !ignored_files.is_empty()
+        };
+        assert!(is_ignored("debug.log"));
+        assert!(is_ignored("src/debug.log"));
+        assert!(!is_ignored("keep.log"));
+        assert!(is_ignored("build/"));
+        assert!(is_ignored("build/main.o"));
+        assert!(!is_ignored("build"));
+        assert!(is_ignored("docs/README.md"));
+        assert!(!is_ignored("other/docs/README.md"));
+        assert!(is_ignored("src/gen"));
+        assert!(is_ignored("src/a/b/gen"));
+        assert!(!is_ignored("main.rs"));
+    }
+
+    #[test]
+    fn test_git_check_ignore_sources() {
+        let directory = "./test_check_ignore_sources";
+        fs::create_dir_all(format!("{}/.git/info", directory))
+            .expect("Error al crear el directorio");
+        fs::create_dir_all(format!("{}/src", directory)).expect("Error al crear el directorio");
+        let global_path = format!("{}/global_excludes", directory);
+        create_file_replace(&global_path, "*.tmp\n*.bak\n").expect("Error al crear el archivo");
+        create_file_replace(
+            &format!("{}/.git/config", directory),
+            &format!("[core]\n\texcludesfile = {}\n", global_path),
+        )
+        .expect("Error al crear el archivo");
+        create_file_replace(
+            &format!("{}/.git/info/exclude", directory),
+            "secret.txt\n!*.bak\n",
+        )
+        .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/.gitignore", directory), "!notes.tmp\n")
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/src/.gitignore", directory), "*.rs\n!lib.rs\n")
+            .expect("Error al crear el archivo");
+
+        let paths = vec![
+            "a.tmp",
+            "notes.tmp",
+            "a.bak",
+            "secret.txt",
+            "src/main.rs",
+            "src/lib.rs",
+            "main.rs",
+        ];
+        let result = git_check_ignore(directory, paths).expect("Falló al ejecutar check-ignore");
+        assert_eq!(result, "a.tmp\nsecret.txt\nsrc/main.rs\n");
+
+        fs::remove_dir_all(directory).expect("Error al eliminar el directorio");
+    }
 }
@@ -1,19 +1,25 @@
-use super::add::add_to_index;
+use super::add::add_to_index_with_mode;
 use super::branch::get_branch;
-use super::branch::get_current_branch;
-use super::branch::git_branch_create;
-use super::cat_file::git_cat_file;
+use super::cat_file::{git_cat_file, git_cat_file_typed};
+use super::commit::Commit;
 use super::errors::CommandsError;
+use super::reflog::append_reflog;
+use super::rev_parse::{git_rev_parse, resolve_commit};
 use super::status::is_files_to_commit;
+use super::symbolic_ref::{read_head, set_detached_head};
 use crate::consts::*;
 use crate::util::files::is_folder_empty;
 
 use crate::models::client::Client;
 use crate::util::files::create_directory;
-use crate::util::files::create_file_replace;
+use crate::util::files::create_file;
+use crate::util::files::get_git_dir;
 use crate::util::files::open_file;
 use crate::util::files::read_file_string;
+use crate::util::files::write_file_with_mode;
 use crate::util::index::empty_index;
+use crate::util::objects::{is_blob_mode, parse_tree_line};
+use crate::util::validation::valid_ref_name;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
@@ -26,11 +32,10 @@ use std::path::Path;
 pub fn handle_checkout(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
     let directory = client.get_directory_path();
     if args.len() == 1 {
-        Ok(git_checkout_switch(directory, args[0])?)
-    } else if args.len() == 2 {
+        git_checkout(directory, args[0])
+    } else if args.len() == 2 || args.len() == 3 {
         if args[0] == "-b" {
-            git_branch_create(directory, args[1])?;
-            Ok(git_checkout_switch(directory, args[1])?)
+            git_checkout_new_branch(directory, args[1], args.get(2).copied())
         } else {
             return Err(CommandsError::FlagCheckoutNotRecognisedError);
         }
@@ -63,30 +68,21 @@ fn load_files(
     mode: usize,
     dir_path: &str,
 ) -> Result<(), CommandsError> {
-    let tree = git_cat_file(directory, tree_hash, "-p")?;
-    for line in tree.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let file_mode;
-        let path_file;
-        if parts[0] == FILE || parts[0] == DIRECTORY {
-            file_mode = parts[0];
-            path_file = parts[1];
-        } else {
-            path_file = parts[0];
-            file_mode = parts[1];
-        }
-        let hash = parts[2];
+    let tree = git_cat_file_typed(directory, tree_hash, "-p", Some(TREE))?;
+    for line in tree.lines().filter(|line| !line.is_empty()) {
+        let (file_mode, path_file, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
         let path_file_format = format!("{}/{}/{}", directory, dir_path, path_file);
-        if file_mode == FILE {
+        if is_blob_mode(file_mode) {
             let content_file = git_cat_file(directory, hash, "-p")?;
 
             if mode == 0 {
-                create_file_replace(&path_file_format, &content_file)?;
-                let git_dir = format!("{}/{}", directory, GIT_DIR);
+                write_file_with_mode(&path_file_format, &content_file, file_mode)?;
+                let git_dir = get_git_dir(directory);
                 let file_name = format!("{}/{}", dir_path, path_file);
-                add_to_index(git_dir, &file_name[1..], hash.to_string())?;
+                add_to_index_with_mode(git_dir, &file_name[1..], hash.to_string(), file_mode)?;
             } else if mode == 1
-                && fs::metadata(&path_file_format).is_ok()
+                && fs::symlink_metadata(&path_file_format).is_ok()
                 && fs::remove_file(&path_file_format).is_err()
             {
                 return Err(CommandsError::RemoveFileError);
@@ -127,6 +123,19 @@ pub fn extract_parent_hash(commit: &str) -> Option<&str> {
     None
 }
 
+/// Esta función se encarga de leer todos los parent hash de un commit (los merge commits tienen más de uno).
+/// ###Parametros:
+/// 'commit': Contenido de un commit
+pub fn extract_parent_hashes(commit: &str) -> Vec<&str> {
+    commit
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.strip_prefix("parent "))
+        .map(|hash| hash.trim())
+        .filter(|hash| *hash != PARENT_INITIAL)
+        .collect()
+}
+
 /// Esta función se encarga de leer los commits padres de un commit recursivamente
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
@@ -136,7 +145,7 @@ pub fn read_parent_commit(
     hash_commit: &str,
     mode: usize,
 ) -> Result<(), CommandsError> {
-    let commit = git_cat_file(directory, hash_commit, "-p")?;
+    let commit = git_cat_file_typed(directory, hash_commit, "-p", Some(COMMIT))?;
 
     if let Some(tree_hash) = get_tree_hash(&commit) {
         load_files(directory, tree_hash, mode, "")?;
@@ -161,6 +170,54 @@ fn load_files_tree(directory: &str, branch_name: &str, mode: usize) -> Result<()
     Ok(())
 }
 
+/// Cambia a una branch existente o, si `rev` no es el nombre de una branch, al commit al que
+/// se resuelve la revisión (un hash, una tag, `HEAD~1`, etc.) en estado detached HEAD: el hash
+/// del commit se escribe directamente en HEAD y el working directory y el index se reemplazan
+/// por el tree del commit.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'rev': Nombre de la branch o revisión a la que cambiar.
+pub fn git_checkout(directory: &str, rev: &str) -> Result<String, CommandsError> {
+    if get_branch(directory)?.contains(&rev.to_string()) {
+        return git_checkout_switch(directory, rev);
+    }
+    let commit_hash = resolve_commit(directory, rev)?;
+    if is_files_to_commit(directory)? {
+        return Ok("Please commit your changes\nAborting".to_string());
+    }
+    let head = read_head(directory)?;
+    let current_commit = git_rev_parse(directory, HEAD).ok();
+    let moving_from = match (head.get_branch_name(), &current_commit) {
+        (Ok(branch_name), _) => branch_name.to_string(),
+        (Err(_), Some(current_commit)) => current_commit.to_string(),
+        (Err(_), None) => HEAD.to_string(),
+    };
+
+    set_detached_head(directory, &commit_hash)?;
+    append_reflog(
+        directory,
+        HEAD,
+        current_commit.as_deref().unwrap_or(ZERO_ID),
+        &commit_hash,
+        REFLOG_DEFAULT_IDENTITY,
+        &format!("checkout: moving from {} to {}", moving_from, rev),
+    )?;
+    empty_index(directory)?;
+    if let Some(current_commit) = current_commit {
+        read_parent_commit(directory, &current_commit, 1)?;
+    }
+    read_parent_commit(directory, &commit_hash, 0)?;
+
+    let content = git_cat_file(directory, &commit_hash, "-p")?;
+    let commit = Commit::from_object(&content)?;
+    let response = format!(
+        "HEAD is now at {} {}",
+        &commit_hash[..7],
+        commit.get_message().lines().next().unwrap_or_default()
+    );
+    Ok(response)
+}
+
 /// Cambia a otra branch existente
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
@@ -170,9 +227,7 @@ pub fn git_checkout_switch(
     branch_switch_name: &str,
 ) -> Result<String, CommandsError> {
     //Falta implementar que verifique si realizó commit ante la pérdida de datos. <- con el status..
-    let current_branch_name = get_current_branch(directory)?;
-
-    if current_branch_name == branch_switch_name {
+    if read_head(directory)?.get_branch_name().ok() == Some(branch_switch_name) {
         return Err(CommandsError::AlreadyOnThatBranch);
     }
 
@@ -185,6 +240,72 @@ pub fn git_checkout_switch(
         return Ok("Please commit your changes\nAborting".to_string());
     }
 
+    let current_commit = git_rev_parse(directory, HEAD).ok();
+    write_head_branch(directory, branch_switch_name)?;
+    empty_index(directory)?;
+    if let Some(current_commit) = current_commit {
+        read_parent_commit(directory, &current_commit, 1)?;
+    }
+    load_files_tree(directory, branch_switch_name, 0)?;
+
+    let response = format!("Switched to branch '{}'", branch_switch_name);
+    Ok(response)
+}
+
+/// Crea una branch que apunta a `start_point` y cambia a ella.
+///
+/// Antes de modificar el repositorio se verifica que la branch no exista, que `start_point`
+/// (HEAD si no se indica) se resuelva a un commit y que no haya cambios sin commitear. Luego se
+/// escribe `refs/heads/<branch_name>`, HEAD pasa a apuntar a la nueva branch y el working
+/// directory y el index se reemplazan por el tree del commit: se borran los archivos del commit
+/// actual y se cargan los del commit de la nueva branch.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'branch_name': Nombre de la branch a crear.
+/// 'start_point': Revisión desde la que se crea la branch.
+pub fn git_checkout_new_branch(
+    directory: &str,
+    branch_name: &str,
+    start_point: Option<&str>,
+) -> Result<String, CommandsError> {
+    valid_ref_name(branch_name)?;
+    if get_branch(directory)?.contains(&branch_name.to_string()) {
+        return Err(CommandsError::BranchAlreadyExistsError);
+    }
+    let start_point = start_point.unwrap_or(HEAD);
+    let commit_hash = resolve_commit(directory, start_point)?;
+    if is_files_to_commit(directory)? {
+        return Ok("Please commit your changes\nAborting".to_string());
+    }
+    let current_commit = git_rev_parse(directory, HEAD).ok();
+
+    let branch_path = format!("{}/{}/{}/{}", directory, GIT_DIR, REF_HEADS, branch_name);
+    create_file(&branch_path, &commit_hash)?;
+    append_reflog(
+        directory,
+        &format!("{}/{}", REF_HEADS, branch_name),
+        ZERO_ID,
+        &commit_hash,
+        REFLOG_DEFAULT_IDENTITY,
+        &format!("branch: Created from {}", start_point),
+    )?;
+    write_head_branch(directory, branch_name)?;
+
+    empty_index(directory)?;
+    if let Some(current_commit) = current_commit {
+        read_parent_commit(directory, &current_commit, 1)?;
+    }
+    read_parent_commit(directory, &commit_hash, 0)?;
+
+    let response = format!("Switched to a new branch '{}'", branch_name);
+    Ok(response)
+}
+
+/// Hace que HEAD apunte a la branch indicada.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'branch_name': Nombre de la branch.
+fn write_head_branch(directory: &str, branch_name: &str) -> Result<(), CommandsError> {
     let directory_git = format!("{}/{}", directory, GIT_DIR);
     let head_file_path = Path::new(&directory_git).join(HEAD);
 
@@ -198,21 +319,21 @@ pub fn git_checkout_switch(
         Err(_) => return Err(CommandsError::BranchDirectoryOpenError),
     };
 
-    let content = format!("ref: refs/heads/{}\n", branch_switch_name);
+    let content = format!("ref: refs/heads/{}\n", branch_name);
     if file.write_all(content.as_bytes()).is_err() {
         return Err(CommandsError::BranchFileWriteError);
     }
-    empty_index(directory)?;
-    load_files_tree(directory, &current_branch_name, 1)?;
-    load_files_tree(directory, branch_switch_name, 0)?;
-
-    let response = format!("Switched to branch '{}'", branch_switch_name);
-    Ok(response)
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::{branch::git_branch_list, status::git_status};
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+    use crate::util::formats::compressor_object;
+    use crate::util::index::open_index;
     use crate::{
         commands::{
             add::git_add,
@@ -252,13 +373,7 @@ mod tests {
         file.write_all(b"hola mundo")
             .expect("Error al escribir en el archivo");
 
-        let test_commit = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit = test_commit("prueba");
 
         git_add(directory, "hola_mundo.txt").expect("Falló al agregar el archivo");
         git_commit(directory, test_commit).expect("Falló al hacer el commit");
@@ -277,4 +392,214 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(head_actualizado, "ref: refs/heads/test_branch_switch2\n")
     }
+
+    #[test]
+    fn test_git_checkout_new_branch() {
+        let directory = "./test_git_checkout_new_branch";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        let test_commit = test_commit("prueba");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit.clone()).expect("Falló al hacer el commit");
+        let first = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        create_file_replace(&format!("{}/a.txt", directory), "dos")
+            .expect("Falló al crear el archivo");
+        create_file_replace(&format!("{}/b.txt", directory), "nuevo")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        git_add(directory, "b.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit).expect("Falló al hacer el commit");
+
+        let result = git_checkout_new_branch(directory, "feature", Some("HEAD~1"));
+        let result_exists = git_checkout_new_branch(directory, "master", None);
+        let result_unknown = git_checkout_new_branch(directory, "otra", Some("inexistente"));
+        let head = fs::read_to_string(format!("{}/{}/{}", directory, GIT_DIR, HEAD))
+            .expect("Falló al leer HEAD");
+        let feature = git_rev_parse(directory, "feature");
+        let content_a = fs::read_to_string(format!("{}/a.txt", directory)).ok();
+        let exists_b = Path::new(&format!("{}/b.txt", directory)).exists();
+        let index =
+            open_index(&format!("{}/{}", directory, GIT_DIR)).expect("Falló al leer el index");
+        let branches = get_branch(directory).expect("Falló al leer las branches");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(result, Ok("Switched to a new branch 'feature'".to_string()));
+        assert_eq!(result_exists, Err(CommandsError::BranchAlreadyExistsError));
+        assert!(result_unknown.is_err());
+        assert!(!branches.contains(&"otra".to_string()));
+        assert_eq!(head, "ref: refs/heads/feature\n");
+        assert_eq!(feature, Ok(first));
+        assert_eq!(content_a.as_deref(), Some("uno"));
+        assert!(!exists_b);
+        assert_eq!(index.lines().count(), 1);
+        assert!(index.starts_with("a.txt "));
+    }
+
+    #[test]
+    fn test_git_checkout_file_name_with_spaces() {
+        let directory = "./test_git_checkout_file_name_with_spaces";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        let test_commit = test_commit("prueba");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit.clone()).expect("Falló al hacer el commit");
+        git_branch_create(directory, "feature").expect("Falló en la creación de la branch");
+
+        create_directory(Path::new(&format!("{}/mis docs", directory)))
+            .expect("Falló al crear el directorio");
+        create_file_replace(&format!("{}/mis docs/mi archivo.txt", directory), "hola")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "mis docs/mi archivo.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit).expect("Falló al hacer el commit");
+
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let removed = !Path::new(&format!("{}/mis docs", directory)).exists();
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let content = fs::read_to_string(format!("{}/mis docs/mi archivo.txt", directory));
+        let status = is_files_to_commit(directory);
+
+        // Un tree con una entrada sin hash no se interpreta
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let malformed = "0123456789abcdef0123456789abcdef01234567";
+        let object_dir = format!("{}/objects/{}", git_dir, &malformed[..2]);
+        create_directory(Path::new(&object_dir)).expect("Falló al crear el directorio");
+        let file = fs::File::create(format!("{}/{}", object_dir, &malformed[2..]))
+            .expect("Falló al crear el objeto");
+        compressor_object(format!("tree 12\0{}", "100644 a.txt"), file)
+            .expect("Falló en la compresión");
+        let result_malformed = load_files(directory, malformed, 0, "");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert!(removed);
+        assert_eq!(content.ok().as_deref(), Some("hola"));
+        assert_eq!(status, Ok(false));
+        assert_eq!(
+            result_malformed,
+            Err(CommandsError::MalformedTreeEntry(
+                "100644 a.txt ".to_string()
+            ))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_git_checkout_keeps_file_modes() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let directory = "./test_git_checkout_keeps_file_modes";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        let test_commit = test_commit("prueba");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit.clone()).expect("Falló al hacer el commit");
+        git_branch_create(directory, "feature").expect("Falló en la creación de la branch");
+
+        let script = format!("{}/run.sh", directory);
+        create_file_replace(&script, "#!/bin/sh\necho hola\n").expect("Falló al crear el archivo");
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))
+            .expect("Falló al cambiar los permisos");
+        symlink("run.sh", format!("{}/link", directory)).expect("Falló al crear el link");
+        git_add(directory, "run.sh").expect("Falló al agregar el archivo");
+        git_add(directory, "link").expect("Falló al agregar el link");
+        git_commit(directory, test_commit).expect("Falló al hacer el commit");
+
+        let commit_hash = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        let commit = git_cat_file(directory, &commit_hash, "-p").unwrap_or_default();
+        let tree_hash = get_tree_hash(&commit).unwrap_or_default().to_string();
+        let tree = git_cat_file(directory, &tree_hash, "-p").unwrap_or_default();
+
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let removed = fs::symlink_metadata(&script).is_err();
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let mode = fs::metadata(&script).map(|metadata| metadata.permissions().mode());
+        let target = fs::read_link(format!("{}/link", directory));
+        let status = is_files_to_commit(directory);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let entries: Vec<(&str, &str)> = tree
+            .lines()
+            .filter_map(
+                |line| match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                    [mode, name, _] => Some((mode, name)),
+                    _ => None,
+                },
+            )
+            .collect();
+        assert!(entries.contains(&("100755", "run.sh")));
+        assert!(entries.contains(&("120000", "link")));
+        assert!(entries.contains(&("100644", "a.txt")));
+        assert!(removed);
+        assert_eq!(mode.ok().map(|mode| mode & 0o111), Some(0o111));
+        assert_eq!(target.ok(), Some(Path::new("run.sh").to_path_buf()));
+        assert_eq!(status, Ok(false));
+    }
+
+    #[test]
+    fn test_git_checkout_detached_head() {
+        let directory = "./test_git_checkout_detached_head";
+        git_init(directory).expect("Falló al inicializar el repositorio");
+        let test_commit = test_commit("prueba");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit.clone()).expect("Falló al hacer el commit");
+        let first = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        create_file_replace(&format!("{}/b.txt", directory), "dos")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "b.txt").expect("Falló al agregar el archivo");
+        git_commit(directory, test_commit.clone()).expect("Falló al hacer el commit");
+        let second = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        let head_path = format!("{}/{}/{}", directory, GIT_DIR, HEAD);
+
+        // Entrar en detached HEAD
+        let result = git_checkout(directory, "HEAD~1");
+        let head = fs::read_to_string(&head_path).expect("Falló al leer HEAD");
+        let exists_b = Path::new(&format!("{}/b.txt", directory)).exists();
+        let status = git_status(directory).expect("Falló el status");
+        let branches = git_branch_list(directory).expect("Falló al listar las branches");
+
+        // Commitear en detached HEAD avanza HEAD y no mueve master
+        create_file_replace(&format!("{}/c.txt", directory), "tres")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "c.txt").expect("Falló al agregar el archivo");
+        let result_commit = git_commit(directory, test_commit);
+        let detached = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        let detached_content = git_cat_file(directory, &detached, "-p").expect("Falló al leer");
+        let master = git_rev_parse(directory, "master");
+
+        // Volver a la branch
+        let result_switch = git_checkout(directory, "master");
+        let head_after = fs::read_to_string(&head_path).expect("Falló al leer HEAD");
+        let exists_b_after = Path::new(&format!("{}/b.txt", directory)).exists();
+        let exists_c_after = Path::new(&format!("{}/c.txt", directory)).exists();
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(result, Ok(format!("HEAD is now at {} prueba", &first[..7])));
+        assert_eq!(head, format!("{}\n", first));
+        assert!(!exists_b);
+        assert!(status.starts_with(&format!("HEAD detached at {}\n", &first[..7])));
+        assert!(branches.starts_with(&format!(" * (HEAD detached at {})\n", &first[..7])));
+        assert!(branches.contains(&format!("    master {} prueba\n", &second[..7])));
+
+        assert_eq!(
+            result_commit,
+            Ok(format!("[detached HEAD {}] prueba", &detached[..7]))
+        );
+        assert_ne!(detached, first);
+        let commit = Commit::from_object(&detached_content).expect("Commit inválido");
+        assert_eq!(commit.get_parents(), [first]);
+        assert_eq!(master, Ok(second));
+
+        assert!(result_switch.is_ok());
+        assert_eq!(head_after, "ref: refs/heads/master\n");
+        assert!(exists_b_after);
+        assert!(!exists_c_after);
+    }
 }
@@ -1,25 +1,45 @@
+use super::cat_file::git_cat_file;
+use super::checkout::read_parent_commit;
+use super::commit::Commit;
 use super::errors::CommandsError;
-use super::log::save_log;
+use super::reflog::append_branch_reflog;
 use crate::commands::config::GitConfig;
-use crate::commands::init::git_init;
-use crate::consts::{DIRECTORY, FILE, GIT_DIR, REF_HEADS};
+use crate::commands::fetch::save_objects;
+use crate::commands::init::git_init_with_format;
+use crate::consts::{
+    CLONE_IN_PROGRESS_FILE, COMMIT, DIRECTORY, FILTER, FILTER_BLOB_NONE, GIT_DIR, INITIAL_BRANCH,
+    NETWORK_MAX_RETRIES, OFS_DELTA, PROMISOR_FILE, REFLOG_DEFAULT_IDENTITY, REFS_HEADS, REF_HEADS,
+    TREE,
+};
 use crate::git_server::GitServer;
 use crate::git_transport::git_request::GitRequest;
-use crate::git_transport::references::reference_discovery;
+use crate::git_transport::references::{reference_discovery, ReferenceType};
 use crate::git_transport::request_command::RequestCommand;
 use crate::models::client::Client;
-use crate::util::connections::{packfile_negotiation, receive_packfile, start_client};
-use crate::util::files::{create_directory, create_file, create_file_replace};
+use crate::util::connections::{
+    packfile_negotiation, packfile_negotiation_resume, receive_packfile_partial, send_flush,
+    start_client,
+};
+use crate::util::errors::UtilError;
+use crate::util::files::{
+    create_directory, create_file, create_file_replace, write_file_with_mode,
+};
+use crate::util::logger::log_debug;
+use crate::util::object_format::ObjectFormat;
 use crate::util::objects::{
-    builder_object_blob, builder_object_commit, builder_object_tree, read_blob, read_commit,
-    read_tree,
+    builder_object_blob, builder_object_commit, builder_object_tag, builder_object_tree,
+    get_object_hashes, is_blob_mode, object_exists, parse_tree_line, read_blob, read_commit,
+    read_tag, read_tree,
 };
 use crate::util::objects::{ObjectEntry, ObjectType};
+use crate::util::packfile::PackfileLimits;
 use crate::util::validation::join_paths_correctly;
+use std::collections::HashMap;
+use std::fs;
 use std::net::TcpStream;
 use std::path::Path;
 
-use super::add::add_to_index;
+use super::add::add_to_index_with_mode;
 
 /// Maneja la ejecución del comando "clone" en el cliente Git.
 ///
@@ -46,23 +66,27 @@ use super::add::add_to_index;
 /// * Otros errores de `CommandsError`: Pueden ocurrir errores relacionados con la conexión al servidor Git, la inicialización del socket, o el proceso de clonación.
 ///
 pub fn handle_clone(args: Vec<&str>, client: Client) -> Result<(String, String), CommandsError> {
-    if args.len() != 1 {
-        return Err(CommandsError::CloneMissingRepoError);
-    }
-    let mut socket = start_client(client.get_address())?;
-    let name = match args[0].split('/').last() {
+    let (remote_repo, filter) = match args.as_slice() {
+        [remote_repo] => (*remote_repo, None),
+        [option, remote_repo] => match option.strip_prefix("--filter=") {
+            Some(FILTER_BLOB_NONE) => (*remote_repo, Some(FILTER_BLOB_NONE)),
+            Some(filter) => return Err(UtilError::UnsupportedFilter(filter.to_string()).into()),
+            None => return Err(CommandsError::CloneMissingRepoError),
+        },
+        _ => return Err(CommandsError::CloneMissingRepoError),
+    };
+    let name = match remote_repo.split('/').last() {
         Some(name) => name,
         None => return Err(CommandsError::CloneMissingRepoError),
     };
     let local_repo = join_paths_correctly(client.get_directory_path(), name);
-    println!("local repo: {}", local_repo);
-    println!("client: {:?}", client);
-    git_clone(
-        &mut socket,
+    git_clone_resumable(
+        client.get_address(),
         client.get_ip(),
         client.get_port(),
         &local_repo,
-        args[0],
+        remote_repo,
+        filter,
     )
 }
 
@@ -87,41 +111,390 @@ pub fn git_clone(
     local_repo: &str,
     remote_repo: &str,
 ) -> Result<(String, String), CommandsError> {
-    println!("Clonando repositorio remoto: {}", remote_repo);
-    println!("En el directorio: {}", local_repo);
+    git_clone_filtered(socket, ip, port, local_repo, remote_repo, None)
+}
+
+/// Clona un repositorio como `git_clone_filtered`, abriendo la conexión con el servidor. Si la
+/// conexión se corta a mitad del packfile, se vuelve a conectar y retoma el clone hasta
+/// `NETWORK_MAX_RETRIES` veces.
+///
+/// # Argumentos
+///
+/// - `address`: Dirección del servidor Git.
+/// - `ip`: La dirección IP del servidor Git.
+/// - `port`: El número de puerto utilizado para la conexión.
+/// - `local_repo`: Ruta del repositorio local a crear.
+/// - `remote_repo`: Repositorio remoto a clonar.
+/// - `filter`: Filtro del clone parcial, si se pidió.
+///
+/// # Returns
+///
+/// El resultado del último intento. Si todos se interrumpieron, `CommandsError::CloneInterrupted`
+/// y el repositorio local queda marcado para retomarse en un próximo clone.
+///
+pub fn git_clone_resumable(
+    address: &str,
+    ip: &str,
+    port: &str,
+    local_repo: &str,
+    remote_repo: &str,
+    filter: Option<&str>,
+) -> Result<(String, String), CommandsError> {
+    let mut retries = 0;
+    loop {
+        let mut socket = start_client(address)?;
+        match git_clone_filtered(&mut socket, ip, port, local_repo, remote_repo, filter) {
+            Err(CommandsError::CloneInterrupted(received, expected))
+                if retries < NETWORK_MAX_RETRIES =>
+            {
+                retries += 1;
+                log_debug(&format!(
+                    "Clone interrumpido ({} de {} objetos), reintento {}",
+                    received, expected, retries
+                ));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Clona un repositorio como `git_clone`, pudiendo pedir un clone parcial.
+///
+/// Con el filtro `blob:none` el servidor envía los commits y los trees pero no los blobs. El
+/// repositorio queda marcado como clone parcial (`.git/promisor`) y no se hace el checkout del
+/// working directory, ya que los blobs todavía no se descargaron. Si el servidor no anuncia la
+/// capacidad `filter`, el filtro se ignora y se hace un clone completo.
+///
+/// Si la conexión se corta antes de recibir todo el packfile, los objetos recibidos completos
+/// se guardan en el repositorio local, que queda marcado con `.git/clone-in-progress`, y se
+/// devuelve `CommandsError::CloneInterrupted`. Al clonar de nuevo en el mismo directorio se
+/// informan al servidor los commits ya recibidos y solo se piden los objetos que faltan.
+///
+/// # Argumentos
+///
+/// - `socket`: Conexión con el servidor.
+/// - `ip`: La dirección IP del servidor Git.
+/// - `port`: El número de puerto utilizado para la conexión.
+/// - `local_repo`: Ruta del repositorio local a crear.
+/// - `remote_repo`: Repositorio remoto a clonar.
+/// - `filter`: Filtro del clone parcial, si se pidió.
+///
+/// # Returns
+///
+/// Un `Result` que contiene una cadena indicando el éxito del clon o un error `CommandsError` en caso de error.
+///
+pub fn git_clone_filtered(
+    socket: &mut TcpStream,
+    ip: &str,
+    port: &str,
+    local_repo: &str,
+    remote_repo: &str,
+    filter: Option<&str>,
+) -> Result<(String, String), CommandsError> {
+    log_debug(&format!("Clonando repositorio remoto: {}", remote_repo));
 
     // Prepara la solicitud "git-upload-pack" para el servidor
     let message =
         GitRequest::generate_request_string(RequestCommand::UploadPack, remote_repo, ip, port);
 
     // Reference Discovery
-    let git_server = reference_discovery(socket, message, remote_repo, &Vec::new())?;
+    let my_capabilities: Vec<String> = match filter {
+        Some(_) => vec![FILTER.to_string()],
+        None => Vec::new(),
+    };
+    let git_server = reference_discovery(socket, message, remote_repo, &my_capabilities)?;
+    let filter = filter.filter(|_| git_server.supports(FILTER));
 
-    // Packfile Negotiation
-    packfile_negotiation(socket, &git_server)?;
+    // El repositorio remoto no tiene commits: no hay nada que negociar
+    if git_server.get_references().is_empty() {
+        send_flush(socket, UtilError::CloseConnection)?;
+        let status = create_empty_repository(&git_server, local_repo)?;
+        return Ok((status, local_repo.to_string()));
+    }
+
+    // Packfile Negotiation. Si se retoma un clone interrumpido, se informan los commits que
+    // ya se recibieron completos
+    let resuming = is_clone_in_progress(local_repo);
+    let haves = match resuming && filter.is_none() && has_single_tip(&git_server) {
+        true => get_complete_commits(local_repo)?,
+        false => Vec::new(),
+    };
+    if haves.is_empty() {
+        packfile_negotiation(socket, &git_server, filter)?;
+    } else {
+        packfile_negotiation_resume(socket, &git_server, &haves)?;
+    }
+
+    // HEAD apunta a la misma branch que en el servidor, anunciada con `symref=HEAD:...`
+    let initial_branch = git_server.get_head_branch().unwrap_or(INITIAL_BRANCH);
 
     // Packfile Data
-    let content = receive_packfile(socket)?;
+    let received = receive_packfile_partial(
+        socket,
+        git_server.supports(OFS_DELTA),
+        &PackfileLimits::default(),
+        git_server.object_format,
+    )?;
+    let object_format = git_server.object_format;
+    if !received.is_complete() {
+        let (count, expected) = (received.objects.len(), received.expected);
+        let repo = (local_repo, object_format);
+        save_interrupted_clone(received.objects, repo, initial_branch)?;
+        return Err(CommandsError::CloneInterrupted(count, expected));
+    }
+    let content = received.objects;
 
-    let local_repo_parts: Vec<&str> = local_repo.split('/').collect();
-    let status = create_repository(content, local_repo, local_repo_parts.len())?;
-    save_references(&git_server, local_repo)?;
+    let repo = (local_repo, object_format);
+    let status = match filter {
+        Some(filter) => create_partial_repository(content, repo, initial_branch, filter)?,
+        None if resuming => resume_repository(content, local_repo, &git_server)?,
+        None => {
+            let local_repo_parts: Vec<&str> = local_repo.split('/').collect();
+            create_repository(content, repo, local_repo_parts.len(), initial_branch)?
+        }
+    };
+    save_references(&git_server, local_repo, remote_repo)?;
 
     // Creo el config
     let git_config = GitConfig::new_from_server(&git_server)?;
     let path_config = format!("{}/{}/{}", local_repo, GIT_DIR, "config");
     git_config.write_to_file(&path_config)?;
 
+    // El clone terminó: ya no hay nada que retomar
+    if resuming && fs::remove_file(clone_in_progress_path(local_repo)).is_err() {
+        return Err(CommandsError::RemoveFileError);
+    }
+
     Ok((status, local_repo.to_string()))
 }
 
-/// Crea un repositorio a partir de los objetos recibidos del servidor.
+/// Devuelve la ruta del archivo que marca un clone interrumpido.
+fn clone_in_progress_path(local_repo: &str) -> String {
+    format!("{}/{}/{}", local_repo, GIT_DIR, CLONE_IN_PROGRESS_FILE)
+}
+
+/// Indica si en el directorio hay un clone interrumpido que se puede retomar.
+fn is_clone_in_progress(local_repo: &str) -> bool {
+    Path::new(&clone_in_progress_path(local_repo)).is_file()
+}
+
+/// Guarda los objetos recibidos antes de que se interrumpiera el clone y marca el repositorio
+/// para retomarlo. Los objetos se escriben de forma atómica, por lo que un objeto a medio
+/// escribir nunca queda en el repositorio.
+///
+/// # Argumentos
+///
+/// - `content`: Objetos recibidos completos
+/// - `repo`: Dirección del repositorio del clone y formato de los objetos del servidor
+/// - `initial_branch`: Branch a la que apunta HEAD
+///
+fn save_interrupted_clone(
+    content: Vec<(ObjectEntry, Vec<u8>)>,
+    (repo, object_format): (&str, ObjectFormat),
+    initial_branch: &str,
+) -> Result<(), CommandsError> {
+    if !is_clone_in_progress(repo) {
+        git_init_with_format(repo, initial_branch, object_format)?;
+        create_file_replace(&clone_in_progress_path(repo), "")?;
+    }
+    save_objects(content, repo)
+}
+
+/// El servidor solo envía en un fetch la historia de la primera referencia pedida, por lo que
+/// un clone se retoma con `have` únicamente si todas las referencias apuntan al mismo commit.
+/// Si no, se vuelve a pedir el packfile completo, aunque los objetos ya guardados no se
+/// reescriben.
+fn has_single_tip(git_server: &GitServer) -> bool {
+    let mut hashes = git_server
+        .get_references()
+        .iter()
+        .map(|reference| reference.get_hash());
+    match hashes.next() {
+        Some(first) => hashes.all(|hash| hash == first),
+        None => false,
+    }
+}
+
+/// Devuelve los commits del repositorio cuyo contenido está completo: el commit, su tree con
+/// todos los sub-trees y blobs, y recursivamente sus parents. Son los commits que se pueden
+/// informar al servidor con `have` al retomar un clone.
+///
+/// # Argumentos
+///
+/// - `repo`: Dirección del repositorio del clone interrumpido
+///
+fn get_complete_commits(repo: &str) -> Result<Vec<String>, CommandsError> {
+    let git_dir = format!("{}/{}", repo, GIT_DIR);
+    let mut complete: HashMap<String, bool> = HashMap::new();
+    let mut commits = Vec::new();
+    for hash in get_object_hashes(&git_dir)? {
+        if git_cat_file(repo, &hash, "-t")? != COMMIT {
+            continue;
+        }
+        if is_complete(repo, &hash, &mut complete)? {
+            commits.push(hash);
+        }
+    }
+    commits.sort();
+    Ok(commits)
+}
+
+/// Indica si el objeto y todos los objetos que alcanza están en el repositorio. El resultado de
+/// cada objeto visitado se guarda en `complete`, para no recorrer dos veces la historia
+/// compartida.
+///
+/// # Argumentos
+///
+/// - `repo`: Dirección del repositorio
+/// - `hash`: Objeto a verificar
+/// - `complete`: Objetos ya verificados
+///
+fn is_complete(
+    repo: &str,
+    hash: &str,
+    complete: &mut HashMap<String, bool>,
+) -> Result<bool, CommandsError> {
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    let mut pending = vec![hash.to_string()];
+    while let Some(hash) = pending.pop() {
+        if complete.contains_key(&hash) {
+            continue;
+        }
+        // Un objeto se resuelve luego de resolver todos los objetos que alcanza
+        if let Some(reached) = children.get(&hash) {
+            let is_complete = reached
+                .iter()
+                .all(|child| complete.get(child) == Some(&true));
+            complete.insert(hash, is_complete);
+            continue;
+        }
+        if !object_exists(repo, &hash) {
+            complete.insert(hash, false);
+            continue;
+        }
+        let reached = get_reached_objects(repo, &hash)?;
+        pending.push(hash.clone());
+        pending.extend(
+            reached
+                .iter()
+                .filter(|child| !complete.contains_key(*child))
+                .cloned(),
+        );
+        children.insert(hash, reached);
+    }
+    Ok(complete.get(hash) == Some(&true))
+}
+
+/// Devuelve los objetos que alcanza directamente un objeto: el tree y los parents de un commit,
+/// o las entradas de un tree.
+fn get_reached_objects(repo: &str, hash: &str) -> Result<Vec<String>, CommandsError> {
+    let object_type = git_cat_file(repo, hash, "-t")?;
+    if object_type != COMMIT && object_type != TREE {
+        return Ok(Vec::new());
+    }
+    let content = git_cat_file(repo, hash, "-p")?;
+    if object_type == COMMIT {
+        let commit = Commit::from_object(&content)?;
+        let mut reached = vec![commit.get_tree().to_string()];
+        reached.extend(commit.get_parents().iter().cloned());
+        return Ok(reached);
+    }
+    Ok(content
+        .lines()
+        .filter_map(parse_tree_line)
+        .filter(|(mode, _, _)| is_blob_mode(mode) || *mode == DIRECTORY)
+        .map(|(_, _, hash)| hash.to_string())
+        .collect())
+}
+
+/// Completa un clone que se había interrumpido: guarda los objetos que faltaban y carga el
+/// working directory y el index con el commit al que apunta HEAD en el servidor.
 ///
 /// # Argumentos
 ///
 /// - `content`: Objetos recibidos desde el servidor
 /// - `repo`: Dirección del repositorio del clone
+/// - `git_server`: Información del servidor, con HEAD como primera referencia
+///
+/// # Returns
+///
+/// Un `Result` que contiene una cadena indicando el éxito del clone o un error `CommandsError` en caso de error.
+///
+fn resume_repository(
+    content: Vec<(ObjectEntry, Vec<u8>)>,
+    repo: &str,
+    git_server: &GitServer,
+) -> Result<String, CommandsError> {
+    save_objects(content, repo)?;
+    if let Some(head) = git_server.get_references().first() {
+        read_parent_commit(repo, head.get_hash(), 0)?;
+    }
+    Ok("Successful cloning (resumed)".to_string())
+}
+
+/// Crea un repositorio vacío con HEAD apuntando a la misma branch que en el servidor (o a la
+/// branch por defecto si el servidor no la anuncia), que todavía no existe.
+///
+/// # Argumentos
+///
+/// - `git_server`: Información del servidor
+/// - `local_repo`: Dirección del repositorio del clone
+///
+/// # Returns
+///
+/// Un `Result` que contiene una cadena indicando el éxito del clone o un error `CommandsError` en caso de error.
+///
+fn create_empty_repository(
+    git_server: &GitServer,
+    local_repo: &str,
+) -> Result<String, CommandsError> {
+    let initial_branch = git_server.get_head_branch().unwrap_or(INITIAL_BRANCH);
+    git_init_with_format(local_repo, initial_branch, git_server.object_format)?;
+    let git_config = GitConfig::new_from_server(git_server)?;
+    let path_config = format!("{}/{}/{}", local_repo, GIT_DIR, "config");
+    git_config.write_to_file(&path_config)?;
+    Ok("Successful cloning: the repository is empty".to_string())
+}
+
+/// Crea un clone parcial: guarda los objetos recibidos, que no incluyen los blobs, y marca el
+/// repositorio como clone parcial con el filtro usado. No se hace el checkout del working
+/// directory.
+///
+/// # Argumentos
+///
+/// - `content`: Objetos recibidos desde el servidor
+/// - `repo`: Dirección del repositorio del clone y formato de los objetos del servidor
+/// - `initial_branch`: Branch a la que apunta HEAD
+/// - `filter`: Filtro con el que se pidió el clone
+///
+/// # Returns
+///
+/// Un `Result` que contiene una cadena indicando el éxito del clone o un error `CommandsError` en caso de error.
+///
+fn create_partial_repository(
+    content: Vec<(ObjectEntry, Vec<u8>)>,
+    (repo, object_format): (&str, ObjectFormat),
+    initial_branch: &str,
+    filter: &str,
+) -> Result<String, CommandsError> {
+    git_init_with_format(repo, initial_branch, object_format)?;
+    save_objects(content, repo)?;
+    let promisor_path = format!("{}/{}/{}", repo, GIT_DIR, PROMISOR_FILE);
+    create_file_replace(&promisor_path, &format!("{}\n", filter))?;
+    Ok(format!(
+        "Successful partial cloning (filter {}): the working tree was not checked out",
+        filter
+    ))
+}
+
+/// Crea un repositorio a partir de los objetos recibidos del servidor.
+///
+/// # Argumentos
+///
+/// - `content`: Objetos recibidos desde el servidor
+/// - `repo`: Dirección del repositorio del clone y formato de los objetos del servidor
 /// - `repo_count`: Cantidad de objetos a crear
+/// - `initial_branch`: Branch a la que apunta HEAD
 ///
 /// # Returns
 ///
@@ -129,25 +502,26 @@ pub fn git_clone(
 ///
 fn create_repository(
     content: Vec<(ObjectEntry, Vec<u8>)>,
-    repo: &str,
+    (repo, object_format): (&str, ObjectFormat),
     repo_count: usize,
+    initial_branch: &str,
 ) -> Result<String, CommandsError> {
     // Cantidad de objetos recibidos
     let count_objects = content.len();
 
     let path_dir_cloned = Path::new(repo);
-    git_init(repo)?;
+    git_init_with_format(repo, initial_branch, object_format)?;
     let git_dir = format!("{}/{}", repo, GIT_DIR);
     let mut first_tree = 0;
     let mut i = 0;
     while i < count_objects {
         if content[i].0.obj_type == ObjectType::Commit {
-            handle_commit(&content, &git_dir, i)?;
+            handle_commit(&content, (&git_dir, object_format), i)?;
             i += 1;
         } else if content[i].0.obj_type == ObjectType::Tree {
             i = match handle_tree(
                 &content,
-                &git_dir,
+                (&git_dir, object_format),
                 i,
                 path_dir_cloned,
                 repo_count,
@@ -158,7 +532,10 @@ fn create_repository(
             };
             i += 1;
             first_tree = 1;
-        } else if content[i].0.obj_type == ObjectType::Blob {
+        } else if content[i].0.obj_type == ObjectType::Tag {
+            builder_object_tag(&read_tag(&content[i].1)?, &git_dir, object_format)?;
+            i += 1;
+        } else {
             i += 1;
         }
     }
@@ -169,22 +546,22 @@ fn create_repository(
 ///
 /// # Argumentos
 ///
-/// - `hash`: hash del blob
+/// - `entry`: modo y hash del blob, tal como figuran en el tree
 /// - `path_dir_cloned`: Dirección del blob
 /// - `content`: Objetos recibidos desde el servidor
 /// - `repo_count`: Cantidad de objetos a crear
-/// - `repo`: Repositorio del cliente
+/// - `repo`: Repositorio del cliente y formato de sus objetos
 ///
 /// # Returns
 ///
 /// Un `Result` que contiene el numero de objeto actual o un error `CommandsError` en caso de error.
 ///
 fn recovery_blob(
-    hash: &str,
+    (mode, hash): (&str, &str),
     path_dir_cloned: &Path,
     content: &[(crate::util::objects::ObjectEntry, Vec<u8>)],
     mut i: usize,
-    repo: &str,
+    (repo, object_format): (&str, ObjectFormat),
     repo_count: usize,
     first_tree: usize,
 ) -> Result<usize, CommandsError> {
@@ -197,12 +574,16 @@ fn recovery_blob(
         let blob_content = read_blob(&content[i].1)?;
         let blob_content_bytes = blob_content.clone();
         if !path_dir_cloned.exists() {
-            println!("{:?}", route);
-            builder_object_blob(blob_content_bytes.into_bytes(), repo)?;
+            builder_object_blob(blob_content_bytes.into_bytes(), repo, object_format)?;
             if let Some(str_path) = path_dir_cloned.to_str() {
                 if first_tree == 0 {
-                    add_to_index(repo.to_string(), &route.join("/"), hash.to_string())?;
-                    create_file_replace(str_path, &blob_content)?;
+                    add_to_index_with_mode(
+                        repo.to_string(),
+                        &route.join("/"),
+                        hash.to_string(),
+                        mode,
+                    )?;
+                    write_file_with_mode(str_path, &blob_content, mode)?;
                 }
             }
         } else {
@@ -220,7 +601,7 @@ fn recovery_blob(
 /// - `path_dir_cloned`: Dirección del tree
 /// - `content`: Objetos recibidos desde el servidor
 /// - `repo_count`: Cantidad de objetos a crear
-/// - `repo`: Repositorio del cliente
+/// - `repo`: Repositorio del cliente y formato de sus objetos
 ///
 /// # Returns
 ///
@@ -231,29 +612,19 @@ fn recovery_tree(
     path_dir_cloned: &Path,
     content: &Vec<(crate::util::objects::ObjectEntry, Vec<u8>)>,
     mut i: usize,
-    repo: &str,
+    repo: (&str, ObjectFormat),
     repo_count: usize,
     first_tree: usize,
 ) -> Result<usize, CommandsError> {
     for line in tree_content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() == 3 {
-            let mode;
-            let file_name;
-            if parts[0] == FILE || parts[0] == DIRECTORY {
-                mode = parts[0];
-                file_name = parts[1];
-            } else {
-                file_name = parts[0];
-                mode = parts[1];
-            }
-            let hash = parts[2];
-
+        if !line.is_empty() {
+            let (mode, file_name, hash) =
+                parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
             let path_dir_cloned = path_dir_cloned.join(file_name);
-            if mode == FILE {
+            if is_blob_mode(mode) {
                 i += 1;
                 i = recovery_blob(
-                    hash,
+                    (mode, hash),
                     &path_dir_cloned,
                     content,
                     i,
@@ -267,8 +638,9 @@ fn recovery_tree(
                     if first_tree == 0 {
                         create_directory(&path_dir_cloned)?;
                     }
-                    let tree_content = read_tree(&content[i].1)?;
-                    builder_object_tree(repo, &tree_content)?;
+                    let (git_dir, object_format) = repo;
+                    let tree_content = read_tree(&content[i].1, object_format)?;
+                    builder_object_tree(git_dir, &tree_content, object_format)?;
                     let count = i;
                     i = recovery_tree(
                         tree_content,
@@ -289,26 +661,49 @@ fn recovery_tree(
     Ok(i)
 }
 
-/// Recorre las referencias recibidas del servidor y las guarda en el repositorio local
+/// Recorre las referencias recibidas del servidor y las guarda en el repositorio local. Las
+/// branches se guardan en `refs/heads` y las tags en `refs/tags`, apuntando al objeto tag si
+/// son anotadas.
 ///
 /// # Argumentos
 ///
 /// - `repo`: Dirección del repositorio
 /// - `advertised`: Contiene las referencias
+/// - `remote_repo`: Repositorio remoto clonado, que se registra en el reflog
 ///
 /// # Returns
 ///
 /// Un `Result` con un retorno `CommandsError` en caso de error.
 ///
-fn save_references(advertised: &GitServer, repo: &str) -> Result<(), CommandsError> {
+fn save_references(
+    advertised: &GitServer,
+    repo: &str,
+    remote_repo: &str,
+) -> Result<(), CommandsError> {
     //refs/remotes/origin
     for refs in advertised.get_references().iter().skip(1) {
         let hash = refs.get_hash();
-        let branch = refs.get_name();
-        if let Some(current_branch) = branch.rsplit('/').next() {
+        if refs.get_type() == ReferenceType::Tag {
+            let tag_path = format!("{}/{}/{}", repo, GIT_DIR, refs.get_ref_path());
+            create_file_replace(&tag_path, hash)?;
+            continue;
+        }
+        // El nombre de la branch es todo lo que sigue a refs/heads/, que puede incluir `/`
+        let branch = refs.get_ref_path().strip_prefix(REFS_HEADS);
+        if let Some(current_branch) = branch.and_then(|branch| branch.strip_prefix('/')) {
             let branch_dir = format!("{}/{}/{}/{}", repo, GIT_DIR, REF_HEADS, current_branch);
+            if let Some(parent) = Path::new(&branch_dir).parent() {
+                create_directory(parent)?;
+            }
             create_file(&branch_dir, hash)?;
-            save_log(repo, current_branch, "logs/refs/heads", REF_HEADS)?;
+            append_branch_reflog(
+                repo,
+                current_branch,
+                &"0".repeat(advertised.object_format.hex_len()),
+                hash,
+                REFLOG_DEFAULT_IDENTITY,
+                &format!("clone: from {}", remote_repo),
+            )?;
         }
     }
     Ok(())
@@ -318,8 +713,8 @@ fn save_references(advertised: &GitServer, repo: &str) -> Result<(), CommandsErr
 ///
 /// # Argumentos
 ///
-/// - `git_dir`: Dirección del repositorio
 /// - `content`: Objetos recibidos del servidor
+/// - `repo`: Dirección del repositorio y formato de sus objetos
 /// - `i`: Numero de objeto actual
 ///
 /// # Returns
@@ -328,11 +723,11 @@ fn save_references(advertised: &GitServer, repo: &str) -> Result<(), CommandsErr
 ///
 fn handle_commit(
     content: &[(ObjectEntry, Vec<u8>)],
-    git_dir: &str,
+    (git_dir, object_format): (&str, ObjectFormat),
     i: usize,
 ) -> Result<(), CommandsError> {
     let commit_content = read_commit(&content[i].1)?;
-    builder_object_commit(&commit_content, git_dir)?;
+    builder_object_commit(&commit_content, git_dir, object_format)?;
 
     Ok(())
 }
@@ -342,7 +737,7 @@ fn handle_commit(
 /// # Argumentos
 ///
 /// - `content`: Objetos recibidos desde el servidor
-/// - `git_dir`: Repositorio del cliente
+/// - `repo`: Repositorio del cliente y formato de sus objetos
 /// - `tree_content`: Contenido del tree
 /// - `path_dir_cloned`: Dirección del tree
 /// - `repo_count`: Cantidad de objetos a crear
@@ -353,22 +748,343 @@ fn handle_commit(
 ///
 fn handle_tree(
     content: &Vec<(ObjectEntry, Vec<u8>)>,
-    git_dir: &str,
+    repo: (&str, ObjectFormat),
     i: usize,
     path_dir_cloned: &Path,
     repo_count: usize,
     first_tree: usize,
 ) -> Result<usize, CommandsError> {
-    let tree_content = read_tree(&content[i].1)?;
-    builder_object_tree(git_dir, &tree_content)?;
+    let (git_dir, object_format) = repo;
+    let tree_content = read_tree(&content[i].1, object_format)?;
+    builder_object_tree(git_dir, &tree_content, object_format)?;
     let i = recovery_tree(
         tree_content,
         path_dir_cloned,
         content,
         i,
-        git_dir,
+        repo,
         repo_count,
         first_tree,
     )?;
     Ok(i)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init_with_branch;
+    use crate::commands::symbolic_ref::{read_head, SymbolicRef};
+    use crate::commands::tag::git_tag_create;
+    use crate::commands::{cat_file::git_cat_file, init::git_init};
+    use crate::commands::{rev_parse::git_rev_parse, rev_parse::resolve_commit, rm::git_rm};
+    use crate::consts::TEMP_FILE_SUFFIX;
+    use crate::consts::{BLOB, COMMIT, PACKED_REFS, REFS_TAGS, TAG, TREE};
+    use crate::test_utils::{commit_file, commit_index, serve_once, test_client, test_commit};
+    use crate::util::formats::open_object_stream;
+    use crate::util::objects::{get_object_hashes, get_object_path};
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, TcpListener};
+    use std::thread;
+
+    /// Atiende una conexión del proxy reenviándola al servidor. La respuesta del servidor se
+    /// corta luego de `limit` bytes, como si se cayera la conexión. Devuelve la cantidad de
+    /// bytes reenviados al cliente.
+    fn proxy_once(listener: &TcpListener, server: &str, limit: Option<usize>) -> usize {
+        let (client, _) = listener.accept().expect("Falló al aceptar la conexión");
+        let upstream = TcpStream::connect(server).expect("Falló al conectar con el servidor");
+        let mut client_reader = client.try_clone().expect("Falló al clonar el socket");
+        let mut upstream_writer = upstream.try_clone().expect("Falló al clonar el socket");
+        let requests = thread::spawn(move || {
+            let _ = std::io::copy(&mut client_reader, &mut upstream_writer);
+        });
+
+        let (mut client, mut upstream) = (client, upstream);
+        let mut forwarded = 0;
+        let mut buffer = [0u8; 4096];
+        while limit.is_none_or(|limit| forwarded < limit) {
+            let n = match upstream.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let n = limit.map_or(n, |limit| n.min(limit - forwarded));
+            if client.write_all(&buffer[..n]).is_err() {
+                break;
+            }
+            forwarded += n;
+        }
+        let _ = client.shutdown(Shutdown::Both);
+        let _ = upstream.shutdown(Shutdown::Both);
+        requests.join().expect("Falló el proxy");
+        forwarded
+    }
+
+    /// Verifica que cada objeto del repositorio tenga como nombre el hash de su contenido.
+    fn objects_are_valid(git_dir: &str) -> bool {
+        get_object_hashes(git_dir)
+            .unwrap_or_default()
+            .iter()
+            .all(|hash| {
+                let path = get_object_path(git_dir, hash).expect("Falló al buscar el objeto");
+                let stream = match open_object_stream(&path) {
+                    Ok(stream) => stream,
+                    Err(_) => return false,
+                };
+                let mut store =
+                    format!("{} {}\0", stream.get_type(), stream.get_size()).into_bytes();
+                match stream.read_content() {
+                    Ok(content) => store.extend(content),
+                    Err(_) => return false,
+                }
+                ObjectFormat::Sha1.hash(&store) == *hash
+            })
+    }
+
+    /// Busca archivos temporales de objetos a medio escribir.
+    fn has_temp_objects(git_dir: &str) -> bool {
+        let entries = fs::read_dir(format!("{}/objects", git_dir)).expect("Falló al leer");
+        entries.flatten().any(|entry| {
+            fs::read_dir(entry.path()).is_ok_and(|objects| {
+                objects.flatten().any(|object| {
+                    object
+                        .file_name()
+                        .to_string_lossy()
+                        .ends_with(TEMP_FILE_SUFFIX)
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn test_clone_empty_repository() {
+        let root = "./test_clone_empty_server";
+        let local_repo = "./test_clone_empty_local";
+        git_init_with_branch(&format!("{}/repo", root), "develop")
+            .expect("Falló al crear el repositorio remoto");
+
+        let (mut socket, port, server) = serve_once(root);
+        let result = git_clone(&mut socket, "127.0.0.1", &port, local_repo, "repo");
+        let server_result = server.join().expect("Falló el servidor");
+
+        let head = read_head(local_repo);
+        let branch_exists =
+            Path::new(&format!("{}/{}/{}/develop", local_repo, GIT_DIR, REF_HEADS)).exists();
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(server_result.is_ok());
+        assert!(result.is_ok());
+        // HEAD sigue a la branch anunciada por el servidor con `symref=HEAD:...`
+        assert_eq!(head, Ok(SymbolicRef::Ref("refs/heads/develop".to_string())));
+        assert!(!branch_exists);
+    }
+
+    #[test]
+    fn test_clone_partial_blob_none() {
+        let root = "./test_clone_partial_server";
+        let local_repo = "./test_clone_partial_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        commit_file(&remote, "a.txt", "contenido");
+        let remote_git_dir = format!("{}/{}", remote, GIT_DIR);
+        let blob = get_object_hashes(&remote_git_dir)
+            .expect("Falló al leer los objetos")
+            .into_iter()
+            .find(|hash| git_cat_file(&remote, hash, "-t").as_deref() == Ok(BLOB))
+            .expect("No se creó el blob");
+
+        let (mut socket, port, server) = serve_once(root);
+        let result = git_clone_filtered(
+            &mut socket,
+            "127.0.0.1",
+            &port,
+            local_repo,
+            "repo",
+            Some(FILTER_BLOB_NONE),
+        );
+        let server_result = server.join().expect("Falló el servidor");
+
+        let local_git_dir = format!("{}/{}", local_repo, GIT_DIR);
+        let types: Vec<String> = get_object_hashes(&local_git_dir)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|hash| git_cat_file(local_repo, hash, "-t").ok())
+            .collect();
+        let promisor = Path::new(&format!("{}/{}", local_git_dir, PROMISOR_FILE)).exists();
+        let missing_blob = git_cat_file(local_repo, &blob, "-p");
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(server_result.is_ok());
+        assert!(result.is_ok());
+        assert!(types.iter().any(|object_type| object_type == COMMIT));
+        assert!(types.iter().any(|object_type| object_type == TREE));
+        assert!(!types.iter().any(|object_type| object_type == BLOB));
+        assert!(promisor);
+        assert_eq!(
+            missing_blob,
+            Err(UtilError::MissingPromisorObject(blob).into())
+        );
+    }
+
+    #[test]
+    fn test_clone_branch_with_slashes() {
+        let root = "./test_clone_slash_server";
+        let local_repo = "./test_clone_slash_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        let first = commit_file(&remote, "a.txt", "uno");
+        let packed_refs = format!("{}/{}/{}", remote, GIT_DIR, PACKED_REFS);
+        create_file_replace(
+            &packed_refs,
+            &format!("{} refs/heads/feature/login\n", first),
+        )
+        .expect("Falló al crear la branch");
+
+        let (mut socket, port, server) = serve_once(root);
+        let result = git_clone(&mut socket, "127.0.0.1", &port, local_repo, "repo");
+        let server_result = server.join().expect("Falló el servidor");
+
+        let branch = git_rev_parse(local_repo, "refs/heads/feature/login");
+        let truncated =
+            Path::new(&format!("{}/{}/{}/login", local_repo, GIT_DIR, REF_HEADS)).exists();
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(server_result.is_ok());
+        assert!(result.is_ok());
+        assert_eq!(branch, Ok(first));
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_clone_annotated_tag() {
+        let root = "./test_clone_tag_server";
+        let local_repo = "./test_clone_tag_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        let tagged = commit_file(&remote, "a.txt", "uno");
+        let client = test_client(&remote, "9418");
+        git_tag_create(&remote, client, "v1.0", "primera version").expect("Falló la tag");
+        let tag_path = format!("{}/{}/{}/v1.0", remote, GIT_DIR, REFS_TAGS);
+        let tag_object = fs::read_to_string(&tag_path).expect("Falló al leer la tag");
+        commit_file(&remote, "a.txt", "dos");
+
+        let (mut socket, port, server) = serve_once(root);
+        let result = git_clone(&mut socket, "127.0.0.1", &port, local_repo, "repo");
+        let server_result = server.join().expect("Falló el servidor");
+
+        let local_tag =
+            fs::read_to_string(format!("{}/{}/{}/v1.0", local_repo, GIT_DIR, REFS_TAGS));
+        let tag_type = git_cat_file(local_repo, tag_object.trim(), "-t");
+        let peeled = resolve_commit(local_repo, "v1.0");
+        let tag_as_branch =
+            Path::new(&format!("{}/{}/{}/v1.0", local_repo, GIT_DIR, REF_HEADS)).exists();
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(server_result.is_ok());
+        assert!(result.is_ok());
+        assert_eq!(
+            local_tag.ok().as_deref().map(str::trim),
+            Some(tag_object.trim())
+        );
+        assert_eq!(tag_type.as_deref(), Ok(TAG));
+        assert_eq!(peeled, Ok(tagged));
+        assert!(!tag_as_branch);
+    }
+
+    #[test]
+    fn test_clone_resumes_after_interrupted_packfile() {
+        let root = "./test_clone_resume_server";
+        let full_repo = "./test_clone_resume_full";
+        let local_repo = "./test_clone_resume_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        commit_file(&remote, "a.txt", "uno");
+        // Un archivo grande, para que la conexión se corte a mitad de su objeto
+        let mut seed: u64 = 42;
+        let big: String = (0..64 * 1024)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (b'a' + ((seed >> 33) % 26) as u8) as char
+            })
+            .collect();
+        commit_file(&remote, "big.txt", &big);
+        // El tercer commit vuelve al tree del primero, que llega completo antes del corte
+        git_rm(&remote, "big.txt").expect("Falló al eliminar el archivo");
+        let third = commit_index(&remote, test_commit("tercero"));
+
+        let server_listener = TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar");
+        let server_address = server_listener.local_addr().expect("Falló").to_string();
+        let server = thread::spawn(move || {
+            let mut results = Vec::new();
+            for _ in 0..3 {
+                let (mut stream, _) = server_listener.accept().expect("Falló al aceptar");
+                let request = GitRequest::read_git_request(&mut stream).expect("Inválida");
+                results.push(request.execute(&mut stream, root));
+            }
+            results
+        });
+        let proxy = TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar el proxy");
+        let proxy_address = proxy.local_addr().expect("Falló").to_string();
+        let port = proxy_address
+            .rsplit(':')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        // Un clone sin cortes, para conocer cuántos bytes envía el servidor
+        let listener = proxy.try_clone().expect("Falló al clonar el proxy");
+        let address = server_address.clone();
+        let measure = thread::spawn(move || proxy_once(&listener, &address, None));
+        let mut socket = TcpStream::connect(&proxy_address).expect("Falló al conectar");
+        let full = git_clone(&mut socket, "127.0.0.1", &port, full_repo, "repo");
+        let total = measure.join().expect("Falló el proxy");
+
+        // El primer intento se corta en el último cuarto de la respuesta y el segundo lo retoma
+        let address = server_address.clone();
+        let proxies = thread::spawn(move || {
+            proxy_once(&proxy, &address, Some(total * 3 / 4));
+            proxy_once(&proxy, &address, None);
+        });
+        let result =
+            git_clone_resumable(&proxy_address, "127.0.0.1", &port, local_repo, "repo", None);
+        proxies.join().expect("Falló el proxy");
+        let server_results = server.join().expect("Falló el servidor");
+
+        let full_git_dir = format!("{}/{}", full_repo, GIT_DIR);
+        let local_git_dir = format!("{}/{}", local_repo, GIT_DIR);
+        let mut expected_objects = get_object_hashes(&full_git_dir).unwrap_or_default();
+        let mut objects = get_object_hashes(&local_git_dir).unwrap_or_default();
+        expected_objects.sort();
+        objects.sort();
+        let valid = objects_are_valid(&local_git_dir);
+        let temp_objects = has_temp_objects(&local_git_dir);
+        let in_progress = is_clone_in_progress(local_repo);
+        let head = git_rev_parse(local_repo, "master");
+        let content = fs::read_to_string(format!("{}/a.txt", local_repo));
+        let big_exists = Path::new(&format!("{}/big.txt", local_repo)).exists();
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(full_repo).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(full.is_ok());
+        assert!(result.is_ok());
+        // El segundo intento informó con `have` el commit recibido completo
+        assert_eq!(server_results[2], Ok("Fetch exitoso".to_string()));
+        assert_eq!(objects, expected_objects);
+        assert!(valid);
+        assert!(!temp_objects);
+        assert!(!in_progress);
+        assert_eq!(head, Ok(third));
+        assert_eq!(content.ok().as_deref(), Some("uno"));
+        assert!(!big_exists);
+    }
+}
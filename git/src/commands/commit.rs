@@ -1,27 +1,34 @@
 use super::errors::CommandsError;
-use super::log::insert_line_between_lines;
-use crate::commands::branch::get_doble_parent_hashes;
-use crate::commands::cat_file::git_cat_file;
+use super::reflog::{append_branch_reflog, append_reflog};
+use super::symbolic_ref::{read_head, set_detached_head, SymbolicRef};
+use crate::commands::cat_file::{git_cat_file, git_cat_file_typed};
 use crate::commands::checkout::get_tree_hash;
 use crate::consts::*;
 use crate::models::client::Client;
 use crate::util::files::*;
 use crate::util::index::{open_index, recovery_index};
+use crate::util::object_format::get_object_format;
 use crate::util::objects::builder_object_commit;
-use chrono::{DateTime, FixedOffset, Local, Utc};
+use chrono::{DateTime, FixedOffset, Local};
 use std::fs;
-use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::Path;
 
-use crate::commands::branch::{get_current_branch, get_parent_hashes};
+use crate::commands::branch::get_current_branch;
 
 use super::status::get_index_content;
 
-const COMMIT_EDITMSG: &str = "COMMIT_EDITMSG";
 const BRANCH_DIR: &str = "refs/heads/";
 
-#[derive(Clone)]
+/// Commit con su tree, sus parents en orden, el autor, el committer y el mensaje.
+///
+/// Las fechas de autor y committer se guardan con el formato del objeto commit:
+/// `<timestamp> <zona>`. Los headers que no se interpretan, como la firma `gpgsig`, se
+/// conservan para que el commit se vuelva a serializar sin cambios.
+///
+/// Los trailers (`Signed-off-by: ...`, `Co-authored-by: ...`) se guardan aparte del mensaje y
+/// se escriben al final del mensaje al serializar. En un commit leído de un objeto quedan
+/// dentro del mensaje.
+#[derive(Clone, Debug)]
 pub struct Commit {
     message: String,
     author_name: String,
@@ -29,6 +36,12 @@ pub struct Commit {
     committer_name: String,
     committer_email: String,
     date: DateTime<Local>,
+    tree: String,
+    parents: Vec<String>,
+    author_date: String,
+    committer_date: String,
+    extra_headers: Vec<String>,
+    trailers: Vec<String>,
 }
 
 impl Commit {
@@ -40,6 +53,7 @@ impl Commit {
         committer_email: String,
     ) -> Self {
         let date_time = Local::now();
+        let date = format_commit_date(date_time.timestamp());
 
         Commit {
             message,
@@ -48,7 +62,160 @@ impl Commit {
             committer_name,
             committer_email,
             date: date_time,
+            tree: String::new(),
+            parents: Vec::new(),
+            author_date: date.clone(),
+            committer_date: date,
+            extra_headers: Vec::new(),
+            trailers: Vec::new(),
+        }
+    }
+
+    /// Interpreta el contenido de un objeto commit: tree, todos los parents en orden, autor,
+    /// committer y mensaje. El mensaje es todo lo que sigue a la primera línea vacía.
+    ///
+    /// Los demás headers, como la firma `gpgsig`, se conservan tal cual para volver a
+    /// serializarlos; sus líneas de continuación comienzan con un espacio.
+    /// ###Parametros:
+    /// 'content': contenido del objeto commit, sin el header del objeto
+    pub fn from_object(content: &str) -> Result<Commit, CommandsError> {
+        let (header, message) = content.split_once("\n\n").unwrap_or((content, ""));
+        let mut tree = None;
+        let mut parents = Vec::new();
+        let mut author = None;
+        let mut committer = None;
+        let mut extra_headers: Vec<String> = Vec::new();
+        let mut in_extra_header = false;
+        for line in header.lines() {
+            if line.starts_with(' ') {
+                if let Some(extra_header) = extra_headers.last_mut().filter(|_| in_extra_header) {
+                    extra_header.push('\n');
+                    extra_header.push_str(line);
+                }
+                continue;
+            }
+            in_extra_header = false;
+            if let Some(hash) = line.strip_prefix("tree ") {
+                tree = Some(hash.trim().to_string());
+            } else if let Some(hash) = line.strip_prefix("parent ") {
+                if hash.trim() != PARENT_INITIAL {
+                    parents.push(hash.trim().to_string());
+                }
+            } else if let Some(signature) = line.strip_prefix("author ") {
+                author = parse_commit_signature(signature);
+            } else if let Some(signature) = line.strip_prefix("committer ") {
+                committer = parse_commit_signature(signature);
+            } else {
+                extra_headers.push(line.to_string());
+                in_extra_header = true;
+            }
+        }
+        let (tree, (author_name, author_email, author_date)) = match (tree, author) {
+            (Some(tree), Some(author)) => (tree, author),
+            _ => return Err(CommandsError::InvalidCommitObject),
+        };
+        let (committer_name, committer_email, committer_date) = committer.unwrap_or((
+            author_name.to_string(),
+            author_email.to_string(),
+            author_date.to_string(),
+        ));
+        // Una fecha inválida no impide leer el resto del commit
+        let date = committer_date
+            .split_whitespace()
+            .next()
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            .map(|date| date.with_timezone(&Local))
+            .unwrap_or_default();
+        Ok(Commit {
+            message: message.strip_suffix('\n').unwrap_or(message).to_string(),
+            author_name,
+            author_email,
+            committer_name,
+            committer_email,
+            date,
+            tree,
+            parents,
+            author_date,
+            committer_date,
+            extra_headers,
+            trailers: Vec::new(),
+        })
+    }
+
+    /// Genera el contenido del objeto commit, con una línea `parent` por cada parent en orden.
+    /// Los headers adicionales leídos del objeto (por ejemplo `gpgsig`) se escriben luego del
+    /// committer.
+    pub fn serialize(&self) -> String {
+        let mut content = format!("tree {}\n", self.tree);
+        for parent in &self.parents {
+            content.push_str(&format!("parent {}\n", parent));
+        }
+        content.push_str(&format!(
+            "author {} <{}> {}\ncommitter {} <{}> {}\n",
+            self.author_name,
+            self.author_email,
+            self.author_date,
+            self.committer_name,
+            self.committer_email,
+            self.committer_date,
+        ));
+        for extra_header in &self.extra_headers {
+            content.push_str(extra_header);
+            content.push('\n');
+        }
+        content.push_str(&format!("\n{}\n", self.get_full_message()));
+        content
+    }
+
+    /// Agrega un trailer `<clave>: <valor>` al final del mensaje. Un trailer idéntico a uno ya
+    /// agregado no se repite.
+    /// ###Parametros:
+    /// 'key': clave del trailer, por ejemplo `Co-authored-by`
+    /// 'value': valor del trailer
+    pub fn add_trailer(&mut self, key: &str, value: &str) {
+        let trailer = format!("{}: {}", key, value);
+        if !self.trailers.contains(&trailer) {
+            self.trailers.push(trailer);
+        }
+    }
+
+    /// Agrega el trailer `Signed-off-by` con el nombre y el mail del committer.
+    pub fn signoff(&mut self) {
+        let identity = format!("{} <{}>", self.committer_name, self.committer_email);
+        self.add_trailer(SIGNED_OFF_BY, &identity);
+    }
+
+    pub fn get_trailers(&self) -> &[String] {
+        &self.trailers
+    }
+
+    /// Devuelve el mensaje con los trailers, uno por línea. Si el último párrafo del mensaje ya
+    /// es un bloque de trailers, se agregan a continuación; si no, luego de una línea vacía.
+    pub fn get_full_message(&self) -> String {
+        if self.trailers.is_empty() {
+            return self.message.to_string();
         }
+        let message = self.message.trim_end();
+        let separator = match message.rsplit("\n\n").next() {
+            _ if message.is_empty() => "",
+            Some(paragraph) if paragraph.lines().all(is_trailer_line) => "\n",
+            _ => "\n\n",
+        };
+        format!("{}{}{}", message, separator, self.trailers.join("\n"))
+    }
+
+    pub fn set_tree(&mut self, tree: &str) {
+        self.tree = tree.to_string();
+    }
+
+    /// Reemplaza los parents del commit. El hash nulo del commit inicial no se guarda.
+    pub fn set_parents(&mut self, parents: &[&str]) {
+        self.parents = parents
+            .iter()
+            .filter(|parent| **parent != PARENT_INITIAL)
+            .map(|parent| parent.trim().to_string())
+            .collect();
     }
 
     pub fn get_message(&self) -> String {
@@ -74,13 +241,65 @@ impl Commit {
     pub fn get_date(&self) -> DateTime<Local> {
         self.date
     }
+
+    pub fn get_tree(&self) -> &str {
+        &self.tree
+    }
+
+    pub fn get_parents(&self) -> &[String] {
+        &self.parents
+    }
+
+    pub fn get_author_date(&self) -> &str {
+        &self.author_date
+    }
+
+    /// Devuelve la fecha del autor en su zona horaria, o `None` si el timestamp no es válido.
+    pub fn get_author_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        parse_signature_date(&self.author_date)
+    }
+
+    pub fn get_committer_date(&self) -> &str {
+        &self.committer_date
+    }
+
+    /// Devuelve la firma `gpgsig` del commit sin el plegado de líneas, si la tiene.
+    /// La firma no se verifica.
+    pub fn get_signature(&self) -> Option<String> {
+        let header = self
+            .extra_headers
+            .iter()
+            .find_map(|header| header.strip_prefix("gpgsig "))?;
+        let lines: Vec<&str> = header
+            .split('\n')
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect();
+        Some(lines.join("\n"))
+    }
 }
 
 /// Esta función se encarga de llamar al comando commit con los parametros necesarios
+///
+/// * `git commit -m <mensaje>`: commitea el index; falla si no hay cambios respecto del parent.
+/// * `git commit --allow-empty -m <mensaje>`: commitea aunque no haya cambios.
+/// * `git commit -s -m <mensaje>` o `--signoff`: agrega el trailer `Signed-off-by` con el
+///   nombre y el mail del cliente.
+///
 /// ###Parametros:
 /// 'args': Vector de Strings que contiene los parametros que se le pasaran al comando commit
 /// 'client': Cliente que contiene el directorio del repositorio local
 pub fn handle_commit(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let mut allow_empty = false;
+    let mut signoff = false;
+    let mut args = &args[..];
+    while let Some(flag) = args.first() {
+        match *flag {
+            "--allow-empty" => allow_empty = true,
+            "-s" | "--signoff" => signoff = true,
+            _ => break,
+        }
+        args = &args[1..];
+    }
     if args.is_empty() {
         return Err(CommandsError::InvalidArgumentCountCommitError);
     }
@@ -96,14 +315,20 @@ pub fn handle_commit(args: Vec<&str>, client: Client) -> Result<String, Commands
         .collect::<Vec<&str>>()
         .join(" ");
 
-    let commit = Commit::new(
+    let mut commit = Commit::new(
         message.to_string(),
         client.get_name().to_string(),
         client.get_email().to_string(),
         client.get_name().to_string(),
         client.get_email().to_string(),
     );
+    if signoff {
+        commit.signoff();
+    }
 
+    if allow_empty {
+        return git_commit_allow_empty(directory, commit);
+    }
     git_commit(directory, commit)
 }
 
@@ -113,15 +338,20 @@ pub fn handle_commit(args: Vec<&str>, client: Client) -> Result<String, Commands
 /// 'branch': nombre de la rama
 pub fn get_commits(directory: &str, branch: &str) -> Result<Vec<String>, CommandsError> {
     let mut commits: Vec<String> = Vec::new();
-    let git_dir = format!("{}/{}", directory, GIT_DIR);
-    let mut branch_current_path = format!("{}/{}{}", git_dir, BRANCH_DIR, branch);
-    if branch.contains('/') {
-        branch_current_path = format!("{}/{}", git_dir, branch);
+    let mut branch_current_path = get_git_path(directory, &format!("{}{}", BRANCH_DIR, branch));
+    if branch.contains('/') || branch == FETCH_HEAD {
+        branch_current_path = get_git_path(directory, branch);
     }
     let mut current_commit = String::new();
     if fs::metadata(&branch_current_path).is_ok() {
         let file = open_file(&branch_current_path)?;
-        current_commit = read_file_string(file)?;
+        // En FETCH_HEAD el hash es el primer campo de la primera línea
+        let content = read_file_string(file)?;
+        current_commit = content
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
     }
     commits.push(current_commit.clone());
     recovery_commits(&mut commits, directory, current_commit)?;
@@ -129,7 +359,8 @@ pub fn get_commits(directory: &str, branch: &str) -> Result<Vec<String>, Command
     Ok(commits)
 }
 
-/// Lee los parent commits y los guarda en un vector recibido por parámetro
+/// Lee los parent commits y los guarda en un vector recibido por parámetro.
+/// Se recorren todos los parents, por lo que se incluyen las ramas mergeadas.
 ///
 /// # Parametros
 ///
@@ -141,29 +372,12 @@ fn recovery_commits(
     directory: &str,
     current_commit: String,
 ) -> Result<(), CommandsError> {
-    let content_commit = git_cat_file(directory, &current_commit, "-p")?;
-    if content_commit.lines().count() == 7 {
-        let mut parent_hash = get_doble_parent_hashes(content_commit.clone());
-        if parent_hash != PARENT_INITIAL {
-            if !commits.contains(&parent_hash) {
-                commits.push(parent_hash.clone());
-            }
-            recovery_commits(commits, directory, parent_hash)?;
-        }
-        parent_hash = get_parent_hashes(content_commit);
-        if parent_hash != PARENT_INITIAL {
-            if !commits.contains(&parent_hash) {
-                commits.push(parent_hash.clone());
-            }
-            recovery_commits(commits, directory, parent_hash)?;
-        }
-    } else {
-        let parent_hash = get_parent_hashes(content_commit);
-        if parent_hash != PARENT_INITIAL {
-            if !commits.contains(&parent_hash) {
-                commits.push(parent_hash.clone());
-            }
-            recovery_commits(commits, directory, parent_hash)?;
+    let content_commit = git_cat_file_typed(directory, &current_commit, "-p", Some(COMMIT))?;
+    let commit = Commit::from_object(&content_commit)?;
+    for parent_hash in commit.get_parents() {
+        if !commits.contains(parent_hash) {
+            commits.push(parent_hash.to_string());
+            recovery_commits(commits, directory, parent_hash.to_string())?;
         }
     }
     Ok(())
@@ -174,7 +388,7 @@ fn recovery_commits(
 /// 'directory': Directorio del git
 /// 'msg': mensaje del commit
 fn builder_commit_msg_edit(directory: &str, msg: String) -> Result<(), CommandsError> {
-    let commit_msg_path = format!("{}/{}/{}", directory, GIT_DIR, COMMIT_EDITMSG);
+    let commit_msg_path = get_git_path(directory, COMMIT_EDITMSG);
     let mut file = match fs::File::create(commit_msg_path) {
         Ok(file) => file,
         Err(_) => return Err(CommandsError::CreateFileError),
@@ -187,169 +401,176 @@ fn builder_commit_msg_edit(directory: &str, msg: String) -> Result<(), CommandsE
     Ok(())
 }
 
-/// Creará el directorio donde se registran los commits y escribirá el contenido en el
-/// archivo con el nombre de la branch actual
+/// Indica si una línea del mensaje tiene el formato de un trailer: `<clave>: <valor>`, donde
+/// la clave está formada por letras, números y guiones.
 /// ###Parametros:
-/// 'directory': Directorio del git
-pub fn builder_commit_log(
-    directory: &str,
-    content: &str,
-    hash_commit: &str,
-    current_branch: &str,
-    path_log: &str,
-) -> Result<(), CommandsError> {
-    //logs/refs/heads
-    let logs_path = format!("{}/{}/{}", directory, GIT_DIR, path_log);
-    if !Path::new(&logs_path).exists() {
-        match fs::create_dir_all(logs_path.clone()) {
-            Ok(_) => (),
-            Err(_) => return Err(CommandsError::CreateDirError),
-        };
-    }
-    let mut lines: Vec<&str> = content.lines().collect();
-    if let Some(first_line) = lines.first_mut() {
-        *first_line = hash_commit;
+/// 'line': línea del mensaje
+fn is_trailer_line(line: &str) -> bool {
+    match line.split_once(": ") {
+        Some((key, _)) => {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+        None => false,
     }
-    let content_mod = lines.join("\n");
-    let content_mod_with_newline = format!("\n{}", content_mod);
-    let logs_path = format!("{}/{}", logs_path, current_branch);
-    let mut file = match OpenOptions::new().append(true).create(true).open(logs_path) {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::OpenFileError),
-    };
-    match file.write_all(content_mod_with_newline.as_bytes()) {
-        Ok(_) => (),
-        Err(_) => return Err(CommandsError::WriteFileError),
-    };
-    Ok(())
 }
 
-/// Funcion que crea el contenido a comprimir del objeto commit
-/// tree <hash-del-arbol> -> contiene las referencias a los archivos y directorios
-/// author Nombre del Autor <correo@ejemplo.com> Fecha
-/// committer Nombre del Commitador <correo@ejemplo.com> Fecha
-///
+/// Devuelve la fecha de un commit con el formato `<timestamp> <zona>`.
 /// ###Parametros:
-/// 'commit': Estructura que contiene la información del commit
-fn commit_content_format(commit: &Commit, tree_hash: &str, parent_hash: &str) -> String {
-    let date: DateTime<Utc> = Utc::now();
-    let timestamp = date.timestamp();
+/// 'timestamp': segundos desde epoch
+fn format_commit_date(timestamp: i64) -> String {
     let offset = FixedOffset::west_opt(3 * 3600).unwrap().to_string();
     let offset_format: String = offset.chars().filter(|&c| c != ':').collect();
-    if parent_hash == PARENT_INITIAL {
-        format!(
-            "tree {}\nauthor {} <{}> {} {}\ncommitter {} <{}> {} {}\n\n{}\n",
-            tree_hash,
-            commit.get_author_name(),
-            commit.get_author_email(),
-            timestamp,
-            offset_format,
-            commit.get_committer_name(),
-            commit.get_committer_email(),
-            timestamp,
-            offset_format,
-            commit.get_message()
-        )
-    } else {
-        format!(
-            "tree {}\nparent {}\nauthor {} <{}> {} {}\ncommitter {} <{}> {} {}\n\n{}\n",
-            tree_hash,
-            parent_hash,
-            commit.get_author_name(),
-            commit.get_author_email(),
-            timestamp,
-            offset_format,
-            commit.get_committer_name(),
-            commit.get_committer_email(),
-            timestamp,
-            offset_format,
-            commit.get_message()
-        )
+    format!("{} {}", timestamp, offset_format)
+}
+
+/// Interpreta la fecha de una firma (`<timestamp> <zona>`) en la zona horaria de quien la hizo.
+/// Si la zona no tiene el formato `±HHMM` se usa UTC.
+/// ###Parametros:
+/// 'date': fecha con el formato del objeto commit
+pub fn parse_signature_date(date: &str) -> Option<DateTime<FixedOffset>> {
+    let mut parts = date.split_whitespace();
+    let timestamp = parts.next()?.parse::<i64>().ok()?;
+    let offset = parts
+        .next()
+        .and_then(parse_timezone)
+        .unwrap_or(FixedOffset::east_opt(0)?);
+    Some(DateTime::from_timestamp(timestamp, 0)?.with_timezone(&offset))
+}
+
+/// Formatea la fecha de una firma como la muestra git, en la zona horaria de quien la hizo,
+/// por ejemplo `Sun Dec 3 16:43:54 2023 -0300`. Si la fecha no es válida devuelve `unknown`.
+/// ###Parametros:
+/// 'date': fecha con el formato del objeto commit
+pub fn format_signature_date(date: &str) -> String {
+    parse_signature_date(date).map_or("unknown".to_string(), |date| {
+        date.format(DATE_FORMAT).to_string()
+    })
+}
+
+/// Convierte una zona horaria con el formato `±HHMM` en un offset.
+/// ###Parametros:
+/// 'timezone': zona horaria, por ejemplo `-0300`
+fn parse_timezone(timezone: &str) -> Option<FixedOffset> {
+    if timezone.len() != 5 {
+        return None;
+    }
+    let sign = match timezone.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let hours: i32 = timezone.get(1..3)?.parse().ok()?;
+    let minutes: i32 = timezone.get(3..5)?.parse().ok()?;
+    if minutes >= 60 {
+        return None;
     }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
 }
 
-fn merge_commit_content_format(
-    merge_commit: &Commit,
-    tree_hash: &str,
-    parent1_hash: &str,
-    parent2_hash: &str,
-) -> String {
-    let date: DateTime<Utc> = Utc::now();
-    let timestamp = date.timestamp();
-    let offset = FixedOffset::west_opt(3 * 3600).unwrap().to_string();
-    let offset_format: String = offset.chars().filter(|&c| c != ':').collect();
-    format!(
-        "tree {}\nparent {}\nparent {}\nauthor {} <{}> {} {}\ncommitter {} <{}> {} {}\n\n{}\n",
-        tree_hash,
-        parent1_hash,
-        parent2_hash,
-        merge_commit.get_author_name(),
-        merge_commit.get_author_email(),
-        timestamp,
-        offset_format,
-        merge_commit.get_committer_name(),
-        merge_commit.get_committer_email(),
-        timestamp,
-        offset_format,
-        merge_commit.get_message()
-    )
+/// Separa la firma de una línea author o committer (`Nombre <mail> <timestamp> <zona>`) en
+/// nombre, mail y fecha.
+/// ###Parametros:
+/// 'signature': firma sin el prefijo author o committer
+fn parse_commit_signature(signature: &str) -> Option<(String, String, String)> {
+    let (name, rest) = signature.split_once(" <")?;
+    let (email, date) = rest.split_once('>')?;
+    Some((name.to_string(), email.to_string(), date.trim().to_string()))
 }
 
-/// Esta función genera y crea el objeto commit
+/// Esta función genera y crea el objeto commit. Si el tree del index es igual al del parent,
+/// devuelve `CommandsError::NothingToCommit`.
 /// ###Parametros:
 /// 'directory': Directorio del git
 /// 'commit': Estructura que contiene la información del commit
 pub fn git_commit(directory: &str, commit: Commit) -> Result<String, CommandsError> {
-    let git_dir = format!("{}/{}", directory, GIT_DIR);
-    check_index_content(&git_dir)?;
+    create_commit(directory, commit, false)
+}
 
-    let current_branch = get_current_branch(directory)?;
-    let branch_current_path = format!("{}/{}{}", git_dir, BRANCH_DIR, current_branch);
+/// Esta función genera y crea el objeto commit aunque su tree sea igual al del parent.
+/// ###Parametros:
+/// 'directory': Directorio del git
+/// 'commit': Estructura que contiene la información del commit
+pub fn git_commit_allow_empty(directory: &str, commit: Commit) -> Result<String, CommandsError> {
+    create_commit(directory, commit, true)
+}
 
-    let mut contents = String::new();
-    if fs::metadata(&branch_current_path).is_ok() {
-        let file = open_file(&branch_current_path)?;
-        contents = read_file_string(file)?;
-    }
+/// Genera el objeto commit con el tree del index y lo agrega a la branch actual.
+/// ###Parametros:
+/// 'directory': Directorio del git
+/// 'commit': Estructura que contiene la información del commit
+/// 'allow_empty': si es false, falla cuando el tree es igual al del parent
+fn create_commit(
+    directory: &str,
+    commit: Commit,
+    allow_empty: bool,
+) -> Result<String, CommandsError> {
+    let git_dir = get_git_dir(directory);
+    check_index_content(&git_dir)?;
+
+    // En estado detached HEAD el parent es el hash de HEAD y no hay branch que actualizar
+    let (current_branch, contents) = match read_head(directory)? {
+        SymbolicRef::Detached(hash) => (None, hash),
+        head => {
+            let current_branch = head.get_branch_name()?.to_string();
+            let branch_current_path =
+                get_git_path(directory, &format!("{}{}", BRANCH_DIR, current_branch));
+            let mut contents = String::new();
+            if fs::metadata(&branch_current_path).is_ok() {
+                let file = open_file(&branch_current_path)?;
+                contents = read_file_string(file)?;
+            }
+            (Some(current_branch), contents)
+        }
+    };
     let parent_hash = if contents.is_empty() {
         PARENT_INITIAL.to_string()
     } else {
         contents
     };
 
+    let object_format = get_object_format(&git_dir);
     let index_content = open_index(&git_dir)?;
-    let tree_hash = recovery_index(&index_content, &git_dir)?;
-    if parent_hash != PARENT_INITIAL {
+    let tree_hash = recovery_index(&index_content, &git_dir, object_format)?;
+    if parent_hash != PARENT_INITIAL && !allow_empty {
         let content_commit = git_cat_file(directory, &parent_hash, "-p")?;
         if let Some(hash_tree_commit) = get_tree_hash(&content_commit) {
             if tree_hash == hash_tree_commit {
-                return Ok("nothing to commit, working tree clean".to_string());
+                return Err(CommandsError::NothingToCommit);
             }
         };
     }
-    let mut commit_content = commit_content_format(&commit, &tree_hash, &parent_hash);
-    let hash_commit = builder_object_commit(&commit_content, &git_dir)?;
-    if commit_content.lines().count() == 5 {
-        commit_content = insert_line_between_lines(&commit_content, 1, PARENT_INITIAL);
+    let mut commit = commit;
+    commit.set_tree(&tree_hash);
+    commit.set_parents(&[&parent_hash]);
+    let commit_content = commit.serialize();
+    let hash_commit = builder_object_commit(&commit_content, &git_dir, object_format)?;
+    builder_commit_msg_edit(directory, commit.get_full_message())?;
+
+    match &current_branch {
+        Some(current_branch) => create_or_replace_commit_into_branch(
+            current_branch.clone(),
+            get_git_path(directory, &format!("{}{}", BRANCH_DIR, current_branch)),
+            hash_commit.clone(),
+        )?,
+        None => set_detached_head(directory, &hash_commit)?,
     }
-    builder_commit_log(
+    let action = if parent_hash == PARENT_INITIAL {
+        "commit (initial)"
+    } else {
+        "commit"
+    };
+    update_reflogs(
         directory,
-        &commit_content,
+        current_branch.as_deref(),
+        &commit,
+        &parent_hash,
         &hash_commit,
-        &current_branch,
-        "logs/refs/heads",
-    )?;
-    builder_commit_msg_edit(directory, commit.get_message())?;
-
-    create_or_replace_commit_into_branch(
-        current_branch.clone(),
-        branch_current_path,
-        hash_commit.clone(),
+        &format!("{}: {}", action, commit.get_message()),
     )?;
 
     let response = format!(
         "[{} {}] {}",
-        current_branch,
+        current_branch.as_deref().unwrap_or("detached HEAD"),
         &hash_commit.as_str()[..7],
         commit.get_message()
     );
@@ -358,6 +579,8 @@ pub fn git_commit(directory: &str, commit: Commit) -> Result<String, CommandsErr
 }
 
 /// Esta función genera y crea el objeto merge commit. Es un tipo de commit especifico que tiene dos parents.
+/// El merge commit se crea aunque su tree sea igual al del primer parent, como con
+/// `--allow-empty`, por ejemplo al mergear una PR que no cambia archivos.
 /// ###Parametros:
 /// 'directory': Directorio del git
 /// 'commit': Estructura que contiene la información del commit
@@ -369,31 +592,35 @@ pub fn merge_commit(
     parent1_hash: &str,
     parent2_hash: &str,
 ) -> Result<String, CommandsError> {
-    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    let git_dir = get_git_dir(directory);
     check_index_content(&git_dir)?;
 
     let current_branch = get_current_branch(directory)?;
-    let branch_current_path = format!("{}/{}{}", git_dir, BRANCH_DIR, current_branch);
+    let branch_current_path = get_git_path(directory, &format!("{}{}", BRANCH_DIR, current_branch));
 
+    let object_format = get_object_format(&git_dir);
     let index_content = open_index(&git_dir)?;
-    let tree_hash = recovery_index(&index_content, &git_dir)?;
-    let commit_content =
-        merge_commit_content_format(&commit, &tree_hash, parent1_hash, parent2_hash);
-    let hash_commit = builder_object_commit(&commit_content, &git_dir)?;
-    builder_commit_log(
-        directory,
-        &commit_content,
-        &hash_commit,
-        &current_branch,
-        "logs/refs/heads",
-    )?;
-    builder_commit_msg_edit(directory, commit.get_message())?;
+    let tree_hash = recovery_index(&index_content, &git_dir, object_format)?;
+    let mut commit = commit;
+    commit.set_tree(&tree_hash);
+    commit.set_parents(&[parent1_hash, parent2_hash]);
+    let commit_content = commit.serialize();
+    let hash_commit = builder_object_commit(&commit_content, &git_dir, object_format)?;
+    builder_commit_msg_edit(directory, commit.get_full_message())?;
 
     create_or_replace_commit_into_branch(
         current_branch.clone(),
         branch_current_path,
         hash_commit.clone(),
     )?;
+    update_reflogs(
+        directory,
+        Some(&current_branch),
+        &commit,
+        parent1_hash,
+        &hash_commit,
+        &format!("commit (merge): {}", commit.get_message()),
+    )?;
 
     let response = format!(
         "[{} {}] {}",
@@ -415,31 +642,41 @@ pub fn rebase_commit(
     commit: Commit,
     parent_hash: &str,
 ) -> Result<String, CommandsError> {
-    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    let git_dir = get_git_dir(directory);
     check_index_content(&git_dir)?;
 
     let current_branch = get_current_branch(directory)?;
-    let branch_current_path = format!("{}/{}{}", git_dir, BRANCH_DIR, current_branch);
+    let branch_current_path = get_git_path(directory, &format!("{}{}", BRANCH_DIR, current_branch));
 
+    let object_format = get_object_format(&git_dir);
     let index_content = open_index(&git_dir)?;
-    let tree_hash = recovery_index(&index_content, &git_dir)?;
+    let tree_hash = recovery_index(&index_content, &git_dir, object_format)?;
 
-    let commit_content = commit_content_format(&commit, &tree_hash, parent_hash);
-    let hash_commit = builder_object_commit(&commit_content, &git_dir)?;
-    builder_commit_log(
-        directory,
-        &commit_content,
-        &hash_commit,
-        &current_branch,
-        "logs/refs/heads",
-    )?;
-    builder_commit_msg_edit(directory, commit.get_message())?;
+    let mut commit = commit;
+    commit.set_tree(&tree_hash);
+    commit.set_parents(&[parent_hash]);
+    let commit_content = commit.serialize();
+    let hash_commit = builder_object_commit(&commit_content, &git_dir, object_format)?;
+    builder_commit_msg_edit(directory, commit.get_full_message())?;
+
+    let old_hash = match open_file(&branch_current_path) {
+        Ok(file) => read_file_string(file)?,
+        Err(_) => PARENT_INITIAL.to_string(),
+    };
 
     create_or_replace_commit_into_branch(
         current_branch.clone(),
         branch_current_path,
         hash_commit.clone(),
     )?;
+    update_reflogs(
+        directory,
+        Some(&current_branch),
+        &commit,
+        &old_hash,
+        &hash_commit,
+        &format!("rebase: {}", commit.get_message()),
+    )?;
 
     let response = format!(
         "[{} {}] {}",
@@ -470,6 +707,40 @@ pub fn create_or_replace_commit_into_branch(
     Ok(())
 }
 
+/// Agrega al reflog de la branch actual y al de HEAD el cambio producido por un nuevo commit.
+/// ###Parametros:
+/// 'directory': Directorio del git
+/// 'current_branch': Nombre de la branch actual, o `None` en estado detached HEAD
+/// 'commit': Estructura que contiene la información del commit, de la que se toma el committer
+/// 'old_hash': hash al que apuntaba la branch antes del commit
+/// 'new_hash': hash del nuevo commit
+/// 'message': descripción del cambio
+fn update_reflogs(
+    directory: &str,
+    current_branch: Option<&str>,
+    commit: &Commit,
+    old_hash: &str,
+    new_hash: &str,
+    message: &str,
+) -> Result<(), CommandsError> {
+    let identity = format!(
+        "{} <{}>",
+        commit.get_committer_name(),
+        commit.get_committer_email()
+    );
+    match current_branch {
+        Some(current_branch) => append_branch_reflog(
+            directory,
+            current_branch,
+            old_hash,
+            new_hash,
+            &identity,
+            message,
+        ),
+        None => append_reflog(directory, HEAD, old_hash, new_hash, &identity, message),
+    }
+}
+
 /// Esta función chequea que el index no este vacio.
 /// ###Parametros:
 /// 'git_dir': Directorio del git
@@ -484,10 +755,34 @@ fn check_index_content(git_dir: &str) -> Result<(), CommandsError> {
 #[cfg(test)]
 mod tests {
 
-    use crate::commands::{add::git_add, init::git_init};
+    use crate::commands::{add::git_add, init::git_init, rm::git_rm};
 
     use super::*;
 
+    #[test]
+    fn test_signature_date_with_timezone() {
+        let content = "tree t\nauthor Valen <mail> 1701632634 +0530\ncommitter Valen <mail> 1701632634 -0300\n\nmensaje\n";
+        let commit = Commit::from_object(content).expect("Falló al leer el commit");
+        let date = commit.get_author_datetime().expect("Fecha inválida");
+
+        assert_eq!(date.offset().local_minus_utc(), 5 * 3600 + 30 * 60);
+        assert_eq!(date.timestamp(), 1701632634);
+        assert_eq!(
+            format_signature_date(commit.get_author_date()),
+            "Mon Dec 4 01:13:54 2023 +0530"
+        );
+        assert_eq!(
+            format_signature_date(commit.get_committer_date()),
+            "Sun Dec 3 16:43:54 2023 -0300"
+        );
+        // Una zona mal formada se toma como UTC
+        assert_eq!(
+            format_signature_date("1701632634 +05:30"),
+            "Sun Dec 3 19:43:54 2023 +0000"
+        );
+        assert_eq!(format_signature_date("fecha +0530"), "unknown");
+    }
+
     #[test]
     fn commit_test() {
         let directory = "./test_commit_repo";
@@ -514,4 +809,218 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_commit_empty_requires_allow_empty() {
+        let directory = "./test_commit_allow_empty";
+        git_init(directory).expect("Falló en el comando init");
+        let file_path = format!("{}/{}", directory, "holamundo.txt");
+        let mut file = fs::File::create(&file_path).expect("Falló al crear el archivo");
+        file.write_all(b"Hola Mundo")
+            .expect("Error al escribir en el archivo");
+        git_add(directory, "holamundo.txt").expect("Fallo en el comando add");
+        let test_commit = Commit::new(
+            "prueba".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(directory, test_commit.clone()).expect("Error commit");
+        let branch_path = format!("{}/.git/refs/heads/master", directory);
+        let first = fs::read_to_string(&branch_path).expect("Error al leer la branch");
+
+        // Sin cambios en el index el commit se rechaza y la branch no se mueve
+        let result = git_commit(directory, test_commit.clone());
+        let unchanged = fs::read_to_string(&branch_path).expect("Error al leer la branch");
+
+        let result_allow_empty = git_commit_allow_empty(directory, test_commit);
+        let second = fs::read_to_string(&branch_path).expect("Error al leer la branch");
+        let content = git_cat_file(directory, &second, "-p").expect("Error al leer el commit");
+        let first_content = git_cat_file(directory, &first, "-p").expect("Error al leer el commit");
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        assert_eq!(result, Err(CommandsError::NothingToCommit));
+        assert_eq!(unchanged, first);
+        assert!(result_allow_empty.is_ok());
+        assert_ne!(second, first);
+        let commit = Commit::from_object(&content).expect("Commit inválido");
+        assert_eq!(commit.get_parents(), [first]);
+        assert_eq!(Some(commit.get_tree()), get_tree_hash(&first_content));
+    }
+
+    #[test]
+    fn test_commit_staged_deletion() {
+        let directory = "./test_commit_staged_deletion";
+        git_init(directory).expect("Falló en el comando init");
+        for file_name in ["a.txt", "a.txt.bak", "b.txt"] {
+            fs::write(format!("{}/{}", directory, file_name), file_name)
+                .expect("Falló al crear el archivo");
+            git_add(directory, file_name).expect("Fallo en el comando add");
+        }
+        let test_commit = Commit::new(
+            "prueba".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(directory, test_commit.clone()).expect("Error commit");
+
+        // Un archivo se borra y se agrega, el otro se borra con rm
+        fs::remove_file(format!("{}/a.txt", directory)).expect("Falló al borrar el archivo");
+        fs::remove_file(format!("{}/b.txt", directory)).expect("Falló al borrar el archivo");
+        git_add(directory, "a.txt").expect("Fallo en el comando add");
+        let result_rm = git_rm(directory, "b.txt");
+        let result = git_commit(directory, test_commit);
+        let branch_path = format!("{}/.git/refs/heads/master", directory);
+        let head = fs::read_to_string(&branch_path).expect("Error al leer la branch");
+        let content = git_cat_file(directory, &head, "-p").expect("Error al leer el commit");
+        let tree_hash = get_tree_hash(&content)
+            .expect("Commit sin tree")
+            .to_string();
+        let tree = git_cat_file(directory, &tree_hash, "-p").expect("Error al leer el tree");
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        assert_eq!(result_rm, Ok("rm 'b.txt'".to_string()));
+        assert!(result.is_ok());
+        let names: Vec<&str> = tree
+            .lines()
+            .filter_map(|line| line.split_whitespace().find(|part| part.contains(".txt")))
+            .collect();
+        assert_eq!(names, ["a.txt.bak"]);
+    }
+
+    #[test]
+    fn test_commit_trailers() {
+        let directory = "./test_commit_trailers";
+        git_init(directory).expect("Falló en el comando init");
+        create_file_replace(&format!("{}/a.txt", directory), "uno").expect("Falló al crear");
+        git_add(directory, "a.txt").expect("Fallo en el comando add");
+
+        let mut commit = Commit::new(
+            "agrega a.txt\n\nDetalle del cambio".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Valen".to_string(),
+            "vlanzillotta@fi.uba.ar".to_string(),
+        );
+        commit.add_trailer(CO_AUTHORED_BY, "Juan <jdr@fi.uba.ar>");
+        commit.signoff();
+        commit.signoff();
+        let result = git_commit(directory, commit);
+        let hash = fs::read_to_string(format!("{}/.git/refs/heads/master", directory))
+            .expect("Falló al leer la branch");
+        let content = git_cat_file(directory, hash.trim(), "-p");
+        let reflog = fs::read_to_string(format!("{}/.git/logs/HEAD", directory));
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        assert!(result.is_ok());
+        let content = content.expect("Falló al leer el commit");
+        assert!(content.ends_with(
+            "\n\nagrega a.txt\n\nDetalle del cambio\n\nCo-authored-by: Juan <jdr@fi.uba.ar>\nSigned-off-by: Valen <vlanzillotta@fi.uba.ar>\n"
+        ));
+        // El reflog solo registra el mensaje, sin los trailers
+        assert!(!reflog
+            .expect("Falló al leer el reflog")
+            .contains(SIGNED_OFF_BY));
+
+        // Si el mensaje ya termina en un bloque de trailers, se agregan a continuación
+        let mut commit = Commit::new(
+            "mensaje\n\nReviewed-by: Ana <ana@fi.uba.ar>".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        commit.signoff();
+        assert_eq!(
+            commit.get_full_message(),
+            "mensaje\n\nReviewed-by: Ana <ana@fi.uba.ar>\nSigned-off-by: Juan <jdr@fi.uba.ar>"
+        );
+    }
+
+    #[test]
+    fn test_commit_two_parents_round_trip() {
+        let first = "0123456789abcdef0123456789abcdef01234567";
+        let second = "89abcdef0123456789abcdef0123456789abcdef";
+        let mut commit = Commit::new(
+            "Merge branch 'feature'\n\nDetalle del merge".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Valen".to_string(),
+            "vlanzillotta@fi.uba.ar".to_string(),
+        );
+        commit.set_tree("4abc6b1e60a965f4dfefbc673322f1c6e98c8b08");
+        commit.set_parents(&[first, second]);
+
+        let content = commit.serialize();
+        let parent_lines: Vec<&str> = content
+            .lines()
+            .filter(|line| line.starts_with("parent "))
+            .collect();
+        assert_eq!(
+            parent_lines,
+            vec![format!("parent {}", first), format!("parent {}", second)]
+        );
+
+        let parsed = Commit::from_object(&content).expect("Falló al leer el commit");
+        assert_eq!(
+            parsed.get_tree(),
+            "4abc6b1e60a965f4dfefbc673322f1c6e98c8b08"
+        );
+        assert_eq!(parsed.get_parents(), [first, second]);
+        assert_eq!(parsed.get_author_name(), "Juan");
+        assert_eq!(parsed.get_author_email(), "jdr@fi.uba.ar");
+        assert_eq!(parsed.get_committer_name(), "Valen");
+        assert_eq!(parsed.get_committer_email(), "vlanzillotta@fi.uba.ar");
+        assert_eq!(parsed.get_committer_date(), commit.get_committer_date());
+        assert_eq!(parsed.get_message(), commit.get_message());
+        assert_eq!(parsed.serialize(), content);
+
+        assert!(matches!(
+            Commit::from_object("no es un commit"),
+            Err(CommandsError::InvalidCommitObject)
+        ));
+    }
+
+    #[test]
+    fn test_commit_from_object_with_signature() {
+        let content = "tree 4abc6b1e60a965f4dfefbc673322f1c6e98c8b08\n\
+            parent ebc52673798d1baf34d9c8b13022c745bac28880\n\
+            author S2JuanS2 <juansdelrio@hotmail.com> 1701632634 -0300\n\
+            committer GitHub <noreply@github.com> 1701632634 -0300\n\
+            gpgsig -----BEGIN PGP SIGNATURE-----\n \n \
+            wsBcBAABCAAQBQJlbJ56CRBK7hj4Ov3rIwAAdHIIAFakeSignature\n \
+            =abcd\n \
+            -----END PGP SIGNATURE-----\n\
+            \n\
+            carpeta\n\
+            \n\
+            parent no es un header\n";
+
+        let commit = Commit::from_object(content).expect("Falló al leer el commit");
+        assert_eq!(
+            commit.get_tree(),
+            "4abc6b1e60a965f4dfefbc673322f1c6e98c8b08"
+        );
+        assert_eq!(
+            commit.get_parents(),
+            ["ebc52673798d1baf34d9c8b13022c745bac28880"]
+        );
+        assert_eq!(commit.get_committer_name(), "GitHub");
+        assert_eq!(commit.get_message(), "carpeta\n\nparent no es un header");
+        assert_eq!(
+            commit.get_signature(),
+            Some(
+                "-----BEGIN PGP SIGNATURE-----\n\n\
+                wsBcBAABCAAQBQJlbJ56CRBK7hj4Ov3rIwAAdHIIAFakeSignature\n=abcd\n\
+                -----END PGP SIGNATURE-----"
+                    .to_string()
+            )
+        );
+        assert_eq!(commit.serialize(), content);
+    }
 }
@@ -3,13 +3,32 @@ use std::fs;
 use std::fs::File;
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use crate::{
-    consts::{CONFIG_FILE, CONFIG_REMOTE_FETCH, GIT_DIR},
+    consts::{CONFIG_FILE, CONFIG_REMOTE_FETCH, CORE_IGNORECASE, GIT_DIR, OBJECT_FORMAT_KEY},
     git_server::GitServer,
+    util::object_format::ObjectFormat,
 };
 
+use super::check_ignore::glob_match;
 use super::errors::CommandsError;
+use crate::models::client::Client;
+
+/// Secciones de un archivo de configuración, con sus claves y valores.
+type ConfigSections = HashMap<String, HashMap<String, String>>;
+
+/// Secciones y directivas de inclusión del archivo principal, en el orden en que aparecen.
+type ConfigLayout = Vec<ConfigItem>;
+
+/// Elemento del archivo de configuración cuya posición se conserva al volver a escribirlo.
+#[derive(Debug)]
+enum ConfigItem {
+    /// Encabezado de una sección, por ejemplo `core` o `remote "origin"`.
+    Section(String),
+    /// Directiva `path` de una sección `[include]` o `[includeIf "..."]`, como (sección, ruta).
+    Include(String, String),
+}
 
 #[derive(Debug)]
 struct BranchInfo {
@@ -57,6 +76,14 @@ impl BranchInfo {
         matches!(attribute, "remote" | "merge")
     }
 
+    /// Devuelve las entradas definidas, como pares (clave, valor), en el orden en que se escriben.
+    fn entries(&self) -> Vec<(&str, &str)> {
+        [("remote", &self.remote), ("merge", &self.merge)]
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.as_deref()?)))
+            .collect()
+    }
+
     fn get_value(&self, key: &str) -> Option<&str> {
         match key {
             "remote" => self.remote.as_deref(),
@@ -132,6 +159,14 @@ impl RemoteInfo {
     pub fn valid_attribute(attribute: &str) -> bool {
         matches!(attribute, "url" | "fetch")
     }
+
+    /// Devuelve las entradas definidas, como pares (clave, valor), en el orden en que se escriben.
+    fn entries(&self) -> Vec<(&str, &str)> {
+        [("url", &self.url), ("fetch", &self.fetch)]
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.as_deref()?)))
+            .collect()
+    }
 }
 
 /// Representa la configuración de Git con secciones específicas.
@@ -142,12 +177,24 @@ impl RemoteInfo {
 /// * `core`: HashMap que contiene la información de la sección "core".
 /// * `remotes`: HashMap que contiene la información de la sección "remote.origin".
 /// * `branch`: HashMap que contiene la información de la sección "branch.main".
+/// * `extensions`: HashMap que contiene la información de la sección "extensions", por ejemplo
+///   el formato de los objetos del repositorio.
+/// * `merge`: HashMap que contiene la información de la sección "merge", por ejemplo el estilo
+///   de las marcas de conflicto.
+/// * `receive`: HashMap que contiene la información de la sección "receive", por ejemplo los
+///   límites de los packfiles que se reciben en un push.
+/// * `layout`: Orden de las secciones y de las directivas `[include]` e `[includeIf]` del
+///   archivo, que se conserva al volver a escribirlo.
 ///
 #[derive(Debug)]
 pub struct GitConfig {
     core: HashMap<String, String>,
     remotes: HashMap<String, RemoteInfo>,
     branch: HashMap<String, BranchInfo>,
+    extensions: HashMap<String, String>,
+    merge: HashMap<String, String>,
+    receive: HashMap<String, String>,
+    layout: ConfigLayout,
 }
 
 impl Default for GitConfig {
@@ -162,6 +209,10 @@ impl GitConfig {
             core: HashMap::new(),
             remotes: HashMap::new(),
             branch: HashMap::new(),
+            extensions: HashMap::new(),
+            merge: HashMap::new(),
+            receive: HashMap::new(),
+            layout: Vec::new(),
         }
     }
 
@@ -179,22 +230,51 @@ impl GitConfig {
     ///
     /// Esta función generará un pánico si hay problemas al leer la configuración de Git desde el repositorio.
     ///
+    /// Los archivos indicados en `[include]` y en los `[includeIf "gitdir:..."]` cuya condición
+    /// se cumple se leen en el lugar de la directiva, por lo que las entradas posteriores del
+    /// archivo tienen prioridad sobre las incluidas.
+    ///
     pub fn new_from_file(repo: &str) -> Result<Self, CommandsError> {
         let path = format!("{}/{}/{}", repo, GIT_DIR, CONFIG_FILE);
         GitConfig::_new_from_file(&path)
     }
 
     fn _new_from_file(path: &str) -> Result<Self, CommandsError> {
+        GitConfig::read_config(path, true)
+    }
+
+    /// Crea una instancia de `GitConfig` a partir del archivo de configuración indicado, por
+    /// ejemplo el `config` del directorio git compartido de un working tree enlazado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta al archivo de configuración.
+    ///
+    pub fn new_from_path(path: &str) -> Result<Self, CommandsError> {
+        GitConfig::_new_from_file(path)
+    }
+
+    /// Lee solo el archivo de configuración del repositorio, sin los archivos incluidos, para
+    /// modificarlo y volver a escribirlo sin copiar en él los valores incluidos.
+    fn new_from_file_without_includes(repo: &str) -> Result<Self, CommandsError> {
+        GitConfig::read_config(&get_config_path(repo), false)
+    }
+
+    fn read_config(path: &str, follow_includes: bool) -> Result<Self, CommandsError> {
         let mut git_config = GitConfig::new();
-        match read_format_config(path) {
-            Ok(section) => {
+        match read_format_config(path, follow_includes) {
+            Ok((section, layout)) => {
                 for (name, attributes) in section {
                     for (key, value) in attributes {
                         git_config.add_entry(&key, &value, &name)?;
                     }
                 }
+                git_config.layout = layout;
                 Ok(git_config)
             }
+            Err(CommandsError::ConfigIncludeCycle(path)) => {
+                Err(CommandsError::ConfigIncludeCycle(path))
+            }
             Err(_) => Err(CommandsError::FileNotFoundConfig),
         }
     }
@@ -212,6 +292,11 @@ impl GitConfig {
     pub fn new_from_server(server: &GitServer) -> Result<Self, CommandsError> {
         let mut git_config = GitConfig::new();
         git_config.add_entry("url", &server.src_repo.to_string(), "remote origin")?;
+        // El clone usa el formato de los objetos del servidor
+        if server.object_format != ObjectFormat::Sha1 {
+            git_config.add_entry("repositoryformatversion", "1", "core")?;
+            git_config.add_entry(OBJECT_FORMAT_KEY, server.object_format.name(), "extensions")?;
+        }
         for refs in server.get_references() {
             let name = refs.get_name().to_string();
             let mut branch_info = BranchInfo::new();
@@ -240,9 +325,22 @@ impl GitConfig {
             self.core.insert(key.to_string(), value.to_string());
             return Ok(());
         };
+        if section == "extensions" {
+            // Las claves no distinguen mayúsculas, `objectFormat` es lo mismo que `objectformat`
+            self.extensions
+                .insert(key.to_lowercase(), value.to_string());
+            return Ok(());
+        };
+        if section == "merge" {
+            self.merge.insert(key.to_lowercase(), value.to_string());
+            return Ok(());
+        };
+        if section == "receive" {
+            self.receive.insert(key.to_lowercase(), value.to_string());
+            return Ok(());
+        };
         let parts: Vec<&str> = section.split_whitespace().collect();
         if parts.len() != 2 {
-            println!("parts: {:?}", parts);
             return Err(CommandsError::InvalidEntryConfigFile);
         }
         match parts[0].trim() {
@@ -305,35 +403,78 @@ impl GitConfig {
 
     fn _write_to_file(&self, file_path: &str) -> io::Result<()> {
         let mut file = File::create(file_path)?;
+        let mut sections = self.format_sections();
+
+        // Las secciones leídas del archivo y las directivas de inclusión se escriben en su
+        // posición original, para que la prioridad entre los valores incluidos y los propios
+        // no cambie. Las secciones nuevas se agregan al final.
+        for item in &self.layout {
+            match item {
+                ConfigItem::Section(header) => {
+                    if let Some(index) = sections.iter().position(|(name, _)| name == header) {
+                        write!(file, "{}", sections.remove(index).1)?;
+                    }
+                }
+                ConfigItem::Include(section, path) => {
+                    writeln!(file, "[{}]", section)?;
+                    writeln!(file, "\tpath = {}", path)?;
+                }
+            }
+        }
+        for (_, content) in sections {
+            write!(file, "{}", content)?;
+        }
+
+        Ok(())
+    }
 
-        // Write core section
-        if !self.core.is_empty() {
-            writeln!(file, "[core]")?;
-            for (key, value) in &self.core {
-                writeln!(file, "\t{} = {}", key, value)?;
+    /// Da formato a cada sección no vacía de la configuración.
+    ///
+    /// # Retorno
+    ///
+    /// Un vector con los pares (encabezado, contenido) de las secciones, donde el contenido
+    /// incluye la línea `[encabezado]`.
+    ///
+    fn format_sections(&self) -> Vec<(String, String)> {
+        let format_entries = |header: &str, entries: &HashMap<String, String>| {
+            let mut content = format!("[{}]\n", header);
+            for (key, value) in entries {
+                content.push_str(&format!("\t{} = {}\n", key, value));
             }
+            (header.to_string(), content)
         };
+        let mut sections = Vec::new();
+
+        // Write core, extensions, merge and receive sections
+        for (header, entries) in [
+            ("core", &self.core),
+            ("extensions", &self.extensions),
+            ("merge", &self.merge),
+            ("receive", &self.receive),
+        ] {
+            if !entries.is_empty() {
+                sections.push(format_entries(header, entries));
+            }
+        }
 
         // Write remote "origin" section
-        if !self.remotes.is_empty() {
-            for (name, value) in &self.remotes {
-                if value.is_empty() {
-                    continue;
-                }
-                writeln!(file, "[remote \"{}\"]", name)?;
-                write!(file, "{}", value.format())?;
+        for (name, value) in &self.remotes {
+            if value.is_empty() {
+                continue;
             }
+            let header = format!("remote \"{}\"", name);
+            let content = format!("[{}]\n{}", header, value.format());
+            sections.push((header, content));
         }
 
         // Write branch "main" section
-        if !self.branch.is_empty() {
-            for (name, value) in &self.branch {
-                writeln!(file, "[branch \"{}\"]", name)?;
-                write!(file, "{}", value.format())?;
-            }
+        for (name, value) in &self.branch {
+            let header = format!("branch \"{}\"", name);
+            let content = format!("[{}]\n{}", header, value.format());
+            sections.push((header, content));
         }
 
-        Ok(())
+        sections
     }
 
     /// Obtiene la URL del repositorio remoto con el nombre especificado.
@@ -387,6 +528,29 @@ impl GitConfig {
         }
     }
 
+    /// Obtiene el nombre de la rama remota que sigue una rama local (`branch.<rama>.merge`), sin
+    /// el prefijo `refs/heads/`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name_branch` - Nombre de la rama local.
+    ///
+    /// # Returns
+    ///
+    /// Retorna el nombre de la rama remota, o `CommandsError::NoTrackingInformationForBranch` si
+    /// la rama no tiene configurada una rama remota.
+    ///
+    pub fn get_merge_by_branch_name(&self, name_branch: &str) -> Result<String, CommandsError> {
+        let merge = match self.branch.get(name_branch) {
+            Some(branch) => branch.merge.as_deref(),
+            None => None,
+        };
+        match merge {
+            Some(merge) => Ok(merge.trim_start_matches("refs/heads/").to_string()),
+            None => Err(CommandsError::NoTrackingInformationForBranch),
+        }
+    }
+
     pub fn get_name_remote_by_url(&self, url: &str) -> Option<String> {
         for (name, remote_info) in &self.remotes {
             if let Some(remote_url) = remote_info.get_value("url") {
@@ -438,9 +602,17 @@ impl GitConfig {
         if section == "core" {
             return self.core.get(key).map(|x| x.as_str());
         }
+        if section == "extensions" {
+            return self.extensions.get(&key.to_lowercase()).map(|x| x.as_str());
+        }
+        if section == "merge" {
+            return self.merge.get(&key.to_lowercase()).map(|x| x.as_str());
+        }
+        if section == "receive" {
+            return self.receive.get(&key.to_lowercase()).map(|x| x.as_str());
+        }
         let parts: Vec<&str> = section.split_whitespace().collect();
         if parts.len() != 2 {
-            println!("parts: {:?}", parts);
             return None;
         }
         match parts[0].trim() {
@@ -462,6 +634,63 @@ impl GitConfig {
         }
     }
 
+    /// Devuelve todas las entradas de la configuración con sus claves en formato con puntos,
+    /// por ejemplo `core.bare`, `remote.origin.url` o `branch.main.remote`.
+    ///
+    /// Primero se listan las entradas de `core`, luego las de los remotos, las de las branches
+    /// y por último las de `extensions`, `merge`, `receive` y las directivas de inclusión.
+    /// Dentro de cada sección las entradas se ordenan por nombre, para que el orden sea siempre
+    /// el mismo. Al igual que al escribir el archivo, se omiten los remotos sin URL.
+    ///
+    /// # Retorno
+    ///
+    /// Un vector con los pares (clave, valor) de la configuración.
+    ///
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let sorted = |section: &HashMap<String, String>, name: &str| {
+            let mut entries: Vec<(String, String)> = section
+                .iter()
+                .map(|(key, value)| (format!("{}.{}", name, key), value.to_string()))
+                .collect();
+            entries.sort();
+            entries
+        };
+        let mut entries = sorted(&self.core, "core");
+
+        let mut remotes: Vec<(&String, &RemoteInfo)> = self.remotes.iter().collect();
+        remotes.sort_by_key(|(name, _)| *name);
+        for (name, remote) in remotes.into_iter().filter(|(_, remote)| !remote.is_empty()) {
+            for (key, value) in remote.entries() {
+                entries.push((format!("remote.{}.{}", name, key), value.to_string()));
+            }
+        }
+
+        let mut branches: Vec<(&String, &BranchInfo)> = self.branch.iter().collect();
+        branches.sort_by_key(|(name, _)| *name);
+        for (name, branch) in branches {
+            for (key, value) in branch.entries() {
+                entries.push((format!("branch.{}.{}", name, key), value.to_string()));
+            }
+        }
+
+        entries.extend(sorted(&self.extensions, "extensions"));
+        entries.extend(sorted(&self.merge, "merge"));
+        entries.extend(sorted(&self.receive, "receive"));
+        for (section, path) in self.layout.iter().filter_map(|item| match item {
+            ConfigItem::Include(section, path) => Some((section, path)),
+            ConfigItem::Section(_) => None,
+        }) {
+            let section = match section.split_once(' ') {
+                Some((name, condition)) => {
+                    format!("{}.{}", name, condition.trim().trim_matches('"'))
+                }
+                None => section.to_string(),
+            };
+            entries.push((format!("{}.path", section), path.to_string()));
+        }
+        entries
+    }
+
     /// Agrega o actualiza la información de un repositorio remoto en la configuración Git.
     ///
     /// Esta función agrega un nuevo repositorio remoto o actualiza la información de uno existente
@@ -633,32 +862,283 @@ impl GitConfig {
     pub fn valid_remote(&self, name_remote: &str) -> bool {
         self.remotes.contains_key(name_remote)
     }
+
+    /// Devuelve el formato de los objetos del repositorio, configurado en
+    /// `extensions.objectformat`. Si no está configurado, o el valor no es válido, es SHA-1.
+    pub fn get_object_format(&self) -> ObjectFormat {
+        self.get_value("extensions", OBJECT_FORMAT_KEY)
+            .and_then(ObjectFormat::from_name)
+            .unwrap_or_default()
+    }
+
+    /// Elimina una entrada de una sección de la configuración Git.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key`: La clave de la entrada que se va a eliminar.
+    /// * `section`: La sección a la que pertenece la entrada.
+    ///
+    /// # Errores
+    ///
+    /// Devuelve `CommandsError::ConfigKeyNotFound` si la entrada no existe.
+    ///
+    pub fn remove_entry(&mut self, key: &str, section: &str) -> Result<(), CommandsError> {
+        let not_found = || CommandsError::ConfigKeyNotFound(format!("{}.{}", section, key));
+        if section == "core" {
+            return match self.core.remove(key) {
+                Some(_) => Ok(()),
+                None => Err(not_found()),
+            };
+        }
+        if section == "extensions" {
+            return match self.extensions.remove(&key.to_lowercase()) {
+                Some(_) => Ok(()),
+                None => Err(not_found()),
+            };
+        }
+        if section == "merge" {
+            return match self.merge.remove(&key.to_lowercase()) {
+                Some(_) => Ok(()),
+                None => Err(not_found()),
+            };
+        }
+        if section == "receive" {
+            return match self.receive.remove(&key.to_lowercase()) {
+                Some(_) => Ok(()),
+                None => Err(not_found()),
+            };
+        }
+        let name = get_name_seccion(section).ok_or_else(not_found)?;
+        let value = match (section.split_whitespace().next(), key) {
+            (Some("remote"), "url") => self.remotes.get_mut(&name).map(|r| &mut r.url),
+            (Some("remote"), "fetch") => self.remotes.get_mut(&name).map(|r| &mut r.fetch),
+            (Some("branch"), "remote") => self.branch.get_mut(&name).map(|b| &mut b.remote),
+            (Some("branch"), "merge") => self.branch.get_mut(&name).map(|b| &mut b.merge),
+            _ => None,
+        };
+        match value {
+            Some(value) if value.is_some() => {
+                *value = None;
+                Ok(())
+            }
+            _ => Err(not_found()),
+        }
+    }
+}
+
+/// Indica si el repositorio tiene configurado `core.ignorecase = true`, en cuyo caso las
+/// rutas de los archivos se comparan sin distinguir mayúsculas de minúsculas.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn is_ignore_case(directory: &str) -> bool {
+    match GitConfig::new_from_file(directory) {
+        Ok(git_config) => match git_config.get_value("core", CORE_IGNORECASE) {
+            Some(value) => value.eq_ignore_ascii_case("true"),
+            None => false,
+        },
+        Err(_) => false,
+    }
+}
+
+/// Compara dos rutas, ignorando mayúsculas y minúsculas si `ignore_case` es `true`.
+/// ###Parametros:
+/// 'path': primera ruta
+/// 'other': segunda ruta
+/// 'ignore_case': valor de `core.ignorecase`
+pub fn same_path(path: &str, other: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        path.to_lowercase() == other.to_lowercase()
+    } else {
+        path == other
+    }
+}
+
+/// Esta función se encarga de llamar al comando config con los parametros necesarios.
+///
+/// * `git config <seccion.clave>`: muestra el valor de la clave.
+/// * `git config <seccion.clave> <valor>`: asigna el valor a la clave.
+/// * `git config --unset <seccion.clave>`: elimina la clave.
+/// * `git config --list`: muestra todas las claves con sus valores.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función config
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_config(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        ["--unset", key] => git_config_unset(directory, key),
+        ["--list" | "-l"] => git_config_list(directory),
+        [key] => git_config_get(directory, key),
+        [key, value] => git_config_set(directory, key, value),
+        _ => Err(CommandsError::InvalidArgumentCountConfigError),
+    }
+}
+
+/// Devuelve el valor de una clave de la configuración del repositorio.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'key': clave en formato `seccion.clave` o `seccion.nombre.clave`, por ejemplo `remote.origin.url`
+pub fn git_config_get(directory: &str, key: &str) -> Result<String, CommandsError> {
+    let (section, name) = parse_config_key(key)?;
+    let git_config = GitConfig::new_from_file(directory)?;
+    match git_config.get_value(&section, &name) {
+        Some(value) => Ok(format!("{}\n", value)),
+        None => Err(CommandsError::ConfigKeyNotFound(key.to_string())),
+    }
+}
+
+/// Devuelve todas las claves de la configuración del repositorio, incluidas las de los archivos
+/// incluidos, con el formato `seccion.clave=valor`, una por línea.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn git_config_list(directory: &str) -> Result<String, CommandsError> {
+    let git_config = GitConfig::new_from_file(directory)?;
+    Ok(git_config
+        .entries()
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect())
+}
+
+/// Asigna un valor a una clave de la configuración del repositorio y la guarda en disco.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'key': clave en formato `seccion.clave` o `seccion.nombre.clave`
+/// 'value': valor a asignar
+pub fn git_config_set(directory: &str, key: &str, value: &str) -> Result<String, CommandsError> {
+    let (section, name) = parse_config_key(key)?;
+    let mut git_config = match GitConfig::new_from_file_without_includes(directory) {
+        Ok(git_config) => git_config,
+        Err(CommandsError::FileNotFoundConfig) => GitConfig::new(),
+        Err(e) => return Err(e),
+    };
+    if git_config.add_entry(&name, value, &section).is_err() {
+        return Err(CommandsError::InvalidConfigKey(key.to_string()));
+    }
+    git_config.write_to_file(&get_config_path(directory))?;
+    Ok(String::new())
+}
+
+/// Elimina una clave de la configuración del repositorio y la guarda en disco.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'key': clave en formato `seccion.clave` o `seccion.nombre.clave`
+pub fn git_config_unset(directory: &str, key: &str) -> Result<String, CommandsError> {
+    let (section, name) = parse_config_key(key)?;
+    let mut git_config = GitConfig::new_from_file_without_includes(directory)?;
+    if git_config.remove_entry(&name, &section).is_err() {
+        return Err(CommandsError::ConfigKeyNotFound(key.to_string()));
+    }
+    git_config.write_to_file(&get_config_path(directory))?;
+    Ok(String::new())
+}
+
+/// Convierte una clave con puntos en la sección y la clave que utiliza `GitConfig`.
+///
+/// * `core.bare` -> (`core`, `bare`)
+/// * `remote.origin.url` -> (`remote "origin"`, `url`)
+///
+/// ###Parametros:
+/// 'key': clave en formato `seccion.clave` o `seccion.nombre.clave`
+fn parse_config_key(key: &str) -> Result<(String, String), CommandsError> {
+    let invalid_key = || CommandsError::InvalidConfigKey(key.to_string());
+    let (section, rest) = key.split_once('.').ok_or_else(invalid_key)?;
+    let (subsection, name) = match rest.rsplit_once('.') {
+        Some((subsection, name)) => (Some(subsection), name),
+        None => (None, rest),
+    };
+    if section.is_empty()
+        || name.is_empty()
+        || subsection == Some("")
+        || key.contains(char::is_whitespace)
+    {
+        return Err(invalid_key());
+    }
+    let section = match subsection {
+        Some(subsection) => format!("{} \"{}\"", section, subsection),
+        None => section.to_string(),
+    };
+    Ok((section, name.to_string()))
+}
+
+fn get_config_path(directory: &str) -> String {
+    format!("{}/{}/{}", directory, GIT_DIR, CONFIG_FILE)
 }
 
 // Lee un archivo de configuración en formato especificado y devuelve un HashMap.
 ///
 /// El archivo de configuración debe tener secciones entre corchetes y atributos en formato clave=valor.
 /// Las secciones actúan como claves en el HashMap externo, y los atributos como claves en los HashMap internos.
-
+/// Si una sección se repite, sus atributos se agregan a los anteriores y el último valor de cada
+/// clave tiene prioridad.
+///
+/// Con `follow_includes`, el `path` de las secciones `[include]` y de las `[includeIf "gitdir:..."]`
+/// cuya condición se cumple se lee en el lugar de la directiva. Las rutas relativas se resuelven
+/// desde el directorio del archivo que las incluye y los archivos inexistentes se ignoran.
+///
 /// # Arguments
 ///
 /// * `path` - Ruta al archivo de configuración.
+/// * `follow_includes` - Indica si se leen los archivos incluidos.
 ///
 /// # Returns
 ///
 /// Retorna un Resultado que contiene un HashMap donde las claves son las secciones y los valores son HashMaps
-/// de atributos en formato clave=valor. En caso de error, se devuelve un error CommandsError.
+/// de atributos en formato clave=valor, junto con el orden de las secciones y de las directivas de inclusión
+/// del archivo. En caso de error,
+/// se devuelve un error CommandsError, `CommandsError::ConfigIncludeCycle` si un archivo se incluye a sí mismo.
 ///
 fn read_format_config(
     path: &str,
-) -> Result<HashMap<String, HashMap<String, String>>, CommandsError> {
+    follow_includes: bool,
+) -> Result<(ConfigSections, ConfigLayout), CommandsError> {
+    let git_dir = match Path::new(path).parent() {
+        Some(git_dir) => fs::canonicalize(git_dir).unwrap_or(git_dir.to_path_buf()),
+        None => PathBuf::new(),
+    };
+    let mut result = HashMap::new();
+    let mut layout = Vec::new();
+    let mut stack = Vec::new();
+    read_config_file(
+        path,
+        &git_dir,
+        follow_includes,
+        &mut result,
+        &mut layout,
+        &mut stack,
+    )?;
+    Ok((result, layout))
+}
+
+/// Lee un archivo de configuración agregando sus atributos a `result`.
+///
+/// # Arguments
+///
+/// * `path` - Ruta al archivo de configuración.
+/// * `git_dir` - Directorio git del repositorio, con el que se evalúan los `includeIf`.
+/// * `follow_includes` - Indica si se leen los archivos incluidos.
+/// * `result` - Secciones leídas hasta el momento.
+/// * `layout` - Secciones y directivas de inclusión del archivo principal, en orden.
+/// * `stack` - Archivos que se están leyendo, para detectar inclusiones cíclicas.
+///
+fn read_config_file(
+    path: &str,
+    git_dir: &Path,
+    follow_includes: bool,
+    result: &mut ConfigSections,
+    layout: &mut ConfigLayout,
+    stack: &mut Vec<PathBuf>,
+) -> Result<(), CommandsError> {
     let content = match fs::read_to_string(path) {
         Ok(content) => content,
         Err(_) => return Err(CommandsError::FileNotFoundConfig),
     };
-    let mut result = HashMap::new();
+    let canonical_path = fs::canonicalize(path).unwrap_or(PathBuf::from(path));
+    if stack.contains(&canonical_path) {
+        return Err(CommandsError::ConfigIncludeCycle(path.to_string()));
+    }
+    stack.push(canonical_path);
     let mut current_section = String::new();
-    let mut current_attributes = HashMap::new();
 
     for line in content.lines() {
         let line = line.trim();
@@ -668,11 +1148,13 @@ fn read_format_config(
         }
 
         if line.starts_with('[') && line.ends_with(']') {
-            if !current_section.is_empty() {
-                result.insert(current_section, current_attributes);
-            }
-            current_attributes = HashMap::new();
             current_section = line[1..line.len() - 1].to_string();
+            if !is_include_section(&current_section) {
+                result.entry(current_section.clone()).or_default();
+                if stack.len() == 1 {
+                    layout.push(ConfigItem::Section(section_header(&current_section)));
+                }
+            }
             continue;
         }
         let parts: Vec<&str> = line.splitn(2, '=').collect();
@@ -682,12 +1164,111 @@ fn read_format_config(
         }
         let key = parts[0].trim();
         let value = parts[1].trim();
-        current_attributes.insert(key.to_string(), value.to_string());
+        if is_include_section(&current_section) {
+            if key != "path" {
+                continue;
+            }
+            if stack.len() == 1 {
+                layout.push(ConfigItem::Include(
+                    current_section.clone(),
+                    value.to_string(),
+                ));
+            }
+            if follow_includes && include_applies(&current_section, git_dir, path) {
+                let include_path = resolve_include_path(value, path);
+                if Path::new(&include_path).is_file() {
+                    read_config_file(
+                        &include_path,
+                        git_dir,
+                        follow_includes,
+                        result,
+                        layout,
+                        stack,
+                    )?;
+                }
+            }
+            continue;
+        }
+        if let Some(attributes) = result.get_mut(&current_section) {
+            attributes.insert(key.to_string(), value.to_string());
+        }
     }
-    if !current_section.is_empty() {
-        result.insert(current_section, current_attributes);
+    stack.pop();
+    Ok(())
+}
+
+/// Devuelve el encabezado con el que se escribe la sección, por ejemplo `remote "origin"` para
+/// `[remote origin]`.
+fn section_header(section: &str) -> String {
+    match (section.split_whitespace().next(), get_name_seccion(section)) {
+        (Some(kind), Some(name)) => format!("{} \"{}\"", kind, name),
+        _ => section.to_string(),
+    }
+}
+
+/// Indica si la sección es `[include]` o `[includeIf "..."]`.
+fn is_include_section(section: &str) -> bool {
+    section == "include" || section.starts_with("includeIf ")
+}
+
+/// Evalúa la condición de una sección de inclusión. `[include]` se aplica siempre y
+/// `[includeIf "gitdir:<patrón>"]` (o `gitdir/i:` sin distinguir mayúsculas) solo si el directorio
+/// git del repositorio coincide con el patrón. Al igual que en git, un patrón relativo se busca en
+/// cualquier directorio (`**/` al comienzo) y uno que termina en `/` incluye los subdirectorios.
+///
+/// # Arguments
+///
+/// * `section` - Sección de inclusión.
+/// * `git_dir` - Directorio git del repositorio.
+/// * `config_path` - Archivo que contiene la sección, para resolver los patrones con `./`.
+///
+fn include_applies(section: &str, git_dir: &Path, config_path: &str) -> bool {
+    let condition = match section.strip_prefix("includeIf ") {
+        Some(condition) => condition.trim().trim_matches('"'),
+        None => return true,
+    };
+    let (pattern, ignore_case) = match (
+        condition.strip_prefix("gitdir:"),
+        condition.strip_prefix("gitdir/i:"),
+    ) {
+        (Some(pattern), _) => (pattern, false),
+        (_, Some(pattern)) => (pattern, true),
+        _ => return false,
+    };
+    let mut pattern = match (pattern.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => match pattern.strip_prefix("./") {
+            Some(rest) => resolve_include_path(rest, config_path),
+            None => pattern.to_string(),
+        },
     };
-    Ok(result)
+    if !pattern.starts_with('/') {
+        pattern = format!("**/{}", pattern);
+    }
+    if pattern.ends_with('/') {
+        pattern.push_str("**");
+    }
+    let mut git_dir = git_dir.to_string_lossy().replace('\\', "/");
+    if ignore_case {
+        pattern = pattern.to_lowercase();
+        git_dir = git_dir.to_lowercase();
+    }
+    glob_match(pattern.as_bytes(), git_dir.as_bytes())
+}
+
+/// Resuelve la ruta de un archivo incluido: `~/` se reemplaza por el directorio del usuario y las
+/// rutas relativas se toman desde el directorio del archivo que las incluye.
+fn resolve_include_path(include_path: &str, config_path: &str) -> String {
+    if let (Some(rest), Ok(home)) = (include_path.strip_prefix("~/"), std::env::var("HOME")) {
+        return format!("{}/{}", home, rest);
+    }
+    if Path::new(include_path).is_absolute() {
+        return include_path.to_string();
+    }
+    match Path::new(config_path).parent() {
+        Some(parent) => parent.join(include_path).to_string_lossy().to_string(),
+        None => include_path.to_string(),
+    }
 }
 
 /// Extrae y devuelve el nombre de una sección a partir de una cadena dada.
@@ -708,7 +1289,6 @@ fn read_format_config(
 fn get_name_seccion(section: &str) -> Option<String> {
     let parts: Vec<&str> = section.split_whitespace().collect();
     if parts.len() != 2 {
-        println!("parts: {:?}", parts);
         return None;
     }
     match parts[0].trim() {
@@ -728,6 +1308,7 @@ mod tests {
     use std::io::Read;
 
     use super::*;
+    use crate::util::files::create_file_replace;
 
     #[test]
     fn add_entry_valid_core() {
@@ -1055,4 +1636,169 @@ mod tests {
         );
         assert_eq!(git_config.get_remote_branch_ref("master"), None);
     }
+
+    #[test]
+    fn test_entries() {
+        let mut git_config = GitConfig::new();
+        git_config
+            .add_entry("mergeStyle", "diff3", "merge")
+            .unwrap();
+        git_config
+            .add_entry("objectFormat", "sha256", "extensions")
+            .unwrap();
+        git_config
+            .add_branch("main", "origin", "refs/heads/main")
+            .unwrap();
+        git_config.add_remote("upstream", "Repository_2").unwrap();
+        git_config.add_remote("origin", "Repository_1").unwrap();
+        git_config
+            .add_entry(
+                "fetch",
+                "+refs/heads/main:refs/remotes/origin/main",
+                "remote \"origin\"",
+            )
+            .unwrap();
+        git_config
+            .add_entry("repositoryformatversion", "0", "core")
+            .unwrap();
+        git_config.add_entry("bare", "false", "core").unwrap();
+        git_config.layout.push(ConfigItem::Include(
+            "includeIf \"gitdir:~/trabajo/\"".to_string(),
+            "trabajo.inc".to_string(),
+        ));
+
+        let entries: Vec<String> = git_config
+            .entries()
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                "core.bare=false",
+                "core.repositoryformatversion=0",
+                "remote.origin.url=Repository_1",
+                "remote.origin.fetch=+refs/heads/main:refs/remotes/origin/main",
+                "remote.upstream.url=Repository_2",
+                "remote.upstream.fetch=+refs/heads/*:refs/remotes/upstream/*",
+                "branch.main.remote=origin",
+                "branch.main.merge=refs/heads/main",
+                "extensions.objectformat=sha256",
+                "merge.mergestyle=diff3",
+                "includeIf.gitdir:~/trabajo/.path=trabajo.inc",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_key() {
+        assert_eq!(
+            parse_config_key("core.bare").unwrap(),
+            ("core".to_string(), "bare".to_string())
+        );
+        assert_eq!(
+            parse_config_key("remote.origin.url").unwrap(),
+            ("remote \"origin\"".to_string(), "url".to_string())
+        );
+        assert_eq!(
+            parse_config_key("branch.feature.v2.merge").unwrap(),
+            ("branch \"feature.v2\"".to_string(), "merge".to_string())
+        );
+        assert!(parse_config_key("core").is_err());
+        assert!(parse_config_key("remote..url").is_err());
+    }
+
+    #[test]
+    fn test_git_config_get_set_unset() {
+        let directory = "./test_git_config_command";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+
+        git_config_set(directory, "remote.origin.url", "repo").expect("Falló al asignar");
+        assert_eq!(
+            git_config_get(directory, "remote.origin.url").unwrap(),
+            "repo\n"
+        );
+        assert!(git_config_set(directory, "remote.origin.cualquiera", "x").is_err());
+
+        git_config_unset(directory, "remote.origin.url").expect("Falló al eliminar");
+        assert_eq!(
+            git_config_get(directory, "remote.origin.url"),
+            Err(CommandsError::ConfigKeyNotFound(
+                "remote.origin.url".to_string()
+            ))
+        );
+        assert!(git_config_unset(directory, "core.bare").is_err());
+        git_config_set(directory, "core.bare", "false").expect("Falló al asignar");
+        let list = git_config_list(directory).expect("Falló al listar la configuración");
+        assert!(list.lines().any(|line| line == "core.bare=false"));
+        assert!(!list.contains("remote.origin"));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_config_include_and_include_if() {
+        let directory = "./test_git_config_include";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+        let config_path = get_config_path(directory);
+        create_file_replace(
+            &format!("{}/extra.config", directory),
+            "[core]\n\tbare = true\n\tignorecase = true\n[remote \"origin\"]\n\turl = incluido\n",
+        )
+        .expect("Falló al crear el archivo");
+        create_file_replace(
+            &format!("{}/condicional.config", directory),
+            "[core]\n\tautocrlf = input\n",
+        )
+        .expect("Falló al crear el archivo");
+        create_file_replace(
+            &format!("{}/otro.config", directory),
+            "[core]\n\tfilemode = false\n",
+        )
+        .expect("Falló al crear el archivo");
+        create_file_replace(
+            &config_path,
+            "[include]\n\tpath = ../extra.config\n[core]\n\tbare = false\n\
+             [includeIf \"gitdir:test_git_config_include/.git\"]\n\tpath = ../condicional.config\n\
+             [includeIf \"gitdir:otro_repo/\"]\n\tpath = ../otro.config\n",
+        )
+        .expect("Falló al crear el archivo");
+
+        let git_config = GitConfig::new_from_file(directory).expect("Falló al leer la config");
+        // La entrada posterior a la inclusión tiene prioridad
+        assert_eq!(git_config.get_value("core", "bare"), Some("false"));
+        assert_eq!(git_config.get_value("core", "ignorecase"), Some("true"));
+        assert_eq!(
+            git_config.get_remote_url_by_name("origin"),
+            Ok("incluido".to_string())
+        );
+        assert_eq!(git_config.get_value("core", "autocrlf"), Some("input"));
+        assert_eq!(git_config.get_value("core", "filemode"), None);
+        assert!(is_ignore_case(directory));
+
+        // Al modificar la configuración se conservan las directivas y no se copian los valores
+        git_config_set(directory, "core.bare", "true").expect("Falló al asignar");
+        let content = fs::read_to_string(&config_path).expect("Falló al leer la config");
+        assert_eq!(
+            content,
+            "[include]\n\tpath = ../extra.config\n[core]\n\tbare = true\n\
+             [includeIf \"gitdir:test_git_config_include/.git\"]\n\tpath = ../condicional.config\n\
+             [includeIf \"gitdir:otro_repo/\"]\n\tpath = ../otro.config\n"
+        );
+        assert_eq!(
+            git_config_get(directory, "core.ignorecase"),
+            Ok("true\n".to_string())
+        );
+
+        create_file_replace(
+            &format!("{}/extra.config", directory),
+            "[include]\n\tpath = .git/config\n",
+        )
+        .expect("Falló al crear el archivo");
+        let result = GitConfig::new_from_file(directory);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert!(matches!(result, Err(CommandsError::ConfigIncludeCycle(_))));
+    }
 }
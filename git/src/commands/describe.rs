@@ -0,0 +1,207 @@
+use super::errors::CommandsError;
+use super::merge_base::CommitGraph;
+use super::rev_parse::resolve_commit;
+use crate::consts::{GIT_DIR, HEAD, REFS_TAGS};
+use crate::git_transport::references::{Reference, ReferenceType};
+use crate::models::client::Client;
+use crate::util::objects::{abbreviate_hash, get_object_hashes};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Opciones del comando describe.
+///
+/// - `tags`: también se consideran las tags livianas, no solo las anotadas.
+/// - `always`: si ninguna tag describe al commit, se muestra su hash abreviado.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DescribeOptions {
+    pub tags: bool,
+    pub always: bool,
+}
+
+/// Esta función se encarga de llamar al comando describe con los parametros necesarios.
+///
+/// * `git describe [--tags] [--always] [<commit>]`: describe el commit (HEAD si no se indica)
+///   a partir de la tag más cercana alcanzable desde él.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función describe
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_describe(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    let mut options = DescribeOptions::default();
+    let mut commit = None;
+    for arg in args {
+        match arg {
+            "--tags" => options.tags = true,
+            "--always" => options.always = true,
+            _ if !arg.starts_with('-') && commit.is_none() => commit = Some(arg),
+            _ => return Err(CommandsError::InvalidArgumentCountDescribeError),
+        }
+    }
+    git_describe(directory, commit.unwrap_or(HEAD), &options)
+}
+
+/// Describe un commit con el nombre de la tag más cercana alcanzable desde él, con el formato
+/// `<tag>-<n>-g<hash abreviado>`, donde `n` es la cantidad de commits alcanzables desde el
+/// commit que no lo son desde la tag. Si el commit es el de la tag, se muestra solo `<tag>`.
+///
+/// Los ancestros se recorren por distancia, siguiendo todos los parents, hasta encontrar el
+/// primer commit con una tag. Si un commit tiene varias tags se prefieren las anotadas y luego
+/// la de menor nombre.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit': revisión del commit a describir
+/// 'options': tags consideradas y qué mostrar si ninguna describe al commit
+pub fn git_describe(
+    directory: &str,
+    commit: &str,
+    options: &DescribeOptions,
+) -> Result<String, CommandsError> {
+    let hash = resolve_commit(directory, commit)?;
+    let tags = get_tagged_commits(directory, options.tags)?;
+    let hashes = get_object_hashes(&format!("{}/{}", directory, GIT_DIR))?;
+
+    let mut graph = CommitGraph::default();
+    let (tagged_commit, tag) = match find_nearest_tag(directory, &hash, &tags, &mut graph)? {
+        Some(nearest) => nearest,
+        None if options.always => return Ok(format!("{}\n", abbreviate_hash(&hash, &hashes))),
+        None if tags.is_empty() => return Err(CommandsError::DescribeNoNames),
+        None => return Err(CommandsError::DescribeNoTags(hash)),
+    };
+    if tagged_commit == hash {
+        return Ok(format!("{}\n", tag));
+    }
+    let tag_ancestors = graph.ancestors(directory, &tagged_commit)?;
+    let distance = graph
+        .ancestors(directory, &hash)?
+        .difference(&tag_ancestors)
+        .count();
+    Ok(format!(
+        "{}-{}-g{}\n",
+        tag,
+        distance,
+        abbreviate_hash(&hash, &hashes)
+    ))
+}
+
+/// Recorre los ancestros del commit por distancia y devuelve el primer commit con una tag,
+/// junto con el nombre de la tag.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'hash': hash del commit desde el que se recorre la historia
+/// 'tags': nombre de la tag preferida de cada commit con tags
+/// 'graph': commits ya leídos
+fn find_nearest_tag(
+    directory: &str,
+    hash: &str,
+    tags: &HashMap<String, String>,
+    graph: &mut CommitGraph,
+) -> Result<Option<(String, String)>, CommandsError> {
+    let mut visited = HashSet::from([hash.to_string()]);
+    let mut pending = VecDeque::from([hash.to_string()]);
+    while let Some(current) = pending.pop_front() {
+        if let Some(tag) = tags.get(&current) {
+            return Ok(Some((current, tag.to_string())));
+        }
+        graph.read_commit(directory, &current)?;
+        for parent in graph.parents(&current) {
+            if visited.insert(parent.to_string()) {
+                pending.push_back(parent.to_string());
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Devuelve, para cada commit con tags, el nombre de la tag con la que se lo describe. Las tags
+/// anotadas se pelan hasta el commit al que apuntan.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'lightweight': si se consideran también las tags livianas
+fn get_tagged_commits(
+    directory: &str,
+    lightweight: bool,
+) -> Result<HashMap<String, String>, CommandsError> {
+    let mut candidates: HashMap<String, Vec<(bool, String)>> = HashMap::new();
+    for reference in Reference::extract_references_from_git(directory)? {
+        if reference.get_type() != ReferenceType::Tag {
+            continue;
+        }
+        let resolved = reference.resolve(directory)?;
+        let annotated = resolved.raw != resolved.peeled;
+        if !annotated && !lightweight {
+            continue;
+        }
+        let name = reference
+            .get_ref_path()
+            .strip_prefix(&format!("{}/", REFS_TAGS))
+            .unwrap_or(reference.get_name())
+            .to_string();
+        candidates
+            .entry(resolved.peeled)
+            .or_default()
+            .push((!annotated, name));
+    }
+    Ok(candidates
+        .into_iter()
+        .filter_map(|(commit, mut tags)| {
+            tags.sort();
+            tags.into_iter().next().map(|(_, name)| (commit, name))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init;
+    use crate::commands::tag::git_tag_create;
+    use crate::test_utils::{commit_file, test_client};
+    use crate::util::files::create_file;
+    use std::fs;
+
+    #[test]
+    fn test_git_describe_descendant_of_tag() {
+        let directory = "./test_describe";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "uno");
+        let client = test_client(directory, "9418");
+
+        let options = DescribeOptions::default();
+        let always = DescribeOptions {
+            tags: false,
+            always: true,
+        };
+        let no_names = git_describe(directory, HEAD, &options);
+        let abbreviated = git_describe(directory, HEAD, &always);
+
+        git_tag_create(directory, client, "v1.0", "primera version")
+            .expect("Falló al crear la tag");
+        commit_file(directory, "b.txt", "dos");
+        let third = commit_file(directory, "c.txt", "tres");
+        // Una tag liviana solo se considera con --tags
+        create_file(
+            &format!("{}/{}/{}/liviana", directory, GIT_DIR, REFS_TAGS),
+            &third,
+        )
+        .expect("Falló al crear la tag liviana");
+
+        let exact = git_describe(directory, &first, &options);
+        let descendant = git_describe(directory, HEAD, &options);
+        let lightweight = git_describe(
+            directory,
+            HEAD,
+            &DescribeOptions {
+                tags: true,
+                always: false,
+            },
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(no_names, Err(CommandsError::DescribeNoNames));
+        assert_eq!(abbreviated, Ok(format!("{}\n", &first[..7])));
+        assert_eq!(exact, Ok("v1.0\n".to_string()));
+        assert_eq!(descendant, Ok(format!("v1.0-2-g{}\n", &third[..7])));
+        assert_eq!(lightweight, Ok("liviana\n".to_string()));
+    }
+}
@@ -6,8 +6,11 @@ use crate::{errors::GitError, servers::errors::ServerError, util::errors::UtilEr
 pub enum CommandsError {
     CommandsFromUtil(String),   // Para tener polimofismo con UtilError
     CommandsFromServer(String), // Para tener polimofismo con ServerError
+    CommandsFromGit(GitError),  // Conserva el GitError para poder distinguirlo
     CloneMissingRepo,
     CommitEmptyIndex,
+    NothingToCommit,
+    InvalidCommitObject,
     InvalidArgumentCountFetchError,
     CloneMissingRepoError,
     RepositoryNotInitialized,
@@ -99,16 +102,66 @@ pub enum CommandsError {
     TimeStamp,
     PushInvalidStatusFromServer,
     IsSameBranch,
+    InvalidArgumentCountLsRemoteError,
+    FlagLogNotRecognizedError,
+    InvalidArgumentCountShowError,
+    ShowCommitNotFoundError,
+    DetachedHead,
+    InvalidArgumentCountSymbolicRefError,
+    InvalidSymbolicRefTarget(String),
+    InvalidSymbolicRefName(String),
+    InvalidArgumentCountConfigError,
+    InvalidConfigKey(String),
+    ConfigKeyNotFound(String),
+    ConfigIncludeCycle(String),
+    InvalidArgumentCountMvError,
+    NotTracked(String),
+    MvDestinationExists(String),
+    RenameFileError,
+    InvalidArgumentCountArchiveError,
+    ArchivePathTooLong(String),
+    InvalidArgumentCountRevParseError,
+    AmbiguousObject(String, Vec<String>),
+    UnknownRevision(String),
+    InvalidArgumentCountReflogError,
+    ReflogNotFound(String),
+    InvalidReflogExpire(String),
+    InvalidArgumentCountBlameError,
+    BlameNoSuchPath(String),
+    InvalidArgumentCountGcError,
+    InvalidArgumentCountPruneError,
+    InvalidArgumentCountNotesError,
+    InvalidRefspec(String),
+    InvalidArgumentCountWorktreeError,
+    WorktreePathExists(String),
+    WorktreeBranchCheckedOut(String),
+    MalformedTreeEntry(String),
+    ShowRefInvalidRef(String),
+    InvalidArgumentCountMergeBaseError,
+    MergeBaseNotFound,
+    NotAncestor(String, String),
+    UnknownObjectFormat(String),
+    CloneInterrupted(usize, usize),
+    InvalidArgumentCountDescribeError,
+    DescribeNoNames,
+    DescribeNoTags(String),
+    InvalidArgumentCountApplyError,
+    InvalidPatch(String),
+    PatchDoesNotApply { file: String, hunk: usize },
+    InvalidPatchPath(String),
 }
 
 fn format_error(error: &CommandsError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match error {
         CommandsError::CommandsFromUtil(info) => write!(f, "{}", info),
         CommandsError::CommandsFromServer(info) => write!(f, "{}", info),
+        CommandsError::CommandsFromGit(error) => write!(f, "{}", error),
         CommandsError::CloneMissingRepo => {
             write!(f, "CloneMissingRepo: Use: <repositorio>")
         }
         CommandsError::CommitEmptyIndex => write!(f, "Nada al que hacer Commit"),
+        CommandsError::NothingToCommit => write!(f, "nothing to commit, working tree clean"),
+        CommandsError::InvalidCommitObject => write!(f, "El objeto no es un commit válido"),
         CommandsError::InvalidArgumentCountFetchError => {
             write!(f, "InvalidArgumentCountFetchError")
         }
@@ -119,7 +172,7 @@ fn format_error(error: &CommandsError, f: &mut fmt::Formatter<'_>) -> fmt::Resul
         CommandsError::CreateGitConfig => write!(f, "CreateGitConfig: No se pudo crear el archivo de configuración de Git"),
         CommandsError::FileNotFoundConfig => write!(f, "FileNotFoundConfig: No se encontró el archivo de configuración de Git"),
         CommandsError::MissingUrlConfig => write!(f, "MissingUrlConfig: No se encontró la URL del repositorio remoto en el archivo de configuración de Git"),
-        CommandsError::InvalidArgumentCountPull => write!(f, "InvalidArgumentCountPull: Use: git pull"),
+        CommandsError::InvalidArgumentCountPull => write!(f, "InvalidArgumentCountPull: Use: git pull [<remote> <branch>]"),
         CommandsError::RemotoNotInitialized => write!(f, "RemotoNotInitialized: No se ha inicializado el repositorio remoto"),
         CommandsError::CreateFetchHEAD => write!(f, "CreateFetchHEAD: No se pudo crear el archivo FETCH_HEAD"),
         CommandsError::ReadFetchHEAD => write!(f, "ReadFetchHEAD: No se pudo leer el archivo FETCH_HEAD"),
@@ -158,7 +211,7 @@ fn format_error(error: &CommandsError, f: &mut fmt::Formatter<'_>) -> fmt::Resul
         CommandsError::PathToStringError => write!(f, "No se pudo convertir el path a str"),
         CommandsError::DirectoryOpenError => write!(f, "No se pudo abrir el directorio"),
         CommandsError::InvalidArgumentCountBranchError => write!(f, "Número de argumentos inválido para el comando branch."),
-        CommandsError::InvalidArgumentCountCatFileError => write!(f, "Número de argumentos inválido para el comando cat-file.\nUsar: <object hash>"),
+        CommandsError::InvalidArgumentCountCatFileError => write!(f, "Número de argumentos inválido para el comando cat-file.\nUsar: <-t|-s|-p> <object hash> o --batch-check [<object hash>...]"),
         CommandsError::FlagCatFileNotRecognizedError => write!(f, "Flag no reconocida para el comando cat-file"),
         CommandsError::InvalidArgumentCountCheckoutError => write!(f, "Número de argumentos inválido para el comando checkout."),
         CommandsError::FlagCheckoutNotRecognisedError => write!(f, "Flag no reconocida para el comando checkout"),
@@ -167,19 +220,19 @@ fn format_error(error: &CommandsError, f: &mut fmt::Formatter<'_>) -> fmt::Resul
         CommandsError::FlagCommitNotRecognizedError => write!(f, "Flag no reconocida para el comando commit"),
         CommandsError::InvalidArgumentCountHashObjectError => write!(f, "Número de argumentos inválido para el comando hash-object.\nUsar: <file name>"),
         CommandsError::FlagHashObjectNotRecognizedError => write!(f, "Flag no reconocida para el comando hash-object"),
-        CommandsError::InvalidArgumentCountInitError => write!(f, "Número de argumentos inválido para el comando init.\nUsar: git init"),
+        CommandsError::InvalidArgumentCountInitError => write!(f, "Número de argumentos inválido para el comando init.\nUsar: git init [-b <branch>] [--object-format=<sha1|sha256>]"),
         CommandsError::InvalidArgumentCountStatusError => writeln!(f, "Número de argumentos inválido para el comando status."),
         CommandsError::InvalidArgumentCountLogError => writeln!(f, "Número de argumentos inválido para el comando log."),
-        CommandsError::InvalidArgumentCountMergeError => write!(f, "Número de argumentos inválido para el comando merge.\nUsar: <branch name>"),
+        CommandsError::InvalidArgumentCountMergeError => write!(f, "Número de argumentos inválido para el comando merge.\nUsar: [--no-ff | --squash] <branch name>"),
         CommandsError::InvalidArgumentCountPullError => write!(f, "Número de argumentos inválido para el comando pull.\nUsar: <branch name>"),
         CommandsError::InvalidArgumentCountPushError => write!(f, "Número de argumentos inválido para el comando push.\nUsar: <branch name>"),
         CommandsError::InvalidArgumentCountRemoteError => write!(f, "Número de argumentos inválido para el comando remote.\nUsar: <flag> <remote name> <url>"),
         CommandsError::InvalidArgumentCountRmError => write!(f, "Número de argumentos inválido para el comando rm.\nUsar: <file name>"),
         CommandsError::InvalidArgumentCountLsFilesError => write!(f, "Número de argumentos inválido para el comando ls-files.\nUsar: <flag>"),
         CommandsError::FlagLsFilesNotRecognizedError => write!(f, "Flag no reconocida para el comando ls-files"),
-        CommandsError::InvalidArgumentCountLsTreeError => write!(f, "Número de argumentos inválido para el comando ls-tree.\nUsar: <tree-hash>"),
+        CommandsError::InvalidArgumentCountLsTreeError => write!(f, "Número de argumentos inválido para el comando ls-tree.\nUsar: [-r] [-d] <tree-ish>"),
         CommandsError::InvalidTreeHashError => write!(f, "fatal: not a tree object"),
-        CommandsError::InvalidArgumentShowRefError => write!(f, "Número de argumentos inválido para el comando show-ref.\nUsar: git show-ref"),
+        CommandsError::InvalidArgumentShowRefError => write!(f, "Número de argumentos inválido para el comando show-ref.\nUsar: git show-ref [--heads] [--tags] | --verify <ref>"),
         CommandsError::InvalidArgumentCountCheckIgnoreError => write!(f, "Número de argumentos inválido para el comando check-ignore.\nUsar: <path name> o --stdin"),
         CommandsError::RemoteAlreadyExistsError => write!(f, "El repositorio remoto ya existe"),
         CommandsError::RemoteDoesNotExistError => write!(f, "El repositorio remoto no existe"),
@@ -205,6 +258,53 @@ fn format_error(error: &CommandsError, f: &mut fmt::Formatter<'_>) -> fmt::Resul
         CommandsError::TimeStamp => write!(f, "No se pudo obtener el timestamp"),
         CommandsError::PushInvalidStatusFromServer => write!(f, "El servidor devolvió un estado inválido"),
         CommandsError::IsSameBranch => write!(f, "No se puede hacer merge con la misma branch"),
+        CommandsError::FlagLogNotRecognizedError => write!(f, "Flag no reconocida para el comando log.\nUsar: [--oneline] [--graph] [--max-count <n>] [--author=<texto>] [--since=<fecha>] [--until=<fecha>] [<revision>]"),
+        CommandsError::InvalidArgumentCountLsRemoteError => write!(f, "Número de argumentos inválido para el comando ls-remote.\nUsar: [--heads] [--tags] [<repositorio>]"),
+        CommandsError::InvalidArgumentCountShowError => write!(f, "Número de argumentos inválido para el comando show.\nUsar: git show [<commit>]"),
+        CommandsError::ShowCommitNotFoundError => write!(f, "No se encontró el commit indicado"),
+        CommandsError::DetachedHead => write!(f, "HEAD no apunta a ninguna branch (detached HEAD)"),
+        CommandsError::InvalidArgumentCountSymbolicRefError => write!(f, "Número de argumentos inválido para el comando symbolic-ref.\nUsar: git symbolic-ref <nombre> [<referencia>]"),
+        CommandsError::InvalidSymbolicRefTarget(target) => write!(f, "Referencia inválida para symbolic-ref: {}", target),
+        CommandsError::InvalidSymbolicRefName(name) => write!(f, "Nombre inválido para symbolic-ref: {}", name),
+        CommandsError::InvalidArgumentCountConfigError => write!(f, "Número de argumentos inválido para el comando config.\nUsar: git config <seccion.clave> [<valor>] | git config --unset <seccion.clave> | git config --list"),
+        CommandsError::InvalidConfigKey(key) => write!(f, "Clave de configuración inválida: {}", key),
+        CommandsError::ConfigKeyNotFound(key) => write!(f, "No se encontró la clave de configuración: {}", key),
+        CommandsError::ConfigIncludeCycle(path) => write!(f, "fatal: inclusión cíclica en la configuración: {}", path),
+        CommandsError::InvalidArgumentCountMvError => write!(f, "Número de argumentos inválido para el comando mv.\nUsar: git mv [-f] <origen> <destino>"),
+        CommandsError::NotTracked(file) => write!(f, "El archivo {} no está bajo control de versiones", file),
+        CommandsError::MvDestinationExists(file) => write!(f, "El destino {} ya existe. Usar -f para sobrescribirlo", file),
+        CommandsError::RenameFileError => write!(f, "No se pudo mover el archivo"),
+        CommandsError::InvalidArgumentCountArchiveError => write!(f, "Número de argumentos inválido para el comando archive.\nUsar: git archive -o <archivo> <tree-ish>"),
+        CommandsError::InvalidArgumentCountRevParseError => write!(f, "Número de argumentos inválido para el comando rev-parse.\nUsar: git rev-parse <revision>..."),
+        CommandsError::AmbiguousObject(prefix, candidates) => write!(f, "error: el hash abreviado {} es ambiguo\nLos candidatos son:\n  {}", prefix, candidates.join("\n  ")),
+        CommandsError::UnknownRevision(spec) => write!(f, "Revisión desconocida: {}", spec),
+        CommandsError::InvalidArgumentCountReflogError => write!(f, "Número de argumentos inválido para el comando reflog.\nUsar: git reflog [show] [<referencia>]\ngit reflog expire [--expire=<n>.days|now|never] [--max-count=<n>] [--all | <referencia>]"),
+        CommandsError::ReflogNotFound(reference) => write!(f, "No hay reflog para la referencia {}", reference),
+        CommandsError::InvalidReflogExpire(expire) => write!(f, "Antigüedad inválida para --expire: {}. Usar <n>.days, now o never", expire),
+        CommandsError::ArchivePathTooLong(path) => write!(f, "La ruta {} es demasiado larga para un archivo tar", path),
+        CommandsError::InvalidArgumentCountBlameError => write!(f, "Número de argumentos inválido para el comando blame.\nUsar: git blame <archivo>"),
+        CommandsError::BlameNoSuchPath(path) => write!(f, "No existe el archivo {} en HEAD", path),
+        CommandsError::InvalidArgumentCountGcError => write!(f, "Número de argumentos inválido para el comando gc.\nUsar: git gc [--prune=now]"),
+        CommandsError::InvalidArgumentCountPruneError => write!(f, "Número de argumentos inválido para el comando prune.\nUsar: git prune"),
+        CommandsError::InvalidArgumentCountNotesError => write!(f, "Número de argumentos inválido para el comando notes.\nUsar: git notes add -m <nota> [<commit>] o git notes show [<commit>]"),
+        CommandsError::InvalidRefspec(refspec) => write!(f, "Refspec inválido: {}.\nUsar: [+]<origen>:<destino>", refspec),
+        CommandsError::InvalidArgumentCountWorktreeError => write!(f, "Número de argumentos inválido para el comando worktree.\nUsar: git worktree add <ruta> <branch>"),
+        CommandsError::WorktreePathExists(path) => write!(f, "fatal: '{}' ya existe", path),
+        CommandsError::WorktreeBranchCheckedOut(branch) => write!(f, "fatal: la rama '{}' ya está en uso en otro working tree", branch),
+        CommandsError::MalformedTreeEntry(line) => write!(f, "fatal: entrada de tree inválida: '{}'", line),
+        CommandsError::ShowRefInvalidRef(reference) => write!(f, "fatal: '{}' - no es una referencia válida", reference),
+        CommandsError::InvalidArgumentCountMergeBaseError => write!(f, "Número de argumentos inválido para el comando merge-base.\nUsar: git merge-base [--all] <commit> <commit>\n       git merge-base --is-ancestor <commit> <commit>"),
+        CommandsError::MergeBaseNotFound => write!(f, "No hay un ancestro común entre los commits"),
+        CommandsError::NotAncestor(ancestor, commit) => write!(f, "{} no es ancestro de {}", ancestor, commit),
+        CommandsError::UnknownObjectFormat(format) => write!(f, "fatal: formato de objetos desconocido '{}'.\nUsar: sha1 o sha256", format),
+        CommandsError::CloneInterrupted(received, expected) => write!(f, "Clone interrumpido: se recibieron {} de {} objetos. Volver a ejecutar el clone para retomarlo", received, expected),
+        CommandsError::InvalidArgumentCountDescribeError => write!(f, "Número de argumentos inválido para el comando describe.\nUsar: git describe [--tags] [--always] [<commit>]"),
+        CommandsError::DescribeNoNames => write!(f, "fatal: No names found, cannot describe anything."),
+        CommandsError::DescribeNoTags(hash) => write!(f, "fatal: No tags can describe '{}'.\nTry --always, or create some tags.", hash),
+        CommandsError::InvalidArgumentCountApplyError => write!(f, "Número de argumentos inválido para el comando apply.\nUsar: git apply [--check] <patch>"),
+        CommandsError::InvalidPatch(line) => write!(f, "error: patch inválido en la línea: {}", line),
+        CommandsError::PatchDoesNotApply { file, hunk } => write!(f, "error: el hunk #{} no se puede aplicar a {}\nerror: {}: el patch no se aplica", hunk, file, file),
+        CommandsError::InvalidPatchPath(path) => write!(f, "error: path inválido en el patch: '{}'", path),
     }
 }
 
@@ -220,6 +320,12 @@ impl From<UtilError> for CommandsError {
     }
 }
 
+impl From<GitError> for CommandsError {
+    fn from(error: GitError) -> Self {
+        CommandsError::CommandsFromGit(error)
+    }
+}
+
 impl From<ServerError> for CommandsError {
     fn from(error: ServerError) -> Self {
         CommandsError::CommandsFromServer(format!("{}", error))
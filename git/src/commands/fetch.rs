@@ -1,7 +1,7 @@
 use crate::commands::branch::get_branch_current_hash;
 use crate::commands::config::GitConfig;
 use crate::commands::fetch_head::FetchHead;
-use crate::consts::{CAPABILITIES_FETCH, GIT_DIR};
+use crate::consts::{CAPABILITIES_FETCH, GIT_DIR, OFS_DELTA, REFLOG_DEFAULT_IDENTITY, REFS_HEADS};
 use crate::git_server::GitServer;
 use crate::git_transport::git_request::GitRequest;
 use crate::git_transport::negotiation::packfile_negotiation_partial;
@@ -11,18 +11,23 @@ use crate::models::client::Client;
 use crate::util::connections::{receive_packfile, send_flush, start_client};
 use crate::util::errors::UtilError;
 use crate::util::files::create_directory;
+use crate::util::logger::log_debug;
+use crate::util::object_format::get_object_format;
 use crate::util::objects::{
-    builder_object_blob, builder_object_commit, builder_object_tree, read_blob, read_commit,
-    read_tree, ObjectEntry, ObjectType,
+    builder_object_blob, builder_object_commit, builder_object_tag, builder_object_tree, read_blob,
+    read_commit, read_tag, read_tree, ObjectEntry, ObjectType,
 };
 use crate::util::pkt_line::read_pkt_line;
+use crate::util::progress::Progress;
+use crate::util::validation::valid_ref_name;
 use std::net::TcpStream;
 use std::path::Path;
 use std::{fmt, fs};
 
-use super::branch::get_branch_remote;
+use super::branch::{get_branch_remote, get_current_branch};
 use super::errors::CommandsError;
-use super::log::save_log;
+use super::merge_base::is_full_ancestor;
+use super::reflog::append_reflog;
 
 #[derive(Debug)]
 pub enum FetchStatus {
@@ -49,6 +54,87 @@ impl fmt::Display for FetchStatus {
     }
 }
 
+/// Refspec de un fetch con el formato `[+]<origen>:<destino>`.
+///
+/// - `force`: si es `true` (prefijo `+`) el destino se actualiza aunque no sea fast-forward.
+/// - `source`: referencia del servidor; puede contener un `*`.
+/// - `destination`: referencia local; contiene un `*` solo si el origen lo tiene.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refspec {
+    pub force: bool,
+    pub source: String,
+    pub destination: String,
+}
+
+impl Refspec {
+    /// Interpreta un refspec `[+]<origen>:<destino>`. Los nombres que no comienzan con `refs/`
+    /// se toman como branches (`refs/heads/<nombre>`).
+    ///
+    /// # Argumentos
+    ///
+    /// * `refspec`: Refspec a interpretar, por ejemplo `+refs/heads/*:refs/remotes/origin/*`.
+    ///
+    /// # Errores
+    ///
+    /// Devuelve `CommandsError::InvalidRefspec` si falta alguno de los lados, si solo uno de
+    /// ellos tiene `*`, si alguno tiene más de un `*` o si los nombres no son válidos.
+    ///
+    pub fn parse(refspec: &str) -> Result<Refspec, CommandsError> {
+        let invalid = || CommandsError::InvalidRefspec(refspec.to_string());
+        let (force, spec) = match refspec.strip_prefix('+') {
+            Some(spec) => (true, spec),
+            None => (false, refspec),
+        };
+        let (source, destination) = spec.split_once(':').ok_or_else(invalid)?;
+        let wildcards = (
+            source.matches('*').count(),
+            destination.matches('*').count(),
+        );
+        if source.is_empty() || destination.is_empty() || !matches!(wildcards, (0, 0) | (1, 1)) {
+            return Err(invalid());
+        }
+        let expand = |name: &str| {
+            if name.starts_with("refs/") {
+                name.to_string()
+            } else {
+                format!("refs/heads/{}", name)
+            }
+        };
+        let (source, destination) = (expand(source), expand(destination));
+        for name in [&source, &destination] {
+            if valid_ref_name(&name.replace('*', "wildcard")).is_err() {
+                return Err(invalid());
+            }
+        }
+        Ok(Refspec {
+            force,
+            source,
+            destination,
+        })
+    }
+
+    /// Devuelve la referencia local en la que se guarda una referencia del servidor, o `None`
+    /// si la referencia no coincide con el origen del refspec.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ref_path`: Referencia del servidor, por ejemplo `refs/heads/master`.
+    ///
+    pub fn map_reference(&self, ref_path: &str) -> Option<String> {
+        match self.source.split_once('*') {
+            Some((prefix, suffix)) => {
+                let matched = ref_path.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                if matched.is_empty() {
+                    return None;
+                }
+                Some(self.destination.replacen('*', matched, 1))
+            }
+            None if ref_path == self.source => Some(self.destination.to_string()),
+            None => None,
+        }
+    }
+}
+
 // use super::cat_file::git_cat_file;
 
 // const REMOTES_DIR: &str = "refs/remotes/";
@@ -60,6 +146,8 @@ impl fmt::Display for FetchStatus {
 /// Solo se aceptaran los comandos que tengan la siguiente estructura:
 ///
 /// * `git fetch`
+/// * `git fetch <remoto> <branch>`
+/// * `git fetch <remoto> [+]<origen>:<destino>`: usa el refspec indicado en lugar del configurado.
 ///
 /// # Argumentos
 ///
@@ -88,6 +176,16 @@ pub fn handle_fetch(args: Vec<&str>, client: Client) -> Result<FetchStatus, Comm
             client.get_directory_path(),
         );
     }
+    if args[1].contains(':') {
+        return git_fetch_refspec(
+            &mut socket,
+            client.get_ip(),
+            client.get_port(),
+            client.get_directory_path(),
+            args[0],
+            args[1],
+        );
+    }
     git_fetch_branch(
         &mut socket,
         client.get_ip(),
@@ -104,12 +202,11 @@ pub fn git_fetch_all(
     port: &str,
     repo_local: &str,
 ) -> Result<FetchStatus, CommandsError> {
-    println!("Estoy aqui");
     // Obtengo los remotos en uso
     let git_config = GitConfig::new_from_file(repo_local)?;
     let remotes = git_config.get_remotes_in_use();
     let mut status = Vec::new();
-    println!("Remotes: {:?}", remotes);
+    log_debug(&format!("Remotes: {:?}", remotes));
 
     for name_remote in remotes {
         let url_remote = &git_config.get_remote_url_by_name(&name_remote)?;
@@ -117,6 +214,13 @@ pub fn git_fetch_all(
         status.push(status_remote.to_string());
     }
 
+    // Como en git, solo la branch actual queda para mergear
+    if let Ok(current_branch) = get_current_branch(repo_local) {
+        let mut fetch_head = FetchHead::new_from_file(repo_local)?;
+        fetch_head.mark_for_merge(&current_branch);
+        fetch_head.write(repo_local)?;
+    }
+
     Ok(FetchStatus::SomeRemotesUpdated(status.join("\n")))
 }
 
@@ -129,8 +233,7 @@ pub fn _git_fetch_all(
     remote_branch: &str,
 ) -> Result<FetchStatus, CommandsError> {
     // Obtengo el repositorio remoto
-    println!("Repositorio local: {}", repo_local);
-    println!("Fetch del repositorio remoto: {}", url_remote);
+    log_debug(&format!("Fetch del repositorio remoto: {}", url_remote));
 
     // Prepara la solicitud "git-upload-pack" para el servidor
     let message =
@@ -140,27 +243,19 @@ pub fn _git_fetch_all(
     let my_capacibilities: Vec<String> =
         CAPABILITIES_FETCH.iter().map(|&s| s.to_string()).collect();
     let mut server = reference_discovery(socket, message, url_remote, &my_capacibilities)?;
-    println!("Reference Discovery");
 
     // Packfile Negotiation
-    packfile_negotiation_partial(socket, &mut server, repo_local)?;
-    println!("packfile_negotiation_partial");
+    if !packfile_negotiation_partial(socket, &mut server, repo_local)? {
+        return Ok(FetchStatus::NoUpdatesRemote(url_remote.to_string()));
+    }
 
     // Packfile Data
     let _last_ack = read_pkt_line(socket)?; // Vlidar last ack
-    println!("Recibi el ultimo ack");
-    println!("_last_ack: {:?}", _last_ack);
 
-    let content = receive_packfile(socket)?;
-    for (object, _) in &content {
-        println!("FETCH --- > object: {:?}", object);
-        // println!("bytes: {:?}", bytes);
-    }
+    let content = receive_packfile(socket, server.supports(OFS_DELTA), server.object_format)?;
     if content.is_empty() {
-        println!("No hay actualizaciones");
         return Ok(FetchStatus::NoUpdatesRemote(url_remote.to_string()));
     }
-    println!("receive_packfile --> {:?}", content);
 
     let refs = server.get_references_for_updating()?;
 
@@ -195,14 +290,11 @@ pub fn git_fetch_branch(
     name_branch: &str,
 ) -> Result<FetchStatus, CommandsError> {
     // Obtengo el repositorio remoto
-    println!("Repositorio local: {}", repo_local);
     let git_config = GitConfig::new_from_file(repo_local)?;
-    println!("Config: {:?}", git_config);
 
     let url_remoto = &git_config.get_remote_url_by_name(name_remote)?;
-    println!("url: {}", url_remoto);
 
-    println!("Fetch del repositorio remoto: {}", url_remoto);
+    log_debug(&format!("Fetch del repositorio remoto: {}", url_remoto));
 
     let rfs_fetch = format!("refs/heads/{}", name_branch);
 
@@ -222,28 +314,29 @@ pub fn git_fetch_branch(
     // Packfile Negotiation
     // Solo solicitar una branch
     server.update_references_filtering([rfs_fetch].to_vec())?;
-    packfile_negotiation_partial(socket, &mut server, repo_local)?;
+    if !packfile_negotiation_partial(socket, &mut server, repo_local)? {
+        return Ok(FetchStatus::NoUpdatesBranch(name_branch.to_string()));
+    }
 
     // Packfile Data
     let _last_ack = read_pkt_line(socket)?; // Vlidar last ack
-    let content = receive_packfile(socket)?;
+    let content = receive_packfile(socket, server.supports(OFS_DELTA), server.object_format)?;
 
     if content.is_empty() {
         return Ok(FetchStatus::NoUpdatesBranch(name_branch.to_string()));
     }
 
     let refs = server.get_references_for_updating()?;
-    println!("Refs: {:?}", refs);
 
     if !is_already_update(repo_local, &refs, name_branch)? {
         if save_objects(content, repo_local).is_err() {
-            println!("Error al guardar los objetos");
             return Err(CommandsError::RepositoryNotInitialized);
         };
         save_references(&refs, repo_local, name_remote)?;
 
         let mut fetch_head = FetchHead::new_from_file(repo_local)?;
         fetch_head.update_references(&refs, url_remoto)?;
+        fetch_head.mark_for_merge(name_branch);
         fetch_head.write(repo_local)?;
 
         let mut status = Vec::new();
@@ -262,6 +355,153 @@ pub fn git_fetch_branch(
     // Ok(FetchStatus::SomeRemotesUpdated(format!("{} --> {}", name_branch, refs.)))
 }
 
+/// Descarga las referencias del servidor que coinciden con el refspec y las guarda en los
+/// destinos que este indica, en lugar de usar el refspec configurado para el remoto.
+///
+/// # Argumentos
+///
+/// * `socket`: Conexión con el servidor.
+/// * `ip`: IP del servidor.
+/// * `port`: Puerto del servidor.
+/// * `repo_local`: Directorio del repositorio local.
+/// * `name_remote`: Nombre del remoto configurado.
+/// * `refspec`: Refspec `[+]<origen>:<destino>`.
+///
+/// # Errores
+///
+/// Devuelve un error del tipo `CommandsError` si el refspec no es válido o si falla la
+/// comunicación con el servidor.
+///
+pub fn git_fetch_refspec(
+    socket: &mut TcpStream,
+    ip: &str,
+    port: &str,
+    repo_local: &str,
+    name_remote: &str,
+    refspec: &str,
+) -> Result<FetchStatus, CommandsError> {
+    let refspec = Refspec::parse(refspec)?;
+    let git_config = GitConfig::new_from_file(repo_local)?;
+    let url_remote = &git_config.get_remote_url_by_name(name_remote)?;
+
+    let message =
+        GitRequest::generate_request_string(RequestCommand::UploadPack, url_remote, ip, port);
+    let my_capacibilities: Vec<String> =
+        CAPABILITIES_FETCH.iter().map(|&s| s.to_string()).collect();
+    let mut server = reference_discovery(socket, message, url_remote, &my_capacibilities)?;
+
+    let refs: Vec<Reference> = server
+        .get_references()
+        .iter()
+        .filter(|reference| refspec.map_reference(reference.get_ref_path()).is_some())
+        .cloned()
+        .collect();
+    if refs.is_empty() {
+        send_flush(socket, UtilError::SendFlushCancelConnection)?;
+        return Ok(FetchStatus::BranchNotFound(refspec.source));
+    }
+
+    // Solo se solicitan las referencias del refspec
+    let paths = refs.iter().map(|r| r.get_ref_path().to_string()).collect();
+    server.update_references_filtering(paths)?;
+    if packfile_negotiation_partial(socket, &mut server, repo_local)? {
+        let _last_ack = read_pkt_line(socket)?;
+        let content = receive_packfile(socket, server.supports(OFS_DELTA), server.object_format)?;
+        if save_objects(content, repo_local).is_err() {
+            return Err(CommandsError::RepositoryNotInitialized);
+        };
+    }
+
+    let status = save_refspec_references(&refs, repo_local, &refspec)?;
+    let mut fetch_head = FetchHead::new_from_file(repo_local)?;
+    fetch_head.update_references(&refs, url_remote)?;
+    fetch_head.write(repo_local)?;
+
+    if status.is_empty() {
+        return Ok(FetchStatus::NoUpdatesRemote(url_remote.to_string()));
+    }
+    Ok(FetchStatus::UpdatesBranch(status.join("\n")))
+}
+
+/// Guarda cada referencia del servidor en el destino que indica el refspec y registra el
+/// cambio en el reflog.
+///
+/// Si el refspec no fuerza la actualización, un destino existente solo se actualiza si el
+/// nuevo commit es descendiente del anterior (fast-forward). Nunca se actualiza la branch que
+/// está en uso, porque el working directory y el index quedarían desfasados.
+///
+/// # Argumentos
+///
+/// * `references`: Referencias del servidor que coinciden con el refspec.
+/// * `repo_path`: Directorio del repositorio local.
+/// * `refspec`: Refspec que indica el destino de cada referencia.
+///
+/// # Retorno
+///
+/// Devuelve una línea de estado por cada destino actualizado o rechazado.
+///
+pub fn save_refspec_references(
+    references: &[Reference],
+    repo_path: &str,
+    refspec: &Refspec,
+) -> Result<Vec<String>, CommandsError> {
+    let mut status = Vec::new();
+    let current_branch = get_current_branch(repo_path)
+        .map(|branch| format!("{}/{}", REFS_HEADS, branch))
+        .ok();
+    for reference in references {
+        let destination = match refspec.map_reference(reference.get_ref_path()) {
+            Some(destination) => destination,
+            None => continue,
+        };
+        let hash = reference.get_hash();
+        let file_path = format!("{}/{}/{}", repo_path, GIT_DIR, destination);
+        let old_hash = fs::read_to_string(&file_path).unwrap_or_default();
+        let old_hash = old_hash.trim();
+        if old_hash == hash {
+            continue;
+        }
+        if current_branch.as_deref() == Some(destination.as_str()) {
+            status.push(format!(
+                "! [rejected] {} -> {} (refusing to fetch into current branch)",
+                reference.get_ref_path(),
+                destination
+            ));
+            continue;
+        }
+        let fast_forward = old_hash.is_empty() || is_full_ancestor(repo_path, old_hash, hash)?;
+        if !fast_forward && !refspec.force {
+            status.push(format!(
+                "! [rejected] {} -> {} (non-fast-forward)",
+                reference.get_ref_path(),
+                destination
+            ));
+            continue;
+        }
+        if let Some(parent) = Path::new(&file_path).parent() {
+            create_directory(parent)?;
+        }
+        if fs::write(&file_path, hash).is_err() {
+            return Err(CommandsError::RemotoNotInitialized);
+        };
+        let message = match (old_hash.is_empty(), fast_forward) {
+            (true, _) => "fetch: storing head",
+            (false, true) => "fetch: fast-forward",
+            (false, false) => "fetch: forced-update",
+        };
+        append_reflog(
+            repo_path,
+            &destination,
+            old_hash,
+            hash,
+            REFLOG_DEFAULT_IDENTITY,
+            message,
+        )?;
+        status.push(format!("{} -> {}", reference.get_ref_path(), destination));
+    }
+    Ok(status)
+}
+
 /// Recibe las referencias del servidor y las compara los hashes de cada branch con el repositorio local,
 /// en caso de ser todas iguales devuelve true, sino false.
 ///  
@@ -363,28 +603,39 @@ pub fn save_references(
     let refs_dir_path = format!("{}/.git/refs/remotes/{}", repo_path, name_remote);
     //ensure_directory_clean(&refs_dir_path)?;  NO VA?
 
-    let log_dir = format!("{}/.git/logs/refs/remotes/{}", repo_path, name_remote);
-    create_directory(Path::new(&log_dir))?;
-
-    // Escribe los hashes en archivos individuales
+    // Escribe los hashes en archivos individuales y registra cada cambio en el reflog
     for reference in references {
         let name_branch = reference.get_name();
         let hash = reference.get_hash();
         let file_path = format!("{}/{}", refs_dir_path, name_branch);
+        let old_hash = fs::read_to_string(&file_path).unwrap_or_default();
+        if old_hash.trim() == hash {
+            continue;
+        }
         if fs::write(&file_path, hash).is_err() {
             return Err(CommandsError::RemotoNotInitialized);
         };
-        let path_log = format!("logs/refs/remotes/{}", name_remote);
-
-        let path_branch = format!("refs/remotes/{}", name_remote);
-
-        save_log(repo_path, name_branch, &path_log, &path_branch)?;
+        let message = if old_hash.is_empty() {
+            "fetch: storing head"
+        } else {
+            "fetch: fast-forward"
+        };
+        append_reflog(
+            repo_path,
+            &format!("refs/remotes/{}/{}", name_remote, name_branch),
+            &old_hash,
+            hash,
+            REFLOG_DEFAULT_IDENTITY,
+            message,
+        )?;
     }
 
     Ok(())
 }
 
-/// Maneja la creación y el guardado de los objetos recibidos del servidor
+/// Maneja la creación y el guardado de los objetos recibidos del servidor. El avance se
+/// registra en el log como "Unpacking objects". Los objetos deben estar completos: un delta
+/// sin resolver es un error.
 ///
 /// # Argumentos
 ///
@@ -403,22 +654,203 @@ pub fn save_objects(
     // Cantidad de objetos recibidos
     let count_objects = content.len();
     let git_dir = format!("{}/{}", git_dir, GIT_DIR);
-
-    let mut i = 0;
-    while i < count_objects {
-        if content[i].0.obj_type == ObjectType::Commit {
-            let commit_content = read_commit(&content[i].1)?;
-            builder_object_commit(&commit_content, &git_dir)?;
-            i += 1;
-        } else if content[i].0.obj_type == ObjectType::Tree {
-            let tree_content = read_tree(&content[i].1)?;
-            builder_object_tree(&git_dir, &tree_content)?;
-            i += 1;
-        } else if content[i].0.obj_type == ObjectType::Blob {
-            let blob_content = read_blob(&content[i].1)?;
-            builder_object_blob(blob_content.into_bytes(), &git_dir)?;
-            i += 1;
+    let object_format = get_object_format(&git_dir);
+
+    let mut progress = Progress::new("Unpacking objects", count_objects);
+    for (i, (entry, data)) in content.iter().enumerate() {
+        if entry.obj_type == ObjectType::Commit {
+            let commit_content = read_commit(data)?;
+            builder_object_commit(&commit_content, &git_dir, object_format)?;
+        } else if entry.obj_type == ObjectType::Tree {
+            let tree_content = read_tree(data, object_format)?;
+            builder_object_tree(&git_dir, &tree_content, object_format)?;
+        } else if entry.obj_type == ObjectType::Blob {
+            let blob_content = read_blob(data)?;
+            builder_object_blob(blob_content.into_bytes(), &git_dir, object_format)?;
+        } else if entry.obj_type == ObjectType::Tag {
+            let tag_content = read_tag(data)?;
+            builder_object_tag(&tag_content, &git_dir, object_format)?;
+        } else {
+            // Los deltas se resuelven al leer el packfile; uno que llega hasta acá no tiene base
+            return Err(UtilError::UnresolvedDeltas(1).into());
         }
+        progress.log(i + 1);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::branch::git_branch_create;
+    use crate::commands::checkout::git_checkout_switch;
+    use crate::commands::clone::git_clone;
+    use crate::commands::merge::{git_merge, git_merge_fetch_head};
+    use crate::commands::{init::git_init, rev_parse::git_rev_parse};
+    use crate::test_utils::{commit_file, serve_once, test_client};
+
+    #[test]
+    fn test_refspec_wildcard() {
+        let refspec = Refspec::parse("+refs/heads/*:refs/remotes/upstream/*")
+            .expect("Falló al interpretar el refspec");
+        assert!(refspec.force);
+        assert_eq!(
+            refspec.map_reference("refs/heads/feature/login"),
+            Some("refs/remotes/upstream/feature/login".to_string())
+        );
+        assert_eq!(refspec.map_reference("refs/tags/v1"), None);
+        assert_eq!(refspec.map_reference("HEAD"), None);
+
+        assert!(Refspec::parse("refs/heads/*:refs/heads/fijo").is_err());
+        assert!(Refspec::parse("refs/heads/*/*:refs/remotes/o/*/*").is_err());
+        assert!(Refspec::parse("refs/heads/foo").is_err());
+        assert!(Refspec::parse(":refs/heads/bar").is_err());
+    }
+
+    #[test]
+    fn test_refspec_single_ref() {
+        let directory = "./test_fetch_refspec_single";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "uno");
+        let second = commit_file(directory, "a.txt", "dos");
+
+        let refspec =
+            Refspec::parse("foo:refs/heads/bar").expect("Falló al interpretar el refspec");
+        assert!(!refspec.force);
+        assert_eq!(refspec.source, "refs/heads/foo");
+        assert_eq!(
+            refspec.map_reference("refs/heads/foo"),
+            Some("refs/heads/bar".to_string())
+        );
+        assert_eq!(refspec.map_reference("refs/heads/foobar"), None);
+
+        let remote = |hash: &str| vec![Reference::new(hash, "refs/heads/foo").expect("Ref")];
+        let status = save_refspec_references(&remote(&first), directory, &refspec)
+            .expect("Falló al guardar las referencias");
+        assert_eq!(status, vec!["refs/heads/foo -> refs/heads/bar"]);
+        assert_eq!(git_rev_parse(directory, "bar"), Ok(first.clone()));
+
+        // Fast-forward
+        save_refspec_references(&remote(&second), directory, &refspec)
+            .expect("Falló al guardar las referencias");
+        assert_eq!(git_rev_parse(directory, "bar"), Ok(second.clone()));
+
+        // Un destino que no avanza se rechaza salvo que el refspec fuerce la actualización
+        let status = save_refspec_references(&remote(&first), directory, &refspec)
+            .expect("Falló al guardar las referencias");
+        assert_eq!(
+            status,
+            vec!["! [rejected] refs/heads/foo -> refs/heads/bar (non-fast-forward)"]
+        );
+        assert_eq!(git_rev_parse(directory, "bar"), Ok(second));
+        let forced = Refspec::parse("+foo:bar").expect("Falló al interpretar el refspec");
+        save_refspec_references(&remote(&first), directory, &forced)
+            .expect("Falló al guardar las referencias");
+        assert_eq!(git_rev_parse(directory, "bar"), Ok(first));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_save_refspec_references_merge_and_current_branch() {
+        let directory = "./test_fetch_refspec_merge";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "uno");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let feature = commit_file(directory, "b.txt", "en feature");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let master = commit_file(directory, "c.txt", "en master");
+        git_merge(
+            directory,
+            "master",
+            "feature",
+            test_client(directory, "9090"),
+            true,
+            None,
+        )
+        .expect("Falló al hacer el merge");
+        let merge = git_rev_parse(directory, "master").expect("Falló al resolver master");
+
+        let refspec = Refspec::parse("foo:bar").expect("Falló al interpretar el refspec");
+        let remote = |hash: &str| vec![Reference::new(hash, "refs/heads/foo").expect("Ref")];
+        save_refspec_references(&remote(&feature), directory, &refspec)
+            .expect("Falló al guardar las referencias");
+        // El destino sólo es el segundo parent del merge, pero sigue siendo un fast-forward
+        let merged = save_refspec_references(&remote(&merge), directory, &refspec);
+        let bar = git_rev_parse(directory, "bar");
+        let current = Refspec::parse("+foo:master").expect("Falló al interpretar el refspec");
+        let rejected = save_refspec_references(&remote(&feature), directory, &current);
+        let head = git_rev_parse(directory, "master");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_ne!(master, merge);
+        assert_eq!(
+            merged,
+            Ok(vec!["refs/heads/foo -> refs/heads/bar".to_string()])
+        );
+        assert_eq!(bar, Ok(merge.clone()));
+        assert_eq!(
+            rejected,
+            Ok(vec![
+                "! [rejected] refs/heads/foo -> refs/heads/master (refusing to fetch into current branch)"
+                    .to_string()
+            ])
+        );
+        assert_eq!(head, Ok(merge));
+    }
+
+    #[test]
+    fn test_fetch_writes_fetch_head_and_merge_uses_it() {
+        let root = "./test_fetch_head_server";
+        let local_repo = "./test_fetch_head_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        let first = commit_file(&remote, "a.txt", "uno");
+
+        let (mut socket, port, server) = serve_once(root);
+        git_clone(&mut socket, "127.0.0.1", &port, local_repo, "repo")
+            .expect("Falló al clonar el repositorio");
+        server
+            .join()
+            .expect("Falló el servidor")
+            .expect("Falló la solicitud");
+
+        let second = commit_file(&remote, "a.txt", "dos");
+        let (mut socket, port, server) = serve_once(root);
+        let status = git_fetch_branch(
+            &mut socket,
+            "127.0.0.1",
+            &port,
+            local_repo,
+            "origin",
+            "master",
+        );
+        server
+            .join()
+            .expect("Falló el servidor")
+            .expect("Falló la solicitud");
+        let fetch_head = fs::read_to_string(format!("{}/{}/FETCH_HEAD", local_repo, GIT_DIR));
+        let before_merge = git_rev_parse(local_repo, "master");
+
+        let client = test_client(local_repo, &port);
+        let merge = git_merge_fetch_head(local_repo, "master", client, None);
+        let after_merge = git_rev_parse(local_repo, "master");
+        let content = fs::read_to_string(format!("{}/a.txt", local_repo));
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(matches!(status, Ok(FetchStatus::UpdatesBranch(_))));
+        assert_eq!(
+            fetch_head.ok(),
+            Some(format!("{}\t\tbranch 'master' of repo\n", second))
+        );
+        // El fetch no mueve la branch local; el merge de FETCH_HEAD sí
+        assert_eq!(before_merge, Ok(first));
+        assert!(merge.is_ok());
+        assert_eq!(after_merge, Ok(second));
+        assert_eq!(content.ok().as_deref(), Some("dos"));
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     io::{self, BufRead, Write},
 };
 
+use crate::consts::{FETCH_HEAD, GIT_DIR};
 use crate::git_transport::references::Reference;
 
 use super::errors::CommandsError;
@@ -37,7 +38,7 @@ impl fmt::Display for FetchHeadEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
             f,
-            "{}\t{}\tbranch '{}' of github.com:{}",
+            "{}\t{}\tbranch '{}' of {}",
             self.commit_hash, self.label, self.branch_name, self.url_remote
         )
     }
@@ -162,8 +163,7 @@ impl FetchHead {
     /// Devuelve un error de tipo CommandsError si ocurre algún problema durante la escritura.
     ///
     pub fn write(&self, repo_local: &str) -> Result<(), CommandsError> {
-        let repo = format!("{}/.git", repo_local);
-        let fetch_head_path = format!("{}/FETCH_HEAD", repo);
+        let fetch_head_path = format!("{}/{}/{}", repo_local, GIT_DIR, FETCH_HEAD);
         match self._write(&fetch_head_path) {
             Ok(_) => Ok(()),
             Err(_) => Err(CommandsError::WriteFetchHEAD),
@@ -171,9 +171,10 @@ impl FetchHead {
     }
 
     // Método auxiliar que realiza la escritura real en el archivo FETCH_HEAD.
+    // Las entradas para mergear se escriben primero, ya que FETCH_HEAD se resuelve con la primera línea.
     fn _write(&self, fetch_head_path: &str) -> io::Result<()> {
         let mut file = fs::File::create(fetch_head_path)?;
-        for entry in self.entries.values() {
+        for entry in self.sorted_entries() {
             let line = format!("{}", entry);
             file.write_all(line.as_bytes())?;
         }
@@ -197,10 +198,58 @@ impl FetchHead {
     ///
     /// Retorna un error si el archivo FETCH_HEAD no se encuentra o si hay problemas al leer su contenido.
     pub fn new_from_file(repo_path: &str) -> Result<FetchHead, CommandsError> {
-        let repo = format!("{}/.git/FETCH_HEAD", repo_path);
+        let repo = format!("{}/{}/{}", repo_path, GIT_DIR, FETCH_HEAD);
         _read_fetch_head(&repo)
     }
 
+    /// Devuelve las entradas en el orden en que se escriben en el archivo: primero las que se
+    /// pueden mergear y luego las marcadas como "not-for-merge", cada grupo ordenado por branch.
+    fn sorted_entries(&self) -> Vec<&FetchHeadEntry> {
+        let mut entries: Vec<&FetchHeadEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            (a.label != Label::Merge, &a.branch_name)
+                .cmp(&(b.label != Label::Merge, &b.branch_name))
+        });
+        entries
+    }
+
+    /// Marca como "not-for-merge" todas las entradas salvo la de la branch indicada, como hace
+    /// git con las branches que no son el upstream de la branch actual.
+    ///
+    /// # Arguments
+    ///
+    /// * `branch_name` - Nombre de la rama que se podrá mergear.
+    ///
+    pub fn mark_for_merge(&mut self, branch_name: &str) {
+        for entry in self.entries.values_mut() {
+            if entry.branch_name != branch_name {
+                entry.label = Label::NotForMerge;
+            }
+        }
+    }
+
+    /// Obtiene el hash del commit que se mergea al hacer `git merge` sin argumentos: el de la
+    /// primera entrada del archivo que no está marcada como "not-for-merge".
+    ///
+    /// # Returns
+    ///
+    /// Retorna el hash del commit a mergear.
+    ///
+    /// # Errors
+    ///
+    /// Retorna `CommandsError::FetchHeadFileNotFound` si no hay entradas y
+    /// `CommandsError::MergeNotAllowedError` si todas están marcadas como "not-for-merge".
+    ///
+    pub fn get_hash_to_merge(&self) -> Result<String, CommandsError> {
+        if self.entries.is_empty() {
+            return Err(CommandsError::FetchHeadFileNotFound);
+        }
+        match self.sorted_entries().first() {
+            Some(entry) if entry.label == Label::Merge => Ok(entry.commit_hash.to_string()),
+            _ => Err(CommandsError::MergeNotAllowedError),
+        }
+    }
+
     /// Verifica si las referencias necesitan actualizarse para la rama dada.
     ///
     /// # Arguments
@@ -304,7 +353,7 @@ impl FetchHead {
 ///
 fn extract_branch_info(branch_info: &str) -> Result<(String, String), CommandsError> {
     let prefix = "branch '";
-    let suffix = "' of github.com:";
+    let suffix = "' of ";
 
     if let Some(start_pos) = branch_info.find(prefix) {
         let start_pos = start_pos + prefix.len();
@@ -359,7 +408,7 @@ mod tests {
 
     #[test]
     fn test_extract_branch_info_valid() {
-        let branch_info = "branch 'my-branch' of github.com:example/repo";
+        let branch_info = "branch 'my-branch' of example/repo";
         let result = extract_branch_info(branch_info);
         assert!(result.is_ok());
 
@@ -378,7 +427,8 @@ mod tests {
 
     #[test]
     fn test_fetch_head_entry_new_from_line_not_for_merge() {
-        let line = "d3214e19f4736504392664d579ce1ef2d15b5581	not-for-merge	branch 'main' of github.com:example/repo";
+        let line =
+            "d3214e19f4736504392664d579ce1ef2d15b5581	not-for-merge	branch 'main' of example/repo";
         let result = FetchHeadEntry::new_from_line(line);
         assert!(result.is_ok());
 
@@ -394,8 +444,7 @@ mod tests {
 
     #[test]
     fn test_fetch_head_entry_new_from_line_merge() {
-        let line =
-            "d3214e19f4736504392664d579ce1ef2d15b5581		branch 'main' of github.com:example/repo";
+        let line = "d3214e19f4736504392664d579ce1ef2d15b5581		branch 'main' of example/repo";
         let result = FetchHeadEntry::new_from_line(line);
         assert!(result.is_ok());
 
@@ -785,4 +834,46 @@ mod tests {
         let result = fetch_head._write("./test_files/test_head_write");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_mark_for_merge_writes_merge_entry_first() {
+        let references = vec![
+            Reference::new(
+                "93455fe53543e1dcca9533dd51d5b83656a6432c",
+                "refs/heads/branch1",
+            )
+            .unwrap(),
+            Reference::new(
+                "56620fe39508e1dcca4873dd51d5b83656a9418c",
+                "refs/heads/branch2",
+            )
+            .unwrap(),
+        ];
+        let mut fetch_head = FetchHead::new(&references, "Repository/Rust").unwrap();
+        fetch_head.mark_for_merge("branch2");
+
+        assert!(!fetch_head.references_needs_update("branch1"));
+        assert_eq!(
+            fetch_head.get_hash_to_merge(),
+            Ok("56620fe39508e1dcca4873dd51d5b83656a9418c".to_string())
+        );
+        let lines: Vec<String> = fetch_head
+            .sorted_entries()
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "56620fe39508e1dcca4873dd51d5b83656a9418c\t\tbranch 'branch2' of Repository/Rust\n",
+                "93455fe53543e1dcca9533dd51d5b83656a6432c\tnot-for-merge\tbranch 'branch1' of Repository/Rust\n",
+            ]
+        );
+
+        fetch_head.mark_for_merge("otra");
+        assert_eq!(
+            fetch_head.get_hash_to_merge(),
+            Err(CommandsError::MergeNotAllowedError)
+        );
+    }
 }
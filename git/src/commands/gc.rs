@@ -0,0 +1,316 @@
+use super::cat_file::git_cat_file;
+use super::commit::Commit;
+use super::errors::CommandsError;
+use super::notes::get_notes_ref_hash;
+use super::reflog::{get_reflogs_hashes, git_reflog_expire_all, REFLOG_EXPIRE};
+use crate::consts::{COMMIT, DIR_OBJECTS, GIT_DIR, HEAD, TAG, TREE, WORKTREES_DIR};
+use crate::git_transport::references::Reference;
+use crate::models::client::Client;
+use crate::util::files::get_common_git_dir;
+use crate::util::index::open_index;
+use crate::util::object_format::{get_object_format, ObjectFormat};
+use crate::util::objects::{get_object_hashes, object_exists};
+use crate::util::validation::is_valid_obj_id;
+use std::collections::HashSet;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+/// Antigüedad mínima que debe tener un objeto inalcanzable para que `git gc` lo elimine (dos
+/// semanas, como en git). Evita borrar objetos recién creados por un comando en curso.
+const GC_PRUNE_EXPIRE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Resultado de eliminar los objetos inalcanzables.
+///
+/// - `objects`: cantidad de objetos eliminados.
+/// - `bytes`: espacio liberado, en bytes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PruneResult {
+    pub objects: usize,
+    pub bytes: u64,
+}
+
+/// Esta función se encarga de llamar al comando gc con los parametros necesarios.
+///
+/// * `git gc`: elimina las entradas de los reflogs con más de 90 días y los objetos sueltos
+///   inalcanzables con más de dos semanas.
+/// * `git gc --prune=now`: elimina todos los objetos sueltos inalcanzables.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función gc
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_gc(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    let result = match args.as_slice() {
+        [] => git_gc(directory)?,
+        ["--prune=now"] => git_prune(directory, Duration::ZERO)?,
+        _ => return Err(CommandsError::InvalidArgumentCountGcError),
+    };
+    Ok(format_prune_result(&result))
+}
+
+/// Esta función se encarga de llamar al comando prune con los parametros necesarios.
+///
+/// * `git prune`: elimina todos los objetos sueltos inalcanzables.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función prune
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_prune(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    if !args.is_empty() {
+        return Err(CommandsError::InvalidArgumentCountPruneError);
+    }
+    let directory = client.get_directory_path();
+    let result = git_prune(directory, Duration::ZERO)?;
+    Ok(format_prune_result(&result))
+}
+
+/// Elimina los objetos sueltos inalcanzables con la antigüedad por defecto de `git gc`.
+///
+/// Antes se eliminan de los reflogs las entradas de más de 90 días, para que no sigan
+/// reteniendo los objetos a los que apuntaban. Todos los objetos se guardan sueltos en este
+/// cliente, por lo que no hay objetos para empaquetar antes de eliminar los inalcanzables.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn git_gc(directory: &str) -> Result<PruneResult, CommandsError> {
+    git_reflog_expire_all(directory, Some(REFLOG_EXPIRE), None)?;
+    git_prune(directory, GC_PRUNE_EXPIRE)
+}
+
+/// Elimina de `.git/objects` los objetos sueltos que no se pueden alcanzar desde ninguna
+/// referencia, HEAD, reflog ni desde el index, y cuya última modificación tiene al menos la
+/// antigüedad indicada. Se tienen en cuenta el HEAD y el index de todos los working trees,
+/// ya que comparten los objetos. Los directorios de objetos que quedan vacíos también se
+/// eliminan.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'expire': antigüedad mínima de los objetos a eliminar
+pub fn git_prune(directory: &str, expire: Duration) -> Result<PruneResult, CommandsError> {
+    let reachable = get_reachable_objects(directory)?;
+    let git_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+    let now = SystemTime::now();
+    let mut result = PruneResult::default();
+
+    for hash in get_object_hashes(&git_dir)? {
+        if reachable.contains(&hash) {
+            continue;
+        }
+        let object_dir = format!("{}/{}/{}", git_dir, DIR_OBJECTS, &hash[..2]);
+        let object_path = format!("{}/{}", object_dir, &hash[2..]);
+        let metadata = match fs::metadata(&object_path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < expire {
+            continue;
+        }
+        if fs::remove_file(&object_path).is_err() {
+            return Err(CommandsError::RemoveFileError);
+        }
+        result.objects += 1;
+        result.bytes += metadata.len();
+        // Solo se elimina si quedó vacío
+        let _ = fs::remove_dir(&object_dir);
+    }
+    Ok(result)
+}
+
+/// Obtiene los hashes de todos los objetos alcanzables: se parte de las referencias, HEAD,
+/// las entradas de los reflogs, las notas y los blobs del index, y se recorren los parents y el tree de
+/// los commits, las entradas de los trees y el objeto de las tags anotadas. Los HEAD y los
+/// index de los working trees enlazados también son puntos de partida.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local o de uno de sus working trees
+fn get_reachable_objects(directory: &str) -> Result<HashSet<String>, CommandsError> {
+    let common_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+    let object_format = get_object_format(&common_dir);
+    let line_hash = |line: &str| last_hash(line, object_format);
+    // Las referencias, los reflogs y las notas se guardan en el repositorio principal
+    let main_directory = format!("{}/..", common_dir);
+    let mut pending: Vec<String> = Reference::extract_references_from_git(&main_directory)?
+        .iter()
+        .map(|reference| reference.get_hash().to_string())
+        .collect();
+    pending.extend(get_reflogs_hashes(&main_directory)?);
+    pending.extend(get_notes_ref_hash(&main_directory)?);
+    for git_dir in get_worktree_git_dirs(&common_dir) {
+        // Un HEAD desacoplado apunta a un commit que puede no estar en ninguna referencia
+        if let Ok(head) = fs::read_to_string(format!("{}/{}", git_dir, HEAD)) {
+            pending.extend(line_hash(&head));
+        }
+        if let Ok(index) = open_index(&git_dir) {
+            pending.extend(index.lines().filter_map(line_hash));
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    while let Some(hash) = pending.pop() {
+        if reachable.contains(&hash) || !object_exists(directory, &hash) {
+            continue;
+        }
+        let object_type = git_cat_file(directory, &hash, "-t")?;
+        if object_type == COMMIT || object_type == TREE || object_type == TAG {
+            let content = git_cat_file(directory, &hash, "-p")?;
+            if object_type == COMMIT {
+                let commit = Commit::from_object(&content)?;
+                pending.push(commit.get_tree().to_string());
+                pending.extend(commit.get_parents().iter().cloned());
+            } else if object_type == TREE {
+                pending.extend(content.lines().filter_map(line_hash));
+            } else if let Some(object) = content
+                .lines()
+                .find_map(|line| line.strip_prefix("object "))
+            {
+                pending.push(object.trim().to_string());
+            }
+        }
+        reachable.insert(hash);
+    }
+    Ok(reachable)
+}
+
+/// Devuelve el directorio git de cada working tree: el compartido, que es el del working tree
+/// principal, y los de `worktrees/<nombre>` de los enlazados.
+/// ###Parametros:
+/// 'common_dir': directorio git compartido por los working trees
+fn get_worktree_git_dirs(common_dir: &str) -> Vec<String> {
+    let mut git_dirs = vec![common_dir.to_string()];
+    if let Ok(entries) = fs::read_dir(format!("{}/{}", common_dir, WORKTREES_DIR)) {
+        git_dirs.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path().to_string_lossy().to_string()),
+        );
+    }
+    git_dirs
+}
+
+/// Devuelve el hash con el que termina una línea del index o de un tree, si lo hay.
+/// ###Parametros:
+/// 'line': línea con el hash como última palabra
+/// 'object_format': formato de los objetos del repositorio
+fn last_hash(line: &str, object_format: ObjectFormat) -> Option<String> {
+    let hash = line.split_whitespace().last()?;
+    if is_valid_obj_id(hash, object_format) {
+        return Some(hash.to_string());
+    }
+    None
+}
+
+/// Formatea la cantidad de objetos eliminados y el espacio liberado.
+/// ###Parametros:
+/// 'result': resultado de eliminar los objetos inalcanzables
+fn format_prune_result(result: &PruneResult) -> String {
+    format!(
+        "Removed {} unreachable objects ({} bytes freed)\n",
+        result.objects, result.bytes
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{add::git_add, commit::git_commit, init::git_init};
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+    use crate::util::objects::builder_object_blob;
+
+    #[test]
+    fn test_git_prune_removes_dangling_blob() {
+        let directory = "./test_gc_prune";
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        git_init(directory).expect("Falló al crear el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let commit = test_commit("primero");
+        git_commit(directory, commit).expect("Falló al hacer el commit");
+        // Un archivo agregado al index pero sin commitear sigue siendo alcanzable
+        create_file_replace(&format!("{}/b.txt", directory), "dos")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "b.txt").expect("Falló al agregar el archivo");
+        let dangling =
+            builder_object_blob(b"sin referencias".to_vec(), &git_dir, ObjectFormat::Sha1)
+                .expect("Falló al crear el blob");
+        let objects_before = get_object_hashes(&git_dir).expect("Falló al leer los objetos");
+
+        // El objeto es reciente, por lo que gc lo conserva
+        assert_eq!(git_gc(directory), Ok(PruneResult::default()));
+        assert!(object_exists(directory, &dangling));
+
+        let result = git_prune(directory, Duration::ZERO).expect("Falló el prune");
+        assert_eq!(result.objects, 1);
+        assert!(result.bytes > 0);
+        assert!(!object_exists(directory, &dangling));
+        let objects_after = get_object_hashes(&git_dir).expect("Falló al leer los objetos");
+        assert_eq!(objects_after.len(), objects_before.len() - 1);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_git_prune_keeps_linked_worktree_objects() {
+        use crate::commands::{branch::git_branch_create, worktree::git_worktree_add};
+
+        let directory = "./test_gc_worktree_main";
+        let linked = "./test_gc_worktree_linked";
+        git_init(directory).expect("Falló al crear el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let commit = test_commit("primero");
+        git_commit(directory, commit).expect("Falló al hacer el commit");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_worktree_add(directory, linked, "feature").expect("Falló al crear el working tree");
+
+        // Commit en el working tree enlazado que luego solo queda en su HEAD desacoplado
+        create_file_replace(&format!("{}/b.txt", linked), "dos")
+            .expect("Falló al crear el archivo");
+        git_add(linked, "b.txt").expect("Falló al agregar el archivo");
+        let commit = test_commit("segundo");
+        git_commit(linked, commit).expect("Falló al hacer el commit");
+        let admin_dir = format!(
+            "{}/{}/{}/{}",
+            directory, GIT_DIR, WORKTREES_DIR, "test_gc_worktree_linked"
+        );
+        let detached = fs::read_to_string(format!("{}/{}/refs/heads/feature", directory, GIT_DIR))
+            .expect("Falló al leer la branch");
+        let detached = detached.trim().to_string();
+        create_file_replace(&format!("{}/{}", admin_dir, HEAD), &detached)
+            .expect("Falló al desacoplar HEAD");
+        fs::remove_file(format!("{}/{}/refs/heads/feature", directory, GIT_DIR))
+            .expect("Falló al borrar la branch");
+        fs::remove_file(format!("{}/{}/logs/refs/heads/feature", directory, GIT_DIR))
+            .expect("Falló al borrar el reflog");
+        // Blob que solo está en el index del working tree enlazado
+        create_file_replace(&format!("{}/c.txt", linked), "tres")
+            .expect("Falló al crear el archivo");
+        git_add(linked, "c.txt").expect("Falló al agregar el archivo");
+        let staged = last_hash(
+            open_index(&admin_dir)
+                .expect("Falló al leer el index")
+                .lines()
+                .find(|line| line.starts_with("c.txt"))
+                .expect("Falta c.txt en el index"),
+            ObjectFormat::Sha1,
+        )
+        .expect("Hash inválido");
+
+        let result_main = git_prune(directory, Duration::ZERO);
+        let result_linked = git_prune(linked, Duration::ZERO);
+        let detached_exists = object_exists(directory, &detached);
+        let staged_exists = object_exists(directory, &staged);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(linked).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(result_main, Ok(PruneResult::default()));
+        assert_eq!(result_linked, Ok(PruneResult::default()));
+        assert!(detached_exists);
+        assert!(staged_exists);
+    }
+}
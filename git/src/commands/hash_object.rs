@@ -1,7 +1,7 @@
 use super::errors::CommandsError;
 use crate::models::client::Client;
-use crate::util::files::read_file;
-use crate::util::formats::hash_generate;
+use crate::util::files::{get_git_dir, read_file};
+use crate::util::object_format::get_object_format;
 use crate::{consts::*, util::files::open_file};
 
 /// Esta función se encarga de llamar al comando hash-object con los parametros necesarios
@@ -37,7 +37,7 @@ pub fn git_hash_object_blob(file_name: &str, directory: &str) -> Result<String,
         String::from_utf8_lossy(&content)
     );
 
-    let hash = hash_generate(&object_contents);
+    let hash = get_object_format(&get_git_dir(directory)).hash(object_contents.as_bytes());
 
     Ok(hash)
 }
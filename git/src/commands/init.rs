@@ -2,25 +2,82 @@ use super::errors::CommandsError;
 use crate::consts::*;
 use crate::models::client::Client;
 use crate::util::files::*;
+use crate::util::index::write_index;
+use crate::util::object_format::{object_format_config, ObjectFormat};
+use crate::util::validation::valid_ref_name;
 use std::path::Path;
 
 /// Esta función se encarga de llamar al comando init con los parametros necesarios
+///
+/// * `git init`: HEAD apunta a la branch por defecto de la configuración (`init.defaultBranch`).
+/// * `git init --initial-branch=<branch>` o `git init -b <branch>`: HEAD apunta a la branch indicada.
+/// * `git init --object-format=<sha1|sha256>`: los objetos del repositorio se nombran con el hash
+///   indicado. Se puede combinar con las opciones de la branch inicial.
+///
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función init
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_init(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if !args.is_empty() {
-        return Err(CommandsError::InvalidArgumentCountInitError);
+    let mut initial_branch = client.get_default_branch();
+    let mut object_format = ObjectFormat::Sha1;
+    let mut args = args.as_slice();
+    while !args.is_empty() {
+        args = match args {
+            ["-b", branch, rest @ ..] | ["--initial-branch", branch, rest @ ..] => {
+                initial_branch = branch;
+                rest
+            }
+            [flag, rest @ ..] if flag.starts_with("--initial-branch=") => {
+                initial_branch = &flag["--initial-branch=".len()..];
+                rest
+            }
+            [flag, rest @ ..] if flag.starts_with("--object-format=") => {
+                let name = &flag["--object-format=".len()..];
+                object_format = ObjectFormat::from_name(name)
+                    .ok_or_else(|| CommandsError::UnknownObjectFormat(name.to_string()))?;
+                rest
+            }
+            _ => return Err(CommandsError::InvalidArgumentCountInitError),
+        };
     }
-    let result = git_init(client.get_directory_path())?;
+    let result = git_init_with_format(client.get_directory_path(), initial_branch, object_format)?;
 
     Ok(result)
 }
 
 /// Esta función inicia un repositorio git creando los directorios y archivos necesarios.
+/// HEAD apunta a la branch `master`.
 /// ###Parametros:
 /// 'directory': dirección donde se inicializará el repositorio.
 pub fn git_init(directory: &str) -> Result<String, CommandsError> {
+    git_init_with_branch(directory, INITIAL_BRANCH)
+}
+
+/// Inicia un repositorio git cuyo HEAD apunta a la branch indicada. Si el repositorio ya
+/// existía, su HEAD no se modifica.
+/// ###Parametros:
+/// 'directory': dirección donde se inicializará el repositorio.
+/// 'initial_branch': nombre de la branch a la que apunta HEAD.
+pub fn git_init_with_branch(
+    directory: &str,
+    initial_branch: &str,
+) -> Result<String, CommandsError> {
+    git_init_with_format(directory, initial_branch, ObjectFormat::Sha1)
+}
+
+/// Inicia un repositorio git cuyos objetos se nombran con el formato indicado. El formato se
+/// guarda en `extensions.objectformat` de la configuración, salvo SHA-1 que es el formato por
+/// defecto. Si el repositorio ya existía, su configuración no se modifica.
+/// ###Parametros:
+/// 'directory': dirección donde se inicializará el repositorio.
+/// 'initial_branch': nombre de la branch a la que apunta HEAD.
+/// 'object_format': formato de los objetos del repositorio.
+pub fn git_init_with_format(
+    directory: &str,
+    initial_branch: &str,
+    object_format: ObjectFormat,
+) -> Result<String, CommandsError> {
+    valid_ref_name(initial_branch)?;
     let mut exist = 0;
     let git_dir = format!("{}/{}", directory, GIT_DIR);
     if Path::new(&git_dir).is_dir() {
@@ -42,13 +99,16 @@ pub fn git_init(directory: &str) -> Result<String, CommandsError> {
     create_directory(Path::new(&origin_dir))?;
 
     let head_file = format!("{}/{}", &git_dir, HEAD);
-    let head_content = format!("{}{}\n", HEAD_POINTER_REF, INITIAL_BRANCH);
+    let head_content = format!("{}{}\n", HEAD_POINTER_REF, initial_branch);
     let index_file = format!("{}/{}", &git_dir, INDEX);
     let config_file = format!("{}/{}", &git_dir, CONFIG_FILE);
 
     create_file(&head_file, &head_content)?;
-    create_file(&index_file, CONTENT_EMPTY)?;
-    create_file(&config_file, CONTENT_EMPTY)?;
+    create_file(&config_file, &object_format_config(object_format))?;
+    // El index se guarda en el formato binario de git, con el formato de objetos ya configurado
+    if !Path::new(&index_file).exists() {
+        write_index(&git_dir, "")?;
+    }
 
     let result = if exist == 0 {
         format!("Initialized empty Git repository in {}/.git/", directory)
@@ -65,6 +125,15 @@ pub fn git_init(directory: &str) -> Result<String, CommandsError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::{
+        add::git_add,
+        cat_file::git_cat_file,
+        checkout::get_tree_hash,
+        commit::{git_commit, Commit},
+        rev_parse::git_rev_parse,
+        status::is_files_to_commit,
+    };
+    use crate::util::object_format::get_object_format;
     use std::fs;
 
     #[test]
@@ -93,4 +162,61 @@ mod tests {
 
         fs::remove_dir_all(&temp_dir).expect("Falló al remover el directorio temporal");
     }
+
+    #[test]
+    fn test_git_init_with_branch() {
+        let directory = "./test_git_init_main";
+        git_init_with_branch(directory, "main").expect("Falló al crear el repositorio");
+        let head = fs::read_to_string(format!("{}/{}/{}", directory, GIT_DIR, HEAD));
+        // Al reinicializar no se cambia la branch de HEAD
+        git_init(directory).expect("Falló al reinicializar el repositorio");
+        let head_after_reinit = fs::read_to_string(format!("{}/{}/{}", directory, GIT_DIR, HEAD));
+        let invalid = git_init_with_branch("./test_git_init_invalid", "a..b");
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(head.ok().as_deref(), Some("ref: refs/heads/main\n"));
+        assert_eq!(
+            head_after_reinit.ok().as_deref(),
+            Some("ref: refs/heads/main\n")
+        );
+        assert!(invalid.is_err());
+        assert!(!Path::new("./test_git_init_invalid").exists());
+    }
+
+    #[test]
+    fn test_git_init_sha256_stores_objects() {
+        let directory = "./test_git_init_sha256";
+        git_init_with_format(directory, "main", ObjectFormat::Sha256)
+            .expect("Falló al crear el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "hola")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let commit = Commit::new(
+            "primero".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(directory, commit).expect("Falló al hacer el commit");
+
+        let git_dir = get_git_dir(directory);
+        let object_format = get_object_format(&git_dir);
+        let head = git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD");
+        let commit_content = git_cat_file(directory, &head, "-p").expect("Falló al leer el commit");
+        let tree = get_tree_hash(&commit_content).expect("El commit no tiene tree");
+        let tree_content = git_cat_file(directory, tree, "-p");
+        let blob = ObjectFormat::Sha256.hash(b"blob 4\0hola");
+        let blob_path = format!("{}/objects/{}/{}", git_dir, &blob[..2], &blob[2..]);
+        let blob_stored = Path::new(&blob_path).is_file();
+        let clean = is_files_to_commit(directory);
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(object_format, ObjectFormat::Sha256);
+        assert_eq!(head.len(), 64);
+        assert_eq!(tree.len(), 64);
+        assert!(blob_stored);
+        assert!(tree_content.is_ok_and(|content| content.contains(&blob)));
+        assert_eq!(clean, Ok(false));
+    }
 }
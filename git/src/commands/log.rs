@@ -1,199 +1,466 @@
 use super::cat_file::git_cat_file;
-use super::checkout::extract_parent_hash;
-use super::commit::builder_commit_log;
+use super::commit::{format_signature_date, Commit};
 use super::errors::CommandsError;
-use crate::consts::{GIT_DIR, PARENT_INITIAL};
+use super::rev_parse::resolve_commit;
+use crate::consts::{GIT_DIR, HEAD};
 use crate::models::client::Client;
-use crate::util::files::{open_file, read_file_string};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::util::files::{file_exists, open_file, read_file_string};
+use crate::util::objects::{abbreviate_hash, get_object_hashes};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
-use super::branch::get_current_branch;
+/// Opciones de formato del comando log.
+///
+/// - `oneline`: muestra cada commit en una línea con el hash abreviado y el título.
+/// - `graph`: dibuja el grafo de commits en ASCII a la izquierda de la salida.
+/// - `max_count`: cantidad máxima de commits a mostrar.
+/// - `author`: solo se muestran los commits cuyo autor contenga este texto.
+/// - `since`: solo se muestran los commits posteriores a este timestamp.
+/// - `until`: solo se muestran los commits anteriores a este timestamp.
+/// - `revision`: revisión desde la que se recorre el historial; por defecto HEAD.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    pub oneline: bool,
+    pub graph: bool,
+    pub max_count: Option<usize>,
+    pub author: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub revision: Option<String>,
+}
+
+impl LogOptions {
+    /// Crea las opciones del log a partir de los argumentos del comando.
+    /// ###Parametros:
+    /// 'args': Argumentos recibidos: `--oneline`, `--graph`, `--max-count <n>`, `--max-count=<n>`, `-n <n>`,
+    /// `--author=<texto>`, `--since=<fecha>`, `--until=<fecha>` y una revisión opcional
+    pub fn new_from_args(args: &[&str]) -> Result<LogOptions, CommandsError> {
+        let mut options = LogOptions::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "--oneline" => options.oneline = true,
+                "--graph" => options.graph = true,
+                "--max-count" | "-n" => {
+                    let value = args
+                        .next()
+                        .ok_or(CommandsError::InvalidArgumentCountLogError)?;
+                    options.max_count = Some(parse_max_count(value)?);
+                }
+                _ if !arg.starts_with('-') && options.revision.is_none() => {
+                    options.revision = Some(arg.to_string())
+                }
+                _ => options.parse_flag_with_value(arg)?,
+            }
+        }
+        Ok(options)
+    }
+
+    /// Lee las opciones con el formato `--flag=<valor>`.
+    fn parse_flag_with_value(&mut self, arg: &str) -> Result<(), CommandsError> {
+        let (flag, value) = arg
+            .split_once('=')
+            .ok_or(CommandsError::FlagLogNotRecognizedError)?;
+        match flag {
+            "--max-count" => self.max_count = Some(parse_max_count(value)?),
+            "--author" => self.author = Some(value.to_string()),
+            "--since" => self.since = Some(parse_date(value, false)?),
+            "--until" => self.until = Some(parse_date(value, true)?),
+            _ => return Err(CommandsError::FlagLogNotRecognizedError),
+        }
+        Ok(())
+    }
+
+    /// Indica si un commit cumple con los filtros de autor y fecha.
+    /// Los commits sin un timestamp válido no cumplen los filtros de fecha.
+    /// ###Parametros:
+    /// 'content': contenido del commit
+    fn matches(&self, content: &str) -> bool {
+        if let Some(author) = &self.author {
+            if !get_commit_author(content).is_some_and(|line| line.contains(author.as_str())) {
+                return false;
+            }
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let timestamp = match get_commit_timestamp(content) {
+            Some(timestamp) => timestamp,
+            None => return false,
+        };
+        let after_since = match self.since {
+            Some(since) => timestamp >= since,
+            None => true,
+        };
+        let before_until = match self.until {
+            Some(until) => timestamp <= until,
+            None => true,
+        };
+        after_since && before_until
+    }
+}
+
+/// Convierte el valor de `--max-count` a un número.
+fn parse_max_count(value: &str) -> Result<usize, CommandsError> {
+    value
+        .parse::<usize>()
+        .map_err(|_| CommandsError::FlagLogNotRecognizedError)
+}
+
+/// Convierte la fecha de `--since`/`--until` a un timestamp (UTC).
+/// Se aceptan timestamps, fechas `AAAA-MM-DD` y fechas con hora `AAAA-MM-DDTHH:MM:SS`.
+/// Si solo se indica el día, `--until` incluye el día completo.
+/// ###Parametros:
+/// 'value': fecha a convertir
+/// 'end_of_day': si es `true` una fecha sin hora se toma al final del día
+fn parse_date(value: &str, end_of_day: bool) -> Result<i64, CommandsError> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(date_time.and_utc().timestamp());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| CommandsError::FlagLogNotRecognizedError)?;
+    let date_time = match end_of_day {
+        true => date.and_hms_opt(23, 59, 59),
+        false => date.and_hms_opt(0, 0, 0),
+    };
+    match date_time {
+        Some(date_time) => Ok(date_time.and_utc().timestamp()),
+        None => Err(CommandsError::FlagLogNotRecognizedError),
+    }
+}
 
 /// Esta función se encarga de llamar al comando log con los parametros necesarios
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función log
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_log(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if args.len() > 1 {
-        return Err(CommandsError::InvalidArgumentCountLogError);
-    }
+    let options = LogOptions::new_from_args(&args)?;
     let directory = client.get_directory_path();
-    git_log(directory)
+    git_log(directory, &options)
 }
 
-/// Muestra el log de los commits
+/// Muestra el log de los commits alcanzables desde HEAD (o desde la revisión indicada en las
+/// opciones), del más nuevo al más viejo.
+/// Se recorren todos los parents de cada commit, por lo que se incluyen las ramas mergeadas.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
-pub fn git_log(directory: &str) -> Result<String, CommandsError> {
-    let mut formatted_result = String::new();
+/// 'options': opciones de formato del log
+pub fn git_log(directory: &str, options: &LogOptions) -> Result<String, CommandsError> {
+    let head = match &options.revision {
+        Some(revision) => resolve_commit(directory, revision)?,
+        None => match get_head_commit(directory)? {
+            Some(head) => head,
+            None => return Ok(String::new()),
+        },
+    };
+    let commits = get_commits_topological(directory, &head)?;
+    let hashes = if options.oneline {
+        get_object_hashes(&format!("{}/{}", directory, GIT_DIR))?
+    } else {
+        Vec::new()
+    };
 
-    let logs_path = format!("{}/.git/logs/refs/heads", directory);
+    let mut formatted_result = String::new();
+    let mut columns: Vec<String> = Vec::new();
+    let mut count = 0;
+    let max_count = options.max_count.unwrap_or(usize::MAX);
+    for (hash, content) in commits.iter() {
+        if count >= max_count {
+            break;
+        }
+        let commit = Commit::from_object(content)?;
+        let parents: Vec<&str> = commit.get_parents().iter().map(String::as_str).collect();
+        if !options.matches(content) {
+            // El commit no se muestra, pero el grafo debe seguir a sus parents
+            if options.graph {
+                draw_graph(&mut columns, hash, &parents, "");
+            }
+            continue;
+        }
+        count += 1;
+        let text = if options.oneline {
+            format_commit_oneline(&abbreviate_hash(hash, &hashes), content)
+        } else {
+            format_commit(hash, content)
+        };
+        if options.graph {
+            formatted_result.push_str(&draw_graph(&mut columns, hash, &parents, &text));
+        } else {
+            formatted_result.push_str(&text);
+        }
+    }
+    Ok(formatted_result)
+}
 
-    let current_branch = get_current_branch(directory)?;
+/// Obtiene el hash del commit al que apunta HEAD.
+/// Devuelve `None` si la branch actual todavía no tiene commits.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn get_head_commit(directory: &str) -> Result<Option<String>, CommandsError> {
+    let head_path = format!("{}/{}/{}", directory, GIT_DIR, HEAD);
+    let head = read_file_string(open_file(&head_path)?)?;
+    let head = head.trim();
+    let ref_path = match head.strip_prefix("ref:") {
+        Some(ref_path) => ref_path.trim(),
+        None => return Ok(Some(head.to_string())),
+    };
+    let ref_path = format!("{}/{}/{}", directory, GIT_DIR, ref_path);
+    if !file_exists(&ref_path) {
+        return Ok(None);
+    }
+    let hash = read_file_string(open_file(&ref_path)?)?;
+    Ok(Some(hash.trim().to_string()))
+}
 
-    let commit_file = format!("{}/{}", logs_path, current_branch);
-    if let Ok(file) = File::open(commit_file) {
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader
-            .lines()
-            .map(|line| {
-                line.map_err(|_| CommandsError::ReadFileError)
-                    .unwrap_or_else(|_| String::new())
-            })
-            .collect();
+/// Recorre todos los commits alcanzables desde `head` y los ordena topológicamente:
+/// un commit siempre aparece antes que sus parents y, entre los disponibles, primero el más reciente.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'head': hash del commit desde donde se comienza el recorrido
+pub fn get_commits_topological(
+    directory: &str,
+    head: &str,
+) -> Result<Vec<(String, String)>, CommandsError> {
+    let mut contents: HashMap<String, String> = HashMap::new();
+    let mut parents: HashMap<String, Vec<String>> = HashMap::new();
+    let mut pending = vec![head.to_string()];
+    while let Some(hash) = pending.pop() {
+        if contents.contains_key(&hash) {
+            continue;
+        }
+        let content = git_cat_file(directory, &hash, "-p")?;
+        let commit_parents = Commit::from_object(&content)?.get_parents().to_vec();
+        pending.extend(commit_parents.iter().cloned());
+        parents.insert(hash.clone(), commit_parents);
+        contents.insert(hash, content);
+    }
 
-        formatted_result = get_parts_commit(lines)?;
+    let mut children: HashMap<&str, usize> = HashMap::new();
+    for commit_parents in parents.values() {
+        for parent in commit_parents {
+            *children.entry(parent).or_insert(0) += 1;
+        }
     }
 
-    Ok(formatted_result)
+    let mut commits = Vec::new();
+    let mut ready = vec![head.to_string()];
+    while !ready.is_empty() {
+        let mut index = 0;
+        for (i, hash) in ready.iter().enumerate() {
+            if get_commit_timestamp(&contents[hash])
+                >= get_commit_timestamp(&contents[&ready[index]])
+            {
+                index = i;
+            }
+        }
+        let hash = ready.remove(index);
+        for parent in &parents[&hash] {
+            if let Some(count) = children.get_mut(parent.as_str()) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(parent.to_string());
+                }
+            }
+        }
+        commits.push((hash.clone(), contents[&hash].to_string()));
+    }
+    Ok(commits)
 }
 
-/// Obtiene las partes del commit.
+/// Recorre los commits alcanzables desde `head` del más reciente al más antiguo, como
+/// `git log -n`, y se detiene al llegar a `limit` commits: solo se leen los commits devueltos
+/// y sus parents. Entre commits con la misma fecha, primero se devuelve el que se encontró antes.
 /// ###Parametros:
-/// 'lines': Vector de strings que contiene las lineas del archivo del commit
-/// 'formatted_result': String que contiene el resultado de git log formateado
-pub fn get_parts_commit(lines: Vec<String>) -> Result<String, CommandsError> {
-    let mut formatted_result = String::new();
-
-    for line in lines {
-        if line.len() == 40 {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            formatted_result.push_str(&format!("Commit: {}\n", parts[0]));
-        } else if line.starts_with("author") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            formatted_result.push_str(&format!("Author: {} {}\n", parts[1], parts[2]));
-            let timestamp = match parts[3].parse::<i64>() {
-                Ok(t) => t,
-                Err(_) => return Err(CommandsError::TimeStamp),
-            };
-            let date_time = chrono::DateTime::from_timestamp(timestamp, 0).unwrap();
-            formatted_result.push_str(&format!("Date: {}\n", date_time));
-            formatted_result.push('\n');
-        } else if !line.is_empty()
-            && line.len() != 40
-            && !line.starts_with("parent")
-            && !line.starts_with("author")
-            && !line.starts_with("committer")
-        {
-            formatted_result.push_str(&format!("\t{}\n\n", line));
+/// 'directory': directorio del repositorio local
+/// 'head': hash del commit desde donde se comienza el recorrido
+/// 'limit': cantidad máxima de commits a devolver
+pub fn get_commits_by_date(
+    directory: &str,
+    head: &str,
+    limit: usize,
+) -> Result<Vec<(String, String)>, CommandsError> {
+    let mut commits = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    // Se ordena por fecha y, a igual fecha, por orden de llegada
+    let mut pending: BinaryHeap<(Option<i64>, Reverse<usize>, String, String)> = BinaryHeap::new();
+    let content = git_cat_file(directory, head, "-p")?;
+    seen.insert(head.to_string());
+    pending.push((
+        get_commit_timestamp(&content),
+        Reverse(0),
+        head.to_string(),
+        content,
+    ));
+    while commits.len() < limit {
+        let (_, _, hash, content) = match pending.pop() {
+            Some(commit) => commit,
+            None => break,
+        };
+        if commits.len() + 1 < limit {
+            for parent in Commit::from_object(&content)?.get_parents() {
+                if seen.insert(parent.to_string()) {
+                    let parent_content = git_cat_file(directory, parent, "-p")?;
+                    pending.push((
+                        get_commit_timestamp(&parent_content),
+                        Reverse(seen.len()),
+                        parent.to_string(),
+                        parent_content,
+                    ));
+                }
+            }
         }
+        commits.push((hash, content));
     }
-    Ok(formatted_result)
+    Ok(commits)
 }
 
-/// Inserta una linea en una cadena recibida por parámetro
-///
-/// # Argumentos
-///
-/// - `original_string`: Cadena original a modificar
-/// - `line_number_1`: Numero de linea a modificar
-/// - `new_line`: Linea a agrear
-///
-pub fn insert_line_between_lines(
-    original_string: &str,
-    line_number_1: usize,
-    new_line: &str,
-) -> String {
-    let mut result = String::new();
+/// Obtiene el timestamp de la línea `committer` de un commit (o de la línea `author` si no
+/// tiene committer), o `None` si falta o no es válido.
+/// ###Parametros:
+/// 'content': contenido del commit
+fn get_commit_timestamp(content: &str) -> Option<i64> {
+    let line = content
+        .lines()
+        .find(|line| line.starts_with("committer "))
+        .or_else(|| content.lines().find(|line| line.starts_with("author ")))?;
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    parts[parts.len() - 2].parse::<i64>().ok()
+}
 
-    let lines = original_string.lines();
+/// Obtiene el nombre y el mail del autor del commit con el formato `Nombre <mail>`.
+/// ###Parametros:
+/// 'content': contenido del commit
+fn get_commit_author(content: &str) -> Option<&str> {
+    let line = content.lines().find(|line| line.starts_with("author "))?;
+    let line = line.strip_prefix("author ")?;
+    let end = line.find('>')?;
+    Some(&line[..=end])
+}
 
-    for (index, line) in lines.enumerate() {
-        result.push_str(line);
-        result.push('\n');
-        if index + 1 == line_number_1 {
-            let parent_format = format!("parent {}", new_line);
-            result.push_str(&parent_format);
-            result.push('\n');
-        }
+/// Obtiene las líneas del mensaje del commit.
+/// ###Parametros:
+/// 'content': contenido del commit
+pub fn get_commit_message(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .skip_while(|line| !line.is_empty())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Formatea un commit con su hash, autor, fecha y mensaje.
+/// ###Parametros:
+/// 'hash': hash del commit
+/// 'content': contenido del commit
+/// La fecha es la del autor en su zona horaria; si el commit no tiene un timestamp válido se
+/// muestra como `unknown`.
+fn format_commit(hash: &str, content: &str) -> String {
+    let mut formatted_result = format!("Commit: {}\n", hash);
+    if let Some(author) = get_commit_author(content) {
+        formatted_result.push_str(&format!("Author: {}\n", author));
+    }
+    let date_time = Commit::from_object(content).map_or("unknown".to_string(), |commit| {
+        format_signature_date(commit.get_author_date())
+    });
+    formatted_result.push_str(&format!("Date: {}\n\n", date_time));
+    for line in get_commit_message(content) {
+        formatted_result.push_str(&format!("\t{}\n", line));
     }
+    formatted_result.push('\n');
+    formatted_result
+}
 
-    result
+/// Formatea un commit en una sola línea con el hash abreviado y la primera línea del mensaje.
+/// ###Parametros:
+/// 'hash': hash abreviado del commit
+/// 'content': contenido del commit
+fn format_commit_oneline(hash: &str, content: &str) -> String {
+    let title = get_commit_message(content).first().copied().unwrap_or("");
+    format!("{} {}\n", hash, title)
 }
 
-/// Recorre los parents de los commits y registra en el log de la branch
-///
-/// # Argumentos
-///
-/// - `directory`: Dirección del repositorio
-/// - `commit`: Contenido del objeto commit
-/// - `branch_name`: Nombre de la branch a modificar el log
+/// Dibuja el grafo ASCII de un commit y actualiza las columnas activas del grafo.
 ///
-/// # Returns
+/// Cada columna contiene el hash del próximo commit que se espera en esa línea.
+/// El commit se marca con `*`; los merge commits abren una columna por cada parent
+/// adicional (`\\`) y las columnas cuyo parent ya está en otra columna se cierran (`/`).
 ///
-/// Un `Result` con un retorno `CommandsError` en caso de error.
-///
-pub fn save_parent_log(
-    directory: &str,
-    commit: &str,
-    branch_name: &str,
-    path_log: &str,
-) -> Result<(), CommandsError> {
-    if let Some(parent_hash) = extract_parent_hash(commit) {
-        if parent_hash != PARENT_INITIAL {
-            let mut parent_commit = git_cat_file(directory, parent_hash, "-p")?;
-            if parent_commit.lines().count() == 5 {
-                parent_commit = insert_line_between_lines(&parent_commit, 1, PARENT_INITIAL);
-            }
-            save_parent_log(directory, &parent_commit, branch_name, path_log)?;
-            builder_commit_log(
-                directory,
-                &parent_commit,
-                parent_hash,
-                branch_name,
-                path_log,
-            )?;
+/// ###Parametros:
+/// 'columns': columnas activas del grafo
+/// 'hash': hash del commit a dibujar
+/// 'parents': parents del commit
+/// 'text': texto formateado del commit
+fn draw_graph(columns: &mut Vec<String>, hash: &str, parents: &[&str], text: &str) -> String {
+    let index = match columns.iter().position(|column| column == hash) {
+        Some(index) => index,
+        None => {
+            columns.push(hash.to_string());
+            columns.len() - 1
         }
     };
+    let commit_row: String = columns
+        .iter()
+        .map(|column| if column == hash { "* " } else { "| " })
+        .collect();
+
+    let mut new_parents: Vec<String> = Vec::new();
+    for parent in parents {
+        if !columns.iter().any(|column| column == parent)
+            && !new_parents.iter().any(|p| p == parent)
+        {
+            new_parents.push(parent.to_string());
+        }
+    }
+    let first_parent_tracked = parents
+        .first()
+        .is_some_and(|parent| !new_parents.iter().any(|p| p == parent));
+    let right_columns = columns.len() - index - 1;
+    let opened = new_parents.len().saturating_sub(1);
+    columns.splice(index..=index, new_parents.iter().cloned());
+
+    let mut transition = String::new();
+    if opened > 0 {
+        transition.push_str(&"| ".repeat(index));
+        transition.push_str("|\\");
+        transition.push_str(&" \\".repeat(right_columns));
+    } else if new_parents.is_empty() && (right_columns > 0 || first_parent_tracked) {
+        transition.push_str(&"| ".repeat(index));
+        if first_parent_tracked && index > 0 {
+            transition.pop();
+            transition.push('/');
+        }
+        transition.push_str(&" /".repeat(right_columns));
+    }
+    let padding_row: String = "| ".repeat(columns.len());
 
-    Ok(())
-}
-
-/// Guarda los logs de los commits recibidos del servidor
-///
-/// # Argumentos
-///
-/// - `directory`: Dirección del repositorio
-/// - `branch_name`: Nombre de la branch a modificar el log
-///
-/// # Returns
-///
-/// Un `Result` con un retorno `CommandsError` en caso de error.
-///
-pub fn save_log(
-    directory: &str,
-    branch_name: &str,
-    path_log: &str,
-    path_branch: &str,
-) -> Result<(), CommandsError> {
-    let dir_branch = format!("{}/{}/{}/{}", directory, GIT_DIR, path_branch, branch_name);
-    let file = open_file(&dir_branch)?;
-    let hash_commit = read_file_string(file)?;
-    let mut commit_content = git_cat_file(directory, &hash_commit, "-p")?;
-    if commit_content.lines().count() == 5 {
-        commit_content = insert_line_between_lines(&commit_content, 1, PARENT_INITIAL);
-    }
-    save_parent_log(directory, &commit_content, branch_name, path_log)?;
-    builder_commit_log(
-        directory,
-        &commit_content,
-        &hash_commit,
-        branch_name,
-        path_log,
-    )?;
-
-    Ok(())
+    let mut lines = text.lines();
+    let mut formatted_result = format!("{}{}\n", commit_row, lines.next().unwrap_or_default());
+    if !transition.trim().is_empty() {
+        formatted_result.push_str(&format!("{}\n", transition.trim_end()));
+    }
+    for line in lines {
+        formatted_result.push_str(format!("{}{}", padding_row, line).trim_end());
+        formatted_result.push('\n');
+    }
+    formatted_result
 }
 
 #[cfg(test)]
 mod tests {
     use crate::commands::add::git_add;
-    use crate::commands::commit::{git_commit, Commit};
+    use crate::commands::commit::git_commit;
     use crate::commands::init::git_init;
     use crate::util::files::create_file;
 
     use super::*;
+    use crate::test_utils::test_commit;
     use std::fs;
 
     #[test]
@@ -206,13 +473,7 @@ mod tests {
 
         git_add(directory, "test.txt").expect("Falló al agregar el primer archivo");
 
-        let test_commit1 = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba");
 
         git_commit(directory, test_commit1).expect("Falló al hacer el primer commit");
 
@@ -221,20 +482,127 @@ mod tests {
 
         git_add(directory, "test2.txt").expect("Falló al agregar el segundo archivo");
 
-        let test_commit1 = Commit::new(
-            "prueba2".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba2");
 
         git_commit(directory, test_commit1).expect("Falló al hacer el segundo commit");
 
-        let result = git_log(directory);
+        let result = git_log(directory, &LogOptions::default());
 
         assert!(result.is_ok());
 
         fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
     }
+
+    #[test]
+    fn test_log_options_from_args() {
+        let options = LogOptions::new_from_args(&["--oneline", "--graph", "-n", "3"])
+            .expect("Falló al leer las opciones");
+        assert_eq!(
+            options,
+            LogOptions {
+                oneline: true,
+                graph: true,
+                max_count: Some(3),
+                ..Default::default()
+            }
+        );
+        let options =
+            LogOptions::new_from_args(&["--max-count=2"]).expect("Falló al leer las opciones");
+        assert_eq!(options.max_count, Some(2));
+        assert!(LogOptions::new_from_args(&["--max-count"]).is_err());
+        assert!(LogOptions::new_from_args(&["--desconocida"]).is_err());
+        let options =
+            LogOptions::new_from_args(&["HEAD~1", "-n", "1"]).expect("Falló al leer las opciones");
+        assert_eq!(options.revision, Some("HEAD~1".to_string()));
+    }
+
+    #[test]
+    fn test_git_log_oneline_max_count() {
+        let directory = "./test_log_oneline";
+        git_init(directory).expect("Falló al crear el repositorio");
+
+        for (file, message) in [("a.txt", "primero"), ("b.txt", "segundo")] {
+            create_file(&format!("{}/{}", directory, file), file).expect("Falló al crear");
+            git_add(directory, file).expect("Falló al agregar el archivo");
+            let commit = test_commit(message);
+            git_commit(directory, commit).expect("Falló al hacer el commit");
+        }
+
+        let options = LogOptions {
+            oneline: true,
+            ..Default::default()
+        };
+        let result = git_log(directory, &options).expect("Falló el log");
+        let options = LogOptions {
+            oneline: true,
+            max_count: Some(1),
+            ..Default::default()
+        };
+        let result_max = git_log(directory, &options).expect("Falló el log");
+        let options = LogOptions {
+            oneline: true,
+            revision: Some("HEAD~1".to_string()),
+            ..Default::default()
+        };
+        let result_revision = git_log(directory, &options).expect("Falló el log");
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" segundo"));
+        assert!(lines[1].ends_with(" primero"));
+        assert_eq!(lines[0].split(' ').next().unwrap_or_default().len(), 7);
+        assert_eq!(result_max.lines().count(), 1);
+        assert_eq!(
+            result_revision.lines().collect::<Vec<&str>>(),
+            vec![lines[1]]
+        );
+    }
+
+    #[test]
+    fn test_log_options_filters() {
+        let options = LogOptions::new_from_args(&[
+            "--author=Valen",
+            "--since=2024-01-01",
+            "--until=2024-01-31",
+        ])
+        .expect("Falló al leer las opciones");
+        assert_eq!(options.since, Some(1704067200));
+        assert_eq!(options.until, Some(1706745599));
+
+        let commit = |author: &str, timestamp: &str| {
+            format!(
+                "tree t\nauthor {} <mail> {} -0300\ncommitter {} <mail> {} -0300\n\nmensaje\n",
+                author, timestamp, author, timestamp
+            )
+        };
+        assert!(options.matches(&commit("Valen", "1705000000")));
+        assert!(!options.matches(&commit("Juan", "1705000000")));
+        assert!(!options.matches(&commit("Valen", "1600000000")));
+        assert!(!options.matches(&commit("Valen", "1800000000")));
+        assert!(!options.matches(&commit("Valen", "fecha")));
+        assert!(LogOptions::new_from_args(&["--since=ayer"]).is_err());
+    }
+
+    #[test]
+    fn test_format_commit_invalid_timestamp() {
+        let content = "tree t\nauthor Valen <mail> 99999999999999999 -0300\n\nmensaje\n";
+        let result = format_commit("hash", content);
+        assert!(result.contains("Date: unknown"));
+    }
+
+    #[test]
+    fn test_draw_graph_merge_commit() {
+        let mut columns = Vec::new();
+        let merge = draw_graph(&mut columns, "m", &["a", "b"], "m merge\n");
+        let first = draw_graph(&mut columns, "a", &["base"], "a primero\n");
+        let second = draw_graph(&mut columns, "b", &["base"], "b segundo\n");
+        let base = draw_graph(&mut columns, "base", &[], "base inicial\n");
+
+        assert_eq!(merge, "* m merge\n|\\\n");
+        assert_eq!(first, "* | a primero\n");
+        assert_eq!(second, "| * b segundo\n|/\n");
+        assert_eq!(base, "* base inicial\n");
+        assert!(columns.is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 use super::errors::CommandsError;
 use crate::models::client::Client;
+use crate::util::objects::parse_tree_line;
 
 use super::status::{
     check_for_deleted_files, compare_hash_lists, get_hashes_index, get_hashes_working_directory,
@@ -37,8 +38,9 @@ pub fn git_ls_files(directory: &str, flag: &str) -> Result<String, CommandsError
     if flag.is_empty() || flag == "-c" {
         let lines_index: Vec<String> = index_content.lines().map(String::from).collect();
         for line in lines_index {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            formatted_result.push_str(&format!("{}\n", parts[0]));
+            let (_, name, _) = parse_tree_line(&line)
+                .ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+            formatted_result.push_str(&format!("{}\n", name));
         }
     }
     if flag == "-d" {
@@ -0,0 +1,153 @@
+use super::errors::CommandsError;
+use crate::commands::config::GitConfig;
+use crate::consts::ORIGIN;
+use crate::git_transport::git_request::GitRequest;
+use crate::git_transport::references::{reference_discovery, Reference, ReferenceType};
+use crate::git_transport::request_command::RequestCommand;
+use crate::models::client::Client;
+use crate::util::connections::{send_flush, start_client};
+use crate::util::errors::UtilError;
+use std::net::TcpStream;
+
+/// Maneja la ejecución del comando "ls-remote" en el cliente Git.
+///
+/// Solo se aceptaran los comandos que tengan la siguiente estructura:
+///
+/// * `git ls-remote [--heads] [--tags] [<repositorio>]`
+///
+/// Si no se indica el repositorio se utiliza la URL del remoto `origin`.
+///
+/// # Argumentos
+///
+/// * `args`: Flags y repositorio remoto pasados al comando.
+/// * `client`: Un objeto `Client` que representa la configuración del cliente Git.
+///
+/// # Devoluciones
+///
+/// Devuelve un `Result` con las referencias del servidor o un error (CommandsError).
+///
+pub fn handle_ls_remote(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let mut heads = false;
+    let mut tags = false;
+    let mut url = None;
+    for arg in args {
+        match arg {
+            "--heads" => heads = true,
+            "--tags" => tags = true,
+            _ if url.is_none() && !arg.starts_with('-') => url = Some(arg.to_string()),
+            _ => return Err(CommandsError::InvalidArgumentCountLsRemoteError),
+        }
+    }
+    let url = match url {
+        Some(url) => url,
+        None => {
+            GitConfig::new_from_file(client.get_directory_path())?.get_remote_url_by_name(ORIGIN)?
+        }
+    };
+    let mut socket = start_client(client.get_address())?;
+    git_ls_remote(
+        &mut socket,
+        client.get_ip(),
+        client.get_port(),
+        &url,
+        heads,
+        tags,
+    )
+}
+
+/// Lista las referencias de un repositorio remoto sin clonarlo.
+///
+/// Solo se realiza la fase de descubrimiento de referencias: se envía la solicitud
+/// `git-upload-pack`, se leen las referencias anunciadas y se cierra la conexión con
+/// un flush sin negociar un packfile.
+///
+/// # Argumentos
+///
+/// - `socket`: Conexión con el servidor.
+/// - `ip`: La dirección IP del servidor Git.
+/// - `port`: El número de puerto utilizado para la conexión.
+/// - `url`: El repositorio remoto a consultar.
+/// - `heads`: Si es `true` se muestran las branches.
+/// - `tags`: Si es `true` se muestran las tags.
+///
+/// Si `heads` y `tags` son `false` se muestran todas las referencias.
+///
+/// # Returns
+///
+/// Un `Result` con una línea `<hash>\t<refname>` por cada referencia o un error `CommandsError`.
+///
+pub fn git_ls_remote(
+    socket: &mut TcpStream,
+    ip: &str,
+    port: &str,
+    url: &str,
+    heads: bool,
+    tags: bool,
+) -> Result<String, CommandsError> {
+    let message = GitRequest::generate_request_string(RequestCommand::UploadPack, url, ip, port);
+    let git_server = reference_discovery(socket, message, url, &Vec::new())?;
+    send_flush(socket, UtilError::CloseConnection)?;
+
+    Ok(format_references(git_server.get_references(), heads, tags))
+}
+
+/// Formatea las referencias anunciadas aplicando los filtros `--heads` y `--tags`.
+///
+/// # Argumentos
+///
+/// - `references`: Referencias anunciadas por el servidor.
+/// - `heads`: Si es `true` se incluyen las branches.
+/// - `tags`: Si es `true` se incluyen las tags.
+///
+fn format_references(references: &[Reference], heads: bool, tags: bool) -> String {
+    let mut formatted_result = String::new();
+    for reference in references {
+        let show = match reference.get_type() {
+            ReferenceType::Branch => heads || !tags,
+            ReferenceType::Tag => tags || !heads,
+            _ => !heads && !tags,
+        };
+        if show {
+            formatted_result.push_str(&format!(
+                "{}\t{}\n",
+                reference.get_hash(),
+                reference.get_ref_path()
+            ));
+        }
+    }
+    formatted_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references() -> Vec<Reference> {
+        vec![
+            Reference::new("hash1", "HEAD").unwrap(),
+            Reference::new("hash2", "refs/heads/master").unwrap(),
+            Reference::new("hash3", "refs/tags/v1").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_format_references_without_filter() {
+        let result = format_references(&references(), false, false);
+        assert_eq!(
+            result,
+            "hash1\tHEAD\nhash2\trefs/heads/master\nhash3\trefs/tags/v1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_references_heads() {
+        let result = format_references(&references(), true, false);
+        assert_eq!(result, "hash2\trefs/heads/master\n");
+    }
+
+    #[test]
+    fn test_format_references_tags() {
+        let result = format_references(&references(), false, true);
+        assert_eq!(result, "hash3\trefs/tags/v1\n");
+    }
+}
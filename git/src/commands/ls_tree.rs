@@ -1,98 +1,141 @@
 use super::errors::CommandsError;
-use crate::consts::{BLOB, COMMIT, TAG};
+use super::rev_parse::git_rev_parse;
+use crate::commands::checkout::get_tree_hash;
+use crate::consts::{BLOB, COMMIT, DIRECTORY, SUBMODULE, TREE};
+use crate::git_transport::references::peel_tag;
 use crate::models::client::Client;
-use crate::util::files::{open_file, read_file_string};
-use std::fs;
+use crate::util::objects::parse_tree_line;
 
 use super::cat_file::git_cat_file;
 
 /// Esta función se encarga de llamar a al comando ls-tree con los parametros necesarios
+///
+/// * `git ls-tree [-r] [-d] <tree-ish>`: `-r` recorre los sub-trees y `-d` muestra solo los trees.
+///
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función ls-tree
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_ls_tree(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if args.len() > 1 || args.is_empty() {
-        return Err(CommandsError::InvalidArgumentCountLsTreeError);
+    let mut recursive = false;
+    let mut only_trees = false;
+    let mut tree_ish = None;
+    for arg in args {
+        match arg {
+            "-r" => recursive = true,
+            "-d" => only_trees = true,
+            _ if tree_ish.is_none() && !arg.starts_with('-') => tree_ish = Some(arg),
+            _ => return Err(CommandsError::InvalidArgumentCountLsTreeError),
+        }
     }
+    let tree_ish = tree_ish.ok_or(CommandsError::InvalidArgumentCountLsTreeError)?;
     let directory = client.get_directory_path();
-    git_ls_tree(directory, args[0])
+    git_ls_tree(directory, tree_ish, recursive, only_trees)
 }
 
-/// Lista el contenido de un arbol pasado por parametro como tree-ish.
+/// Lista el contenido de un arbol pasado por parametro como tree-ish, una entrada por línea con
+/// el formato `<modo> <tipo> <hash>\t<path>`.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
-/// 'tree_ish': un tree hash, un commit hash o un path a una branch que contiene un commit hash..
-pub fn git_ls_tree(directory: &str, tree_ish: &str) -> Result<String, CommandsError> {
-    let mut tree_hash = tree_ish.to_string();
-    let directory_tree = format!("{}/.git/{}", directory, tree_ish);
-    if fs::metadata(&directory_tree).is_ok() {
-        tree_hash = associated_commit(directory, &directory_tree)?;
-    }
-    if git_cat_file(directory, &tree_hash, "-t")? == BLOB
-        || git_cat_file(directory, &tree_hash, "-t")? == TAG
-    {
-        return Err(CommandsError::InvalidTreeHashError);
-    }
-    if git_cat_file(directory, &tree_hash, "-t")? == COMMIT {
-        tree_hash = associated_tree(directory, tree_hash)?;
-    }
-
+/// 'tree_ish': un tree, o cualquier revisión que apunte a un commit (hash, branch, tag o HEAD).
+/// 'recursive': si es true se recorren los sub-trees, con sus paths desde la raíz del tree.
+/// 'only_trees': si es true solo se muestran las entradas que son trees.
+pub fn git_ls_tree(
+    directory: &str,
+    tree_ish: &str,
+    recursive: bool,
+    only_trees: bool,
+) -> Result<String, CommandsError> {
+    let tree_hash = resolve_tree(directory, tree_ish)?;
     let mut formatted_result = String::new();
-    let content_tree = git_cat_file(directory, &tree_hash, "-p")?;
-    formatted_result.push_str(content_tree.as_str());
-
+    list_tree(
+        directory,
+        &tree_hash,
+        "",
+        recursive,
+        only_trees,
+        &mut formatted_result,
+    )?;
     Ok(formatted_result)
 }
 
-/// Obtiene el commit asociado a un path a una branch o a HEAD.
+/// Obtiene el hash del tree al que apunta un tree-ish, pelando las tags y pasando de un commit
+/// a su tree.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
-/// 'path_to_commit': un path a una branch (o a HEAD) que contiene un commit hash.
-fn associated_commit(directory: &str, path_to_commit: &str) -> Result<String, CommandsError> {
-    let path = open_file(path_to_commit)?;
-    let content = read_file_string(path)?;
-    if path_to_commit.contains("HEAD") {
-        let tree = get_head_tree(directory, content)?;
-        return Ok(tree);
-    }
-    if content.len() != 40 {
-        return Err(CommandsError::InvalidTreeHashError);
+/// 'tree_ish': revisión que apunta a un tree o a un commit.
+fn resolve_tree(directory: &str, tree_ish: &str) -> Result<String, CommandsError> {
+    let hash = peel_tag(directory, &git_rev_parse(directory, tree_ish)?)?;
+    match git_cat_file(directory, &hash, "-t")?.as_str() {
+        TREE => Ok(hash),
+        COMMIT => {
+            let content_commit = git_cat_file(directory, &hash, "-p")?;
+            match get_tree_hash(&content_commit) {
+                Some(tree_hash) => Ok(tree_hash.to_string()),
+                None => Err(CommandsError::InvalidTreeHashError),
+            }
+        }
+        _ => Err(CommandsError::InvalidTreeHashError),
     }
-    let tree = associated_tree(directory, content)?;
-
-    Ok(tree)
-}
-
-/// Obtiene el tree asociado a un commit hash.
-/// ###Parametros:
-/// 'directory': directorio del repositorio local.
-/// 'content': commit hash.
-fn associated_tree(directory: &str, content: String) -> Result<String, CommandsError> {
-    let content_commit = git_cat_file(directory, &content, "-p")?;
-    let parts: Vec<&str> = content_commit.split_whitespace().collect();
-    let tree = parts[1];
-    Ok(tree.to_string())
 }
 
-/// Obtiene el tree asociado a HEAD.
+/// Agrega al resultado las entradas de un tree, recorriendo los sub-trees si se pidió.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
-/// 'content': contenido del archivo HEAD.
-fn get_head_tree(directory: &str, content: String) -> Result<String, CommandsError> {
-    let path_branch = content.split_whitespace().collect::<Vec<&str>>()[1];
-    let path_branch = format!("{}/.git/{}", directory, path_branch);
-    let branch = open_file(&path_branch)?;
-    let commit_branch = read_file_string(branch)?;
-    let tree = associated_tree(directory, commit_branch)?;
-    Ok(tree)
+/// 'tree_hash': hash del tree a listar.
+/// 'prefix': path del tree desde la raíz, vacío para la raíz.
+/// 'recursive': si es true se recorren los sub-trees.
+/// 'only_trees': si es true solo se agregan los trees.
+/// 'formatted_result': resultado al que se agregan las líneas.
+fn list_tree(
+    directory: &str,
+    tree_hash: &str,
+    prefix: &str,
+    recursive: bool,
+    only_trees: bool,
+    formatted_result: &mut String,
+) -> Result<(), CommandsError> {
+    let content_tree = git_cat_file(directory, tree_hash, "-p")?;
+    for tree_line in content_tree.lines().filter(|line| !line.is_empty()) {
+        let (mode, name, hash) = parse_tree_line(tree_line)
+            .ok_or(CommandsError::MalformedTreeEntry(tree_line.to_string()))?;
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let object_type = match mode {
+            DIRECTORY => TREE,
+            SUBMODULE => COMMIT,
+            _ => BLOB,
+        };
+        // Con -r los trees no se muestran, salvo que se pida -d
+        let is_tree = object_type == TREE;
+        if (!is_tree && !only_trees) || (is_tree && (only_trees || !recursive)) {
+            formatted_result.push_str(&format!(
+                "{:0>6} {} {}\t{}\n",
+                mode, object_type, hash, path
+            ));
+        }
+        if is_tree && recursive {
+            list_tree(
+                directory,
+                hash,
+                &path,
+                recursive,
+                only_trees,
+                formatted_result,
+            )?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::commit::Commit;
     use crate::commands::init::git_init;
     use crate::commands::{add::git_add, commit::git_commit};
+    use crate::test_utils::test_commit;
     use std::fs;
     use std::io::Write;
 
@@ -115,33 +158,79 @@ mod tests {
         git_add(directory, "file1.rs").expect("Error al agregar el archivo");
         git_add(directory, "file2.rs").expect("Error al agregar el archivo");
 
-        let test_commit1 = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba");
 
         git_commit(directory, test_commit1).expect("Error al ejecutar el comando");
 
         // para obtener el tree del commit y ver si funciona ls-tree
         let branch_path = format!("{}/.git/refs/heads/master", directory);
-        let branch = open_file(&branch_path).expect("Error al abrir el archivo");
-        let commit_hash = read_file_string(branch).expect("Error al leer el archivo");
-        let tree_hash = associated_tree(directory, commit_hash).expect("Error al obtener el tree");
-        let result_tree = git_ls_tree(directory, &tree_hash);
+        let commit_hash = fs::read_to_string(branch_path).expect("Error al leer el archivo");
+        let content_commit =
+            git_cat_file(directory, commit_hash.trim(), "-p").expect("Error al leer el commit");
+        let tree_hash = get_tree_hash(&content_commit).expect("Error al obtener el tree");
+        let result_tree = git_ls_tree(directory, tree_hash, false, false);
 
         // para obtener el tree asociado al HEAD y ver si funciona ls-tree
-        let result_head = git_ls_tree(directory, "HEAD");
+        let result_head = git_ls_tree(directory, "HEAD", false, false);
 
         // para obtener el tree asociado a una branch (master) y ver si funciona ls-tree
-        let result_master = git_ls_tree(directory, "refs/heads/master");
+        let result_master = git_ls_tree(directory, "refs/heads/master", false, false);
 
         fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
 
         assert!(result_tree.is_ok());
         assert!(result_head.is_ok());
         assert!(result_master.is_ok());
+        assert_eq!(result_head, result_tree);
+    }
+
+    #[test]
+    fn test_git_ls_tree_recursive() {
+        let directory = "./test_ls_tree_recursive";
+        git_init(directory).expect("Error al crear el repositorio");
+        fs::create_dir_all(format!("{}/src/util", directory))
+            .expect("Error al crear el directorio");
+        for file in ["README.md", "src/main.rs", "src/util/files.rs"] {
+            fs::write(format!("{}/{}", directory, file), file).expect("Error al escribir");
+            git_add(directory, file).expect("Error al agregar el archivo");
+        }
+        let test_commit = test_commit("prueba");
+        git_commit(directory, test_commit).expect("Error al ejecutar el comando");
+
+        let paths = |result: &str| -> Vec<(String, String)> {
+            result
+                .lines()
+                .map(|line| {
+                    let (info, path) = line.split_once('\t').expect("Falta el path");
+                    let object_type = info.split(' ').nth(1).expect("Falta el tipo");
+                    (object_type.to_string(), path.to_string())
+                })
+                .collect()
+        };
+        let top = git_ls_tree(directory, "master", false, false).expect("Error en ls-tree");
+        let recursive = git_ls_tree(directory, "HEAD", true, false).expect("Error en ls-tree");
+        let trees = git_ls_tree(directory, "HEAD", true, true).expect("Error en ls-tree");
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+
+        let pair = |object_type: &str, path: &str| (object_type.to_string(), path.to_string());
+        assert!(top.lines().any(|line| line.starts_with("040000 tree ")));
+        assert!(top.lines().any(|line| line.starts_with("100644 blob ")));
+        let mut top = paths(&top);
+        top.sort();
+        assert_eq!(top, vec![pair("blob", "README.md"), pair("tree", "src")]);
+        let mut recursive = paths(&recursive);
+        recursive.sort();
+        assert_eq!(
+            recursive,
+            vec![
+                pair("blob", "README.md"),
+                pair("blob", "src/main.rs"),
+                pair("blob", "src/util/files.rs"),
+            ]
+        );
+        let mut trees = paths(&trees);
+        trees.sort();
+        assert_eq!(trees, vec![pair("tree", "src"), pair("tree", "src/util")]);
     }
 }
@@ -1,49 +1,124 @@
-use super::add::add_to_index;
+use super::add::add_to_index_with_mode;
 use super::branch::{get_branch_current_hash, get_current_branch};
-use super::cat_file::git_cat_file;
+use super::cat_file::{git_cat_file, git_cat_file_typed};
 use super::checkout::extract_parent_hash;
-use super::commit::{get_commits, merge_commit, Commit};
+use super::commit::{merge_commit, Commit};
+use super::config::GitConfig;
 use super::errors::CommandsError;
+use super::fetch_head::FetchHead;
+use super::merge_base::get_merge_bases;
+use super::reflog::append_branch_reflog;
 use crate::commands::checkout::get_tree_hash;
 use crate::commands::rm::remove_from_index;
-use crate::consts::{DIRECTORY, FILE, GIT_DIR, PARENT_INITIAL, REFS_HEADS};
+use crate::consts::{
+    COMMIT, CONFLICT_STYLE_DIFF3, CO_AUTHORED_BY, DIRECTORY, FETCH_HEAD, GIT_DIR,
+    MERGE_CONFLICT_STYLE_KEY, PARENT_INITIAL, PR_MERGE_MESSAGE_TEMPLATE, REFS_HEADS, TREE,
+};
 use crate::models::client::Client;
-use crate::util::files::{create_file_replace, open_file, read_file_string};
+use crate::util::files::{
+    create_file_replace, get_git_dir, open_file, read_file_string, write_file_with_mode,
+};
+use crate::util::index::{open_index, parse_index_line};
+use crate::util::objects::{is_blob_mode, parse_tree_line};
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::io::{self, BufRead};
 
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
 struct FileEntry {
     path: String,
     hash: String,
+    mode: String,
+}
+
+/// Estilo de las marcas que se escriben en los archivos en conflicto, configurado en
+/// `merge.conflictStyle`.
+///
+/// - `Merge`: solo se muestran las líneas de las dos ramas.
+/// - `Diff3`: además se muestran las líneas del ancestro común, entre `|||||||` e `=======`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStyle {
+    #[default]
+    Merge,
+    Diff3,
+}
+
+impl ConflictStyle {
+    /// Lee el estilo configurado en el repositorio. Si no está configurado, o el valor no es
+    /// válido, se usa el estilo `merge`.
+    pub fn from_config(directory: &str) -> ConflictStyle {
+        let git_config = match GitConfig::new_from_file(directory) {
+            Ok(git_config) => git_config,
+            Err(_) => return ConflictStyle::Merge,
+        };
+        match git_config.get_value("merge", MERGE_CONFLICT_STYLE_KEY) {
+            Some(style) if style.eq_ignore_ascii_case(CONFLICT_STYLE_DIFF3) => ConflictStyle::Diff3,
+            _ => ConflictStyle::Merge,
+        }
+    }
 }
 
 /// Esta función se encarga de llamar al comando merge con los parametros necesarios.
+///
+/// * `git merge`: mergea el commit de FETCH_HEAD que dejó el último fetch.
+/// * `git merge <branch>`: mergea la branch en la actual, con fast forward si es posible.
+/// * `git merge --no-ff <branch>`: crea siempre un merge commit, aunque sea posible un fast forward.
+/// * `git merge --squash <branch>`: deja los cambios de la branch en el index, sin commitear.
+/// * `git merge [--no-ff] [<branch>] -m <mensaje>`: usa el mensaje indicado para el merge commit.
+///   Sin `-m` el mensaje es `Merge branch '<branch>' into '<actual>'`.
+///
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función merge
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_merge(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if args.len() != 1 {
-        return Err(CommandsError::InvalidArgumentCountMergeError);
-    }
     let directory = client.get_directory_path();
-    let branch_name = args[0];
     let current_branch = get_current_branch(directory)?;
-    git_merge(directory, &current_branch, branch_name, client.clone())
+    // `-m` toma exactamente el argumento siguiente; el resto se interpreta como en un merge sin mensaje
+    let (args, message) = match args.iter().position(|arg| *arg == "-m") {
+        Some(position) => match args.get(position + 1) {
+            Some(message) => {
+                let mut rest = args[..position].to_vec();
+                rest.extend_from_slice(&args[position + 2..]);
+                (rest, Some(*message))
+            }
+            None => return Err(CommandsError::InvalidArgumentCountMergeError),
+        },
+        None => (args, None),
+    };
+    let (branch_name, no_ff) = match args.as_slice() {
+        [] => return git_merge_fetch_head(directory, &current_branch, client.clone(), message),
+        [branch_name] => (*branch_name, false),
+        ["--no-ff", branch_name] => (*branch_name, true),
+        ["--squash", branch_name] if message.is_none() => {
+            return git_merge_squash(directory, &current_branch, branch_name)
+        }
+        _ => return Err(CommandsError::InvalidArgumentCountMergeError),
+    };
+    git_merge(
+        directory,
+        &current_branch,
+        branch_name,
+        client.clone(),
+        no_ff,
+        message,
+    )
 }
 
 /// Ejecuta la accion de merge en el repositorio local.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
 /// 'merge_branch': nombre de la rama a mergear
+/// 'no_ff': si es true, se crea un merge commit aunque sea posible un fast forward
+/// 'message': mensaje del merge commit; si no se indica se usa `default_merge_message`
 pub fn git_merge(
     directory: &str,
     current_branch: &str,
     merge_branch: &str,
     client: Client,
+    no_ff: bool,
+    message: Option<&str>,
 ) -> Result<String, CommandsError> {
-    let (result_merge, strategy) = perform_merge(current_branch, merge_branch, directory, "merge")?;
+    let (result_merge, strategy, _) =
+        perform_merge(current_branch, merge_branch, directory, "merge", no_ff)?;
 
     if result_merge.contains("up to date") {
         return Ok(result_merge);
@@ -56,21 +131,16 @@ pub fn git_merge(
     let branch_to_merge_hash = get_branch_hash(&path_branch_to_merge)?;
 
     if !result_merge.contains("CONFLICT") {
-        update_logs_refs(
-            directory,
-            strategy.clone(),
-            current_branch,
-            merge_branch,
-            &current_branch_hash,
-            &branch_to_merge_hash,
-        )?;
+        let message = match message {
+            Some(message) => message.to_string(),
+            None => default_merge_message(current_branch, merge_branch),
+        };
         update_refs(
             directory,
             strategy,
-            current_branch,
-            merge_branch,
-            &current_branch_hash,
-            &branch_to_merge_hash,
+            (current_branch, &current_branch_hash),
+            (merge_branch, &branch_to_merge_hash),
+            &message,
             client.clone(),
         )?;
     }
@@ -78,18 +148,74 @@ pub fn git_merge(
     Ok(result_merge)
 }
 
+/// Mergea en la branch actual el commit de FETCH_HEAD que no está marcado como "not-for-merge",
+/// es decir, lo que trajo el último fetch.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'current_branch': nombre de la rama actual
+/// 'client': Cliente que contiene la información del cliente que se conectó
+/// 'message': mensaje del merge commit; si no se indica se usa `default_merge_message`
+pub fn git_merge_fetch_head(
+    directory: &str,
+    current_branch: &str,
+    client: Client,
+    message: Option<&str>,
+) -> Result<String, CommandsError> {
+    // Se valida que haya un commit para mergear; FETCH_HEAD se resuelve con su primera línea
+    FetchHead::new_from_file(directory)?.get_hash_to_merge()?;
+    git_merge(
+        directory,
+        current_branch,
+        FETCH_HEAD,
+        client,
+        false,
+        message,
+    )
+}
+
+/// Devuelve el mensaje por defecto del merge commit, como en git:
+/// `Merge branch '<merge_branch>' into '<current_branch>'`.
+/// ###Parametros:
+/// 'current_branch': nombre de la rama actual
+/// 'merge_branch': nombre de la rama a mergear
+pub fn default_merge_message(current_branch: &str, merge_branch: &str) -> String {
+    format!("Merge branch '{}' into '{}'", merge_branch, current_branch)
+}
+
+/// Aplica los cambios de la branch a mergear en el working directory y en el index, pero no
+/// crea el commit ni mueve la branch actual: los cambios quedan listos para commitearse.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'current_branch': nombre de la rama actual
+/// 'merge_branch': nombre de la rama a mergear
+pub fn git_merge_squash(
+    directory: &str,
+    current_branch: &str,
+    merge_branch: &str,
+) -> Result<String, CommandsError> {
+    let (result_merge, _, _) =
+        perform_merge(current_branch, merge_branch, directory, "squash", false)?;
+    Ok(result_merge)
+}
+
 /// Chequea que estrategia se debe utilizar para el merge y procede a realizarlo.
 /// ###Parametros:
 /// 'current_branch': nombre de la rama actual
 /// 'merge_branch': nombre de la rama a mergear
 /// 'directory': directorio del repositorio local
 /// 'merge_type': tipo de merge a realizar
+/// 'no_ff': si es true y es posible un fast forward, se actualizan los archivos igual que en el
+/// fast forward pero se devuelve la estrategia de tres vías para que se cree un merge commit
+///
+/// Devuelve el resultado del merge, la estrategia utilizada y los paths de los archivos en
+/// conflicto, ordenados (vacío si no hubo conflictos).
 pub fn perform_merge(
     current_branch: &str,
     merge_branch: &str,
     directory: &str,
     merge_type: &str,
-) -> Result<(String, String), CommandsError> {
+    no_ff: bool,
+) -> Result<(String, String, Vec<String>), CommandsError> {
     if is_same_branch(current_branch, merge_branch) {
         return Err(CommandsError::IsSameBranch);
     }
@@ -97,118 +223,168 @@ pub fn perform_merge(
     let mut result_merge = String::new();
     let common_ancestor = find_commit_common_ancestor(directory, current_branch, merge_branch)?;
     if is_up_to_date(directory, current_branch, merge_branch, &common_ancestor)? {
-        return Ok(("Already up to date.".to_string(), "".to_string()));
+        return Ok((
+            "Already up to date.".to_string(),
+            "".to_string(),
+            Vec::new(),
+        ));
     }
     let path_current_branch = get_refs_path(directory, current_branch);
     let path_branch_to_merge = get_refs_path(directory, merge_branch);
     let current_branch_hash = get_branch_hash(&path_current_branch)?;
     let branch_to_merge_hash = get_branch_hash(&path_branch_to_merge)?;
 
-    let strategy = get_merge_strategy(common_ancestor, current_branch_hash.clone())?;
+    let mut strategy = get_merge_strategy(common_ancestor, current_branch_hash.clone())?;
     if strategy == "Fast Forward" {
         let merge_tree = fast_forward(directory, merge_branch)?;
         if is_head {
             for file in merge_tree.iter() {
                 let content_file = git_cat_file(directory, &file.hash, "-p")?;
                 let full_path = format!("{}/{}", directory, file.path);
-                create_file_replace(&full_path, &content_file)?;
-                add_to_index(
+                write_file_with_mode(&full_path, &content_file, &file.mode)?;
+                add_to_index_with_mode(
                     format!("{}/{}", directory, GIT_DIR),
                     &file.path,
                     file.hash.clone(),
+                    &file.mode,
                 )?;
             }
         }
-        get_result_fast_forward(
-            &mut result_merge,
-            current_branch_hash.clone(),
-            branch_to_merge_hash.clone(),
-        );
+        if merge_type == "squash" {
+            get_result_fast_forward(
+                &mut result_merge,
+                current_branch_hash.clone(),
+                branch_to_merge_hash.clone(),
+            );
+            result_merge.push_str("Squash commit -- not updating HEAD\n");
+        } else if no_ff {
+            strategy = "Three Way".to_string();
+            result_merge.push_str("Merge made by the 'recursive' strategy.");
+        } else {
+            get_result_fast_forward(
+                &mut result_merge,
+                current_branch_hash.clone(),
+                branch_to_merge_hash.clone(),
+            );
+        }
     } else {
-        let merge_tree = three_way_merge(directory, current_branch, merge_branch, merge_type)?;
+        let (merge_tree, conflicts) =
+            three_way_merge(directory, current_branch, merge_branch, merge_type)?;
 
-        if merge_type == "pr" {
-            if let Some((path, _)) = merge_tree.iter().find(|(_, status)| *status == "CONFLICT") {
-                get_result_conflict(&mut result_merge, path);
-                return Ok((result_merge, strategy));
-            }
+        if merge_type == "pr" && !conflicts.is_empty() {
+            get_result_conflict(&mut result_merge, &conflicts);
+            return Ok((result_merge, strategy, conflicts));
         }
 
         for (file, status) in merge_tree.iter() {
             if status == "CONFLICT" {
-                get_result_conflict(&mut result_merge, file);
-                return Ok((result_merge, strategy));
+                get_result_conflict(&mut result_merge, &conflicts);
+                return Ok((result_merge, strategy, conflicts));
             } else if is_head {
                 let content_file = git_cat_file(directory, &file.hash, "-p")?;
                 let full_path = format!("{}/{}", directory, file.path);
-                create_file_replace(&full_path, &content_file)?;
-                add_to_index(
+                write_file_with_mode(&full_path, &content_file, &file.mode)?;
+                add_to_index_with_mode(
                     format!("{}/{}", directory, GIT_DIR),
                     &file.path,
                     file.hash.clone(),
+                    &file.mode,
                 )?;
             }
         }
-        result_merge.push_str("Merge made by the 'recursive' strategy.");
+        if merge_type == "squash" {
+            result_merge.push_str("Squash commit -- not updating HEAD\n");
+            result_merge
+                .push_str("Automatic merge went well; stopped before committing as requested\n");
+        } else {
+            result_merge.push_str("Merge made by the 'recursive' strategy.");
+        }
     }
 
     if is_head {
         update_work_directory(directory, &branch_to_merge_hash, &mut result_merge)?;
     }
 
-    Ok((result_merge, strategy))
+    Ok((result_merge, strategy, Vec::new()))
 }
 
-/// Esta función realiza un merge de una PR.
+/// Analiza el merge de una rama sobre otra sin modificar el working directory, el index ni las
+/// referencias.
+///
+/// Devuelve la estrategia que correspondería ("Fast Forward" o "Three Way", vacía si la rama
+/// actual ya contiene a la rama a mergear) y los paths de los archivos en conflicto, ordenados.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
-/// 'base_branch': nombre de la rama base
-/// 'head_branch': nombre de la rama a mergear
-/// 'owner': nombre del dueño de la PR
-/// 'title': título de la PR
-/// 'pr_number': número de la PR
-/// 'repo_name': nombre del repositorio
-pub fn merge_pr(
+/// 'current_branch': nombre de la rama actual
+/// 'merge_branch': nombre de la rama a mergear
+pub fn analyze_merge(
     directory: &str,
-    base_branch: &str,
-    head_branch: &str,
-    owner: &str,
-    title: &str,
-    pr_number: &str,
-    repo_name: &str,
-) -> Result<String, CommandsError> {
-    let (result_merge, strategy) = perform_merge(base_branch, head_branch, directory, "pr")?;
-    let current_branch_commit = get_branch_current_hash(directory, base_branch.to_string())?;
-    let merge_branch_commit = get_branch_current_hash(directory, head_branch.to_string())?;
-    let mut result_merge_pr = String::new();
-
-    if result_merge.contains("CONFLICT") {
-        let first_line = result_merge.lines().next().unwrap();
-        let conflict_path = first_line.strip_prefix("Auto-merging ").unwrap();
-        result_merge_pr.push_str("Can’t automatically merge.\n");
-        result_merge_pr.push_str(format!("Conflict in file:{}\n", conflict_path).as_str());
-    } else {
-        result_merge_pr.push_str("Automatic Merge was successfull\n");
-        update_logs_refs(
-            directory,
-            strategy.clone(),
-            base_branch,
-            head_branch,
-            &current_branch_commit,
-            &merge_branch_commit,
-        )?;
+    current_branch: &str,
+    merge_branch: &str,
+) -> Result<(String, Vec<String>), CommandsError> {
+    if is_same_branch(current_branch, merge_branch) {
+        return Err(CommandsError::IsSameBranch);
+    }
+    let common_ancestor = find_commit_common_ancestor(directory, current_branch, merge_branch)?;
+    if is_up_to_date(directory, current_branch, merge_branch, &common_ancestor)? {
+        return Ok(("".to_string(), Vec::new()));
+    }
+    let path_current_branch = get_refs_path(directory, current_branch);
+    let current_branch_hash = get_branch_hash(&path_current_branch)?;
+
+    let strategy = get_merge_strategy(common_ancestor, current_branch_hash)?;
+    if strategy == "Fast Forward" {
+        return Ok((strategy, Vec::new()));
+    }
+    // En las PR no se reescriben los archivos en conflicto, por lo que el análisis es de solo lectura
+    let (_, conflicts) = three_way_merge(directory, current_branch, merge_branch, "pr")?;
+    Ok((strategy, conflicts))
+}
+
+/// Datos de una PR que se usan al integrarla con `merge_pr`.
+///
+/// - `base_branch`: nombre de la rama base.
+/// - `head_branch`: nombre de la rama a mergear.
+/// - `owner`: nombre del dueño de la PR.
+/// - `title`: título de la PR.
+/// - `number`: número de la PR.
+/// - `repo_name`: nombre del repositorio.
+/// - `template`: plantilla del mensaje del merge commit; si no se indica se usa
+///   `PR_MERGE_MESSAGE_TEMPLATE` (ver `format_pr_merge_message`).
+///
+#[derive(Debug, Clone, Copy)]
+pub struct PrMergeOptions<'a> {
+    pub base_branch: &'a str,
+    pub head_branch: &'a str,
+    pub owner: &'a str,
+    pub title: &'a str,
+    pub number: &'a str,
+    pub repo_name: &'a str,
+    pub template: Option<&'a str>,
+}
+
+/// Esta función realiza un merge de una PR. Si no hay conflictos se crea el merge commit en la
+/// rama base; en caso contrario no se modifica el repositorio.
+/// Devuelve los paths de los archivos en conflicto, vacío si el merge se realizó.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'pr': datos de la PR a integrar
+pub fn merge_pr(directory: &str, pr: &PrMergeOptions) -> Result<Vec<String>, CommandsError> {
+    let (_, conflicts) = analyze_merge(directory, pr.base_branch, pr.head_branch)?;
+    if conflicts.is_empty() {
+        // Las PR siempre se integran con un merge commit, como en la historia de GitHub
+        perform_merge(pr.base_branch, pr.head_branch, directory, "pr", true)?;
+        let message = format_pr_merge_message(pr);
         update_refs_pr(
             directory,
-            base_branch,
-            head_branch,
-            owner,
-            title,
-            pr_number,
-            repo_name,
+            pr.base_branch,
+            pr.head_branch,
+            pr.owner,
+            &message,
         )?;
     }
 
-    Ok(result_merge)
+    Ok(conflicts)
 }
 
 /// Actualiza el repositorio en caso de recibir un commit con archivos eliminados
@@ -237,16 +413,10 @@ fn update_work_directory(
 
     let mut vec_objects_hash: Vec<String> = Vec::new();
     save_hash_objects(directory, &mut vec_objects_hash, tree_hash.to_string())?;
-    let index_path = format!("{}/.git/index", directory);
-    let index_file = open_file(index_path.as_str())?;
-    let reader_index = io::BufReader::new(index_file);
+    let index_content = open_index(&get_git_dir(directory))?;
 
-    for line in reader_index.lines().map_while(Result::ok) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-
-        if parts.len() == 3 {
-            let path = parts[0];
-            let hash = parts[2];
+    for line in index_content.lines() {
+        if let Some((path, _, hash)) = parse_index_line(line) {
             if vec_objects_hash.contains(&hash.to_string()) {
                 // println!("Persiste");
             } else {
@@ -287,15 +457,10 @@ fn save_hash_objects(
     tree_hash: String,
 ) -> Result<(), CommandsError> {
     let tree = git_cat_file(directory, &tree_hash, "-p")?;
-    for line in tree.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let file_mode = if parts[0] == FILE || parts[0] == DIRECTORY {
-            parts[0]
-        } else {
-            parts[1]
-        };
-        let hash = parts[2];
-        if file_mode == FILE {
+    for line in tree.lines().filter(|line| !line.is_empty()) {
+        let (file_mode, _, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+        if is_blob_mode(file_mode) {
             vec.push(hash.to_string());
         } else if file_mode == DIRECTORY {
             vec.push(hash.to_string());
@@ -305,16 +470,19 @@ fn save_hash_objects(
     Ok(())
 }
 
-/// Obtiene el resultado en caso de que haya un conflicto.
+/// Obtiene el resultado en caso de que haya conflictos.
 /// ###Parametros:
 /// 'result_merge': resultado del merge
-/// 'file': archivo que tiene conflicto
-fn get_result_conflict(result_merge: &mut String, file: &FileEntry) {
-    result_merge.push_str(format!("Auto-merging {}\n", file.path).as_str());
-    result_merge
-        .push_str(format!("CONFLICT (content): Merge conflict in {}\n", file.path).as_str());
+/// 'conflicts': paths de los archivos que tienen conflicto
+fn get_result_conflict(result_merge: &mut String, conflicts: &[String]) {
+    for path in conflicts {
+        result_merge.push_str(format!("Auto-merging {}\n", path).as_str());
+        result_merge.push_str(format!("CONFLICT (content): Merge conflict in {}\n", path).as_str());
+    }
     result_merge.push_str("Automatic merge failed; fix conflicts and then commit the result.\n");
-    result_merge.push_str(format!("Conflict in file:{}\n", file.path).as_str());
+    for path in conflicts {
+        result_merge.push_str(format!("Conflict in file:{}\n", path).as_str());
+    }
 }
 
 /// Obtiene el resultado en caso de que haya un fast forward.
@@ -353,86 +521,40 @@ fn is_same_branch(current_branch: &str, merge_branch: &str) -> bool {
     current_branch == merge_branch
 }
 
-// Función para actualizar los logs de las ramas
-/// ###Parametros:
-/// 'directory': directorio del repositorio local
-/// 'strategy': estrategia de merge
-/// 'current_branch': nombre de la rama actual
-/// 'merge_branch': nombre de la rama a mergear
-/// 'current_commit': commit actual
-/// 'merge_commit': commit a mergear
-fn update_logs_refs(
-    directory: &str,
-    strategy: String,
-    current_branch: &str,
-    merge_branch: &str,
-    current_commit: &str,
-    merge_commit: &str,
-) -> Result<(), CommandsError> {
-    let log_merge_path = get_log_path(directory, merge_branch);
-    let log_merge_file = open_file(&log_merge_path)?;
-    let log_merge_content = read_file_string(log_merge_file)?;
-    let log_current_path = get_log_path(directory, current_branch);
-    let log_current_file = open_file(&log_current_path)?;
-    let mut log_current_content = read_file_string(log_current_file)?;
-
-    if strategy == "Fast Forward" {
-        create_file_replace(&log_current_path, &log_merge_content)?;
-    } else {
-        let logs_current_branch = get_log_from_branch(directory, current_commit)?;
-        let logs_merge_branch = get_log_from_branch(directory, merge_commit)?;
-        let logs_just_in_merge_branch =
-            logs_just_in_one_branch(logs_merge_branch.to_vec(), logs_current_branch.to_vec());
-        // revertir el orden de logs_just_in_merge_branch
-        let logs_just_in_merge_branch = logs_just_in_merge_branch.iter().rev().collect::<Vec<_>>();
-
-        let new_commits: String = log_merge_content
-            .lines()
-            .skip_while(|&line| !line.contains(logs_just_in_merge_branch[0]))
-            .collect::<Vec<&str>>()
-            .join("\n");
-
-        log_current_content.push_str(format!("\n{}", new_commits).as_str());
-        create_file_replace(&log_current_path, &log_current_content)?;
-    }
-    Ok(())
-}
-
 // Función para actualizar las referencias de las ramas
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
 /// 'strategy': estrategia de merge
-/// 'current_branch': nombre de la rama actual
-/// 'merge_branch': nombre de la rama a mergear
-/// 'current_branch_commit': commit actual
-/// 'merge_branch_commit': commit a mergear
+/// 'current': nombre y commit de la rama actual
+/// 'merge': nombre y commit de la rama a mergear
+/// 'message': mensaje del merge commit
 /// 'client': cliente que realizó el merge
 fn update_refs(
     directory: &str,
     strategy: String,
-    current_branch: &str,
-    merge_branch: &str,
-    current_branch_commit: &str,
-    merge_branch_commit: &str,
+    (current_branch, current_branch_commit): (&str, &str),
+    (merge_branch, merge_branch_commit): (&str, &str),
+    message: &str,
     client: Client,
 ) -> Result<(), CommandsError> {
     let current_commit_path = format!(
         "{}/{}/{}/{}",
         directory, GIT_DIR, REFS_HEADS, current_branch
     );
-    let mut merge_commit_path =
-        format!("{}/{}/{}/{}", directory, GIT_DIR, REFS_HEADS, merge_branch);
-    if merge_branch.contains('/') {
-        merge_commit_path = format!("{}/{}/{}", directory, GIT_DIR, merge_branch);
-    }
-    let merge_commit_file = open_file(&merge_commit_path)?;
-    let merge_commit_content = read_file_string(merge_commit_file)?;
 
     if strategy == "Fast Forward" {
-        create_file_replace(&current_commit_path, &merge_commit_content)?;
+        create_file_replace(&current_commit_path, merge_branch_commit)?;
+        append_branch_reflog(
+            directory,
+            current_branch,
+            current_branch_commit,
+            merge_branch_commit,
+            &format!("{} <{}>", client.get_name(), client.get_email()),
+            &format!("merge {}: Fast-forward", merge_branch),
+        )?;
     } else {
         let commit = Commit::new(
-            "Merge Commit".to_string(),
+            message.to_string(),
             client.get_name().to_string(),
             client.get_email().to_string(),
             client.get_name().to_string(),
@@ -448,41 +570,36 @@ fn update_refs(
     Ok(())
 }
 
-// Función para actualizar las referencias de las ramas en caso de una PR. Se commitea un merge pull request.
+/// Función para actualizar las referencias de las ramas en caso de una PR. Se commitea un merge pull request
+/// con el trailer `Co-authored-by` del dueño de la PR.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
 /// 'base_branch': nombre de la rama base
 /// 'head_branch': nombre de la rama a mergear
 /// 'owner': nombre del dueño de la PR
-/// 'title': título de la PR
-/// 'pr_number': número de la PR
-/// 'repo_name': nombre del repositorio
+/// 'message': mensaje del merge commit
 fn update_refs_pr(
     directory: &str,
     base_branch: &str,
     head_branch: &str,
     owner: &str,
-    title: &str,
-    pr_number: &str,
-    repo_name: &str,
+    message: &str,
 ) -> Result<(), CommandsError> {
     let current_branch_commit = get_branch_current_hash(directory, base_branch.to_string())?;
     let branch_to_merge_commit = get_branch_current_hash(directory, head_branch.to_string())?;
 
-    let message = format!(
-        "Merge pull request #{} from {}/{}. Title {}",
-        pr_number, repo_name, head_branch, title
-    );
     let owner_email = format!("{}@users.noreply.rusteam.com", owner);
     let commiter_name = "Rusteam".to_string();
     let commiter_email = "noreply@rusteam.com".to_string();
-    let commit = Commit::new(
-        message,
+    let co_author = format!("{} <{}>", owner, owner_email);
+    let mut commit = Commit::new(
+        message.to_string(),
         owner.to_string(),
         owner_email,
         commiter_name,
         commiter_email,
     );
+    commit.add_trailer(CO_AUTHORED_BY, &co_author);
     merge_commit(
         directory,
         commit,
@@ -492,7 +609,25 @@ fn update_refs_pr(
     Ok(())
 }
 
+/// Arma el mensaje del merge commit de una PR reemplazando en la plantilla `{number}`,
+/// `{repo}`, `{head}`, `{base}`, `{title}` y `{owner}` por los datos de la PR.
+/// Sin plantilla se usa `PR_MERGE_MESSAGE_TEMPLATE`.
+/// ###Parametros:
+/// 'pr': datos de la PR
+pub fn format_pr_merge_message(pr: &PrMergeOptions) -> String {
+    pr.template
+        .unwrap_or(PR_MERGE_MESSAGE_TEMPLATE)
+        .replace("{number}", pr.number)
+        .replace("{repo}", pr.repo_name)
+        .replace("{head}", pr.head_branch)
+        .replace("{base}", pr.base_branch)
+        .replace("{title}", pr.title)
+        .replace("{owner}", pr.owner)
+}
+
 // Función para encontrar el ancestro común de dos branches, es decir, el commit más reciente que comparten ambas ramas.
+/// Se recorren todos los parents con `get_merge_bases`, por lo que se tienen en cuenta las ramas
+/// mergeadas. Si las ramas no tienen historia en común se devuelve un string vacío.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
 /// 'current_branch': nombre de la rama actual
@@ -502,17 +637,12 @@ pub fn find_commit_common_ancestor(
     current_branch: &str,
     branch_to_merge: &str,
 ) -> Result<String, CommandsError> {
-    let mut commit_common_ancestor = String::new();
-    let commits_current = get_commits(directory, current_branch)?;
-    let commits_merge = get_commits(directory, branch_to_merge)?;
-    for commit_current in commits_current.iter().rev() {
-        for commit_merge in commits_merge.iter().rev() {
-            if commit_current == commit_merge {
-                commit_common_ancestor = commit_current.to_string();
-            }
-        }
-    }
-    Ok(commit_common_ancestor)
+    let current_hash = get_branch_hash(&get_refs_path(directory, current_branch))?;
+    let merge_hash = get_branch_hash(&get_refs_path(directory, branch_to_merge))?;
+    Ok(get_merge_bases(directory, &current_hash, &merge_hash)?
+        .into_iter()
+        .next()
+        .unwrap_or_default())
 }
 
 // Función para verificar si la current_branch ya esta actualizada con la merge_branch. Si las ramas tienen el mismo hash, o si el ancestro común es igual
@@ -546,7 +676,8 @@ fn is_up_to_date(
 fn fast_forward(directory: &str, merge_branch: &str) -> Result<Vec<FileEntry>, CommandsError> {
     let path_branch_to_merge = get_refs_path(directory, merge_branch);
     let branch_to_merge_hash = get_branch_hash(&path_branch_to_merge)?;
-    let merge_commit_content = git_cat_file(directory, &branch_to_merge_hash, "-p")?;
+    let merge_commit_content =
+        git_cat_file_typed(directory, &branch_to_merge_hash, "-p", Some(COMMIT))?;
 
     // Obtener el tree del merge_branch
     let merge_tree_hash =
@@ -568,12 +699,15 @@ fn fast_forward(directory: &str, merge_branch: &str) -> Result<Vec<FileEntry>, C
 /// 'current_branch': nombre de la rama actual
 /// 'merge_branch': nombre de la rama a mergear
 /// 'merge_type': tipo de merge a realizar
+///
+/// Devuelve los archivos a integrar con su estado (OK o CONFLICT) y los paths de los archivos en
+/// conflicto, ordenados.
 fn three_way_merge(
     directory: &str,
     current_branch: &str,
     merge_branch: &str,
     merge_type: &str,
-) -> Result<HashMap<FileEntry, String>, CommandsError> {
+) -> Result<(HashMap<FileEntry, String>, Vec<String>), CommandsError> {
     let path_current_branch = get_refs_path(directory, current_branch);
     let path_branch_to_merge = get_refs_path(directory, merge_branch);
 
@@ -588,7 +722,7 @@ fn three_way_merge(
     let merge_tree_hash =
         get_tree_hash(&merge_commit_content).ok_or(CommandsError::InvalidTreeHashError)?;
 
-    // Obtener los files de ambas branches con el formato FileEntry (path, hash)
+    // Obtener los files de ambas branches con el formato FileEntry (path, hash, mode)
     let mut files_in_current_tree = Vec::new();
     let mut files_in_merge_tree = Vec::new();
     get_files_in_tree(
@@ -606,14 +740,39 @@ fn three_way_merge(
 
     // Voy a devolver una estructura que sea un HashMap<FileEntry, String> con el FileEntry de los archivos y sus blobs y un string con OK o CONFLICT
     let mut result: HashMap<FileEntry, String> = HashMap::new();
+    let mut conflicts: Vec<String> = Vec::new();
+
+    // Con el estilo diff3 las marcas de conflicto incluyen las líneas del ancestro común
+    let writes_conflicts =
+        merge_type == "merge" || merge_type == "rebase" || merge_type == "squash";
+    let style = match writes_conflicts {
+        true => ConflictStyle::from_config(directory),
+        false => ConflictStyle::Merge,
+    };
+    let mut files_in_base_tree = Vec::new();
+    if style == ConflictStyle::Diff3 {
+        let base_commit = find_commit_common_ancestor(directory, current_branch, merge_branch)?;
+        let base_commit_content = git_cat_file(directory, &base_commit, "-p")?;
+        let base_tree_hash =
+            get_tree_hash(&base_commit_content).ok_or(CommandsError::InvalidTreeHashError)?;
+        get_files_in_tree(
+            directory,
+            base_tree_hash,
+            &mut "".to_string(),
+            &mut files_in_base_tree,
+        )?;
+    }
 
     for file in files_in_merge_tree.iter() {
         if let Some(current_file) = files_in_current_tree.iter().find(|f| f.path == file.path) {
             if current_file.hash != file.hash {
                 // El archivo existe en current_branch pero fue modificado en merge_branch
                 result.insert(file.clone(), "CONFLICT".to_string());
-                if merge_type == "merge" || merge_type == "rebase" {
-                    check_each_line(directory, current_file, file, merge_branch)?;
+                conflicts.push(file.path.to_string());
+                if writes_conflicts {
+                    let base_file = files_in_base_tree.iter().find(|f| f.path == file.path);
+                    let versions = (base_file, current_file, file);
+                    check_each_line(directory, versions, merge_branch, style)?;
                 }
             }
         } else {
@@ -621,8 +780,9 @@ fn three_way_merge(
             result.insert(file.clone(), "OK".to_string());
         }
     }
+    conflicts.sort();
 
-    Ok(result)
+    Ok((result, conflicts))
 }
 
 /// Obtiene los archivos de un tree.
@@ -636,22 +796,24 @@ fn get_files_in_tree(
     path: &mut str,
     files_in_tree: &mut Vec<FileEntry>,
 ) -> Result<(), CommandsError> {
-    let tree_content = git_cat_file(directory, tree_hash, "-p")?;
+    let tree_content = git_cat_file_typed(directory, tree_hash, "-p", Some(TREE))?;
     for line in tree_content.lines() {
         if !line.is_empty() {
-            let tree_parts: Vec<&str> = line.split_whitespace().collect();
+            let (mode, name, hash) =
+                parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
             let mut current_path = if path.is_empty() {
-                tree_parts[1].to_string()
+                name.to_string()
             } else {
-                format!("{}/{}", path, tree_parts[1])
+                format!("{}/{}", path, name)
             };
 
-            if git_cat_file(directory, tree_parts[2], "-t")? == "tree" {
-                get_files_in_tree(directory, tree_parts[2], &mut current_path, files_in_tree)?;
+            if git_cat_file(directory, hash, "-t")? == "tree" {
+                get_files_in_tree(directory, hash, &mut current_path, files_in_tree)?;
             } else {
                 files_in_tree.push(FileEntry {
                     path: current_path,
-                    hash: tree_parts[2].to_string(),
+                    hash: hash.to_string(),
+                    mode: mode.to_string(),
                 });
             }
         }
@@ -662,20 +824,25 @@ fn get_files_in_tree(
 /// Chequea cada linea de los archivos que difieren entre las ramas a mergear. Esto solo se hace en caso de merge o rebase, NO en caso de un merge PR.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
-/// 'current_file': archivo de la rama actual
-/// 'merge_file': archivo de la rama a mergear
+/// 'versions': archivo del ancestro común (si existe), de la rama actual y de la rama a mergear
 /// 'merge_branch': nombre de la rama a mergear
+/// 'style': estilo de las marcas de conflicto; con diff3 se muestra también la línea del ancestro
 fn check_each_line(
     directory: &str,
-    current_file: &FileEntry,
-    merge_file: &FileEntry,
+    (base_file, current_file, merge_file): (Option<&FileEntry>, &FileEntry, &FileEntry),
     merge_branch: &str,
+    style: ConflictStyle,
 ) -> Result<(), CommandsError> {
     let current_file_content = git_cat_file(directory, &current_file.hash, "-p")?;
     let merge_file_content = git_cat_file(directory, &merge_file.hash, "-p")?;
+    let base_file_content = match base_file {
+        Some(base_file) => git_cat_file(directory, &base_file.hash, "-p")?,
+        None => String::new(),
+    };
 
     let mut current_file_lines = current_file_content.lines();
     let mut merge_file_lines = merge_file_content.lines();
+    let mut base_file_lines = base_file_content.lines();
     let mut line_current = current_file_lines.next();
     let mut line_merge = merge_file_lines.next();
 
@@ -687,6 +854,14 @@ fn check_each_line(
             if let Some(line_current) = line_current {
                 new_content_file.push_str(line_current);
             }
+            if style == ConflictStyle::Diff3 {
+                new_content_file.push_str("\n||||||| merged common ancestors\n");
+                if let Some(line_base) = base_file_lines.next() {
+                    new_content_file.push_str(line_base);
+                }
+            } else {
+                base_file_lines.next();
+            }
             new_content_file.push_str("\n=======\n");
             if let Some(line_merge) = line_merge {
                 new_content_file.push_str(line_merge);
@@ -699,6 +874,7 @@ fn check_each_line(
                 new_content_file.push_str(line_current);
             }
             new_content_file.push('\n');
+            base_file_lines.next();
         }
         line_current = current_file_lines.next();
         line_merge = merge_file_lines.next();
@@ -757,6 +933,9 @@ pub fn logs_just_in_one_branch(
 /// 'directory': directorio del repositorio local
 /// 'branch_name': nombre de la rama
 pub fn get_refs_path(directory: &str, branch_name: &str) -> String {
+    if branch_name == FETCH_HEAD {
+        return format!("{}/{}/{}", directory, GIT_DIR, FETCH_HEAD);
+    }
     let mut path_branch_to_merge = format!("{}/.git/refs/heads/{}", directory, branch_name);
     if branch_name.contains("remotes") {
         path_branch_to_merge = format!("{}/.git/{}", directory, branch_name);
@@ -766,43 +945,15 @@ pub fn get_refs_path(directory: &str, branch_name: &str) -> String {
     path_branch_to_merge
 }
 
-/// Obtiene el path del archivo de logs de una rama (en logs/refs/heads si es local o en logs/refs/remotes
-/// si es remota).
-/// ###Parametros:
-/// 'directory': directorio del repositorio local
-/// 'branch_name': nombre de la rama
-fn get_log_path(directory: &str, branch_name: &str) -> String {
-    let mut log_path = format!("{}/.git/logs/refs/heads/{}", directory, branch_name);
-    if branch_name.contains("remotes") {
-        let branch_path = branch_name.split('/').collect::<Vec<_>>();
-        if branch_path.len() >= 4 {
-            log_path = format!(
-                "{}/.git/logs/refs/remotes/{}/{}",
-                directory, branch_path[2], branch_path[3]
-            );
-        } else {
-            log_path = format!("{}/.git/logs/refs/remotes/{}", directory, branch_path[2]);
-        }
-    } else {
-        let branch_path = branch_name.split('/').collect::<Vec<_>>();
-        if branch_path.len() == 2 {
-            log_path = format!(
-                "{}/.git/logs/refs/remotes/{}/{}",
-                directory, branch_path[0], branch_path[1]
-            );
-        }
-    }
-    log_path
-}
-
-/// Obtiene el hash de una rama.
+/// Obtiene el hash de una rama. En FETCH_HEAD se toma el hash de la primera línea.
 /// ###Parametros:
 /// 'path_current_branch': path del archivo de la rama actual
 /// 'path_branch_to_merge': path del archivo de la rama a mergear
 pub fn get_branch_hash(path_branch: &str) -> Result<String, CommandsError> {
     let branch_file = open_file(path_branch)?;
-    let branch_hash = read_file_string(branch_file)?;
-    Ok(branch_hash)
+    let content = read_file_string(branch_file)?;
+    let branch_hash = content.split_whitespace().next().unwrap_or_default();
+    Ok(branch_hash.to_string())
 }
 
 /// Obtiene el path del archivo en conflicto.
@@ -819,3 +970,229 @@ pub fn get_conflict_path(conflict_msg: &str) -> String {
     }
     conflict_path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        branch::git_branch_create, checkout::git_checkout_switch, config::git_config_set,
+        init::git_init, rev_parse::git_rev_parse,
+    };
+    use crate::test_utils::{commit_file, test_client};
+    use std::fs;
+
+    #[test]
+    fn test_git_merge_no_ff_creates_merge_commit() {
+        let directory = "./test_merge_no_ff";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "primero");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let second = commit_file(directory, "b.txt", "segundo");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let client = test_client(directory, "9090");
+
+        // Sin --no-ff el merge sería un fast forward
+        let common_ancestor = find_commit_common_ancestor(directory, "master", "feature")
+            .expect("Falló al buscar el ancestro común");
+        assert_eq!(
+            get_merge_strategy(common_ancestor, first.clone()),
+            Ok("Fast Forward".to_string())
+        );
+
+        let result = git_merge(directory, "master", "feature", client, true, None)
+            .expect("Falló al hacer el merge");
+        assert!(!result.contains("Fast-forward"));
+
+        let merge = git_rev_parse(directory, "master").expect("Falló al resolver master");
+        assert_ne!(merge, second);
+        let content = git_cat_file(directory, &merge, "-p").expect("Falló al leer el commit");
+        let commit = Commit::from_object(&content).expect("Commit inválido");
+        assert_eq!(commit.get_parents(), &[first, second.clone()]);
+        assert_eq!(
+            commit.get_message().trim(),
+            "Merge branch 'feature' into 'master'"
+        );
+        // El tree del merge commit es el de la branch mergeada
+        let feature_content =
+            git_cat_file(directory, &second, "-p").expect("Falló al leer el commit");
+        assert_eq!(Some(commit.get_tree()), get_tree_hash(&feature_content));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_find_commit_common_ancestor_after_merge() {
+        let directory = "./test_merge_common_ancestor";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "primero");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        let merged = commit_file(directory, "b.txt", "en master");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(directory, "c.txt", "en feature");
+        // Al mergear master en feature el ancestro común pasa a ser la punta mergeada
+        git_merge(
+            directory,
+            "feature",
+            "master",
+            test_client(directory, "9090"),
+            true,
+            None,
+        )
+        .expect("Falló al hacer el merge");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        commit_file(directory, "d.txt", "otro en master");
+
+        let common_ancestor = find_commit_common_ancestor(directory, "master", "feature");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(common_ancestor, Ok(merged));
+    }
+
+    #[test]
+    fn test_git_merge_custom_message_and_diff3_conflicts() {
+        let directory = "./test_merge_message_diff3";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "base");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_branch_create(directory, "otra").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(directory, "b.txt", "feature");
+        git_checkout_switch(directory, "otra").expect("Falló al cambiar de branch");
+        commit_file(directory, "a.txt", "otra");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        commit_file(directory, "c.txt", "master");
+        let client = test_client(directory, "9090");
+
+        // `-m` toma un solo argumento, que puede ir antes o después de la branch
+        assert_eq!(
+            handle_merge(vec!["feature", "-m", "Integra", "feature"], client.clone()),
+            Err(CommandsError::InvalidArgumentCountMergeError)
+        );
+        handle_merge(vec!["-m", "Integra", "feature"], client.clone())
+            .expect("Falló al hacer el merge");
+        let merge = git_rev_parse(directory, "master").expect("Falló al resolver master");
+        let content = git_cat_file(directory, &merge, "-p").expect("Falló al leer el commit");
+        let message = Commit::from_object(&content)
+            .expect("Commit inválido")
+            .get_message();
+
+        commit_file(directory, "a.txt", "master");
+        git_config_set(directory, "merge.conflictStyle", "diff3")
+            .expect("Falló al configurar el estilo");
+        let result = git_merge(directory, "master", "otra", client, false, None)
+            .expect("Falló al hacer el merge");
+        let conflicted =
+            fs::read_to_string(format!("{}/a.txt", directory)).expect("Falló al leer el archivo");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(message.trim(), "Integra");
+        assert!(result.contains("CONFLICT"));
+        assert_eq!(
+            conflicted,
+            "<<<<<<< HEAD\nmaster\n||||||| merged common ancestors\nbase\n=======\notra\n>>>>>>> otra\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_pr_message_template() {
+        let directory = "./test_merge_pr_template";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "base");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(directory, "b.txt", "feature");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+
+        let template = "PR #{number}: {title} ({head} -> {base})";
+        let pr = PrMergeOptions {
+            base_branch: "master",
+            head_branch: "feature",
+            owner: "Valen",
+            title: "Agrega b",
+            number: "7",
+            repo_name: "repo",
+            template: Some(template),
+        };
+        let conflicts = merge_pr(directory, &pr).expect("Falló al hacer el merge");
+        let merge = git_rev_parse(directory, "master").expect("Falló al resolver master");
+        let content = git_cat_file(directory, &merge, "-p").expect("Falló al leer el commit");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert!(conflicts.is_empty());
+        assert!(content.contains("PR #7: Agrega b (feature -> master)"));
+        assert_eq!(
+            format_pr_merge_message(&PrMergeOptions {
+                template: None,
+                ..pr
+            }),
+            "Merge pull request #7 from repo/feature. Title Agrega b"
+        );
+    }
+
+    #[test]
+    fn test_git_merge_squash_stages_changes() {
+        let directory = "./test_merge_squash";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "primero");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let feature = commit_file(directory, "b.txt", "segundo");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let master = commit_file(directory, "c.txt", "tercero");
+
+        let result =
+            git_merge_squash(directory, "master", "feature").expect("Falló al hacer el merge");
+        assert!(result.contains("Squash commit -- not updating HEAD"));
+
+        // La branch no se mueve y no se crea ningún commit
+        assert_eq!(git_rev_parse(directory, "master"), Ok(master));
+        assert_eq!(git_rev_parse(directory, "feature"), Ok(feature));
+        // El archivo de la branch mergeada queda en el working directory y en el index
+        let content =
+            fs::read_to_string(format!("{}/b.txt", directory)).expect("Falló al leer el archivo");
+        assert_eq!(content, "segundo");
+        let index =
+            open_index(&format!("{}/{}", directory, GIT_DIR)).expect("Falló al leer el index");
+        assert!(index.lines().any(|line| line.starts_with("b.txt ")));
+        assert!(index.lines().any(|line| line.starts_with("c.txt ")));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_merge_pr_reports_all_conflicts() {
+        let directory = "./test_merge_pr_conflicts";
+        git_init(directory).expect("Falló al crear el repositorio");
+        commit_file(directory, "a.txt", "a base");
+        commit_file(directory, "b.txt", "b base");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(directory, "b.txt", "b feature");
+        let feature = commit_file(directory, "a.txt", "a feature");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        commit_file(directory, "a.txt", "a master");
+        let master = commit_file(directory, "b.txt", "b master");
+
+        let pr = PrMergeOptions {
+            base_branch: "master",
+            head_branch: "feature",
+            owner: "Valen",
+            title: "PR",
+            number: "1",
+            repo_name: "repo",
+            template: None,
+        };
+        let conflicts = merge_pr(directory, &pr).expect("Falló al hacer el merge");
+        assert_eq!(conflicts, vec!["a.txt".to_string(), "b.txt".to_string()]);
+
+        // Con conflictos no se modifica ninguna de las branches
+        assert_eq!(git_rev_parse(directory, "master"), Ok(master));
+        assert_eq!(git_rev_parse(directory, "feature"), Ok(feature));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
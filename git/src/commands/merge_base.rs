@@ -0,0 +1,319 @@
+use super::cat_file::git_cat_file_typed;
+use super::commit::Commit;
+use super::errors::CommandsError;
+use super::rev_parse::git_rev_parse;
+use crate::consts::COMMIT;
+use crate::models::client::Client;
+use std::collections::{HashMap, HashSet};
+
+/// Esta función se encarga de llamar al comando merge-base con los parametros necesarios.
+///
+/// * `git merge-base <commit> <commit>`: muestra el mejor ancestro común de los dos commits.
+/// * `git merge-base --all <commit> <commit>`: muestra todos los mejores ancestros comunes, uno
+///   por línea. En historias con merges cruzados puede haber más de uno.
+/// * `git merge-base --is-ancestor <commit> <commit>`: no muestra nada y falla si el primer
+///   commit no es ancestro del segundo.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función merge-base
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_merge_base(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        ["--is-ancestor", ancestor, commit] => {
+            git_merge_base_is_ancestor(directory, ancestor, commit)
+        }
+        ["--all", commit_a, commit_b] => git_merge_base_all(directory, commit_a, commit_b),
+        [commit_a, commit_b] => git_merge_base(directory, commit_a, commit_b),
+        _ => Err(CommandsError::InvalidArgumentCountMergeBaseError),
+    }
+}
+
+/// Devuelve el mejor ancestro común de dos commits. Si hay más de uno, se elige el de fecha de
+/// commit más reciente.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit_a': revisión del primer commit
+/// 'commit_b': revisión del segundo commit
+pub fn git_merge_base(
+    directory: &str,
+    commit_a: &str,
+    commit_b: &str,
+) -> Result<String, CommandsError> {
+    let hash_a = git_rev_parse(directory, commit_a)?;
+    let hash_b = git_rev_parse(directory, commit_b)?;
+    match get_merge_bases(directory, &hash_a, &hash_b)?.first() {
+        Some(merge_base) => Ok(format!("{}\n", merge_base)),
+        None => Err(CommandsError::MergeBaseNotFound),
+    }
+}
+
+/// Devuelve todos los mejores ancestros comunes de dos commits, uno por línea.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit_a': revisión del primer commit
+/// 'commit_b': revisión del segundo commit
+pub fn git_merge_base_all(
+    directory: &str,
+    commit_a: &str,
+    commit_b: &str,
+) -> Result<String, CommandsError> {
+    let hash_a = git_rev_parse(directory, commit_a)?;
+    let hash_b = git_rev_parse(directory, commit_b)?;
+    let merge_bases = get_merge_bases(directory, &hash_a, &hash_b)?;
+    if merge_bases.is_empty() {
+        return Err(CommandsError::MergeBaseNotFound);
+    }
+    Ok(merge_bases
+        .iter()
+        .map(|merge_base| format!("{}\n", merge_base))
+        .collect())
+}
+
+/// Verifica que `ancestor` sea ancestro de `commit`, recorriendo todos los parents. Un commit
+/// se considera ancestro de sí mismo. Si no lo es, devuelve `CommandsError::NotAncestor`.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'ancestor': revisión del posible ancestro
+/// 'commit': revisión del commit desde el que se recorre la historia
+pub fn git_merge_base_is_ancestor(
+    directory: &str,
+    ancestor: &str,
+    commit: &str,
+) -> Result<String, CommandsError> {
+    let hash_ancestor = git_rev_parse(directory, ancestor)?;
+    let hash_commit = git_rev_parse(directory, commit)?;
+    if is_full_ancestor(directory, &hash_ancestor, &hash_commit)? {
+        return Ok(String::new());
+    }
+    Err(CommandsError::NotAncestor(
+        ancestor.to_string(),
+        commit.to_string(),
+    ))
+}
+
+/// Indica si `ancestor` es ancestro de `commit`, recorriendo todos los parents, por lo que se
+/// tienen en cuenta las ramas mergeadas. Un commit se considera ancestro de sí mismo.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'ancestor': hash del posible ancestro
+/// 'commit': hash del commit desde el que se recorre la historia
+pub fn is_full_ancestor(
+    directory: &str,
+    ancestor: &str,
+    commit: &str,
+) -> Result<bool, CommandsError> {
+    let mut graph = CommitGraph::default();
+    Ok(graph.ancestors(directory, commit)?.contains(ancestor))
+}
+
+/// Devuelve los mejores ancestros comunes de dos commits: los ancestros comunes que no son
+/// ancestros de otro ancestro común. Se ordenan por fecha de commit, del más reciente al más
+/// antiguo, y luego por hash.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'hash_a': hash del primer commit
+/// 'hash_b': hash del segundo commit
+pub fn get_merge_bases(
+    directory: &str,
+    hash_a: &str,
+    hash_b: &str,
+) -> Result<Vec<String>, CommandsError> {
+    let mut graph = CommitGraph::default();
+    let ancestors_a = graph.ancestors(directory, hash_a)?;
+    let ancestors_b = graph.ancestors(directory, hash_b)?;
+    let common: HashSet<&String> = ancestors_a.intersection(&ancestors_b).collect();
+
+    // Se descartan los ancestros de cada ancestro común
+    let mut redundant: HashSet<&String> = HashSet::new();
+    for hash in &common {
+        let mut pending: Vec<&String> = graph.parents(hash).iter().collect();
+        while let Some(parent) = pending.pop() {
+            if redundant.insert(parent) {
+                pending.extend(graph.parents(parent));
+            }
+        }
+    }
+
+    let mut merge_bases: Vec<&String> = common
+        .into_iter()
+        .filter(|hash| !redundant.contains(hash))
+        .collect();
+    merge_bases.sort_by(|a, b| {
+        graph
+            .timestamp(b)
+            .cmp(&graph.timestamp(a))
+            .then_with(|| a.cmp(b))
+    });
+    Ok(merge_bases.into_iter().cloned().collect())
+}
+
+/// Parents y fechas de los commits ya leídos, para no leer dos veces el mismo commit.
+#[derive(Debug, Default)]
+pub struct CommitGraph {
+    parents: HashMap<String, Vec<String>>,
+    timestamps: HashMap<String, i64>,
+}
+
+impl CommitGraph {
+    /// Devuelve el commit y todos sus ancestros, recorriendo todos los parents.
+    pub fn ancestors(
+        &mut self,
+        directory: &str,
+        hash: &str,
+    ) -> Result<HashSet<String>, CommandsError> {
+        let mut ancestors = HashSet::new();
+        let mut pending = vec![hash.to_string()];
+        while let Some(hash) = pending.pop() {
+            if ancestors.contains(&hash) {
+                continue;
+            }
+            self.read_commit(directory, &hash)?;
+            pending.extend(self.parents(&hash).iter().cloned());
+            ancestors.insert(hash);
+        }
+        Ok(ancestors)
+    }
+
+    /// Lee los parents y la fecha del commit, si todavía no se leyeron.
+    pub fn read_commit(&mut self, directory: &str, hash: &str) -> Result<(), CommandsError> {
+        if self.parents.contains_key(hash) {
+            return Ok(());
+        }
+        let content = git_cat_file_typed(directory, hash, "-p", Some(COMMIT))?;
+        let commit = Commit::from_object(&content)?;
+        let timestamp = commit
+            .get_committer_date()
+            .split_whitespace()
+            .next()
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .unwrap_or(0);
+        self.parents
+            .insert(hash.to_string(), commit.get_parents().to_vec());
+        self.timestamps.insert(hash.to_string(), timestamp);
+        Ok(())
+    }
+
+    /// Devuelve los parents de un commit ya leído.
+    pub fn parents(&self, hash: &str) -> &[String] {
+        self.parents.get(hash).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn timestamp(&self, hash: &str) -> i64 {
+        self.timestamps.get(hash).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init;
+    use crate::test_utils::test_commit;
+    use crate::util::files::get_git_dir;
+    use crate::util::object_format::ObjectFormat;
+    use crate::util::objects::builder_object_commit;
+    use std::fs;
+
+    const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+    fn create_commit(directory: &str, message: &str, parents: &[&str]) -> String {
+        let mut commit = test_commit(message);
+        commit.set_tree(EMPTY_TREE);
+        commit.set_parents(parents);
+        builder_object_commit(
+            &commit.serialize(),
+            &get_git_dir(directory),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el commit")
+    }
+
+    #[test]
+    fn test_merge_base_linear_history() {
+        let directory = "./test_merge_base_linear";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = create_commit(directory, "primero", &[]);
+        let second = create_commit(directory, "segundo", &[&first]);
+        let third = create_commit(directory, "tercero", &[&second]);
+
+        assert_eq!(
+            git_merge_base(directory, &second, &third),
+            Ok(format!("{}\n", second))
+        );
+        assert_eq!(
+            git_merge_base(directory, &third, &first),
+            Ok(format!("{}\n", first))
+        );
+        assert_eq!(
+            git_merge_base_is_ancestor(directory, &first, &third),
+            Ok(String::new())
+        );
+        assert_eq!(
+            git_merge_base_is_ancestor(directory, &third, &third),
+            Ok(String::new())
+        );
+        assert_eq!(
+            git_merge_base_is_ancestor(directory, &third, &first),
+            Err(CommandsError::NotAncestor(third.clone(), first.clone()))
+        );
+
+        let unrelated = create_commit(directory, "otra historia", &[]);
+        assert_eq!(
+            git_merge_base(directory, &third, &unrelated),
+            Err(CommandsError::MergeBaseNotFound)
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_merge_base_diamond_history() {
+        let directory = "./test_merge_base_diamond";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let base = create_commit(directory, "base", &[]);
+        let left = create_commit(directory, "izquierda", &[&base]);
+        let right = create_commit(directory, "derecha", &[&base]);
+        let merge = create_commit(directory, "merge", &[&left, &right]);
+
+        assert_eq!(
+            git_merge_base(directory, &left, &right),
+            Ok(format!("{}\n", base))
+        );
+        assert_eq!(
+            git_merge_base_all(directory, &merge, &right),
+            Ok(format!("{}\n", right))
+        );
+        // El segundo parent del merge no se alcanza siguiendo los primeros parents
+        assert_eq!(
+            git_merge_base_is_ancestor(directory, &right, &merge),
+            Ok(String::new())
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_merge_base_criss_cross_history() {
+        let directory = "./test_merge_base_criss_cross";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let base = create_commit(directory, "base", &[]);
+        let left = create_commit(directory, "izquierda", &[&base]);
+        let right = create_commit(directory, "derecha", &[&base]);
+        let merge_left = create_commit(directory, "merge izquierda", &[&left, &right]);
+        let merge_right = create_commit(directory, "merge derecha", &[&right, &left]);
+
+        let all = git_merge_base_all(directory, &merge_left, &merge_right)
+            .expect("Falló al buscar los ancestros comunes");
+        let mut merge_bases: Vec<&str> = all.lines().collect();
+        merge_bases.sort();
+        let mut expected = vec![left.as_str(), right.as_str()];
+        expected.sort();
+        assert_eq!(merge_bases, expected);
+
+        let best = git_merge_base(directory, &merge_left, &merge_right)
+            .expect("Falló al buscar el ancestro común");
+        assert!(all.starts_with(&best));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
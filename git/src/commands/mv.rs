@@ -0,0 +1,144 @@
+use super::errors::CommandsError;
+use super::rm::update_index;
+use crate::models::client::Client;
+use crate::util::files::get_git_dir;
+use crate::util::index::open_index;
+use crate::util::validation::normalize_path;
+use std::fs;
+use std::path::Path;
+
+/// Esta función se encarga de llamar al comando mv con los parametros necesarios.
+///
+/// * `git mv <origen> <destino>`: mueve o renombra un archivo trackeado.
+/// * `git mv -f <origen> <destino>`: sobrescribe el destino si ya existe.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función mv
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_mv(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        [src, dst] => git_mv(directory, src, dst, false),
+        ["-f", src, dst] => git_mv(directory, src, dst, true),
+        _ => Err(CommandsError::InvalidArgumentCountMvError),
+    }
+}
+
+/// Mueve un archivo trackeado en el working directory y actualiza el index.
+///
+/// La entrada del index conserva el hash del blob, ya que el contenido no cambia. Si el destino
+/// es un directorio, el archivo se mueve dentro de él con el mismo nombre. Si el index no se
+/// puede actualizar, el archivo vuelve a su ubicación original.
+///
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'src': ruta del archivo a mover, relativa al repositorio.
+/// 'dst': ruta de destino, relativa al repositorio.
+/// 'force': si es true, se sobrescribe el destino aunque ya exista.
+pub fn git_mv(directory: &str, src: &str, dst: &str, force: bool) -> Result<String, CommandsError> {
+    let src = normalize_path(src).trim_end_matches('/').to_string();
+    let mut dst = normalize_path(dst).trim_end_matches('/').to_string();
+
+    let git_dir = get_git_dir(directory);
+    let index_content = open_index(&git_dir)?;
+    let mut lines: Vec<String> = index_content.lines().map(String::from).collect();
+
+    let src_index = match lines.iter().position(|line| get_entry_name(line) == src) {
+        Some(src_index) => src_index,
+        None => return Err(CommandsError::NotTracked(src)),
+    };
+
+    if Path::new(&format!("{}/{}", directory, dst)).is_dir() {
+        let file_name = src.rsplit('/').next().unwrap_or(&src);
+        dst = if dst.is_empty() || dst == "." {
+            file_name.to_string()
+        } else {
+            format!("{}/{}", dst, file_name)
+        };
+    }
+    if dst == src {
+        return Err(CommandsError::MvDestinationExists(dst));
+    }
+
+    let src_path = format!("{}/{}", directory, src);
+    let dst_path = format!("{}/{}", directory, dst);
+    let dst_index = lines.iter().position(|line| get_entry_name(line) == dst);
+    if (dst_index.is_some() || Path::new(&dst_path).exists()) && !force {
+        return Err(CommandsError::MvDestinationExists(dst));
+    }
+
+    if let Some(parent) = Path::new(&dst_path).parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return Err(CommandsError::CreateDirError);
+        }
+    }
+    if fs::rename(&src_path, &dst_path).is_err() {
+        return Err(CommandsError::RenameFileError);
+    }
+
+    // Se conserva el tipo y el hash del blob de la entrada original
+    let entry_data = lines[src_index][src.len()..].to_string();
+    lines[src_index] = format!("{}{}", dst, entry_data);
+    if let Some(dst_index) = dst_index {
+        lines.remove(dst_index);
+    }
+    if let Err(e) = update_index(&git_dir, lines) {
+        let _ = fs::rename(&dst_path, &src_path);
+        return Err(e);
+    }
+
+    Ok(format!("Renaming {} to {}", src, dst))
+}
+
+/// Devuelve el nombre del archivo de una línea del index.
+/// ###Parametros:
+/// 'line': línea del index con el formato `<nombre> <tipo> <hash>`.
+fn get_entry_name(line: &str) -> String {
+    normalize_path(line.split(' ').next().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        add::git_add,
+        commit::git_commit,
+        init::git_init,
+        status::{git_status, git_status_porcelain},
+    };
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+
+    #[test]
+    fn test_git_mv_committed_file() {
+        let directory = "./test_mv";
+        git_init(directory).expect("Error al ejecutar git init");
+        create_file_replace(&format!("{}/old.rs", directory), "Hola Mundo")
+            .expect("Falló al crear el archivo");
+        create_file_replace(&format!("{}/other.rs", directory), "Chau Mundo")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "old.rs").expect("Error al ejecutar git add");
+        git_add(directory, "other.rs").expect("Error al ejecutar git add");
+        let test_commit = test_commit("prueba");
+        git_commit(directory, test_commit).expect("Error al commitear");
+
+        assert_eq!(
+            git_mv(directory, "untracked.rs", "new.rs", false),
+            Err(CommandsError::NotTracked("untracked.rs".to_string()))
+        );
+        assert!(git_mv(directory, "old.rs", "other.rs", false).is_err());
+
+        fs::create_dir_all(format!("{}/src", directory)).expect("Falló al crear el directorio");
+        git_mv(directory, "old.rs", "src", false).expect("Error al ejecutar git mv");
+        assert!(!Path::new(&format!("{}/old.rs", directory)).exists());
+        assert!(Path::new(&format!("{}/src/old.rs", directory)).exists());
+
+        let porcelain = git_status_porcelain(directory, false).expect("Error al ejecutar status");
+        assert_eq!(porcelain, "R  old.rs -> src/old.rs\n");
+        let status = git_status(directory).expect("Error al ejecutar status");
+        assert!(status.contains("renamed:\told.rs -> src/old.rs"));
+        assert!(!status.contains("deleted"));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
@@ -0,0 +1,218 @@
+use super::cat_file::git_cat_file;
+use super::commit::Commit;
+use super::errors::CommandsError;
+use super::rev_parse::resolve_commit;
+use crate::consts::{FILE, GIT_DIR, HEAD, REFS_NOTES};
+use crate::models::client::Client;
+use crate::util::files::{create_directory, create_file_replace, open_file, read_file_string};
+use crate::util::object_format::get_object_format;
+use crate::util::objects::{builder_object_blob, builder_object_commit, builder_object_tree};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Nombre y email con los que se firman los commits de las notas si el cliente no tiene
+/// una identidad configurada.
+const NOTES_AUTHOR_NAME: &str = "Rusteam";
+const NOTES_AUTHOR_EMAIL: &str = "noreply@rusteam.com";
+
+/// Esta función se encarga de llamar al comando notes con los parametros necesarios.
+///
+/// * `git notes add -m <nota> [<commit>]`: agrega o reemplaza la nota del commit (por defecto HEAD).
+/// * `git notes show [<commit>]`: muestra la nota del commit (por defecto HEAD).
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función notes
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_notes(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    let identity = (client.get_name(), client.get_email());
+    match args.as_slice() {
+        ["add", "-m", note] => git_notes_add(directory, HEAD, note, identity),
+        ["add", "-m", note, commit] => git_notes_add(directory, commit, note, identity),
+        ["show"] => git_notes_show(directory, HEAD),
+        ["show", commit] => git_notes_show(directory, commit),
+        _ => Err(CommandsError::InvalidArgumentCountNotesError),
+    }
+}
+
+/// Agrega una nota a un commit sin modificarlo. Si el commit ya tenía una nota, se reemplaza.
+///
+/// Las notas se guardan como blobs en un tree cuyas entradas se nombran con el hash del commit
+/// anotado. Cada cambio crea un nuevo commit de notas, cuyo parent es el anterior, y actualiza
+/// `refs/notes/commits`.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit': revisión del commit a anotar
+/// 'note': texto de la nota
+/// 'identity': nombre y email con los que se firma el commit de notas; si alguno está vacío se
+/// usa la identidad por defecto
+pub fn git_notes_add(
+    directory: &str,
+    commit: &str,
+    note: &str,
+    identity: (&str, &str),
+) -> Result<String, CommandsError> {
+    let commit = resolve_commit(directory, commit)?;
+    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    let object_format = get_object_format(&git_dir);
+    let previous = get_notes_ref_hash(directory)?;
+    let mut notes = match &previous {
+        Some(notes_commit) => read_notes_tree(directory, notes_commit)?,
+        None => BTreeMap::new(),
+    };
+
+    let note_content = if note.ends_with('\n') {
+        note.to_string()
+    } else {
+        format!("{}\n", note)
+    };
+    let blob = builder_object_blob(note_content.into_bytes(), &git_dir, object_format)?;
+    notes.insert(commit.to_string(), blob);
+
+    let tree_content: String = notes
+        .iter()
+        .map(|(annotated, blob)| format!("{} {} {}\n", FILE, annotated, blob))
+        .collect();
+    let tree = builder_object_tree(&git_dir, &tree_content, object_format)?;
+
+    let (name, email) = match identity {
+        (name, email) if !name.trim().is_empty() && !email.trim().is_empty() => (name, email),
+        _ => (NOTES_AUTHOR_NAME, NOTES_AUTHOR_EMAIL),
+    };
+    let mut notes_commit = Commit::new(
+        "Notes added by 'git notes add'".to_string(),
+        name.to_string(),
+        email.to_string(),
+        name.to_string(),
+        email.to_string(),
+    );
+    notes_commit.set_tree(&tree);
+    if let Some(previous) = &previous {
+        notes_commit.set_parents(&[previous]);
+    }
+    let notes_hash = builder_object_commit(&notes_commit.serialize(), &git_dir, object_format)?;
+
+    let notes_ref = format!("{}/{}", git_dir, REFS_NOTES);
+    if let Some(parent) = Path::new(&notes_ref).parent() {
+        create_directory(parent)?;
+    }
+    create_file_replace(&notes_ref, &notes_hash)?;
+
+    Ok(format!("Note added to {}\n", commit))
+}
+
+/// Devuelve la nota de un commit, o un string vacío si no tiene.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit': revisión del commit anotado
+pub fn git_notes_show(directory: &str, commit: &str) -> Result<String, CommandsError> {
+    let commit = resolve_commit(directory, commit)?;
+    let notes_commit = match get_notes_ref_hash(directory)? {
+        Some(notes_commit) => notes_commit,
+        None => return Ok(String::new()),
+    };
+    match read_notes_tree(directory, &notes_commit)?.get(&commit) {
+        Some(blob) => git_cat_file(directory, blob, "-p"),
+        None => Ok(String::new()),
+    }
+}
+
+/// Devuelve el hash del commit de notas al que apunta `refs/notes/commits`, si existe.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn get_notes_ref_hash(directory: &str) -> Result<Option<String>, CommandsError> {
+    let notes_ref = format!("{}/{}/{}", directory, GIT_DIR, REFS_NOTES);
+    if !Path::new(&notes_ref).exists() {
+        return Ok(None);
+    }
+    let hash = read_file_string(open_file(&notes_ref)?)?;
+    Ok(Some(hash.trim().to_string()))
+}
+
+/// Lee el tree de un commit de notas y devuelve, por cada commit anotado, el hash del blob con
+/// su nota.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'notes_commit': hash del commit de notas
+fn read_notes_tree(
+    directory: &str,
+    notes_commit: &str,
+) -> Result<BTreeMap<String, String>, CommandsError> {
+    let content = git_cat_file(directory, notes_commit, "-p")?;
+    let tree = Commit::from_object(&content)?.get_tree().to_string();
+    let mut notes = BTreeMap::new();
+    for line in git_cat_file(directory, &tree, "-p")?.lines() {
+        if let [_, annotated, blob] = line.split_whitespace().collect::<Vec<&str>>()[..] {
+            notes.insert(annotated.to_string(), blob.to_string());
+        }
+    }
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init::git_init, rev_parse::git_rev_parse};
+    use crate::test_utils::{commit_file, TEST_EMAIL, TEST_NAME};
+    use std::fs;
+
+    #[test]
+    fn test_git_notes_add_and_show() {
+        let directory = "./test_notes";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "primero");
+        let second = commit_file(directory, "b.txt", "segundo");
+
+        assert_eq!(git_notes_show(directory, &first), Ok(String::new()));
+
+        let identity = (TEST_NAME, TEST_EMAIL);
+        git_notes_add(directory, &first, "revisado", identity).expect("Falló al agregar la nota");
+        let notes_first = get_notes_ref_hash(directory)
+            .expect("Falló al leer las notas")
+            .expect("No existe la referencia de notas");
+        git_notes_add(directory, "HEAD", "pendiente", ("", "")).expect("Falló al agregar la nota");
+        let notes_before = get_notes_ref_hash(directory).expect("Falló al leer las notas");
+        git_notes_add(directory, &first, "aprobado", identity)
+            .expect("Falló al reemplazar la nota");
+
+        assert_eq!(
+            git_notes_show(directory, &first),
+            Ok("aprobado\n".to_string())
+        );
+        assert_eq!(
+            git_notes_show(directory, &second),
+            Ok("pendiente\n".to_string())
+        );
+        // Las notas no modifican los commits anotados
+        assert_eq!(git_rev_parse(directory, "HEAD"), Ok(second));
+        // Cada cambio crea un commit de notas cuyo parent es el anterior
+        let notes = get_notes_ref_hash(directory)
+            .expect("Falló al leer las notas")
+            .expect("No existe la referencia de notas");
+        let content = git_cat_file(directory, &notes, "-p").expect("Falló al leer el commit");
+        let notes_commit = Commit::from_object(&content).expect("Commit de notas inválido");
+        assert_eq!(
+            notes_commit.get_parents(),
+            &notes_before.clone().into_iter().collect::<Vec<_>>()
+        );
+        // El commit de notas se firma con la identidad del cliente o, si no tiene, la por defecto
+        let author_line = |hash: &str| {
+            let content = git_cat_file(directory, hash, "-p").expect("Falló al leer el commit");
+            content
+                .lines()
+                .find(|line| line.starts_with("author "))
+                .map(str::to_string)
+                .unwrap_or_default()
+        };
+        assert!(author_line(&notes_first)
+            .starts_with(&format!("author {} <{}>", TEST_NAME, TEST_EMAIL)));
+        assert!(
+            author_line(&notes_before.unwrap_or_default()).starts_with(&format!(
+                "author {} <{}>",
+                NOTES_AUTHOR_NAME, NOTES_AUTHOR_EMAIL
+            ))
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
@@ -1,132 +1,265 @@
 use super::errors::CommandsError;
 use crate::commands::branch::get_current_branch;
 use crate::commands::config::GitConfig;
-use crate::commands::fetch::git_fetch_branch;
+use crate::commands::fetch::{git_fetch_branch, FetchStatus};
 use crate::commands::fetch_head::FetchHead;
-use crate::commands::merge::{get_conflict_path, git_merge};
-use crate::git_transport::references::Reference;
+use crate::commands::merge::git_merge_fetch_head;
+use crate::consts::{CONFIG_FILE, GIT_DIR};
 use crate::models::client::Client;
 use crate::util::connections::start_client;
 use std::net::TcpStream;
 
 /// Acepto:
-/// git pull -> pull del branch actual
+/// git pull -> pull de la branch remota que sigue la branch actual
 /// git pull <remote> <branch> -> pull del branch <branch> del repositorio remoto <remote>
 /// Maneja el comando "pull".
 ///
-/// Esta función inicia una operación de pull desde el servidor de Git.
+/// Esta función inicia una operación de pull desde el servidor de Git. Si no se indican el
+/// remoto y la branch, se usan los configurados para la branch actual (`branch.<actual>.remote`
+/// y `branch.<actual>.merge`). Si se indican, la branch actual pasa a seguir esa branch remota.
 ///
 /// # Argumentos
 ///
-/// * `args` - Un vector de argumentos de línea de comandos. Debería estar vacío para una operación de pull.
+/// * `args` - Un vector de argumentos de línea de comandos: vacío, o el remoto y la branch.
 /// * `client` - Una instancia de la estructura `Client` que contiene detalles de la conexión.
 ///
 /// # Devuelve
 ///
-/// * `Result<(), CommandsError>` - Un resultado que indica el éxito o un error encontrado durante la operación de pull.
+/// * `Result<String, CommandsError>` - El resultado del fetch y del merge, o un error encontrado durante la operación de pull.
 ///
 /// # Errores
 ///
 /// * `CommandsError::InvalidArgumentCountPull` - Indica que se proporcionó un número incorrecto de argumentos para el comando pull.
+/// * `CommandsError::NoTrackingInformationForBranch` - La branch actual no sigue ninguna branch remota.
 /// * `CommandsError` - Indica varios errores relacionados con Git que podrían ocurrir durante la operación de pull.
 ///
 pub fn handle_pull(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if !args.is_empty() && args.len() != 2 {
-        return Err(CommandsError::InvalidArgumentCountPull);
-    }
-    println!("Pull ...");
+    let directory = client.get_directory_path();
     let mut status = Vec::new();
-    let path_repo = client.get_directory_path();
-    if args.len() == 2 {
-        let name_remote = args[0];
-        let name_branch = args[1];
-        status.push(format!("Local branch: {}", args[0]));
-        status.push(format!("Remote: {}", args[1]));
-        let current_rfs = Reference::get_current_references(path_repo)?;
-        let mut git_config: GitConfig = GitConfig::new_from_file(path_repo)?;
-        if !git_config.valid_remote(name_remote) {
-            status.push(format!("Remote repository {} does not exist", name_remote));
-            return Ok(status.join("\n"));
-        };
-        git_config.add_branch(
-            current_rfs.get_name(),
-            name_remote,
-            &format!("refs/heads/{}", name_branch),
-        )?;
-        let path_config = format!("{}/.git/config", path_repo);
-        git_config.write_to_file(&path_config)?;
-        status.push("The local branch was associated with the remote".to_string());
-    }
+    let (remote, branch) = match args.as_slice() {
+        [] => get_upstream(directory)?,
+        [remote, branch] => {
+            if !set_upstream(directory, remote, branch)? {
+                return Ok(format!("Remote repository {} does not exist", remote));
+            }
+            status.push("The local branch was associated with the remote".to_string());
+            (remote.to_string(), branch.to_string())
+        }
+        _ => return Err(CommandsError::InvalidArgumentCountPull),
+    };
     let mut socket = start_client(client.get_address())?;
-    println!("Status: {:?}", status);
-    git_pull(
+    status.push(git_pull(
         &mut socket,
-        client.get_ip(),
-        client.get_port(),
-        client.get_directory_path(),
+        directory,
+        &remote,
+        &branch,
         client.clone(),
-        &mut status,
-    )
+    )?);
+    Ok(status.join("\n"))
+}
+
+/// Devuelve el remoto y la branch remota que sigue la branch actual.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio local.
+///
+pub fn get_upstream(directory: &str) -> Result<(String, String), CommandsError> {
+    let current_branch = match get_current_branch(directory) {
+        Ok(current_branch) => current_branch,
+        Err(_) => return Err(CommandsError::PullCurrentBranchNotFound),
+    };
+    let git_config = GitConfig::new_from_file(directory)?;
+    let remote = git_config.get_remote_by_branch_name(&current_branch)?;
+    let branch = git_config.get_merge_by_branch_name(&current_branch)?;
+    Ok((remote, branch))
 }
 
-// Quiero actualizar mi branch actual con los cambios del repositorio remoto
+/// Configura la branch actual para que siga la branch del remoto indicado.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio local.
+/// * `remote` - Nombre del remoto configurado.
+/// * `branch` - Nombre de la branch en el repositorio remoto.
+///
+/// # Retorno
+///
+/// Devuelve `false` si el remoto no existe, en cuyo caso no se modifica la configuración.
+///
+fn set_upstream(directory: &str, remote: &str, branch: &str) -> Result<bool, CommandsError> {
+    let current_branch = match get_current_branch(directory) {
+        Ok(current_branch) => current_branch,
+        Err(_) => return Err(CommandsError::PullCurrentBranchNotFound),
+    };
+    let mut git_config = GitConfig::new_from_file(directory)?;
+    if !git_config.valid_remote(remote) {
+        return Ok(false);
+    }
+    git_config.add_branch(&current_branch, remote, &format!("refs/heads/{}", branch))?;
+    git_config.write_to_file(&format!("{}/{}/{}", directory, GIT_DIR, CONFIG_FILE))?;
+    Ok(true)
+}
+
+/// Trae la branch del repositorio remoto y la mergea en la branch actual.
+///
+/// El fetch actualiza `refs/remotes/<remote>/<branch>` y deja la branch en FETCH_HEAD como la
+/// única que se puede mergear; luego se mergea FETCH_HEAD con `git_merge_fetch_head`, con fast
+/// forward si es posible. Una vez mergeada, la branch se quita de FETCH_HEAD, por lo que un
+/// segundo pull sin cambios en el remoto no vuelve a mergear. Si hay conflictos se dejan las
+/// marcas en los archivos, al igual que en `git_merge`.
+///
+/// # Argumentos
+///
+/// * `socket` - Conexión con el servidor.
+/// * `directory` - Directorio del repositorio local.
+/// * `remote` - Nombre del remoto configurado.
+/// * `branch` - Nombre de la branch en el repositorio remoto.
+/// * `client` - Cliente con la dirección del servidor y el autor del merge commit.
+///
+/// # Errores
+///
+/// Devuelve el error del fetch o del merge.
+///
 pub fn git_pull(
     socket: &mut TcpStream,
-    ip: &str,
-    port: &str,
-    repo_local: &str,
+    directory: &str,
+    remote: &str,
+    branch: &str,
     client: Client,
-    status: &mut Vec<String>,
 ) -> Result<String, CommandsError> {
-    // Obtengo el repositorio remoto
-    println!("Pull del repositorio remoto ...");
-    let current_rfs = match Reference::get_current_references(repo_local) {
-        Ok(rfs) => rfs,
+    let current_branch = match get_current_branch(directory) {
+        Ok(current_branch) => current_branch,
         Err(_) => return Err(CommandsError::PullCurrentBranchNotFound),
     };
-    let name_branch = current_rfs.get_name();
-    let git_config = GitConfig::new_from_file(repo_local)?;
-    let remote_name = git_config.get_remote_by_branch_name(name_branch)?;
-    let result = git_fetch_branch(socket, ip, port, repo_local, &remote_name, name_branch)?;
-    status.push(format!("{}", result));
-    println!("Result del fetch: {}", result);
-
-    // Esto pasa cuando ya hicimos fetch anteriormente y no mergeamos
-    let mut fetch_head = FetchHead::new_from_file(repo_local)?;
-    if !fetch_head.references_needs_update(current_rfs.get_name()) {
+    let fetch_status = git_fetch_branch(
+        socket,
+        client.get_ip(),
+        client.get_port(),
+        directory,
+        remote,
+        branch,
+    )?;
+    if let FetchStatus::BranchNotFound(_) = fetch_status {
+        return Ok(fetch_status.to_string());
+    }
+    let mut status = vec![fetch_status.to_string()];
+
+    // Esto pasa cuando ya se mergeó lo que trajo el último fetch de la branch
+    let mut fetch_head = FetchHead::new_from_file(directory)?;
+    if !fetch_head.references_needs_update(branch) {
         status.push("No updates to merge".to_string());
         return Ok(status.join("\n"));
     }
+    // FETCH_HEAD puede tener otras branches de un fetch anterior: solo se mergea la pedida
+    fetch_head.mark_for_merge(branch);
+    fetch_head.write(directory)?;
 
-    let git_config = GitConfig::new_from_file(repo_local)?;
-    let remote_branch_ref = match git_config.get_remote_branch_ref(name_branch) {
-        Some(rfs) => rfs,
-        None => return Err(CommandsError::PullRemoteBranchNotFound),
-    };
-    println!("Remote branch ref: {}", remote_branch_ref);
-    println!("Mergeando con el repositorio remoto ...");
-    let current_branch = get_current_branch(repo_local)?;
-    let merge_result = git_merge(repo_local, &current_branch, &remote_branch_ref, client)?;
-    println!("Result del merge: {}", merge_result);
+    let url = GitConfig::new_from_file(directory)?.get_remote_url_by_name(remote)?;
+    let message = format!("Merge branch '{}' of {}", branch, url);
+    let merge_result = git_merge_fetch_head(directory, &current_branch, client, Some(&message))?;
+    // Con conflictos la branch queda en FETCH_HEAD, para volver a intentarlo
     if merge_result.contains("CONFLICT") {
-        let path_conflict = get_conflict_path(&merge_result);
-        status.push(format!(
-            "[ERROR] The following file will be overwritten when merged:\n\t{}\nAborting.",
-            path_conflict
-        ));
+        status.push(merge_result);
         status.push("Cannot do pull since there are conflicts".to_string());
         return Ok(status.join("\n"));
     }
 
-    // Actualizo el fetch_head si todo salio bien
-    fetch_head.branch_already_merged(current_rfs.get_name())?;
-    fetch_head.write(repo_local)?;
+    fetch_head.branch_already_merged(branch)?;
+    fetch_head.write(directory)?;
+    status.push(merge_result);
+    Ok(status.join("\n"))
+}
 
-    if merge_result.contains("Already up to date.") {
-        Ok(merge_result)
-    } else {
-        status.push(merge_result);
-        status.push("End Pull".to_string());
-        Ok(status.join("\n"))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::clone::git_clone;
+    use crate::commands::init::git_init;
+    use crate::commands::rev_parse::git_rev_parse;
+    use crate::test_utils::{commit_file, serve_once, test_client};
+    use std::fs;
+
+    /// Clona `<root>/repo` en `local_repo` y devuelve el hash de `master` en el clon.
+    fn clone(root: &'static str, local_repo: &str) -> String {
+        let (mut socket, port, server) = serve_once(root);
+        git_clone(&mut socket, "127.0.0.1", &port, local_repo, "repo")
+            .expect("Falló al clonar el repositorio");
+        server
+            .join()
+            .expect("Falló el servidor")
+            .expect("Falló la solicitud");
+        git_rev_parse(local_repo, "master").expect("Falló al resolver master")
+    }
+
+    /// Hace pull de `origin/master` en `local_repo` y devuelve el resultado.
+    fn pull(root: &'static str, local_repo: &str) -> String {
+        let (mut socket, port, server) = serve_once(root);
+        let client = test_client(local_repo, &port);
+        let result = git_pull(&mut socket, local_repo, "origin", "master", client);
+        server
+            .join()
+            .expect("Falló el servidor")
+            .expect("Falló la solicitud");
+        result.expect("Falló el pull")
+    }
+
+    #[test]
+    fn test_git_pull_fast_forward() {
+        let root = "./test_pull_ff_server";
+        let local_repo = "./test_pull_ff_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        let first = commit_file(&remote, "a.txt", "uno");
+        let cloned = clone(root, local_repo);
+
+        let second = commit_file(&remote, "a.txt", "dos");
+        let upstream = get_upstream(local_repo);
+        let result = pull(root, local_repo);
+        let local_head = git_rev_parse(local_repo, "master");
+        let remote_ref = git_rev_parse(local_repo, "refs/remotes/origin/master");
+        let content = fs::read_to_string(format!("{}/a.txt", local_repo));
+        // Lo que trajo el fetch ya se mergeó, por lo que no se vuelve a mergear
+        let second_result = pull(root, local_repo);
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(cloned, first);
+        // El remoto y la branch por defecto salen de la configuración que deja el clone
+        assert_eq!(upstream, Ok(("origin".to_string(), "master".to_string())));
+        assert!(result.contains("Fast-forward"));
+        assert_eq!(local_head, Ok(second.clone()));
+        assert_eq!(remote_ref, Ok(second));
+        assert_eq!(content.ok().as_deref(), Some("dos"));
+        assert!(second_result.ends_with("No updates to merge"));
+    }
+
+    #[test]
+    fn test_git_pull_reports_conflicts() {
+        let root = "./test_pull_conflict_server";
+        let local_repo = "./test_pull_conflict_local";
+        let remote = format!("{}/repo", root);
+        git_init(&remote).expect("Falló al crear el repositorio remoto");
+        commit_file(&remote, "a.txt", "uno");
+        clone(root, local_repo);
+
+        let remote_head = commit_file(&remote, "a.txt", "remoto");
+        let local_head = commit_file(local_repo, "a.txt", "local");
+        let result = pull(root, local_repo);
+        let head_after = git_rev_parse(local_repo, "master");
+        let content = fs::read_to_string(format!("{}/a.txt", local_repo)).unwrap_or_default();
+        let fetch_head = fs::read_to_string(format!("{}/.git/FETCH_HEAD", local_repo));
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(local_repo).expect("Falló al remover el directorio temporal");
+
+        assert!(result.contains("CONFLICT (content): Merge conflict in a.txt"));
+        assert!(result.ends_with("Cannot do pull since there are conflicts"));
+        // No se crea el merge commit y se dejan las marcas en el archivo
+        assert_eq!(head_after, Ok(local_head));
+        assert!(content.contains("<<<<<<<") && content.contains(">>>>>>>"));
+        // La branch sigue en FETCH_HEAD para poder mergearla luego de resolver los conflictos
+        assert!(fetch_head.is_ok_and(|fetch_head| fetch_head.starts_with(&remote_head)));
     }
 }
@@ -2,7 +2,7 @@ use super::cat_file::git_cat_file;
 use super::checkout::extract_parent_hash;
 use super::errors::CommandsError;
 use crate::commands::config::GitConfig;
-use crate::consts::{CAPABILITIES_PUSH, ZERO_ID};
+use crate::consts::{CAPABILITIES_PUSH, UNPACK_OK};
 use crate::git_transport::git_request::GitRequest;
 use crate::git_transport::references::{
     get_objects_from_hash_to_hash, reference_discovery, Reference,
@@ -13,7 +13,10 @@ use crate::util::connections::{send_flush, send_message, start_client};
 use crate::util::errors::UtilError;
 use crate::util::packfile::send_packfile;
 use crate::util::pkt_line;
-use std::net::TcpStream;
+use crate::util::validation::is_null_obj_id;
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{Shutdown, TcpStream};
 
 pub struct PushBranch {
     pub path_local: String,
@@ -32,7 +35,6 @@ impl PushBranch {
     ) -> Result<Self, CommandsError> {
         // Obtengo el repositorio remoto
         let git_config = GitConfig::new_from_file(&path_local)?;
-        println!("Git config: {:?}", git_config);
         let branch = Reference::create_from_name_branch(&path_local, name_branch)?;
         let remote_name = git_config.get_remote_by_branch_name(branch.get_name())?;
         let url_remote = git_config.get_remote_url_by_name(&remote_name)?;
@@ -63,7 +65,7 @@ impl PushBranch {
         self.status.push(status.to_string());
     }
 
-    fn _add_status_vec(&mut self, status: Vec<String>) {
+    fn add_status_vec(&mut self, status: Vec<String>) {
         for s in status {
             self.status.push(s.to_string());
         }
@@ -163,13 +165,11 @@ pub fn git_push_branch(
     let capacibilities: Vec<String> = CAPABILITIES_PUSH.iter().map(|&s| s.to_string()).collect();
     let server = reference_discovery(socket, message, &push.url_remote, &capacibilities)?;
     let prev_hash = match server.get_remote_reference_hash(push.branch.get_ref_path()) {
-        Some(hash) => hash,          // Actualizo en el remoto
-        None => ZERO_ID.to_string(), // Creo en el remoto
+        Some(hash) => hash,                                 // Actualizo en el remoto
+        None => "0".repeat(server.object_format.hex_len()), // Creo en el remoto
     };
 
-    println!("Prev hash: {}", prev_hash);
     let current_hash = push.get_hash(); // Commit local
-    println!("Current hash: {}", current_hash);
 
     if !is_necessary_to_update(push, &current_hash, &prev_hash)? {
         send_flush(socket, UtilError::CloseConnection)?; // Envio el flush
@@ -183,17 +183,24 @@ pub fn git_push_branch(
         push.branch.get_ref_path(),
         &capacibilities,
     )?;
-    println!("Se actualizo la referencia");
 
     // Envio los objetos que no tiene el remoto
     let objects = get_objects_from_hash_to_hash(&push.path_local, &prev_hash, &current_hash)?;
     if !objects.is_empty() {
         push.add_status("[STATUS] The objects were sent to the remote");
     }
-    send_packfile(socket, &server, objects, true)?;
+    send_packfile(
+        socket,
+        &server,
+        objects.len(),
+        objects.into_iter().map(Ok),
+        true,
+    )?;
+    // El servidor lee el packfile hasta el final de la conexión, así que cierro la escritura
+    let _ = socket.shutdown(Shutdown::Write);
     // Recibo el estatus del push
-    // let status_server = read_status_from_server(socket, 1)?; // 1 -> Solo una branch
-    // push.add_status_vec(status_server);
+    let status_server = read_status_from_server(socket)?;
+    push.add_status_vec(status_server);
     Ok(push.get_status())
 }
 
@@ -269,7 +276,7 @@ pub fn is_update(
     hash_prev: &str,
     count_commits: &mut usize,
 ) -> Result<bool, CommandsError> {
-    if hash_prev == ZERO_ID {
+    if is_null_obj_id(hash_prev) {
         return Ok(true);
     }
 
@@ -289,12 +296,60 @@ pub fn is_update(
     Ok(false)
 }
 
+/// Indica si `hash_prev` es un ancestro de `hash_current` siguiendo los primeros parents.
+///
+/// Un commit no se considera ancestro de sí mismo, y el hash nulo es ancestro de cualquier commit.
+///
+/// # Argumentos
+///
+/// * `directory`: Directorio del repositorio.
+/// * `hash_current`: Hash del commit desde el que se recorre la historia.
+/// * `hash_prev`: Hash del posible ancestro.
+///
 pub fn is_ancestor(
     directory: &str,
     hash_current: &str,
     hash_prev: &str,
 ) -> Result<bool, CommandsError> {
-    if hash_prev == ZERO_ID {
+    is_ancestor_cached(directory, hash_current, hash_prev, None)
+}
+
+/// Parents ya leídos por `is_ancestor_cached`, para no volver a leer los mismos commits al hacer
+/// varias consultas sobre la misma historia.
+#[derive(Debug, Default)]
+pub struct AncestorCache {
+    parents: HashMap<String, Option<String>>,
+    reads: usize,
+}
+
+impl AncestorCache {
+    pub fn new() -> Self {
+        AncestorCache::default()
+    }
+
+    /// Cantidad de commits que se leyeron del repositorio usando este cache.
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
+}
+
+/// Igual que `is_ancestor`, pero recibe opcionalmente un cache con los parents ya leídos. Los
+/// commits que se leen durante el recorrido se agregan al cache.
+///
+/// # Argumentos
+///
+/// * `directory`: Directorio del repositorio.
+/// * `hash_current`: Hash del commit desde el que se recorre la historia.
+/// * `hash_prev`: Hash del posible ancestro.
+/// * `cache`: Cache compartido entre consultas, o `None` para no guardar los parents.
+///
+pub fn is_ancestor_cached(
+    directory: &str,
+    hash_current: &str,
+    hash_prev: &str,
+    mut cache: Option<&mut AncestorCache>,
+) -> Result<bool, CommandsError> {
+    if is_null_obj_id(hash_prev) {
         return Ok(true);
     }
 
@@ -302,15 +357,41 @@ pub fn is_ancestor(
         return Ok(false);
     }
 
-    let commit = git_cat_file(directory, hash_current, "-p")?;
-    if let Some(parent_hash) = extract_parent_hash(&commit) {
-        if hash_prev == parent_hash || is_ancestor(directory, parent_hash, hash_prev)? {
+    let mut current = hash_current.to_string();
+    while let Some(parent_hash) = read_first_parent(directory, &current, &mut cache)? {
+        if parent_hash == hash_prev {
             return Ok(true);
         }
-    };
+        current = parent_hash;
+    }
     Ok(false)
 }
 
+/// Obtiene el primer parent de un commit, buscándolo primero en el cache si lo hay.
+///
+/// # Argumentos
+///
+/// * `directory`: Directorio del repositorio.
+/// * `hash`: Hash del commit.
+/// * `cache`: Cache de parents ya leídos.
+///
+fn read_first_parent(
+    directory: &str,
+    hash: &str,
+    cache: &mut Option<&mut AncestorCache>,
+) -> Result<Option<String>, CommandsError> {
+    if let Some(parent) = cache.as_ref().and_then(|cache| cache.parents.get(hash)) {
+        return Ok(parent.clone());
+    }
+    let commit = git_cat_file(directory, hash, "-p")?;
+    let parent = extract_parent_hash(&commit).map(String::from);
+    if let Some(cache) = cache {
+        cache.reads += 1;
+        cache.parents.insert(hash.to_string(), parent.clone());
+    }
+    Ok(parent)
+}
+
 /// Actualiza una referencia en el servidor Git con los hashes de commits proporcionados.
 ///
 /// # Argumentos
@@ -346,30 +427,44 @@ fn reference_update(
     Ok(())
 }
 
-fn _read_status_from_server(
-    socket: &mut TcpStream,
-    number_requests: usize,
-) -> Result<Vec<String>, CommandsError> {
-    let mut status = Vec::new();
-    let commad_status = pkt_line::read_pkt_line(socket)?;
-    let commad_status = match String::from_utf8(commad_status) {
-        Ok(status) => status,
-        Err(_) => return Err(CommandsError::PushInvalidStatusFromServer),
+/// Lee el estado del push enviado por el servidor (capacidad `report-status`).
+///
+/// El servidor responde con `unpack ok` o `unpack <error>`, seguido de una línea
+/// `ok <ref>` o `ng <ref> <motivo>` por cada referencia solicitada, terminando con un flush.
+///
+/// # Argumentos
+///
+/// * `socket`: Referencia mutable a un flujo utilizado para la comunicación con el servidor.
+///
+/// # Devuelve
+///
+/// Un `Result<Vec<String>, CommandsError>` con las líneas de estado a mostrar al usuario.
+///
+fn read_status_from_server(socket: &mut dyn Read) -> Result<Vec<String>, CommandsError> {
+    let lines = pkt_line::read(socket)?;
+    let mut lines = lines.into_iter().map(String::from_utf8);
+    let unpack_status = match lines.next() {
+        Some(Ok(line)) => line,
+        _ => return Err(CommandsError::PushInvalidStatusFromServer),
     };
-    println!("Status: {}", commad_status);
-    if commad_status != "unpack ok" {
-        status.push("Push degenado por el servidor".to_string());
-        status.push(format!("Error: {}", commad_status));
-        return Ok(status);
-    }
 
-    for _ in 0..number_requests {
-        let line = pkt_line::read_pkt_line(socket)?;
-        let line = match String::from_utf8(line) {
+    let mut status = Vec::new();
+    if unpack_status != UNPACK_OK.trim_end() {
+        status.push("[ERROR] The remote failed to unpack the objects".to_string());
+        status.push(format!("\t{}", unpack_status));
+    }
+    for line in lines {
+        let line = match line {
             Ok(line) => line,
             Err(_) => return Err(CommandsError::PushInvalidStatusFromServer),
         };
-        status.push(line);
+        if let Some(reference) = line.strip_prefix("ok ") {
+            status.push(format!("[OK] {}", reference));
+        } else if let Some(reason) = line.strip_prefix("ng ") {
+            status.push(format!("[REJECTED] {}", reason));
+        } else {
+            return Err(CommandsError::PushInvalidStatusFromServer);
+        }
     }
     Ok(status)
 }
@@ -378,7 +473,8 @@ fn _read_status_from_server(
 mod tests {
 
     use crate::commands::{add::git_add, commit::*, init::git_init};
-    use crate::util::files::{open_file, read_file_string};
+    use crate::consts::ZERO_ID;
+    use crate::util::files::{file_exists, open_file, read_file_string};
 
     use super::*;
     use std::fs;
@@ -437,4 +533,211 @@ mod tests {
 
         assert_eq!(result, true)
     }
+
+    #[test]
+    fn test_is_ancestor_cached_long_history() {
+        let directory = "./test_is_ancestor_cached";
+        git_init(directory).expect("Falló en el comando init");
+        let branch = format!("{}/.git/refs/heads/master", directory);
+        let mut hashes = Vec::new();
+        for i in 0..40 {
+            let commit = Commit::new(
+                format!("commit {}", i),
+                "Juan".to_string(),
+                "jdr@fi.uba.ar".to_string(),
+                "Juan".to_string(),
+                "jdr@fi.uba.ar".to_string(),
+            );
+            let file = format!("{}.txt", i);
+            fs::write(format!("{}/{}", directory, file), "contenido").expect("Error write");
+            git_add(directory, &file).expect("Fallo en el comando add");
+            git_commit(directory, commit).expect("Error commit");
+            let file_branch = open_file(&branch).expect("Error open file");
+            hashes.push(read_file_string(file_branch).expect("Error read file"));
+        }
+        let (first, last) = (&hashes[0], &hashes[hashes.len() - 1]);
+
+        let mut cache = AncestorCache::new();
+        assert_eq!(
+            is_ancestor_cached(directory, last, first, Some(&mut cache)),
+            Ok(true)
+        );
+        let first_walk = cache.reads();
+        assert_eq!(first_walk, hashes.len() - 1);
+
+        // Las consultas siguientes sobre la misma historia no vuelven a leer commits
+        assert_eq!(
+            is_ancestor_cached(directory, last, &hashes[1], Some(&mut cache)),
+            Ok(true)
+        );
+        assert_eq!(
+            is_ancestor_cached(directory, first, last, Some(&mut cache)),
+            Ok(false)
+        );
+        assert_eq!(
+            is_ancestor_cached(directory, last, last, Some(&mut cache)),
+            Ok(false)
+        );
+        assert_eq!(cache.reads(), first_walk + 1);
+        assert_eq!(is_ancestor(directory, last, first), Ok(true));
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+    }
+
+    #[cfg(unix)]
+    fn write_hook(repo: &str, name: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let path = format!("{}/.git/hooks/{}", repo, name);
+        fs::create_dir_all(format!("{}/.git/hooks", repo)).expect("Falló al crear los hooks");
+        fs::write(&path, script).expect("Falló al crear el hook");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .expect("Falló al dar permisos al hook");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_push_rejected_by_pre_receive_hook() {
+        use crate::commands::branch::git_branch_create;
+        use std::net::TcpListener;
+        use std::thread;
+
+        let root = "./test_push_hooks_server";
+        let local = "./test_push_hooks_local";
+        let repo = format!("{}/repo", root);
+        git_init(&repo).expect("Falló en el comando init");
+        write_hook(
+            &repo,
+            "pre-receive",
+            "#!/bin/sh\nwhile read old new ref; do\n  if [ \"$ref\" = refs/heads/blocked ]; then\n    echo \"rama $ref bloqueada\" >&2\n    exit 1\n  fi\ndone\n",
+        );
+        write_hook(&repo, "post-receive", "#!/bin/sh\ncat > post-receive.log\n");
+
+        git_init(local).expect("Falló en el comando init");
+        fs::write(format!("{}/hola.txt", local), "Hola").expect("Error write");
+        git_add(local, "hola.txt").expect("Fallo en el comando add");
+        let commit = Commit::new(
+            "prueba".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(local, commit).expect("Error commit");
+        git_branch_create(local, "blocked").expect("Error branch");
+        let mut git_config = GitConfig::new_from_file(local).expect("Error config");
+        git_config
+            .add_remote("origin", "repo")
+            .expect("Error remote");
+        git_config
+            .add_branch("master", "origin", "refs/heads/master")
+            .expect("Error branch config");
+        git_config
+            .add_branch("blocked", "origin", "refs/heads/blocked")
+            .expect("Error branch config");
+        git_config
+            .write_to_file(&format!("{}/.git/config", local))
+            .expect("Error config");
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar el servidor");
+        let port = listener
+            .local_addr()
+            .expect("Falló al obtener el puerto")
+            .port()
+            .to_string();
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().expect("Falló al aceptar la conexión");
+                let request =
+                    GitRequest::read_git_request(&mut stream).expect("Solicitud inválida");
+                request
+                    .execute(&mut stream, root)
+                    .expect("Falló el receive-pack");
+            }
+        });
+        let push = |branch: &str| {
+            let mut socket =
+                TcpStream::connect(format!("127.0.0.1:{}", port)).expect("Falló al conectar");
+            let mut push =
+                PushBranch::new(local.to_string(), branch, Vec::new()).expect("Error push branch");
+            git_push_branch(&mut socket, "127.0.0.1", &port, &mut push).expect("Error push")
+        };
+        let status_blocked = push("blocked");
+        let status_master = push("master");
+        server.join().expect("Falló el servidor");
+
+        let hash = fs::read_to_string(format!("{}/.git/refs/heads/master", local))
+            .expect("Error read ref");
+        let blocked_exists = file_exists(&format!("{}/.git/refs/heads/blocked", repo));
+        let remote_master = fs::read_to_string(format!("{}/.git/refs/heads/master", repo));
+        let post_receive_log = fs::read_to_string(format!("{}/post-receive.log", repo));
+        fs::remove_dir_all(root).expect("Falló al remover los directorios");
+        fs::remove_dir_all(local).expect("Falló al remover los directorios");
+
+        assert!(status_blocked.contains(
+            "[REJECTED] refs/heads/blocked pre-receive hook declined: rama refs/heads/blocked bloqueada"
+        ));
+        assert!(!blocked_exists);
+        assert!(status_master.contains("[OK] refs/heads/master"));
+        assert_eq!(remote_master.expect("Error read ref").trim(), hash.trim());
+        assert_eq!(
+            post_receive_log.expect("No se ejecutó el post-receive"),
+            format!("{} {} refs/heads/master\n", ZERO_ID, hash.trim())
+        );
+    }
+
+    #[test]
+    fn test_push_new_branch_sha256() {
+        use crate::commands::init::git_init_with_format;
+        use crate::test_utils::{commit_file, serve_once};
+        use crate::util::object_format::ObjectFormat;
+
+        let root = "./test_push_sha256_server";
+        let local = "./test_push_sha256_local";
+        git_init_with_format(&format!("{}/repo", root), "master", ObjectFormat::Sha256)
+            .expect("Falló en el comando init");
+        git_init_with_format(local, "master", ObjectFormat::Sha256)
+            .expect("Falló en el comando init");
+        let hash = commit_file(local, "hola.txt", "Hola");
+        let mut git_config = GitConfig::new_from_file(local).expect("Error config");
+        git_config
+            .add_remote("origin", "repo")
+            .expect("Error remote");
+        git_config
+            .add_branch("master", "origin", "refs/heads/master")
+            .expect("Error branch config");
+        git_config
+            .write_to_file(&format!("{}/.git/config", local))
+            .expect("Error config");
+
+        let (mut socket, port, server) = serve_once(root);
+        let mut push =
+            PushBranch::new(local.to_string(), "master", Vec::new()).expect("Error push branch");
+        let status = git_push_branch(&mut socket, "127.0.0.1", &port, &mut push);
+        let served = server.join().expect("Falló el servidor");
+        let remote_master = fs::read_to_string(format!("{}/repo/.git/refs/heads/master", root));
+        fs::remove_dir_all(root).expect("Falló al remover los directorios");
+        fs::remove_dir_all(local).expect("Falló al remover los directorios");
+
+        assert_eq!(hash.len(), ObjectFormat::Sha256.hex_len());
+        assert!(served.is_ok());
+        assert!(status
+            .expect("Error push")
+            .contains("[OK] refs/heads/master"));
+        assert_eq!(remote_master.expect("Error read ref").trim(), hash);
+    }
+
+    #[test]
+    fn test_read_status_from_server() {
+        let response =
+            "000eunpack ok\n0019ok refs/heads/master\n0027ng refs/heads/dev non-fast-forward\n0000";
+        let mut socket = std::io::Cursor::new(response.as_bytes().to_vec());
+        let status = read_status_from_server(&mut socket).expect("Error status");
+        assert_eq!(
+            status,
+            vec![
+                "[OK] refs/heads/master".to_string(),
+                "[REJECTED] refs/heads/dev non-fast-forward".to_string()
+            ]
+        );
+    }
 }
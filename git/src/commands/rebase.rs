@@ -7,7 +7,7 @@ use super::merge::{
 };
 use crate::commands::commit::rebase_commit;
 use crate::models::client::Client;
-use crate::util::files::{create_file_replace, open_file, read_file_string};
+use crate::util::files::{open_file, read_file_string};
 
 /// Esta función se encarga de llamar al comando rebase con los parametros necesarios.
 /// ###Parametros:
@@ -42,7 +42,8 @@ pub fn git_rebase(
     let log_rebase_branch = get_log_from_branch(directory, &branch_to_rebase_hash)?;
 
     formatted_result.push_str("First, rewinding head to replay your work on top of it...\n");
-    let (result_merge, _) = perform_merge(&current_branch, branch_name, directory, "rebase")?;
+    let (result_merge, _, _) =
+        perform_merge(&current_branch, branch_name, directory, "rebase", false)?;
 
     formatted_result.push_str(result_merge.as_str());
     if !result_merge.contains("CONFLICT") {
@@ -53,7 +54,6 @@ pub fn git_rebase(
             client,
             logs_just_in_current_branch,
             &current_branch,
-            branch_name,
             &branch_to_rebase_hash,
             &mut formatted_result,
         )?;
@@ -63,25 +63,22 @@ pub fn git_rebase(
 }
 
 /// Crea un nuevo commit por cada commit que está en la branch actual y no en la branch sobre la
-/// que se hizo el rebase. Con estos nuevos commits se actualiza el log.
+/// que se hizo el rebase. Cada nuevo commit queda registrado en el reflog.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local
 /// 'client': Cliente que contiene la información del cliente que se conectó
 /// 'log_current_branch': logs de la branch actual
 /// 'current_branch': nombre de la branch actual
-/// 'branch_name': nombre de la branch sobre la que se hizo el rebase
+/// 'branch_to_rebase_hash': hash de la branch sobre la que se hizo el rebase
 /// 'formatted_result': String que contiene el resultado de git rebase formateado
 fn create_new_commits(
     directory: &str,
     client: Client,
     log_current_branch: Vec<String>,
     current_branch: &str,
-    branch_name: &str,
     branch_to_rebase_hash: &str,
     formatted_result: &mut String,
 ) -> Result<(), CommandsError> {
-    update_log_current_branch(directory, current_branch, branch_name)?;
-
     let log_current_branch_reversed = log_current_branch
         .iter()
         .rev()
@@ -113,25 +110,6 @@ fn create_new_commits(
     Ok(())
 }
 
-/// Actualiza el log de la branch actual con el log de la branch sobre la que se hizo el rebase.
-/// ###Parametros:
-/// 'directory': directorio del repositorio local
-/// 'current_branch': nombre de la branch actual
-/// 'rebase_branch': nombre de la branch sobre la que se hizo el rebase
-fn update_log_current_branch(
-    directory: &str,
-    current_branch: &str,
-    rebase_branch: &str,
-) -> Result<(), CommandsError> {
-    let path_rebase_log = format!("{}/.git/logs/refs/heads/{}", directory, rebase_branch);
-    let file_rebase_log = open_file(&path_rebase_log)?;
-    let content_rebase_log = read_file_string(file_rebase_log)?;
-    let path_current_log = format!("{}/.git/logs/refs/heads/{}", directory, current_branch);
-
-    create_file_replace(&path_current_log, &content_rebase_log)?;
-    Ok(())
-}
-
 /// Obtiene el mensaje de un commit.
 /// ###Parametros:
 /// 'content_commit': contenido del commit
@@ -157,9 +135,14 @@ mod tests {
 
     use super::*;
     use crate::commands::{
-        add::git_add, branch::git_branch_create, checkout::git_checkout_switch, commit::git_commit,
-        init::git_init, log::git_log,
+        add::git_add,
+        branch::git_branch_create,
+        checkout::git_checkout_switch,
+        commit::git_commit,
+        init::git_init,
+        log::{git_log, LogOptions},
     };
+    use crate::test_utils::{test_client, test_commit};
 
     #[test]
     fn test_rebase() {
@@ -185,13 +168,7 @@ mod tests {
 
         git_add(directory, "holamundo.txt").expect("Error al hacer git add");
 
-        let test_commit1 = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba");
 
         git_commit(directory, test_commit1).expect("Error al hacer git commit");
 
@@ -200,29 +177,18 @@ mod tests {
 
         git_add(directory, "holamundo2.txt").expect("Error al hacer git add");
 
-        let test_commit2 = Commit::new(
-            "prueba otra".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit2 = test_commit("prueba otra");
 
         git_commit(directory, test_commit2).expect("Error al hacer git commit");
 
         git_add(directory, "holamundo3.txt").expect("Error al hacer git add");
 
-        let test_commit3 = Commit::new(
-            "aa".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit3 = test_commit("aa");
 
         git_commit(directory, test_commit3).expect("Error al hacer git commit");
 
-        let log_nueva_branch = git_log(directory).expect("Error al hacer git log");
+        let log_nueva_branch =
+            git_log(directory, &LogOptions::default()).expect("Error al hacer git log");
 
         git_checkout_switch(directory, "master").expect("Error al hacer git checkout");
 
@@ -234,29 +200,16 @@ mod tests {
 
         git_add(directory, "holamundo2.txt").expect("Error al hacer git add");
 
-        let test_commit4 = Commit::new(
-            "bb".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit4 = test_commit("bb");
 
         git_commit(directory, test_commit4).expect("Error al hacer git commit");
 
-        let client = Client::new(
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "19992020".to_string(),
-            "9090".to_string(),
-            "localhost".to_string(),
-            "./".to_string(),
-            "master".to_string(),
-        );
+        let client = test_client("./", "9090");
 
         let result = git_rebase(directory, "nueva_branch", client);
 
-        let log_current_branch_after_rebase = git_log(directory).expect("Error al hacer git log");
+        let log_current_branch_after_rebase =
+            git_log(directory, &LogOptions::default()).expect("Error al hacer git log");
         assert!(log_current_branch_after_rebase.contains(&log_nueva_branch));
 
         fs::remove_dir_all(directory).expect("Error al borrar el directorio");
@@ -287,13 +240,7 @@ mod tests {
 
         git_add(directory, "holamundo.txt").expect("Error al hacer git add");
 
-        let test_commit1 = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba");
 
         git_commit(directory, test_commit1).expect("Error al hacer git commit");
 
@@ -302,29 +249,18 @@ mod tests {
 
         git_add(directory, "holamundo2.txt").expect("Error al hacer git add");
 
-        let test_commit2 = Commit::new(
-            "prueba otra".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit2 = test_commit("prueba otra");
 
         git_commit(directory, test_commit2).expect("Error al hacer git commit");
 
         git_add(directory, "holamundo3.txt").expect("Error al hacer git add");
 
-        let test_commit3 = Commit::new(
-            "aa".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit3 = test_commit("aa");
 
         git_commit(directory, test_commit3).expect("Error al hacer git commit");
 
-        let log_nueva_branch = git_log(directory).expect("Error al hacer git log");
+        let log_nueva_branch =
+            git_log(directory, &LogOptions::default()).expect("Error al hacer git log");
 
         git_checkout_switch(directory, "master").expect("Error al hacer git checkout");
 
@@ -337,29 +273,16 @@ mod tests {
 
         git_add(directory, "holamundo2.txt").expect("Error al hacer git add");
 
-        let test_commit4 = Commit::new(
-            "bb".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit4 = test_commit("bb");
 
         git_commit(directory, test_commit4).expect("Error al hacer git commit");
 
-        let client = Client::new(
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "19992020".to_string(),
-            "9090".to_string(),
-            "localhost".to_string(),
-            "./".to_string(),
-            "master".to_string(),
-        );
+        let client = test_client("./", "9090");
 
         let result = git_rebase(directory, "nueva_branch", client);
 
-        let log_current_branch_after_rebase = git_log(directory).expect("Error al hacer git log");
+        let log_current_branch_after_rebase =
+            git_log(directory, &LogOptions::default()).expect("Error al hacer git log");
         assert!(!log_current_branch_after_rebase.contains(&log_nueva_branch));
 
         fs::remove_dir_all(directory).expect("Error al borrar el directorio");
@@ -0,0 +1,703 @@
+use super::branch::get_current_branch;
+use super::errors::CommandsError;
+use super::rev_parse::git_rev_parse;
+use crate::consts::{GIT_DIR, HEAD, LOGS_DIR, PARENT_INITIAL, REFS_HEADS, REFS_REMOTES};
+use crate::models::client::Client;
+use crate::util::files::{
+    file_exists, get_git_dir, get_git_path, open_file, read_file_string, write_file_atomic,
+};
+use crate::util::object_format::get_object_format;
+use crate::util::objects::{abbreviate_hash, get_object_hashes};
+use crate::util::validation::{is_null_obj_id, is_valid_obj_id_any_format};
+use chrono::Local;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Antigüedad a partir de la cual `git reflog expire` y `git gc` eliminan las entradas de los
+/// reflogs si no se indica otra (90 días, como `gc.reflogExpire` en git).
+pub const REFLOG_EXPIRE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Entrada del reflog de una referencia.
+///
+/// - `old_hash`: hash al que apuntaba la referencia antes del cambio.
+/// - `new_hash`: hash al que apunta la referencia luego del cambio.
+/// - `identity`: nombre y mail de quien realizó el cambio (`Nombre <mail>`).
+/// - `timestamp`: fecha del cambio en segundos desde epoch.
+/// - `timezone`: zona horaria del cambio, por ejemplo `-0300`.
+/// - `message`: descripción del cambio, por ejemplo `commit: agrega tests`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    pub old_hash: String,
+    pub new_hash: String,
+    pub identity: String,
+    pub timestamp: i64,
+    pub timezone: String,
+    pub message: String,
+}
+
+impl ReflogEntry {
+    /// Interpreta una línea del reflog con el formato de git:
+    /// `<hash viejo> <hash nuevo> <Nombre> <<mail>> <timestamp> <zona>\t<mensaje>`.
+    /// ###Parametros:
+    /// 'line': línea del reflog
+    pub fn parse(line: &str) -> Option<ReflogEntry> {
+        let (header, message) = line.split_once('\t').unwrap_or((line, ""));
+        let (old_hash, rest) = header.split_once(' ')?;
+        let (new_hash, signature) = rest.split_once(' ')?;
+        if !is_hash(old_hash) || !is_hash(new_hash) {
+            return None;
+        }
+        let (identity, timestamp, timezone) = parse_signature(signature)?;
+        Some(ReflogEntry {
+            old_hash: old_hash.to_string(),
+            new_hash: new_hash.to_string(),
+            identity,
+            timestamp,
+            timezone,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// Esta función se encarga de llamar al comando reflog con los parametros necesarios.
+///
+/// * `git reflog [show] [<referencia>]`: muestra el reflog de la referencia (por defecto HEAD).
+/// * `git reflog expire [--expire=<n>.days|now|never] [--max-count=<n>] [--all | <referencia>]`:
+///   elimina las entradas más viejas que la antigüedad indicada (90 días por defecto) o que
+///   exceden la cantidad indicada, de la referencia (por defecto HEAD) o de todos los reflogs.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función reflog
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_reflog(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        ["expire", options @ ..] => handle_reflog_expire(directory, options),
+        [] | ["show"] => git_reflog(directory, HEAD),
+        [reference] | ["show", reference] => git_reflog(directory, reference),
+        _ => Err(CommandsError::InvalidArgumentCountReflogError),
+    }
+}
+
+/// Interpreta las opciones de `git reflog expire` y elimina las entradas vencidas.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'options': opciones del comando, luego de `expire`
+fn handle_reflog_expire(directory: &str, options: &[&str]) -> Result<String, CommandsError> {
+    let mut max_age = Some(REFLOG_EXPIRE);
+    let mut max_count = None;
+    let mut all = false;
+    let mut reference = None;
+    for option in options {
+        if let Some(expire) = option.strip_prefix("--expire=") {
+            max_age = parse_expire(expire)?;
+        } else if let Some(count) = option.strip_prefix("--max-count=") {
+            match count.parse::<usize>() {
+                Ok(count) => max_count = Some(count),
+                Err(_) => return Err(CommandsError::InvalidArgumentCountReflogError),
+            }
+        } else if *option == "--all" {
+            all = true;
+        } else if !option.starts_with('-') && reference.is_none() {
+            reference = Some(*option);
+        } else {
+            return Err(CommandsError::InvalidArgumentCountReflogError);
+        }
+    }
+    match (all, reference) {
+        (true, None) => git_reflog_expire_all(directory, max_age, max_count)?,
+        (false, reference) => {
+            git_reflog_expire(directory, reference.unwrap_or(HEAD), max_age, max_count)?
+        }
+        (true, Some(_)) => return Err(CommandsError::InvalidArgumentCountReflogError),
+    };
+    Ok(String::new())
+}
+
+/// Interpreta la antigüedad de `--expire`: `now` vence todas las entradas, `never` ninguna y
+/// `<n>.days` las que tienen más de n días.
+/// ###Parametros:
+/// 'expire': valor de la opción
+fn parse_expire(expire: &str) -> Result<Option<Duration>, CommandsError> {
+    match expire {
+        "now" => return Ok(Some(Duration::ZERO)),
+        "never" => return Ok(None),
+        _ => {}
+    }
+    let days = expire
+        .strip_suffix(".days")
+        .or_else(|| expire.strip_suffix(".day"))
+        .and_then(|days| days.parse::<u64>().ok());
+    match days {
+        Some(days) => Ok(Some(Duration::from_secs(days * 24 * 60 * 60))),
+        None => Err(CommandsError::InvalidReflogExpire(expire.to_string())),
+    }
+}
+
+/// Muestra el reflog de una referencia, de la entrada más nueva a la más vieja, con el formato
+/// `<hash abreviado> <referencia>@{n}: <mensaje>`.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': HEAD, el nombre de una branch local o remota, o una referencia completa
+pub fn git_reflog(directory: &str, reference: &str) -> Result<String, CommandsError> {
+    let entries = read_reflog(directory, reference)?;
+    let hashes = get_object_hashes(&format!("{}/{}", directory, GIT_DIR))?;
+    let mut formatted_result = String::new();
+    for (n, entry) in entries.iter().enumerate() {
+        formatted_result.push_str(&format!(
+            "{} {}@{{{}}}: {}\n",
+            abbreviate_hash(&entry.new_hash, &hashes),
+            reference,
+            n,
+            entry.message
+        ));
+    }
+    Ok(formatted_result)
+}
+
+/// Lee el reflog de una referencia y devuelve sus entradas de la más nueva a la más vieja.
+///
+/// Además del formato de git, se admiten los logs de branches que guarda este cliente, que
+/// contienen el contenido de cada commit precedido por su hash.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': HEAD, el nombre de una branch local o remota, o una referencia completa
+pub fn read_reflog(directory: &str, reference: &str) -> Result<Vec<ReflogEntry>, CommandsError> {
+    let path = get_reflog_path(directory, reference);
+    if !file_exists(&path) {
+        return Err(CommandsError::ReflogNotFound(reference.to_string()));
+    }
+    let content = read_file_string(open_file(&path)?)?;
+    let mut entries = parse_reflog(&content);
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Resuelve `<referencia>@{n}` al hash al que apuntaba la referencia hace n cambios.
+/// `@{0}` es el valor actual y `@{1}` el hash anterior al último cambio.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': referencia cuyo reflog se consulta
+/// 'n': cantidad de cambios hacia atrás
+pub fn resolve_reflog_entry(
+    directory: &str,
+    reference: &str,
+    n: usize,
+) -> Result<String, CommandsError> {
+    let entries = read_reflog(directory, reference)?;
+    match entries.get(n) {
+        Some(entry) => Ok(entry.new_hash.to_string()),
+        None => Err(CommandsError::UnknownRevision(format!(
+            "{}@{{{}}}",
+            reference, n
+        ))),
+    }
+}
+
+/// Agrega una entrada al reflog de una referencia con el formato de git.
+///
+/// Todas las operaciones que modifican una referencia registran el cambio con esta función,
+/// que agrega la fecha actual a la identidad. Un hash viejo vacío indica que la referencia
+/// no existía.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': referencia modificada, por ejemplo HEAD o refs/heads/master
+/// 'old_hash': hash al que apuntaba la referencia
+/// 'new_hash': hash al que apunta la referencia luego del cambio
+/// 'identity': `Nombre <mail>` de quien realizó el cambio
+/// 'message': descripción del cambio
+pub fn append_reflog(
+    directory: &str,
+    reference: &str,
+    old_hash: &str,
+    new_hash: &str,
+    identity: &str,
+    message: &str,
+) -> Result<(), CommandsError> {
+    let path = get_reflog_path(directory, reference);
+    if let Some(parent) = Path::new(&path).parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return Err(CommandsError::CreateDirError);
+        }
+    }
+    let old_hash = match old_hash.trim() {
+        "" => "0".repeat(get_object_format(&get_git_dir(directory)).hex_len()),
+        old_hash => old_hash.to_string(),
+    };
+    let now = Local::now();
+    let line = format!(
+        "{} {} {} {} {}\t{}\n",
+        old_hash,
+        new_hash.trim(),
+        identity.trim(),
+        now.timestamp(),
+        now.format("%z"),
+        message.lines().next().unwrap_or_default()
+    );
+    let mut file = match OpenOptions::new().append(true).create(true).open(path) {
+        Ok(file) => file,
+        Err(_) => return Err(CommandsError::OpenFileError),
+    };
+    if file.write_all(line.as_bytes()).is_err() {
+        return Err(CommandsError::WriteFileError);
+    }
+    Ok(())
+}
+
+/// Agrega una entrada al reflog de una branch local y, si es la branch actual, también al de HEAD.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'branch': nombre de la branch modificada
+/// 'old_hash': hash al que apuntaba la branch
+/// 'new_hash': hash al que apunta la branch luego del cambio
+/// 'identity': `Nombre <mail>` de quien realizó el cambio
+/// 'message': descripción del cambio
+pub fn append_branch_reflog(
+    directory: &str,
+    branch: &str,
+    old_hash: &str,
+    new_hash: &str,
+    identity: &str,
+    message: &str,
+) -> Result<(), CommandsError> {
+    let branch_ref = format!("{}/{}", REFS_HEADS, branch);
+    append_reflog(
+        directory,
+        &branch_ref,
+        old_hash,
+        new_hash,
+        identity,
+        message,
+    )?;
+    if get_current_branch(directory).is_ok_and(|current| current == branch) {
+        append_reflog(directory, HEAD, old_hash, new_hash, identity, message)?;
+    }
+    Ok(())
+}
+
+/// Elimina el reflog de una referencia, por ejemplo al borrar una branch.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': referencia eliminada
+pub fn delete_reflog(directory: &str, reference: &str) -> Result<(), CommandsError> {
+    let path = get_reflog_path(directory, reference);
+    if file_exists(&path) && fs::remove_file(path).is_err() {
+        return Err(CommandsError::RemoveFileError);
+    }
+    Ok(())
+}
+
+/// Elimina del reflog de una referencia las entradas más viejas que `max_age` y las que
+/// exceden las `max_count` más recientes. La entrada más reciente que corresponde al valor
+/// actual de la referencia nunca se elimina.
+///
+/// Las entradas que quedan se escriben tal como estaban en el archivo, por lo que se conserva
+/// su formato, y el archivo se reemplaza de forma atómica.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': HEAD, el nombre de una branch local o remota, o una referencia completa
+/// 'max_age': antigüedad máxima de las entradas, o `None` para no vencerlas por antigüedad
+/// 'max_count': cantidad máxima de entradas, o `None` para no limitarla
+///
+/// Devuelve la cantidad de entradas eliminadas.
+pub fn git_reflog_expire(
+    directory: &str,
+    reference: &str,
+    max_age: Option<Duration>,
+    max_count: Option<usize>,
+) -> Result<usize, CommandsError> {
+    let path = get_reflog_path(directory, reference);
+    if !file_exists(&path) {
+        return Err(CommandsError::ReflogNotFound(reference.to_string()));
+    }
+    let content = read_file_string(open_file(&path)?)?;
+    let (mut kept, records) = parse_reflog_records(&content);
+
+    let current_index = git_rev_parse(directory, reference)
+        .ok()
+        .and_then(|current| {
+            records
+                .iter()
+                .rposition(|(entry, _)| entry.new_hash == current)
+        });
+    let oldest = max_age.map(|max_age| Local::now().timestamp() - max_age.as_secs() as i64);
+    let first_counted = max_count.map_or(0, |max_count| records.len().saturating_sub(max_count));
+
+    let mut removed = 0;
+    for (index, (entry, raw)) in records.iter().enumerate() {
+        let expired =
+            index < first_counted || oldest.is_some_and(|oldest| entry.timestamp < oldest);
+        if expired && current_index != Some(index) {
+            removed += 1;
+            continue;
+        }
+        kept.push_str(raw);
+    }
+    if removed > 0 {
+        write_file_atomic(&path, &kept)?;
+    }
+    Ok(removed)
+}
+
+/// Aplica `git_reflog_expire` a todos los reflogs del repositorio.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'max_age': antigüedad máxima de las entradas, o `None` para no vencerlas por antigüedad
+/// 'max_count': cantidad máxima de entradas de cada reflog, o `None` para no limitarla
+///
+/// Devuelve la cantidad de entradas eliminadas.
+pub fn git_reflog_expire_all(
+    directory: &str,
+    max_age: Option<Duration>,
+    max_count: Option<usize>,
+) -> Result<usize, CommandsError> {
+    let logs = get_git_path(directory, LOGS_DIR);
+    let mut pending = vec![Path::new(&logs).to_path_buf()];
+    let mut removed = 0;
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let entries = fs::read_dir(&path).map_err(|_| CommandsError::ReadDirError)?;
+            pending.extend(entries.flatten().map(|entry| entry.path()));
+            continue;
+        }
+        let reference = match path.strip_prefix(&logs) {
+            Ok(reference) => reference
+                .iter()
+                .map(|part| part.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => continue,
+        };
+        removed += git_reflog_expire(directory, &reference, max_age, max_count)?;
+    }
+    Ok(removed)
+}
+
+/// Obtiene los hashes, viejos y nuevos, de todas las entradas de todos los reflogs del
+/// repositorio. Se usa para no eliminar objetos a los que todavía se puede volver.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn get_reflogs_hashes(directory: &str) -> Result<Vec<String>, CommandsError> {
+    let mut pending = vec![Path::new(directory).join(GIT_DIR).join(LOGS_DIR)];
+    let mut hashes = Vec::new();
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let entries = fs::read_dir(&path).map_err(|_| CommandsError::ReadDirError)?;
+            pending.extend(entries.flatten().map(|entry| entry.path()));
+            continue;
+        }
+        let content = fs::read_to_string(&path).map_err(|_| CommandsError::ReadFileError)?;
+        for entry in parse_reflog(&content) {
+            hashes.push(entry.old_hash);
+            hashes.push(entry.new_hash);
+        }
+    }
+    hashes.retain(|hash| !is_null_obj_id(hash));
+    Ok(hashes)
+}
+
+/// Obtiene la ruta del reflog de una referencia.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'reference': HEAD, el nombre de una branch local o remota, o una referencia completa
+fn get_reflog_path(directory: &str, reference: &str) -> String {
+    if reference == HEAD || reference.starts_with("refs/") {
+        return get_git_path(directory, &format!("{}/{}", LOGS_DIR, reference));
+    }
+    let logs = get_git_path(directory, LOGS_DIR);
+    let branch_log = format!("{}/{}/{}", logs, REFS_HEADS, reference);
+    if file_exists(&branch_log) {
+        return branch_log;
+    }
+    format!("{}/{}/{}", logs, REFS_REMOTES, reference)
+}
+
+/// Interpreta el contenido de un reflog, en el orden en que fue escrito.
+/// ###Parametros:
+/// 'content': contenido del archivo de reflog
+fn parse_reflog(content: &str) -> Vec<ReflogEntry> {
+    let (_, records) = parse_reflog_records(content);
+    records.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// Interpreta el contenido de un reflog y devuelve, además de cada entrada, su texto original
+/// en el archivo, para poder reescribirlo sin cambiar su formato. También se devuelve el texto
+/// previo a la primera entrada.
+///
+/// Las líneas con el formato de git se leen directamente. Los logs de branches escritos por
+/// versiones anteriores de este cliente guardaban por cada commit su hash seguido del contenido
+/// del commit: de allí se toma el primer parent como hash viejo, el committer como identidad y
+/// el título como mensaje.
+/// ###Parametros:
+/// 'content': contenido del archivo de reflog
+fn parse_reflog_records(content: &str) -> (String, Vec<(ReflogEntry, String)>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let start = index;
+        if let Some(entry) = ReflogEntry::parse(lines[index]) {
+            entries.push((entry, start));
+            index += 1;
+            continue;
+        }
+        let is_block = is_hash(lines[index])
+            && lines
+                .get(index + 1)
+                .is_some_and(|line| line.starts_with("parent "));
+        if !is_block {
+            index += 1;
+            continue;
+        }
+        let new_hash = lines[index].to_string();
+        index += 1;
+        let mut parents = Vec::new();
+        let mut signature = None;
+        while index < lines.len() && !lines[index].is_empty() {
+            if let Some(parent) = lines[index].strip_prefix("parent ") {
+                parents.push(parent.trim().to_string());
+            } else if let Some(committer) = lines[index].strip_prefix("committer ") {
+                signature = parse_signature(committer);
+            }
+            index += 1;
+        }
+        // El título es la primera línea luego de la línea vacía que separa el header
+        let title = lines.get(index + 1).copied().unwrap_or_default();
+        let old_hash = parents
+            .first()
+            .cloned()
+            .unwrap_or(PARENT_INITIAL.to_string());
+        let action = if old_hash == PARENT_INITIAL {
+            "commit (initial)"
+        } else if parents.len() > 1 {
+            "commit (merge)"
+        } else {
+            "commit"
+        };
+        let (identity, timestamp, timezone) = signature.unwrap_or_default();
+        let entry = ReflogEntry {
+            old_hash,
+            new_hash,
+            identity,
+            timestamp,
+            timezone,
+            message: format!("{}: {}", action, title),
+        };
+        entries.push((entry, start));
+    }
+
+    // Cada entrada abarca desde su primera línea hasta el comienzo de la siguiente
+    let join =
+        |lines: &[&str]| -> String { lines.iter().map(|line| format!("{}\n", line)).collect() };
+    let prefix_end = entries.first().map_or(lines.len(), |(_, start)| *start);
+    let ends: Vec<usize> = entries
+        .iter()
+        .skip(1)
+        .map(|(_, start)| *start)
+        .chain([lines.len()])
+        .collect();
+    let records = entries
+        .into_iter()
+        .zip(ends)
+        .map(|((entry, start), end)| (entry, join(&lines[start..end])))
+        .collect();
+    (join(&lines[..prefix_end]), records)
+}
+
+/// Separa una firma `Nombre <mail> <timestamp> <zona>` en identidad, timestamp y zona horaria.
+/// ###Parametros:
+/// 'signature': firma a separar
+fn parse_signature(signature: &str) -> Option<(String, i64, String)> {
+    let mut parts = signature.trim().rsplitn(3, ' ');
+    let timezone = parts.next()?.to_string();
+    let timestamp = parts.next()?.parse().ok()?;
+    let identity = parts.next()?.to_string();
+    Some((identity, timestamp, timezone))
+}
+
+/// Indica si un texto es un hash completo, SHA-1 o SHA-256.
+fn is_hash(text: &str) -> bool {
+    is_valid_obj_id_any_format(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        branch::git_branch_create, checkout::git_checkout_switch, init::git_init, merge::git_merge,
+        rev_parse::git_rev_parse,
+    };
+    use crate::consts::ZERO_ID;
+    use crate::test_utils::{commit_file, test_client};
+    use crate::util::files::create_file_replace;
+
+    #[test]
+    fn test_parse_reflog_entry() {
+        let old = "0123456789abcdef0123456789abcdef01234567";
+        let new = "89abcdef0123456789abcdef0123456789abcdef";
+        let line = format!(
+            "{} {} Valen <v@fi.uba.ar> 1700000000 -0300\tcommit: hola",
+            old, new
+        );
+        let entry = ReflogEntry::parse(&line).expect("Falló al leer la entrada");
+        assert_eq!(entry.old_hash, old);
+        assert_eq!(entry.new_hash, new);
+        assert_eq!(entry.identity, "Valen <v@fi.uba.ar>");
+        assert_eq!(entry.timestamp, 1700000000);
+        assert_eq!(entry.timezone, "-0300");
+        assert_eq!(entry.message, "commit: hola");
+        assert!(ReflogEntry::parse("cualquier cosa").is_none());
+    }
+
+    #[test]
+    fn test_git_reflog() {
+        let directory = "./test_reflog";
+        git_init(directory).expect("Falló al crear el repositorio");
+        assert!(git_reflog(directory, HEAD).is_err());
+
+        let first = commit_file(directory, "a.txt", "primero");
+        let second = commit_file(directory, "b.txt", "segundo");
+
+        let reflog = git_reflog(directory, HEAD).expect("Falló al leer el reflog");
+        let lines: Vec<&str> = reflog.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" HEAD@{0}: commit: segundo"));
+        assert!(lines[1].ends_with(" HEAD@{1}: commit (initial): primero"));
+
+        let entries = read_reflog(directory, HEAD).expect("Falló al leer el reflog");
+        assert_eq!(entries[0].old_hash, first);
+        assert_eq!(entries[0].new_hash, second);
+        assert_eq!(entries[0].identity, "Valen <vlanzillotta@fi.uba.ar>");
+
+        // El reflog de la branch registra los mismos cambios que el de HEAD
+        let entries = read_reflog(directory, "master").expect("Falló al leer el reflog");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].new_hash, second);
+        assert_eq!(entries[0].message, "commit: segundo");
+        assert_eq!(entries[1].old_hash, PARENT_INITIAL);
+
+        assert_eq!(resolve_reflog_entry(directory, HEAD, 1), Ok(first.clone()));
+        assert_eq!(git_rev_parse(directory, "HEAD@{1}"), Ok(first.clone()));
+        assert_eq!(git_rev_parse(directory, "master@{0}"), Ok(second));
+        assert!(git_rev_parse(directory, "HEAD@{2}").is_err());
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_commit_and_merge_append_reflog() {
+        let directory = "./test_reflog_merge";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "primero");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(directory, "feature").expect("Falló al cambiar de branch");
+        let second = commit_file(directory, "b.txt", "segundo");
+        git_checkout_switch(directory, "master").expect("Falló al cambiar de branch");
+        let client = test_client(directory, "9090");
+        git_merge(directory, "master", "feature", client, false, None)
+            .expect("Falló al hacer el merge");
+
+        let path = get_reflog_path(directory, "refs/heads/master");
+        let content = fs::read_to_string(path).expect("Falló al leer el reflog");
+        let entries: Vec<ReflogEntry> = content
+            .lines()
+            .map(|line| ReflogEntry::parse(line).expect("Entrada del reflog mal formada"))
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].old_hash, ZERO_ID);
+        assert_eq!(entries[0].new_hash, first);
+        assert_eq!(entries[0].message, "commit (initial): primero");
+        assert_eq!(entries[1].old_hash, first);
+        assert_eq!(entries[1].new_hash, second);
+        assert_eq!(entries[1].identity, "Valen <vlanzillotta@fi.uba.ar>");
+        assert_eq!(entries[1].message, "merge feature: Fast-forward");
+        assert!(entries[0].timestamp <= entries[1].timestamp);
+
+        let feature = read_reflog(directory, "feature").expect("Falló al leer el reflog");
+        assert_eq!(feature[1].message, "branch: Created from master");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_git_reflog_expire() {
+        let directory = "./test_reflog_expire";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "primero");
+        let second = commit_file(directory, "b.txt", "segundo");
+        let third = commit_file(directory, "c.txt", "tercero");
+
+        // La branch quedó en `third`, pero hubo movimientos posteriores que ya no la apuntan
+        let now = Local::now().timestamp();
+        let day = 24 * 60 * 60;
+        let records = [
+            (
+                ZERO_ID,
+                first.as_str(),
+                now - 200 * day,
+                "commit (initial): primero",
+            ),
+            (
+                first.as_str(),
+                second.as_str(),
+                now - 150 * day,
+                "commit: segundo",
+            ),
+            (
+                second.as_str(),
+                third.as_str(),
+                now - 120 * day,
+                "commit: tercero",
+            ),
+            (
+                third.as_str(),
+                first.as_str(),
+                now - 10 * day,
+                "reset: moving to primero",
+            ),
+            (
+                first.as_str(),
+                second.as_str(),
+                now - day,
+                "reset: moving to segundo",
+            ),
+        ];
+        let lines: Vec<String> = records
+            .iter()
+            .map(|(old, new, timestamp, message)| {
+                format!(
+                    "{} {} Valen <v@fi.uba.ar> {} -0300\t{}\n",
+                    old, new, timestamp, message
+                )
+            })
+            .collect();
+        let path = get_reflog_path(directory, "refs/heads/master");
+        create_file_replace(&path, &lines.concat()).expect("Falló al escribir el reflog");
+
+        assert_eq!(
+            git_reflog_expire(directory, "master", None, None),
+            Ok(0),
+            "Sin límites no se elimina nada"
+        );
+        assert_eq!(
+            git_reflog_expire(
+                directory,
+                "master",
+                Some(Duration::from_secs(30 * day as u64)),
+                Some(1)
+            ),
+            Ok(3)
+        );
+        let content = fs::read_to_string(&path).expect("Falló al leer el reflog");
+        assert_eq!(content, format!("{}{}", lines[2], lines[4]));
+        assert!(parse_expire("2.weeks").is_err());
+        assert_eq!(
+            parse_expire("30.days"),
+            Ok(Some(Duration::from_secs(30 * day as u64)))
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
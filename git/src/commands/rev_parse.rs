@@ -0,0 +1,303 @@
+use super::cat_file::git_cat_file;
+use super::checkout::extract_parent_hashes;
+use super::errors::CommandsError;
+use super::reflog::resolve_reflog_entry;
+use crate::consts::{COMMIT, GIT_DIR, HEAD, REFS_HEADS, REFS_REMOTES, REFS_TAGS};
+use crate::git_transport::references::{peel_tag, Reference};
+use crate::models::client::Client;
+use crate::util::files::get_git_dir;
+use crate::util::object_format::{get_object_format, ObjectFormat};
+use crate::util::objects::find_object_hashes_with_prefix;
+use crate::util::validation::{is_valid_obj_id, is_valid_ref_name};
+
+/// Cantidad mínima de caracteres de un hash abreviado que se acepta como revisión.
+const MIN_ABBREV_REV: usize = 4;
+
+/// Esta función se encarga de llamar al comando rev-parse con los parametros necesarios.
+///
+/// * `git rev-parse <revision>...`: muestra el hash de cada revisión, una por línea.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene las revisiones a resolver
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_rev_parse(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    if args.is_empty() {
+        return Err(CommandsError::InvalidArgumentCountRevParseError);
+    }
+    let directory = client.get_directory_path();
+    let mut formatted_result = String::new();
+    for spec in args {
+        formatted_result.push_str(&git_rev_parse(directory, spec)?);
+        formatted_result.push('\n');
+    }
+    Ok(formatted_result)
+}
+
+/// Resuelve una revisión al hash del objeto al que hace referencia.
+///
+/// Se aceptan hashes completos o abreviados, nombres de branches, tags y remotos, `HEAD`,
+/// entradas del reflog (`<referencia>@{n}`), y los sufijos `~N` (el N-ésimo ancestro siguiendo los primeros parents) y `^N` (el N-ésimo parent).
+/// `~` y `^` sin número equivalen a `~1` y `^1`, y se pueden encadenar (`HEAD~2^2`).
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'spec': revisión a resolver
+pub fn git_rev_parse(directory: &str, spec: &str) -> Result<String, CommandsError> {
+    let unknown = || CommandsError::UnknownRevision(spec.to_string());
+    let (base, mut suffixes) = match spec.find(['~', '^']) {
+        Some(pos) => spec.split_at(pos),
+        None => (spec, ""),
+    };
+    if base.is_empty() {
+        return Err(unknown());
+    }
+    let mut hash = match base
+        .strip_suffix('}')
+        .and_then(|base| base.rsplit_once("@{"))
+    {
+        Some((reference, n)) => {
+            let n = n.parse::<usize>().map_err(|_| unknown())?;
+            let reference = if reference.is_empty() {
+                HEAD
+            } else {
+                reference
+            };
+            if !is_valid_ref_name(reference) {
+                return Err(unknown());
+            }
+            resolve_reflog_entry(directory, reference, n).map_err(|_| unknown())?
+        }
+        None => resolve_base(directory, base)?.ok_or_else(unknown)?,
+    };
+
+    while let Some(operator) = suffixes.chars().next() {
+        let rest = &suffixes[1..];
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = match &rest[..digits_len] {
+            "" => 1,
+            digits => digits.parse::<usize>().map_err(|_| unknown())?,
+        };
+        suffixes = &rest[digits_len..];
+
+        let commit = peel_to_commit(directory, &hash).map_err(|_| unknown())?;
+        let content = git_cat_file(directory, &commit, "-p")?;
+        hash = match operator {
+            '~' => nth_ancestor(directory, commit, &content, number).ok_or_else(unknown)?,
+            '^' if number == 0 => commit,
+            '^' => extract_parent_hashes(&content)
+                .get(number - 1)
+                .ok_or_else(unknown)?
+                .to_string(),
+            _ => return Err(unknown()),
+        };
+    }
+    Ok(hash)
+}
+
+/// Resuelve una revisión y pela las tags anotadas hasta llegar a un commit.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'spec': revisión a resolver
+pub fn resolve_commit(directory: &str, spec: &str) -> Result<String, CommandsError> {
+    let hash = git_rev_parse(directory, spec)?;
+    peel_to_commit(directory, &hash).map_err(|_| CommandsError::UnknownRevision(spec.to_string()))
+}
+
+/// Resuelve la parte de la revisión anterior a los sufijos `~` y `^`.
+///
+/// Un hash completo existente tiene prioridad, luego las referencias en el orden en que las busca
+/// git y por último los hashes abreviados.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'base': nombre de una referencia o hash completo o abreviado
+fn resolve_base(directory: &str, base: &str) -> Result<Option<String>, CommandsError> {
+    let object_format = get_object_format(&get_git_dir(directory));
+    if is_valid_obj_id(base, object_format) && git_cat_file(directory, base, "-t").is_ok() {
+        return Ok(Some(base.to_lowercase()));
+    }
+    // El nombre se usa como ruta dentro de '.git', por lo que no puede salir de ella
+    if !is_valid_ref_name(base) {
+        return Ok(None);
+    }
+
+    let candidates = [
+        base.to_string(),
+        format!("refs/{}", base),
+        format!("{}/{}", REFS_TAGS, base),
+        format!("{}/{}", REFS_HEADS, base),
+        format!("{}/{}", REFS_REMOTES, base),
+        format!("{}/{}/HEAD", REFS_REMOTES, base),
+    ];
+    let resolved = candidates.iter().find_map(|ref_path| {
+        let reference = Reference::new("", ref_path).ok()?;
+        reference.resolve(directory).ok()
+    });
+    if let Some(resolved) = resolved {
+        return Ok(Some(resolved.raw));
+    }
+
+    match find_objects_by_prefix(directory, base) {
+        Ok(hash) => Ok(Some(hash)),
+        Err(CommandsError::UnknownRevision(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Busca el objeto cuyo hash comienza con el prefijo dado, entre los objetos del repositorio y
+/// los de sus alternates.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'prefix': hash abreviado, de al menos 4 caracteres hexadecimales
+///
+/// Devuelve el hash completo si un solo objeto tiene ese prefijo, `AmbiguousObject` con los
+/// candidatos si lo tienen varios, o `UnknownRevision` si ninguno lo tiene o el prefijo no es
+/// válido.
+pub fn find_objects_by_prefix(directory: &str, prefix: &str) -> Result<String, CommandsError> {
+    let is_hex = prefix.chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hex || prefix.len() < MIN_ABBREV_REV || prefix.len() > ObjectFormat::Sha256.hex_len() {
+        return Err(CommandsError::UnknownRevision(prefix.to_string()));
+    }
+    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    let mut candidates = find_object_hashes_with_prefix(&git_dir, &prefix.to_lowercase());
+    match candidates.len() {
+        0 => Err(CommandsError::UnknownRevision(prefix.to_string())),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(CommandsError::AmbiguousObject(
+            prefix.to_string(),
+            candidates,
+        )),
+    }
+}
+
+/// Pela las tags anotadas de un objeto y verifica que el resultado sea un commit.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'hash': hash del objeto
+fn peel_to_commit(directory: &str, hash: &str) -> Result<String, CommandsError> {
+    let hash = peel_tag(directory, hash)?;
+    if git_cat_file(directory, &hash, "-t")? != COMMIT {
+        return Err(CommandsError::UnknownRevision(hash));
+    }
+    Ok(hash)
+}
+
+/// Obtiene el N-ésimo ancestro de un commit siguiendo los primeros parents.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit': hash del commit
+/// 'content': contenido del commit
+/// 'generations': cantidad de primeros parents a recorrer
+fn nth_ancestor(
+    directory: &str,
+    commit: String,
+    content: &str,
+    generations: usize,
+) -> Option<String> {
+    let mut current = commit;
+    let mut content = content.to_string();
+    for generation in 0..generations {
+        current = extract_parent_hashes(&content).first()?.to_string();
+        if generation + 1 < generations {
+            content = git_cat_file(directory, &current, "-p").ok()?;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init;
+    use crate::test_utils::commit_file;
+    use crate::util::objects::{builder_object_commit, builder_object_tag};
+    use std::fs;
+
+    #[test]
+    fn test_git_rev_parse() {
+        let directory = "./test_rev_parse";
+        git_init(directory).expect("Falló al crear el repositorio");
+        assert_eq!(
+            git_rev_parse(directory, "HEAD"),
+            Err(CommandsError::UnknownRevision("HEAD".to_string()))
+        );
+
+        let first = commit_file(directory, "a.txt", "primero");
+        let second = commit_file(directory, "b.txt", "segundo");
+        let third = commit_file(directory, "c.txt", "tercero");
+        // refs/heads/../../HEAD apuntaría a HEAD desde fuera de refs
+        assert_eq!(
+            git_rev_parse(directory, "../../HEAD"),
+            Err(CommandsError::UnknownRevision("../../HEAD".to_string()))
+        );
+
+        assert_eq!(git_rev_parse(directory, "master"), Ok(third.clone()));
+        assert_eq!(git_rev_parse(directory, "HEAD~"), Ok(second.clone()));
+        assert_eq!(git_rev_parse(directory, "HEAD~2"), Ok(first.clone()));
+        assert_eq!(git_rev_parse(directory, "HEAD^^"), Ok(first.clone()));
+        assert_eq!(git_rev_parse(directory, "HEAD~1^0"), Ok(second.clone()));
+        assert_eq!(git_rev_parse(directory, &third[..8]), Ok(third.clone()));
+        assert_eq!(git_rev_parse(directory, &third), Ok(third.clone()));
+        assert!(git_rev_parse(directory, "HEAD~3").is_err());
+        assert!(git_rev_parse(directory, "HEAD^2").is_err());
+        assert!(git_rev_parse(directory, "inexistente").is_err());
+
+        // Tag anotada: sin sufijos se devuelve el objeto tag, con sufijos se pela hasta el commit
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let tag_content = format!(
+            "object {}\ntype commit\ntag v1\ntagger Valen <vlanzillotta@fi.uba.ar> 0 +0000\n\nv1",
+            second
+        );
+        let tag = builder_object_tag(&tag_content, &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear la tag");
+        fs::write(format!("{}/refs/tags/v1", git_dir), &tag).expect("Falló al escribir");
+        assert_eq!(git_rev_parse(directory, "v1"), Ok(tag));
+        assert_eq!(git_rev_parse(directory, "v1^0"), Ok(second.clone()));
+        assert_eq!(git_rev_parse(directory, "v1~1"), Ok(first.clone()));
+        assert_eq!(resolve_commit(directory, "v1"), Ok(second.clone()));
+
+        // Commit de merge: `^2` es el segundo parent
+        let content = git_cat_file(directory, &third, "-p").expect("Falló al leer el commit");
+        let merge_content = content.replacen(
+            &format!("parent {}\n", second),
+            &format!("parent {}\nparent {}\n", second, first),
+            1,
+        );
+        let merge = builder_object_commit(&merge_content, &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear");
+        fs::write(format!("{}/refs/heads/merge", git_dir), &merge).expect("Falló al escribir");
+        assert_eq!(git_rev_parse(directory, "merge^"), Ok(second.clone()));
+        assert_eq!(git_rev_parse(directory, "merge^2"), Ok(first.clone()));
+        assert!(git_rev_parse(directory, "merge^3").is_err());
+
+        // Dos objetos con el mismo prefijo
+        fs::create_dir_all(format!("{}/objects/ab", git_dir)).expect("Falló al crear");
+        for name in [
+            "cd000000000000000000000000000000000001",
+            "cd000000000000000000000000000000000002",
+        ] {
+            fs::write(format!("{}/objects/ab/{}", git_dir, name), "").expect("Falló al escribir");
+        }
+        let candidates = vec![
+            "abcd000000000000000000000000000000000001".to_string(),
+            "abcd000000000000000000000000000000000002".to_string(),
+        ];
+        assert_eq!(
+            git_rev_parse(directory, "abcd"),
+            Err(CommandsError::AmbiguousObject(
+                "abcd".to_string(),
+                candidates.clone()
+            ))
+        );
+        assert_eq!(
+            find_objects_by_prefix(directory, "ABCD000000000000000000000000000000000002"),
+            Ok(candidates[1].clone())
+        );
+        assert_eq!(
+            find_objects_by_prefix(directory, "abc"),
+            Err(CommandsError::UnknownRevision("abc".to_string()))
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
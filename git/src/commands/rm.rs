@@ -1,10 +1,11 @@
 use super::errors::CommandsError;
 use crate::consts::*;
 use crate::models::client::Client;
-use crate::util::files::{open_file, read_file_string};
-use crate::util::formats::hash_generate;
-use std::fs::{self, File};
-use std::io::Write;
+use crate::util::files::{get_git_dir, open_file, read_file_string};
+use crate::util::index::{open_index, write_index};
+use crate::util::object_format::get_object_format;
+use crate::util::validation::normalize_path;
+use std::fs;
 
 /// Esta función se encarga de llamar al comando rm con los parametros necesarios.
 /// ###Parametros:
@@ -24,6 +25,12 @@ pub fn handle_rm(args: Vec<&str>, client: Client) -> Result<String, CommandsErro
 /// 'directory': directorio del repositorio local.
 /// 'file_name': nombre del archivo a remover.
 pub fn git_rm(directory: &str, file_name: &str) -> Result<String, CommandsError> {
+    let file_name = &normalize_path(file_name);
+    // Si ya se eliminó del working directory solo queda sacarlo del index
+    if fs::metadata(format!("{}/{}", directory, file_name)).is_err() {
+        remove_from_index_with_filename(directory, file_name)?;
+        return Ok(format!("rm '{}'", file_name));
+    }
     let result = compare_hash(file_name, directory)?;
 
     Ok(result)
@@ -40,7 +47,7 @@ fn compare_hash(file_name: &str, directory: &str) -> Result<String, CommandsErro
 
     let header = format!("{} {}\0", BLOB, file_content.len());
     let store = header + &file_content;
-    let hash_file = hash_generate(&store);
+    let hash_file = get_object_format(&get_git_dir(directory)).hash(store.as_bytes());
 
     let result = remove_from_index(directory, file_name, &hash_file)?;
 
@@ -55,29 +62,24 @@ pub fn remove_from_index_with_filename(
     directory: &str,
     file_name: &str,
 ) -> Result<(), CommandsError> {
-    let directory_git = format!("{}/{}", directory, GIT_DIR);
-    let index_file_path = format!("{}/{}", directory_git, INDEX);
-
-    let index_file = open_file(&index_file_path)?;
-    let index_content = read_file_string(index_file)?;
+    let directory_git = get_git_dir(directory);
+    let index_content = open_index(&directory_git)?;
 
     let mut lines: Vec<String> = index_content.lines().map(String::from).collect();
-    let index_hash = lines.iter().position(|line| line.starts_with(file_name));
+    let index_hash = find_index_entry(&lines, file_name);
 
     if let Some(index) = index_hash {
-        if lines[index].starts_with(file_name) {
-            lines.remove(index);
-            let file_path = format!("{}/{}", directory, file_name);
-            if fs::metadata(&file_path).is_ok() {
-                // Se remueve del working directory
-                match fs::remove_file(&file_path) {
-                    Ok(_) => {}
-                    Err(_) => return Err(CommandsError::RemoveFileError),
-                };
-            }
+        lines.remove(index);
+        let file_path = format!("{}/{}", directory, file_name);
+        if fs::metadata(&file_path).is_ok() {
+            // Se remueve del working directory
+            match fs::remove_file(&file_path) {
+                Ok(_) => {}
+                Err(_) => return Err(CommandsError::RemoveFileError),
+            };
         }
     }
-    update_index(index_file_path, lines)?;
+    update_index(&directory_git, lines)?;
 
     Ok(())
 }
@@ -93,14 +95,11 @@ pub fn remove_from_index(
     file_name: &str,
     hash_file: &str,
 ) -> Result<String, CommandsError> {
-    let directory_git = format!("{}/{}", directory, GIT_DIR);
-    let index_file_path = format!("{}/{}", directory_git, INDEX);
-
-    let index_file = open_file(&index_file_path)?;
-    let index_content = read_file_string(index_file)?;
+    let directory_git = get_git_dir(directory);
+    let index_content = open_index(&directory_git)?;
 
     let mut lines: Vec<String> = index_content.lines().map(String::from).collect();
-    let index_hash = lines.iter().position(|line| line.starts_with(file_name));
+    let index_hash = find_index_entry(&lines, file_name);
 
     if let Some(index) = index_hash {
         if lines[index].ends_with(hash_file) {
@@ -121,27 +120,31 @@ pub fn remove_from_index(
         }
     }
 
-    update_index(index_file_path, lines)?;
+    update_index(&directory_git, lines)?;
 
     let response = format!("rm '{}'", file_name);
     Ok(response)
 }
 
+/// Busca la línea del index que corresponde exactamente al archivo, sin confundirlo con otros
+/// archivos cuyo nombre empieza igual (por ejemplo `a.txt` y `a.txt.bak`).
+/// ###Parametros
+/// 'lines': lineas del index.
+/// 'file_name': nombre del archivo.
+fn find_index_entry(lines: &[String], file_name: &str) -> Option<usize> {
+    lines.iter().position(|line| {
+        line.strip_prefix(file_name)
+            .is_some_and(|rest| rest.starts_with(' '))
+    })
+}
+
 /// Actualiza el index con las lineas que se le pasan por parametro.
 /// ###Parametros
-/// 'index_file_path': path del index.
+/// 'git_dir': directorio donde esta el directory/.git
 /// 'lines': lineas que se quieren escribir en el index.
-fn update_index(index_file_path: String, lines: Vec<String>) -> Result<(), CommandsError> {
-    let mut index_file = match File::create(index_file_path) {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::CreateFileError),
-    };
-
-    for line in &lines {
-        if writeln!(index_file, "{}", line).is_err() {
-            return Err(CommandsError::WriteFileError);
-        };
-    }
+pub fn update_index(git_dir: &str, lines: Vec<String>) -> Result<(), CommandsError> {
+    let content: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    write_index(git_dir, &content)?;
     Ok(())
 }
 
@@ -163,22 +166,22 @@ mod tests {
         create_file(&file_path, "hola").expect("Fallo al crear el archivo");
 
         let index_path = format!("{}/{}/{}", directory, GIT_DIR, INDEX);
-        let index_content = "remove.rs blob b8b4a4e2a5db3ebed5f5e02beb3e2d27bca9fc9a\nhola.rs blob sjdi293usjdkosju29eue2993sjhdia9992udhh0";
+        let index_content = "remove.rs blob b8b4a4e2a5db3ebed5f5e02beb3e2d27bca9fc9a\nhola.rs blob 9d4b14f3a8eb8e5c3b4ef0f6a4ef0e1b9c11f1a3";
         create_file_replace(&index_path, index_content).expect("Fallo al crear el archivo");
 
         let index_file = open_file(&index_path).expect("Fallo al abrir el archivo");
         let index_content = read_file_string(index_file).expect("Fallo al leer el archivo");
         // Se chequea que el index tiene remove.rs y hola.rs con sus tipos y hashes correpondientes.
-        assert_eq!(index_content, "remove.rs blob b8b4a4e2a5db3ebed5f5e02beb3e2d27bca9fc9a\nhola.rs blob sjdi293usjdkosju29eue2993sjhdia9992udhh0");
+        assert_eq!(index_content, "remove.rs blob b8b4a4e2a5db3ebed5f5e02beb3e2d27bca9fc9a\nhola.rs blob 9d4b14f3a8eb8e5c3b4ef0f6a4ef0e1b9c11f1a3");
 
         let result = git_rm(directory, "remove.rs");
 
         // Se chequea que el index se haya modificado correctamente luego de la ejecucion de git_rm.
-        let index_file = open_file(&index_path).expect("Fallo al abrir el archivo");
-        let new_index_content = read_file_string(index_file).expect("Fallo al leer el archivo");
+        let new_index_content =
+            open_index(&format!("{}/{}", directory, GIT_DIR)).expect("Fallo al leer el index");
         assert_eq!(
             new_index_content,
-            "hola.rs blob sjdi293usjdkosju29eue2993sjhdia9992udhh0\n"
+            "hola.rs blob 9d4b14f3a8eb8e5c3b4ef0f6a4ef0e1b9c11f1a3"
         );
 
         fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
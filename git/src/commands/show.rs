@@ -0,0 +1,269 @@
+use super::cat_file::git_cat_file;
+use super::checkout::get_tree_hash;
+use super::commit::{format_signature_date, Commit};
+use super::errors::CommandsError;
+use super::log::get_commit_message;
+use super::rev_parse::resolve_commit;
+use crate::consts::{GIT_DIR, HEAD, TREE};
+use crate::models::client::Client;
+use crate::util::diff::format_unified_diff;
+use crate::util::objects::{abbreviate_hash, get_object_hashes, parse_tree_line};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Esta función se encarga de llamar al comando show con los parametros necesarios
+/// ###Parametros:
+/// 'args': Vector de strings que contiene la revisión a mostrar (por defecto HEAD)
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_show(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    if args.len() > 1 {
+        return Err(CommandsError::InvalidArgumentCountShowError);
+    }
+    let commit = args.first().copied().unwrap_or(HEAD);
+    let directory = client.get_directory_path();
+    git_show(directory, commit)
+}
+
+/// Muestra la información de un commit y los cambios que introdujo respecto de su primer parent.
+/// Para el commit inicial se compara contra un tree vacío, por lo que todos los archivos
+/// aparecen como agregados. Para los merges se indica cuáles son sus parents y se muestra
+/// un resumen de los archivos modificados respecto del primer parent.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'commit': hash del commit, nombre de una branch, de una tag o HEAD
+pub fn git_show(directory: &str, commit: &str) -> Result<String, CommandsError> {
+    let hash = resolve_commit(directory, commit)?;
+    let content = git_cat_file(directory, &hash, "-p")?;
+    let commit = Commit::from_object(&content)?;
+    let parents: Vec<&str> = commit.get_parents().iter().map(String::as_str).collect();
+
+    let mut formatted_result = format_header(directory, &hash, &content, &parents)?;
+
+    let new_files = get_commit_files(directory, &content)?;
+    let old_files = match parents.first() {
+        Some(parent) => get_commit_files(directory, &git_cat_file(directory, parent, "-p")?)?,
+        None => BTreeMap::new(),
+    };
+    if parents.len() > 1 {
+        formatted_result.push_str(&format_merge_summary(&old_files, &new_files));
+    } else {
+        formatted_result.push_str(&format_diff(directory, &old_files, &new_files)?);
+    }
+    Ok(formatted_result)
+}
+
+/// Formatea el encabezado del commit: hash, parents (si es un merge), autor, committer,
+/// fecha y mensaje.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'hash': hash del commit
+/// 'content': contenido del commit
+/// 'parents': hashes de los parents del commit
+fn format_header(
+    directory: &str,
+    hash: &str,
+    content: &str,
+    parents: &[&str],
+) -> Result<String, CommandsError> {
+    let mut formatted_result = format!("Commit: {}\n", hash);
+    if parents.len() > 1 {
+        let hashes = get_object_hashes(&format!("{}/{}", directory, GIT_DIR))?;
+        let abbreviated: Vec<String> = parents
+            .iter()
+            .map(|parent| abbreviate_hash(parent, &hashes))
+            .collect();
+        formatted_result.push_str(&format!("Merge: {}\n", abbreviated.join(" ")));
+    }
+    if let Some(author) = get_signature(content, "author ") {
+        formatted_result.push_str(&format!("Author: {}\n", author));
+    }
+    if let Some(committer) = get_signature(content, "committer ") {
+        formatted_result.push_str(&format!("Committer: {}\n", committer));
+    }
+    let date_time = Commit::from_object(content).map_or("unknown".to_string(), |commit| {
+        format_signature_date(commit.get_author_date())
+    });
+    formatted_result.push_str(&format!("Date: {}\n\n", date_time));
+    for line in get_commit_message(content) {
+        formatted_result.push_str(&format!("\t{}\n", line));
+    }
+    formatted_result.push('\n');
+    Ok(formatted_result)
+}
+
+/// Obtiene el nombre y el mail de la línea indicada del commit con el formato `Nombre <mail>`.
+/// ###Parametros:
+/// 'content': contenido del commit
+/// 'prefix': comienzo de la línea buscada (`author ` o `committer `)
+fn get_signature<'a>(content: &'a str, prefix: &str) -> Option<&'a str> {
+    let line = content.lines().find(|line| line.starts_with(prefix))?;
+    let line = line.strip_prefix(prefix)?;
+    let end = line.find('>')?;
+    Some(&line[..=end])
+}
+
+/// Obtiene los archivos del tree de un commit junto con el hash de su blob, ordenados por path.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'content': contenido del commit
+pub fn get_commit_files(
+    directory: &str,
+    content: &str,
+) -> Result<BTreeMap<String, String>, CommandsError> {
+    let tree_hash = get_tree_hash(content).ok_or(CommandsError::InvalidTreeHashError)?;
+    let mut files = BTreeMap::new();
+    get_tree_files(directory, tree_hash, "", &mut files)?;
+    Ok(files)
+}
+
+/// Recorre un tree recursivamente y guarda el path y el hash de cada archivo.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'tree_hash': hash del tree
+/// 'path': path del tree dentro del repositorio
+/// 'files': archivos encontrados
+fn get_tree_files(
+    directory: &str,
+    tree_hash: &str,
+    path: &str,
+    files: &mut BTreeMap<String, String>,
+) -> Result<(), CommandsError> {
+    let tree_content = git_cat_file(directory, tree_hash, "-p")?;
+    for line in tree_content.lines() {
+        let (_, name, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+        let file_path = if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", path, name)
+        };
+        if git_cat_file(directory, hash, "-t")? == TREE {
+            get_tree_files(directory, hash, &file_path, files)?;
+        } else {
+            files.insert(file_path, hash.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Genera el diff de todos los archivos que cambiaron entre dos trees.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'old_files': archivos del tree original
+/// 'new_files': archivos del tree nuevo
+fn format_diff(
+    directory: &str,
+    old_files: &BTreeMap<String, String>,
+    new_files: &BTreeMap<String, String>,
+) -> Result<String, CommandsError> {
+    let mut formatted_result = String::new();
+    let paths: BTreeSet<&String> = old_files.keys().chain(new_files.keys()).collect();
+    for path in paths {
+        let old_hash = old_files.get(path);
+        let new_hash = new_files.get(path);
+        if old_hash == new_hash {
+            continue;
+        }
+        formatted_result.push_str(&format!("diff --git a/{} b/{}\n", path, path));
+        let old_content = match old_hash {
+            Some(hash) => git_cat_file(directory, hash, "-p")?,
+            None => {
+                formatted_result.push_str("new file\n");
+                String::new()
+            }
+        };
+        let new_content = match new_hash {
+            Some(hash) => git_cat_file(directory, hash, "-p")?,
+            None => {
+                formatted_result.push_str("deleted file\n");
+                String::new()
+            }
+        };
+        let old_path = match old_hash {
+            Some(_) => format!("a/{}", path),
+            None => "/dev/null".to_string(),
+        };
+        let new_path = match new_hash {
+            Some(_) => format!("b/{}", path),
+            None => "/dev/null".to_string(),
+        };
+        formatted_result.push_str(&format_unified_diff(
+            &old_path,
+            &new_path,
+            &old_content,
+            &new_content,
+        ));
+    }
+    Ok(formatted_result)
+}
+
+/// Genera un resumen de los archivos modificados por un merge respecto de su primer parent.
+/// ###Parametros:
+/// 'old_files': archivos del primer parent
+/// 'new_files': archivos del merge
+fn format_merge_summary(
+    old_files: &BTreeMap<String, String>,
+    new_files: &BTreeMap<String, String>,
+) -> String {
+    let mut formatted_result = String::new();
+    let paths: BTreeSet<&String> = old_files.keys().chain(new_files.keys()).collect();
+    for path in paths {
+        let status = match (old_files.get(path), new_files.get(path)) {
+            (None, Some(_)) => "A",
+            (Some(_), None) => "D",
+            (Some(old), Some(new)) if old != new => "M",
+            _ => continue,
+        };
+        formatted_result.push_str(&format!("{}\t{}\n", status, path));
+    }
+    formatted_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::add::git_add;
+    use crate::commands::checkout::extract_parent_hash;
+    use crate::commands::init::git_init;
+    use crate::commands::log::get_head_commit;
+    use crate::test_utils::{commit_index, test_commit};
+    use crate::util::files::create_file_replace;
+    use std::fs;
+
+    #[test]
+    fn test_git_show() {
+        let directory = "./test_show";
+        git_init(directory).expect("Falló al crear el repositorio");
+
+        let file_path = format!("{}/test.txt", directory);
+        create_file_replace(&file_path, "hola\nmundo\n").expect("Falló al crear el archivo");
+        git_add(directory, "test.txt").expect("Falló al agregar el archivo");
+        commit_index(directory, test_commit("primero"));
+
+        create_file_replace(&file_path, "hola\ngit\n").expect("Falló al modificar el archivo");
+        git_add(directory, "test.txt").expect("Falló al agregar el archivo");
+        commit_index(directory, test_commit("segundo"));
+
+        let result = git_show(directory, HEAD).expect("Falló al hacer git show");
+        assert!(result.contains("Author: Valen <vlanzillotta@fi.uba.ar>"));
+        assert!(result.contains("\tsegundo\n"));
+        assert!(result.contains("diff --git a/test.txt b/test.txt\n"));
+        assert!(result.contains("@@ -1,2 +1,2 @@\n hola\n-mundo\n+git\n"));
+
+        let head = get_head_commit(directory)
+            .expect("Falló al leer HEAD")
+            .expect("HEAD sin commits");
+        let content = git_cat_file(directory, &head, "-p").expect("Falló al leer el commit");
+        let root = extract_parent_hash(&content).expect("El commit no tiene parent");
+        let result = git_show(directory, root).expect("Falló al hacer git show");
+        assert!(result.contains("\tprimero\n"));
+        assert!(result.contains("new file\n--- /dev/null\n+++ b/test.txt\n"));
+        assert!(result.contains("+hola\n+mundo\n"));
+
+        let result = git_show(directory, "HEAD~1").expect("Falló al hacer git show");
+        assert!(result.contains("\tprimero\n"));
+
+        assert!(git_show(directory, "inexistente").is_err());
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
@@ -1,48 +1,119 @@
 use super::errors::CommandsError;
+use super::symbolic_ref::{read_head, SymbolicRef};
+use crate::consts::{GIT_DIR, HEAD, REFS_HEADS, REFS_REMOTES, REFS_TAGS};
+use crate::git_transport::references::{read_packed_refs, Reference, ReferenceType};
 use crate::models::client::Client;
-use crate::util::files::{open_file, read_file_string};
+use crate::util::files::{get_common_git_dir, open_file, read_file_string};
 use std::fs;
 
-/// Esta función se encarga de llamar a al comando ls-files con los parametros necesarios
+/// Esta función se encarga de llamar a al comando show-ref con los parametros necesarios
+///
+/// * `git show-ref`: muestra todas las referencias.
+/// * `git show-ref --heads --tags`: muestra solo las branches y/o los tags.
+/// * `git show-ref --verify <ref>`: muestra la referencia indicada o falla si no existe.
+///
 /// ###Parametros:
-/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función ls-files
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función show-ref
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_show_ref(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if !args.is_empty() {
-        return Err(CommandsError::InvalidArgumentShowRefError);
-    }
     let directory = client.get_directory_path();
-    git_show_ref(directory)
+    if let ["--verify", reference] = args.as_slice() {
+        return git_show_ref_verify(directory, reference);
+    }
+    let mut types = Vec::new();
+    for arg in args {
+        match arg {
+            "--heads" => types.push(ReferenceType::Branch),
+            "--tags" => types.push(ReferenceType::Tag),
+            _ => return Err(CommandsError::InvalidArgumentShowRefError),
+        }
+    }
+    git_show_ref_filtered(directory, &types)
 }
 
 /// Muestra las referencias de un repositorio local con sus commits.
 /// ###Parametros:
 /// 'directory': directorio del repositorio local.
 pub fn git_show_ref(directory: &str) -> Result<String, CommandsError> {
-    let refs_heads_path = format!("{}/.git/refs/heads", directory);
-    let refs_remotes_path = format!("{}/.git/refs/remotes", directory);
-    let refs_tags_path = format!("{}/.git/refs/tags", directory);
-    let mut formatted_result = String::new();
+    git_show_ref_filtered(directory, &[])
+}
 
-    visit_refs_dirs(refs_heads_path, &mut formatted_result, directory)?;
-    visit_refs_dirs(refs_remotes_path, &mut formatted_result, directory)?;
-    visit_refs_dirs(refs_tags_path, &mut formatted_result, directory)?;
+/// Muestra las referencias de los tipos indicados, una por línea con el formato
+/// `<hash> <referencia>` y ordenadas por nombre.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'types': tipos de referencia a mostrar; si está vacío se muestran todas.
+pub fn git_show_ref_filtered(
+    directory: &str,
+    types: &[ReferenceType],
+) -> Result<String, CommandsError> {
+    let references = get_all_references(directory)?;
+    Ok(references
+        .iter()
+        .filter(|reference| types.is_empty() || types.contains(&reference.get_type()))
+        .map(format_reference)
+        .collect())
+}
 
-    Ok(formatted_result)
+/// Muestra una referencia indicada con su nombre completo, por ejemplo `refs/heads/master`, o
+/// `HEAD`.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local.
+/// 'ref_path': nombre completo de la referencia.
+pub fn git_show_ref_verify(directory: &str, ref_path: &str) -> Result<String, CommandsError> {
+    let references = get_all_references(directory)?;
+    let find = |ref_path: &str| {
+        references
+            .iter()
+            .find(|reference| reference.get_ref_path() == ref_path)
+            .map(|reference| reference.get_hash().to_string())
+    };
+    let hash = match ref_path {
+        HEAD => match read_head(directory) {
+            Ok(SymbolicRef::Ref(target)) => find(&target),
+            Ok(SymbolicRef::Detached(hash)) => Some(hash),
+            Err(_) => None,
+        },
+        _ => find(ref_path),
+    };
+    match hash {
+        Some(hash) => Ok(format!("{} {}\n", hash, ref_path)),
+        None => Err(CommandsError::ShowRefInvalidRef(ref_path.to_string())),
+    }
 }
 
-/// Recorre los directorios de .git/refs y agrega los contenidos al resultado formateado.
+/// Devuelve las branches, remotos y tags del repositorio ordenadas por nombre, tanto las
+/// sueltas como las guardadas en `packed-refs`. Las sueltas tienen prioridad sobre las
+/// empaquetadas con el mismo nombre.
 /// ###Parametros:
-/// 'refs_path': directorio para recorrer.
-/// 'formatted_result': resultado formateado.
 /// 'directory': directorio del repositorio local.
+pub fn get_all_references(directory: &str) -> Result<Vec<Reference>, CommandsError> {
+    let git_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+    let mut references = Vec::new();
+    for refs in [REFS_HEADS, REFS_REMOTES, REFS_TAGS] {
+        visit_refs_dirs(&git_dir, refs, &mut references)?;
+    }
+    for packed in read_packed_refs(&git_dir) {
+        let ref_path = packed.reference.get_ref_path();
+        if !references.iter().any(|r| r.get_ref_path() == ref_path) {
+            references.push(packed.reference);
+        }
+    }
+    references.sort();
+    Ok(references)
+}
+
+/// Recorre los directorios de .git/refs y agrega las referencias encontradas.
+/// ###Parametros:
+/// 'git_dir': directorio git del repositorio.
+/// 'refs': ruta del directorio a recorrer, relativa al directorio git.
+/// 'references': referencias encontradas.
 fn visit_refs_dirs(
-    refs_path: String,
-    formatted_result: &mut String,
-    directory: &str,
+    git_dir: &str,
+    refs: &str,
+    references: &mut Vec<Reference>,
 ) -> Result<(), CommandsError> {
-    let git_dir = format!("{}/.git/", directory);
-    let refs = &refs_path[git_dir.len()..];
+    let refs_path = format!("{}/{}", git_dir, refs);
     if fs::metadata(&refs_path).is_ok() {
         let entries = match fs::read_dir(&refs_path) {
             Ok(entries) => entries,
@@ -53,23 +124,28 @@ fn visit_refs_dirs(
                 Ok(entry) => entry,
                 Err(_) => return Err(CommandsError::ReadDirError),
             };
+            let file_name = match entry.file_name().into_string() {
+                Ok(file_name) => file_name,
+                Err(_) => continue,
+            };
+            let ref_path = format!("{}/{}", refs, file_name);
             if entry.path().is_dir() {
-                if let Some(entry) = entry.path().to_str() {
-                    visit_refs_dirs(entry.to_string(), formatted_result, directory)?;
-                }
-            } else if let Ok(file_name) = entry.file_name().into_string() {
-                let file_path = format!("{}/{}", refs_path, file_name);
-                let file_hash = open_file(&file_path)?;
+                visit_refs_dirs(git_dir, &ref_path, references)?;
+            } else {
+                let file_hash = open_file(&format!("{}/{}", git_dir, ref_path))?;
                 let file_hash_content = read_file_string(file_hash)?;
-                formatted_result.push_str(
-                    format!("{} {}/{}\n", file_hash_content.trim(), refs, file_name).as_str(),
-                );
+                references.push(Reference::new(file_hash_content.trim(), &ref_path)?);
             }
         }
     }
     Ok(())
 }
 
+/// Formatea una referencia como `<hash> <referencia>`.
+fn format_reference(reference: &Reference) -> String {
+    format!("{} {}\n", reference.get_hash(), reference.get_ref_path())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +175,65 @@ mod tests {
 
         fs::remove_dir_all(directory).expect("Error al borrar el directorio");
     }
+
+    #[test]
+    fn test_show_ref_packed_filters_and_verify() {
+        let directory = "./test_git_show_ref_packed";
+        git_init(directory).expect("Error al inicializar el repositorio");
+        let master = "1111111111111111111111111111111111111111";
+        let packed = "2222222222222222222222222222222222222222";
+        let tag = "3333333333333333333333333333333333333333";
+        create_file_replace(&format!("{}/.git/refs/heads/master", directory), master)
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/.git/refs/tags/v1", directory), tag)
+            .expect("Error al crear el archivo");
+        create_file_replace(
+            &format!("{}/.git/packed-refs", directory),
+            &format!(
+                "{0} refs/heads/feature\n{0} refs/heads/master\n{0} refs/remotes/origin/master\n",
+                packed
+            ),
+        )
+        .expect("Error al crear el archivo");
+
+        let all = git_show_ref(directory);
+        let heads = git_show_ref_filtered(directory, &[ReferenceType::Branch]);
+        let tags = git_show_ref_filtered(directory, &[ReferenceType::Tag]);
+        let verify_head = git_show_ref_verify(directory, HEAD);
+        let verify_packed = git_show_ref_verify(directory, "refs/heads/feature");
+        let verify_missing = git_show_ref_verify(directory, "refs/heads/otra");
+        let verify_short = git_show_ref_verify(directory, "master");
+        fs::remove_dir_all(directory).expect("Error al borrar el directorio");
+
+        assert_eq!(
+            all,
+            Ok(format!(
+                "{1} refs/heads/feature\n{0} refs/heads/master\n{1} refs/remotes/origin/master\n{2} refs/tags/v1\n",
+                master, packed, tag
+            ))
+        );
+        assert_eq!(
+            heads,
+            Ok(format!(
+                "{1} refs/heads/feature\n{0} refs/heads/master\n",
+                master, packed
+            ))
+        );
+        assert_eq!(tags, Ok(format!("{} refs/tags/v1\n", tag)));
+        assert_eq!(verify_head, Ok(format!("{} HEAD\n", master)));
+        assert_eq!(
+            verify_packed,
+            Ok(format!("{} refs/heads/feature\n", packed))
+        );
+        assert_eq!(
+            verify_missing,
+            Err(CommandsError::ShowRefInvalidRef(
+                "refs/heads/otra".to_string()
+            ))
+        );
+        assert_eq!(
+            verify_short,
+            Err(CommandsError::ShowRefInvalidRef("master".to_string()))
+        );
+    }
 }
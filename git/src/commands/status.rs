@@ -1,18 +1,26 @@
-use super::check_ignore::{check_gitignore, get_gitignore_content};
+use super::check_ignore::{check_gitignore, get_gitignore_content, get_nested_gitignore_content};
 use super::errors::CommandsError;
+use super::symbolic_ref::{read_head, SymbolicRef};
 use crate::commands::checkout::get_tree_hash;
 use crate::consts::*;
 use crate::models::client::Client;
-use crate::util::files::{open_file, read_file, read_file_string};
-use crate::util::formats::hash_generate;
-use crate::util::index::{open_index, recovery_index};
-use std::collections::HashMap;
+use crate::util::errors::UtilError;
+use crate::util::files::{
+    get_git_dir, get_git_path, open_file, read_file_string, read_working_file,
+};
+use crate::util::index::{open_index, parse_index_line, recovery_index};
+use crate::util::object_format::{get_object_format, ObjectFormat};
+use crate::util::objects::parse_tree_line;
+use crate::util::validation::normalize_path;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::fs::File;
-use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use super::cat_file::git_cat_file;
+use super::commit::Commit;
+use super::config::{is_ignore_case, same_path, GitConfig};
+use super::merge::{get_branch_hash, get_log_from_branch, logs_just_in_one_branch};
+use super::push::is_ancestor;
 
 #[derive(Debug)]
 pub struct StatusData {
@@ -21,6 +29,7 @@ pub struct StatusData {
     staged_files_list: Vec<(String, String)>,
     deleted_files_list: Vec<String>,
     deleted_staged_files_list: Vec<String>,
+    renamed_files_list: Vec<(String, String)>,
 }
 
 impl StatusData {
@@ -43,47 +52,56 @@ impl StatusData {
     pub fn deleted_staged_files_list(&self) -> &Vec<String> {
         &self.deleted_staged_files_list
     }
+
+    pub fn renamed_files_list(&self) -> &Vec<(String, String)> {
+        &self.renamed_files_list
+    }
 }
 
 /// Esta función se encarga de llamar al comando status con los parametros necesarios.
+///
+/// * `git status`: muestra el estado del repositorio en formato legible.
+/// * `git status --porcelain`: muestra un código de dos caracteres por archivo.
+/// * `git status -z`: igual que `--porcelain`, pero separa las entradas con `\0`.
+///
 /// ###Parametros:
 /// 'args': Vector de strings que contiene los argumentos que se le pasan a la función status
 /// 'client': Cliente que contiene la información del cliente que se conectó
 pub fn handle_status(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
-    if !args.is_empty() {
-        return Err(CommandsError::InvalidArgumentCountStatusError);
-    }
     let directory = client.get_directory_path();
+    let mut porcelain = false;
+    let mut null_terminated = false;
+    for arg in args {
+        match arg {
+            "--porcelain" => porcelain = true,
+            "-z" => null_terminated = true,
+            _ => return Err(CommandsError::InvalidArgumentCountStatusError),
+        }
+    }
+    if porcelain || null_terminated {
+        return git_status_porcelain(directory, null_terminated);
+    }
     git_status(directory)
 }
 
-/// Devuelve el nombre de la rama actual.
+/// Devuelve el hash del commit al que apunta HEAD, ya sea a través de la branch actual o
+/// directamente en estado detached HEAD, o `None` si la branch actual todavía no tiene commits.
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
-fn get_head_branch(directory: &str) -> Result<String, CommandsError> {
-    // "directory/.git/HEAD"
-    let directory_git = format!("{}/{}", directory, GIT_DIR);
-    let head_file_path = Path::new(&directory_git).join(HEAD);
-
-    let head_file = File::open(head_file_path);
-    let mut head_file = match head_file {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::OpenFileError),
-    };
-    let mut head_branch: String = String::new();
-    let read_head_file = head_file.read_to_string(&mut head_branch);
-    let _ = match read_head_file {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::ReadFileError),
+fn get_head_commit(directory: &str) -> Result<Option<String>, CommandsError> {
+    let ref_path = match read_head(directory)? {
+        SymbolicRef::Detached(hash) => return Ok(Some(hash)),
+        SymbolicRef::Ref(target) => get_git_path(directory, &target),
     };
-    let head_branch_name = head_branch.split('/').last();
-    let head_branch_name = match head_branch_name {
-        Some(name) => name,
-        None => return Err(CommandsError::HeadBranchError),
-    };
-    let head_branch_name = head_branch_name.trim().to_string();
-
-    Ok(head_branch_name)
+    if fs::metadata(&ref_path).is_err() {
+        return Ok(None);
+    }
+    let file = open_file(&ref_path)?;
+    let hash = read_file_string(file)?;
+    if hash.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(hash))
 }
 
 /// Compara los hashes de los archivos del directorio de trabajo con los del index e imprime el estado
@@ -92,20 +110,13 @@ fn get_head_branch(directory: &str) -> Result<String, CommandsError> {
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 pub fn git_status(directory: &str) -> Result<String, CommandsError> {
-    let directory_git = format!("{}/{}", directory, GIT_DIR);
-
-    let index_content = get_index_content(&directory_git)?;
-
-    let index_files = get_lines_in_index(index_content);
-
-    let working_directory_hash_list = get_hashes_working_directory(directory)?;
-    let index_hashes = get_hashes_index(index_files)?;
-    let status_data = compare_hash_lists(&working_directory_hash_list, &index_hashes, directory)?;
+    let status_data = get_status_data(directory)?;
     let updated_files_list = status_data.updated_files_list();
     let untracked_files_list = status_data.untracked_files_list();
     let staged_files_list = status_data.staged_files_list();
     let deleted_files_list = status_data.deleted_files_list();
     let deleted_staged_files_list = status_data.deleted_staged_files_list();
+    let renamed_files_list = status_data.renamed_files_list();
     let files_not_commited_list = check_for_commit(directory, staged_files_list.to_vec())?;
     let value = print_changes(
         updated_files_list.to_vec(),
@@ -113,12 +124,105 @@ pub fn git_status(directory: &str) -> Result<String, CommandsError> {
         files_not_commited_list,
         deleted_files_list.to_vec(),
         deleted_staged_files_list.to_vec(),
+        renamed_files_list.to_vec(),
         directory,
     )?;
 
     Ok(value)
 }
 
+/// Devuelve el estado del repositorio en un formato pensado para scripts: una línea por archivo
+/// con un código de dos caracteres (índice y working directory) seguido de la ruta relativa a la
+/// raíz del repositorio.
+///
+/// * `?? archivo`: archivo no trackeado.
+/// * ` M archivo`: archivo modificado que no se agregó al staging area.
+/// * ` D archivo`: archivo eliminado del working directory pero que sigue en el index.
+/// * `A  archivo`: archivo nuevo agregado al staging area.
+/// * `M  archivo`: archivo modificado agregado al staging area.
+/// * `D  archivo`: archivo eliminado del index.
+/// * `R  origen -> destino`: archivo movido en el index.
+///
+/// Un archivo con cambios en el index y en el working directory se muestra en una sola línea
+/// que combina ambos códigos, por ejemplo `AM archivo` para un archivo nuevo que se volvió a
+/// modificar después de agregarlo.
+///
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'null_terminated': si es true, las entradas terminan con `\0` en lugar de un salto de línea.
+pub fn git_status_porcelain(
+    directory: &str,
+    null_terminated: bool,
+) -> Result<String, CommandsError> {
+    let status_data = get_status_data(directory)?;
+    let index_content = get_index_content(&get_git_dir(directory))?;
+    let index_hashes = get_hashes_index(get_lines_in_index(index_content))?;
+    let files_in_head = get_files_in_head(directory)?;
+
+    // Código de cada archivo: (estado en el index, estado en el working directory)
+    let mut entries: BTreeMap<String, (char, char)> = BTreeMap::new();
+    let mut origins: HashMap<String, String> = HashMap::new();
+    let mut set_index =
+        |path: String, code: char| entries.entry(path).or_insert((' ', ' ')).0 = code;
+    // El estado en el index se obtiene comparándolo con el último commit, sin importar si el
+    // archivo se volvió a modificar en el working directory
+    for (file_path, hash) in index_hashes {
+        let file_path = normalize_path(&file_path);
+        match files_in_head.iter().find(|(path, _)| path == &file_path) {
+            Some((_, head_hash)) if head_hash == &hash => {}
+            Some(_) => set_index(file_path, 'M'),
+            None => set_index(file_path, 'A'),
+        }
+    }
+    for file in status_data.deleted_staged_files_list() {
+        set_index(file.to_string(), 'D');
+    }
+    for (old_path, new_path) in status_data.renamed_files_list() {
+        set_index(new_path.to_string(), 'R');
+        origins.insert(new_path.to_string(), old_path.to_string());
+    }
+    let mut set_worktree =
+        |path: String, code: char| entries.entry(path).or_insert((' ', ' ')).1 = code;
+    for file in status_data.updated_files_list() {
+        set_worktree(file.0[directory.len() + 1..].to_string(), 'M');
+    }
+    for file in status_data.deleted_files_list() {
+        set_worktree(file.to_string(), 'D');
+    }
+    for file in status_data.untracked_files_list() {
+        entries.insert(file.0[directory.len() + 1..].to_string(), ('?', '?'));
+    }
+
+    let terminator = if null_terminated { '\0' } else { '\n' };
+    let mut formatted_result = String::new();
+    for (file_path, (index, worktree)) in entries {
+        let file_path = match origins.get(&file_path) {
+            Some(old_path) => format!("{} -> {}", old_path, file_path),
+            None => file_path,
+        };
+        formatted_result.push_str(&format!(
+            "{}{} {}{}",
+            index, worktree, file_path, terminator
+        ));
+    }
+    Ok(formatted_result)
+}
+
+/// Compara el working directory con el index y devuelve el estado de cada archivo.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+fn get_status_data(directory: &str) -> Result<StatusData, CommandsError> {
+    let directory_git = get_git_dir(directory);
+
+    let index_content = get_index_content(&directory_git)?;
+
+    let index_files = get_lines_in_index(index_content);
+
+    let working_directory_hash_list = get_hashes_working_directory(directory)?;
+    let index_hashes = get_hashes_index(index_files)?;
+    compare_hash_lists(&working_directory_hash_list, &index_hashes, directory)
+}
+
 pub fn get_lines_in_index(index_content: String) -> Vec<String> {
     let lines: Vec<String> = index_content.lines().map(String::from).collect();
     let mut index_files: Vec<String> = Vec::new();
@@ -134,19 +238,11 @@ pub fn get_lines_in_index(index_content: String) -> Vec<String> {
 /// ###Parámetros:
 /// 'directory_git': directorio del repositorio local.
 pub fn get_index_content(directory_git: &str) -> Result<String, CommandsError> {
-    let index_path = format!("{}/index", directory_git);
-    let index_file = File::open(index_path);
-    let mut index_file = match index_file {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::OpenFileError),
-    };
-    let mut index_content: String = String::new();
-    let read_index_file = index_file.read_to_string(&mut index_content);
-    let _ = match read_index_file {
-        Ok(file) => file,
-        Err(_) => return Err(CommandsError::ReadFileError),
-    };
-    Ok(index_content)
+    match open_index(directory_git) {
+        Ok(index_content) => Ok(index_content),
+        Err(UtilError::OpenFileError) => Err(CommandsError::OpenFileError),
+        Err(_) => Err(CommandsError::ReadFileError),
+    }
 }
 
 /// Imprime el resultado de git status.
@@ -163,20 +259,31 @@ fn print_changes(
     files_not_commited_list: Vec<String>,
     deleted_files_list: Vec<String>,
     deleted_staged_files_list: Vec<String>,
+    renamed_files_list: Vec<(String, String)>,
     directory: &str,
 ) -> Result<String, CommandsError> {
     let mut formatted_result = String::new();
-    let head_branch_name = get_head_branch(directory)?;
-
-    formatted_result.push_str("On branch ");
-    formatted_result.push_str(&head_branch_name);
+    match read_head(directory)? {
+        SymbolicRef::Detached(hash) => {
+            formatted_result.push_str(&format!("HEAD detached at {}", &hash[..7]));
+        }
+        head => {
+            let head_branch_name = head.get_branch_name()?;
+            formatted_result.push_str("On branch ");
+            formatted_result.push_str(head_branch_name);
+            if let Some(tracking_status) = get_tracking_status(directory, head_branch_name)? {
+                formatted_result.push_str(&format!("\n{}\n", tracking_status));
+            }
+        }
+    }
     if updated_files_list.is_empty()
         && untracked_files_list.is_empty()
         && files_not_commited_list.is_empty()
         && deleted_files_list.is_empty()
         && deleted_staged_files_list.is_empty()
+        && renamed_files_list.is_empty()
     {
-        branch_up_to_date(&mut formatted_result, head_branch_name);
+        working_tree_clean(&mut formatted_result);
     }
     if !updated_files_list.is_empty() || !deleted_files_list.is_empty() {
         branch_with_untracked_changes(
@@ -195,11 +302,15 @@ fn print_changes(
             directory,
         );
     }
-    if !files_not_commited_list.is_empty() || !deleted_staged_files_list.is_empty() {
+    if !files_not_commited_list.is_empty()
+        || !deleted_staged_files_list.is_empty()
+        || !renamed_files_list.is_empty()
+    {
         branch_missing_commits(
             &mut formatted_result,
             &files_not_commited_list,
             &deleted_staged_files_list,
+            &renamed_files_list,
         );
     }
 
@@ -211,7 +322,7 @@ fn print_changes(
 /// 'directory': directorio del repositorio local.
 /// 'file_name': nombre del archivo.
 pub fn is_files_to_delete(directory: &str, file_name: &str) -> Result<bool, CommandsError> {
-    let dir_git = format!("{}/{}", directory, GIT_DIR);
+    let dir_git = get_git_dir(directory);
 
     let index_content = get_index_content(&dir_git)?;
     let index_files = get_lines_in_index(index_content);
@@ -221,8 +332,9 @@ pub fn is_files_to_delete(directory: &str, file_name: &str) -> Result<bool, Comm
     let deleted_files_list =
         check_for_deleted_files(&index_hashes, &working_directory_hash_list, directory);
 
+    let ignore_case = is_ignore_case(directory);
     for deleted_file in deleted_files_list {
-        if deleted_file == file_name {
+        if same_path(&deleted_file, file_name, ignore_case) {
             return Ok(true);
         }
     }
@@ -233,30 +345,19 @@ pub fn is_files_to_delete(directory: &str, file_name: &str) -> Result<bool, Comm
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 pub fn is_files_to_commit(directory: &str) -> Result<bool, CommandsError> {
-    let dir_git = format!("{}/{}", directory, GIT_DIR);
+    let dir_git = get_git_dir(directory);
 
     let index_content = open_index(&dir_git)?;
-    let tree_hash = recovery_index(&index_content, &dir_git)?;
-
-    let current_branch = get_head_branch(directory)?;
-    let head_branch = format!("{}/.git/refs/heads/{}", directory, current_branch);
+    let tree_hash = recovery_index(&index_content, &dir_git, get_object_format(&dir_git))?;
 
-    let mut contents = String::new();
-    if fs::metadata(&head_branch).is_ok() {
-        let file = open_file(&head_branch)?;
-        contents = read_file_string(file)?;
-    }
+    let contents = match get_head_commit(directory)? {
+        Some(contents) => contents,
+        None => return Ok(false),
+    };
 
-    if contents.is_empty() {
-        return Ok(false);
-    } else {
-        let content_commit = git_cat_file(directory, &contents, "-p")?;
-        let tree_hash_commit = get_tree_hash(&content_commit).unwrap_or(PARENT_INITIAL);
-        if tree_hash == tree_hash_commit {
-            return Ok(false);
-        }
-    }
-    Ok(true)
+    let content_commit = git_cat_file(directory, &contents, "-p")?;
+    let tree_hash_commit = get_tree_hash(&content_commit).unwrap_or(PARENT_INITIAL);
+    Ok(tree_hash != tree_hash_commit)
 }
 
 /// Muestra los archivos con cambios que no estan en el staging area.
@@ -331,10 +432,12 @@ fn branch_with_untracked_files(
 /// 'formatted_result': string con el resultado del status formateado.
 /// 'files_not_commited_list': vector con los nombres de los archivos que estan en el staging area y se van a incluir en el proximo commit.
 /// 'deleted_staged_files_list': vector con los nombres de los archivos que se eliminaron del working directory y del index, que se incluiran en el proximo commit.
+/// 'renamed_files_list': vector con los archivos movidos, con su nombre anterior y su nombre nuevo.
 fn branch_missing_commits(
     formatted_result: &mut String,
     files_not_commited_list: &Vec<String>,
     deleted_staged_files_list: &Vec<String>,
+    renamed_files_list: &Vec<(String, String)>,
 ) {
     formatted_result.push_str("\n\nChanges to be committed:\n");
     formatted_result.push_str("  (use \"git reset HEAD <file>...\" to unstage)\n\n");
@@ -346,26 +449,97 @@ fn branch_missing_commits(
     for file in deleted_staged_files_list {
         formatted_result.push_str(&format!("\tdeleted:\t{}\n", file));
     }
+
+    for (old_path, new_path) in renamed_files_list {
+        formatted_result.push_str(&format!("\trenamed:\t{} -> {}\n", old_path, new_path));
+    }
 }
 
-/// Muestra que el repositorio local esta actualizado.
+/// Muestra que no hay cambios en el working directory.
 /// ###Parámetros:
 /// 'formatted_result': string con el resultado del status formateado.
-/// 'head_branch_name': nombre de la rama actual.
-fn branch_up_to_date(formatted_result: &mut String, head_branch_name: String) {
-    formatted_result.push_str(&format!(
-        "\nYour branch is up to date with 'origin/{}'.\n",
-        head_branch_name
-    ));
+fn working_tree_clean(formatted_result: &mut String) {
     formatted_result.push_str("\nnothing to commit, working tree clean\n");
 }
 
+/// Compara la rama actual con la rama remota que sigue y devuelve el mensaje que lo describe:
+/// si está actualizada, adelantada, atrasada o si ambas divergieron.
+/// Devuelve None si la rama no tiene configurada una rama remota o si alguna de las dos aún no
+/// tiene commits.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+/// 'head_branch_name': nombre de la rama actual.
+fn get_tracking_status(
+    directory: &str,
+    head_branch_name: &str,
+) -> Result<Option<String>, CommandsError> {
+    let git_config = match GitConfig::new_from_file(directory) {
+        Ok(git_config) => git_config,
+        Err(_) => return Ok(None),
+    };
+    let remote_ref = match git_config.get_remote_branch_ref(head_branch_name) {
+        Some(remote_ref) => remote_ref,
+        None => return Ok(None),
+    };
+    let local_path = get_git_path(directory, &format!("{}/{}", REFS_HEADS, head_branch_name));
+    let remote_path = get_git_path(directory, &remote_ref);
+    let (local_hash, remote_hash) =
+        match (get_branch_hash(&local_path), get_branch_hash(&remote_path)) {
+            (Ok(local_hash), Ok(remote_hash)) => (
+                local_hash.trim().to_string(),
+                remote_hash.trim().to_string(),
+            ),
+            _ => return Ok(None),
+        };
+    let upstream = remote_ref.trim_start_matches("refs/remotes/");
+    if local_hash == remote_hash {
+        return Ok(Some(format!(
+            "Your branch is up to date with '{}'.",
+            upstream
+        )));
+    }
+
+    let log_local = get_log_from_branch(directory, &local_hash)?;
+    let log_remote = get_log_from_branch(directory, &remote_hash)?;
+    let ahead = logs_just_in_one_branch(log_local.clone(), log_remote.clone()).len();
+    let behind = logs_just_in_one_branch(log_remote, log_local).len();
+    if is_ancestor(directory, &local_hash, &remote_hash)? {
+        Ok(Some(format!(
+            "Your branch is ahead of '{}' by {} {}.",
+            upstream,
+            ahead,
+            plural_commits(ahead)
+        )))
+    } else if is_ancestor(directory, &remote_hash, &local_hash)? {
+        Ok(Some(format!(
+            "Your branch is behind '{}' by {} {}, and can be fast-forwarded.",
+            upstream,
+            behind,
+            plural_commits(behind)
+        )))
+    } else {
+        Ok(Some(format!(
+            "Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.",
+            upstream, ahead, behind
+        )))
+    }
+}
+
+fn plural_commits(count: usize) -> &'static str {
+    if count == 1 {
+        "commit"
+    } else {
+        "commits"
+    }
+}
+
 /// Compara los hashes de los archivos del directorio de trabajo con los del index y devuelve cinco vectores en un struct StatusData:
 /// - updated_files_list: vector con los archivos que se modificaron y no se actualizaron en el staging area.
 /// - untracked_files_list: vector con los archivos que no estan trackeados.
 /// - staged_files_list: vector con los archivos que estan en el staging area y se van a incluir en el proximo commit.
 /// - deleted_files_list: vector con los archivos que se eliminaron del working directory pero siguen en el index.
 /// - deleted_staged_files_list: vector con los archivos que se eliminaron del working directory y del index.
+/// - renamed_files_list: vector con los archivos que se movieron en el index, con su nombre anterior y su nombre nuevo.
 /// ###Parámetros:
 /// 'working_directory_hash_list': HashMap con los nombres de los archivos en el working directory y sus hashes.
 /// 'index_hashes': vector con los nombres de los archivos en el index y sus hashes.
@@ -378,11 +552,12 @@ pub fn compare_hash_lists(
     let mut updated_files_list: Vec<(String, String)> = Vec::new();
     let mut untracked_files_list: Vec<(String, String)> = Vec::new();
     let mut staged_files_list: Vec<(String, String)> = Vec::new();
+    let ignore_case = is_ignore_case(directory);
     for working_dir_hash in working_directory_hash_list {
         let mut found_hash_in_index = false;
         for index_hash in index_hashes {
             let file_path = &working_dir_hash.0[directory.len() + 1..];
-            if file_path == index_hash.0 {
+            if same_path(file_path, &normalize_path(&index_hash.0), ignore_case) {
                 // el archivo esta trackeado, debo ver si esta en su ultima version
                 found_hash_in_index = true;
                 if working_dir_hash.1 != &index_hash.1 {
@@ -407,16 +582,59 @@ pub fn compare_hash_lists(
     }
     let deleted_files_list =
         check_for_deleted_files(index_hashes, working_directory_hash_list, directory);
-    let deleted_staged_files_list = check_for_deleted_staged_files(index_hashes, directory)?;
+    let mut deleted_staged_files_list = check_for_deleted_staged_files(index_hashes, directory)?;
+    let renamed_files_list =
+        check_for_renamed_files(index_hashes, &mut deleted_staged_files_list, directory)?;
     Ok(StatusData {
         updated_files_list,
         untracked_files_list,
         staged_files_list,
         deleted_files_list,
         deleted_staged_files_list,
+        renamed_files_list,
     })
 }
 
+/// Devuelve los archivos que se movieron en el index, con su nombre anterior y su nombre nuevo:
+/// su blob sigue en el index con un nombre que no existe en el ultimo commit. Los archivos
+/// movidos se quitan de la lista de archivos eliminados.
+/// ###Parámetros:
+/// 'index_hashes': vector con los nombres de los archivos en el index y sus hashes.
+/// 'deleted_staged_files_list': vector con los archivos que se eliminaron del working directory y del index.
+/// 'directory': directorio del repositorio local.
+fn check_for_renamed_files(
+    index_hashes: &[(String, String)],
+    deleted_staged_files_list: &mut Vec<String>,
+    directory: &str,
+) -> Result<Vec<(String, String)>, CommandsError> {
+    let mut renamed_files_list: Vec<(String, String)> = Vec::new();
+    if deleted_staged_files_list.is_empty() {
+        return Ok(renamed_files_list);
+    }
+    let files_in_head = get_files_in_head(directory)?;
+    deleted_staged_files_list.retain(|deleted_file| {
+        let head_hash = files_in_head
+            .iter()
+            .find(|(path, _)| path == deleted_file)
+            .map(|(_, hash)| hash);
+        let new_path = index_hashes.iter().find(|(path, hash)| {
+            Some(hash) == head_hash
+                && !files_in_head.iter().any(|(head_path, _)| head_path == path)
+                && !renamed_files_list
+                    .iter()
+                    .any(|(_, renamed)| renamed == path)
+        });
+        match new_path {
+            Some((new_path, _)) => {
+                renamed_files_list.push((deleted_file.to_string(), new_path.to_string()));
+                false
+            }
+            None => true,
+        }
+    });
+    Ok(renamed_files_list)
+}
+
 /// Devuelve un vector con los nombres de los archivos que se eliminaron del working directory pero siguen en el index.
 /// ###Parámetros:
 /// 'index_hashes': vector con los nombres de los archivos en el index y sus hashes.
@@ -430,17 +648,18 @@ pub fn check_for_deleted_files(
     let index_files_len = &index_hashes.len();
     let working_directory_files_len = &working_directory_hash_list.len();
     let mut deleted_files_list: Vec<String> = Vec::new();
+    let ignore_case = is_ignore_case(directory);
     if index_files_len > working_directory_files_len {
         for index_hash in index_hashes {
             let mut found_hash_in_index = false;
             for working_dir_hash in working_directory_hash_list {
                 let file_path = &working_dir_hash.0[directory.len() + 1..];
-                if file_path == index_hash.0 {
+                if same_path(file_path, &normalize_path(&index_hash.0), ignore_case) {
                     found_hash_in_index = true;
                 }
             }
             if !found_hash_in_index {
-                deleted_files_list.push(index_hash.0.to_string());
+                deleted_files_list.push(normalize_path(&index_hash.0));
             }
         }
     }
@@ -457,14 +676,7 @@ pub fn check_for_deleted_staged_files(
 ) -> Result<Vec<String>, CommandsError> {
     let mut deleted_staged_files_list: Vec<String> = Vec::new();
     if is_files_to_commit(directory)? {
-        let current_branch = get_head_branch(directory)?;
-        let head_branch = format!("{}/.git/refs/heads/{}", directory, current_branch);
-
-        let mut current_commit = String::new();
-        if fs::metadata(&head_branch).is_ok() {
-            let file = open_file(&head_branch)?;
-            current_commit = read_file_string(file)?;
-        }
+        let current_commit = get_head_commit(directory)?.unwrap_or_default();
         let current_commit_content = git_cat_file(directory, &current_commit, "-p")?;
         let current_commit_lines = current_commit_content.split('\n');
         let mut files_in_tree = Vec::new();
@@ -475,7 +687,7 @@ pub fn check_for_deleted_staged_files(
                 get_files_in_tree(directory, tree_parts[1], &mut file_path, &mut files_in_tree)?;
             }
         }
-        for file in files_in_tree {
+        for (file, _) in files_in_tree {
             let mut found_file_in_tree = false;
             for index_hash in index_hashes {
                 if file == index_hash.0 {
@@ -494,39 +706,52 @@ pub fn check_for_deleted_staged_files(
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 /// 'tree_hash': hash del arbol de archivos.
-/// 'files_in_tree': vector con los nombres de los archivos en el arbol de archivos.
+/// 'files_in_tree': vector con los nombres de los archivos en el arbol de archivos y sus hashes.
 fn get_files_in_tree(
     directory: &str,
     tree_hash: &str,
     file_path: &mut String,
-    files_in_tree: &mut Vec<String>,
+    files_in_tree: &mut Vec<(String, String)>,
 ) -> Result<(), CommandsError> {
     let tree_content = git_cat_file(directory, tree_hash, "-p")?;
     let tree_lines = tree_content.split('\n');
     for tree_line in tree_lines {
         if !tree_line.is_empty() {
-            let tree_parts: Vec<&str> = tree_line.split_whitespace().collect();
+            let (_, name, hash) = parse_tree_line(tree_line)
+                .ok_or(CommandsError::MalformedTreeEntry(tree_line.to_string()))?;
             let current_path = if file_path.is_empty() {
-                tree_parts[1].to_string()
+                name.to_string()
             } else {
-                format!("{}/{}", file_path, tree_parts[1])
+                format!("{}/{}", file_path, name)
             };
 
-            if git_cat_file(directory, tree_parts[2], "-t")? == "tree" {
-                get_files_in_tree(
-                    directory,
-                    tree_parts[2],
-                    &mut current_path.clone(),
-                    files_in_tree,
-                )?;
+            if git_cat_file(directory, hash, "-t")? == "tree" {
+                get_files_in_tree(directory, hash, &mut current_path.clone(), files_in_tree)?;
             } else {
-                files_in_tree.push(current_path);
+                files_in_tree.push((current_path, hash.to_string()));
             }
         }
     }
     Ok(())
 }
 
+/// Devuelve las rutas y los hashes de los archivos incluidos en el ultimo commit de la branch
+/// actual, o un vector vacio si la branch todavia no tiene commits.
+/// ###Parámetros:
+/// 'directory': directorio del repositorio local.
+fn get_files_in_head(directory: &str) -> Result<Vec<(String, String)>, CommandsError> {
+    let mut files_in_tree = Vec::new();
+    let head_branch_commit = match get_head_commit(directory)? {
+        Some(head_branch_commit) => head_branch_commit,
+        None => return Ok(files_in_tree),
+    };
+    let commit_content = git_cat_file(directory, &head_branch_commit, "-p")?;
+    if let Some(tree_hash) = get_tree_hash(&commit_content) {
+        get_files_in_tree(directory, tree_hash, &mut String::new(), &mut files_in_tree)?;
+    }
+    Ok(files_in_tree)
+}
+
 /// Se para en el ultimo commit de la branch actual y reconstruye el arbol de archivos incluidos
 /// en ese commit para ver si los archivos que estan en el staging area fueron incluidos en ese commit.
 /// Si no fueron incluidos, los agrega a un vector de 'files_not_commited_list' (que devuelve).
@@ -539,17 +764,16 @@ fn check_for_commit(
 ) -> Result<Vec<String>, CommandsError> {
     let mut files_not_commited_list: Vec<String> = Vec::new();
     if !staged_files_list.is_empty() {
-        let head_branch = get_head_branch(directory)?;
-        let head_branch = format!("{}/.git/refs/heads/{}", directory, head_branch);
-        if fs::metadata(&head_branch).is_err() {
-            files_not_commited_list = staged_files_list
-                .iter()
-                .map(|file| file.0.to_string())
-                .collect();
-            return Ok(files_not_commited_list);
-        }
-        let head_branch_file = open_file(&head_branch)?;
-        let head_branch_commmit = read_file_string(head_branch_file)?;
+        let head_branch_commmit = match get_head_commit(directory)? {
+            Some(head_branch_commmit) => head_branch_commmit,
+            None => {
+                files_not_commited_list = staged_files_list
+                    .iter()
+                    .map(|file| file.0.to_string())
+                    .collect();
+                return Ok(files_not_commited_list);
+            }
+        };
         for file in staged_files_list {
             let commited = get_files_in_commit(directory, &head_branch_commmit, &file.1)?;
             if !commited {
@@ -568,13 +792,8 @@ pub fn get_hashes_index(
 ) -> Result<Vec<(String, String)>, CommandsError> {
     let mut index_hashes: Vec<(String, String)> = Vec::new();
     for file in index_files_list {
-        let parts: Vec<&str> = file.split(' ').collect();
-        let file_name = match parts.first() {
-            Some(name) => name,
-            None => return Err(CommandsError::GenericError),
-        };
-        let file_hash = match parts.last() {
-            Some(hash) => hash,
+        let (file_name, _, file_hash) = match parse_index_line(&file) {
+            Some(parts) => parts,
             None => return Err(CommandsError::GenericError),
         };
         index_hashes.push((file_name.to_string(), file_hash.to_string()));
@@ -596,26 +815,18 @@ fn get_files_in_commit(
     let mut commited = false;
     if !commit_actual.is_empty() {
         let commit_content = git_cat_file(directory, commit_actual, "-p")?;
-        let commit_lines = commit_content.split('\n');
-        let mut parent_commit = "";
-        for line in commit_lines {
-            if line.starts_with("parent") {
-                if let Some(parent_hash) = line.split(' ').last() {
-                    parent_commit = parent_hash;
-                }
-            }
-            if line.starts_with("tree") {
-                if let Some(tree_hash) = line.split(' ').last() {
-                    get_tree_content(
-                        directory,
-                        tree_hash,
-                        file_hash,
-                        &mut commited,
-                        parent_commit,
-                    )?;
-                }
-            }
-        }
+        let commit = Commit::from_object(&commit_content)?;
+        let parent_commit = match commit.get_parents().first() {
+            Some(parent) => parent.as_str(),
+            None => PARENT_INITIAL,
+        };
+        get_tree_content(
+            directory,
+            commit.get_tree(),
+            file_hash,
+            &mut commited,
+            parent_commit,
+        )?;
     }
     Ok(commited)
 }
@@ -639,11 +850,12 @@ fn get_tree_content(
     let tree_lines = tree_content.split('\n');
     for tree_line in tree_lines {
         if !tree_line.is_empty() {
-            let tree_parts: Vec<&str> = tree_line.split_whitespace().collect();
-            if git_cat_file(directory, tree_parts[2], "-t")? == "tree" {
-                get_tree_content(directory, tree_parts[2], file_hash, commited, parent_commit)?;
+            let (_, _, hash) = parse_tree_line(tree_line)
+                .ok_or(CommandsError::MalformedTreeEntry(tree_line.to_string()))?;
+            if git_cat_file(directory, hash, "-t")? == "tree" {
+                get_tree_content(directory, hash, file_hash, commited, parent_commit)?;
             }
-            if tree_parts[2] == file_hash {
+            if hash == file_hash {
                 *commited = true;
                 return Ok(());
             }
@@ -665,28 +877,72 @@ pub fn get_hashes_working_directory(
     let working_directory = directory.to_string();
     let gitignore_content = get_gitignore_content(directory)?;
     calculate_directory_hashes(
+        directory,
         &working_directory,
         &mut working_directory_hash_list,
         &gitignore_content,
+        get_object_format(&get_git_dir(directory)),
     )?;
+    if is_ignore_case(directory) {
+        working_directory_hash_list = fold_case_to_index(directory, working_directory_hash_list);
+    }
     Ok(working_directory_hash_list)
 }
 
-/// Recorre el directorio de trabajo recursivamente y devuelve un HashMap con los nombres de los archivos y
-/// sus hashes correspondientes.
+/// Con `core.ignorecase` activado, renombra los archivos del working directory que coinciden
+/// con una entrada del index salvo por mayúsculas y minúsculas, para que utilicen el nombre
+/// registrado en el index. Así un cambio solo de mayúsculas no aparece como eliminado y nuevo.
 /// ###Parámetros:
 /// 'directory': directorio del repositorio local.
 /// 'hash_list': HashMap con los nombres de los archivos en el working directory y sus hashes.
+fn fold_case_to_index(
+    directory: &str,
+    hash_list: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let index_content = get_index_content(&get_git_dir(directory)).unwrap_or_default();
+    let index_files: HashMap<String, String> = get_lines_in_index(index_content)
+        .iter()
+        .filter_map(|line| parse_index_line(line).map(|(name, _, _)| name))
+        .map(|name| (name.to_lowercase(), name.to_string()))
+        .collect();
+    hash_list
+        .into_iter()
+        .map(|(path, hash)| {
+            let file_path = &path[directory.len() + 1..];
+            match index_files.get(&file_path.to_lowercase()) {
+                Some(index_name) => (format!("{}/{}", directory, index_name), hash),
+                None => (path, hash),
+            }
+        })
+        .collect()
+}
+
+/// Recorre el directorio de trabajo recursivamente y devuelve un HashMap con los nombres de los archivos y
+/// sus hashes correspondientes. Los `.gitignore` de los subdirectorios se agregan a los patrones a
+/// medida que se recorren.
+/// ###Parámetros:
+/// 'root': directorio raíz del repositorio local.
+/// 'directory': directorio que se está recorriendo.
+/// 'hash_list': HashMap con los nombres de los archivos en el working directory y sus hashes.
+/// 'gitignore_content': patrones de exclusión que se aplican al directorio.
+/// 'object_format': formato de los objetos del repositorio, con el que se calculan los hashes.
 pub fn calculate_directory_hashes(
+    root: &str,
     directory: &str,
     hash_list: &mut HashMap<String, String>,
     gitignore_content: &str,
+    object_format: ObjectFormat,
 ) -> Result<(), CommandsError> {
     let entries = match fs::read_dir(directory) {
         Ok(entries) => entries,
         Err(_) => return Err(CommandsError::ReadDirError),
     };
     let mut ignored_files: Vec<String> = Vec::new();
+    let relative_dir = normalize_path(directory.get(root.len() + 1..).unwrap_or_default());
+    let mut gitignore_content = gitignore_content.to_string();
+    if !relative_dir.is_empty() {
+        gitignore_content.push_str(&get_nested_gitignore_content(root, &relative_dir)?);
+    }
 
     for entry in entries {
         let entry = match entry {
@@ -700,39 +956,56 @@ pub fn calculate_directory_hashes(
             if file_name.starts_with('.') {
                 continue;
             }
-            check_gitignore(file_name, &mut ignored_files, gitignore_content)?;
-            if !ignored_files.is_empty() && ignored_files.contains(&file_name.to_string()) {
+            let mut relative_path = if relative_dir.is_empty() {
+                file_name.to_string()
+            } else {
+                format!("{}/{}", relative_dir, file_name)
+            };
+            if path.is_dir() {
+                relative_path.push('/');
+            }
+            check_gitignore(&relative_path, &mut ignored_files, &gitignore_content)?;
+            if ignored_files.contains(&relative_path) {
                 continue;
             }
         }
 
-        create_hash_working_dir(path, hash_list, gitignore_content)?;
+        create_hash_working_dir(root, path, hash_list, &gitignore_content, object_format)?;
     }
     Ok(())
 }
 
 /// Crea el hash de un archivo del working directory y lo agrega a un HashMap.
 /// ###Parámetros:
+/// 'root': directorio raíz del repositorio local.
 /// 'path': path del archivo.
 /// 'hash_list': HashMap con los nombres de los archivos en el working directory y sus hashes.
+/// 'object_format': formato de los objetos del repositorio, con el que se calculan los hashes.
 fn create_hash_working_dir(
+    root: &str,
     path: PathBuf,
     hash_list: &mut HashMap<String, String>,
     gitignore_content: &str,
+    object_format: ObjectFormat,
 ) -> Result<(), CommandsError> {
-    if path.is_dir() {
+    if path.is_dir() && !path.is_symlink() {
         if let Some(path_str) = path.to_str() {
-            calculate_directory_hashes(path_str, hash_list, gitignore_content)?;
+            calculate_directory_hashes(
+                root,
+                path_str,
+                hash_list,
+                gitignore_content,
+                object_format,
+            )?;
         }
     } else if let Some(file_name_str) = path.to_str() {
-        let file = open_file(file_name_str)?;
-        let content = read_file(file)?;
+        let content = read_working_file(file_name_str)?;
 
         let header = format!("{} {}\0", BLOB, content.len());
         let store = header + String::from_utf8_lossy(&content).as_ref();
-        let hash_object = hash_generate(&store);
+        let hash_object = object_format.hash(store.as_bytes());
 
-        hash_list.insert(file_name_str.to_string(), hash_object);
+        hash_list.insert(normalize_path(file_name_str), hash_object);
     }
     Ok(())
 }
@@ -740,15 +1013,12 @@ fn create_hash_working_dir(
 #[cfg(test)]
 mod tests {
     use crate::{
-        commands::{
-            add::git_add,
-            commit::{git_commit, Commit},
-            init::git_init,
-        },
+        commands::{add::git_add, commit::git_commit, config::git_config_set, init::git_init},
         util::files::create_file_replace,
     };
 
     use super::*;
+    use crate::test_utils::test_commit;
     use std::io::Write;
 
     #[test]
@@ -774,13 +1044,7 @@ mod tests {
         let result_after = "On branch master\nUntracked files:\n  (use \"git add <file>...\" to include in what will be committed)\n\n\tnew file: \tmain.rs\n\nChanges to be committed:\n  (use \"git reset HEAD <file>...\" to unstage)\n\n\tmodified:\t./test_status/testfile.rs\n";
         assert_eq!(result_after_add, Ok(result_after.to_string()));
 
-        let test_commit1 = Commit::new(
-            "prueba".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit1 = test_commit("prueba");
         git_commit(directory, test_commit1).expect("Error al commitear");
 
         let result_after_commit = git_status(directory);
@@ -789,17 +1053,11 @@ mod tests {
 
         git_add(directory, "main.rs").expect("Error al ejecutar git add");
 
-        let test_commit2 = Commit::new(
-            "prueba2".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-            "Valen".to_string(),
-            "vlanzillotta@fi.uba.ar".to_string(),
-        );
+        let test_commit2 = test_commit("prueba2");
         git_commit(directory, test_commit2).expect("Error al commitear");
 
         let result_after_commit2 = git_status(directory);
-        let result_after_commit2_ = "On branch master\nYour branch is up to date with 'origin/master'.\n\nnothing to commit, working tree clean\n";
+        let result_after_commit2_ = "On branch master\nnothing to commit, working tree clean\n";
         assert_eq!(result_after_commit2, Ok(result_after_commit2_.to_string()));
 
         let testfile = format!("{}/{}", directory, "testfile.rs");
@@ -818,6 +1076,160 @@ mod tests {
         assert!(result_after_remove.is_ok());
     }
 
+    #[test]
+    fn test_status_paths_use_forward_slashes() {
+        let directory: &str = "./test_status_separators";
+        git_init(directory).expect("Error al ejecutar git init");
+        fs::create_dir_all(format!("{}/src/bin", directory)).expect("Falló al crear el directorio");
+        create_file_replace(&format!("{}/src/bin/main.rs", directory), "Hola Mundo")
+            .expect("Falló al crear el archivo");
+        create_file_replace(&format!("{}/src/lib.rs", directory), "Chau Mundo")
+            .expect("Falló al crear el archivo");
+        create_file_replace(&format!("{}/src/old.rs", directory), "Adios")
+            .expect("Falló al crear el archivo");
+
+        // Rutas escritas con el separador de Windows
+        git_add(directory, "src\\bin\\main.rs").expect("Error al ejecutar git add");
+        git_add(directory, "src/old.rs").expect("Error al ejecutar git add");
+        git_add(directory, "src\\lib.rs").expect("Error al ejecutar git add");
+        let index_path = format!("{}/.git/index", directory);
+        let index_content =
+            open_index(&format!("{}/.git", directory)).expect("Falló al leer el index");
+        assert!(index_content.contains("src/bin/main.rs blob"));
+        fs::write(
+            &index_path,
+            index_content.replace("src/old.rs", "src\\old.rs"),
+        )
+        .expect("Falló al escribir el index");
+        fs::remove_file(format!("{}/src/old.rs", directory)).expect("Falló al remover");
+
+        let human = git_status(directory).expect("Error al ejecutar git status");
+        let porcelain =
+            git_status_porcelain(directory, false).expect("Error al ejecutar git status");
+        assert!(!human.contains('\\'));
+        assert!(!porcelain.contains('\\'));
+        assert!(porcelain.contains("A  src/lib.rs\n"));
+        assert!(porcelain.contains("AD src/old.rs\n"));
+        assert!(porcelain.contains("A  src/bin/main.rs\n"));
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+    }
+
+    #[test]
+    fn test_git_status_porcelain() {
+        let directory: &str = "./test_status_porcelain";
+        git_init(directory).expect("Error al ejecutar git init");
+
+        let file_path = format!("{}/{}", directory, "testfile.rs");
+        create_file_replace(&file_path, "Hola Mundo").expect("Falló al crear el archivo");
+        let file_path2 = format!("{}/{}", directory, "main.rs");
+        create_file_replace(&file_path2, "Chau Mundo").expect("Falló al crear el archivo");
+        git_add(directory, "testfile.rs").expect("Error al ejecutar git add");
+
+        let result = git_status_porcelain(directory, false);
+        assert_eq!(result, Ok("?? main.rs\nA  testfile.rs\n".to_string()));
+
+        // Un archivo agregado y modificado después se muestra en una sola línea
+        create_file_replace(&file_path, "Hola Mundo!").expect("Falló al escribir el archivo");
+        let result = git_status_porcelain(directory, false);
+        assert_eq!(result, Ok("?? main.rs\nAM testfile.rs\n".to_string()));
+        create_file_replace(&file_path, "Hola Mundo").expect("Falló al escribir el archivo");
+
+        let test_commit = test_commit("prueba");
+        git_commit(directory, test_commit).expect("Error al commitear");
+        create_file_replace(&file_path, "Hola de nuevo").expect("Falló al escribir el archivo");
+
+        let result = git_status_porcelain(directory, true);
+        assert_eq!(result, Ok("?? main.rs\0 M testfile.rs\0".to_string()));
+
+        git_add(directory, "testfile.rs").expect("Error al ejecutar git add");
+        fs::remove_file(&file_path2).expect("Error al intentar remover el archivo");
+        let result = git_status_porcelain(directory, false);
+        assert_eq!(result, Ok("M  testfile.rs\n".to_string()));
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+    }
+
+    #[test]
+    fn test_status_tracking_branch() {
+        let directory: &str = "./test_status_tracking";
+        git_init(directory).expect("Error al ejecutar git init");
+        git_config_set(directory, "remote.origin.url", "localhost:9418/repo")
+            .expect("Falló al configurar");
+        git_config_set(
+            directory,
+            "remote.origin.fetch",
+            "+refs/heads/*:refs/remotes/origin/*",
+        )
+        .expect("Falló al configurar");
+        git_config_set(directory, "branch.master.remote", "origin").expect("Falló al configurar");
+
+        let file_path = format!("{}/{}", directory, "testfile.rs");
+        create_file_replace(&file_path, "Hola Mundo").expect("Falló al crear el archivo");
+        git_add(directory, "testfile.rs").expect("Error al ejecutar git add");
+        let test_commit1 = test_commit("prueba");
+        git_commit(directory, test_commit1).expect("Error al commitear");
+
+        // Sin la rama remota descargada no se informa nada
+        let result = git_status(directory).expect("Error al ejecutar git status");
+        assert_eq!(
+            result,
+            "On branch master\nnothing to commit, working tree clean\n"
+        );
+
+        let local_ref = format!("{}/.git/refs/heads/master", directory);
+        let remote_ref = format!("{}/.git/refs/remotes/origin/master", directory);
+        let first_hash = fs::read_to_string(&local_ref).expect("Falló al leer la rama");
+        fs::create_dir_all(format!("{}/.git/refs/remotes/origin", directory))
+            .expect("Falló al crear el directorio");
+        create_file_replace(&remote_ref, &first_hash).expect("Falló al escribir la rama");
+        let result = git_status(directory).expect("Error al ejecutar git status");
+        assert!(result.contains("Your branch is up to date with 'origin/master'."));
+
+        create_file_replace(&file_path, "Chau Mundo").expect("Falló al escribir el archivo");
+        git_add(directory, "testfile.rs").expect("Error al ejecutar git add");
+        let test_commit2 = test_commit("prueba2");
+        git_commit(directory, test_commit2).expect("Error al commitear");
+        let result = git_status(directory).expect("Error al ejecutar git status");
+        assert!(result.contains("Your branch is ahead of 'origin/master' by 1 commit."));
+
+        let second_hash = fs::read_to_string(&local_ref).expect("Falló al leer la rama");
+        create_file_replace(&remote_ref, &second_hash).expect("Falló al escribir la rama");
+        create_file_replace(&local_ref, &first_hash).expect("Falló al escribir la rama");
+        let result = git_status(directory).expect("Error al ejecutar git status");
+        assert!(result.contains("Your branch is behind 'origin/master' by 1 commit"));
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+    }
+
+    #[test]
+    fn test_status_ignore_case_rename() {
+        let directory: &str = "./test_status_ignorecase";
+        git_init(directory).expect("Error al ejecutar git init");
+        git_config_set(directory, "core.ignorecase", "true").expect("Falló al configurar");
+
+        let file_path = format!("{}/{}", directory, "file.txt");
+        let mut file = fs::File::create(&file_path).expect("Falló al crear el archivo");
+        file.write_all(b"Hola Mundo")
+            .expect("Error al escribir en el archivo");
+        git_add(directory, "file.txt").expect("Error al ejecutar git add");
+        let test_commit = test_commit("prueba");
+        git_commit(directory, test_commit).expect("Error al commitear");
+
+        fs::rename(&file_path, format!("{}/{}", directory, "File.txt"))
+            .expect("Falló al renombrar el archivo");
+        let result = git_status(directory).expect("Error al ejecutar git status");
+        assert!(!result.contains("deleted"));
+        assert!(!result.contains("new file"));
+
+        git_add(directory, "File.txt").expect("Error al ejecutar git add");
+        let index_content =
+            get_index_content(&format!("{}/{}", directory, GIT_DIR)).expect("Falló al leer");
+        assert!(index_content.starts_with("file.txt "));
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+    }
+
     #[test]
     fn skip_gitignore_files_status() {
         let directory = "./test_status_skips_gitignore_files";
@@ -844,4 +1256,26 @@ mod tests {
         fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn skip_nested_gitignore_files_status() {
+        let directory = "./test_status_skips_nested_gitignore_files";
+        git_init(directory).expect("Error al ejecutar git init");
+        fs::create_dir_all(format!("{}/src/gen", directory)).expect("Falló al crear el directorio");
+        create_file_replace(&format!("{}/src/.gitignore", directory), "gen/\n*.tmp\n")
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/src/gen/out.rs", directory), "generado")
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/src/cache.tmp", directory), "cache")
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/src/main.rs", directory), "Hola Mundo")
+            .expect("Error al crear el archivo");
+        create_file_replace(&format!("{}/cache.tmp", directory), "cache")
+            .expect("Error al crear el archivo");
+
+        let result = git_status_porcelain(directory, false);
+        assert_eq!(result, Ok("?? cache.tmp\n?? src/main.rs\n".to_string()));
+
+        fs::remove_dir_all(directory).expect("Error al intentar remover el directorio");
+    }
 }
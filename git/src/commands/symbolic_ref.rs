@@ -0,0 +1,158 @@
+use super::errors::CommandsError;
+use crate::consts::{HEAD, REFS_HEADS};
+use crate::models::client::Client;
+use crate::util::files::{create_file_replace, get_git_path};
+use crate::util::validation::{is_valid_obj_id_any_format, is_valid_ref_name};
+use std::fs;
+
+/// Contenido de una referencia simbólica como HEAD.
+///
+/// - `Ref`: la referencia apunta a otra referencia (`ref: refs/heads/master`).
+/// - `Detached`: la referencia contiene directamente el hash de un commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolicRef {
+    Ref(String),
+    Detached(String),
+}
+
+impl SymbolicRef {
+    /// Lee una referencia simbólica del directorio `.git`.
+    /// ###Parametros:
+    /// 'directory': directorio del repositorio local
+    /// 'name': nombre de la referencia, por ejemplo `HEAD`
+    pub fn read(directory: &str, name: &str) -> Result<SymbolicRef, CommandsError> {
+        let path = get_git_path(directory, name);
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Err(CommandsError::HeadBranchError),
+        };
+        SymbolicRef::parse(&content)
+    }
+
+    /// Interpreta el contenido de una referencia simbólica.
+    /// ###Parametros:
+    /// 'content': contenido del archivo de la referencia
+    pub fn parse(content: &str) -> Result<SymbolicRef, CommandsError> {
+        let content = content.trim();
+        if let Some(target) = content.strip_prefix("ref:") {
+            // Se toleran referencias escritas con una barra inicial (`ref: /refs/heads/...`)
+            let target = target.trim().trim_start_matches('/');
+            return Ok(SymbolicRef::Ref(target.to_string()));
+        }
+        if is_valid_obj_id_any_format(content) {
+            return Ok(SymbolicRef::Detached(content.to_string()));
+        }
+        Err(CommandsError::HeadBranchError)
+    }
+
+    /// Devuelve el nombre de la branch a la que apunta la referencia,
+    /// o `CommandsError::DetachedHead` si no apunta a ninguna branch.
+    pub fn get_branch_name(&self) -> Result<&str, CommandsError> {
+        match self {
+            SymbolicRef::Ref(target) => match target.strip_prefix(REFS_HEADS) {
+                Some(name) => Ok(name.trim_start_matches('/')),
+                None => Err(CommandsError::DetachedHead),
+            },
+            SymbolicRef::Detached(_) => Err(CommandsError::DetachedHead),
+        }
+    }
+}
+
+/// Esta función se encarga de llamar al comando symbolic-ref con los parametros necesarios.
+///
+/// * `git symbolic-ref HEAD`: muestra la referencia a la que apunta HEAD.
+/// * `git symbolic-ref HEAD <referencia>`: hace que HEAD apunte a la referencia indicada.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función symbolic-ref
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_symbolic_ref(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.len() {
+        1 => match SymbolicRef::read(directory, args[0])? {
+            SymbolicRef::Ref(target) => Ok(target),
+            SymbolicRef::Detached(_) => Err(CommandsError::DetachedHead),
+        },
+        2 => {
+            set_symbolic_ref(directory, args[0], args[1])?;
+            Ok(String::new())
+        }
+        _ => Err(CommandsError::InvalidArgumentCountSymbolicRefError),
+    }
+}
+
+/// Escribe una referencia simbólica para que apunte a otra referencia.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'name': nombre de la referencia simbólica, por ejemplo `HEAD`
+/// 'target': referencia destino, por ejemplo `refs/heads/master`
+pub fn set_symbolic_ref(directory: &str, name: &str, target: &str) -> Result<(), CommandsError> {
+    // El nombre termina en una ruta dentro de .git, por lo que no puede salir de ese directorio
+    if name != HEAD && !is_valid_ref_name(name) {
+        return Err(CommandsError::InvalidSymbolicRefName(name.to_string()));
+    }
+    if !target.starts_with("refs/") || target.contains("..") {
+        return Err(CommandsError::InvalidSymbolicRefTarget(target.to_string()));
+    }
+    let path = get_git_path(directory, name);
+    create_file_replace(&path, &format!("ref: {}\n", target))?;
+    Ok(())
+}
+
+/// Escribe directamente el hash de un commit en HEAD, dejando el repositorio en estado
+/// detached HEAD.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'hash': hash del commit
+pub fn set_detached_head(directory: &str, hash: &str) -> Result<(), CommandsError> {
+    let path = get_git_path(directory, HEAD);
+    create_file_replace(&path, &format!("{}\n", hash.trim()))?;
+    Ok(())
+}
+
+/// Devuelve el contenido de HEAD del repositorio.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+pub fn read_head(directory: &str) -> Result<SymbolicRef, CommandsError> {
+    SymbolicRef::read(directory, HEAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_symbolic_ref() {
+        let head = SymbolicRef::parse("ref: refs/heads/feature/login\n").expect("Falló al leer");
+        assert_eq!(head.get_branch_name(), Ok("feature/login"));
+
+        let hash = "0123456789abcdef0123456789abcdef01234567";
+        let head = SymbolicRef::parse(hash).expect("Falló al leer");
+        assert_eq!(head, SymbolicRef::Detached(hash.to_string()));
+        assert_eq!(head.get_branch_name(), Err(CommandsError::DetachedHead));
+
+        assert!(SymbolicRef::parse("cualquier cosa").is_err());
+    }
+
+    #[test]
+    fn test_set_symbolic_ref() {
+        let directory = "./test_symbolic_ref";
+        git_init(directory).expect("Falló al crear el repositorio");
+
+        set_symbolic_ref(directory, HEAD, "refs/heads/develop").expect("Falló al escribir HEAD");
+        let head = read_head(directory).expect("Falló al leer HEAD");
+        assert_eq!(head, SymbolicRef::Ref("refs/heads/develop".to_string()));
+        assert!(set_symbolic_ref(directory, HEAD, "develop").is_err());
+        assert_eq!(
+            set_symbolic_ref(directory, "../../HEAD", "refs/heads/develop"),
+            Err(CommandsError::InvalidSymbolicRefName(
+                "../../HEAD".to_string()
+            ))
+        );
+        assert!(!Path::new("./HEAD").exists());
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
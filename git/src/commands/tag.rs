@@ -2,7 +2,9 @@ use super::errors::CommandsError;
 use crate::consts::{GIT_DIR, REFS_TAGS};
 use crate::models::client::Client;
 use crate::util::files::{create_file, delete_file, open_file, read_file_string};
+use crate::util::object_format::get_object_format;
 use crate::util::objects::builder_object_tag;
+use crate::util::validation::valid_ref_name;
 
 use super::branch::get_current_branch;
 
@@ -105,6 +107,7 @@ pub fn git_tag_create(
     tag_name: &str,
     version_name: &str,
 ) -> Result<String, CommandsError> {
+    valid_ref_name(tag_name)?;
     let tags = get_tags(directory)?;
     if tags.contains(&tag_name.to_string()) {
         return Err(CommandsError::TagAlreadyExistsError);
@@ -136,7 +139,7 @@ pub fn git_tag_create(
         version_name,
     );
 
-    let tag_hash = builder_object_tag(&tag_content, &git_dir)?;
+    let tag_hash = builder_object_tag(&tag_content, &git_dir, get_object_format(&git_dir))?;
 
     let dir_tag = format!("{}/.git/refs/tags/{}", directory, tag_name);
 
@@ -0,0 +1,191 @@
+use super::branch::{get_branch, get_current_branch};
+use super::checkout::read_parent_commit;
+use super::errors::CommandsError;
+use super::symbolic_ref::SymbolicRef;
+use crate::consts::{
+    COMMONDIR_FILE, GITDIR_FILE, GITDIR_PREFIX, GIT_DIR, HEAD, REFS_HEADS, WORKTREES_DIR,
+};
+use crate::models::client::Client;
+use crate::util::files::{
+    create_directory, create_file_replace, get_common_git_dir, get_git_path, is_folder_empty,
+    open_file, read_file_string,
+};
+use crate::util::index::write_index;
+use crate::util::validation::join_paths_correctly;
+use std::fs;
+use std::path::Path;
+
+/// Esta función se encarga de llamar al comando worktree con los parametros necesarios.
+///
+/// * `git worktree add <ruta> <branch>`: crea un working tree enlazado en la ruta indicada, con
+///   la branch ya existente, que comparte los objetos y las referencias del repositorio. Las
+///   rutas relativas se interpretan respecto del directorio del repositorio.
+///
+/// ###Parametros:
+/// 'args': Vector de strings que contiene los argumentos que se le pasan a la función worktree
+/// 'client': Cliente que contiene la información del cliente que se conectó
+pub fn handle_worktree(args: Vec<&str>, client: Client) -> Result<String, CommandsError> {
+    let directory = client.get_directory_path();
+    match args.as_slice() {
+        ["add", path, branch] => {
+            let path = match Path::new(path).is_absolute() {
+                true => path.to_string(),
+                false => join_paths_correctly(directory, path),
+            };
+            git_worktree_add(directory, &path, branch)
+        }
+        _ => Err(CommandsError::InvalidArgumentCountWorktreeError),
+    }
+}
+
+/// Crea un working tree enlazado en `path` con la branch indicada.
+///
+/// El directorio del working tree tiene un archivo `.git` con una línea `gitdir: <ruta>` que
+/// apunta a `.git/worktrees/<nombre>` del repositorio principal. Allí se guardan el HEAD y el
+/// index propios del working tree, y el archivo `commondir` con la ruta al `.git` compartido,
+/// donde se guardan los objetos y las referencias. Una branch no puede estar en uso en dos
+/// working trees a la vez.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'path': ruta del nuevo working tree
+/// 'branch': branch existente que se carga en el working tree
+pub fn git_worktree_add(
+    directory: &str,
+    path: &str,
+    branch: &str,
+) -> Result<String, CommandsError> {
+    if !get_branch(directory)?.contains(&branch.to_string()) {
+        return Err(CommandsError::BranchNotFoundError);
+    }
+    let common_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+    if get_checked_out_branches(directory, &common_dir).contains(&branch.to_string()) {
+        return Err(CommandsError::WorktreeBranchCheckedOut(branch.to_string()));
+    }
+    let name = match Path::new(path).file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return Err(CommandsError::WorktreePathExists(path.to_string())),
+    };
+    let admin_dir = format!("{}/{}/{}", common_dir, WORKTREES_DIR, name);
+    let path_in_use = Path::new(path).exists() && !is_folder_empty(path).unwrap_or(false);
+    if path_in_use || Path::new(&admin_dir).exists() {
+        return Err(CommandsError::WorktreePathExists(path.to_string()));
+    }
+
+    create_directory(Path::new(path))?;
+    create_directory(Path::new(&admin_dir))?;
+    let absolute_path = canonicalize(path)?;
+    let absolute_admin_dir = canonicalize(&admin_dir)?;
+
+    create_file_replace(
+        &format!("{}/{}", admin_dir, HEAD),
+        &format!("ref: {}/{}\n", REFS_HEADS, branch),
+    )?;
+    create_file_replace(&format!("{}/{}", admin_dir, COMMONDIR_FILE), "../..\n")?;
+    create_file_replace(
+        &format!("{}/{}", admin_dir, GITDIR_FILE),
+        &format!("{}/{}\n", absolute_path, GIT_DIR),
+    )?;
+    write_index(&admin_dir, "")?;
+    create_file_replace(
+        &format!("{}/{}", path, GIT_DIR),
+        &format!("{} {}\n", GITDIR_PREFIX, absolute_admin_dir),
+    )?;
+
+    // Se cargan el working directory y el index con el tree del último commit de la branch
+    let branch_path = get_git_path(path, &format!("{}/{}", REFS_HEADS, branch));
+    let hash_commit = read_file_string(open_file(&branch_path)?)?;
+    read_parent_commit(path, hash_commit.trim(), 0)?;
+
+    Ok(format!("Preparing worktree (checking out '{}')\n", branch))
+}
+
+/// Devuelve las branches en uso en el working tree principal y en los working trees enlazados.
+/// ###Parametros:
+/// 'directory': directorio del repositorio local
+/// 'common_dir': directorio git compartido por los working trees
+fn get_checked_out_branches(directory: &str, common_dir: &str) -> Vec<String> {
+    let mut branches: Vec<String> = get_current_branch(directory).into_iter().collect();
+    let entries = match fs::read_dir(format!("{}/{}", common_dir, WORKTREES_DIR)) {
+        Ok(entries) => entries,
+        Err(_) => return branches,
+    };
+    for entry in entries.flatten() {
+        let head = match fs::read_to_string(entry.path().join(HEAD)) {
+            Ok(head) => head,
+            Err(_) => continue,
+        };
+        if let Ok(head) = SymbolicRef::parse(&head) {
+            if let Ok(branch) = head.get_branch_name() {
+                branches.push(branch.to_string());
+            }
+        }
+    }
+    branches
+}
+
+/// Devuelve la ruta absoluta de un directorio existente.
+/// ###Parametros:
+/// 'path': ruta del directorio
+fn canonicalize(path: &str) -> Result<String, CommandsError> {
+    match fs::canonicalize(path) {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(_) => Err(CommandsError::ReadDirError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        branch::git_branch_create, rev_parse::git_rev_parse, status::is_files_to_commit,
+    };
+    use crate::commands::{cat_file::git_cat_file, init::git_init};
+    use crate::test_utils::commit_file;
+    use crate::util::files::get_git_dir;
+    use crate::util::objects::object_exists;
+
+    #[test]
+    fn test_git_worktree_add_and_commit() {
+        let directory = "./test_worktree_main";
+        let linked = "./test_worktree_linked";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let first = commit_file(directory, "a.txt", "uno");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+
+        assert_eq!(
+            git_worktree_add(directory, linked, "master"),
+            Err(CommandsError::WorktreeBranchCheckedOut(
+                "master".to_string()
+            ))
+        );
+        git_worktree_add(directory, linked, "feature").expect("Falló al crear el working tree");
+        assert_eq!(
+            git_worktree_add(directory, "./test_worktree_other", "feature"),
+            Err(CommandsError::WorktreeBranchCheckedOut(
+                "feature".to_string()
+            ))
+        );
+
+        // El working tree enlazado tiene los archivos de la branch y su propio HEAD
+        let content = fs::read_to_string(format!("{}/a.txt", linked));
+        assert_eq!(content.ok(), Some("uno".to_string()));
+        assert_eq!(get_current_branch(linked), Ok("feature".to_string()));
+        assert_eq!(is_files_to_commit(linked), Ok(false));
+
+        let second = commit_file(linked, "b.txt", "dos");
+
+        // El commit queda en el almacén de objetos compartido y actualiza la branch
+        assert!(object_exists(directory, &second));
+        assert_eq!(
+            git_cat_file(directory, &second, "-t"),
+            Ok("commit".to_string())
+        );
+        assert_eq!(git_rev_parse(directory, "feature"), Ok(second));
+        assert_eq!(git_rev_parse(directory, HEAD), Ok(first));
+        assert!(!Path::new(&format!("{}/objects", get_git_dir(linked))).exists());
+        assert!(!Path::new(&format!("{}/b.txt", directory)).exists());
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(linked).expect("Falló al remover el directorio temporal");
+    }
+}
@@ -23,7 +23,10 @@ use std::{
 
 use crate::{
     consts::*,
-    util::validation::{valid_directory_src, valid_email, valid_ip, valid_port},
+    util::validation::{
+        valid_default_branch, valid_directory_src, valid_email, valid_ip, valid_max_repositories,
+        valid_network_timeout, valid_port, valid_rate_limit, valid_webhook_url,
+    },
 };
 use crate::{errors::GitError, util::validation::valid_path_log};
 
@@ -39,6 +42,12 @@ pub struct Config {
     pub port_daemon: String,
     pub port_http: String,
     pub src: String,
+    pub max_repositories: usize,
+    pub rate_limit_refill: u32,
+    pub rate_limit_burst: u32,
+    pub network_timeout: u64,
+    pub webhook_urls: Vec<String>,
+    pub default_branch: String,
 }
 
 impl fmt::Display for Config {
@@ -65,6 +74,12 @@ impl Config {
             port_daemon: GIT_DAEMON_PORT.to_string(),
             port_http: HTTP_PORT_DEFAULT.to_string(),
             src: SRC_DEFAULT.to_string(),
+            max_repositories: MAX_REPOSITORIES_DEFAULT,
+            rate_limit_refill: RATE_LIMIT_REFILL_DEFAULT,
+            rate_limit_burst: RATE_LIMIT_BURST_DEFAULT,
+            network_timeout: NETWORK_TIMEOUT_DEFAULT,
+            webhook_urls: Vec::new(),
+            default_branch: INITIAL_BRANCH.to_string(),
         };
 
         read_input(&path, &mut config, process_line)?;
@@ -116,7 +131,7 @@ pub fn open_file_for_reading(path: &str) -> Result<File, GitError> {
 /// Devuelve un error en caso de que la línea no sea válida.
 pub fn process_line(line: &str, config: &mut Config) -> Result<(), GitError> {
     let line = line.trim();
-    let mut parts = line.split('=');
+    let mut parts = line.splitn(2, '=');
     let key = parts.next().ok_or(GitError::InvalidConfigFormatError)?;
     let value = parts.next().ok_or(GitError::InvalidConfigFormatError)?;
 
@@ -128,6 +143,12 @@ pub fn process_line(line: &str, config: &mut Config) -> Result<(), GitError> {
         "port_daemon" => config.port_daemon = valid_port(value)?,
         "port_http" => config.port_http = valid_port(value)?,
         "src" => config.src = valid_directory_src(value)?, //value.to_string()
+        "max_repositories" => config.max_repositories = valid_max_repositories(value)?,
+        "rate_limit_refill" => config.rate_limit_refill = valid_rate_limit(value)?,
+        "rate_limit_burst" => config.rate_limit_burst = valid_rate_limit(value)?,
+        "network_timeout" => config.network_timeout = valid_network_timeout(value)?,
+        "webhook_url" => config.webhook_urls.push(valid_webhook_url(value)?),
+        "init.defaultBranch" => config.default_branch = valid_default_branch(value)?,
         _ => return Err(GitError::InvalidConfigurationValueError),
     }
     Ok(())
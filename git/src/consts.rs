@@ -24,6 +24,9 @@ pub const CONFIG_PATH_ARG_INDEX: usize = 1;
 // Path por defecto del archivo log.
 pub const LOG_PATH_DEFAULT: &str = "./default.log";
 
+// Variable de entorno que habilita los mensajes de log de nivel debug ("1" o "true").
+pub const LOG_DEBUG_ENV: &str = "RUSTICO_DEBUG";
+
 // Path por defecto del src
 pub const SRC_DEFAULT: &str = "client_root";
 
@@ -60,6 +63,33 @@ pub const GIT_DAEMON_PORT: u16 = 9418;
 // Puerto por defecto para HTTP
 pub const HTTP_PORT_DEFAULT: u16 = 8080;
 
+// Tokens por segundo que recarga cada cliente del servidor HTTP
+pub const RATE_LIMIT_REFILL_DEFAULT: u32 = 10;
+
+// Cantidad máxima de solicitudes seguidas que puede realizar un cliente del servidor HTTP
+pub const RATE_LIMIT_BURST_DEFAULT: u32 = 20;
+
+// Cantidad máxima de repositorios que se pueden crear en el servidor HTTP
+pub const MAX_REPOSITORIES_DEFAULT: usize = 1000;
+
+// Segundos entre cada limpieza de los clientes inactivos del limitador
+pub const RATE_LIMIT_PRUNE_INTERVAL: u64 = 60;
+
+// Segundos que se espera a que la otra parte envíe o reciba datos antes de reintentar
+pub const NETWORK_TIMEOUT_DEFAULT: u64 = 30;
+
+// Cantidad de intentos de envío de cada evento a un webhook
+pub const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+
+// Segundos de espera entre intentos de envío a un webhook (se multiplica por el número de intento)
+pub const WEBHOOK_RETRY_DELAY: u64 = 1;
+
+// Segundos que se espera a que el webhook acepte la conexión y responda
+pub const WEBHOOK_TIMEOUT: u64 = 5;
+
+// Cantidad de reintentos ante un timeout o una interrupción antes de abandonar la conexión
+pub const NETWORK_MAX_RETRIES: usize = 3;
+
 // Request de git-upload-pack
 pub const GIT_UPLOAD_PACK: &str = "git-upload-pack";
 
@@ -83,10 +113,47 @@ pub const DONE: &str = "done";
 
 pub const PKT_NAK: &str = "0008NAK\n";
 
+pub const PKT_ACK: &str = "0008ACK\n";
+
+// Prefijo de la línea con la que el servidor informa un error en lugar de las referencias
+pub const ERR_PREFIX: &str = "ERR ";
+
+// Bandas del protocolo side-band
+pub const SIDE_BAND_DATA: u8 = 1;
+
+pub const SIDE_BAND_PROGRESS: u8 = 2;
+
+pub const SIDE_BAND_ERROR: u8 = 3;
+
+// Máximo de datos en una línea pkt-line (65520 - 4 del largo)
+pub const PKT_LINE_MAX_DATA: usize = 65516;
+
+// Máximo de datos en una línea side-band (65520 - 4 del largo - 1 de la banda)
+pub const SIDE_BAND_MAX_DATA: usize = 65515;
+
 pub const PACK_SIGNATURE: &str = "PACK";
 
 pub const PACK_BYTES: [u8; 4] = [b'P', b'A', b'C', b'K'];
 
+// Firma, versión y cantidad de objetos, de 4 bytes cada uno
+pub const PACKFILE_HEADER_SIZE: usize = 12;
+
+// Límites por defecto de los packfiles que recibe el servidor en un push
+pub const PACKFILE_MAX_BYTES: usize = 512 * 1024 * 1024;
+
+pub const PACKFILE_MAX_OBJECT_SIZE: usize = 128 * 1024 * 1024;
+
+pub const PACKFILE_MAX_OBJECTS: usize = 1_000_000;
+
+// Claves de la sección `receive` de la configuración con las que se cambian esos límites
+pub const RECEIVE_SECTION: &str = "receive";
+
+pub const RECEIVE_MAX_INPUT_SIZE_KEY: &str = "maxinputsize";
+
+pub const RECEIVE_MAX_OBJECT_SIZE_KEY: &str = "maxobjectsize";
+
+pub const RECEIVE_MAX_OBJECTS_KEY: &str = "maxobjects";
+
 pub const SPACE: u8 = 32;
 
 pub const NULL: u8 = 0;
@@ -99,14 +166,49 @@ pub const HAVE: &str = "have";
 
 pub const WANT: &str = "want";
 
-pub const CAPABILITIES_FETCH: [&str; 1] = ["multi_ack"];
+pub const MULTI_ACK: &str = "multi_ack";
+
+pub const OFS_DELTA: &str = "ofs-delta";
+
+pub const NO_PROGRESS: &str = "no-progress";
+
+// Capacidad con la que el packfile se envía multiplexado en bandas de hasta 65520 bytes
+pub const SIDE_BAND_64K: &str = "side-band-64k";
+
+pub const REPORT_STATUS: &str = "report-status";
+
+// Capacidad con la que el servidor acepta que el cliente borre referencias en un push
+pub const DELETE_REFS: &str = "delete-refs";
+
+// Capacidad con la que el servidor pide que el packfile de un push no tenga deltas contra objetos
+// que no vienen en él, ya que no puede resolverlos
+pub const NO_THIN: &str = "no-thin";
+
+// Capacidad y línea de la negociación con la que el cliente pide un clone parcial
+pub const FILTER: &str = "filter";
+
+// Filtro que omite todos los blobs del packfile
+pub const FILTER_BLOB_NONE: &str = "blob:none";
+
+// Archivo dentro de .git que marca el repositorio como clone parcial; contiene el filtro usado
+pub const PROMISOR_FILE: &str = "promisor";
+
+// Archivo dentro de .git que marca un clone interrumpido, cuyos objetos recibidos se conservan
+// para retomarlo
+pub const CLONE_IN_PROGRESS_FILE: &str = "clone-in-progress";
+
+pub const CAPABILITIES_FETCH: [&str; 3] = [MULTI_ACK, OFS_DELTA, NO_PROGRESS];
 
-pub const CAPABILITIES_PUSH: [&str; 1] = ["report-status"];
+pub const CAPABILITIES_PUSH: [&str; 3] = [REPORT_STATUS, DELETE_REFS, NO_THIN];
+
+// Capacidades sin las que no se puede llevar a cabo la negociación
+pub const CAPABILITIES_REQUIRED: [&str; 2] = [MULTI_ACK, REPORT_STATUS];
 
 // Directorios
 pub const GIT_DIR: &str = ".git";
 
 pub const HEAD: &str = "HEAD";
+pub const FETCH_HEAD: &str = "FETCH_HEAD";
 
 pub const INITIAL_BRANCH: &str = "master";
 
@@ -124,12 +226,41 @@ pub const REFS_REMOTES: &str = "refs/remotes";
 
 pub const REFS_TAGS: &str = "refs/tags";
 
+pub const REFS_NOTES: &str = "refs/notes/commits";
+
 pub const ORIGIN: &str = "origin";
 
 pub const DIR_REFS: &str = "refs";
 
 pub const DIR_OBJECTS: &str = "objects";
 
+pub const ALTERNATES: &str = "info/alternates";
+
+pub const COMMIT_EDITMSG: &str = "COMMIT_EDITMSG";
+
+// Trailers del mensaje de un commit
+pub const SIGNED_OFF_BY: &str = "Signed-off-by";
+
+pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+
+// Index binario (formato DIRC de git)
+pub const INDEX_SIGNATURE: &[u8; 4] = b"DIRC";
+
+pub const INDEX_VERSION: u32 = 2;
+
+pub const INDEX_DEFAULT_MODE: u32 = 0o100644;
+
+pub const LOGS_DIR: &str = "logs";
+
+// Working trees enlazados
+pub const WORKTREES_DIR: &str = "worktrees";
+
+pub const GITDIR_PREFIX: &str = "gitdir:";
+
+pub const GITDIR_FILE: &str = "gitdir";
+
+pub const COMMONDIR_FILE: &str = "commondir";
+
 pub const CONTENT_EMPTY: &str = "";
 
 // Objetos
@@ -147,18 +278,83 @@ pub const DIRECTORY: &str = "40000";
 
 pub const FILE: &str = "100644";
 
+pub const EXECUTABLE_FILE: &str = "100755";
+
+pub const SYMLINK: &str = "120000";
+
+pub const SUBMODULE: &str = "160000";
+
+// Tamaño de los bloques de un archivo tar
+pub const TAR_BLOCK_SIZE: usize = 512;
+
+// Longitud minima de un hash abreviado
+pub const ABBREV_MIN_LENGTH: usize = 7;
+
 pub const PARENT_INITIAL: &str = "0000000000000000000000000000000000000000";
 
+// Formato con el que git muestra las fechas de los commits, por ejemplo `Sun Dec 3 16:43:54 2023 -0300`
+pub const DATE_FORMAT: &str = "%a %b %-d %H:%M:%S %Y %z";
+
 pub const VERSION_DEFAULT: u32 = 2;
 
 pub const CONFIG_FILE: &str = "config";
 
+pub const CORE_IGNORECASE: &str = "ignorecase";
+
+pub const OBJECT_FORMAT_KEY: &str = "objectformat";
+
+pub const MERGE_CONFLICT_STYLE_KEY: &str = "conflictstyle";
+
+pub const MERGE_PR_TEMPLATE_KEY: &str = "prtemplate";
+
+pub const CONFLICT_STYLE_DIFF3: &str = "diff3";
+
+pub const PR_MERGE_MESSAGE_TEMPLATE: &str =
+    "Merge pull request #{number} from {repo}/{head}. Title {title}";
+
+pub const SHA1_NAME: &str = "sha1";
+
+pub const SHA256_NAME: &str = "sha256";
+
+pub const CORE_EXCLUDES_FILE: &str = "excludesfile";
+
+pub const GITIGNORE: &str = ".gitignore";
+
+pub const INFO_EXCLUDE: &str = "info/exclude";
+
 pub const CONFIG_REMOTE_FETCH: &str = "+refs/heads/*:refs/remotes/origin/*";
 
 pub const ZERO_ID: &str = "0000000000000000000000000000000000000000";
 
+// Identidad con la que se registran en el reflog las operaciones que no tienen un usuario asociado
+pub const REFLOG_DEFAULT_IDENTITY: &str = "Rusteam <noreply@rusteam.com>";
+
+pub const CAPABILITIES_EMPTY_REPO: &str = "capabilities^{}";
+
+pub const PEELED_SUFFIX: &str = "^{}";
+
+// Capacidad con la que se anuncia la referencia a la que apunta HEAD (`symref=HEAD:refs/heads/main`)
+pub const SYMREF_HEAD: &str = "symref=HEAD:";
+
+// Capacidad con la que se anuncia el formato de los objetos (`object-format=sha256`); sin ella es SHA-1
+pub const OBJECT_FORMAT_CAPABILITY: &str = "object-format=";
+
+pub const PACKED_REFS: &str = "packed-refs";
+
+pub const MAX_SYMREF_DEPTH: usize = 5;
+pub const MAX_HAVE_COMMITS: usize = 256;
+
 pub const BUFFER_SIZE: usize = 1024;
 
+// Header de un objeto suelto (`<tipo> <tamaño>\0`); el más largo es `commit <u64>\0`
+pub const OBJECT_HEADER_MAX_LEN: usize = 32;
+
+// Bytes comprimidos que se leen por vez de un objeto suelto
+pub const OBJECT_READ_BUFFER_SIZE: usize = 8 * 1024;
+
+// Capacidad inicial máxima del buffer de un objeto; el tamaño del header no es confiable
+pub const OBJECT_INITIAL_CAPACITY_MAX: usize = 64 * 1024;
+
 pub const UNPACK_OK: &str = "unpack ok\n";
 
 // Pull Request
@@ -189,14 +385,55 @@ pub const TEXT_YAML: &str = "text/yaml";
 
 pub const TEXT_XML: &str = "text/xml";
 
+pub const TEXT_PLAIN: &str = "text/plain";
+
+pub const MULTIPART_FORM_DATA: &str = "multipart/form-data";
+
+pub const GIT_UPLOAD_PACK_ADVERTISEMENT: &str = "application/x-git-upload-pack-advertisement";
+
+pub const GIT_UPLOAD_PACK_RESULT: &str = "application/x-git-upload-pack-result";
+
+pub const GIT_INFO_REFS: &str = "/info/refs";
+
 pub const PR_FILE_EXTENSION: &str = ".json";
 
+pub const TEMP_FILE_SUFFIX: &str = ".tmp";
+
 pub const CONTENT_LENGTH: &str = "Content-Length";
 
 pub const CONTENT_TYPE: &str = "Content-Type";
 
+pub const CONTENT_DISPOSITION: &str = "Content-Disposition";
+
+pub const TRANSFER_ENCODING: &str = "Transfer-Encoding";
+
+pub const CHUNKED: &str = "chunked";
+
+pub const ACCEPT: &str = "Accept";
+
+pub const AUTHORIZATION: &str = "Authorization";
+
+pub const IF_NONE_MATCH: &str = "If-None-Match";
+
+pub const ETAG: &str = "ETag";
+
+pub const HTTP_CREDENTIALS_FILE: &str = "credentials";
+
+// Archivo con la política de acceso a los repositorios del git daemon
+pub const ACCESS_POLICY_FILE: &str = "access";
+
+// Hooks del servidor, dentro del directorio git del repositorio
+pub const HOOKS_DIR: &str = "hooks";
+
+pub const PRE_RECEIVE_HOOK: &str = "pre-receive";
+
+pub const POST_RECEIVE_HOOK: &str = "post-receive";
+
 pub const PR_MAP_FILE: &str = "pr_map.json";
 
+// Cantidad máxima de líneas de diff que se devuelven para los archivos de un pull request
+pub const PR_DIFF_MAX_LINES: usize = 1000;
+
 pub const MESSAGE: &str = "message";
 
 pub const OPEN: &str = "open";
@@ -1,11 +1,15 @@
 use crate::commands::{
-    add::handle_add, branch::handle_branch, cat_file::handle_cat_file,
-    check_ignore::handle_check_ignore, checkout::handle_checkout, clone::handle_clone,
-    commit::handle_commit, errors::CommandsError, fetch::handle_fetch,
-    hash_object::handle_hash_object, init::handle_init, log::handle_log, ls_files::handle_ls_files,
-    ls_tree::handle_ls_tree, merge::handle_merge, pull::handle_pull, push::handle_push,
-    rebase::handle_rebase, remote::handle_remote, rm::handle_rm, show_ref::handle_show_ref,
-    status::handle_status, tag::handle_tag,
+    add::handle_add, apply::handle_apply, archive::handle_archive, blame::handle_blame,
+    branch::handle_branch, cat_file::handle_cat_file, check_ignore::handle_check_ignore,
+    checkout::handle_checkout, clone::handle_clone, commit::handle_commit, config::handle_config,
+    describe::handle_describe, errors::CommandsError, fetch::handle_fetch, gc::handle_gc,
+    gc::handle_prune, hash_object::handle_hash_object, init::handle_init, log::handle_log,
+    ls_files::handle_ls_files, ls_remote::handle_ls_remote, ls_tree::handle_ls_tree,
+    merge::handle_merge, merge_base::handle_merge_base, mv::handle_mv, notes::handle_notes,
+    pull::handle_pull, push::handle_push, rebase::handle_rebase, reflog::handle_reflog,
+    remote::handle_remote, rev_parse::handle_rev_parse, rm::handle_rm, show::handle_show,
+    show_ref::handle_show_ref, status::handle_status, symbolic_ref::handle_symbolic_ref,
+    tag::handle_tag, worktree::handle_worktree,
 };
 
 use crate::errors::GitError;
@@ -125,6 +129,7 @@ fn handle_command(buffer: String, client: &mut Client) -> Result<String, GitErro
             "init" => result = handle_init(rest_of_command, client.clone())?,
             "cat-file" => result = handle_cat_file(rest_of_command, client.clone())?,
             "add" => result = handle_add(rest_of_command, client.clone())?,
+            "apply" => result = handle_apply(rest_of_command, client.clone())?,
             "checkout" => result = handle_checkout(rest_of_command, client.clone())?,
             "hash-object" => result = handle_hash_object(rest_of_command, client.clone())?,
             "status" => result = handle_status(rest_of_command, client.clone())?,
@@ -132,14 +137,29 @@ fn handle_command(buffer: String, client: &mut Client) -> Result<String, GitErro
             "pull" => result = handle_pull(rest_of_command, client.clone())?,
             "push" => result = handle_push(rest_of_command, client.clone())?,
             "merge" => result = handle_merge(rest_of_command, client.clone())?,
+            "describe" => result = handle_describe(rest_of_command, client.clone())?,
+            "merge-base" => result = handle_merge_base(rest_of_command, client.clone())?,
             "remote" => result = handle_remote(rest_of_command, client.clone())?,
             "rm" => result = handle_rm(rest_of_command, client.clone())?,
+            "mv" => result = handle_mv(rest_of_command, client.clone())?,
             "ls-files" => result = handle_ls_files(rest_of_command, client.clone())?,
             "ls-tree" => result = handle_ls_tree(rest_of_command, client.clone())?,
+            "rev-parse" => result = handle_rev_parse(rest_of_command, client.clone())?,
+            "reflog" => result = handle_reflog(rest_of_command, client.clone())?,
+            "archive" => result = handle_archive(rest_of_command, client.clone())?,
+            "blame" => result = handle_blame(rest_of_command, client.clone())?,
+            "gc" => result = handle_gc(rest_of_command, client.clone())?,
+            "notes" => result = handle_notes(rest_of_command, client.clone())?,
+            "prune" => result = handle_prune(rest_of_command, client.clone())?,
+            "ls-remote" => result = handle_ls_remote(rest_of_command, client.clone())?,
+            "show" => result = handle_show(rest_of_command, client.clone())?,
+            "symbolic-ref" => result = handle_symbolic_ref(rest_of_command, client.clone())?,
             "check-ignore" => result = handle_check_ignore(rest_of_command, client.clone())?,
             "show-ref" => result = handle_show_ref(rest_of_command, client.clone())?,
+            "config" => result = handle_config(rest_of_command, client.clone())?,
             "tag" => result = handle_tag(rest_of_command, client.clone())?,
             "rebase" => result = handle_rebase(rest_of_command, client.clone())?,
+            "worktree" => result = handle_worktree(rest_of_command, client.clone())?,
             _ => return Err(GitError::CommandNotRecognizedError),
         }
     } else {
@@ -6,7 +6,7 @@ use std::fmt;
 /// Cada variante de este enum representa un tipo específico de error que puede ocurrir, y
 /// se utiliza para identificar y manejar los errores de manera adecuada.
 ///
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum GitError {
     MissingConfigPathError,
     ConfigFileError,
@@ -16,6 +16,11 @@ pub enum GitError {
     InvalidUserNameError,
     InvalidUserMailError,
     InvalidPortError,
+    InvalidRateLimitError,
+    InvalidNetworkTimeoutError,
+    InvalidMaxRepositoriesError,
+    InvalidWebhookUrlError,
+    InvalidDefaultBranchError,
     InvalidLogDirectoryError,
     InvalidIpError,
     GenericError, // Error genérico, lo uso para tests.
@@ -45,6 +50,11 @@ pub enum GitError {
     DirEntryError,
     NotAGitRepository,
     RequestFailed(String),
+    ObjectTypeMismatch {
+        expected: String,
+        actual: String,
+        hash: String,
+    },
 }
 
 impl GitError {
@@ -68,6 +78,11 @@ impl GitError {
             GitError::InvalidUserNameError => "Nombre de usuario inválido, revise su archivo de configuración.",
             GitError::InvalidUserMailError => "Correo de usuario inválido, revise su archivo de configuración.",
             GitError::InvalidPortError => "Puerto inválido, revise su archivo de configuración.",
+            GitError::InvalidRateLimitError => "Límite de solicitudes inválido, debe ser un entero positivo. Revise su archivo de configuración.",
+            GitError::InvalidNetworkTimeoutError => "Timeout de red inválido, debe ser una cantidad positiva de segundos. Revise su archivo de configuración.",
+            GitError::InvalidMaxRepositoriesError => "Cantidad máxima de repositorios inválida, debe ser un entero positivo. Revise su archivo de configuración.",
+            GitError::InvalidWebhookUrlError => "URL de webhook inválida, debe comenzar con http://. Revise su archivo de configuración.",
+            GitError::InvalidDefaultBranchError => "Branch por defecto inválida, revise su archivo de configuración.",
             GitError::InvalidSrcDirectoryError => "Directorio de código fuente inválido, revise su archivo de configuración.",
             GitError::InvalidLogDirectoryError => "Path de log inválido, revise su archivo de configuración.",
             GitError::InvalidIpError => "Dirección IP inválida, revise su archivo de configuración.",
@@ -97,12 +112,24 @@ impl GitError {
             GitError::DirEntryError => "Falló al obtener la entrada del directorio",
             GitError::NotAGitRepository => "not a git repository",
             GitError::RequestFailed(msg) => msg,
+            GitError::ObjectTypeMismatch { .. } => "El objeto no es del tipo esperado",
         }
     }
 }
 
 fn format_error(error: &GitError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", error.message())
+    match error {
+        GitError::ObjectTypeMismatch {
+            expected,
+            actual,
+            hash,
+        } => write!(
+            f,
+            "fatal: el objeto {} es de tipo {}, se esperaba {}",
+            hash, actual, expected
+        ),
+        _ => write!(f, "{}", error.message()),
+    }
 }
 
 impl fmt::Display for GitError {
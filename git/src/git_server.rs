@@ -1,18 +1,28 @@
 pub mod handle_references;
 pub mod reference_information;
 
-use std::{collections::HashSet, io::Write};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
 
 use crate::{
-    consts::VERSION_DEFAULT,
+    commands::symbolic_ref::{read_head, SymbolicRef},
+    consts::{
+        CAPABILITIES_EMPTY_REPO, CAPABILITIES_REQUIRED, GIT_DIR, MULTI_ACK, PEELED_SUFFIX,
+        REFS_HEADS, SYMREF_HEAD, VERSION_DEFAULT,
+    },
     git_transport::{
         advertised::AdvertisedRefLine,
-        references::{Reference, ReferenceType},
+        references::{read_packed_refs, Reference, ReferenceType},
     },
     util::{
         connections::{send_flush, send_message},
         errors::UtilError,
+        logger::log_debug,
+        object_format::{get_object_format, ObjectFormat},
         pkt_line,
+        validation::join_paths_correctly,
     },
 };
 
@@ -26,6 +36,9 @@ pub struct GitServer {
     pub shallow: Vec<String>,
     pub available_references: Vec<Reference>,
     handle_references: HandleReferences, // No tendra el Head
+    peeled_references: HashMap<String, String>, // Tags empaquetados con su objeto pelado
+    head_symref: Option<String>,         // Referencia a la que apunta HEAD
+    pub object_format: ObjectFormat,     // Formato de los objetos, para el checksum del packfile
 }
 
 impl GitServer {
@@ -42,7 +55,8 @@ impl GitServer {
     ///
     /// # Retorno
     /// Devuelve un `Result` que contiene la estructura `GitServer` si la operación es exitosa,
-    /// o un error de `UtilError` si ocurre algún problema durante el proceso.
+    /// o un error de `UtilError` si ocurre algún problema durante el proceso. Si el servidor
+    /// respondió con una línea `ERR <mensaje>` se devuelve `UtilError::RemoteError` con su mensaje.
     ///
     pub fn new(
         content: &Vec<Vec<u8>>,
@@ -76,18 +90,31 @@ impl GitServer {
         let mut capabilities: Vec<String> = Vec::new();
         let mut shallow: Vec<String> = Vec::new();
         let mut available_references: Vec<Reference> = Vec::new();
+        let mut head_symref: Option<String> = None;
 
         for line in classified {
             match line {
                 AdvertisedRefLine::Version(v) => version = v,
-                AdvertisedRefLine::Capabilities(c) => capabilities = c,
+                AdvertisedRefLine::Capabilities(c) => {
+                    head_symref = c
+                        .iter()
+                        .find_map(|capability| capability.strip_prefix(SYMREF_HEAD))
+                        .map(String::from);
+                    capabilities = c
+                }
                 AdvertisedRefLine::Shallow { obj_id } => shallow.push(obj_id),
+                // Las referencias de tipos que no se manejan (por ejemplo `refs/pull/..`) se ignoran
                 AdvertisedRefLine::Ref { obj_id, ref_name } => {
-                    available_references.push(Reference::new(&obj_id, &ref_name)?)
+                    match Reference::new(&obj_id, &ref_name) {
+                        Ok(reference) => available_references.push(reference),
+                        Err(_) => log_debug(&format!("Referencia ignorada: {}", ref_name)),
+                    }
                 }
             }
         }
 
+        // El formato de los objetos se anuncia como capacidad, que el cliente no negocia
+        let object_format = ObjectFormat::from_capabilities(&capabilities);
         GitServer::filter_capabilities(&mut capabilities, my_capabilities)?;
         Ok(GitServer {
             src_repo: src_repo.to_string(),
@@ -96,6 +123,9 @@ impl GitServer {
             shallow,
             handle_references: HandleReferences::new_from_references(&available_references),
             available_references,
+            peeled_references: HashMap::new(),
+            head_symref,
+            object_format,
         })
     }
 
@@ -105,7 +135,8 @@ impl GitServer {
     /// Devuelve una referencia al vector que contiene las referencias disponibles.
     ///
     /// # Retorno
-    /// Devuelve una referencia al vector que contiene las referencias disponibles.
+    /// Devuelve una referencia al vector que contiene las referencias disponibles, con HEAD
+    /// primero y el resto ordenadas por su ruta.
     ///
     pub fn get_references(&self) -> &Vec<Reference> {
         &self.available_references
@@ -133,7 +164,9 @@ impl GitServer {
     ///
     /// Esta función crea una instancia de la estructura `GitServer` a partir de la ruta del
     /// repositorio, la versión del servidor Git, y las capacidades del servidor. Además, extrae
-    /// las referencias del repositorio utilizando la función `Reference::extract_references_from_git`.
+    /// las referencias del repositorio utilizando la función `Reference::extract_references_from_git`,
+    /// que incluye las referencias de `packed-refs`. Los tags empaquetados que indican su objeto
+    /// pelado se anuncian sin leer el objeto tag.
     ///
     /// # Argumentos
     ///
@@ -153,6 +186,17 @@ impl GitServer {
     ) -> Result<GitServer, UtilError> {
         let available_references = Reference::extract_references_from_git(path_repo)?;
         // GitServer::filter_capabilities(&mut capabilities, );
+        let peeled_references = read_packed_refs(&join_paths_correctly(path_repo, GIT_DIR))
+            .into_iter()
+            .filter(|packed| available_references.contains(&packed.reference))
+            .filter_map(|packed| {
+                Some((packed.reference.get_ref_path().to_string(), packed.peeled?))
+            })
+            .collect();
+        let head_symref = match read_head(path_repo) {
+            Ok(SymbolicRef::Ref(target)) => Some(target),
+            _ => None,
+        };
 
         Ok(GitServer {
             src_repo: path_repo.to_string(),
@@ -161,6 +205,9 @@ impl GitServer {
             shallow: Vec::new(),
             handle_references: HandleReferences::new_from_references(&available_references),
             available_references,
+            peeled_references,
+            head_symref,
+            object_format: get_object_format(&join_paths_correctly(path_repo, GIT_DIR)),
         })
     }
 
@@ -173,16 +220,37 @@ impl GitServer {
             &version,
             UtilError::VersionNotSentDiscoveryReferences,
         )?;
+        self.send_advertisement(writer)
+    }
 
+    /// Envía el anuncio de referencias sin la línea de versión, terminado en un flush.
+    ///
+    /// Es el formato que espera el protocolo HTTP en `info/refs`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `writer` - Destino del anuncio.
+    ///
+    pub fn send_advertisement(&self, writer: &mut dyn Write) -> Result<(), UtilError> {
         // Send references
         // HEAD lo inserte 1ero en el vector
         // Primera refer
-        self.send_first_reference(writer)?;
+        // Si el repositorio está vacío se envían las capacidades en una referencia especial
+        match self.available_references.first() {
+            Some(first) => {
+                self.send_first_reference(writer, first.get_hash(), first.get_ref_path())?
+            }
+            None => {
+                let zero_id = "0".repeat(self.object_format.hex_len());
+                self.send_first_reference(writer, &zero_id, CAPABILITIES_EMPTY_REPO)?
+            }
+        }
 
-        for reference in &self.available_references[1..] {
-            let reference = format!("{} {}\n", reference.get_hash(), reference.get_ref_path());
-            let reference = pkt_line::add_length_prefix(&reference, reference.len());
-            send_message(writer, &reference, UtilError::ReferencesObtaining)?;
+        for reference in self.available_references.iter().skip(1) {
+            let line = format!("{} {}\n", reference.get_hash(), reference.get_ref_path());
+            let line = pkt_line::add_length_prefix(&line, line.len());
+            send_message(writer, &line, UtilError::ReferencesObtaining)?;
+            self.send_peeled_reference(writer, reference)?;
         }
 
         // Send shallow
@@ -196,17 +264,51 @@ impl GitServer {
         Ok(())
     }
 
-    fn send_first_reference(&self, writer: &mut dyn Write) -> Result<(), UtilError> {
-        let mut firts_references = format!(
-            "{} {}",
-            self.available_references[0].get_hash(),
-            self.available_references[0].get_ref_path()
-        );
-        if !self.capabilities.is_empty() {
+    /// Si la referencia es un tag anotado, anuncia también el objeto al que apunta con el
+    /// formato `<hash> refs/tags/<nombre>^{}`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `writer` - Destino del anuncio.
+    /// * `reference` - Referencia recién anunciada.
+    ///
+    fn send_peeled_reference(
+        &self,
+        writer: &mut dyn Write,
+        reference: &Reference,
+    ) -> Result<(), UtilError> {
+        if reference.get_type() != ReferenceType::Tag {
+            return Ok(());
+        }
+        let peeled = match self.peeled_references.get(reference.get_ref_path()) {
+            Some(peeled) => peeled.to_string(),
+            None => match reference.resolve(&self.src_repo) {
+                Ok(resolved) if resolved.raw != resolved.peeled => resolved.peeled,
+                _ => return Ok(()),
+            },
+        };
+        let line = format!("{} {}{}\n", peeled, reference.get_ref_path(), PEELED_SUFFIX);
+        let line = pkt_line::add_length_prefix(&line, line.len());
+        send_message(writer, &line, UtilError::ReferencesObtaining)
+    }
+
+    fn send_first_reference(
+        &self,
+        writer: &mut dyn Write,
+        hash: &str,
+        ref_path: &str,
+    ) -> Result<(), UtilError> {
+        let mut firts_references = format!("{} {}", hash, ref_path);
+        let mut capabilities = self.capabilities.clone();
+        if let Some(target) = &self.head_symref {
+            capabilities.push(format!("{}{}", SYMREF_HEAD, target));
+        }
+        capabilities.extend(self.object_format.capability());
+        if !capabilities.is_empty() {
             let mut len = firts_references.len();
             firts_references.push('\0');
             len += 1;
-            let capabilities = format!("{}\n", self.capabilities.join(" "));
+            let capabilities = format!("{}\n", capabilities.join(" "));
             len += capabilities.len();
             firts_references.push_str(&capabilities);
             let firts_references = pkt_line::add_length_prefix(&firts_references, len);
@@ -219,6 +321,20 @@ impl GitServer {
         }
     }
 
+    /// Devuelve la referencia a la que apunta HEAD en el servidor, anunciada con la capacidad
+    /// `symref=HEAD:<referencia>`, si la hay.
+    pub fn get_head_symref(&self) -> Option<&str> {
+        self.head_symref.as_deref()
+    }
+
+    /// Devuelve el nombre de la branch a la que apunta HEAD en el servidor, si HEAD se anunció
+    /// como una referencia simbólica a una branch.
+    pub fn get_head_branch(&self) -> Option<&str> {
+        self.get_head_symref()?
+            .strip_prefix(REFS_HEADS)?
+            .strip_prefix('/')
+    }
+
     /// Actualiza los datos del `GitServer` con nuevas capacidades y referencias.
     ///
     /// Esta función toma un vector de nuevas capacidades y referencias, y actualiza el `GitServer`
@@ -315,6 +431,9 @@ impl GitServer {
 
     /// Filtra las capacidades del servidor manteniendo solo aquellas que coinciden con las capacidades del cliente.
     ///
+    /// Así nunca se envía una capacidad que no esté implementada. Las capacidades opcionales
+    /// (como `ofs-delta` o `no-progress`) que la otra parte no anuncie simplemente se descartan.
+    ///
     /// # Argumentos
     ///
     /// * `capabilities`: Vector mutable que contiene las capacidades del servidor.
@@ -324,23 +443,38 @@ impl GitServer {
     ///
     /// Retorna un `Result` que contiene un mensaje de éxito (`Ok(())`) si las capacidades fueron filtradas
     /// exitosamente, o un error de utilidad (`Err(UtilError::ServerCapabilitiesNotSupported)`) si
-    /// las capacidades del servidor no son compatibles con las del cliente.
+    /// falta alguna de las capacidades de `CAPABILITIES_REQUIRED` que pide el cliente.
     ///
     fn filter_capabilities(
         capabilities: &mut Vec<String>,
         my_capabilities: &[String],
     ) -> Result<(), UtilError> {
         retain_common_values(capabilities, my_capabilities);
-        if capabilities.len() == my_capabilities.len() {
-            Ok(())
-        } else {
+        let missing_required = my_capabilities.iter().any(|capability| {
+            CAPABILITIES_REQUIRED.contains(&capability.as_str())
+                && !capabilities.contains(capability)
+        });
+        if missing_required {
             Err(UtilError::ServerCapabilitiesNotSupported)
+        } else {
+            Ok(())
         }
     }
+
+    /// Indica si la capacidad fue negociada con la otra parte.
+    ///
+    /// # Argumentos
+    ///
+    /// * `capability`: Nombre de la capacidad, por ejemplo `ofs-delta`.
+    ///
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
     /// Verifica si el servidor Git soporta la capacidad de "multi_ack".
     ///
     pub fn is_multiack(&self) -> bool {
-        self.capabilities.contains(&"multi_ack".to_string())
+        self.supports(MULTI_ACK)
     }
 
     /// Filtra las referencias del servidor para actualización basado en una lista de rutas de referencias.
@@ -481,6 +615,79 @@ fn retain_unconfirmed_references(references: &mut Vec<Reference>, confirmed_hash
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::consts::{CAPABILITIES_FETCH, NO_PROGRESS, OFS_DELTA};
+    use crate::git_transport::negotiation::send_firts_request;
+
+    fn fetch_capabilities() -> Vec<String> {
+        CAPABILITIES_FETCH.iter().map(|&s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn new_propagates_remote_error() {
+        let content = vec![b"ERR repositorio 'otro' no encontrado\n".to_vec()];
+        assert_eq!(
+            GitServer::new(&content, "repo", &fetch_capabilities()).map(|_| ()),
+            Err(UtilError::RemoteError(
+                "repositorio 'otro' no encontrado".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn new_skips_unexpected_advertisement_lines() {
+        let content = vec![
+            b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 HEAD\0multi_ack ofs-delta\n".to_vec(),
+            b"\n".to_vec(),
+            b"linea inesperada\n".to_vec(),
+            b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 refs/pull/1/head\n".to_vec(),
+            b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 refs/heads/master\n".to_vec(),
+        ];
+        let server = GitServer::new(&content, "repo", &fetch_capabilities())
+            .expect("Falló al crear el servidor");
+        let paths: Vec<&String> = server
+            .get_references()
+            .iter()
+            .map(|reference| reference.get_ref_path())
+            .collect();
+        assert_eq!(paths, vec!["HEAD", "refs/heads/master"]);
+    }
+
+    #[test]
+    fn negotiation_sends_only_common_capabilities() {
+        let content = vec![
+            b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 HEAD\0multi_ack thin-pack side-band side-band-64k ofs-delta shallow no-progress include-tag\n".to_vec(),
+            b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 refs/heads/master\n".to_vec(),
+        ];
+        let server = GitServer::new(&content, "repo", &fetch_capabilities())
+            .expect("Falló al crear el servidor");
+
+        assert_eq!(server.get_capabilities(), &fetch_capabilities());
+        assert!(server.supports(OFS_DELTA));
+        assert!(server.supports(NO_PROGRESS));
+        assert!(!server.supports("side-band-64k"));
+
+        let mut request: Vec<u8> = Vec::new();
+        send_firts_request(&mut request, &server.available_references[0], &server)
+            .expect("Falló al enviar la solicitud");
+        assert_eq!(
+            String::from_utf8_lossy(&request),
+            "0052want 7217a7c7e582c46cec22a130adf4b9d7d950fba0 multi_ack ofs-delta no-progress\n"
+        );
+    }
+
+    #[test]
+    fn negotiation_drops_optional_capabilities_not_advertised() {
+        let content =
+            vec![b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 HEAD\0multi_ack side-band\n".to_vec()];
+        let server = GitServer::new(&content, "repo", &fetch_capabilities())
+            .expect("Falló al crear el servidor");
+        assert_eq!(server.get_capabilities(), &vec![MULTI_ACK.to_string()]);
+        assert!(!server.supports(OFS_DELTA));
+
+        // multi_ack es imprescindible para el fetch
+        let content = vec![b"7217a7c7e582c46cec22a130adf4b9d7d950fba0 HEAD\0side-band\n".to_vec()];
+        assert!(GitServer::new(&content, "repo", &fetch_capabilities()).is_err());
+    }
 
     #[test]
     fn filter_by_hash_should_retain_common_references() {
@@ -567,4 +774,109 @@ mod tests {
             vec![Reference::new("hash3", "refs/heads/main").unwrap()]
         );
     }
+    #[test]
+    fn advertisement_includes_head_symref() {
+        let directory = "./test_git_server_symref";
+        crate::commands::init::git_init_with_branch(directory, "main")
+            .expect("Falló al crear el repositorio");
+        let hash = "1111111111111111111111111111111111111111";
+        std::fs::write(format!("{}/{}/refs/heads/main", directory, GIT_DIR), hash)
+            .expect("Falló al escribir la branch");
+
+        let server = GitServer::create_from_path(directory, VERSION_DEFAULT, &fetch_capabilities())
+            .expect("Falló al crear el servidor");
+        let mut advertisement: Vec<u8> = Vec::new();
+        server
+            .send_advertisement(&mut advertisement)
+            .expect("Falló al enviar el anuncio");
+        std::fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let lines = pkt_line::read(&mut advertisement.as_slice()).expect("Anuncio inválido");
+        let first_line = String::from_utf8_lossy(&lines[0]);
+        assert!(first_line.starts_with(&format!("{} HEAD\0", hash)));
+        assert!(first_line.contains(" symref=HEAD:refs/heads/main"));
+
+        let client = GitServer::new(&lines, "repo", &fetch_capabilities())
+            .expect("Falló al leer el anuncio");
+        assert_eq!(client.get_head_symref(), Some("refs/heads/main"));
+        assert_eq!(client.get_head_branch(), Some("main"));
+        // La capacidad no se solicita al negociar
+        assert_eq!(client.get_capabilities(), &fetch_capabilities());
+    }
+
+    #[test]
+    fn create_from_path_advertises_packed_refs() {
+        let directory = "./test_git_server_packed_refs";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let loose = "1111111111111111111111111111111111111111";
+        let packed = "2222222222222222222222222222222222222222";
+        let tag = "3333333333333333333333333333333333333333";
+        std::fs::write(format!("{}/refs/heads/master", git_dir), loose)
+            .expect("Falló al escribir la branch");
+        let packed_refs = format!(
+            "# pack-refs with: peeled fully-peeled sorted \n{0} refs/heads/feature\n{0} refs/heads/master\n{1} refs/tags/v1\n^{0}\n",
+            packed, tag
+        );
+        std::fs::write(format!("{}/packed-refs", git_dir), packed_refs)
+            .expect("Falló al escribir packed-refs");
+
+        let server = GitServer::create_from_path(directory, VERSION_DEFAULT, &[])
+            .expect("Falló al crear el servidor");
+        let mut advertisement: Vec<u8> = Vec::new();
+        server
+            .send_advertisement(&mut advertisement)
+            .expect("Falló al enviar el anuncio");
+        std::fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let advertisement = String::from_utf8_lossy(&advertisement);
+        assert!(advertisement.contains(&format!("{} HEAD", loose)));
+        // La branch suelta tiene prioridad sobre la empaquetada
+        assert!(advertisement.contains(&format!("{} refs/heads/master\n", loose)));
+        assert!(!advertisement.contains(&format!("{} refs/heads/master\n", packed)));
+        assert!(advertisement.contains(&format!("{} refs/heads/feature\n", packed)));
+        assert!(advertisement.contains(&format!("{} refs/tags/v1\n", tag)));
+        // El objeto pelado se toma de packed-refs, sin leer el objeto tag
+        assert!(advertisement.contains(&format!("{} refs/tags/v1^{{}}\n", packed)));
+    }
+
+    #[test]
+    fn create_from_path_sorts_references() {
+        let directory = "./test_git_server_sorted_refs";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let hash = "1111111111111111111111111111111111111111";
+        for branch in ["zeta", "master", "gamma", "alpha"] {
+            std::fs::write(format!("{}/refs/heads/{}", git_dir, branch), hash)
+                .expect("Falló al escribir la branch");
+        }
+        std::fs::write(format!("{}/refs/tags/v1", git_dir), hash).expect("Falló el tag");
+        std::fs::write(
+            format!("{}/packed-refs", git_dir),
+            format!("{} refs/heads/beta\n", hash),
+        )
+        .expect("Falló al escribir packed-refs");
+
+        let server = GitServer::create_from_path(directory, VERSION_DEFAULT, &[])
+            .expect("Falló al crear el servidor");
+        std::fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let paths: Vec<&str> = server
+            .get_references()
+            .iter()
+            .map(|reference| reference.get_ref_path().as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "HEAD",
+                "refs/heads/alpha",
+                "refs/heads/beta",
+                "refs/heads/gamma",
+                "refs/heads/master",
+                "refs/heads/zeta",
+                "refs/tags/v1",
+            ]
+        );
+    }
 }
@@ -1,5 +1,9 @@
 pub mod git_request;
 
+pub mod access_policy;
+
+pub mod hooks;
+
 pub mod advertised;
 
 pub mod negotiation;
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path};
+
+use crate::consts::{ACCESS_POLICY_FILE, PR_FOLDER};
+use crate::util::errors::UtilError;
+
+/// Clave de la regla que se aplica a todos los repositorios que no tienen una propia.
+const ANY_REPOSITORY: &str = "*";
+
+/// Tipo de acceso que requiere una operación sobre un repositorio.
+///
+/// - `Read`: leer el repositorio, como en `git-upload-pack` y `git-upload-archive`.
+/// - `Write`: actualizar sus referencias, como en `git-receive-pack`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// Política de acceso a los repositorios del servidor.
+///
+/// Se lee del archivo `<src>/.pr/access`, que contiene una línea
+/// `repositorio[:usuario] = operaciones` por regla, donde las operaciones son `read`, `write`
+/// o `none`, separadas por espacios o comas. Para una solicitud se usa la regla del usuario en
+/// el repositorio, luego la del repositorio y por último la de `*`. Si ninguna regla aplica
+/// se permiten todas las operaciones. Las líneas vacías y las que comienzan con `#` se ignoran.
+///
+/// Los nombres de los repositorios se comparan relativos al directorio raíz del servidor, por
+/// lo que `/docs`, `docs/` y `./docs` corresponden a la misma regla.
+///
+/// ```text
+/// # Solo lectura para todos, salvo alice
+/// docs = read
+/// docs:alice = read write
+/// privado = none
+/// ```
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct AccessPolicy {
+    rules: HashMap<String, Vec<Access>>,
+}
+
+impl AccessPolicy {
+    /// Carga la política de acceso del directorio fuente del servidor.
+    ///
+    /// Si el archivo no existe la política queda vacía y se permite cualquier operación.
+    ///
+    /// # Argumentos
+    ///
+    /// * `src` - Directorio fuente del servidor.
+    ///
+    pub fn load(src: &str) -> Self {
+        let path = format!("{}/{}/{}", src, PR_FOLDER, ACCESS_POLICY_FILE);
+        let content = fs::read_to_string(path).unwrap_or_default();
+        AccessPolicy::parse(&content)
+    }
+
+    /// Interpreta el contenido del archivo de la política de acceso.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content` - Contenido del archivo de la política de acceso.
+    ///
+    pub fn parse(content: &str) -> Self {
+        let mut rules = HashMap::new();
+        for line in content.lines().map(|line| line.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, operations)) = line.split_once('=') {
+                let operations = operations
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(|operation| match operation {
+                        "read" => Some(Access::Read),
+                        "write" => Some(Access::Write),
+                        _ => None,
+                    })
+                    .collect();
+                let key = match key.trim().split_once(':') {
+                    Some((repo, principal)) => {
+                        format!("{}:{}", canonical_repo_name(repo), principal.trim())
+                    }
+                    None if key.trim() == ANY_REPOSITORY => ANY_REPOSITORY.to_string(),
+                    None => canonical_repo_name(key),
+                };
+                rules.insert(key, operations);
+            }
+        }
+        AccessPolicy { rules }
+    }
+
+    /// Indica si la política permite un acceso a un repositorio.
+    ///
+    /// # Argumentos
+    ///
+    /// * `repo` - Ruta del repositorio relativa al directorio raíz del servidor.
+    /// * `principal` - Usuario autenticado, o `None` si la conexión es anónima.
+    /// * `access` - Acceso requerido.
+    ///
+    /// # Retorno
+    ///
+    /// Retorna `true` si la regla más específica que aplica permite el acceso, o si no aplica
+    /// ninguna.
+    ///
+    pub fn allows(&self, repo: &str, principal: Option<&str>, access: Access) -> bool {
+        let repo = canonical_repo_name(repo);
+        let keys = [
+            principal.map(|principal| format!("{}:{}", repo, principal)),
+            Some(repo),
+            Some(ANY_REPOSITORY.to_string()),
+        ];
+        match keys.iter().flatten().find_map(|key| self.rules.get(key)) {
+            Some(operations) => operations.contains(&access),
+            None => true,
+        }
+    }
+
+    /// Verifica que la política permita un acceso a un repositorio.
+    ///
+    /// # Argumentos
+    ///
+    /// * `repo` - Ruta del repositorio relativa al directorio raíz del servidor.
+    /// * `principal` - Usuario autenticado, o `None` si la conexión es anónima.
+    /// * `access` - Acceso requerido.
+    ///
+    /// # Retorno
+    ///
+    /// Devuelve `Ok(())` si se permite el acceso, o `UtilError::AccessDenied` si no.
+    ///
+    pub fn check(
+        &self,
+        repo: &str,
+        principal: Option<&str>,
+        access: Access,
+    ) -> Result<(), UtilError> {
+        match self.allows(repo, principal, access) {
+            true => Ok(()),
+            false => Err(UtilError::AccessDenied(canonical_repo_name(repo))),
+        }
+    }
+}
+
+/// Normaliza la ruta de un repositorio relativa al directorio raíz: se ignoran las barras
+/// sobrantes y los componentes `.`, y cada `..` descarta el componente anterior. Un `..` que
+/// saldría del directorio raíz se conserva, por lo que no coincide con ningún repositorio.
+fn canonical_repo_name(repo: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in Path::new(repo.trim()).components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str().unwrap_or_default()),
+            Component::ParentDir => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                _ => components.push(".."),
+            },
+            _ => {}
+        }
+    }
+    components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_policy_allows() {
+        let policy = AccessPolicy::parse(
+            "# reglas\ndocs = read\ndocs:alice = read, write\nprivado = none\n* = read write\n",
+        );
+
+        assert!(policy.allows("/docs", None, Access::Read));
+        assert!(!policy.allows("/docs", None, Access::Write));
+        assert!(!policy.allows("docs", Some("bob"), Access::Write));
+        assert!(policy.allows("docs", Some("alice"), Access::Write));
+        assert!(!policy.allows("privado", Some("alice"), Access::Read));
+        assert!(policy.allows("otro", None, Access::Write));
+        assert!(AccessPolicy::default().allows("docs", None, Access::Write));
+    }
+
+    #[test]
+    fn test_access_policy_canonical_repo_name() {
+        let policy = AccessPolicy::parse(
+            "docs/ = read
+./equipo//docs:alice = read write
+",
+        );
+
+        assert!(!policy.allows("/./docs", None, Access::Write));
+        assert!(!policy.allows("docs/.", None, Access::Write));
+        assert!(!policy.allows("otro/../docs", None, Access::Write));
+        assert!(policy.allows("/equipo/./docs/", Some("alice"), Access::Write));
+        assert!(policy.allows("../docs", None, Access::Write));
+        assert_eq!(
+            policy.check("//docs/.", None, Access::Write),
+            Err(UtilError::AccessDenied("docs".to_string()))
+        );
+    }
+}
@@ -174,6 +174,8 @@
 
 use std::fmt;
 
+use crate::consts::{CAPABILITIES_EMPTY_REPO, ERR_PREFIX, PEELED_SUFFIX};
+use crate::util::object_format::ObjectFormat;
 use crate::util::{errors::UtilError, validation::is_valid_obj_id};
 
 /// `AdvertisedRefLine` es una enumeración que representa anuncios de referencias en el contexto de Git.
@@ -231,14 +233,21 @@ impl AdvertisedRefLine {
     ///
     /// - `Ok(Vec<AdvertisedRefLine>)`: Si se procesan con éxito todas las líneas del vector de bytes y se generan
     ///   los anuncios apropiados, se devuelve un vector de anuncios de referencias.
-    /// - `Err(UtilError)`: Si se produce un error al procesar las líneas o al clasificar las referencias, se devuelve
-    ///   un error `UtilError` que indica el problema.
+    /// - `Err(UtilError::RemoteError)`: Si el servidor respondió con una línea `ERR <mensaje>`, por
+    ///   ejemplo porque el repositorio no existe, con el mensaje del servidor.
+    ///
+    /// Las líneas vacías o que no se pueden clasificar se ignoran. Los hashes se validan con el
+    /// formato de objetos anunciado con la capacidad `object-format`, o SHA-1 si no se anuncia.
     ///
     pub fn classify_vec(content: &Vec<Vec<u8>>) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+        let object_format = advertised_object_format(content);
         let mut result: Vec<AdvertisedRefLine> = Vec::new();
         for c in content {
             if let Ok(line_str) = std::str::from_utf8(c) {
-                if let Ok(refs) = AdvertisedRefLine::classify_server_refs(line_str) {
+                if let Some(message) = line_str.strip_prefix(ERR_PREFIX) {
+                    return Err(UtilError::RemoteError(message.trim().to_string()));
+                }
+                if let Ok(refs) = AdvertisedRefLine::classify_server_refs(line_str, object_format) {
                     result.extend(refs);
                 }
             }
@@ -277,6 +286,7 @@ impl AdvertisedRefLine {
     /// # Argumentos
     ///
     /// - `obj_id`: Una cadena que representa el ID del objeto.
+    /// - `object_format`: Formato de los objetos del servidor.
     ///
     /// # Retorno
     ///
@@ -285,8 +295,11 @@ impl AdvertisedRefLine {
     /// - `Err(UtilError::InvalidObjectIdError)`: Si el ID del objeto no es válido, se genera un error `UtilError`
     ///   indicando que el ID del objeto es inválido.
     ///
-    fn create_shallow(obj_id: &str) -> Result<Vec<AdvertisedRefLine>, UtilError> {
-        if !is_valid_obj_id(obj_id) {
+    fn create_shallow(
+        obj_id: &str,
+        object_format: ObjectFormat,
+    ) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+        if !is_valid_obj_id(obj_id, object_format) {
             return Err(UtilError::InvalidObjectId);
         }
         Ok(vec![AdvertisedRefLine::Shallow {
@@ -303,6 +316,7 @@ impl AdvertisedRefLine {
     /// # Argumentos
     ///
     /// - `input`: Una cadena que representa el anuncio de referencias Git.
+    /// - `object_format`: Formato de los objetos del servidor.
     ///
     /// # Retorno
     ///
@@ -315,9 +329,12 @@ impl AdvertisedRefLine {
     /// - `Err(UtilError::InvalidObjectIdError)`: Si el anuncio de referencias Git es inválido o contiene una cantidad incorrecta
     ///   de partes, se genera un error `UtilError` indicando que el ID del objeto es inválido.
     ///
-    fn create_ref(input: &str) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+    fn create_ref(
+        input: &str,
+        object_format: ObjectFormat,
+    ) -> Result<Vec<AdvertisedRefLine>, UtilError> {
         if !contains_capacity_list(input) {
-            return _create_ref(input);
+            return _create_ref(input, object_format);
         }
 
         let parts: Vec<&str> = input.split('\0').collect();
@@ -325,11 +342,36 @@ impl AdvertisedRefLine {
             return Err(UtilError::InvalidObjectId);
         }
 
-        let mut vec: Vec<AdvertisedRefLine> = _create_ref(parts[0])?;
+        let mut vec: Vec<AdvertisedRefLine> = _create_ref(parts[0], object_format)?;
         vec.insert(0, extract_capabilities(parts[1])?);
         Ok(vec)
     }
 
+    /// Crea el anuncio de capacidades de un repositorio vacío.
+    ///
+    /// Un servidor sin referencias envía `0{40} capabilities^{}` seguido de sus capacidades,
+    /// línea que no corresponde a ninguna referencia real.
+    ///
+    /// # Argumentos
+    ///
+    /// - `input`: Una cadena que representa el anuncio del servidor Git.
+    ///
+    /// # Retorna
+    ///
+    /// - `Ok(vec![AdvertisedRefLine::Capabilities(..)])`: Si el anuncio contiene capacidades.
+    /// - `Ok(vec![])`: Si el anuncio no contiene capacidades.
+    ///
+    fn create_capabilities_empty_repository(
+        input: &str,
+    ) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+        match input.split_once('\0') {
+            Some((_, capabilities)) if !capabilities.trim().is_empty() => {
+                Ok(vec![extract_capabilities(capabilities)?])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
     /// Clasifica y crea anuncios de referencias del servidor Git en función de la entrada proporcionada.
     ///
     /// Esta función toma una cadena de entrada que representa un anuncio de referencias del servidor Git y genera
@@ -339,6 +381,7 @@ impl AdvertisedRefLine {
     /// # Argumentos
     ///
     /// - `input`: Una cadena que representa el anuncio de referencias del servidor Git.
+    /// - `object_format`: Formato de los objetos del servidor.
     ///
     /// # Retorna
     ///
@@ -347,10 +390,13 @@ impl AdvertisedRefLine {
     /// - `Err(UtilError::InvalidServerReferenceError)`: Si la entrada no se puede clasificar o es inválida, se genera un
     ///   error `UtilError` indicando que la referencia del servidor es inválida.
     ///
-    fn classify_server_refs(input: &str) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+    fn classify_server_refs(
+        input: &str,
+        object_format: ObjectFormat,
+    ) -> Result<Vec<AdvertisedRefLine>, UtilError> {
         let parts: Vec<&str> = input.split_whitespace().collect();
 
-        if parts.len() == 1 {
+        if parts.len() < 2 {
             return Err(UtilError::InvalidServerReference);
         }
 
@@ -360,12 +406,23 @@ impl AdvertisedRefLine {
         }
         // Verificar si el primer elemento es "shallow"
         if parts[0] == "shallow" {
-            return AdvertisedRefLine::create_shallow(parts[1]);
+            return AdvertisedRefLine::create_shallow(parts[1], object_format);
+        }
+
+        // Un repositorio vacío solo anuncia sus capacidades
+        if parts[1].starts_with(CAPABILITIES_EMPTY_REPO) {
+            return AdvertisedRefLine::create_capabilities_empty_repository(input);
+        }
+
+        // Los tags anotados se anuncian también pelados (`refs/tags/v1^{}`); esa línea no es
+        // una referencia real, por lo que no se registra
+        if parts[1].ends_with(PEELED_SUFFIX) {
+            return Ok(Vec::new());
         }
 
         // Verificar si el segundo elemento parece ser una referencia
         if parts[1].starts_with("refs/") || parts[1].starts_with("HEAD") {
-            return AdvertisedRefLine::create_ref(input);
+            return AdvertisedRefLine::create_ref(input, object_format);
         }
         Err(UtilError::InvalidServerReference)
     }
@@ -384,12 +441,29 @@ fn contains_capacity_list(input: &str) -> bool {
     input.contains('\0')
 }
 
-fn _create_ref(input: &str) -> Result<Vec<AdvertisedRefLine>, UtilError> {
+/// Devuelve el formato de los objetos anunciado en las capacidades de la primera referencia.
+/// Si el servidor no anuncia `object-format`, los objetos son SHA-1.
+fn advertised_object_format(content: &[Vec<u8>]) -> ObjectFormat {
+    content
+        .iter()
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .find_map(|line| line.split_once('\0'))
+        .map(|(_, capabilities)| {
+            let capabilities: Vec<&str> = capabilities.split_whitespace().collect();
+            ObjectFormat::from_capabilities(&capabilities)
+        })
+        .unwrap_or_default()
+}
+
+fn _create_ref(
+    input: &str,
+    object_format: ObjectFormat,
+) -> Result<Vec<AdvertisedRefLine>, UtilError> {
     let parts: Vec<&str> = input.split_whitespace().collect();
     if parts.len() != 2 {
         return Err(UtilError::InvalidServerReference);
     }
-    if !is_valid_obj_id(parts[0]) {
+    if !is_valid_obj_id(parts[0], object_format) {
         return Err(UtilError::InvalidObjectId);
     }
     Ok(vec![AdvertisedRefLine::Ref {
@@ -428,8 +502,11 @@ mod tests {
 
     #[test]
     fn test_create_shallow_valid() {
-        let result =
-            AdvertisedRefLine::create_shallow("1d3fcd5ced445d1abc402225c0b8a1299641f497").unwrap();
+        let result = AdvertisedRefLine::create_shallow(
+            "1d3fcd5ced445d1abc402225c0b8a1299641f497",
+            ObjectFormat::Sha1,
+        )
+        .unwrap();
         assert_eq!(
             result,
             vec![AdvertisedRefLine::Shallow {
@@ -440,14 +517,14 @@ mod tests {
 
     #[test]
     fn test_create_shallow_invalid() {
-        let invalid_result = AdvertisedRefLine::create_shallow("invalid_id");
+        let invalid_result = AdvertisedRefLine::create_shallow("invalid_id", ObjectFormat::Sha1);
         assert!(invalid_result.is_err());
     }
 
     #[test]
     fn test_create_ref_without_capabilities() {
         let input = "1d3fcd5ced445d1abc402225c0b8a1299641f497 master";
-        let result = AdvertisedRefLine::create_ref(input).unwrap();
+        let result = AdvertisedRefLine::create_ref(input, ObjectFormat::Sha1).unwrap();
 
         assert_eq!(
             result,
@@ -461,14 +538,14 @@ mod tests {
     #[test]
     fn test_create_ref_without_capabilities_empty() {
         let input = "1d3fcd5ced445d1abc402225c0b8a1299641f497 master\0";
-        let invalid_result = AdvertisedRefLine::create_ref(input);
+        let invalid_result = AdvertisedRefLine::create_ref(input, ObjectFormat::Sha1);
         assert!(invalid_result.is_err());
     }
 
     #[test]
     fn test_create_ref_with_capabilities() {
         let input = "1d3fcd5ced445d1abc402225c0b8a1299641f497 master\0cap1 cap2";
-        let result = AdvertisedRefLine::create_ref(input).unwrap();
+        let result = AdvertisedRefLine::create_ref(input, ObjectFormat::Sha1).unwrap();
 
         assert_eq!(
             result,
@@ -485,14 +562,14 @@ mod tests {
     #[test]
     fn test_create_ref_invalid() {
         let input = "invalid_data";
-        let invalid_result = AdvertisedRefLine::create_ref(input);
+        let invalid_result = AdvertisedRefLine::create_ref(input, ObjectFormat::Sha1);
         assert!(invalid_result.is_err());
     }
 
     #[test]
     fn test_classify_server_refs_version() {
         let input = "version 2";
-        let result = AdvertisedRefLine::classify_server_refs(input).unwrap();
+        let result = AdvertisedRefLine::classify_server_refs(input, ObjectFormat::Sha1).unwrap();
         let expected = AdvertisedRefLine::create_version("2").unwrap();
         assert_eq!(result, expected);
     }
@@ -500,9 +577,12 @@ mod tests {
     #[test]
     fn test_classify_server_refs_shallow() {
         let input = "shallow 7217a7c7e582c46cec22a130adf4b9d7d950fba0";
-        let result = AdvertisedRefLine::classify_server_refs(input).unwrap();
-        let expected =
-            AdvertisedRefLine::create_shallow("7217a7c7e582c46cec22a130adf4b9d7d950fba0").unwrap();
+        let result = AdvertisedRefLine::classify_server_refs(input, ObjectFormat::Sha1).unwrap();
+        let expected = AdvertisedRefLine::create_shallow(
+            "7217a7c7e582c46cec22a130adf4b9d7d950fba0",
+            ObjectFormat::Sha1,
+        )
+        .unwrap();
         assert_eq!(result, expected);
     }
 
@@ -510,15 +590,52 @@ mod tests {
     fn test_classify_server_refs_ref() {
         // Clasificar y crear un anuncio de referencia del servidor Git.
         let input = "7217a7c7e582c46cec22a130adf4b9d7d950fba0 refs/heads/master";
-        let result = AdvertisedRefLine::classify_server_refs(input).unwrap();
-        let expected = AdvertisedRefLine::create_ref(input).unwrap();
+        let result = AdvertisedRefLine::classify_server_refs(input, ObjectFormat::Sha1).unwrap();
+        let expected = AdvertisedRefLine::create_ref(input, ObjectFormat::Sha1).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_classify_server_refs_invalid() {
         let input = "invalid_data";
-        let invalid_result = AdvertisedRefLine::classify_server_refs(input);
+        let invalid_result = AdvertisedRefLine::classify_server_refs(input, ObjectFormat::Sha1);
         assert!(invalid_result.is_err());
     }
+
+    #[test]
+    fn test_classify_empty_repository_capabilities() {
+        let input = "0000000000000000000000000000000000000000 capabilities^{}\0multi_ack\n";
+        let result = AdvertisedRefLine::classify_server_refs(input, ObjectFormat::Sha1).unwrap();
+        assert_eq!(
+            result,
+            vec![AdvertisedRefLine::Capabilities(vec![
+                "multi_ack".to_string()
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_classify_vec_object_format() {
+        let hash = "a".repeat(64);
+        let content = vec![
+            format!("{} HEAD\0object-format=sha256\n", hash).into_bytes(),
+            format!("{} refs/heads/master\n", hash).into_bytes(),
+        ];
+        let result = AdvertisedRefLine::classify_vec(&content).unwrap();
+        let refs = result
+            .iter()
+            .filter(|line| matches!(line, AdvertisedRefLine::Ref { obj_id, .. } if *obj_id == hash))
+            .count();
+        assert_eq!(refs, 2);
+
+        // Sin la capacidad el servidor usa SHA-1, y un hash de 64 caracteres no es válido
+        let content = vec![
+            format!("{} HEAD\0multi_ack\n", hash).into_bytes(),
+            format!("{} refs/heads/master\n", hash).into_bytes(),
+        ];
+        let result = AdvertisedRefLine::classify_vec(&content).unwrap();
+        assert!(!result
+            .iter()
+            .any(|line| matches!(line, AdvertisedRefLine::Ref { .. })));
+    }
 }
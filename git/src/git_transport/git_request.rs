@@ -1,36 +1,51 @@
 use std::fmt;
 use std::fs;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::path::Path;
 
-use crate::commands::branch::get_parent_hashes;
-use crate::commands::cat_file::git_cat_file;
+use crate::commands::archive::build_archive;
+use crate::commands::branch::get_current_branch;
 use crate::commands::fetch::save_objects;
-use crate::commands::log::save_log;
 use crate::commands::merge::git_merge;
+use crate::commands::reflog::{append_branch_reflog, append_reflog, delete_reflog};
 use crate::consts::{
-    CAPABILITIES_FETCH, CAPABILITIES_PUSH, END_OF_STRING, GIT_DIR, PARENT_INITIAL, PKT_NAK,
-    VERSION_DEFAULT,
+    CAPABILITIES_FETCH, CAPABILITIES_PUSH, DELETE_REFS, END_OF_STRING, ERR_PREFIX, FILTER, GIT_DIR,
+    HEAD, LENGTH_PREFIX_SIZE, NO_PROGRESS, OFS_DELTA, PKT_ACK, PKT_NAK, POST_RECEIVE_HOOK,
+    PRE_RECEIVE_HOOK, REFLOG_DEFAULT_IDENTITY, REFS_HEADS, SIDE_BAND_64K, SIDE_BAND_DATA,
+    SIDE_BAND_MAX_DATA, VERSION_DEFAULT,
 };
 use crate::git_server::GitServer;
 use crate::git_transport::negotiation::{receive_reference_update_request, receive_request};
 use crate::models::client::Client;
-use crate::util::connections::{receive_packfile, send_message};
+use crate::servers::server_config::resolve_repo_path;
+use crate::util::connections::{
+    receive_packfile_with_limits, send_bytes, send_flush, send_message,
+};
 use crate::util::errors::UtilError;
-use crate::util::files::{
-    create_directory, create_file, create_file_replace, open_file, read_file_string,
+use crate::util::files::{create_file, create_file_replace, file_exists};
+use crate::util::logger::log_debug;
+use crate::util::object_format::get_object_format;
+use crate::util::objects::{object_exists, ObjectEntry, ObjectType};
+use crate::util::packfile::{send_packfile, send_packfile_side_band, PackfileLimits};
+use crate::util::pkt_line::{add_length_prefix, read, read_line_from_bytes, read_pkt_line};
+use crate::util::validation::{
+    is_null_obj_id, is_valid_obj_id_any_format, is_valid_ref_name, join_paths_correctly,
 };
-use crate::util::objects::{ObjectEntry, ObjectType};
-use crate::util::packfile::send_packfile;
-use crate::util::pkt_line::{add_length_prefix, read_line_from_bytes, read_pkt_line};
-use crate::util::validation::join_paths_correctly;
 
+use super::access_policy::{Access, AccessPolicy};
+use super::hooks::{format_hook_input, format_hook_message, run_hook};
 use super::negotiation::{
     receive_done, send_acknowledge_last_reference, sent_references_valid_client,
 };
-use super::references::{get_objects, get_objects_fetch_with_hash_valid};
-use super::references_update::ReferencesUpdate;
+use super::references::{
+    get_objects, get_objects_fetch_with_hash_valid, read_pack_objects, read_packed_refs,
+    remove_packed_ref,
+};
+use super::references_update::{
+    send_decompressed_package_status, send_decompression_failure_status, ReferenceStatus,
+    ReferencesUpdate,
+};
 use super::request_command::RequestCommand;
 
 /// # `GitRequest`
@@ -183,33 +198,106 @@ impl GitRequest {
         add_length_prefix(&message, len)
     }
 
+    /// Ejecuta la solicitud, verificando antes de transferir datos que la política de acceso
+    /// del servidor permita leer o escribir el repositorio. El protocolo git:// no autentica a
+    /// los clientes, por lo que se aplican las reglas de las conexiones anónimas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `stream` - Conexión con el cliente.
+    /// * `root` - Directorio raíz de los repositorios del servidor.
+    ///
+    /// # Retorno
+    ///
+    /// Devuelve el resultado de la solicitud, o `UtilError::AccessDenied` si la política no
+    /// permite la operación. En ese caso el cliente recibe una línea `ERR <mensaje>` en lugar
+    /// de las referencias.
+    ///
     pub fn execute(&self, stream: &mut TcpStream, root: &str) -> Result<String, UtilError> {
+        let path_repo = get_path_repository(root, &self.pathname)?;
+        check_access(stream, root, &self.pathname, self.request_command.access())?;
         match self.request_command {
-            RequestCommand::UploadPack => {
-                let path_repo = get_path_repository(root, &self.pathname)?;
-                handle_upload_pack(stream, &path_repo)
-            }
-            RequestCommand::ReceivePack => {
-                let path_repo = get_path_repository(root, &self.pathname)?;
-                handle_receive_pack(stream, &path_repo)
-            }
-            RequestCommand::UploadArchive => {
-                println!("Funcion aun no implementada");
-                println!("UploadArchive");
-                Ok("".to_string())
-            }
+            RequestCommand::UploadPack => handle_upload_pack(stream, &path_repo),
+            RequestCommand::ReceivePack => handle_receive_pack(stream, &path_repo),
+            RequestCommand::UploadArchive => handle_upload_archive(stream, &path_repo),
         }
     }
 }
 
+/// Verifica que la política de acceso del servidor permita la operación sobre el repositorio.
+/// Si no la permite, se le informa al cliente con una línea `ERR <mensaje>`.
+///
+/// # Argumentos
+///
+/// * `writer` - Conexión con el cliente.
+/// * `root` - Directorio raíz de los repositorios del servidor.
+/// * `pathname` - Ruta del repositorio solicitado.
+/// * `access` - Acceso que requiere la operación.
+///
+/// # Retorno
+///
+/// Devuelve `Ok(())` si se permite la operación, o `UtilError::AccessDenied` si no.
+///
+fn check_access(
+    writer: &mut dyn Write,
+    root: &str,
+    pathname: &str,
+    access: Access,
+) -> Result<(), UtilError> {
+    let error = match AccessPolicy::load(root).check(pathname, None, access) {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+    let message = format!("{}{}\n", ERR_PREFIX, error);
+    send_message(
+        writer,
+        &add_length_prefix(&message, message.len()),
+        error.clone(),
+    )?;
+    Err(error)
+}
+
 fn handle_upload_pack(stream: &mut TcpStream, path_repo: &str) -> Result<String, UtilError> {
-    println!("UploadPack");
-    let capabilities: Vec<String> = CAPABILITIES_FETCH.iter().map(|&s| s.to_string()).collect();
-    let mut server = GitServer::create_from_path(path_repo, VERSION_DEFAULT, &capabilities)?;
-    // println!("Server: {:?}", server);
+    let mut server = create_upload_pack_server(path_repo)?;
     server.send_references(stream)?;
-    // println!("Envie las referencias");
-    let pack_negotation = receive_request(stream)?;
+    upload_pack_negotiation(stream, &mut server, path_repo)
+}
+
+/// Crea el `GitServer` que atiende un `git-upload-pack` sobre el repositorio indicado.
+///
+/// # Argumentos
+///
+/// * `path_repo` - Ruta del repositorio en el servidor.
+///
+/// # Retorno
+///
+/// Retorna el `GitServer` con las referencias del repositorio y las capacidades de fetch.
+///
+pub fn create_upload_pack_server(path_repo: &str) -> Result<GitServer, UtilError> {
+    let mut capabilities: Vec<String> = CAPABILITIES_FETCH.iter().map(|&s| s.to_string()).collect();
+    capabilities.push(FILTER.to_string());
+    capabilities.push(SIDE_BAND_64K.to_string());
+    GitServer::create_from_path(path_repo, VERSION_DEFAULT, &capabilities)
+}
+
+/// Realiza la negociación del `git-upload-pack` una vez anunciadas las referencias:
+/// recibe los want/have del cliente y le envía el packfile correspondiente.
+///
+/// Se utiliza tanto en el transporte git:// como en el protocolo HTTP, donde el
+/// stream representa el cuerpo de la solicitud y el de la respuesta.
+///
+/// # Argumentos
+///
+/// * `stream` - Canal con el cliente.
+/// * `server` - Servidor creado con `create_upload_pack_server`.
+/// * `path_repo` - Ruta del repositorio en el servidor.
+///
+pub fn upload_pack_negotiation<S: Read + Write>(
+    stream: &mut S,
+    server: &mut GitServer,
+    path_repo: &str,
+) -> Result<String, UtilError> {
+    let pack_negotation = receive_request(stream, server.object_format)?;
     let (capabilities, wanted_objects, had_objects) = pack_negotation.get_components();
 
     if capabilities.is_empty() && wanted_objects.is_empty() && had_objects.is_empty() {
@@ -218,64 +306,89 @@ fn handle_upload_pack(stream: &mut TcpStream, path_repo: &str) -> Result<String,
 
     if !had_objects.is_empty() {
         // Si el cliente cuenta con objetos ya en su repo, esta haciendo un FETCH
-        println!("FETCH");
         server.update_data(capabilities, wanted_objects);
         let local_hashes = search_available_references(path_repo, &had_objects);
-        println!("Local hashes: {:?}", local_hashes);
+        log_debug(&format!("Local hashes: {:?}", local_hashes));
 
         // Las referencias al dia las filtro
         server.filter_available_references(&local_hashes);
-        println!("Server: {:?}", server);
         sent_references_valid_client(stream, &local_hashes)?;
         // Confirmo las referencias del usuario que el servidor tiene disponibles
         // Actualizo las referencias disponibles del servidor
         // server.update_local_references(&local_references);
 
         // Las confirmaciones terminan con recibiendo un done
-        println!("Recibiendo done");
         receive_done(stream, UtilError::ReceiveDoneConfRefs)?;
-        println!("Recibo el done");
 
         // Envio el ultimo ACK
         send_acknowledge_last_reference(stream, &local_hashes)?;
 
-        let objects = get_objects_fetch(&mut server, local_hashes)?;
-        println!("Objects: {:?}", objects);
-        send_packfile(stream, &server, objects, true)?;
+        let objects = get_objects_fetch(server, local_hashes)?;
+        let number_objects = objects.len();
+        let objects = read_pack_objects(path_repo, objects);
+        send_pack_to_client(stream, server, number_objects, objects)?;
 
         return Ok("Fetch exitoso".to_string());
     }
-    // Si el cliente solicita todo, esta haciendo un CLONE
+    // Si el cliente solicita todo, esta haciendo un CLONE. Con `filter blob:none` se omiten
+    // los blobs, que el cliente descargará más adelante
+    let omit_blobs = pack_negotation.omits_blobs();
     server.update_data(capabilities, wanted_objects);
-    let objects = match get_objects(path_repo, &server.available_references[1..]) {
+    let objects = match get_objects(path_repo, &server.available_references[1..], omit_blobs) {
         Ok(objects) => objects,
         Err(_) => return Err(UtilError::GetObjectsPackfile),
     };
     send_message(stream, PKT_NAK, UtilError::SendNAKPackfile)?;
-    send_packfile(stream, &server, objects, true)?; // Debo modificarlo, el NAK no debe estar dentro
+    let number_objects = objects.len();
+    let objects = read_pack_objects(path_repo, objects);
+    send_pack_to_client(stream, server, number_objects, objects)?;
     Ok("Clone exitoso".to_string())
 }
 
-// [TODO #4]
-// Dado las referencias(local_hash: Vector de hashes) que el cliente supuestamente tiene
-// Se deben filtrar los hash que tiene el servidor
-// Me debes devolver un Vec<String> con los hash que tenemos en comun
-// Acordate que el repo esta en path_repo
-pub fn search_available_references(path_repo: &str, local_hash: &Vec<String>) -> Vec<String> {
-    let mut confirmed_commits: Vec<String> = Vec::new();
-    let commits_in_repo = match get_commits(path_repo) {
-        Ok(commits) => commits,
-        Err(_) => return confirmed_commits,
-    };
-    for hash in local_hash {
-        // Ejemplo de como seria
-        // if reference.la_tenemos() {
-        //     available_references.push(reference);
-        // }
-        if commits_in_repo.contains(hash) {
-            confirmed_commits.push(hash.to_string());
-        }
+/// Envía el packfile al cliente. Si el cliente negoció `side-band-64k` el packfile se envía
+/// multiplexado, con el avance en la banda de progreso salvo que haya pedido `no-progress`.
+///
+/// # Argumentos
+///
+/// * `stream` - Canal con el cliente.
+/// * `server` - Servidor con las capacidades negociadas con el cliente.
+/// * `number_objects` - Cantidad de objetos del packfile.
+/// * `objects` - Tipo y contenido comprimido de cada objeto.
+///
+fn send_pack_to_client<I>(
+    stream: &mut dyn Write,
+    server: &GitServer,
+    number_objects: usize,
+    objects: I,
+) -> Result<(), UtilError>
+where
+    I: IntoIterator<Item = Result<(ObjectType, Vec<u8>), UtilError>>,
+{
+    if !server.supports(SIDE_BAND_64K) {
+        return send_packfile(stream, server, number_objects, objects, true);
     }
+    let progress = !server.supports(NO_PROGRESS);
+    send_packfile_side_band(stream, server, number_objects, objects, true, progress)
+}
+
+/// Filtra los hashes que el cliente dice tener (`have`) y se queda con los que también están
+/// en el repositorio del servidor. Solo se verifica que el objeto exista, sin descomprimirlo.
+///
+/// # Argumentos
+///
+/// * `path_repo` - Ruta del repositorio en el servidor.
+/// * `local_hash` - Hashes que el cliente tiene.
+///
+/// # Retorno
+///
+/// Los hashes en común. Si no hay ninguno, se devuelve el primer hash del cliente.
+///
+pub fn search_available_references(path_repo: &str, local_hash: &[String]) -> Vec<String> {
+    let mut confirmed_commits: Vec<String> = local_hash
+        .iter()
+        .filter(|hash| object_exists(path_repo, hash))
+        .cloned()
+        .collect();
     if confirmed_commits.is_empty() {
         if let Some(current_hash) = local_hash.first() {
             confirmed_commits.push(current_hash.to_string())
@@ -284,46 +397,6 @@ pub fn search_available_references(path_repo: &str, local_hash: &Vec<String>) ->
     confirmed_commits
 }
 
-fn get_commits(path_repo: &str) -> Result<Vec<String>, UtilError> {
-    let mut commits: Vec<String> = Vec::new();
-    let branches_path = join_paths_correctly(path_repo, ".git/refs/heads");
-    let branches = match std::fs::read_dir(branches_path) {
-        Ok(branches) => branches,
-        Err(_) => return Err(UtilError::ReadDirError),
-    };
-    for branch in branches {
-        let branch = match branch {
-            Ok(branch) => branch,
-            Err(_) => return Err(UtilError::ReadDirError),
-        };
-        let branch_name = branch.file_name();
-        if let Some(branch_name) = branch_name.to_str() {
-            let branch_path =
-                join_paths_correctly(path_repo, &format!(".git/refs/heads/{}", branch_name));
-            let branch_file = open_file(&branch_path)?;
-            let branch_content = read_file_string(branch_file)?;
-            recover_commits(path_repo, &branch_content, &mut commits)?;
-        }
-    }
-
-    Ok(commits)
-}
-
-fn recover_commits(
-    path_repo: &str,
-    branch_content: &str,
-    commits: &mut Vec<String>,
-) -> Result<(), UtilError> {
-    commits.push(branch_content.to_string());
-    let commit_content = git_cat_file(path_repo, branch_content, "-p")?;
-    let parent_commit = get_parent_hashes(commit_content);
-    if parent_commit == PARENT_INITIAL {
-        return Ok(());
-    }
-    recover_commits(path_repo, &parent_commit, commits)?;
-    Ok(())
-}
-
 /// Procesa los datos de una solicitud Git y los convierte en una estructura `GitRequest`.
 /// Esta función toma los datos de la solicitud Git y los divide en comandos y argumentos.
 ///
@@ -395,9 +468,20 @@ fn get_components_request(bytes: &[u8]) -> Result<(&[u8], Vec<String>), UtilErro
 ///
 /// Devuelve un resultado que contiene la ruta del repositorio si la operación es exitosa.
 /// En caso de error, retorna un error de tipo UtilError indicando la no existencia del repositorio.
+/// Una ruta que sale del directorio raíz, por ejemplo con `..` o un enlace simbólico, se trata
+/// como un repositorio inexistente.
 ///
-fn get_path_repository(root: &str, pathname: &str) -> Result<String, UtilError> {
-    let path_repo = join_paths_correctly(root, pathname);
+pub fn get_path_repository(root: &str, pathname: &str) -> Result<String, UtilError> {
+    // El cliente puede enviar la ruta como `/repo`, `repo/` o `./repo`
+    let name = pathname
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/");
+    let path_repo = match resolve_repo_path(root, &name) {
+        Ok(path_repo) => path_repo,
+        Err(_) => return Err(UtilError::RepoNotFoundError(pathname.to_string())),
+    };
     let path = Path::new(&path_repo);
     if !(path.exists() && path.is_dir()) {
         return Err(UtilError::RepoNotFoundError(pathname.to_string()));
@@ -420,7 +504,7 @@ fn get_path_repository(root: &str, pathname: &str) -> Result<String, UtilError>
 pub fn get_objects_fetch(
     git_server: &mut GitServer,
     confirmed_hashes: Vec<String>,
-) -> Result<Vec<(ObjectType, Vec<u8>)>, UtilError> {
+) -> Result<Vec<(ObjectType, String)>, UtilError> {
     git_server.delete_head_in_available_references();
     let references = &git_server.available_references;
     let objects = get_objects_fetch_with_hash_valid(
@@ -435,179 +519,421 @@ pub fn get_objects_fetch(
 pub fn handle_receive_pack(stream: &mut TcpStream, path_repo: &str) -> Result<String, UtilError> {
     let capabilitites: Vec<String> = CAPABILITIES_PUSH.iter().map(|&s| s.to_string()).collect();
     let mut server = GitServer::create_from_path(path_repo, VERSION_DEFAULT, &capabilitites)?;
-    println!("Server: {:?}", server);
     server.send_references(stream)?;
 
     let requests = receive_reference_update_request(stream, &mut server)?;
     if requests.is_empty() {
         return Ok("El cliente no solicito referencias".to_string());
     }
-    let objects = receive_packfile(stream)?;
-    // println!("handle_receive_pack Objects -> : {:?}", objects);
-    // El server no enviara estatus
-    // match process_request_update(requests, objects, path_repo)
-    // {
-    //     Ok(status) => send_decompressed_package_status(stream, &status),
-    //     Err(_) => send_decompression_failure_status(stream),
-    // }
-    match process_request_update(requests, objects, path_repo) {
-        Ok(_) => Ok("Se pusheo correctamente".to_string()),
-        Err(e) => Err(e),
+    let delete_refs = server.supports(DELETE_REFS);
+    // Si el cliente solo borra referencias no envía un packfile
+    let objects = if requests
+        .iter()
+        .all(|request| is_null_obj_id(request.get_new()))
+    {
+        Vec::new()
+    } else {
+        let limits = PackfileLimits::from_repository(path_repo);
+        match receive_packfile_with_limits(
+            stream,
+            server.supports(OFS_DELTA),
+            &limits,
+            server.object_format,
+        ) {
+            Ok(objects) => objects,
+            Err(e) => {
+                send_decompression_failure_status(stream, &e.to_string(), &requests)?;
+                return Err(e);
+            }
+        }
+    };
+    let status = process_request_update(&requests, objects, path_repo, delete_refs)?;
+    send_decompressed_package_status(stream, &status)?;
+    run_post_receive_hook(&requests, &status, path_repo);
+    Ok("Se pusheo correctamente".to_string())
+}
+
+/// Procesa las actualizaciones de referencias solicitadas por el cliente en un push.
+///
+/// Guarda los objetos recibidos en el packfile, ejecuta el hook `pre-receive` del repositorio
+/// y luego intenta actualizar cada una de las referencias solicitadas. Si el hook termina con
+/// error se rechazan todas las referencias, informando al cliente lo que escribió el hook en
+/// su salida de error. Cada `ReferencesUpdate` indica lo que el cliente quiere hacer:
+///   create branch     =  old-id=zero-id  new-id
+///   delete branch     =  old-id          new-id=zero-id
+///   update branch     =  old-id          new-id
+///
+/// # Argumentos
+///
+/// * `requests` - Referencias que el cliente quiere actualizar.
+/// * `objects` - Objetos recibidos en el packfile.
+/// * `path_repo` - Ruta del repositorio en el servidor.
+/// * `delete_refs` - Indica si el servidor anunció la capacidad `delete-refs`; si no, se
+///   rechaza el borrado de referencias.
+///
+/// # Retorno
+///
+/// Devuelve un vector con la ruta de cada referencia y el resultado de su actualización,
+/// donde el error contiene el motivo del rechazo que se informará al cliente.
+/// Si no se pueden guardar los objetos recibidos se devuelve un `UtilError`.
+///
+pub fn process_request_update(
+    requests: &[ReferencesUpdate],
+    objects: Vec<(ObjectEntry, Vec<u8>)>,
+    path_repo: &str,
+    delete_refs: bool,
+) -> Result<Vec<ReferenceStatus>, UtilError> {
+    if !objects.is_empty() {
+        save_objects(objects, path_repo)?;
+    }
+    if let Some(hook) = run_hook(path_repo, PRE_RECEIVE_HOOK, &format_hook_input(requests)) {
+        if !hook.success {
+            let mut reason = format!("{} hook declined", PRE_RECEIVE_HOOK);
+            let message = format_hook_message(&hook.output());
+            if !message.is_empty() {
+                reason = format!("{}: {}", reason, message);
+            }
+            return Ok(requests
+                .iter()
+                .map(|request| (request.get_path_refs().to_string(), Err(reason.clone())))
+                .collect());
+        }
+    }
+    let mut status = Vec::new();
+    for request in requests {
+        let result = process_reference_update(request, path_repo, delete_refs);
+        status.push((request.get_path_refs().to_string(), result));
     }
+    Ok(status)
 }
 
-/// Guarda referencias (nombres y hashes) en archivos individuales dentro del directorio de referencias
-/// remotas en un repositorio Git.
+/// Ejecuta el hook `post-receive` del repositorio con las referencias que se actualizaron
+/// en el push. El resultado del hook no afecta al push, que ya fue informado al cliente.
 ///
-/// Esta función toma un vector de tuplas `(String, String)` que representa pares de nombres y hashes de
-/// referencias. El path del repositorio `repo_path` se utiliza para construir la ruta del directorio de
-/// referencias y, a continuación, se asegura de que el directorio esté limpio. Luego, escribe cada
-/// par de nombre y hash en archivos individuales dentro del directorio.
+/// # Argumentos
 ///
-/// # Errores
+/// * `requests` - Referencias que el cliente quiso actualizar.
+/// * `status` - Resultado de la actualización de cada referencia.
+/// * `path_repo` - Ruta del repositorio en el servidor.
 ///
-/// - Si no puede asegurar que el directorio de referencias esté limpio o no puede escribir en los archivos,
-///   se devuelve un error del tipo `CommandsError::RemotoNotInitialized`.
+fn run_post_receive_hook(
+    requests: &[ReferencesUpdate],
+    status: &[ReferenceStatus],
+    path_repo: &str,
+) {
+    let updated = requests
+        .iter()
+        .zip(status)
+        .filter(|(_, (_, result))| result.is_ok())
+        .map(|(request, _)| request);
+    let input = format_hook_input(updated);
+    if input.is_empty() {
+        return;
+    }
+    if let Some(hook) = run_hook(path_repo, POST_RECEIVE_HOOK, &input) {
+        if !hook.success || !hook.output().is_empty() {
+            log_debug(&format!(
+                "{} hook: {}",
+                POST_RECEIVE_HOOK,
+                format_hook_message(&hook.output())
+            ));
+        }
+    }
+}
+
+/// Actualiza una única referencia del repositorio a partir de una solicitud del cliente.
 ///
-fn save_references_with_name_head(repo_path: &str, name: &str) -> Result<(), UtilError> {
-    let log_dir = format!("{}/.git/logs/refs/heads", repo_path);
-    let log_full_dir = format!("{}/{}", log_dir, name);
-    create_directory(Path::new(&log_dir))?;
-    if fs::metadata(log_full_dir).is_err() {
-        let path_log = "logs/refs/heads".to_string();
-        let path_branch = "refs/heads".to_string();
-        save_log(repo_path, name, &path_log, &path_branch)?;
+/// # Argumentos
+///
+/// * `request` - Solicitud de actualización de la referencia.
+/// * `path_repo` - Ruta del repositorio en el servidor.
+/// * `delete_refs` - Indica si se permite borrar la referencia.
+///
+/// # Retorno
+///
+/// Devuelve `Ok(())` si la referencia fue actualizada o un `Err` con el motivo por el
+/// cual fue rechazada.
+///
+fn process_reference_update(
+    request: &ReferencesUpdate,
+    path_repo: &str,
+    delete_refs: bool,
+) -> Result<(), String> {
+    let hash_reference_new = request.get_new();
+    if hash_reference_new == request.get_old() {
+        return Ok(());
+    }
+    if is_null_obj_id(hash_reference_new) {
+        if !delete_refs {
+            return Err("deletion not supported".to_string());
+        }
+        return delete_reference(request, path_repo);
+    }
+    let path_object = format!(
+        "{}/{}/objects/{}/{}",
+        path_repo,
+        GIT_DIR,
+        &hash_reference_new[..2],
+        &hash_reference_new[2..]
+    );
+    if !file_exists(&path_object) {
+        return Err("missing necessary objects".to_string());
+    }
+
+    let current_branch_path = request.get_path_refs().split('/').collect::<Vec<_>>();
+    let mut current_branch = "master";
+    if current_branch_path.len() >= 3 {
+        current_branch = current_branch_path[2];
+    }
+    match update_branch(path_repo, current_branch, hash_reference_new) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("merge conflict".to_string()),
+        Err(e) => Err(e.to_string()),
     }
-    Ok(())
 }
 
-/// Guarda referencias (nombres y hashes) en archivos individuales dentro del directorio de referencias
-/// remotas en un repositorio Git.
+/// Elimina una referencia del repositorio a pedido del cliente, junto con su reflog. No se
+/// permite borrar la branch a la que apunta HEAD, ni una referencia cuyo valor no coincide con
+/// el que el cliente indicó como anterior.
 ///
-/// Esta función toma un vector de tuplas `(String, String)` que representa pares de nombres y hashes de
-/// referencias. El path del repositorio `repo_path` se utiliza para construir la ruta del directorio de
-/// referencias y, a continuación, se asegura de que el directorio esté limpio. Luego, escribe cada
-/// par de nombre y hash en archivos individuales dentro del directorio.
+/// # Argumentos
 ///
-/// # Errores
+/// * `request` - Solicitud de borrado, con el hash nuevo en cero.
+/// * `path_repo` - Ruta del repositorio en el servidor.
 ///
-/// - Si no puede asegurar que el directorio de referencias esté limpio o no puede escribir en los archivos,
-///   se devuelve un error del tipo `CommandsError::RemotoNotInitialized`.
+/// # Retorno
+///
+/// Devuelve `Ok(())` si la referencia fue eliminada o un `Err` con el motivo del rechazo.
 ///
-fn save_references_with_name_remote(name: &str, repo_path: &str) -> Result<(), UtilError> {
-    let log_dir = format!("{}/.git/logs/refs/remotes/origin", repo_path);
-    create_directory(Path::new(&log_dir))?;
+fn delete_reference(request: &ReferencesUpdate, path_repo: &str) -> Result<(), String> {
+    let ref_path = request.get_path_refs();
+    if ref_path == HEAD || !is_valid_ref_name(ref_path) {
+        return Err("invalid ref".to_string());
+    }
+    let is_current_branch = get_current_branch(path_repo)
+        .is_ok_and(|branch| format!("{}/{}", REFS_HEADS, branch) == *ref_path);
+    if is_current_branch {
+        return Err("deletion of the current branch prohibited".to_string());
+    }
+    let git_dir = format!("{}/{}", path_repo, GIT_DIR);
+    let path = format!("{}/{}", git_dir, ref_path);
+    // La referencia puede estar suelta, empaquetada en packed-refs o en ambos lugares
+    let loose = fs::read_to_string(&path).ok();
+    let packed = read_packed_refs(&git_dir)
+        .into_iter()
+        .find(|packed| packed.reference.get_ref_path() == ref_path)
+        .map(|packed| packed.reference.get_hash().to_string());
+    let current = match loose.as_deref().map(str::trim).or(packed.as_deref()) {
+        Some(current) => current.to_string(),
+        None => return Err("failed to delete: no such ref".to_string()),
+    };
+    if !is_null_obj_id(request.get_old()) && *request.get_old() != current {
+        return Err("failed to delete: stale old value".to_string());
+    }
+    if loose.is_some() && fs::remove_file(&path).is_err() {
+        return Err("failed to delete".to_string());
+    }
+    if packed.is_some() && remove_packed_ref(&git_dir, ref_path).is_err() {
+        return Err("failed to delete".to_string());
+    }
+    delete_reflog(path_repo, ref_path).map_err(|e| e.to_string())
+}
 
-    let path_log = "logs/refs/remotes".to_string();
+/// Escribe el nuevo hash de la branch en el servidor y, si la branch ya existía,
+/// mergea la referencia remota en ella.
+///
+/// # Retorno
+///
+/// Devuelve `true` si la branch quedó actualizada o `false` si el merge tuvo conflictos.
+///
+fn update_branch(path_repo: &str, current_branch: &str, hash: &str) -> Result<bool, UtilError> {
+    let branch_path = format!(
+        "{}/{}/{}/{}",
+        path_repo, GIT_DIR, "refs/heads", current_branch
+    );
+    let exists = Path::new(&branch_path).exists();
+    if !exists {
+        create_file(branch_path.as_str(), hash)?;
+        let object_format = get_object_format(&join_paths_correctly(path_repo, GIT_DIR));
+        append_branch_reflog(
+            path_repo,
+            current_branch,
+            &"0".repeat(object_format.hex_len()),
+            hash,
+            REFLOG_DEFAULT_IDENTITY,
+            "push",
+        )?;
+    }
+    let remote_ref = format!("{}/{}", "refs/remotes", current_branch);
+    let remote_path = format!("{}/{}/{}", path_repo, GIT_DIR, remote_ref);
+    let old_remote = fs::read_to_string(&remote_path).unwrap_or_default();
+    create_file_replace(remote_path.as_str(), hash)?;
+    append_reflog(
+        path_repo,
+        &remote_ref,
+        &old_remote,
+        hash,
+        REFLOG_DEFAULT_IDENTITY,
+        "push",
+    )?;
 
-    let path_branch = "refs/remotes".to_string();
+    if exists {
+        let client: Client = Client::new(
+            "test".to_string(),
+            "test@fi.uba.ar".to_string(),
+            "19992020".to_string(),
+            "9090".to_string(),
+            "localhost".to_string(),
+            path_repo.to_string(),
+            current_branch.to_string(),
+        );
+        let result_merge = git_merge(path_repo, current_branch, &remote_ref, client, false, None)?;
+        if result_merge.contains("CONFLICT") {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
 
-    save_log(repo_path, name, &path_log, &path_branch)?;
+/// Atiende una solicitud `git-upload-archive`.
+///
+/// Recibe los argumentos enviados por el cliente (`argument <valor>`) hasta el flush y
+/// responde con el archivo tar del tree-ish solicitado.
+///
+/// # Argumentos
+///
+/// * `stream` - Conexión con el cliente.
+/// * `path_repo` - Ruta del repositorio solicitado.
+///
+/// # Retorna
+///
+/// Un mensaje con el resultado de la operación o un `UtilError` si la solicitud fue rechazada.
+fn handle_upload_archive(stream: &mut TcpStream, path_repo: &str) -> Result<String, UtilError> {
+    let arguments = receive_archive_arguments(stream)?;
+    send_archive(stream, path_repo, &arguments)
+}
 
-    Ok(())
+/// Lee los argumentos de una solicitud `git-upload-archive` hasta el flush.
+///
+/// # Argumentos
+///
+/// * `stream` - Conexión con el cliente.
+///
+/// # Retorna
+///
+/// Los argumentos sin el prefijo `argument `, o un `UtilError` si alguna línea no es un argumento.
+fn receive_archive_arguments(stream: &mut dyn Read) -> Result<Vec<String>, UtilError> {
+    let mut arguments = Vec::new();
+    for line in read(stream)? {
+        let line = String::from_utf8_lossy(&line).to_string();
+        match line.strip_prefix("argument ") {
+            Some(argument) => arguments.push(argument.trim().to_string()),
+            None => return Err(UtilError::UploadArchiveRejected(line)),
+        }
+    }
+    Ok(arguments)
 }
 
-// [TODO #8]
-// Esta funcion es la que se encarga de procesar las actualizaciones de las referencias
-// Y de actualizar el repo
-// Recibe un vector de ReferencesUpdate y un vector de (ObjectEntry, Vec<u8>)
-// El vector de ReferencesUpdate son las referencias que el cliente quiere actualizar
-// Atributos de ReferencesUpdate:
-// - old: String -> Hash del objeto viejo
-// - new: String -> Hash del objeto nuevo
-// - path_refs: String -> Ruta de la referencia -> Ejemplo: refs/heads/master
-// ReferencesUpdate tambien se usara para saber lo que el cliente quiere hacer:
-//   create branch     =  old-id=zero-id  new-id
-//   delete branch     =  old-id          new-id=zero-id
-//   update branch     =  old-id          new-id
-// Se puede devolvera un vector del tipo Vec<(String, bool)>
-// Donde el String es la referencia y el bool es si fue exitosa o no
-// ESto se usara para decirle al cliente si la actualizacion fue exitosa o no
-// EL falso es solo si no se puede actualizar
-// SI el paquete esta corrupto se debe enviar un error
-pub fn process_request_update(
-    requests: Vec<ReferencesUpdate>,
-    objects: Vec<(ObjectEntry, Vec<u8>)>,
+/// Envía al cliente el archivo tar del tree-ish indicado en los argumentos.
+///
+/// Si la solicitud es válida se responde `ACK` seguido de un flush y luego el archivo por la banda
+/// de datos del protocolo side-band, terminando con otro flush. En caso contrario se responde
+/// `NACK <motivo>` y un flush.
+///
+/// # Argumentos
+///
+/// * `writer` - Conexión con el cliente.
+/// * `path_repo` - Ruta del repositorio solicitado.
+/// * `arguments` - Argumentos recibidos del cliente.
+///
+/// # Retorna
+///
+/// Un mensaje con el resultado de la operación o un `UtilError` si la solicitud fue rechazada.
+pub fn send_archive(
+    writer: &mut dyn Write,
     path_repo: &str,
-) -> Result<Vec<(String, bool)>, UtilError> {
-    if objects.is_empty() {
-        println!("Objects is empty");
-        // let mut result: Vec<(String, bool)> = Vec::new();
-        // for request in requests {
-        //     result.push((request.get_path_refs().to_string(), false));
-        // }
-        return Ok(Vec::new());
-    }
-    let mut result_vec: Vec<(String, bool)> = Vec::new();
-    let mut result: (String, bool) = ("".to_string(), false);
-
-    if let Some(branch_hash) = requests.first() {
-        let path_reference = branch_hash.get_path_refs();
-        let hash_reference_new = branch_hash.get_new();
-        let hash_reference_old = branch_hash.get_old();
-        if hash_reference_new != hash_reference_old {
-            save_objects(objects, path_repo)?;
-            let current_branch_path = path_reference.split('/').collect::<Vec<_>>();
-            let mut current_branch = "master";
-            if current_branch_path.len() >= 3 {
-                current_branch = current_branch_path[2];
-            }
-            let mut branch_path = format!(
-                "{}/{}/{}/{}",
-                path_repo, GIT_DIR, "refs/heads", current_branch
-            );
-
-            let mut new = 0;
-            let path = Path::new(&branch_path);
-            if path.exists() {
-                new = 1;
-            }
-            create_file(branch_path.as_str(), hash_reference_new.as_str())?;
-            save_references_with_name_head(path_repo, current_branch)?;
-            branch_path = format!(
-                "{}/{}/{}/{}",
-                path_repo, GIT_DIR, "refs/remotes", current_branch
-            );
-            create_file_replace(branch_path.as_str(), hash_reference_new.as_str())?;
-            save_references_with_name_remote(current_branch, path_repo)?;
-
-            if new == 1 {
-                let client: Client = Client::new(
-                    "test".to_string(),
-                    "test@fi.uba.ar".to_string(),
-                    "19992020".to_string(),
-                    "9090".to_string(),
-                    "localhost".to_string(),
-                    path_repo.to_string(),
-                    current_branch.to_string(),
-                );
-                let remote_branch = format!("{}/{}", "refs/remotes", current_branch);
-                let result_merge = git_merge(path_repo, current_branch, &remote_branch, client)?;
-                if result_merge.contains("CONFLICT") {
-                    result.0 = hash_reference_old.to_string();
-                    result.1 = false;
-                    result_vec.push(result.clone());
-                }
-                result.0 = hash_reference_new.to_string();
-                result.1 = true;
-
-                result_vec.push(result.clone());
-            } else {
-                result.0 = hash_reference_old.to_string();
-                result.1 = false;
-                result_vec.push(result.clone());
-            }
+    arguments: &[String],
+) -> Result<String, UtilError> {
+    let archive = match parse_archive_arguments(arguments)
+        .and_then(|treeish| Ok(build_archive(path_repo, &treeish)?))
+    {
+        Ok(archive) => archive,
+        Err(e) => {
+            let reason = match &e {
+                UtilError::UploadArchiveRejected(reason) => format!("NACK {}\n", reason),
+                e => format!("NACK {}\n", e),
+            };
+            send_message(writer, &add_length_prefix(&reason, reason.len()), e.clone())?;
+            send_flush(writer, e.clone())?;
+            return Err(e);
         }
     };
-    if result_vec.is_empty() {
-        result_vec.push(result);
+    let error = UtilError::UploadArchiveRejected("No se pudo enviar el archivo".to_string());
+    send_message(writer, PKT_ACK, error.clone())?;
+    send_flush(writer, error.clone())?;
+    for chunk in archive.chunks(SIDE_BAND_MAX_DATA) {
+        let mut line = format!("{:04x}", chunk.len() + LENGTH_PREFIX_SIZE + 1).into_bytes();
+        line.push(SIDE_BAND_DATA);
+        line.extend_from_slice(chunk);
+        send_bytes(writer, &line, error.clone())?;
+    }
+    send_flush(writer, error)?;
+    Ok(format!("Archivo enviado ({} bytes)", archive.len()))
+}
+
+/// Obtiene el tree-ish de los argumentos de `git-upload-archive`.
+///
+/// Solo se admite el formato tar; cualquier otra opción se rechaza.
+///
+/// # Argumentos
+///
+/// * `arguments` - Argumentos recibidos del cliente.
+fn parse_archive_arguments(arguments: &[String]) -> Result<String, UtilError> {
+    let mut treeish = None;
+    for argument in arguments {
+        if let Some(format) = argument.strip_prefix("--format=") {
+            if format != "tar" {
+                return Err(UtilError::UploadArchiveRejected(format!(
+                    "formato no soportado: {}",
+                    format
+                )));
+            }
+        } else if argument.starts_with('-') {
+            return Err(UtilError::UploadArchiveRejected(format!(
+                "opción no soportada: {}",
+                argument
+            )));
+        } else if is_valid_ref_name(argument) || is_valid_obj_id_any_format(argument) {
+            treeish = Some(argument.to_string());
+        } else {
+            return Err(UtilError::UploadArchiveRejected(format!(
+                "tree-ish inválido: {}",
+                argument
+            )));
+        }
+    }
+    match treeish {
+        Some(treeish) => Ok(treeish),
+        None => Err(UtilError::UploadArchiveRejected(
+            "falta el tree-ish".to_string(),
+        )),
     }
-    Ok(result_vec)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::{
+        add::git_add,
+        branch::git_branch_create,
+        commit::{git_commit, Commit},
+        init::git_init,
+    };
+    use crate::consts::{
+        ACCESS_POLICY_FILE, FLUSH_PKT, NO_THIN, PR_FOLDER, REPORT_STATUS, ZERO_ID,
+    };
+    use crate::git_transport::advertised::AdvertisedRefLine;
+    use crate::test_utils::{serve_once, test_commit};
 
     #[test]
     fn test_generate_request_string_upload_pack() {
@@ -651,6 +977,259 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_path_repository_stays_in_root() {
+        let root = "./test_path_repository";
+        git_init(&format!("{}/raiz/repo", root)).expect("Falló al crear el repositorio");
+        git_init(&format!("{}/afuera", root)).expect("Falló al crear el repositorio");
+        let root = format!("{}/raiz", root);
+
+        let repo = get_path_repository(&root, "/repo");
+        let slashes = get_path_repository(&root, "./repo/");
+        let parent = get_path_repository(&root, "/../afuera");
+
+        fs::remove_dir_all("./test_path_repository").expect("Falló al remover los directorios");
+        assert_eq!(repo, Ok(format!("{}/repo", root)));
+        assert_eq!(slashes, Ok(format!("{}/repo", root)));
+        assert_eq!(
+            parent,
+            Err(UtilError::RepoNotFoundError("/../afuera".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_send_archive_side_band() {
+        let directory = "./test_upload_archive";
+        git_init(directory).expect("Error al ejecutar git init");
+        create_file_replace(&format!("{}/README.md", directory), "Hola Mundo")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "README.md").expect("Error al ejecutar git add");
+        git_commit(directory, test_commit("prueba")).expect("Error al commitear");
+
+        let input = b"001aargument --format=tar\n0014argument master\n0000";
+        let arguments = receive_archive_arguments(&mut &input[..]).expect("Falló al leer");
+        assert_eq!(arguments, vec!["--format=tar", "master"]);
+
+        let mut output = Vec::new();
+        send_archive(&mut output, directory, &arguments).expect("Falló al enviar el archivo");
+        let header = format!("{}{}", PKT_ACK, FLUSH_PKT);
+        assert!(output.starts_with(header.as_bytes()));
+        assert!(output.ends_with(FLUSH_PKT.as_bytes()));
+
+        // Se reconstruye el archivo a partir de las líneas de la banda de datos
+        let mut received = Vec::new();
+        let mut rest = &output[header.len()..output.len() - FLUSH_PKT.len()];
+        while !rest.is_empty() {
+            let len = usize::from_str_radix(&String::from_utf8_lossy(&rest[..4]), 16)
+                .expect("Largo inválido");
+            assert_eq!(rest[4], SIDE_BAND_DATA);
+            received.extend_from_slice(&rest[5..len]);
+            rest = &rest[len..];
+        }
+        let expected = build_archive(directory, "master").expect("Falló al generar el archivo");
+        assert_eq!(received, expected);
+
+        let mut output = Vec::new();
+        let arguments = vec!["--format=zip".to_string(), "master".to_string()];
+        assert!(send_archive(&mut output, directory, &arguments).is_err());
+        assert!(output[4..].starts_with(b"NACK formato no soportado: zip\n"));
+
+        let mut output = Vec::new();
+        let arguments = vec!["../../HEAD".to_string()];
+        assert!(send_archive(&mut output, directory, &arguments).is_err());
+        assert!(output[4..].starts_with("NACK tree-ish inválido: ../../HEAD\n".as_bytes()));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_receive_pack_deletes_reference() {
+        let root = "./test_receive_pack_delete";
+        let repo = format!("{}/repo", root);
+        git_init(&repo).expect("Error al ejecutar git init");
+        create_file_replace(&format!("{}/hola.txt", repo), "Hola")
+            .expect("Falló al crear el archivo");
+        git_add(&repo, "hola.txt").expect("Error al ejecutar git add");
+        let commit = Commit::new(
+            "prueba".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(&repo, commit).expect("Error al ejecutar git commit");
+        git_branch_create(&repo, "foo").expect("Error al crear la branch");
+        let hash = fs::read_to_string(format!("{}/.git/refs/heads/master", repo))
+            .expect("Falló al leer la referencia");
+        let hash = hash.trim();
+
+        let (mut socket, port, server) = serve_once(root);
+        let request = GitRequest::generate_request_string(
+            RequestCommand::ReceivePack,
+            "repo",
+            "127.0.0.1",
+            &port,
+        );
+        socket
+            .write_all(request.as_bytes())
+            .expect("Falló al enviar la solicitud");
+        let advertised = read(&mut socket).expect("Falló al leer las referencias");
+        let capabilities = advertised
+            .iter()
+            .map(|line| String::from_utf8_lossy(line).to_string())
+            .find_map(|line| line.split_once('\0').map(|(_, caps)| caps.to_string()))
+            .unwrap_or_default();
+
+        // Solo se borran referencias, por lo que no se envía un packfile
+        let delete_foo = format!(
+            "{} {} refs/heads/foo\0{} {}\n",
+            hash, ZERO_ID, REPORT_STATUS, DELETE_REFS
+        );
+        let delete_master = format!("{} {} refs/heads/master\n", hash, ZERO_ID);
+        for line in [delete_foo, delete_master] {
+            socket
+                .write_all(add_length_prefix(&line, line.len()).as_bytes())
+                .expect("Falló al enviar la actualización");
+        }
+        socket
+            .write_all(FLUSH_PKT.as_bytes())
+            .expect("Falló al enviar el flush");
+        let status = read(&mut socket).expect("Falló al leer el estado");
+        let server_result = server.join().expect("Falló el servidor");
+        let status: Vec<String> = status
+            .iter()
+            .map(|line| String::from_utf8_lossy(line).trim_end().to_string())
+            .collect();
+        let foo_exists = file_exists(&format!("{}/.git/refs/heads/foo", repo));
+        let foo_reflog_exists = file_exists(&format!("{}/.git/logs/refs/heads/foo", repo));
+        let master_exists = file_exists(&format!("{}/.git/refs/heads/master", repo));
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+
+        assert!(server_result.is_ok());
+        assert!(capabilities.contains(DELETE_REFS) && capabilities.contains(NO_THIN));
+        assert_eq!(
+            status,
+            vec![
+                "unpack ok".to_string(),
+                "ok refs/heads/foo".to_string(),
+                "ng refs/heads/master deletion of the current branch prohibited".to_string(),
+            ]
+        );
+        assert!(!foo_exists);
+        assert!(!foo_reflog_exists);
+        assert!(master_exists);
+    }
+
+    #[test]
+    fn test_delete_reference_removes_packed_refs() {
+        let repo = "./test_delete_reference_packed";
+        git_init(repo).expect("Error al ejecutar git init");
+        create_file_replace(&format!("{}/hola.txt", repo), "Hola")
+            .expect("Falló al crear el archivo");
+        git_add(repo, "hola.txt").expect("Error al ejecutar git add");
+        let commit = Commit::new(
+            "prueba".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+            "Juan".to_string(),
+            "jdr@fi.uba.ar".to_string(),
+        );
+        git_commit(repo, commit).expect("Error al ejecutar git commit");
+        let hash = fs::read_to_string(format!("{}/.git/refs/heads/master", repo))
+            .expect("Falló al leer la referencia");
+        let hash = hash.trim();
+        let tag_hash = "1".repeat(40);
+        // `packed` solo está empaquetada y `both` está empaquetada y suelta
+        create_file_replace(&format!("{}/.git/refs/heads/both", repo), hash)
+            .expect("Falló al crear la referencia");
+        let packed_refs = format!(
+            "# pack-refs with: peeled\n{h} refs/heads/both\n{h} refs/heads/packed\n{t} refs/tags/v1\n^{h}\n",
+            h = hash,
+            t = tag_hash
+        );
+        create_file_replace(&format!("{}/.git/packed-refs", repo), &packed_refs)
+            .expect("Falló al crear packed-refs");
+
+        let delete_packed = ReferencesUpdate::new(
+            hash.to_string(),
+            ZERO_ID.to_string(),
+            "refs/heads/packed".to_string(),
+        );
+        let delete_both = ReferencesUpdate::new(
+            hash.to_string(),
+            ZERO_ID.to_string(),
+            "refs/heads/both".to_string(),
+        );
+        let delete_missing = ReferencesUpdate::new(
+            ZERO_ID.to_string(),
+            ZERO_ID.to_string(),
+            "refs/heads/packed".to_string(),
+        );
+        let packed_result = delete_reference(&delete_packed, repo);
+        let both_result = delete_reference(&delete_both, repo);
+        let missing_result = delete_reference(&delete_missing, repo);
+        let both_exists = file_exists(&format!("{}/.git/refs/heads/both", repo));
+        let packed_content = fs::read_to_string(format!("{}/.git/packed-refs", repo))
+            .expect("Falló al leer packed-refs");
+
+        fs::remove_dir_all(repo).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(packed_result, Ok(()));
+        assert_eq!(both_result, Ok(()));
+        assert_eq!(
+            missing_result,
+            Err("failed to delete: no such ref".to_string())
+        );
+        assert!(!both_exists);
+        assert_eq!(
+            packed_content,
+            format!(
+                "# pack-refs with: peeled\n{} refs/tags/v1\n^{}\n",
+                tag_hash, hash
+            )
+        );
+    }
+
+    #[test]
+    fn test_receive_pack_denied_on_read_only_repository() {
+        let root = "./test_access_denied_server";
+        git_init(&format!("{}/docs", root)).expect("Error al ejecutar git init");
+        create_file_replace(
+            &format!("{}/{}/{}", root, PR_FOLDER, ACCESS_POLICY_FILE),
+            "docs = read\n",
+        )
+        .expect("Falló al crear la política de acceso");
+
+        let (mut socket, port, server) = serve_once(root);
+        let request = GitRequest::generate_request_string(
+            RequestCommand::ReceivePack,
+            "docs",
+            "127.0.0.1",
+            &port,
+        );
+        socket
+            .write_all(request.as_bytes())
+            .expect("Falló al enviar la solicitud");
+        let server_result = server.join().expect("Falló el servidor");
+        let line = read_pkt_line(&mut socket).expect("Falló al leer la respuesta");
+        let mut rest = Vec::new();
+        socket
+            .read_to_end(&mut rest)
+            .expect("Falló al leer la respuesta");
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+
+        let error = UtilError::AccessDenied("docs".to_string());
+        assert_eq!(server_result, Err(error.clone()));
+        // El cliente recibe el error en lugar de las referencias y no se transfiere nada más
+        assert_eq!(
+            AdvertisedRefLine::classify_vec(&vec![line]),
+            Err(UtilError::RemoteError(error.to_string()))
+        );
+        assert!(rest.is_empty());
+    }
+
     #[test]
     fn test_git_request_new_with_valid_format() {
         // Datos de entrada válidos con un espacio
@@ -0,0 +1,203 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::consts::{GIT_DIR, HOOKS_DIR};
+
+use super::references_update::ReferencesUpdate;
+
+/// Resultado de la ejecución de un hook.
+///
+/// - `success`: si el hook terminó con código de salida 0.
+/// - `stdout`: lo que el hook escribió en la salida estándar.
+/// - `stderr`: lo que el hook escribió en la salida de error.
+///
+/// Al igual que en git, ambas salidas se le informan al cliente.
+///
+#[derive(Debug, PartialEq, Eq)]
+pub struct HookOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookOutput {
+    fn failed(name: &str, error: std::io::Error) -> Self {
+        HookOutput {
+            success: false,
+            stdout: String::new(),
+            stderr: format!("no se pudo ejecutar el hook {}: {}", name, error),
+        }
+    }
+
+    /// Devuelve la salida estándar seguida de la salida de error del hook.
+    pub fn output(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr)
+    }
+}
+
+/// Ejecuta el hook `.git/hooks/<name>` del repositorio, pasándole `input` por la entrada
+/// estándar. El hook se ejecuta desde el directorio del repositorio, con `GIT_DIR` apuntando a
+/// su directorio git, al igual que en git.
+///
+/// # Argumentos
+///
+/// * `path_repo` - Ruta del repositorio en el servidor.
+/// * `name` - Nombre del hook, por ejemplo `pre-receive`.
+/// * `input` - Contenido que recibe el hook por la entrada estándar.
+///
+/// # Retorno
+///
+/// Devuelve `None` si el hook no existe o no es ejecutable, en cuyo caso se omite. Si no se
+/// puede ejecutar el hook se lo considera fallido, con el motivo en `stderr`.
+///
+pub fn run_hook(path_repo: &str, name: &str, input: &str) -> Option<HookOutput> {
+    let hook_path = format!("{}/{}/{}/{}", path_repo, GIT_DIR, HOOKS_DIR, name);
+    if !is_executable(Path::new(&hook_path)) {
+        return None;
+    }
+    let child = Command::new(fs::canonicalize(&hook_path).ok()?)
+        .current_dir(path_repo)
+        .env("GIT_DIR", GIT_DIR)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return Some(HookOutput::failed(name, e)),
+    };
+    // La entrada se escribe desde otro hilo mientras se leen las salidas: si el hook llena
+    // el pipe de salida antes de terminar de leer la entrada, ambos procesos se bloquearían
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input = input.to_string();
+        // Un hook que no lee la entrada estándar la cierra antes de que se termine de escribir
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        })
+    });
+    let output = child.wait_with_output();
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    match output {
+        Ok(output) => Some(HookOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }),
+        Err(e) => Some(HookOutput::failed(name, e)),
+    }
+}
+
+/// Genera la entrada de los hooks `pre-receive` y `post-receive`: una línea
+/// `<old> <new> <ref>` por cada referencia.
+///
+/// # Argumentos
+///
+/// * `requests` - Actualizaciones de referencias del push.
+///
+pub fn format_hook_input<'a>(requests: impl IntoIterator<Item = &'a ReferencesUpdate>) -> String {
+    requests
+        .into_iter()
+        .map(|request| {
+            format!(
+                "{} {} {}\n",
+                request.get_old(),
+                request.get_new(),
+                request.get_path_refs()
+            )
+        })
+        .collect()
+}
+
+/// Convierte la salida de un hook en una sola línea, para poder enviarla como motivo
+/// del rechazo de una referencia en el `report-status`.
+///
+/// # Argumentos
+///
+/// * `output` - Salida del hook.
+///
+pub fn format_hook_message(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Indica si el archivo existe y tiene permiso de ejecución. En sistemas que no son unix
+/// alcanza con que exista.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    match fs::metadata(path) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_hook_input_and_message() {
+        let requests = vec![
+            ReferencesUpdate::new(
+                "0".repeat(40),
+                "1".repeat(40),
+                "refs/heads/master".to_string(),
+            ),
+            ReferencesUpdate::new("1".repeat(40), "2".repeat(40), "refs/heads/dev".to_string()),
+        ];
+        assert_eq!(
+            format_hook_input(&requests),
+            format!(
+                "{} {} refs/heads/master\n{} {} refs/heads/dev\n",
+                "0".repeat(40),
+                "1".repeat(40),
+                "1".repeat(40),
+                "2".repeat(40)
+            )
+        );
+        assert_eq!(
+            format_hook_message("rama bloqueada\n\n  pedir permiso \n"),
+            "rama bloqueada; pedir permiso"
+        );
+        assert_eq!(run_hook("./test_hooks_missing", "pre-receive", ""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_relays_stdout_with_large_input() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = "./test_hooks_stdout";
+        let hooks = format!("{}/{}/{}", directory, GIT_DIR, HOOKS_DIR);
+        fs::create_dir_all(&hooks).expect("Falló al crear los hooks");
+        let path = format!("{}/pre-receive", hooks);
+        // El hook llena la salida estándar antes de leer la entrada
+        let script = "#!/bin/sh\nhead -c 200000 /dev/zero | tr '\\0' a\ncat > /dev/null\necho\necho rama bloqueada\nexit 1\n";
+        fs::write(&path, script).expect("Falló al crear el hook");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .expect("Falló al dar permisos al hook");
+
+        let input = "x".repeat(200_000);
+        let result = run_hook(directory, "pre-receive", &input);
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        let hook = result.expect("El hook debería ejecutarse");
+        assert!(!hook.success);
+        assert_eq!(hook.stdout.len(), 200_000 + "\nrama bloqueada\n".len());
+        assert!(hook.output().ends_with("\nrama bloqueada\n"));
+    }
+}
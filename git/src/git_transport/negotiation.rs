@@ -1,16 +1,26 @@
 use crate::{
-    commands::branch::get_branch,
-    consts::{GIT_DIR, HAVE, PKT_DONE, PKT_NAK, REFS_HEADS},
+    commands::{
+        branch::get_branch,
+        cat_file::git_cat_file,
+        push::{is_ancestor_cached, AncestorCache},
+    },
+    consts::{
+        FILTER, FILTER_BLOB_NONE, GIT_DIR, HAVE, MAX_HAVE_COMMITS, PARENT_INITIAL, PKT_DONE,
+        PKT_NAK, REFS_HEADS,
+    },
     git_server::GitServer,
     util::{
         connections::{send_done, send_flush, send_message},
         errors::UtilError,
         files::{open_file, read_file_string},
+        logger::log_debug,
+        object_format::ObjectFormat,
         pkt_line,
         validation::is_valid_obj_id,
     },
 };
 use std::{
+    collections::{HashSet, VecDeque},
     io::{Read, Write},
     net::TcpStream,
 };
@@ -21,6 +31,7 @@ pub struct PackfileNegotiation {
     pub capabilities: Vec<String>,
     pub wanted_objects: Vec<String>,
     pub common_objects: Vec<String>,
+    pub filter: Option<String>, // Filtro pedido con la línea `filter <spec>`
 }
 
 impl PackfileNegotiation {
@@ -33,9 +44,15 @@ impl PackfileNegotiation {
             capabilities,
             wanted_objects,
             common_objects,
+            filter: None,
         }
     }
 
+    /// Indica si el cliente pidió omitir los blobs del packfile (`filter blob:none`).
+    pub fn omits_blobs(&self) -> bool {
+        self.filter.as_deref() == Some(FILTER_BLOB_NONE)
+    }
+
     pub fn get_components(&self) -> (Vec<String>, Vec<String>, Vec<String>) {
         (
             self.capabilities.clone(),
@@ -66,11 +83,31 @@ impl PackfileNegotiation {
 ///
 pub fn upload_request_type(
     socket: &mut TcpStream,
-    refs: &Vec<Reference>,
+    refs: &[Reference],
+    type_req: &str,
+) -> Result<(), UtilError> {
+    let hashes: Vec<String> = refs
+        .iter()
+        .map(|refs| refs.get_hash().to_string())
+        .collect();
+    upload_request_hashes(socket, &hashes, type_req)
+}
+
+/// Envía una solicitud (`want` o `have`) por cada hash indicado, terminando con un flush-pkt.
+///
+/// # Argumentos
+///
+/// - `socket`: Canal de comunicación con el servidor.
+/// - `hashes`: Hashes de los objetos a solicitar o confirmar.
+/// - `type_req`: Tipo de solicitud, ya sea "want" o "have".
+///
+pub fn upload_request_hashes(
+    socket: &mut dyn Write,
+    hashes: &[String],
     type_req: &str,
 ) -> Result<(), UtilError> {
-    for refs in refs {
-        let message = format!("{} {}\n", type_req, refs.get_hash());
+    for hash in hashes {
+        let message = format!("{} {}\n", type_req, hash);
         let message = pkt_line::add_length_prefix(&message, message.len());
         send_message(socket, &message, UtilError::UploadRequest)?;
     }
@@ -133,7 +170,6 @@ pub fn receive_done(stream: &mut dyn Read, err: UtilError) -> Result<(), UtilErr
     if stream.read_exact(&mut buffer).is_err() {
         return Err(err);
     }
-    println!("Recibi el done: buffer -> {:?}", buffer);
     let response = String::from_utf8_lossy(&buffer);
 
     if response != PKT_DONE {
@@ -160,35 +196,69 @@ pub fn receive_done(stream: &mut dyn Read, err: UtilError) -> Result<(), UtilErr
 ///
 /// Retorna un `UtilError` en caso de cualquier problema durante la comunicación o si no se recibe el mensaje "done" esperado.
 
-pub fn receive_request(stream: &mut dyn Read) -> Result<PackfileNegotiation, UtilError> {
+pub fn receive_request(
+    stream: &mut dyn Read,
+    object_format: ObjectFormat,
+) -> Result<PackfileNegotiation, UtilError> {
     // Want
-    println!("Recibiendo solicitudes...");
     let lines = pkt_line::read(stream)?;
     if lines.is_empty() {
         return Ok(PackfileNegotiation::new(Vec::new(), Vec::new(), Vec::new()));
     }
     for line in &lines {
-        println!("want -> {}", String::from_utf8_lossy(line));
+        log_debug(&format!("want -> {}", String::from_utf8_lossy(line)));
     }
-    let (capabilities, request) = process_received_requests_want(lines)?;
+    let (lines, filter) = extract_filter(lines)?;
+    if lines.is_empty() {
+        return Err(UtilError::UnexpectedRequestNotWant);
+    }
+    let (capabilities, request) = process_received_requests_want(lines, object_format)?;
 
     let lines = pkt_line::read(stream)?;
     for line in &lines {
-        println!("have -> {}", String::from_utf8_lossy(line));
+        log_debug(&format!("have -> {}", String::from_utf8_lossy(line)));
     }
-    if lines.len() == 1 && lines[0] == b"done" {
-        return Ok(PackfileNegotiation::new(capabilities, request, Vec::new()));
-    }
-
-    // Have
-    let request_have = receive_request_type(lines, "have", UtilError::UnexpectedRequestNotHave)?;
-    println!("Termine de procesar el have");
+    let mut negotiation = if lines.len() == 1 && lines[0] == b"done" {
+        PackfileNegotiation::new(capabilities, request, Vec::new())
+    } else {
+        // Have
+        let request_have = receive_request_type(
+            lines,
+            "have",
+            UtilError::UnexpectedRequestNotHave,
+            object_format,
+        )?;
+        PackfileNegotiation::new(capabilities, request, request_have)
+    };
     // Done
-    Ok(PackfileNegotiation::new(
-        capabilities,
-        request,
-        request_have,
-    ))
+    negotiation.filter = filter;
+    Ok(negotiation)
+}
+
+/// Separa de las líneas `want` la línea `filter <spec>` con la que el cliente pide un clone
+/// parcial. Solo se soporta el filtro `blob:none`.
+///
+/// # Argumentos
+///
+/// * `lines` - Líneas recibidas hasta el primer flush.
+///
+/// # Retorna
+///
+/// Las líneas restantes y el filtro pedido, si lo hay, o `UtilError::UnsupportedFilter` si el
+/// filtro no está soportado.
+///
+fn extract_filter(lines: Vec<Vec<u8>>) -> Result<(Vec<Vec<u8>>, Option<String>), UtilError> {
+    let mut filter = None;
+    let mut requests = Vec::new();
+    for line in lines {
+        let line_str = String::from_utf8_lossy(&line);
+        match line_str.trim_end().strip_prefix(&format!("{} ", FILTER)) {
+            Some(FILTER_BLOB_NONE) => filter = Some(FILTER_BLOB_NONE.to_string()),
+            Some(spec) => return Err(UtilError::UnsupportedFilter(spec.to_string())),
+            None => requests.push(line),
+        }
+    }
+    Ok((requests, filter))
 }
 
 /// Procesa las solicitudes recibidas a partir de un conjunto de líneas de bytes.
@@ -216,11 +286,12 @@ pub fn receive_request(stream: &mut dyn Read) -> Result<PackfileNegotiation, Uti
 ///
 fn process_received_requests_want(
     lines: Vec<Vec<u8>>,
+    object_format: ObjectFormat,
 ) -> Result<(Vec<String>, Vec<String>), UtilError> {
     let mut request = Vec::new();
 
     // Want and capabilities
-    let (hash, capabilities) = extraction_capabilities(&lines[0])?;
+    let (hash, capabilities) = extraction_capabilities(&lines[0], object_format)?;
     request.push(hash);
 
     // Want
@@ -228,6 +299,7 @@ fn process_received_requests_want(
         lines[1..].to_vec(),
         "want",
         UtilError::UnexpectedRequestNotWant,
+        object_format,
     )?;
 
     request.extend(want);
@@ -242,6 +314,7 @@ fn process_received_requests_want(
 /// # Argumentos
 ///
 /// * `line` - Referencia al vector de bytes que contiene la línea con las capacidades y el hash.
+/// * `object_format` - Formato de los objetos del repositorio, con el que se valida el hash.
 ///
 /// # Errores
 ///
@@ -257,7 +330,10 @@ fn process_received_requests_want(
 /// en formato de cadenas (`(String, Vec<String>)`), o un error (`UtilError`) en caso de que falle la extracción
 /// o validación de las capacidades y el hash.
 ///
-fn extraction_capabilities(line: &[u8]) -> Result<(String, Vec<String>), UtilError> {
+fn extraction_capabilities(
+    line: &[u8],
+    object_format: ObjectFormat,
+) -> Result<(String, Vec<String>), UtilError> {
     let line_str = String::from_utf8_lossy(line);
     let mut line_split = line_str.split_ascii_whitespace();
     let type_request = line_split
@@ -269,7 +345,7 @@ fn extraction_capabilities(line: &[u8]) -> Result<(String, Vec<String>), UtilErr
     let hash = line_split
         .next()
         .ok_or_else(|| UtilError::InvalidRequestFormat(line_str.to_string()))?;
-    if !is_valid_obj_id(hash) {
+    if !is_valid_obj_id(hash, object_format) {
         return Err(UtilError::InvalidObjectId);
     }
     let capabilities = line_split
@@ -290,6 +366,7 @@ fn extraction_capabilities(line: &[u8]) -> Result<(String, Vec<String>), UtilErr
 /// * `lines` - Vector que contiene las líneas de bytes con las solicitudes.
 /// * `type_req` - Tipo de solicitud esperada, por ejemplo, "want".
 /// * `err` - Error específico a devolver en caso de que no se encuentre el tipo de solicitud esperado.
+/// * `object_format` - Formato de los objetos del repositorio, con el que se validan los hashes.
 ///
 /// # Errores
 ///
@@ -308,6 +385,7 @@ fn receive_request_type(
     lines: Vec<Vec<u8>>,
     type_req: &str,
     error: UtilError,
+    object_format: ObjectFormat,
 ) -> Result<Vec<String>, UtilError> {
     lines.iter().try_fold(Vec::new(), |mut acc, line| {
         let line_str = String::from_utf8_lossy(line);
@@ -322,7 +400,7 @@ fn receive_request_type(
             .nth(1)
             .ok_or_else(|| UtilError::InvalidRequestFormat(request.to_string()))?;
 
-        if !is_valid_obj_id(hash) {
+        if !is_valid_obj_id(hash, object_format) {
             return Err(UtilError::InvalidObjectId);
         }
 
@@ -353,11 +431,9 @@ pub fn sent_references_valid_client(
     for h in hash {
         let message = format!("ACK {} continue\n", h);
         let message = pkt_line::add_length_prefix(&message, message.len());
-        println!("Enviando mensaje: {}", message);
         send_message(stream, &message, UtilError::UploadRequest)?;
     }
     send_message(stream, PKT_NAK, UtilError::SendNAKConfirmReferences)?; // SendNAKConfirmReferences
-    println!("Termine de enviar las referencias enviando un NACK");
     Ok(())
 }
 
@@ -381,49 +457,170 @@ pub fn send_acknowledge_last_reference(
 ) -> Result<(), UtilError> {
     let message = format!("ACK {}\n", confirmed_hashes[confirmed_hashes.len() - 1]);
     let message = pkt_line::add_length_prefix(&message, message.len());
-    println!("Enviando ultimo ack: {}", message);
+    log_debug(&format!("Enviando ultimo ack: {}", message));
     send_message(writer, &message, UtilError::SendLastACKConf)
 }
 
+/// Negocia con el servidor los objetos a descargar en un fetch.
+///
+/// Solo se envían `want` y `have` de las referencias en las que la rama local está atrasada
+/// respecto del servidor; las que están al día o adelantadas no necesitan objetos nuevos.
+/// Si ninguna referencia está atrasada, se envía solo un flush-pkt y el servidor no responde
+/// con un packfile.
+///
+/// # Argumentos
+///
+/// * `stream`: Canal de comunicación con el servidor.
+/// * `server`: Servidor con las referencias anunciadas.
+/// * `path_repo`: Ruta al directorio del repositorio local.
+///
+/// # Retorno
+///
+/// `true` si se solicitaron objetos y el servidor enviará un packfile, `false` si todas las
+/// referencias están al día.
+///
 pub fn packfile_negotiation_partial(
     stream: &mut TcpStream,
     server: &mut GitServer,
     path_repo: &str,
-) -> Result<(), UtilError> {
+) -> Result<bool, UtilError> {
     let local_references = get_local_references(path_repo)?;
     server.update_local_references(&local_references);
-    let remote_references = server.get_remote_references()?;
+    let local_references = server.get_local_references()?;
+
+    let references_status: Vec<(String, String, String)> = server
+        .get_remote_references()?
+        .iter()
+        .filter_map(|remote| {
+            local_references
+                .iter()
+                .find(|local| local.get_ref_path() == remote.get_ref_path())
+                .map(|local| {
+                    (
+                        remote.get_ref_path().to_string(),
+                        local.get_hash().to_string(),
+                        remote.get_hash().to_string(),
+                    )
+                })
+        })
+        .collect();
+    let up_to_date: Vec<String> = check_references_up_to_date(path_repo, references_status)
+        .into_iter()
+        .filter(|(_, up_to_date)| *up_to_date)
+        .map(|(path_branch, _)| path_branch)
+        .collect();
+
+    let remote_references: Vec<Reference> = server
+        .get_remote_references()?
+        .into_iter()
+        .filter(|reference| !up_to_date.contains(reference.get_ref_path()))
+        .collect();
+    if remote_references.is_empty() {
+        send_flush(stream, UtilError::UploadRequestFlush)?;
+        return Ok(false);
+    }
     send_firts_request(stream, &remote_references[0], server)?;
-    upload_request_type(stream, &remote_references[1..].to_vec(), "want")?;
-
-    //  [TODO #5]
-    // Este TODO es para optmizar el fetch
-    // Supongamos que nuestro main local tiene h1(mas antigua) h2 h3 h4 h5 h6(mas nueva)
-    // Y el servidor tiene h1 h2 h3 h4 h5
-    // Si sabemos que el h1 h2 h3 h4 h5 son iguales en el servidor y en el local
-    // No haria falta que el servidor nos mande esos commits
-    // En este caso solo tenemos los ultimos commits del local y remoto
-    // Se necesita una funcion que dado 3 vectores de String
-    // Vec(path_branch, hash local, hash remoto)
-    // Nos devuelva un vector de path_branch y un booleano  -> Vec<(String, bool)>
-    // El booleano indica si el path_branch local es igual al hash remoto
-    // True: La referencia local esta actualizada o adelantada, no necesitamos descargar cosas
-    // False: La referencia local esta atrasada, necesitamos descargar cosas
-    // Ejemplo:
-    // Vec<(refs/heads/master, h3, h4), (refs/heads/develop, h4, h3)>
-    // Vec<(refs/heads/master, false), (refs/heads/develop, true)>
-    // Supongamos que el orden de los hash es por su indice
+    upload_request_type(stream, &remote_references[1..], "want")?;
 
-    let local_references = server.get_local_references()?;
-    upload_request_type(stream, &local_references, HAVE)?;
+    let local_references: Vec<Reference> = local_references
+        .into_iter()
+        .filter(|reference| !up_to_date.contains(reference.get_ref_path()))
+        .collect();
+    let have_hashes = get_have_hashes(path_repo, &local_references, MAX_HAVE_COMMITS);
+    upload_request_hashes(stream, &have_hashes, HAVE)?;
 
     let ack_references = recive_acknowledgments_multi_ack(stream, server)?;
     server.confirm_local_references(&ack_references);
 
-    println!("ACKS: {:?}", ack_references);
-    println!("Le enviare el done");
+    log_debug(&format!("ACKS: {:?}", ack_references));
     send_done(stream, UtilError::UploadRequestDone)?;
-    Ok(())
+    Ok(true)
+}
+
+/// Indica, para cada referencia, si la rama local está al día con la del servidor.
+///
+/// Una referencia está al día si el hash local es igual al remoto o si el remoto es un ancestro
+/// del local (la rama local está adelantada). En cualquier otro caso, incluido cuando el
+/// commit remoto no existe en el repositorio local, la rama está atrasada.
+///
+/// # Argumentos
+///
+/// * `path_repo`: Ruta al directorio del repositorio local.
+/// * `references`: Tuplas `(path_branch, hash local, hash remoto)`.
+///
+/// # Retorno
+///
+/// Tuplas `(path_branch, al_dia)` en el mismo orden que `references`: `true` si la referencia
+/// está al día o adelantada, `false` si hay que descargar objetos.
+///
+/// # Ejemplo
+///
+/// Si la historia es `h1 <- h2 <- h3 <- h4`:
+///
+/// `[(refs/heads/master, h3, h4), (refs/heads/develop, h4, h3)]` devuelve
+/// `[(refs/heads/master, false), (refs/heads/develop, true)]`.
+///
+pub fn check_references_up_to_date(
+    path_repo: &str,
+    references: Vec<(String, String, String)>,
+) -> Vec<(String, bool)> {
+    // Las referencias suelen compartir historia, por lo que los parents leídos se reutilizan
+    let mut cache = AncestorCache::new();
+    references
+        .into_iter()
+        .map(|(path_branch, local_hash, remote_hash)| {
+            let up_to_date = local_hash == remote_hash
+                || is_ancestor_cached(path_repo, &local_hash, &remote_hash, Some(&mut cache))
+                    .unwrap_or(false);
+            (path_branch, up_to_date)
+        })
+        .collect()
+}
+
+/// Obtiene los commits locales que se anuncian al servidor con `have`.
+///
+/// Recorre la historia hacia atrás desde cada referencia local, incluyendo todos los padres
+/// de los merges, para que el servidor pueda encontrar un ancestro común aunque no tenga
+/// las puntas locales. El recorrido se detiene al alcanzar `limit` commits; los commits que
+/// no se pueden leer se omiten.
+///
+/// # Argumentos
+///
+/// * `path_repo`: Ruta al directorio del repositorio Git.
+/// * `references`: Referencias locales desde las que se recorre la historia.
+/// * `limit`: Cantidad máxima de commits a devolver.
+///
+/// # Retorno
+///
+/// Los hashes de los commits, empezando por las puntas de las referencias y sin repetidos.
+///
+pub fn get_have_hashes(path_repo: &str, references: &[Reference], limit: usize) -> Vec<String> {
+    let mut haves: Vec<String> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut pending: VecDeque<String> = references
+        .iter()
+        .map(|reference| reference.get_hash().to_string())
+        .collect();
+
+    while let Some(hash) = pending.pop_front() {
+        if haves.len() >= limit {
+            break;
+        }
+        if hash == PARENT_INITIAL || !visited.insert(hash.clone()) {
+            continue;
+        }
+        let commit = match git_cat_file(path_repo, &hash, "-p") {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+        for line in commit.lines() {
+            if let Some(parent) = line.strip_prefix("parent ") {
+                pending.push_back(parent.trim().to_string());
+            }
+        }
+        haves.push(hash);
+    }
+    haves
 }
 
 /// Obtiene las referencias locales de un repositorio Git ubicado en la ruta especificada.
@@ -506,19 +703,16 @@ pub fn recive_acknowledgments_multi_ack(
     server: &GitServer,
 ) -> Result<Vec<String>, UtilError> {
     if !server.is_multiack() {
-        println!("No es multiack el server!");
         return Err(UtilError::MultiAckNotSupported);
     }
 
     let lines = pkt_line::read(stream)?;
-    println!("Lines: {:?}", lines);
     let mut acks = Vec::new();
     for line in lines {
         if line == b"NAK" {
             break;
         }
-        let hash = process_ack_response(line)?;
-        println!("recive_acknowledgments_multi_ack -> Hash: {}", hash);
+        let hash = process_ack_response(line, server.object_format)?;
         acks.push(hash);
     }
     Ok(acks)
@@ -529,6 +723,7 @@ pub fn recive_acknowledgments_multi_ack(
 /// # Argumentos
 ///
 /// * `response`: Vector de bytes que representa la respuesta ACK recibida del servidor.
+/// * `object_format`: Formato de los objetos del servidor, con el que se valida el hash.
 ///
 /// # Returns
 ///
@@ -544,7 +739,10 @@ pub fn recive_acknowledgments_multi_ack(
 /// - `UtilError::ExpectedHashInAckResponse`: Se esperaba un hash en la respuesta ACK y no se encontró.
 /// - `UtilError::ExpectedStatusInAckResponse`: Se esperaba un estado en la respuesta ACK y no se encontró.
 ///
-pub fn process_ack_response(response: Vec<u8>) -> Result<String, UtilError> {
+pub fn process_ack_response(
+    response: Vec<u8>,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let line_str = String::from_utf8_lossy(&response);
     let mut line_split = line_str.split_ascii_whitespace();
     let type_request = line_split
@@ -556,7 +754,7 @@ pub fn process_ack_response(response: Vec<u8>) -> Result<String, UtilError> {
     let hash = line_split
         .next()
         .ok_or(UtilError::ExpectedHashInAckResponse)?;
-    if !is_valid_obj_id(hash) {
+    if !is_valid_obj_id(hash, object_format) {
         return Err(UtilError::InvalidHashInAckResponse);
     }
     let status = line_split
@@ -597,12 +795,14 @@ pub fn receive_reference_update_request(
     }
     let mut result = Vec::new();
 
-    let (refs_first, capabilities) = recieve_first_reference_update(&update_request[0])?;
+    let object_format = git_server.object_format;
+    let (refs_first, capabilities) =
+        recieve_first_reference_update(&update_request[0], object_format)?;
     result.push(refs_first);
 
-    for request in &update_request {
+    for request in update_request.iter().skip(1) {
         if let Ok(line_str) = std::str::from_utf8(request) {
-            let refupdate = ReferencesUpdate::new_from_line(line_str)?;
+            let refupdate = ReferencesUpdate::new_from_line(line_str, object_format)?;
             result.push(refupdate);
         }
     }
@@ -613,6 +813,7 @@ pub fn receive_reference_update_request(
 
 pub fn recieve_first_reference_update(
     line: &[u8],
+    object_format: ObjectFormat,
 ) -> Result<(ReferencesUpdate, Vec<String>), UtilError> {
     if let Ok(line_str) = std::str::from_utf8(line) {
         let parts = line_str.split('\0').collect::<Vec<&str>>();
@@ -620,14 +821,17 @@ pub fn recieve_first_reference_update(
             return Err(UtilError::InvalidReferenceUpdateRequest);
         }
         if parts.len() == 1 {
-            let refupdate = ReferencesUpdate::new_from_line(parts[0])?;
+            let refupdate = ReferencesUpdate::new_from_line(parts[0], object_format)?;
             return Ok((refupdate, Vec::new()));
         }
         let capabilites: Vec<String> = parts[1]
             .split_ascii_whitespace()
             .map(|s| s.to_string())
             .collect();
-        return Ok((ReferencesUpdate::new_from_line(parts[0])?, capabilites));
+        return Ok((
+            ReferencesUpdate::new_from_line(parts[0], object_format)?,
+            capabilites,
+        ));
     }
     Err(UtilError::InvalidReferenceUpdateRequest)
 }
@@ -635,6 +839,80 @@ pub fn recieve_first_reference_update(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::init::git_init;
+    use crate::git_transport::{
+        git_request::search_available_references, references::get_objects_fetch_with_hash_valid,
+    };
+    use crate::test_utils::commit_file;
+    use std::fs;
+
+    #[test]
+    fn test_check_references_up_to_date() {
+        let directory = "./test_check_references_up_to_date";
+        git_init(directory).expect("Error al ejecutar git init");
+        commit_file(directory, "uno.txt", "uno");
+        commit_file(directory, "dos.txt", "dos");
+        let h3 = commit_file(directory, "tres.txt", "tres");
+        let h4 = commit_file(directory, "cuatro.txt", "cuatro");
+        let unknown = "0123456789abcdef0123456789abcdef01234567".to_string();
+
+        let result = check_references_up_to_date(
+            directory,
+            vec![
+                ("refs/heads/master".to_string(), h3.clone(), h4.clone()),
+                ("refs/heads/develop".to_string(), h4.clone(), h3.clone()),
+                ("refs/heads/main".to_string(), h4.clone(), h4.clone()),
+                ("refs/heads/feature".to_string(), h4, unknown),
+            ],
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            result,
+            vec![
+                ("refs/heads/master".to_string(), false),
+                ("refs/heads/develop".to_string(), true),
+                ("refs/heads/main".to_string(), true),
+                ("refs/heads/feature".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_have_hashes_reduce_fetched_objects() {
+        let directory = "./test_have_hashes_fetch";
+        git_init(directory).expect("Error al ejecutar git init");
+        let first = commit_file(directory, "uno.txt", "uno");
+        let second = commit_file(directory, "dos.txt", "dos");
+        let local_tip = Reference::new(&second, "refs/heads/master").expect("Error reference");
+
+        let haves = get_have_hashes(
+            directory,
+            std::slice::from_ref(&local_tip),
+            MAX_HAVE_COMMITS,
+        );
+        let limited = get_have_hashes(directory, &[local_tip], 1);
+
+        // El servidor recibe un cambio pequeño sobre la historia que el cliente ya tiene
+        let third = commit_file(directory, "tres.txt", "tres");
+        let master = Reference::new(&third, "refs/heads/master").expect("Error reference");
+
+        let tip_only = search_available_references(directory, std::slice::from_ref(&second));
+        let objects_tip_only =
+            get_objects_fetch_with_hash_valid(directory, vec![master.clone()], &tip_only)
+                .expect("Error get objects");
+        let confirmed = search_available_references(directory, &haves);
+        let objects_history =
+            get_objects_fetch_with_hash_valid(directory, vec![master], &confirmed)
+                .expect("Error get objects");
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(haves, vec![second.clone(), first]);
+        assert_eq!(limited, vec![second]);
+        assert!(objects_history.len() < objects_tip_only.len());
+    }
 
     #[test]
     fn test_receive_request_type_valid_want() {
@@ -642,7 +920,12 @@ mod tests {
         lines.push(b"want 74730d410fcb6603ace96f1dc55ea6196122532d".to_vec());
         lines.push(b"want 7d1665144a3a975c05f1f43902ddaf084e784dbe".to_vec());
         lines.push(b"want 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_vec());
-        let result = receive_request_type(lines, "want", UtilError::UnexpectedRequestNotWant);
+        let result = receive_request_type(
+            lines,
+            "want",
+            UtilError::UnexpectedRequestNotWant,
+            ObjectFormat::Sha1,
+        );
         assert!(result.is_ok());
         let wanted_hashes = result.unwrap();
         assert_eq!(
@@ -660,7 +943,12 @@ mod tests {
         let mut lines = Vec::new();
         lines.push(b"have 7e47fe2bd8d01d481f44d7af0531bd93d3b21c01".to_vec());
         lines.push(b"have 74730d410fcb6603ace96f1dc55ea6196122532d".to_vec());
-        let result = receive_request_type(lines, "have", UtilError::UnexpectedRequestNotHave);
+        let result = receive_request_type(
+            lines,
+            "have",
+            UtilError::UnexpectedRequestNotHave,
+            ObjectFormat::Sha1,
+        );
         assert!(result.is_ok());
         let have_hashes = result.unwrap();
         assert_eq!(
@@ -678,14 +966,24 @@ mod tests {
         lines.push(b"have 74730d410fcb6603ace96f1dc55ea6196122532d".to_vec());
         lines.push(b"want 7d1665144a3a975c05f1f43902ddaf084e784dbe".to_vec());
         lines.push(b"have 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_vec());
-        let result = receive_request_type(lines, "want", UtilError::UnexpectedRequestNotWant);
+        let result = receive_request_type(
+            lines,
+            "want",
+            UtilError::UnexpectedRequestNotWant,
+            ObjectFormat::Sha1,
+        );
         assert!(result.is_err());
     }
 
     #[test]
     fn test_receive_request_empty() {
         let lines = Vec::new();
-        let result = receive_request_type(lines, "want", UtilError::UnexpectedRequestNotWant);
+        let result = receive_request_type(
+            lines,
+            "want",
+            UtilError::UnexpectedRequestNotWant,
+            ObjectFormat::Sha1,
+        );
         assert!(result.is_ok());
         let result = result.unwrap();
         assert_eq!(result.len(), 0);
@@ -696,7 +994,7 @@ mod tests {
         let line =
             b"want 74730d410fcb6603ace96f1dc55ea6196122532d capability1 capability2 capability3\n"
                 .to_vec();
-        let result = extraction_capabilities(&line);
+        let result = extraction_capabilities(&line, ObjectFormat::Sha1);
         assert!(result.is_ok());
         let (hash, capabilities) = result.unwrap();
         assert_eq!(hash, "74730d410fcb6603ace96f1dc55ea6196122532d");
@@ -709,7 +1007,7 @@ mod tests {
     #[test]
     fn test_extraction_capabilities_empty() {
         let line = b"want 74730d410fcb6603ace96f1dc55ea6196122532d\n".to_vec();
-        let result = extraction_capabilities(&line);
+        let result = extraction_capabilities(&line, ObjectFormat::Sha1);
         assert!(result.is_ok());
         let (hash, capabilities) = result.unwrap();
         assert_eq!(hash, "74730d410fcb6603ace96f1dc55ea6196122532d");
@@ -726,7 +1024,7 @@ mod tests {
         lines.push(b"want 7d1665144a3a975c05f1f43902ddaf084e784dbe".to_vec());
         lines.push(b"want 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_vec());
 
-        let result = process_received_requests_want(lines);
+        let result = process_received_requests_want(lines, ObjectFormat::Sha1);
         assert!(result.is_ok());
         let (capabilities, request) = result.unwrap();
 
@@ -751,7 +1049,7 @@ mod tests {
         lines.push(b"want 7d1665144a3a975c05f1f43902ddaf084e784dbe".to_vec());
         lines.push(b"want 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_vec());
 
-        let result = process_received_requests_want(lines);
+        let result = process_received_requests_want(lines, ObjectFormat::Sha1);
         assert!(result.is_ok());
         let (capabilities, request) = result.unwrap();
 
@@ -770,7 +1068,7 @@ mod tests {
     fn test_process_ack_response_valid() {
         let response = b"ACK 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a continue\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Ok(String::from("5a3f6be755bbb7deae50065988cbfa1ffa9ab68a"))
         );
     }
@@ -779,7 +1077,7 @@ mod tests {
     fn test_process_ack_response_invalid_ack_missing() {
         let response = b"NAK\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Err(UtilError::ExpectedAckMissing)
         );
     }
@@ -788,7 +1086,7 @@ mod tests {
     fn test_process_ack_response_invalid_format() {
         let response = b"LALA 5a3f6be755bbb7deae50065988cbfa1ffa9ab68a continue\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Err(UtilError::ExpectedAckMissing)
         );
     }
@@ -797,7 +1095,7 @@ mod tests {
     fn test_process_ack_response_invalid_object_id() {
         let response = b"ACK invalid_hash continue\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Err(UtilError::InvalidHashInAckResponse)
         );
     }
@@ -806,7 +1104,7 @@ mod tests {
     fn test_process_ack_response_expected_hash_missing() {
         let response = b"ACK\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Err(UtilError::ExpectedHashInAckResponse)
         );
     }
@@ -815,7 +1113,7 @@ mod tests {
     fn test_process_ack_response_expected_status_missing() {
         let response = b"ACK 7e47fe2bd8d01d481f44d7af0531bd93d3b21c01\n".to_vec();
         assert_eq!(
-            process_ack_response(response),
+            process_ack_response(response, ObjectFormat::Sha1),
             Err(UtilError::ExpectedStatusInAckResponse)
         );
     }
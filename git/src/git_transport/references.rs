@@ -1,22 +1,33 @@
 use crate::commands::branch::{get_branch, get_current_branch, get_parent_hashes};
-use crate::commands::cat_file::git_cat_file;
-use crate::commands::checkout::{extract_parent_hash, get_tree_hash};
-use crate::commands::commit::get_commits;
+use crate::commands::cat_file::{git_cat_file, git_cat_file_typed};
+use crate::commands::checkout::get_tree_hash;
+use crate::commands::commit::{get_commits, Commit};
+use crate::commands::errors::CommandsError;
 use crate::commands::push::is_ancestor;
-use crate::consts::PARENT_INITIAL;
+use crate::commands::symbolic_ref::{read_head, SymbolicRef};
+use crate::consts::{MAX_SYMREF_DEPTH, PARENT_INITIAL, TAG, TREE};
 use crate::git_server::GitServer;
-use crate::util::files::{open_file, read_file, read_file_string};
+use crate::util::files::{get_git_path, open_file, read_file, read_file_string};
 use crate::util::formats::{
-    compressor_object_content, compressor_object_with_bytes_content, decompression_object,
+    compressor_object_content, compressor_object_with_bytes_content, decompression_object_content,
+};
+use crate::util::objects::{
+    get_object_path, is_blob_mode, object_exists, parse_tree_line, ObjectType,
 };
-use crate::util::objects::ObjectType;
 use crate::{
-    consts::{DIRECTORY, FILE, GIT_DIR, HEAD, REFS_REMOTES, REFS_TAGS, REF_HEADS},
+    consts::{
+        DIRECTORY, ERR_PREFIX, GIT_DIR, HEAD, PACKED_REFS, REFS_REMOTES, REFS_TAGS, REF_HEADS,
+    },
     util::{
-        connections::send_message, errors::UtilError, pkt_line, validation::join_paths_correctly,
+        connections::send_message,
+        errors::UtilError,
+        pkt_line,
+        validation::{join_paths_correctly, valid_ref_name},
     },
 };
 use std::{
+    cmp::Ordering,
+    collections::HashSet,
     fs,
     net::TcpStream,
     path::{Path, PathBuf},
@@ -30,6 +41,17 @@ pub enum ReferenceType {
     Head,
 }
 
+/// Hashes a los que apunta una referencia una vez resuelta.
+///
+/// - `raw`: hash al que apunta la referencia luego de seguir las referencias simbólicas.
+/// - `peeled`: hash del objeto final luego de pelar los objetos tag; coincide con `raw`
+///   si la referencia no apunta a un tag anotado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedReference {
+    pub raw: String,
+    pub peeled: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Reference {
     hash: String,
@@ -37,6 +59,24 @@ pub struct Reference {
     reference_type: ReferenceType,
 }
 
+/// Las referencias se ordenan como en el anuncio de git: primero HEAD y luego el resto por su
+/// ruta en orden lexicográfico.
+impl Ord for Reference {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let is_head = |reference: &Reference| reference.reference_type == ReferenceType::Head;
+        is_head(other)
+            .cmp(&is_head(self))
+            .then_with(|| self.ref_path.cmp(&other.ref_path))
+            .then_with(|| self.hash.cmp(&other.hash))
+    }
+}
+
+impl PartialOrd for Reference {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Reference {
     pub fn new(hash: &str, ref_path: &str) -> Result<Reference, UtilError> {
         let hash = hash.to_string();
@@ -70,7 +110,8 @@ impl Reference {
         }
     }
 
-    /// Extrae las referencias de un repositorio Git.
+    /// Extrae las referencias de un repositorio Git, tanto las sueltas como las guardadas en
+    /// `packed-refs`.
     ///
     /// # Argumentos
     ///
@@ -78,8 +119,9 @@ impl Reference {
     ///
     /// # Retorna
     ///
-    /// Un resultado que contiene un vector de Referencias si la operación es exitosa.
-    /// En caso de error, retorna un error de tipo UtilError.
+    /// Un resultado que contiene un vector de Referencias si la operación es exitosa, con HEAD
+    /// primero y el resto ordenadas por su ruta, sin depender del orden en que el sistema de
+    /// archivos devuelve los directorios. En caso de error, retorna un error de tipo UtilError.
     pub fn extract_references_from_git(root: &str) -> Result<Vec<Reference>, UtilError> {
         let path_git = join_paths_correctly(root, GIT_DIR);
 
@@ -92,9 +134,23 @@ impl Reference {
         refs.extend(refs_branch);
         refs.extend(refs_tag);
         refs.extend(refs_remote);
+        // Las referencias sueltas tienen prioridad sobre las empaquetadas con el mismo nombre
+        for packed in read_packed_refs(&path_git) {
+            if !refs
+                .iter()
+                .any(|reference| reference.ref_path == packed.reference.ref_path)
+            {
+                refs.push(packed.reference);
+            }
+        }
 
+        // Un repositorio vacío (sin commits) no tiene referencias, ni siquiera HEAD
+        if refs.is_empty() {
+            return Ok(refs);
+        }
         let head = get_reference_head(&path_git, &refs)?;
-        refs.insert(0, head);
+        refs.push(head);
+        refs.sort();
         Ok(refs)
     }
 
@@ -102,6 +158,30 @@ impl Reference {
         &self.hash
     }
 
+    /// Resuelve la referencia en el repositorio indicado.
+    ///
+    /// Sigue las referencias simbólicas (`ref: refs/heads/main`) hasta obtener un hash y, si ese
+    /// hash corresponde a un tag anotado, pela los objetos tag hasta llegar al objeto al que apuntan.
+    /// Si la referencia no existe en disco se utiliza el hash con el que fue creada.
+    ///
+    /// # Argumentos
+    ///
+    /// * `directory` - Directorio del repositorio.
+    ///
+    /// # Retorna
+    ///
+    /// Un `ResolvedReference` con el hash de la referencia y el hash pelado, o un `UtilError` si la
+    /// referencia no existe o tiene demasiados niveles de referencias simbólicas.
+    pub fn resolve(&self, directory: &str) -> Result<ResolvedReference, UtilError> {
+        let raw = match resolve_ref_path(directory, &self.ref_path) {
+            Ok(hash) => hash,
+            Err(UtilError::ReferenceNotFound(_)) if !self.hash.is_empty() => self.hash.to_string(),
+            Err(e) => return Err(e),
+        };
+        let peeled = peel_tag(directory, &raw)?;
+        Ok(ResolvedReference { raw, peeled })
+    }
+
     pub fn get_ref_path(&self) -> &String {
         &self.ref_path
     }
@@ -121,6 +201,7 @@ impl Reference {
     }
 
     pub fn new_from_branch(path_repo: &str, name_branch: &str) -> Result<Reference, UtilError> {
+        valid_ref_name(name_branch)?;
         let path_branch = format!("{}/{}/{}/{}", path_repo, GIT_DIR, REF_HEADS, name_branch);
         let file_branch = open_file(&path_branch)?;
         let binding = read_file_string(file_branch)?;
@@ -154,20 +235,17 @@ impl Reference {
     /// Retorna un error si la referencia actual no se encuentra o si hay problemas al leer los archivos asociados a la referencia.
     ///
     pub fn get_current_references(repo_local: &str) -> Result<Self, UtilError> {
-        let path: String = format!("{}/.git/HEAD", repo_local);
-        let head = match std::fs::read_to_string(path) {
-            Ok(head) => head,
-            Err(_) => return Err(UtilError::CurrentBranchNotFound),
+        let ref_path = match read_head(repo_local) {
+            Ok(SymbolicRef::Ref(ref_path)) => ref_path,
+            _ => return Err(UtilError::CurrentBranchNotFound),
         };
-        let ref_path = head.split(':').last().unwrap();
-        let ref_path = ref_path.trim();
         let path: String = format!("{}/.git/{}", repo_local, ref_path);
         let hash = match std::fs::read_to_string(path) {
             Ok(reference) => reference,
             Err(_) => return Err(UtilError::CurrentBranchNotFound),
         };
         let hash = hash.trim();
-        let reference = Reference::new(hash, ref_path)?;
+        let reference = Reference::new(hash, &ref_path)?;
         Ok(reference)
     }
 
@@ -175,6 +253,7 @@ impl Reference {
         path_local: &str,
         name_branch: &str,
     ) -> Result<Reference, UtilError> {
+        valid_ref_name(name_branch)?;
         let ref_path = format!("{}/.git/refs/heads/{}", path_local, name_branch);
         let hash = match std::fs::read_to_string(ref_path) {
             Ok(reference) => reference,
@@ -186,6 +265,158 @@ impl Reference {
     }
 }
 
+/// Sigue una referencia, y las referencias simbólicas a las que apunte, hasta obtener un hash.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio.
+/// * `ref_path` - Referencia a resolver, por ejemplo `HEAD` o `refs/heads/master`.
+///
+/// # Retorna
+///
+/// El hash al que apunta la referencia, o un `UtilError` si no existe o si se superan
+/// `MAX_SYMREF_DEPTH` niveles de referencias simbólicas.
+fn resolve_ref_path(directory: &str, ref_path: &str) -> Result<String, UtilError> {
+    let mut current = ref_path.to_string();
+    for _ in 0..MAX_SYMREF_DEPTH {
+        let path = get_git_path(directory, &current);
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Err(UtilError::ReferenceNotFound(current)),
+        };
+        match SymbolicRef::parse(&content) {
+            Ok(SymbolicRef::Ref(target)) => current = target,
+            Ok(SymbolicRef::Detached(hash)) => return Ok(hash),
+            Err(_) => return Err(UtilError::InvalidReferencePath),
+        }
+    }
+    Err(UtilError::SymbolicRefTooDeep(ref_path.to_string()))
+}
+
+/// Referencia leída del archivo `packed-refs`.
+///
+/// - `reference`: referencia con el hash guardado en el archivo.
+/// - `peeled`: objeto al que apunta el tag anotado (línea `^<hash>`), si el archivo lo indica.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedRef {
+    pub reference: Reference,
+    pub peeled: Option<String>,
+}
+
+/// Lee las referencias guardadas en el archivo `packed-refs` del repositorio.
+///
+/// Cada línea tiene el formato `<hash> <referencia>`, y la línea `^<hash>` que puede seguirla
+/// indica el objeto al que apunta un tag anotado. Se ignoran los comentarios y las referencias
+/// que no son branches, tags ni remotos.
+///
+/// # Argumentos
+///
+/// * `path_git` - Ruta al directorio '.git'.
+///
+/// # Retorna
+///
+/// Las referencias empaquetadas en el orden del archivo; si el archivo no existe, un vector vacío.
+pub fn read_packed_refs(path_git: &str) -> Vec<PackedRef> {
+    let path = Path::new(path_git).join(PACKED_REFS);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+    let mut packed_refs: Vec<PackedRef> = Vec::new();
+    // Indica si la línea anterior fue una referencia leída, a la que se asocia un `^<hash>`
+    let mut follows_reference = false;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            follows_reference = false;
+            continue;
+        }
+        if let Some(peeled) = line.strip_prefix('^') {
+            if let (true, Some(last)) = (follows_reference, packed_refs.last_mut()) {
+                last.peeled = Some(peeled.trim().to_string());
+            }
+            follows_reference = false;
+            continue;
+        }
+        let reference = line
+            .split_once(' ')
+            .and_then(|(hash, ref_path)| Reference::new(hash.trim(), ref_path.trim()).ok());
+        follows_reference = reference.is_some();
+        if let Some(reference) = reference {
+            packed_refs.push(PackedRef {
+                reference,
+                peeled: None,
+            });
+        }
+    }
+    packed_refs
+}
+
+/// Elimina una referencia del archivo `packed-refs`, junto con la línea `^<hash>` que la sigue
+/// si es un tag anotado. El resto del archivo, incluidos los comentarios, se conserva.
+///
+/// # Argumentos
+///
+/// * `path_git` - Ruta al directorio '.git'.
+/// * `ref_path` - Ruta de la referencia, por ejemplo `refs/heads/master`.
+///
+/// # Retorna
+///
+/// `true` si la referencia estaba empaquetada y se eliminó, `false` si no estaba en el archivo.
+pub fn remove_packed_ref(path_git: &str, ref_path: &str) -> Result<bool, UtilError> {
+    let path = Path::new(path_git).join(PACKED_REFS);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return Ok(false),
+    };
+    let mut removed = false;
+    let mut skipping = false;
+    let mut new_content = String::new();
+    for line in content.lines() {
+        if skipping && line.starts_with('^') {
+            continue;
+        }
+        skipping = !line.starts_with('#')
+            && line
+                .split_once(' ')
+                .is_some_and(|(_, name)| name.trim() == ref_path);
+        if skipping {
+            removed = true;
+            continue;
+        }
+        new_content.push_str(line);
+        new_content.push('\n');
+    }
+    if removed {
+        fs::write(&path, new_content).map_err(|_| UtilError::WriteFileError)?;
+    }
+    Ok(removed)
+}
+
+/// Pela los objetos tag hasta llegar al objeto al que apuntan.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio.
+/// * `hash` - Hash del objeto a pelar.
+///
+/// # Retorna
+///
+/// El hash del primer objeto que no es un tag; si `hash` no es un tag, se devuelve sin cambios.
+pub fn peel_tag(directory: &str, hash: &str) -> Result<String, UtilError> {
+    let mut current = hash.to_string();
+    while git_cat_file(directory, &current, "-t")? == TAG {
+        let content = git_cat_file(directory, &current, "-p")?;
+        current = match content
+            .lines()
+            .find_map(|line| line.strip_prefix("object "))
+        {
+            Some(object) => object.trim().to_string(),
+            None => return Err(UtilError::InvalidObjectId),
+        };
+    }
+    Ok(current)
+}
+
 /// Extrae el contenido de un objeto a partir de su hash
 ///
 /// # Argumentos
@@ -198,26 +429,23 @@ impl Reference {
 /// Un resultado con el contenido del objeto si la operación es exitosa.
 /// En caso de error, retorna un error de tipo UtilError.
 fn get_content(directory: &str, hash_object: &str) -> Result<Vec<u8>, UtilError> {
-    let path_object = format!(
-        "{}/{}/objects/{}/{}",
-        directory,
-        GIT_DIR,
-        &hash_object[..2],
-        &hash_object[2..]
-    );
+    let path_object = get_object_path(&format!("{}/{}", directory, GIT_DIR), hash_object)?;
     let file_object = open_file(&path_object)?;
     let content_object = read_file(file_object)?;
 
     Ok(content_object)
 }
 
-/// Recorre los sub-tree recursivamente y los agrega al vector objects
+/// Recorre los sub-tree recursivamente y agrega el tipo y el hash de sus objetos al vector
+/// objects. El contenido de los objetos se lee recién al enviarlos, con `read_pack_objects`.
 ///
 /// # Argumentos
 ///
 /// * `directory` - directorio del repositorio
 /// * `tree_hash` - Hash del tree
 /// * `objects` - Vector para guardar los objetos a enviar
+/// * `saved_hashes` - Hashes de los objetos ya guardados en `objects`
+/// * `omit_blobs` - Si es true no se agregan los blobs (clone parcial con `filter blob:none`)
 ///
 /// # Retorna
 ///
@@ -225,23 +453,27 @@ fn get_content(directory: &str, hash_object: &str) -> Result<Vec<u8>, UtilError>
 pub fn recovery_tree_clone(
     directory: &str,
     tree_hash: &str,
-    objects: &mut Vec<(ObjectType, Vec<u8>)>,
+    objects: &mut Vec<(ObjectType, String)>,
+    saved_hashes: &mut HashSet<String>,
+    omit_blobs: bool,
 ) -> Result<(), UtilError> {
-    let tree_content = git_cat_file(directory, tree_hash, "-p")?;
+    let tree_content = git_cat_file_typed(directory, tree_hash, "-p", Some(TREE))?;
     for line in tree_content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let mode = parts[0];
-        let hash = parts[2];
-        if mode == FILE {
-            let mut object_blob: (ObjectType, Vec<u8>) = (ObjectType::Blob, Vec::new());
-            let blob_content = get_content(directory, hash)?;
-            object_blob.1 = blob_content;
-            save_object_pack(objects, object_blob)
+        let (mode, _, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+        // Un tree ya guardado se recorrió junto con todos sus objetos
+        if saved_hashes.contains(hash) {
+            continue;
+        }
+        if is_blob_mode(mode) {
+            if !omit_blobs {
+                let object_blob = (ObjectType::Blob, hash.to_string());
+                save_object_pack(objects, saved_hashes, hash, object_blob);
+            }
         } else if mode == DIRECTORY {
-            let mut object_tree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
-            object_tree.1 = get_content(directory, hash)?;
-            save_object_pack(objects, object_tree);
-            recovery_tree_clone(directory, hash, objects)?;
+            let object_tree = (ObjectType::Tree, hash.to_string());
+            save_object_pack(objects, saved_hashes, hash, object_tree);
+            recovery_tree_clone(directory, hash, objects, saved_hashes, omit_blobs)?;
         }
     }
     Ok(())
@@ -254,42 +486,51 @@ pub fn recovery_tree_clone(
 /// * `directory` - directorio del repositorio
 /// * `tree_hash` - Hash del tree
 /// * `objects` - Vector para guardar los objetos a enviar
+/// * `saved_hashes` - Hashes de los objetos ya guardados en `objects`
 ///
 /// # Retorna
 ///
-/// En caso de error, retorna un error de tipo GitError.
+/// En caso de error, retorna un error de tipo UtilError. Si falta el tree o alguno de sus
+/// objetos, retorna `UtilError::ObjectNotFound` con su hash.
 pub fn recovery_tree(
     directory: &str,
     tree_hash: &str,
     objects: &mut Vec<(ObjectType, Vec<u8>)>,
+    saved_hashes: &mut HashSet<String>,
 ) -> Result<(), UtilError> {
-    let tree_content = git_cat_file(directory, tree_hash, "-p")?;
+    if !object_exists(directory, tree_hash) {
+        return Err(UtilError::ObjectNotFound(tree_hash.to_string()));
+    }
+    let tree_content = git_cat_file_typed(directory, tree_hash, "-p", Some(TREE))?;
     for line in tree_content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let mode = parts[0];
-        let hash = parts[2];
-        if mode == FILE {
+        let (mode, _, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+        if saved_hashes.contains(hash) {
+            continue;
+        }
+        if !object_exists(directory, hash) {
+            return Err(UtilError::ObjectNotFound(hash.to_string()));
+        }
+        if is_blob_mode(mode) {
             let mut object_blob: (ObjectType, Vec<u8>) = (ObjectType::Blob, Vec::new());
             let blob_content = git_cat_file(directory, hash, "-p")?;
             object_blob.1 = compressor_object_content(blob_content)?;
-            save_object_pack(objects, object_blob)
+            save_object_pack(objects, saved_hashes, hash, object_blob);
         } else if mode == DIRECTORY {
             let mut object_tree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
             let path = format!("{}/{}/objects/{}", directory, GIT_DIR, &hash[..2]);
             let file_path = format!("{}/{}", path, &hash[2..]);
-            let mut decompresed = decompression_object(&file_path)?;
-            if let Some(pos) = decompresed.iter().position(|&x| x == b'\0') {
-                let tree = decompresed.split_off(pos + 1);
-                object_tree.1 = compressor_object_with_bytes_content(tree)?;
-                save_object_pack(objects, object_tree);
-            }
-            recovery_tree(directory, hash, objects)?;
+            let tree = decompression_object_content(&file_path)?;
+            object_tree.1 = compressor_object_with_bytes_content(tree)?;
+            save_object_pack(objects, saved_hashes, hash, object_tree);
+            recovery_tree(directory, hash, objects, saved_hashes)?;
         }
     }
     Ok(())
 }
 
-/// Recorre los commits recursivamente y los agrega al vector objects
+/// Recorre los commits recursivamente, siguiendo todos los parents, y agrega su hash al vector
+/// objects
 ///
 /// # Argumentos
 ///
@@ -297,6 +538,8 @@ pub fn recovery_tree(
 /// * `hash_commit` - Hash del Commit
 /// * `commit` - Contenido del commit
 /// * `objects` - Vector para guardar los objetos a enviar
+/// * `saved_hashes` - Hashes de los objetos ya guardados en `objects`
+/// * `hashes_commits` - Hashes de los parents recorridos
 ///
 /// # Retorna
 ///
@@ -305,40 +548,55 @@ pub fn recovery_commits(
     directory: &str,
     hash_commit: &str,
     commit: String,
-    objects: &mut Vec<(ObjectType, Vec<u8>)>,
+    objects: &mut Vec<(ObjectType, String)>,
+    saved_hashes: &mut HashSet<String>,
     hashes_commits: &mut Vec<String>,
 ) -> Result<(), UtilError> {
-    let mut object_commit: (ObjectType, Vec<u8>) = (ObjectType::Commit, Vec::new());
-    object_commit.1 = get_content(directory, hash_commit)?;
-    save_object_pack(objects, object_commit);
-
-    if let Some(parent_hash) = extract_parent_hash(&commit) {
-        if parent_hash != PARENT_INITIAL {
-            hashes_commits.push(parent_hash.to_string());
-            let parent_commit = git_cat_file(directory, parent_hash, "-p")?;
-            recovery_commits(
-                directory,
-                parent_hash,
-                parent_commit,
-                objects,
-                hashes_commits,
-            )?;
+    let object_commit = (ObjectType::Commit, hash_commit.to_string());
+    save_object_pack(objects, saved_hashes, hash_commit, object_commit);
+
+    for parent_hash in Commit::from_object(&commit)?.get_parents() {
+        if saved_hashes.contains(parent_hash) {
+            continue;
         }
+        hashes_commits.push(parent_hash.to_string());
+        let parent_commit = git_cat_file(directory, parent_hash, "-p")?;
+        recovery_commits(
+            directory,
+            parent_hash,
+            parent_commit,
+            objects,
+            saved_hashes,
+            hashes_commits,
+        )?;
     }
     Ok(())
 }
 
-/// Guarda el objeto recibido por parámetro en el vector de objetos, solo si el vector
-/// no contiene al mismo.
+/// Guarda el objeto recibido por parámetro en el vector de objetos, solo si no se guardó
+/// antes un objeto con el mismo hash. El vector conserva el orden en que se guardaron.
 ///
 /// # Argumentos
 ///
 /// * `objects` - vector donde se almacenan los objetos
+/// * `saved_hashes` - hashes de los objetos ya almacenados
+/// * `hash` - hash del objeto a almacenar
 /// * `object` - objeto a almacenar.
-fn save_object_pack(objects: &mut Vec<(ObjectType, Vec<u8>)>, object: (ObjectType, Vec<u8>)) {
-    if !objects.contains(&object) {
-        objects.push(object);
+///
+/// # Retorna
+///
+/// `true` si el objeto se guardó, `false` si ya estaba.
+fn save_object_pack<T>(
+    objects: &mut Vec<T>,
+    saved_hashes: &mut HashSet<String>,
+    hash: &str,
+    object: T,
+) -> bool {
+    if !saved_hashes.insert(hash.to_string()) {
+        return false;
     }
+    objects.push(object);
+    true
 }
 
 /// Extrae los objetos de un repositorio para guardar los mismos en un vector
@@ -360,6 +618,7 @@ pub fn get_objects_from_hash_to_hash(
     current_hash: &str,
 ) -> Result<Vec<(ObjectType, Vec<u8>)>, UtilError> {
     let mut objects = Vec::new();
+    let mut saved_hashes = HashSet::new();
 
     if is_ancestor(path_local, current_hash, prev_hash)? {
         let mut hash_commit: String = current_hash.to_string();
@@ -367,19 +626,16 @@ pub fn get_objects_from_hash_to_hash(
             let mut object_commit: (ObjectType, Vec<u8>) = (ObjectType::Commit, Vec::new());
             let content_commit = git_cat_file(path_local, &hash_commit, "-p")?;
             object_commit.1 = compressor_object_content(content_commit.clone())?;
-            save_object_pack(&mut objects, object_commit);
+            save_object_pack(&mut objects, &mut saved_hashes, &hash_commit, object_commit);
             let commit = git_cat_file(path_local, &hash_commit, "-p")?;
             if let Some(tree_hash) = get_tree_hash(&commit) {
                 let mut object_tree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
                 let path = format!("{}/{}/objects/{}", path_local, GIT_DIR, &tree_hash[..2]);
                 let file_path = format!("{}/{}", path, &tree_hash[2..]);
-                let mut decompresed = decompression_object(&file_path)?;
-                if let Some(pos) = decompresed.iter().position(|&x| x == b'\0') {
-                    let tree = decompresed.split_off(pos + 1);
-                    object_tree.1 = compressor_object_with_bytes_content(tree)?;
-                    save_object_pack(&mut objects, object_tree);
-                }
-                recovery_tree(path_local, tree_hash, &mut objects)?;
+                let tree = decompression_object_content(&file_path)?;
+                object_tree.1 = compressor_object_with_bytes_content(tree)?;
+                save_object_pack(&mut objects, &mut saved_hashes, tree_hash, object_tree);
+                recovery_tree(path_local, tree_hash, &mut objects, &mut saved_hashes)?;
             }
             hash_commit = get_parent_hashes(content_commit.clone());
             if hash_commit == PARENT_INITIAL {
@@ -401,18 +657,17 @@ pub fn get_objects_from_hash_to_hash(
 ///
 /// # Retorna
 ///
-/// Un vector con el contenido de los objetos si la operación es exitosa.
+/// Un vector con el tipo y el hash de los objetos si la operación es exitosa.
 /// En caso de error, retorna un error de tipo UtilError.
 pub fn get_objects_fetch_with_hash_valid(
     directory: &str,
     references: Vec<Reference>,
-    confirmed_hashes: &Vec<String>,
-) -> Result<Vec<(ObjectType, Vec<u8>)>, UtilError> {
-    let mut objects: Vec<(ObjectType, Vec<u8>)> = Vec::new();
-    println!("{:?}", confirmed_hashes);
+    confirmed_hashes: &[String],
+) -> Result<Vec<(ObjectType, String)>, UtilError> {
+    let mut objects: Vec<(ObjectType, String)> = Vec::new();
+    let mut saved_hashes: HashSet<String> = HashSet::new();
 
     if !references.is_empty() {
-        println!("{:?}", references[0].get_name());
         let commits_in_repo = get_commits(directory, references[0].get_name())?;
 
         let mut available = true;
@@ -428,19 +683,26 @@ pub fn get_objects_fetch_with_hash_valid(
             }
             available = true;
         }
-        println!("{:?}", send_hashes);
         let branches = get_branch(directory)?;
         for _branch in branches {
             for hash in send_hashes.clone() {
-                let mut object_commit: (ObjectType, Vec<u8>) = (ObjectType::Commit, Vec::new());
-                object_commit.1 = get_content(directory, &hash)?;
-                save_object_pack(&mut objects, object_commit);
+                if saved_hashes.contains(&hash) {
+                    continue;
+                }
+                let object_commit = (ObjectType::Commit, hash.to_string());
+                save_object_pack(&mut objects, &mut saved_hashes, &hash, object_commit);
                 let commit = git_cat_file(directory, &hash, "-p")?;
                 if let Some(tree_hash) = get_tree_hash(&commit) {
-                    let mut object_tree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
-                    object_tree.1 = get_content(directory, tree_hash)?;
-                    save_object_pack(&mut objects, object_tree);
-                    recovery_tree_clone(directory, tree_hash, &mut objects)?;
+                    let object_tree = (ObjectType::Tree, tree_hash.to_string());
+                    if save_object_pack(&mut objects, &mut saved_hashes, tree_hash, object_tree) {
+                        recovery_tree_clone(
+                            directory,
+                            tree_hash,
+                            &mut objects,
+                            &mut saved_hashes,
+                            false,
+                        )?;
+                    }
                 }
             }
         }
@@ -450,27 +712,35 @@ pub fn get_objects_fetch_with_hash_valid(
 }
 /// Extrae los objetos de un repositorio para guardar los mismos en un vector
 ///
+/// Solo se recorren los commits y los trees: los blobs no se leen y el contenido de los
+/// objetos se obtiene al enviarlos con `read_pack_objects`, de modo que el vector no crece
+/// con el tamaño del repositorio sino con la cantidad de objetos.
+///
 /// # Argumentos
 ///
 /// * `directory` - directorio del repositorio
 /// * `references` - Rama actual del directorio
+/// * `omit_blobs` - Si es true solo se agregan los commits, tags y trees (`filter blob:none`)
 ///
 /// # Retorna
 ///
-/// Un vector con el contenido de los objetos si la operación es exitosa.
+/// Un vector con el tipo y el hash de los objetos si la operación es exitosa.
 /// En caso de error, retorna un error de tipo UtilError.
 pub fn get_objects(
     directory: &str,
     references: &[Reference],
-) -> Result<Vec<(ObjectType, Vec<u8>)>, UtilError> {
-    let mut objects: Vec<(ObjectType, Vec<u8>)> = vec![];
+    omit_blobs: bool,
+) -> Result<Vec<(ObjectType, String)>, UtilError> {
+    let mut objects: Vec<(ObjectType, String)> = vec![];
+    let mut saved_hashes: HashSet<String> = HashSet::new();
     let mut hashes_commits: Vec<String> = vec![];
     for reference in references.iter() {
-        let parts: Vec<&str> = reference.get_ref_path().split('/').collect();
-        let branch = parts.last().map_or("", |&x| x);
-        let branch_current_path = format!("{}/{}/{}/{}", directory, GIT_DIR, REF_HEADS, branch);
-        let file_current_branch = open_file(&branch_current_path)?;
-        let hash_commit_current_branch = read_file_string(file_current_branch)?;
+        let resolved = reference.resolve(directory)?;
+        if resolved.raw != resolved.peeled {
+            let object_tag = (ObjectType::Tag, resolved.raw.to_string());
+            save_object_pack(&mut objects, &mut saved_hashes, &resolved.raw, object_tag);
+        }
+        let hash_commit_current_branch = resolved.peeled;
 
         let commit_content = git_cat_file(directory, &hash_commit_current_branch, "-p")?;
         recovery_commits(
@@ -478,30 +748,63 @@ pub fn get_objects(
             &hash_commit_current_branch,
             commit_content,
             &mut objects,
+            &mut saved_hashes,
             &mut hashes_commits,
         )?;
         let content_commit = git_cat_file(directory, &hash_commit_current_branch, "-p")?;
         if let Some(tree_hash) = get_tree_hash(&content_commit) {
-            let mut object_tree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
-            object_tree.1 = get_content(directory, tree_hash)?;
-
-            save_object_pack(&mut objects, object_tree);
-
-            recovery_tree_clone(directory, tree_hash, &mut objects)?;
+            let object_tree = (ObjectType::Tree, tree_hash.to_string());
+            if save_object_pack(&mut objects, &mut saved_hashes, tree_hash, object_tree) {
+                recovery_tree_clone(
+                    directory,
+                    tree_hash,
+                    &mut objects,
+                    &mut saved_hashes,
+                    omit_blobs,
+                )?;
+            }
         };
         for hash_commit in hashes_commits.clone() {
             let content_commit = git_cat_file(directory, &hash_commit, "-p")?;
             if let Some(tree_hash) = get_tree_hash(&content_commit) {
-                let mut object_subtree: (ObjectType, Vec<u8>) = (ObjectType::Tree, Vec::new());
-                object_subtree.1 = get_content(directory, tree_hash)?;
-                save_object_pack(&mut objects, object_subtree);
-                recovery_tree_clone(directory, tree_hash, &mut objects)?;
+                let object_subtree = (ObjectType::Tree, tree_hash.to_string());
+                if save_object_pack(&mut objects, &mut saved_hashes, tree_hash, object_subtree) {
+                    recovery_tree_clone(
+                        directory,
+                        tree_hash,
+                        &mut objects,
+                        &mut saved_hashes,
+                        omit_blobs,
+                    )?;
+                }
             };
         }
     }
     Ok(objects)
 }
 
+/// Lee, a medida que se lo piden, el contenido de los objetos obtenidos con `get_objects` o
+/// `get_objects_fetch_with_hash_valid`, para enviarlos con `send_packfile` sin tenerlos a
+/// todos en memoria.
+///
+/// # Argumentos
+///
+/// * `directory` - directorio del repositorio
+/// * `objects` - tipo y hash de los objetos a leer
+///
+/// # Retorna
+///
+/// Un iterador con el tipo y el contenido de cada objeto, o un error de tipo UtilError si no
+/// se pudo leer.
+pub fn read_pack_objects(
+    directory: &str,
+    objects: Vec<(ObjectType, String)>,
+) -> impl Iterator<Item = Result<(ObjectType, Vec<u8>), UtilError>> + '_ {
+    objects
+        .into_iter()
+        .map(move |(object_type, hash)| Ok((object_type, get_content(directory, &hash)?)))
+}
+
 /// Extrae la branch actual y el hash del ultimo commit.
 ///
 /// # Argumentos
@@ -535,7 +838,8 @@ pub fn get_ref_name(directory: &str) -> Result<Reference, UtilError> {
 ///
 /// # Retorno
 /// Un Result que contiene un vector de AdvertisedRefLine si la operación fue exitosa,
-/// o un error de UtilError en caso contrario.
+/// o un error de UtilError en caso contrario. Si el servidor respondió con una línea
+/// `ERR <mensaje>`, retorna `UtilError::RemoteError` con su mensaje.
 pub fn reference_discovery(
     stream: &mut TcpStream,
     message: String,
@@ -543,7 +847,16 @@ pub fn reference_discovery(
     my_capabilities: &[String],
 ) -> Result<GitServer, UtilError> {
     send_message(stream, &message, UtilError::ReferenceDiscovey)?;
-    let lines = pkt_line::read(stream)?;
+    // Ante un error el servidor envía solo la línea `ERR` y cierra la conexión, sin flush
+    let first_line = pkt_line::read_pkt_line(stream)?;
+    if let Some(message) = String::from_utf8_lossy(&first_line).strip_prefix(ERR_PREFIX) {
+        return Err(UtilError::RemoteError(message.trim().to_string()));
+    }
+    let mut lines = Vec::new();
+    if !first_line.is_empty() {
+        lines.push(first_line);
+        lines.extend(pkt_line::read(stream)?);
+    }
     GitServer::new(&lines, src_repo, my_capabilities)
 }
 
@@ -573,7 +886,6 @@ fn extract_references_from_path(
         if let Ok(hash) = fs::read_to_string(path) {
             let name_ref = format!("{}/{}", signature, name);
             let refs = Reference::new(hash.trim(), &name_ref)?;
-            println!("Refs: {:?}", refs);
             references.push(refs);
         }
     }
@@ -609,27 +921,7 @@ fn get_files_in_directory(directory_path: &PathBuf) -> Vec<String> {
     files
 }
 
-/// Extrae la referencia HEAD de una línea dada.
-///
-/// # Argumentos
-///
-/// * `line` - Cadena que contiene la referencia HEAD.
-///
-/// # Retorna
-///
-/// Devuelve un resultado que contiene la cadena de la referencia HEAD si la operación es exitosa.
-/// En caso de un formato inválido, retorna un error de tipo UtilError.
-///
-fn extract_reference_head(line: &str) -> Result<String, UtilError> {
-    let trimmed_line = line.trim();
-    if let Some(reference) = trimmed_line.split_once(' ').map(|x| x.1) {
-        Ok(reference.to_string())
-    } else {
-        Err(UtilError::InvalidHeadReferenceFormat)
-    }
-}
-
-/// Extrae el nombre de la referencia HEAD del archivo 'HEAD' en el directorio '.git'.
+/// Lee el archivo 'HEAD' en el directorio '.git'.
 ///
 /// # Argumentos
 ///
@@ -637,14 +929,13 @@ fn extract_reference_head(line: &str) -> Result<String, UtilError> {
 ///
 /// # Retorna
 ///
-/// Devuelve un resultado que contiene el nombre de la referencia HEAD si la operación es exitosa.
-/// En caso de que no se encuentre el archivo 'HEAD', retorna un error de tipo UtilError.
+/// Devuelve un resultado con la referencia a la que apunta HEAD o el hash si HEAD está detached.
+/// En caso de que no se encuentre el archivo 'HEAD' o su formato sea inválido, retorna un error de tipo UtilError.
 ///
-fn extract_name_head_from_path(path_git: &str) -> Result<String, UtilError> {
+fn extract_head_from_path(path_git: &str) -> Result<SymbolicRef, UtilError> {
     let path = Path::new(&path_git).join("HEAD");
     if let Ok(line) = fs::read_to_string(path) {
-        let refs = extract_reference_head(&line)?;
-        return Ok(refs);
+        return SymbolicRef::parse(&line).map_err(|_| UtilError::InvalidHeadReferenceFormat);
     }
     Err(UtilError::HeadFolderNotFound)
 }
@@ -686,7 +977,10 @@ fn extract_hash_head_from_path(
 /// En caso de fallo al extraer la referencia HEAD, retorna un error de tipo UtilError.
 ///
 fn get_reference_head(path_git: &str, refs: &Vec<Reference>) -> Result<Reference, UtilError> {
-    let mut name_head = extract_name_head_from_path(path_git)?;
+    let mut name_head = match extract_head_from_path(path_git)? {
+        SymbolicRef::Ref(name_head) => name_head,
+        SymbolicRef::Detached(hash) => return Reference::new(&hash, HEAD),
+    };
     if let Some('/') = name_head.chars().next() {
         name_head.remove(0);
     }
@@ -703,9 +997,36 @@ mod tests {
         commit::{git_commit, Commit},
         init::git_init,
     };
+    use crate::test_utils::{commit_file, test_commit};
+    use crate::util::object_format::ObjectFormat;
+    use crate::util::objects::{builder_object_blob, builder_object_tag};
 
     use super::*;
 
+    #[test]
+    fn test_reference_discovery_remote_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar");
+        let address = listener
+            .local_addr()
+            .expect("Falló al obtener la dirección");
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Falló al aceptar la conexión");
+            let line = "ERR acceso denegado\n";
+            let message = pkt_line::add_length_prefix(line, line.len());
+            stream
+                .write_all(message.as_bytes())
+                .expect("Falló al enviar el error");
+        });
+        let mut stream = TcpStream::connect(address).expect("Falló al conectar");
+        let result = reference_discovery(&mut stream, "0000".to_string(), "repo", &[]);
+        server.join().expect("Falló el servidor");
+
+        assert_eq!(
+            result.map(|_| ()),
+            Err(UtilError::RemoteError("acceso denegado".to_string()))
+        );
+    }
+
     #[test]
     fn test_create_head_reference() {
         let result = Reference::new("some_hash", "HEAD");
@@ -897,4 +1218,222 @@ mod tests {
 
         assert_eq!(objects.len(), 7)
     }
+
+    fn init_with_commit(directory: &str) -> String {
+        git_init(directory).expect("Error al ejecutar git init");
+        commit_file(directory, "holamundo.txt", "Hola Mundo")
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_objects_include_executable_blobs() {
+        use std::os::unix::fs::PermissionsExt;
+        let directory = "./test_get_objects_executable";
+        let first = init_with_commit(directory);
+        let script = format!("{}/script.sh", directory);
+        fs::write(&script, "#!/bin/sh\necho hola\n").expect("Error al escribir");
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))
+            .expect("Error al dar permisos");
+        git_add(directory, "script.sh").expect("Fallo en el comando add");
+        git_commit(directory, test_commit("script")).expect("Error commit");
+        let second = get_ref_name(directory)
+            .expect("Error ref")
+            .get_hash()
+            .to_string();
+
+        let references = vec![Reference::new(&second, "refs/heads/master").expect("Error ref")];
+        let clone_objects = get_objects(directory, &references, false).expect("Error get objects");
+        let push_objects =
+            get_objects_from_hash_to_hash(directory, &first, &second).expect("Error get objects");
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        // Dos blobs en el clone: el archivo y el script ejecutable
+        let blobs = |types: Vec<&ObjectType>| {
+            types
+                .into_iter()
+                .filter(|t| **t == ObjectType::Blob)
+                .count()
+        };
+        assert_eq!(
+            blobs(clone_objects.iter().map(|object| &object.0).collect()),
+            2
+        );
+        assert_eq!(
+            blobs(push_objects.iter().map(|object| &object.0).collect()),
+            2
+        );
+    }
+
+    #[test]
+    fn test_recovery_tree_rejects_blob() {
+        let directory = "./test_recovery_tree_blob";
+        init_with_commit(directory);
+        let blob = builder_object_blob(
+            b"Hola Mundo".to_vec(),
+            &format!("{}/.git", directory),
+            ObjectFormat::Sha1,
+        )
+        .expect("Error al crear el blob");
+
+        let mut objects = Vec::new();
+        let result = recovery_tree(directory, &blob, &mut objects, &mut HashSet::new());
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        match result {
+            Err(UtilError::UtilFromCommands(message)) => assert!(message.contains(&blob)),
+            other => panic!("Se esperaba un error de tipo, se obtuvo {:?}", other),
+        }
+        assert!(objects.is_empty());
+    }
+
+    #[test]
+    fn test_get_objects_dedup_by_hash() {
+        let directory = "./test_get_objects_dedup";
+        let first = init_with_commit(directory);
+        // Un segundo archivo con el mismo contenido reutiliza el blob del primer commit
+        fs::write(format!("{}/copia.txt", directory), "Hola Mundo").expect("Error al escribir");
+        git_add(directory, "copia.txt").expect("Fallo en el comando add");
+        git_commit(directory, test_commit("copia")).expect("Error commit");
+        let second = get_ref_name(directory)
+            .expect("Error ref")
+            .get_hash()
+            .to_string();
+        fs::write(format!("{}/.git/refs/heads/otra", directory), &second)
+            .expect("Error al escribir");
+
+        let references = vec![
+            Reference::new(&second, "refs/heads/master").expect("Error reference"),
+            Reference::new(&second, "refs/heads/otra").expect("Error reference"),
+        ];
+        let objects = get_objects(directory, &references, false).expect("Error get objects");
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        // Dos commits, dos trees y un único blob, con los commits primero
+        let types: Vec<&ObjectType> = objects.iter().map(|object| &object.0).collect();
+        assert_eq!(
+            types,
+            vec![
+                &ObjectType::Commit,
+                &ObjectType::Commit,
+                &ObjectType::Tree,
+                &ObjectType::Blob,
+                &ObjectType::Tree,
+            ]
+        );
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_resolve_symref_chain() {
+        let directory = "./test_resolve_symref_chain";
+        let commit = init_with_commit(directory);
+        fs::write(
+            format!("{}/.git/refs/heads/alias", directory),
+            "ref: refs/heads/master\n",
+        )
+        .expect("Falló al escribir la referencia");
+        fs::write(
+            format!("{}/.git/refs/heads/alias2", directory),
+            "ref: refs/heads/alias\n",
+        )
+        .expect("Falló al escribir la referencia");
+
+        let reference = Reference::new("", "refs/heads/alias2").expect("Error reference");
+        let resolved = reference.resolve(directory).expect("Error resolve");
+        let head = Reference::new("", "HEAD").expect("Error reference");
+        let head_resolved = head.resolve(directory).expect("Error resolve");
+
+        // Un ciclo de referencias simbólicas no debe resolverse
+        fs::write(
+            format!("{}/.git/refs/heads/loop", directory),
+            "ref: refs/heads/loop\n",
+        )
+        .expect("Falló al escribir la referencia");
+        let looped = Reference::new("", "refs/heads/loop").expect("Error reference");
+        let looped_result = looped.resolve(directory);
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        assert_eq!(resolved.raw, commit);
+        assert_eq!(resolved.peeled, commit);
+        assert_eq!(head_resolved.peeled, commit);
+        assert_eq!(
+            looped_result,
+            Err(UtilError::SymbolicRefTooDeep("refs/heads/loop".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_peels_annotated_tag() {
+        let directory = "./test_resolve_peels_tag";
+        let commit = init_with_commit(directory);
+        let tag_content = format!(
+            "object {}\ntype commit\ntag v1\ntagger Juan <jdr@fi.uba.ar> 0 +0000\n\nversion 1",
+            commit
+        );
+        let git_dir = format!("{}/.git", directory);
+        let tag_hash =
+            builder_object_tag(&tag_content, &git_dir, ObjectFormat::Sha1).expect("Error tag");
+        fs::write(format!("{}/.git/refs/tags/v1", directory), &tag_hash)
+            .expect("Falló al escribir la referencia");
+
+        let reference = Reference::new(&tag_hash, "refs/tags/v1").expect("Error reference");
+        let resolved = reference.resolve(directory).expect("Error resolve");
+        let objects = get_objects(directory, &[reference], false).expect("Error get objects");
+
+        fs::remove_dir_all(directory).expect("Falló al remover los directorios");
+
+        assert_eq!(resolved.raw, tag_hash);
+        assert_eq!(resolved.peeled, commit);
+        assert!(objects
+            .iter()
+            .any(|(object_type, _)| *object_type == ObjectType::Tag));
+        assert!(objects
+            .iter()
+            .any(|(object_type, _)| *object_type == ObjectType::Commit));
+    }
+
+    #[test]
+    fn test_read_packed_refs_skipped_line_drops_peeled() {
+        let path_git = "./test_read_packed_refs";
+        fs::create_dir_all(path_git).expect("Falló al crear el directorio");
+        let (master, notes, peeled, tag, tag_peeled) = (
+            "1".repeat(40),
+            "2".repeat(40),
+            "3".repeat(40),
+            "4".repeat(40),
+            "5".repeat(40),
+        );
+        fs::write(
+            format!("{}/{}", path_git, PACKED_REFS),
+            format!(
+                "# pack-refs with: peeled fully-peeled sorted \n{} refs/heads/master\n\
+                 {} refs/notes/commits\n^{}\n{} refs/tags/v1\n^{}\n",
+                master, notes, peeled, tag, tag_peeled
+            ),
+        )
+        .expect("Falló al escribir el archivo");
+
+        let packed_refs = read_packed_refs(path_git);
+
+        fs::remove_dir_all(path_git).expect("Falló al remover el directorio temporal");
+
+        // La referencia ignorada no le pasa su `^<hash>` a la anterior
+        assert_eq!(
+            packed_refs,
+            vec![
+                PackedRef {
+                    reference: Reference::new(&master, "refs/heads/master").expect("Ref"),
+                    peeled: None,
+                },
+                PackedRef {
+                    reference: Reference::new(&tag, "refs/tags/v1").expect("Ref"),
+                    peeled: Some(tag_peeled),
+                },
+            ]
+        );
+    }
 }
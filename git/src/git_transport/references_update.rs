@@ -1,15 +1,22 @@
 use std::io::Write;
 
 use crate::{
-    consts::UNPACK_OK,
+    consts::{PKT_LINE_MAX_DATA, UNPACK_OK},
     util::{
-        connections::send_message, errors::UtilError, pkt_line::add_length_prefix,
+        connections::{send_flush, send_message},
+        errors::UtilError,
+        logger::log_debug,
+        object_format::ObjectFormat,
+        pkt_line::add_length_prefix,
         validation::is_valid_obj_id,
     },
 };
 
 use super::references::Reference;
 
+/// Resultado de la actualización de una referencia: su ruta y, si fue rechazada, el motivo.
+pub type ReferenceStatus = (String, Result<(), String>);
+
 #[derive(Debug)]
 pub struct ReferencesUpdate {
     old: String,
@@ -26,19 +33,23 @@ impl ReferencesUpdate {
         }
     }
 
-    pub fn new_from_line(line: &str) -> Result<ReferencesUpdate, UtilError> {
-        println!("line: {}", line);
+    /// Interpreta una línea `<old> <new> <ref>` de un push. Los hashes se validan con el
+    /// formato de los objetos del repositorio.
+    pub fn new_from_line(
+        line: &str,
+        object_format: ObjectFormat,
+    ) -> Result<ReferencesUpdate, UtilError> {
+        log_debug(&format!("line: {}", line));
         let parts = line.split_ascii_whitespace().collect::<Vec<&str>>();
         if parts.len() != 3 {
             return Err(UtilError::InvalidReferenceUpdateRequest);
         }
-        if !is_valid_obj_id(parts[0]) || !is_valid_obj_id(parts[1]) {
+        if !is_valid_obj_id(parts[0], object_format) || !is_valid_obj_id(parts[1], object_format) {
             return Err(UtilError::InvalidObjectId);
         }
         let old = parts[0].to_string();
         let new = parts[1].to_string();
         let reference = parts[2].to_string();
-        println!("reference: {}", reference);
         if !Reference::is_valid_references_path(&reference) {
             return Err(UtilError::InvalidReferencePath);
         }
@@ -58,28 +69,138 @@ impl ReferencesUpdate {
     }
 }
 
+/// Envía al cliente el estado de la actualización de referencias (capacidad `report-status`).
+///
+/// Primero se envía `unpack ok`, luego una línea `ok <ref>` o `ng <ref> <motivo>` por cada
+/// referencia solicitada y, por último, un flush-pkt.
+///
+/// # Argumentos
+///
+/// * `writer` - Flujo de escritura hacia el cliente.
+/// * `status` - Vector con la ruta de cada referencia y el resultado de su actualización.
+///
 pub fn send_decompressed_package_status(
     writer: &mut dyn Write,
-    reference: &Vec<(String, bool)>,
+    status: &[ReferenceStatus],
 ) -> Result<(), UtilError> {
     let message = add_length_prefix(UNPACK_OK, UNPACK_OK.len());
     send_message(writer, &message, UtilError::SendStatusUpdateRequest)?;
 
-    for (reference, status) in reference {
-        let message = match status {
-            true => format!("ok {}\n", reference),
-            false => format!("ng {} non-fast-forward\n", reference),
+    for (reference, result) in status {
+        let message = match result {
+            Ok(()) => format!("ok {}\n", reference),
+            Err(reason) => {
+                // El motivo puede ser la salida de un hook: se corta para que la línea
+                // no supere el máximo de una pkt-line
+                let prefix = format!("ng {} ", reference);
+                let max_reason = PKT_LINE_MAX_DATA.saturating_sub(prefix.len() + 1);
+                format!(
+                    "{}{}\n",
+                    prefix,
+                    truncate_at_char_boundary(reason, max_reason)
+                )
+            }
         };
         let message = add_length_prefix(&message, message.len());
         send_message(writer, &message, UtilError::SendStatusUpdateRequest)?;
     }
-    Ok(())
+    send_flush(writer, UtilError::SendStatusUpdateRequest)
+}
+
+/// Devuelve el prefijo más largo del texto que no supera `max_len` bytes, sin cortar un
+/// carácter a la mitad.
+fn truncate_at_char_boundary(text: &str, max_len: usize) -> &str {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
 }
 
-pub fn send_decompression_failure_status(writer: &mut dyn Write) -> Result<(), UtilError> {
-    let signature = "Err PaqueteCorrupto\n";
-    let message = add_length_prefix(signature, signature.len());
+/// Envía al cliente el estado de un packfile que no pudo ser desempaquetado.
+///
+/// Se envía `unpack <error>` seguido de una línea `ng <ref> unpacker error` por cada
+/// referencia solicitada, ya que ninguna pudo ser actualizada, y un flush-pkt.
+///
+/// # Argumentos
+///
+/// * `writer` - Flujo de escritura hacia el cliente.
+/// * `error` - Descripción del error ocurrido al desempaquetar.
+/// * `requests` - Referencias que el cliente solicitó actualizar.
+///
+pub fn send_decompression_failure_status(
+    writer: &mut dyn Write,
+    error: &str,
+    requests: &[ReferencesUpdate],
+) -> Result<(), UtilError> {
+    let error = error.lines().next().unwrap_or("error");
+    let message = format!("unpack {}\n", error);
+    let message = add_length_prefix(&message, message.len());
     send_message(writer, &message, UtilError::SendStatusUpdateRequest)?;
-    println!("message unpack error: {:?}", message);
-    Ok(())
+
+    for request in requests {
+        let message = format!("ng {} unpacker error\n", request.get_path_refs());
+        let message = add_length_prefix(&message, message.len());
+        send_message(writer, &message, UtilError::SendStatusUpdateRequest)?;
+    }
+    send_flush(writer, UtilError::SendStatusUpdateRequest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_decompressed_package_status() {
+        let mut writer: Vec<u8> = Vec::new();
+        let status = vec![
+            ("refs/heads/master".to_string(), Ok(())),
+            (
+                "refs/heads/dev".to_string(),
+                Err("non-fast-forward".to_string()),
+            ),
+        ];
+        send_decompressed_package_status(&mut writer, &status).unwrap();
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "000eunpack ok\n0019ok refs/heads/master\n0027ng refs/heads/dev non-fast-forward\n0000"
+        );
+    }
+
+    #[test]
+    fn test_send_decompressed_package_status_long_reason() {
+        let mut writer: Vec<u8> = Vec::new();
+        let reason = format!("pre-receive hook declined: {}", "á".repeat(100_000));
+        let status = vec![("refs/heads/dev".to_string(), Err(reason.clone()))];
+        send_decompressed_package_status(&mut writer, &status).unwrap();
+
+        let output = String::from_utf8(writer).expect("La salida no es UTF-8 válido");
+        let line = &output["000eunpack ok\n".len()..output.len() - "0000".len()];
+        let length = usize::from_str_radix(&line[..4], 16).expect("Largo inválido");
+        assert_eq!(length, line.len());
+        assert!(length <= PKT_LINE_MAX_DATA + 4);
+        let sent_reason = line[4..]
+            .strip_prefix("ng refs/heads/dev ")
+            .and_then(|rest| rest.strip_suffix('\n'))
+            .expect("Línea de estado inválida");
+        assert!(!sent_reason.is_empty() && reason.starts_with(sent_reason));
+    }
+
+    #[test]
+    fn test_send_decompression_failure_status() {
+        let mut writer: Vec<u8> = Vec::new();
+        let requests = vec![ReferencesUpdate::new(
+            "0".repeat(40),
+            "1".repeat(40),
+            "refs/heads/master".to_string(),
+        )];
+        send_decompression_failure_status(&mut writer, "corrupt pack", &requests).unwrap();
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "0018unpack corrupt pack\n0028ng refs/heads/master unpacker error\n0000"
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use super::access_policy::Access;
 use crate::util::errors::UtilError;
 
 /// Enumeración `RequestCommand` representa los comandos de solicitud en un protocolo Git.
@@ -38,6 +39,15 @@ impl RequestCommand {
         }
     }
 
+    /// Obtiene el acceso al repositorio que requiere el comando: `git-receive-pack` actualiza
+    /// referencias y el resto solo lee el repositorio.
+    pub fn access(&self) -> Access {
+        match self {
+            RequestCommand::ReceivePack => Access::Write,
+            RequestCommand::UploadPack | RequestCommand::UploadArchive => Access::Read,
+        }
+    }
+
     pub fn from_string(data: &[u8]) -> Result<RequestCommand, UtilError> {
         let binding = String::from_utf8_lossy(data);
         let command = binding.trim();
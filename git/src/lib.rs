@@ -19,3 +19,6 @@ pub mod git_transport;
 pub mod git_server;
 
 pub mod servers;
+
+#[cfg(test)]
+pub mod test_utils;
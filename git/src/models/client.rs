@@ -1,3 +1,5 @@
+use crate::consts::INITIAL_BRANCH;
+
 #[derive(Clone, Debug)]
 pub struct Client {
     name: String,
@@ -7,6 +9,7 @@ pub struct Client {
     address: String,
     directory_path: String,
     path_log: String,
+    default_branch: String,
 }
 
 impl Client {
@@ -27,6 +30,7 @@ impl Client {
             address,
             directory_path,
             path_log,
+            default_branch: INITIAL_BRANCH.to_string(),
         }
     }
 
@@ -61,4 +65,13 @@ impl Client {
     pub fn get_port(&self) -> &str {
         &self.port
     }
+
+    /// Branch a la que apunta HEAD en los repositorios creados con `git init`.
+    pub fn get_default_branch(&self) -> &str {
+        &self.default_branch
+    }
+
+    pub fn set_default_branch(&mut self, default_branch: String) {
+        self.default_branch = default_branch;
+    }
 }
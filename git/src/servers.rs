@@ -1,5 +1,7 @@
 pub mod server;
 
+pub mod server_config;
+
 pub mod daemon_server;
 
 pub mod http_server;
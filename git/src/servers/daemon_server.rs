@@ -1,4 +1,5 @@
 use super::server::{process_request, receive_request};
+use super::server_config::ServerConfig;
 use crate::errors::GitError;
 use std::net::TcpStream;
 use std::sync::mpsc::Sender;
@@ -10,7 +11,7 @@ use std::sync::{Arc, Mutex};
 ///
 /// * `stream` - Un mutable de referencia a la conexión TCP del cliente.
 /// * `tx` - Un Arc de un Mutex que contiene el transmisor para enviar mensajes de registro.
-/// * `root_directory` - Una cadena que representa el directorio raíz.
+/// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
 ///
 /// # Returns
 ///
@@ -20,8 +21,8 @@ pub fn handle_client_daemon(
     stream: &mut TcpStream,
     signature: String,
     tx: &Arc<Mutex<Sender<String>>>,
-    root_directory: String,
+    config: Arc<ServerConfig>,
 ) -> Result<(), GitError> {
     let request = receive_request(stream, signature.clone(), tx.clone())?;
-    process_request(stream, tx, &signature, &request, &root_directory)
+    process_request(stream, tx, &signature, &request, config.get_root())
 }
@@ -43,6 +43,10 @@ pub enum ServerError {
     SaveMapPrFile,
     BadRequest(String),
     PrNotFoundInMap,
+    WebhookDelivery(String),
+    InvalidBody,
+    InvalidChunkedBody(String),
+    PathOutsideRoot(String),
 }
 
 fn format_error(error: &ServerError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -86,6 +90,10 @@ fn format_error(error: &ServerError, f: &mut fmt::Formatter<'_>) -> fmt::Result
         ServerError::SaveMapPrFile => write!(f, "Error al guardar el mapa de PRs."),
         ServerError::BadRequest(e) => write!(f, "Solicitud HTTP incorrecta: {}", e),
         ServerError::PrNotFoundInMap => write!(f, "No se encontró el PR en el mapa."),
+        ServerError::WebhookDelivery(e) => write!(f, "Error al enviar el webhook: {}", e),
+        ServerError::InvalidBody => write!(f, "El cuerpo de la solicitud HTTP es inválido."),
+        ServerError::InvalidChunkedBody(e) => write!(f, "Cuerpo chunked inválido: {}", e),
+        ServerError::PathOutsideRoot(e) => write!(f, "La ruta está fuera del directorio raíz del servidor: {}", e),
     }
 }
 
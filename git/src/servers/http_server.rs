@@ -22,3 +22,11 @@ pub mod pr_registry;
 pub mod method;
 
 pub mod model;
+
+pub mod git_http;
+
+pub mod auth;
+
+pub mod rate_limiter;
+
+pub mod webhook;
@@ -0,0 +1,219 @@
+use super::{method::Method, status_code::StatusCode};
+use crate::consts::{AUTHORIZATION, HTTP_CREDENTIALS_FILE, PR_FOLDER};
+use std::{collections::HashMap, fs};
+
+/// Almacén de credenciales de la API de pull requests.
+///
+/// Se lee del archivo `<src>/.pr/credentials`, que contiene una línea `usuario:token`
+/// por cada usuario habilitado. La línea `public_read=false` hace que también las
+/// solicitudes de lectura requieran autenticación; por defecto son públicas.
+/// Las líneas vacías y las que comienzan con `#` se ignoran.
+///
+#[derive(Debug, PartialEq)]
+pub struct CredentialStore {
+    tokens: HashMap<String, String>,
+    public_read: bool,
+}
+
+impl CredentialStore {
+    /// Carga el almacén de credenciales del directorio fuente del servidor.
+    ///
+    /// Si el archivo no existe el almacén queda vacío, por lo que ninguna solicitud
+    /// que modifique pull requests podrá autenticarse.
+    ///
+    /// # Argumentos
+    ///
+    /// * `src` - Directorio fuente del servidor.
+    ///
+    pub fn load(src: &str) -> Self {
+        let path = format!("{}/{}/{}", src, PR_FOLDER, HTTP_CREDENTIALS_FILE);
+        let content = fs::read_to_string(path).unwrap_or_default();
+        CredentialStore::parse(&content)
+    }
+
+    /// Interpreta el contenido del archivo de credenciales.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content` - Contenido del archivo de credenciales.
+    ///
+    pub fn parse(content: &str) -> Self {
+        let mut tokens = HashMap::new();
+        let mut public_read = true;
+        for line in content.lines().map(|line| line.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("public_read=") {
+                public_read = value.trim() != "false";
+            } else if let Some((user, token)) = line.split_once(':') {
+                tokens.insert(user.trim().to_string(), token.trim().to_string());
+            }
+        }
+        CredentialStore {
+            tokens,
+            public_read,
+        }
+    }
+
+    /// Obtiene el usuario correspondiente al encabezado `Authorization` de la solicitud.
+    ///
+    /// Se aceptan los esquemas `Bearer <token>` y `Basic <base64(usuario:token)>`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `authorization` - Valor del encabezado `Authorization`.
+    ///
+    /// # Retorno
+    ///
+    /// Retorna el nombre del usuario autenticado o `None` si las credenciales no son válidas.
+    ///
+    pub fn authenticate(&self, authorization: &str) -> Option<String> {
+        let (scheme, value) = authorization.trim().split_once(' ')?;
+        let value = value.trim();
+        if scheme.eq_ignore_ascii_case("Bearer") {
+            return self
+                .tokens
+                .iter()
+                .find(|(_, token)| token.as_str() == value)
+                .map(|(user, _)| user.to_string());
+        }
+        if scheme.eq_ignore_ascii_case("Basic") {
+            let decoded = String::from_utf8(decode_base64(value)?).ok()?;
+            let (user, token) = decoded.split_once(':')?;
+            if self.tokens.get(user)? == token {
+                return Some(user.to_string());
+            }
+        }
+        None
+    }
+}
+
+/// Valida las credenciales de una solicitud antes de despacharla.
+///
+/// Las solicitudes de lectura (`GET`) no requieren autenticación si el almacén lo permite;
+/// el resto debe incluir credenciales válidas.
+///
+/// # Argumentos
+///
+/// * `method` - Método de la solicitud.
+/// * `headers` - Encabezados de la solicitud.
+/// * `src` - Directorio fuente del servidor.
+///
+/// # Retornos
+///
+/// Retorna el usuario autenticado, `None` si la solicitud es pública y no se autenticó,
+/// o `StatusCode::Unauthorized` si las credenciales faltan o no son válidas.
+///
+pub fn authorize(
+    method: &Method,
+    headers: &HashMap<String, String>,
+    src: &str,
+) -> Result<Option<String>, StatusCode> {
+    let authorization = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(AUTHORIZATION))
+        .map(|(_, value)| value.as_str());
+    authorize_credentials(method, authorization, src)
+}
+
+/// Valida las credenciales del encabezado `Authorization` de una solicitud, con las mismas
+/// reglas que `authorize`.
+///
+/// # Argumentos
+///
+/// * `method` - Método de la solicitud, o `Method::Get` si solo lee datos.
+/// * `authorization` - Valor del encabezado `Authorization`, si la solicitud lo incluye.
+/// * `src` - Directorio fuente del servidor.
+///
+pub fn authorize_credentials(
+    method: &Method,
+    authorization: Option<&str>,
+    src: &str,
+) -> Result<Option<String>, StatusCode> {
+    let store = CredentialStore::load(src);
+    let user = authorization.and_then(|value| store.authenticate(value));
+    if user.is_some() || (*method == Method::Get && store.public_read) {
+        return Ok(user);
+    }
+    match authorization {
+        Some(_) => Err(StatusCode::Unauthorized("Invalid credentials".to_string())),
+        None => Err(StatusCode::Unauthorized(
+            "Authentication required".to_string(),
+        )),
+    }
+}
+
+/// Decodifica un string en base64 (alfabeto estándar, con o sin padding).
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> CredentialStore {
+        CredentialStore::parse("# usuarios\npublic_read=false\nalice:secreto\n")
+    }
+
+    #[test]
+    fn test_authenticate_bearer_and_basic() {
+        let store = store();
+        assert_eq!(
+            store.authenticate("Bearer secreto"),
+            Some("alice".to_string())
+        );
+        // base64("alice:secreto")
+        assert_eq!(
+            store.authenticate("Basic YWxpY2U6c2VjcmV0bw=="),
+            Some("alice".to_string())
+        );
+        assert_eq!(store.authenticate("Bearer otro"), None);
+        // base64("alice:otro")
+        assert_eq!(store.authenticate("Basic YWxpY2U6b3Rybw=="), None);
+    }
+
+    #[test]
+    fn test_authorize() {
+        let src = "./test_http_auth";
+        fs::create_dir_all(format!("{}/{}", src, PR_FOLDER)).expect("Falló al crear el directorio");
+        let mut headers = HashMap::new();
+
+        // Sin archivo de credenciales la lectura es pública y la escritura no se permite
+        assert_eq!(authorize(&Method::Get, &headers, src), Ok(None));
+        assert!(authorize(&Method::Put, &headers, src).is_err());
+
+        let path = format!("{}/{}/{}", src, PR_FOLDER, HTTP_CREDENTIALS_FILE);
+        fs::write(path, "public_read=false\nalice:secreto\n").expect("Falló al escribir");
+        assert!(authorize(&Method::Get, &headers, src).is_err());
+
+        headers.insert("authorization".to_string(), "Bearer secreto".to_string());
+        assert_eq!(
+            authorize(&Method::Put, &headers, src),
+            Ok(Some("alice".to_string()))
+        );
+
+        fs::remove_dir_all(src).expect("Falló al remover el directorio temporal");
+    }
+}
@@ -1,22 +1,39 @@
-use super::model::Model;
-use super::pr::{CommitsPr, PullRequest};
+use super::model::{CommitNode, Diff, FileDiff, MergeCheck, Model};
+use super::pr::{CommitsPr, FileStat, PullRequest};
 use super::pr_registry::{
     delete_pr_map, generate_head_base_hash, generate_pr_hash_key, pr_already_exists, read_pr_map,
     update_pr_map,
 };
 use super::utils::{
-    get_next_pr_number, save_pr_to_file, setup_pr_directory, valid_repository,
-    validate_branch_changes,
+    get_next_pr_number, is_valid_repository_name, list_repositories, save_pr_to_file,
+    setup_pr_directory, valid_repository, validate_branch_changes,
 };
+use super::webhook::dispatch_pull_request_event;
 use super::{http_body::HttpBody, status_code::StatusCode};
-use crate::commands::branch::get_branch_current_hash;
-use crate::commands::cat_file::git_cat_file;
+use crate::commands::branch::{get_branch_current_hash, get_current_branch};
+use crate::commands::cat_file::{git_cat_file, git_cat_file_typed};
 use crate::commands::checkout::get_tree_hash;
-use crate::commands::commit::get_commits;
-use crate::commands::merge::{find_commit_common_ancestor, merge_pr};
-use crate::consts::{APPLICATION_SERVER, FILE, OPEN, PR_FILE_EXTENSION, PR_FOLDER, PR_MAP_FILE};
+use crate::commands::commit::{get_commits, Commit};
+use crate::commands::config::GitConfig;
+use crate::commands::errors::CommandsError;
+use crate::commands::init::git_init_with_branch;
+use crate::commands::log::get_commits_by_date;
+use crate::commands::merge::{analyze_merge, merge_pr, PrMergeOptions};
+use crate::commands::merge_base::get_merge_bases;
+use crate::commands::show::get_commit_files;
+use crate::consts::{
+    APPLICATION_SERVER, DATE_FORMAT, INITIAL_BRANCH, MERGE_PR_TEMPLATE_KEY, OPEN,
+    PR_DIFF_MAX_LINES, PR_FILE_EXTENSION, PR_FOLDER, PR_MAP_FILE, TREE,
+};
 use crate::servers::errors::ServerError;
+use crate::servers::server_config::{resolve_repo_path, ServerConfig};
+use crate::util::diff::{count_changes, diff_hunks, is_binary};
+use crate::util::errors::UtilError;
 use crate::util::files::{file_exists, folder_exists};
+use crate::util::formats::hash_generate;
+use crate::util::logger::log_message;
+use crate::util::objects::{is_blob_mode, object_exists, parse_tree_line};
+use crate::util::validation::valid_ref_name;
 use std::collections::HashMap;
 use std::sync::{mpsc::Sender, Arc, Mutex};
 
@@ -26,28 +43,34 @@ use std::sync::{mpsc::Sender, Arc, Mutex};
 /// Si la solicitud de extracción es válida y contiene cambios, se procede a crear la solicitud y se actualiza el
 /// archivo de mapa de solicitudes de extracción.
 ///
+/// Al crearse se envía el evento `opened` a los webhooks configurados.
+///
 /// # Parámetros
 /// - `body`: El cuerpo HTTP que contiene la información de la solicitud de extracción.
 /// - `repo_name`: El nombre del repositorio al que pertenece la solicitud de extracción.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
-/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
 /// - `Ok(StatusCode::Created)`: Si la solicitud de extracción se crea correctamente.
 /// - `Ok(StatusCode::ResourceNotFound)`: Si el repositorio no existe.
-/// - `Ok(StatusCode::ValidationFailed)`: Si la solicitud de extracción ya existe en el mapa o si no contiene cambios.
+/// - `Ok(StatusCode::ValidationFailed)`: Si las ramas son la misma o no existen, si la solicitud de extracción ya existe en el mapa o si no contiene cambios.
 /// - `Ok(StatusCode::InternalError)`: Si ocurre un error al crear el cuerpo HTTP desde el archivo.
 pub fn create_pull_requests(
     body: &HttpBody,
     repo_name: &str,
-    src: &String,
-    _tx: &Arc<Mutex<Sender<String>>>,
+    src: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
     if valid_repository(repo_name, src).is_err() {
         return Ok(StatusCode::ResourceNotFound(
             "The repository does not exist.".to_string(),
         ));
     }
+    let directory = resolve_repo_path(src, repo_name)?;
+    if let Err(e) = PullRequest::from_http_body(body)?.validate(&directory) {
+        return Ok(e);
+    }
 
     let path = match setup_pr_directory(repo_name, src) {
         Ok(p) => p,
@@ -65,7 +88,6 @@ pub fn create_pull_requests(
         Err(e) => return Ok(e),
     };
 
-    let directory = format!("{}/{}", src, repo_name);
     let next_pr = get_next_pr_number(&format!("{}/.next_pr", path))?;
     let mut pr = PullRequest::from_http_body(body)?;
 
@@ -79,6 +101,7 @@ pub fn create_pull_requests(
         Err(e) => return Ok(e),
     };
     save_pr_to_file(&body, &path, next_pr)?;
+    dispatch_pull_request_event(tx, repo_name, next_pr, "opened");
 
     Ok(StatusCode::Created)
 }
@@ -94,6 +117,7 @@ pub fn create_pull_requests(
 /// # Parámetros
 /// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
+/// - `label`: Si se indica, solo se listan los pull requests que tienen esa etiqueta.
 /// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
@@ -103,11 +127,12 @@ pub fn create_pull_requests(
 ///
 pub fn list_pull_request(
     repo_name: &str,
-    src: &String,
+    src: &str,
+    label: Option<&str>,
     _tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
-    let directory = format!("{}/{}", src, repo_name);
-    let pr_repo_folder_path = format!("{}/{}/{}", src, PR_FOLDER, repo_name);
+    let directory = resolve_repo_path(src, repo_name)?;
+    let pr_repo_folder_path = resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name))?;
 
     if valid_repository(repo_name, src).is_err() {
         return Ok(StatusCode::ResourceNotFound(
@@ -133,6 +158,9 @@ pub fn list_pull_request(
         let body = HttpBody::create_from_file(APPLICATION_SERVER, &pr_path)?;
         let mut pr;
         pr = PullRequest::from_http_body(&body)?;
+        if label.is_some_and(|label| !pr.has_label(label)) {
+            continue;
+        }
         add_attributes(&directory, &body, &mut pr, *value)?;
         if pr.is_open() {
             pr_list.push(pr);
@@ -141,6 +169,80 @@ pub fn list_pull_request(
     Ok(StatusCode::Ok(Some(Model::ListPullRequest(pr_list))))
 }
 
+/// Lista los repositorios disponibles en el servidor.
+///
+/// # Parámetros
+/// - `src`: La ruta base donde se encuentran los repositorios.
+/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `Ok(StatusCode::Ok)`: Con los nombres de los repositorios, ordenados alfabéticamente.
+/// - `Err(ServerError)`: Si ocurre un error al leer el directorio base.
+///
+pub fn list_repos(
+    src: &String,
+    _tx: &Arc<Mutex<Sender<String>>>,
+) -> Result<StatusCode, ServerError> {
+    let repositories = list_repositories(src)?;
+    Ok(StatusCode::Ok(Some(Model::ListRepositories(repositories))))
+}
+
+/// Crea un repositorio en el servidor.
+///
+/// Se leen del cuerpo el nombre del repositorio (`name`) y, opcionalmente, la branch por
+/// defecto (`default_branch`, `master` si no se indica). Se inicializa el repositorio en
+/// `<root>/<name>` con HEAD apuntando a la branch por defecto. No se crean más repositorios que
+/// el máximo indicado en la configuración del servidor.
+///
+/// # Parámetros
+/// - `body`: El cuerpo HTTP con el nombre y la branch por defecto del repositorio.
+/// - `config`: La configuración del servidor, con el directorio raíz de los repositorios.
+/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `Ok(StatusCode::Created)`: Si el repositorio se creó correctamente.
+/// - `Ok(StatusCode::ValidationFailed)`: Si el nombre o la branch no son válidos, o si el
+///   repositorio ya existe, o si se alcanzó la cantidad máxima de repositorios.
+/// - `Err(ServerError)`: Si ocurre un error al inicializar el repositorio.
+///
+pub fn create_repository(
+    body: &HttpBody,
+    config: &ServerConfig,
+    _tx: &Arc<Mutex<Sender<String>>>,
+) -> Result<StatusCode, ServerError> {
+    let src = config.get_root();
+    let repo_name = match body.get_field("name") {
+        Ok(repo_name) if is_valid_repository_name(&repo_name) => repo_name,
+        _ => {
+            return Ok(StatusCode::ValidationFailed(
+                "Invalid repository name.".to_string(),
+            ))
+        }
+    };
+    let default_branch = body
+        .get_field("default_branch")
+        .unwrap_or_else(|_| INITIAL_BRANCH.to_string());
+    if valid_ref_name(&default_branch).is_err() {
+        return Ok(StatusCode::ValidationFailed(
+            "Invalid default branch name.".to_string(),
+        ));
+    }
+    let directory = resolve_repo_path(src, &repo_name)?;
+    if folder_exists(&directory) {
+        return Ok(StatusCode::ValidationFailed(
+            "The repository already exists.".to_string(),
+        ));
+    }
+    if list_repositories(src)?.len() >= config.get_max_repositories() {
+        return Ok(StatusCode::ValidationFailed(
+            "The maximum number of repositories has been reached.".to_string(),
+        ));
+    }
+
+    git_init_with_branch(&directory, &default_branch)?;
+    Ok(StatusCode::Created)
+}
+
 /// Obtiene una solicitud de extracción desde el archivo correspondiente.
 ///
 /// Esta función construye la ruta al archivo del pull request usando el nombre del repositorio
@@ -152,6 +254,8 @@ pub fn list_pull_request(
 /// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
 /// - `pull_number`: El número del pull request que se desea obtener.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
+/// - `include_stats`: Si es true y el pull request está abierto, se agregan las líneas agregadas y
+///   eliminadas de cada archivo modificado.
 /// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
@@ -162,7 +266,8 @@ pub fn list_pull_request(
 pub fn get_pull_request(
     repo_name: &str,
     pull_number: &str,
-    src: &String,
+    src: &str,
+    include_stats: bool,
     _tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
     if valid_repository(repo_name, src).is_err() {
@@ -170,14 +275,14 @@ pub fn get_pull_request(
             "The repository does not exist.".to_string(),
         ));
     }
-    let file_path: String = get_pull_request_file_path(repo_name, pull_number, src);
+    let file_path: String = get_pull_request_file_path(repo_name, pull_number, src)?;
     if !file_exists(&file_path) {
         return Ok(StatusCode::ResourceNotFound(
             "The pull request does not exist.".to_string(),
         ));
     }
     let body = HttpBody::create_from_file(APPLICATION_SERVER, &file_path)?;
-    let directory = format!("{}/{}", src, repo_name);
+    let directory = resolve_repo_path(src, repo_name)?;
     let mut pr = PullRequest::from_http_body(&body)?;
     let commits = body.get_array_field("commits")?;
     let files = body.get_array_field("changed_files")?;
@@ -189,6 +294,12 @@ pub fn get_pull_request(
         pr.set_commits(commits.clone());
         pr.set_amount_commits(commits.len());
         pr.set_changed_files(files);
+    } else if include_stats {
+        let (head, base, _owner, _title) = match extract_pr_fields(&body) {
+            Ok(fields) => fields,
+            Err(e) => return Ok(e),
+        };
+        pr.set_file_stats(get_file_stats_pr(&directory, &base, &head)?);
     }
     Ok(StatusCode::Ok(Some(Model::ListPullRequest(Vec::from([
         pr,
@@ -215,11 +326,11 @@ pub fn get_pull_request(
 pub fn list_commits(
     repo_name: &str,
     pull_number: &str,
-    src: &String,
-    _tx: &Arc<Mutex<Sender<String>>>,
+    src: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
-    let directory = format!("{}/{}", src, repo_name);
-    let file_path = get_pull_request_file_path(repo_name, pull_number, src);
+    let directory = resolve_repo_path(src, repo_name)?;
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
     if !file_exists(&file_path) {
         return Ok(StatusCode::ResourceNotFound(
             "The pull request does not exist.".to_string(),
@@ -227,9 +338,9 @@ pub fn list_commits(
     }
     let body = HttpBody::create_from_file(APPLICATION_SERVER, &file_path)?;
 
-    let mut commits = get_body_commits_pr(body.clone(), src, repo_name)?;
+    let mut commits = get_body_commits_pr(body.clone(), src, repo_name, tx)?;
     if body.get_field("state")? != OPEN {
-        commits = build_commits(&directory, body.get_array_field("commits")?)?;
+        commits = build_commits(&directory, body.get_array_field("commits")?, tx)?;
     }
     Ok(StatusCode::Ok(Some(Model::ListCommits(commits))))
 }
@@ -240,24 +351,28 @@ pub fn list_commits(
 /// Si el pull request es mergeable, se realiza el merge y se actualiza el estado de la solicitud a "closed".
 /// Finalmente, se actualiza el archivo de la solicitud de extracción y se elimina la entrada del mapa de solicitudes.
 ///
+/// Luego del merge se envía el evento `merged` a los webhooks configurados.
+///
 /// # Parámetros
 /// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
 /// - `pull_number`: El número del pull request que se desea fusionar.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
-/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+/// - `user`: El usuario autenticado que realiza el merge, utilizado como autor del commit.
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
 /// - `Ok(StatusCode::MergeWasSuccessful)`: Si el merge se realiza correctamente.
 /// - `Ok(StatusCode::ResourceNotFound)`: Si el repositorio o la solicitud de extracción no existen.
 /// - `Ok(StatusCode::InternalError)`: Si ocurre un error al leer el archivo de la solicitud de extracción o al actualizar el mapa de solicitudes.
-/// - `Ok(StatusCode::Conflict)`: Si el pull request no es mergeable.
+/// - `Ok(StatusCode::Conflict)`: Si el pull request no es mergeable, con `mergeable: false` y la lista de archivos en conflicto.
 pub fn merge_pull_request(
     repo_name: &str,
     pull_number: &str,
-    src: &String,
-    _tx: &Arc<Mutex<Sender<String>>>,
+    src: &str,
+    user: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
-    let file_path = get_pull_request_file_path(repo_name, pull_number, src);
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
     if !file_exists(&file_path) {
         return Ok(StatusCode::ResourceNotFound(
             "The pull request does not exist.".to_string(),
@@ -270,8 +385,8 @@ pub fn merge_pull_request(
             "This pull request is closed".to_string(),
         ));
     }
-    let directory = format!("{}/{}", src, repo_name);
-    let (head, base, owner, title) = match extract_pr_fields(&body) {
+    let directory = resolve_repo_path(src, repo_name)?;
+    let (head, base, _owner, title) = match extract_pr_fields(&body) {
         Ok(fields) => fields,
         Err(e) => return Ok(e),
     };
@@ -280,22 +395,26 @@ pub fn merge_pull_request(
     if let Err(e) = update_pr_attributes(&directory, &body, &mut pr, pull_number) {
         return Ok(e);
     }
-    let result_merge_pr = merge_pr(
-        &directory,
-        &base,
-        &head,
-        &owner,
-        &title,
-        pull_number,
+    // La plantilla del mensaje del merge commit se puede configurar en `merge.prTemplate`
+    let template = GitConfig::new_from_file(&directory)
+        .ok()
+        .and_then(|config| {
+            config
+                .get_value("merge", MERGE_PR_TEMPLATE_KEY)
+                .map(str::to_string)
+        });
+    let options = PrMergeOptions {
+        base_branch: &base,
+        head_branch: &head,
+        owner: user,
+        title: &title,
+        number: pull_number,
         repo_name,
-    )?;
-    if result_merge_pr.contains("Conflict") {
-        let lines = result_merge_pr.split('\n').collect::<Vec<&str>>();
-        match lines.len() {
-            0 => return Ok(StatusCode::Conflict("".to_string())),
-            1 => return Ok(StatusCode::Conflict(lines[0].to_string())),
-            _ => return Ok(StatusCode::Conflict(lines[lines.len() - 2].to_string())),
-        }
+        template: template.as_deref(),
+    };
+    let conflicts = merge_pr(&directory, &options)?;
+    if !conflicts.is_empty() {
+        return Ok(StatusCode::Conflict(Model::MergeConflict(conflicts)));
     }
 
     pr.change_state("closed");
@@ -312,14 +431,208 @@ pub fn merge_pull_request(
 
     if let Err(e) = delete_pr_in_map(
         &updated_body_http,
-        &format!("{}/{}/{}", src, PR_FOLDER, repo_name),
+        &resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name))?,
     ) {
         return Ok(e);
     };
+    if let Ok(number) = pull_number.parse::<usize>() {
+        dispatch_pull_request_event(tx, repo_name, number, "merged");
+    }
 
     Ok(StatusCode::MergeWasSuccessful)
 }
 
+/// Analiza si un pull request se puede mergear sin realizar el merge.
+///
+/// Se combina la verificación de `is_mergeable` con la detección de conflictos del merge de tres
+/// vías. No se crea el merge commit ni se modifican las referencias, el index ni el working
+/// directory del repositorio.
+///
+/// # Parámetros
+/// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
+/// - `pull_number`: El número del pull request a analizar.
+/// - `src`: La ruta base donde se encuentran los archivos del pull request.
+/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `Ok(StatusCode::Ok)`: Con `mergeable`, la lista de archivos en conflicto y la estrategia
+///   (`fast-forward`, `three-way` o `up-to-date`).
+/// - `Ok(StatusCode::ResourceNotFound)`: Si el repositorio o la solicitud de extracción no existen.
+/// - `Ok(StatusCode::InternalError)`: Si el pull request está cerrado o le faltan campos.
+pub fn check_merge_pull_request(
+    repo_name: &str,
+    pull_number: &str,
+    src: &str,
+    _tx: &Arc<Mutex<Sender<String>>>,
+) -> Result<StatusCode, ServerError> {
+    if valid_repository(repo_name, src).is_err() {
+        return Ok(StatusCode::ResourceNotFound(
+            "The repository does not exist.".to_string(),
+        ));
+    }
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
+    if !file_exists(&file_path) {
+        return Ok(StatusCode::ResourceNotFound(
+            "The pull request does not exist.".to_string(),
+        ));
+    }
+    let body = HttpBody::create_from_file(APPLICATION_SERVER, &file_path)?;
+    if body.get_field("state")? != OPEN {
+        return Ok(StatusCode::InternalError(
+            "This pull request is closed".to_string(),
+        ));
+    }
+    let (head, base, _owner, _title) = match extract_pr_fields(&body) {
+        Ok(fields) => fields,
+        Err(e) => return Ok(e),
+    };
+    let directory = resolve_repo_path(src, repo_name)?;
+
+    let (strategy, conflicts) = analyze_merge(&directory, &base, &head)?;
+    let mergeable = conflicts.is_empty() && is_mergeable(&directory, &base, &head)?;
+    let strategy = match strategy.as_str() {
+        "Fast Forward" => "fast-forward",
+        "Three Way" => "three-way",
+        _ => "up-to-date",
+    };
+    Ok(StatusCode::Ok(Some(Model::MergeCheck(MergeCheck {
+        mergeable,
+        conflicts,
+        strategy: strategy.to_string(),
+    }))))
+}
+
+/// Obtiene el diff de cada archivo modificado por un pull request abierto.
+///
+/// Se compara la versión de cada archivo en la rama base con su versión en la rama head. Los
+/// archivos binarios se marcan sin hunks y, si el diff supera `PR_DIFF_MAX_LINES` líneas, los
+/// hunks restantes se omiten y se marca como truncado.
+///
+/// # Parámetros
+/// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
+/// - `pull_number`: El número del pull request.
+/// - `src`: La ruta base donde se encuentran los archivos del pull request.
+/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `Ok(StatusCode::Ok)`: Con el diff de cada archivo modificado.
+/// - `Ok(StatusCode::ResourceNotFound)`: Si el repositorio o la solicitud de extracción no existen.
+/// - `Ok(StatusCode::InternalError)`: Si el pull request está cerrado o le faltan campos.
+pub fn get_files_pull_request(
+    repo_name: &str,
+    pull_number: &str,
+    src: &str,
+    _tx: &Arc<Mutex<Sender<String>>>,
+) -> Result<StatusCode, ServerError> {
+    if valid_repository(repo_name, src).is_err() {
+        return Ok(StatusCode::ResourceNotFound(
+            "The repository does not exist.".to_string(),
+        ));
+    }
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
+    if !file_exists(&file_path) {
+        return Ok(StatusCode::ResourceNotFound(
+            "The pull request does not exist.".to_string(),
+        ));
+    }
+    let body = HttpBody::create_from_file(APPLICATION_SERVER, &file_path)?;
+    if body.get_field("state")? != OPEN {
+        return Ok(StatusCode::InternalError(
+            "This pull request is closed".to_string(),
+        ));
+    }
+    let (head, base, _owner, _title) = match extract_pr_fields(&body) {
+        Ok(fields) => fields,
+        Err(e) => return Ok(e),
+    };
+    let directory = resolve_repo_path(src, repo_name)?;
+    let diff = get_file_diffs_pr(&directory, &base, &head, PR_DIFF_MAX_LINES)?;
+    Ok(StatusCode::Ok(Some(Model::Diff(diff))))
+}
+
+/// Obtiene el grafo de commits de una branch de un repositorio.
+///
+/// Se recorren todos los parents de cada commit, por lo que también se incluyen las branches
+/// mergeadas. Los commits se ordenan del más reciente al más antiguo, cada uno indica todos sus
+/// parents y el recorrido se detiene al alcanzar el límite.
+///
+/// # Parámetros
+/// - `repo_name`: El nombre del repositorio.
+/// - `branch`: La branch desde la que se recorre la historia; si no se indica se usa la actual.
+/// - `limit`: La cantidad máxima de commits a devolver; si no se indica se devuelven todos.
+/// - `src`: La ruta base donde se encuentran los repositorios.
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `Ok(StatusCode::Ok)`: Con el grafo de commits.
+/// - `Ok(StatusCode::ResourceNotFound)`: Si el repositorio o la branch no existen.
+/// - `Ok(StatusCode::BadRequest)`: Si el límite no es un número positivo.
+/// - `Err(ServerError)`: Si ocurre un error al leer los commits.
+///
+pub fn get_commit_graph(
+    repo_name: &str,
+    branch: Option<&str>,
+    limit: Option<&str>,
+    src: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
+) -> Result<StatusCode, ServerError> {
+    if valid_repository(repo_name, src).is_err() {
+        return Ok(StatusCode::ResourceNotFound(
+            "The repository does not exist.".to_string(),
+        ));
+    }
+    let limit = match limit.map(|limit| limit.parse::<usize>()) {
+        None => usize::MAX,
+        Some(Ok(limit)) if limit > 0 => limit,
+        Some(_) => return Ok(StatusCode::BadRequest("Invalid limit.".to_string())),
+    };
+    let directory = resolve_repo_path(src, repo_name)?;
+    let branch = match branch {
+        Some(branch) => branch.to_string(),
+        None => get_current_branch(&directory)?,
+    };
+    let head = match valid_ref_name(&branch) {
+        Ok(()) => get_branch_current_hash(&directory, branch).ok(),
+        Err(_) => None,
+    };
+    let head = match head {
+        Some(head) => head.trim().to_string(),
+        None => {
+            return Ok(StatusCode::ResourceNotFound(
+                "The branch does not exist.".to_string(),
+            ))
+        }
+    };
+    let commits = get_commits_by_date(&directory, &head, limit)?
+        .into_iter()
+        .map(|(hash, content)| build_commit_node(&hash, &content, tx))
+        .collect();
+    Ok(StatusCode::Ok(Some(Model::CommitGraph(commits))))
+}
+
+/// Construye un commit del grafo a partir de su contenido, con el mismo autor, fecha y
+/// título que `build_commit_pr`, y con todos sus parents.
+///
+/// # Parámetros
+/// - `hash`: hash del commit
+/// - `content`: contenido del objeto commit
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+fn build_commit_node(hash: &str, content: &str, tx: &Arc<Mutex<Sender<String>>>) -> CommitNode {
+    let commit_pr = build_commit_pr(hash, content, tx);
+    let parents = match Commit::from_object(content) {
+        Ok(commit) => commit.get_parents().to_vec(),
+        Err(_) => Vec::new(),
+    };
+    CommitNode {
+        hash: hash.to_string(),
+        parents,
+        author: commit_pr.author_name,
+        date: commit_pr.date,
+        summary: commit_pr.message,
+    }
+}
+
 /// Extrae los campos "head", "base", "owner" y "title" del cuerpo de la solicitud de extracción.
 ///
 /// # Parámetros
@@ -366,7 +679,7 @@ pub fn modify_pull_request(
     body: &HttpBody,
     repo_name: &str,
     pull_number: &str,
-    src: &String,
+    src: &str,
     _tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
     let mut pr = match read_and_validate_pull_request(repo_name, pull_number, src) {
@@ -389,11 +702,11 @@ pub fn modify_pull_request(
     };
     let body = HttpBody::create_from_pr(&pr, APPLICATION_SERVER)?;
 
-    let directory = format!("{}/{}", src, repo_name);
+    let directory = resolve_repo_path(src, repo_name)?;
     add_attributes(&directory, &body, &mut pr, n_pull_number)?;
     let body = HttpBody::create_from_pr(&pr, APPLICATION_SERVER)?;
 
-    let file_path = get_pull_request_file_path(repo_name, pull_number, src);
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
     body.save_body_to_file(&file_path, APPLICATION_SERVER)?;
     Ok(StatusCode::Ok(None))
 }
@@ -403,11 +716,13 @@ pub fn modify_pull_request(
 /// Esta función elimina una solicitud de extracción, cerrándola si está abierta y
 /// actualizando el archivo de mapa de solicitudes de extracción para reflejar el cambio.
 ///
+/// Al cerrarse se envía el evento `closed` a los webhooks configurados.
+///
 /// # Parámetros
 /// - `repo_name`: El nombre del repositorio al que pertenece la solicitud de extracción.
 /// - `pull_number`: El número de la solicitud de extracción que se desea eliminar.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
-/// - `_tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
 /// - `Ok(StatusCode::Ok(None))`: Si la solicitud de extracción se elimina correctamente.
@@ -417,10 +732,10 @@ pub fn modify_pull_request(
 pub fn delete_pull_request(
     repo_name: &str,
     pull_number: &str,
-    src: &String,
-    _tx: &Arc<Mutex<Sender<String>>>,
+    src: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<StatusCode, ServerError> {
-    let directory = format!("{}/{}", src, repo_name);
+    let directory = resolve_repo_path(src, repo_name)?;
     let mut pr = match read_and_validate_pull_request(repo_name, pull_number, src) {
         Ok(pr) => pr,
         Err(e) => return Ok(e),
@@ -433,13 +748,16 @@ pub fn delete_pull_request(
         return Ok(e);
     }
     let body = HttpBody::create_from_pr(&pr, APPLICATION_SERVER)?;
-    let path = format!("{}/{}/{}", src, PR_FOLDER, repo_name);
+    let path = resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name))?;
     match delete_pr_in_map(&body, &path) {
         Ok(_) => {}
         Err(e) => return Ok(e),
     };
-    let file_path = get_pull_request_file_path(repo_name, pull_number, src);
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
     body.save_body_to_file(&file_path, APPLICATION_SERVER)?;
+    if let Ok(number) = pull_number.parse::<usize>() {
+        dispatch_pull_request_event(tx, repo_name, number, "closed");
+    }
 
     Ok(StatusCode::Ok(None))
 }
@@ -448,7 +766,7 @@ fn update_pr_from_http_body(
     repo_name: &str,
     pr: &mut PullRequest,
     body: &HttpBody,
-    src: &String,
+    src: &str,
 ) -> Result<(), StatusCode> {
     if let Ok(title) = body.get_field("title") {
         pr.change_title(&title)
@@ -458,6 +776,14 @@ fn update_pr_from_http_body(
         pr.change_body(&body)
     }
 
+    if let Ok(labels) = body.get_array_field("labels") {
+        pr.set_labels(labels)
+    }
+
+    if let Ok(assignees) = body.get_array_field("assignees") {
+        pr.set_assignees(assignees)
+    }
+
     // match body.get_field("state") {
     //     Ok(state) => pr.change_state(state),
     //     Err(_) => {},
@@ -473,7 +799,7 @@ fn update_pr_from_http_body(
 pub fn change_base_in_pr(
     repo_name: &str,
     pr: &mut PullRequest,
-    src: &String,
+    src: &str,
     body: &HttpBody,
     new_base: String,
 ) -> Result<(), StatusCode> {
@@ -483,6 +809,9 @@ pub fn change_base_in_pr(
         ));
     }
 
+    if let Err(e) = valid_ref_name(&new_base) {
+        return Err(StatusCode::ValidationFailed(e.to_string()));
+    }
     let head = body.get_field("head")?;
     let validate = match validate_branch_changes(repo_name, src, &new_base, &head) {
         Ok(v) => v,
@@ -493,7 +822,7 @@ pub fn change_base_in_pr(
             "No changes between branches".to_string(),
         ));
     }
-    let pr_repo_folder_path = format!("{}/{}/{}", src, PR_FOLDER, repo_name);
+    let pr_repo_folder_path = resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name))?;
     let pr_map_path = format!("{}/{}", pr_repo_folder_path, PR_MAP_FILE);
 
     let mut pr_map = read_pr_map(&pr_map_path)?;
@@ -684,7 +1013,7 @@ fn delete_pr_in_map(body: &HttpBody, path: &str) -> Result<(), StatusCode> {
 pub fn read_and_validate_pull_request(
     repo_name: &str,
     pull_number: &str,
-    src: &String,
+    src: &str,
 ) -> Result<PullRequest, StatusCode> {
     if valid_repository(repo_name, src).is_err() {
         return Err(StatusCode::ResourceNotFound(
@@ -693,7 +1022,7 @@ pub fn read_and_validate_pull_request(
     };
 
     // Construir la ruta del archivo de la solicitud de extracción
-    let file_path = get_pull_request_file_path(repo_name, pull_number, src);
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src)?;
     if !file_exists(&file_path) {
         return Err(StatusCode::ResourceNotFound(
             "La solicitud de extracción no existe.".to_string(),
@@ -732,12 +1061,18 @@ pub fn read_and_validate_pull_request(
 ///
 /// # Retorna
 ///
-/// Una `String` que contiene la ruta completa al archivo de la solicitud de extracción.
-fn get_pull_request_file_path(repo_name: &str, pull_number: &str, src: &String) -> String {
-    format!(
-        "{}/{}/{}/{}{}",
-        src, PR_FOLDER, repo_name, pull_number, PR_FILE_EXTENSION
-    )
+/// Una `String` que contiene la ruta completa al archivo de la solicitud de extracción, o
+/// `ServerError::PathOutsideRoot` si la ruta sale del directorio de origen.
+fn get_pull_request_file_path(
+    repo_name: &str,
+    pull_number: &str,
+    src: &str,
+) -> Result<String, ServerError> {
+    let file = format!(
+        "{}/{}/{}{}",
+        PR_FOLDER, repo_name, pull_number, PR_FILE_EXTENSION
+    );
+    resolve_repo_path(src, &file)
 }
 
 /// Obtiene los commits dado el cuerpo del pull request
@@ -746,6 +1081,7 @@ fn get_pull_request_file_path(repo_name: &str, pull_number: &str, src: &String)
 /// - `body`: Cuerpo del pr
 /// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
 /// - `src`: La ruta base donde se encuentran los archivos del pull request.
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
 /// - `Ok(Vec)`: Si se creo correctamente el formato del commit.
@@ -754,12 +1090,13 @@ fn get_body_commits_pr(
     body: HttpBody,
     src: &str,
     repo_name: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<Vec<CommitsPr>, ServerError> {
     let head = body.get_field("head")?;
     let base = body.get_field("base")?;
-    let directory = format!("{}/{}", src, repo_name);
+    let directory = resolve_repo_path(src, repo_name)?;
     let commits_head = get_commits_pr(&directory, &base, &head)?;
-    let result = build_commits(&directory, commits_head)?;
+    let result = build_commits(&directory, commits_head, tx)?;
     Ok(result)
 }
 
@@ -768,6 +1105,7 @@ fn get_body_commits_pr(
 /// # Parámetros
 /// - `directory`: ruta del repositorio
 /// - `commits_head`: commits a construir el formato
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
 ///
 /// # Retornos
 /// - `Ok(Vec<CommitsPr>)`: Si se creo correctamente el formato del commit.
@@ -775,42 +1113,78 @@ fn get_body_commits_pr(
 fn build_commits(
     directory: &str,
     commits_head: Vec<String>,
+    tx: &Arc<Mutex<Sender<String>>>,
 ) -> Result<Vec<CommitsPr>, ServerError> {
     let mut result = vec![];
     for commit in commits_head {
-        let mut commits_pr = CommitsPr::new();
         let commit_content = git_cat_file(directory, &commit, "-p")?;
-        commits_pr.sha_1.clone_from(&commit);
-        let mut lines_commit = commit_content.lines();
-        for line in lines_commit.by_ref() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                if line.starts_with("author") {
-                    commits_pr.author_name = parts[1].to_string();
-                    commits_pr.author_email = parts[2].to_string();
-                    let timestamp: i64 = parts[3].parse().unwrap_or(0);
-                    commits_pr.date = chrono::DateTime::from_timestamp(timestamp, 0)
-                        .unwrap()
-                        .to_string();
-                } else if line.starts_with("committer") {
-                    commits_pr.committer_name = parts[1].to_string();
-                    commits_pr.committer_email = parts[2].to_string();
-                }
-            }
-            if parts.len() >= 2 {
-                if line.starts_with("tree") {
-                    commits_pr.tree_hash = parts[1].to_string();
-                } else if line.starts_with("parent") {
-                    commits_pr.parent = parts[1].to_string();
-                }
-            }
-            commits_pr.message = line.to_string();
-        }
-        result.push(commits_pr);
+        result.push(build_commit_pr(&commit, &commit_content, tx));
     }
     Ok(result)
 }
 
+/// Construye el formato de un commit a partir de su contenido.
+///
+/// Como mensaje se toma el título del commit y como fecha la del autor en su zona horaria. Si
+/// el timestamp del autor no es válido la fecha queda como `unknown` y se registra una
+/// advertencia en el log.
+///
+/// # Parámetros
+/// - `commit`: hash del commit
+/// - `commit_content`: contenido del objeto commit
+/// - `tx`: Un canal de transmisión (`Sender<String>`) usado para comunicación con el archivo de log.
+///
+/// # Retornos
+/// - `CommitsPr`: El formato del commit.
+///
+fn build_commit_pr(
+    commit: &str,
+    commit_content: &str,
+    tx: &Arc<Mutex<Sender<String>>>,
+) -> CommitsPr {
+    let mut commits_pr = CommitsPr::new();
+    commits_pr.sha_1 = commit.to_string();
+    let parsed = match Commit::from_object(commit_content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            log_message(
+                tx,
+                &format!("Advertencia: commit {} inválido: {}", commit, e),
+            );
+            return commits_pr;
+        }
+    };
+    commits_pr.tree_hash = parsed.get_tree().to_string();
+    if let Some(parent) = parsed.get_parents().first() {
+        commits_pr.parent = parent.to_string();
+    }
+    commits_pr.author_name = parsed.get_author_name();
+    commits_pr.author_email = format!("<{}>", parsed.get_author_email());
+    commits_pr.committer_name = parsed.get_committer_name();
+    commits_pr.committer_email = format!("<{}>", parsed.get_committer_email());
+    commits_pr.date = match parsed.get_author_datetime() {
+        Some(date) => date.format(DATE_FORMAT).to_string(),
+        None => {
+            log_message(
+                tx,
+                &format!(
+                    "Advertencia: timestamp inválido en el commit {}: {}",
+                    commit,
+                    parsed.get_author_date()
+                ),
+            );
+            "unknown".to_string()
+        }
+    };
+    commits_pr.message = parsed
+        .get_message()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    commits_pr
+}
+
 /// Función que recibe 2 branches, compara sus commits y envía los commits que
 /// no estan contenidos en la branch target en un vector.
 ///
@@ -853,24 +1227,23 @@ fn recovery_tree_pr(
     tree_hash_head: &str,
     path: &str,
 ) -> Result<(), ServerError> {
-    let content_tree_head = git_cat_file(directory, tree_hash_head, "-p")?;
-    for line in content_tree_head.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() == 3 {
-            if parts[0] == FILE {
-                let path_complete = format!("{}{}", path, parts[1]);
-                pr_files_map.insert(parts[2].to_string(), path_complete);
-            } else {
-                let path_complete = format!("{}{}/", path, parts[1]);
-                recovery_tree_pr(directory, pr_files_map, parts[2], &path_complete)?;
-            }
+    let content_tree_head = git_cat_file_typed(directory, tree_hash_head, "-p", Some(TREE))?;
+    for line in content_tree_head.lines().filter(|line| !line.is_empty()) {
+        let (mode, name, hash) =
+            parse_tree_line(line).ok_or(CommandsError::MalformedTreeEntry(line.to_string()))?;
+        if is_blob_mode(mode) {
+            let path_complete = format!("{}{}", path, name);
+            pr_files_map.insert(hash.to_string(), path_complete);
+        } else {
+            let path_complete = format!("{}{}/", path, name);
+            recovery_tree_pr(directory, pr_files_map, hash, &path_complete)?;
         }
     }
     Ok(())
 }
 
-/// Función que recibe 2 branches, compara los archivos que fueron modificados en las
-/// diferencias entre sus commits y envía los nombres de los mismos en un vector.
+/// Función que recibe 2 branches y devuelve los paths de los archivos que cambian en la rama
+/// head desde su ancestro común con la rama base, incluidos los eliminados.
 ///
 /// # Argumentos
 ///
@@ -879,43 +1252,177 @@ fn recovery_tree_pr(
 /// * `head` - branch origen.
 ///
 /// # Retornos
-/// Devuelve `Ok(result)` El vector con los nombres de los archivos modificados.
-/// Devuelve `Err( )`
+/// Devuelve `Ok(result)` El vector con los nombres de los archivos modificados, ordenados por path.
+/// Devuelve `Err(ServerError)` si no se pueden leer los commits de las ramas.
 fn get_changed_files_pr(
     directory: &str,
     base: &str,
     head: &str,
 ) -> Result<Vec<String>, ServerError> {
-    let mut result = vec![];
-    let mut pr_files_map_head: HashMap<String, String> = HashMap::new();
-    let mut pr_files_map_base: HashMap<String, String> = HashMap::new();
-    let head_current_commit = get_branch_current_hash(directory, head.to_string())?;
-    let base_current_commit = get_branch_current_hash(directory, base.to_string())?;
-    let content_commit_head = git_cat_file(directory, &head_current_commit, "-p")?;
-    if let Some(tree_hash_head) = get_tree_hash(&content_commit_head) {
-        let mut path = "";
-        recovery_tree_pr(directory, &mut pr_files_map_head, tree_hash_head, path)?;
-        let content_commit_base = git_cat_file(directory, &base_current_commit, "-p")?;
-        if let Some(tree_hash_base) = get_tree_hash(&content_commit_base) {
-            path = "";
-            recovery_tree_pr(directory, &mut pr_files_map_base, tree_hash_base, path)?;
-        }
+    Ok(get_file_changes_pr(directory, base, head)?
+        .into_iter()
+        .map(|change| change.path)
+        .collect())
+}
+
+/// Archivo que cambia en la rama head desde su ancestro común con la rama base.
+///
+/// - `path`: ruta del archivo.
+/// - `old_blob`: blob en el ancestro común, o `None` si el archivo es nuevo.
+/// - `new_blob`: blob en la rama head, o `None` si el archivo se eliminó.
+struct FileChange {
+    path: String,
+    old_blob: Option<String>,
+    new_blob: Option<String>,
+}
+
+impl FileChange {
+    /// Lee el contenido del archivo en el ancestro común y en la rama head; el lado en el que
+    /// el archivo no existe queda vacío.
+    fn read_contents(&self, directory: &str) -> Result<(String, String), ServerError> {
+        let read_blob = |blob: &Option<String>| match blob {
+            Some(blob) => git_cat_file(directory, blob, "-p"),
+            None => Ok(String::new()),
+        };
+        Ok((read_blob(&self.old_blob)?, read_blob(&self.new_blob)?))
     }
-    // Archivos nuevos
-    for file in pr_files_map_head.clone().into_iter() {
-        if !pr_files_map_base.contains_key(&file.0) {
-            result.push(file.1);
-        }
+}
+
+/// Obtiene los archivos que cambian en la rama head desde su ancestro común con la rama base,
+/// como `git diff base...head`. Así los commits que solo están en la base no se cuentan como
+/// cambios del pull request. Si las ramas no tienen historia en común se compara directamente
+/// contra la base.
+///
+/// # Argumentos
+///
+/// * `directory` - Ruta del repositorio del pull request.
+/// * `base` - branch target.
+/// * `head` - branch origen.
+///
+/// # Retornos
+/// Devuelve `Ok(result)` con los archivos que cambian, ordenados por path.
+/// Devuelve `Err(ServerError)` si no se pueden leer los commits de las ramas.
+fn get_file_changes_pr(
+    directory: &str,
+    base: &str,
+    head: &str,
+) -> Result<Vec<FileChange>, ServerError> {
+    let base_commit = get_branch_current_hash(directory, base.to_string())?;
+    let head_commit = get_branch_current_hash(directory, head.to_string())?;
+    let merge_base = get_merge_bases(directory, &base_commit, &head_commit)?
+        .into_iter()
+        .next()
+        .unwrap_or(base_commit);
+    let base_files = get_commit_files(directory, &git_cat_file(directory, &merge_base, "-p")?)?;
+    let head_files = get_commit_files(directory, &git_cat_file(directory, &head_commit, "-p")?)?;
+
+    let mut paths: Vec<&String> = base_files.keys().chain(head_files.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    Ok(paths
+        .into_iter()
+        .filter(|path| base_files.get(*path) != head_files.get(*path))
+        .map(|path| FileChange {
+            path: path.to_string(),
+            old_blob: base_files.get(path).cloned(),
+            new_blob: head_files.get(path).cloned(),
+        })
+        .collect())
+}
+
+/// Calcula las líneas agregadas y eliminadas de cada archivo que cambia en el pull request,
+/// comparando los blobs con el motor de diff. Los archivos nuevos se comparan contra un
+/// contenido vacío, los eliminados contra su versión en el ancestro común y los binarios no
+/// tienen cantidades.
+///
+/// # Argumentos
+///
+/// * `directory` - Ruta del repositorio del pull request.
+/// * `base` - branch target.
+/// * `head` - branch origen.
+///
+/// # Retornos
+/// Devuelve `Ok(result)` con las estadísticas de cada archivo, ordenadas por path.
+/// Devuelve `Err(ServerError)` si no se pueden leer los commits o los blobs de las ramas.
+fn get_file_stats_pr(
+    directory: &str,
+    base: &str,
+    head: &str,
+) -> Result<Vec<FileStat>, ServerError> {
+    let mut stats = Vec::new();
+    for change in get_file_changes_pr(directory, base, head)? {
+        let (old, new) = change.read_contents(directory)?;
+        let (additions, deletions) = if is_binary(&old) || is_binary(&new) {
+            (None, None)
+        } else {
+            let (additions, deletions) = count_changes(&old, &new);
+            (Some(additions), Some(deletions))
+        };
+        stats.push(FileStat {
+            filename: change.path,
+            additions,
+            deletions,
+        });
     }
-    // Archivos modificados
-    for (key, value) in pr_files_map_base {
-        if let Some(other_value) = pr_files_map_head.get(&key) {
-            if other_value.clone() != value {
-                result.push(other_value.clone());
+    Ok(stats)
+}
+
+/// Calcula el diff de cada archivo que cambia en el pull request, desde el ancestro común de
+/// las ramas base y head. Los archivos nuevos se comparan contra un contenido vacío y los
+/// eliminados contra su versión en el ancestro común.
+///
+/// # Argumentos
+///
+/// * `directory` - Ruta del repositorio del pull request.
+/// * `base` - branch target.
+/// * `head` - branch origen.
+/// * `max_lines` - cantidad máxima de líneas de todos los hunks; los que la superan se omiten.
+///
+/// # Retornos
+/// Devuelve `Ok(result)` con el diff de cada archivo, ordenados por path.
+/// Devuelve `Err(ServerError)` si no se pueden leer los commits o los blobs de las ramas.
+fn get_file_diffs_pr(
+    directory: &str,
+    base: &str,
+    head: &str,
+    max_lines: usize,
+) -> Result<Diff, ServerError> {
+    let mut remaining = max_lines;
+    let mut diff = Diff {
+        files: Vec::new(),
+        truncated: false,
+    };
+    for change in get_file_changes_pr(directory, base, head)? {
+        let status = match (&change.old_blob, &change.new_blob) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "modified",
+        };
+        let (old, new) = change.read_contents(directory)?;
+        let binary = is_binary(&old) || is_binary(&new);
+        let mut file = FileDiff {
+            filename: change.path,
+            status: status.to_string(),
+            binary,
+            hunks: Vec::new(),
+            truncated: false,
+        };
+        if !binary {
+            for hunk in diff_hunks(&old, &new) {
+                let lines = hunk.lines().count();
+                if lines > remaining {
+                    file.truncated = true;
+                    break;
+                }
+                remaining -= lines;
+                file.hunks.push(hunk);
             }
         }
+        diff.truncated |= file.truncated;
+        diff.files.push(file);
     }
-    Ok(result)
+    Ok(diff)
 }
 
 /// Verifica si un pull request contiene cambios antes de proceder con su creación.
@@ -966,8 +1473,13 @@ pub fn check_pull_request_changes(
 pub fn is_mergeable(directory: &str, base: &str, head: &str) -> Result<bool, ServerError> {
     let base_current_commit = get_branch_current_hash(directory, base.to_string())?;
     let head_current_commit = get_branch_current_hash(directory, head.to_string())?;
-    let common_ancestor = find_commit_common_ancestor(directory, base, head)?;
-    if common_ancestor == base_current_commit {
+    for commit in [&base_current_commit, &head_current_commit] {
+        if !object_exists(directory, commit) {
+            return Err(UtilError::ObjectNotFound(commit.to_string()).into());
+        }
+    }
+    let common_ancestor = get_merge_bases(directory, &base_current_commit, &head_current_commit)?;
+    if common_ancestor.first() == Some(&base_current_commit) {
         return Ok(true);
     }
     let mut pr_files_map_head: HashMap<String, String> = HashMap::new();
@@ -999,3 +1511,508 @@ pub fn is_mergeable(directory: &str, base: &str, head: &str) -> Result<bool, Ser
     }
     Ok(true)
 }
+
+/// Calcula el ETag de la respuesta de `get_pull_request`.
+///
+/// El ETag se obtiene del contenido almacenado del pull request junto con los hashes
+/// actuales de las branches base y head, de modo que cambia si alguna de ellas avanza
+/// aunque el archivo guardado no se haya modificado.
+///
+/// # Parámetros
+/// - `repo_name`: El nombre del repositorio al que pertenece el pull request.
+/// - `pull_number`: El número del pull request.
+/// - `src`: La ruta base donde se encuentran los archivos del pull request.
+///
+/// # Retornos
+/// - `Some(String)`: El ETag entre comillas.
+/// - `None`: Si el pull request no existe o no se pudo leer.
+///
+pub fn get_etag_pull_request(repo_name: &str, pull_number: &str, src: &str) -> Option<String> {
+    let directory = resolve_repo_path(src, repo_name).ok()?;
+    let file_path = get_pull_request_file_path(repo_name, pull_number, src).ok()?;
+    let content = get_etag_content(&directory, &file_path)?;
+    Some(format!("\"{}\"", hash_generate(&content)))
+}
+
+/// Calcula el ETag de la respuesta de `list_pull_request`, combinando el mapa de
+/// pull requests abiertos con el contenido y las branches de cada uno de ellos.
+///
+/// # Parámetros
+/// - `repo_name`: El nombre del repositorio.
+/// - `src`: La ruta base donde se encuentran los archivos del pull request.
+///
+/// # Retornos
+/// - `Some(String)`: El ETag entre comillas.
+/// - `None`: Si el repositorio no tiene pull requests o no se pudieron leer.
+///
+pub fn get_etag_list_pull_request(repo_name: &str, src: &str) -> Option<String> {
+    let directory = resolve_repo_path(src, repo_name).ok()?;
+    let pr_repo_folder_path =
+        resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name)).ok()?;
+    let pr_map_path = format!("{}/{}", pr_repo_folder_path, PR_MAP_FILE);
+    let mut content = std::fs::read_to_string(&pr_map_path).ok()?;
+
+    let mut pr_numbers: Vec<usize> = read_pr_map(&pr_map_path).ok()?.into_values().collect();
+    pr_numbers.sort();
+    for number in pr_numbers {
+        let pr_path = format!("{}/{}{}", pr_repo_folder_path, number, PR_FILE_EXTENSION);
+        content.push_str(&get_etag_content(&directory, &pr_path)?);
+    }
+    Some(format!("\"{}\"", hash_generate(&content)))
+}
+
+/// Devuelve el contenido del archivo del pull request seguido de los hashes actuales
+/// de sus branches base y head.
+fn get_etag_content(directory: &str, file_path: &str) -> Option<String> {
+    let mut content = std::fs::read_to_string(file_path).ok()?;
+    let body = HttpBody::parse(APPLICATION_SERVER, &content).ok()?;
+    for field in ["base", "head"] {
+        let branch = body.get_field(field).ok()?;
+        let tip = get_branch_current_hash(directory, branch).unwrap_or_default();
+        content.push_str(&tip);
+    }
+    Some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        branch::git_branch_create, checkout::git_checkout_switch, commit::git_commit,
+        init::git_init, rm::git_rm,
+    };
+    use crate::test_utils::{commit_file, test_commit};
+    use crate::util::object_format::ObjectFormat;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_get_commit_graph_includes_merge_parents() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_commit_graph".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        commit_file(&directory, "a.txt", "base");
+        let base = get_branch_current_hash(&directory, "master".to_string())
+            .expect("Falló al leer la branch");
+        git_branch_create(&directory, "feature").expect("Falló al crear la branch");
+        commit_file(&directory, "a.txt", "master");
+        let left = get_branch_current_hash(&directory, "master".to_string())
+            .expect("Falló al leer la branch");
+        git_checkout_switch(&directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(&directory, "b.txt", "feature");
+        let right = get_branch_current_hash(&directory, "feature".to_string())
+            .expect("Falló al leer la branch");
+
+        let mut merge = test_commit("merge feature");
+        let right_content = git_cat_file(&directory, right.trim(), "-p").expect("Falló al leer");
+        merge.set_tree(get_tree_hash(&right_content).expect("Falló al leer el tree"));
+        merge.set_parents(&[left.trim(), right.trim()]);
+        let merge_hash = crate::util::objects::builder_object_commit(
+            &merge.serialize(),
+            &format!("{}/.git", directory),
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al crear el merge commit");
+        std::fs::write(format!("{}/.git/refs/heads/master", directory), &merge_hash)
+            .expect("Falló al actualizar la branch");
+
+        let graph = get_commit_graph("repo", Some("master"), None, &src, &tx);
+        let limited = get_commit_graph("repo", Some("master"), Some("2"), &src, &tx);
+        let missing = get_commit_graph("repo", Some("no-existe"), None, &src, &tx);
+        let invalid_limit = get_commit_graph("repo", None, Some("cero"), &src, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        let commits = match graph {
+            Ok(StatusCode::Ok(Some(Model::CommitGraph(commits)))) => commits,
+            other => panic!("Respuesta inesperada: {:?}", other),
+        };
+        assert_eq!(commits.len(), 4);
+        assert_eq!(commits[0].hash, merge_hash);
+        assert_eq!(commits[0].parents, vec![left.trim(), right.trim()]);
+        assert_eq!(commits[0].summary, "merge feature");
+        assert_eq!(commits[0].author, "Valen");
+        assert_eq!(commits[3].hash, base.trim());
+        assert!(commits[3].parents.is_empty());
+
+        let body: serde_json::Value = serde_json::from_str(
+            &Model::CommitGraph(commits).to_string(crate::consts::APPLICATION_JSON),
+        )
+        .expect("El grafo no es un JSON válido");
+        assert_eq!(body["commits"][0]["parents"][1], right.trim());
+
+        assert!(matches!(
+            limited,
+            Ok(StatusCode::Ok(Some(Model::CommitGraph(commits)))) if commits.len() == 2
+        ));
+        assert!(matches!(missing, Ok(StatusCode::ResourceNotFound(_))));
+        assert!(matches!(invalid_limit, Ok(StatusCode::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_get_commit_graph_stops_at_limit() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_commit_graph_limit".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        let first = commit_file(&directory, "a.txt", "uno");
+        commit_file(&directory, "a.txt", "dos");
+        let third = commit_file(&directory, "a.txt", "tres");
+        // Sin el primer commit, solo se puede responder si el recorrido no llega hasta él
+        let (dir, file) = first.split_at(2);
+        std::fs::remove_file(format!("{}/.git/objects/{}/{}", directory, dir, file))
+            .expect("Falló al borrar el objeto");
+
+        let limited = get_commit_graph("repo", Some("master"), Some("2"), &src, &tx);
+        let full = get_commit_graph("repo", Some("master"), None, &src, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        let commits = match limited {
+            Ok(StatusCode::Ok(Some(Model::CommitGraph(commits)))) => commits,
+            other => panic!("Respuesta inesperada: {:?}", other),
+        };
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, third);
+        assert!(full.is_err());
+    }
+
+    #[test]
+    fn test_build_commit_pr_invalid_timestamp() {
+        let (sender, receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let content = "tree 1234\nauthor Juan <juan@mail.com> 99999999999999999 -0300\ncommitter Juan <juan@mail.com> 99999999999999999 -0300\n\nmensaje";
+
+        let commit_pr = build_commit_pr("abcd", content, &tx);
+
+        assert_eq!(commit_pr.date, "unknown");
+        assert_eq!(commit_pr.author_name, "Juan");
+        assert_eq!(commit_pr.tree_hash, "1234");
+        assert_eq!(commit_pr.message, "mensaje");
+        assert!(receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_build_commit_pr_author_timezone() {
+        let (sender, receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let content = "tree 1234\nauthor Juan <juan@mail.com> 1701632634 +0530\ncommitter Juan <juan@mail.com> 1701632634 -0300\n\nmensaje";
+
+        let commit_pr = build_commit_pr("abcd", content, &tx);
+
+        // 19:43:54 UTC es la 01:13:54 del día siguiente en +0530
+        assert_eq!(commit_pr.date, "Mon Dec 4 01:13:54 2023 +0530");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_etag_pull_request_changes_with_branch_tips() {
+        let src = "./test_etag_pr".to_string();
+        let heads = format!("{}/repo/.git/refs/heads", src);
+        let pr_folder = format!("{}/{}/repo", src, PR_FOLDER);
+        std::fs::create_dir_all(&heads).expect("Falló al crear el directorio");
+        std::fs::create_dir_all(&pr_folder).expect("Falló al crear el directorio");
+        std::fs::write(format!("{}/master", heads), "1111").expect("Falló al escribir");
+        std::fs::write(format!("{}/feature", heads), "2222").expect("Falló al escribir");
+        std::fs::write(
+            format!("{}/1{}", pr_folder, PR_FILE_EXTENSION),
+            "{\"base\": \"master\", \"head\": \"feature\"}",
+        )
+        .expect("Falló al escribir");
+
+        let etag = get_etag_pull_request("repo", "1", &src).expect("Falló al calcular el ETag");
+        assert_eq!(get_etag_pull_request("repo", "1", &src), Some(etag.clone()));
+
+        std::fs::write(format!("{}/feature", heads), "3333").expect("Falló al escribir");
+        assert_ne!(get_etag_pull_request("repo", "1", &src), Some(etag));
+        assert_eq!(get_etag_pull_request("repo", "2", &src), None);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_create_repository() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_create_repository".to_string();
+        let config = ServerConfig::new(&src, 2);
+        std::fs::create_dir_all(&src).expect("Falló al crear el directorio");
+        let body = |content: &str| {
+            HttpBody::parse(crate::consts::APPLICATION_JSON, content).expect("Body inválido")
+        };
+
+        let created = create_repository(
+            &body("{\"name\": \"repo\", \"default_branch\": \"main\"}"),
+            &config,
+            &tx,
+        );
+        let head = std::fs::read_to_string(format!("{}/repo/.git/HEAD", src));
+        let existing = create_repository(&body("{\"name\": \"repo\"}"), &config, &tx);
+        let traversal = create_repository(&body("{\"name\": \"../repo\"}"), &config, &tx);
+        let invalid_branch = create_repository(
+            &body("{\"name\": \"otro\", \"default_branch\": \"a..b\"}"),
+            &config,
+            &tx,
+        );
+        let default_branch = create_repository(&body("{\"name\": \"otro\"}"), &config, &tx);
+        let default_head = std::fs::read_to_string(format!("{}/otro/.git/HEAD", src));
+        let limit = create_repository(&body("{\"name\": \"tercero\"}"), &config, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(created, Ok(StatusCode::Created));
+        assert_eq!(head.ok().as_deref(), Some("ref: refs/heads/main\n"));
+        assert!(matches!(existing, Ok(StatusCode::ValidationFailed(_))));
+        assert!(matches!(traversal, Ok(StatusCode::ValidationFailed(_))));
+        assert!(matches!(
+            invalid_branch,
+            Ok(StatusCode::ValidationFailed(_))
+        ));
+        assert_eq!(default_branch, Ok(StatusCode::Created));
+        assert_eq!(
+            default_head.ok().as_deref(),
+            Some("ref: refs/heads/master\n")
+        );
+        assert!(matches!(limit, Ok(StatusCode::ValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_check_merge_pull_request_does_not_touch_refs() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_merge_check".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        commit_file(&directory, "a.txt", "base");
+        git_branch_create(&directory, "feature").expect("Falló al crear la branch");
+        git_checkout_switch(&directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(&directory, "a.txt", "feature");
+        git_checkout_switch(&directory, "master").expect("Falló al cambiar de branch");
+        commit_file(&directory, "a.txt", "master");
+        let pr_folder = format!("{}/{}/repo", src, PR_FOLDER);
+        std::fs::create_dir_all(&pr_folder).expect("Falló al crear el directorio");
+        std::fs::write(
+            format!("{}/1{}", pr_folder, PR_FILE_EXTENSION),
+            "{\"base\": \"master\", \"head\": \"feature\", \"owner\": \"Valen\", \"title\": \"PR\", \"state\": \"open\"}",
+        )
+        .expect("Falló al escribir");
+        let master = get_branch_current_hash(&directory, "master".to_string());
+        let feature = get_branch_current_hash(&directory, "feature".to_string());
+
+        let result = check_merge_pull_request("repo", "1", &src, &tx);
+        let master_after = get_branch_current_hash(&directory, "master".to_string());
+        let feature_after = get_branch_current_hash(&directory, "feature".to_string());
+        let content = std::fs::read_to_string(format!("{}/a.txt", directory));
+        let missing = check_merge_pull_request("repo", "2", &src, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            result,
+            Ok(StatusCode::Ok(Some(Model::MergeCheck(MergeCheck {
+                mergeable: false,
+                conflicts: vec!["a.txt".to_string()],
+                strategy: "three-way".to_string(),
+            }))))
+        );
+        assert!(master.is_ok());
+        assert_eq!(master_after, master);
+        assert_eq!(feature_after, feature);
+        assert_eq!(content.ok().as_deref(), Some("master"));
+        assert!(matches!(missing, Ok(StatusCode::ResourceNotFound(_))));
+    }
+
+    #[test]
+    fn test_pull_request_labels() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_pr_labels".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        commit_file(&directory, "a.txt", "base");
+        for branch in ["feature", "fix"] {
+            git_branch_create(&directory, branch).expect("Falló al crear la branch");
+            git_checkout_switch(&directory, branch).expect("Falló al cambiar de branch");
+            commit_file(&directory, &format!("{}.txt", branch), branch);
+            git_checkout_switch(&directory, "master").expect("Falló al cambiar de branch");
+        }
+        let body = |content: &str| {
+            HttpBody::parse(crate::consts::APPLICATION_JSON, content).expect("Body inválido")
+        };
+        let pr_body = |head: &str, labels: &str| {
+            body(&format!(
+                "{{\"head\": \"{}\", \"base\": \"master\", \"owner\": \"Valen\", \"title\": \"{}\", \"body\": \"\", \"labels\": {}, \"assignees\": [\"juan\", \"juan\"]}}",
+                head, head, labels
+            ))
+        };
+        let created_feature = create_pull_requests(
+            &pr_body("feature", "[\"bug\", \"ui\", \"bug\"]"),
+            "repo",
+            &src,
+            &tx,
+        );
+        let created_fix = create_pull_requests(&pr_body("fix", "[\"docs\"]"), "repo", &src, &tx);
+        let stored = PullRequest::create_from_file(
+            &get_pull_request_file_path("repo", "1", &src).expect("Ruta inválida"),
+        );
+        let bug = list_pull_request("repo", &src, Some("bug"), &tx);
+        let modified = modify_pull_request(
+            &body("{\"labels\": [\"docs\"], \"assignees\": []}"),
+            "repo",
+            "1",
+            &src,
+            &tx,
+        );
+        let modified_pr = PullRequest::create_from_file(
+            &get_pull_request_file_path("repo", "1", &src).expect("Ruta inválida"),
+        );
+        let docs = list_pull_request("repo", &src, Some("docs"), &tx);
+        let all = list_pull_request("repo", &src, None, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        let titles = |result: Result<StatusCode, ServerError>| -> Vec<String> {
+            let mut titles: Vec<String> = match result {
+                Ok(StatusCode::Ok(Some(Model::ListPullRequest(prs)))) => {
+                    prs.into_iter().filter_map(|pr| pr.title).collect()
+                }
+                _ => Vec::new(),
+            };
+            titles.sort();
+            titles
+        };
+        assert_eq!(created_feature, Ok(StatusCode::Created));
+        assert_eq!(created_fix, Ok(StatusCode::Created));
+        let stored = stored.expect("Falló al leer el pull request");
+        assert_eq!(stored.labels, vec!["bug".to_string(), "ui".to_string()]);
+        assert_eq!(stored.assignees, vec!["juan".to_string()]);
+        assert_eq!(titles(bug), vec!["feature".to_string()]);
+        assert_eq!(modified, Ok(StatusCode::Ok(None)));
+        let modified_pr = modified_pr.expect("Falló al leer el pull request");
+        assert_eq!(modified_pr.labels, vec!["docs".to_string()]);
+        assert!(modified_pr.assignees.is_empty());
+        assert_eq!(titles(docs), vec!["feature".to_string(), "fix".to_string()]);
+        assert_eq!(titles(all).len(), 2);
+    }
+
+    #[test]
+    fn test_get_pull_request_file_stats() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_pr_file_stats".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        commit_file(&directory, "a.txt", "uno\ndos\n");
+        commit_file(&directory, "borrado.txt", "uno\ndos\n");
+        git_branch_create(&directory, "feature").expect("Falló al crear la branch");
+        // Un commit que solo está en la base no es parte del pull request
+        commit_file(&directory, "base.txt", "solo en master\n");
+        git_checkout_switch(&directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(&directory, "a.txt", "uno\nDOS\ntres\n");
+        commit_file(&directory, "imagen.bin", "PNG\0\u{1}");
+        git_rm(&directory, "borrado.txt").expect("Falló al borrar el archivo");
+        git_commit(&directory, test_commit("borrado")).expect("Falló al hacer el commit");
+        git_checkout_switch(&directory, "master").expect("Falló al cambiar de branch");
+        let body = HttpBody::parse(
+            crate::consts::APPLICATION_JSON,
+            "{\"head\": \"feature\", \"base\": \"master\", \"owner\": \"Valen\", \"title\": \"PR\", \"body\": \"\"}",
+        )
+        .expect("Body inválido");
+        let created = create_pull_requests(&body, "repo", &src, &tx);
+        let with_stats = get_pull_request("repo", "1", &src, true, &tx);
+        let without_stats = get_pull_request("repo", "1", &src, false, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        let file_stats = |result: Result<StatusCode, ServerError>| match result {
+            Ok(StatusCode::Ok(Some(Model::ListPullRequest(mut prs)))) => prs.remove(0).file_stats,
+            _ => panic!("Se esperaba el pull request"),
+        };
+        assert_eq!(created, Ok(StatusCode::Created));
+        assert_eq!(
+            file_stats(with_stats),
+            Some(vec![
+                FileStat {
+                    filename: "a.txt".to_string(),
+                    additions: Some(2),
+                    deletions: Some(1),
+                },
+                FileStat {
+                    filename: "borrado.txt".to_string(),
+                    additions: Some(0),
+                    deletions: Some(2),
+                },
+                FileStat {
+                    filename: "imagen.bin".to_string(),
+                    additions: None,
+                    deletions: None,
+                },
+            ])
+        );
+        assert_eq!(file_stats(without_stats), None);
+    }
+
+    #[test]
+    fn test_get_files_pull_request() {
+        let (sender, _receiver) = channel();
+        let tx = Arc::new(Mutex::new(sender));
+        let src = "./test_pr_files".to_string();
+        let directory = format!("{}/repo", src);
+        git_init(&directory).expect("Falló al crear el repositorio");
+        commit_file(&directory, "a.txt", "uno\ndos\n");
+        commit_file(&directory, "b.txt", "uno\n");
+        git_branch_create(&directory, "feature").expect("Falló al crear la branch");
+        // Un commit que solo está en la base no es parte del diff del pull request
+        commit_file(&directory, "base.txt", "solo en master\n");
+        git_checkout_switch(&directory, "feature").expect("Falló al cambiar de branch");
+        commit_file(&directory, "a.txt", "uno\nDOS\ntres\n");
+        commit_file(&directory, "imagen.bin", "PNG\0\u{1}");
+        commit_file(&directory, "c.txt", "a\nb\nc\nd\n");
+        git_checkout_switch(&directory, "master").expect("Falló al cambiar de branch");
+        let body = HttpBody::parse(
+            crate::consts::APPLICATION_JSON,
+            "{\"head\": \"feature\", \"base\": \"master\", \"owner\": \"Valen\", \"title\": \"PR\", \"body\": \"\"}",
+        )
+        .expect("Body inválido");
+        let created = create_pull_requests(&body, "repo", &src, &tx);
+        let files = get_files_pull_request("repo", "1", &src, &tx);
+        let truncated = get_file_diffs_pr(&directory, "master", "feature", 6);
+        let missing = get_files_pull_request("repo", "2", &src, &tx);
+
+        std::fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(created, Ok(StatusCode::Created));
+        let diff = match files {
+            Ok(StatusCode::Ok(Some(Model::Diff(diff)))) => diff,
+            _ => panic!("Se esperaba el diff del pull request"),
+        };
+        assert!(!diff.truncated);
+        let summary: Vec<(&str, &str, bool)> = diff
+            .files
+            .iter()
+            .map(|file| (file.filename.as_str(), file.status.as_str(), file.binary))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.txt", "modified", false),
+                ("c.txt", "added", false),
+                ("imagen.bin", "added", true),
+            ]
+        );
+        assert_eq!(
+            diff.files[0].hunks,
+            vec!["@@ -1,2 +1,3 @@\n uno\n-dos\n+DOS\n+tres\n".to_string()]
+        );
+        assert!(diff.files[2].hunks.is_empty());
+
+        let truncated = truncated.expect("Falló al calcular el diff");
+        assert!(truncated.truncated);
+        assert!(!truncated.files[0].truncated);
+        assert!(truncated.files[1].truncated);
+        assert!(truncated.files[1].hunks.is_empty());
+        assert!(matches!(missing, Ok(StatusCode::ResourceNotFound(_))));
+    }
+}
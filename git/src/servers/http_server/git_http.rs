@@ -0,0 +1,440 @@
+use super::status_code::StatusCode;
+use super::utils::{is_chunked, read_chunked_body, read_remaining_body, send_response_http};
+use crate::consts::{
+    APPLICATION_SERVER, CONTENT_LENGTH, CONTENT_TYPE, CRLF, CRLF_DOUBLE, FLUSH_PKT, GIT_INFO_REFS,
+    GIT_UPLOAD_PACK, GIT_UPLOAD_PACK_ADVERTISEMENT, GIT_UPLOAD_PACK_RESULT, HTTP_VERSION,
+    TRANSFER_ENCODING,
+};
+use crate::git_transport::access_policy::{Access, AccessPolicy};
+use crate::git_transport::git_request::{
+    create_upload_pack_server, get_path_repository, upload_pack_negotiation,
+};
+use crate::servers::errors::ServerError;
+use crate::util::errors::UtilError;
+use crate::util::logger::log_message_with_signature;
+use crate::util::pkt_line::add_length_prefix;
+use std::io::{Cursor, Read, Write};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+/// Solicitudes del protocolo HTTP "smart" de Git que atiende el servidor.
+///
+/// - `InfoRefs`: `GET /<repo>/info/refs?service=<servicio>`, descubrimiento de referencias.
+/// - `UploadPack`: `POST /<repo>/git-upload-pack`, negociación y envío del packfile. Si el
+///   cliente envía el cuerpo con `Transfer-Encoding: chunked`, `chunked` es `true` y
+///   `content_length` no se usa.
+///
+#[derive(Debug, PartialEq)]
+pub enum GitHttpRequest {
+    InfoRefs {
+        repo_name: String,
+        service: String,
+    },
+    UploadPack {
+        repo_name: String,
+        body: Vec<u8>,
+        content_length: usize,
+        chunked: bool,
+    },
+}
+
+impl GitHttpRequest {
+    /// Interpreta una solicitud HTTP como una solicitud del protocolo de Git.
+    ///
+    /// # Argumentos
+    ///
+    /// * `request` - La solicitud HTTP completa tal como se leyó del cliente.
+    ///
+    /// # Retorno
+    ///
+    /// Retorna `None` si la solicitud no corresponde al protocolo de Git, en cuyo caso
+    /// debe ser atendida por la API de pull requests.
+    ///
+    pub fn parse(request: &str) -> Option<GitHttpRequest> {
+        let request_line = request.lines().next()?;
+        let parts: Vec<&str> = request_line.split_whitespace().collect();
+        if parts.len() < 3 {
+            return None;
+        }
+        let (path, query) = match parts[1].split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (parts[1], ""),
+        };
+        match parts[0] {
+            "GET" => {
+                let repo_name = path.strip_suffix(GIT_INFO_REFS)?;
+                let service = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("service="))?;
+                Some(GitHttpRequest::InfoRefs {
+                    repo_name: repo_name.trim_start_matches('/').to_string(),
+                    service: service.to_string(),
+                })
+            }
+            "POST" => {
+                let repo_name = path.strip_suffix(&format!("/{}", GIT_UPLOAD_PACK))?;
+                let body = request.split_once(CRLF_DOUBLE).map_or("", |(_, body)| body);
+                let header = |name: &str| get_request_header(request, name);
+                let content_length = header(CONTENT_LENGTH)
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .unwrap_or(body.len());
+                Some(GitHttpRequest::UploadPack {
+                    repo_name: repo_name.trim_start_matches('/').to_string(),
+                    body: body.as_bytes().to_vec(),
+                    content_length,
+                    chunked: header(TRANSFER_ENCODING).is_some_and(is_chunked),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Obtiene el valor de un encabezado de una solicitud HTTP, sin distinguir mayúsculas en su
+/// nombre.
+///
+/// # Argumentos
+///
+/// * `request` - La solicitud HTTP tal como se leyó del cliente.
+/// * `name` - Nombre del encabezado.
+///
+pub fn get_request_header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    let headers = request
+        .split_once(CRLF_DOUBLE)
+        .map_or(request, |(headers, _)| headers);
+    headers
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Canal en memoria utilizado para reutilizar la negociación del transporte git://
+/// sobre HTTP: se lee del cuerpo de la solicitud y se escribe en el de la respuesta.
+struct GitHttpStream {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Read for GitHttpStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for GitHttpStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Atiende una solicitud del protocolo HTTP de Git y envía la respuesta al cliente.
+///
+/// # Argumentos
+///
+/// * `stream` - Conexión con el cliente.
+/// * `request` - La solicitud de Git a atender.
+/// * `root_directory` - Directorio raíz donde se encuentran los repositorios del servidor.
+/// * `principal` - Usuario autenticado, o `None` si la solicitud es anónima. Se usa para
+///   aplicar la política de acceso del servidor.
+/// * `tx` - Un `Arc<Mutex<Sender<String>>>` que se utiliza para enviar mensajes de log.
+/// * `signature` - La firma del cliente.
+///
+/// # Retornos
+///
+/// Retorna el `StatusCode` con el que se respondió o un `ServerError` si no se pudo enviar la respuesta.
+///
+pub fn handle_git_http_request<S: Read + Write>(
+    stream: &mut S,
+    request: GitHttpRequest,
+    root_directory: &str,
+    principal: Option<&str>,
+    tx: &Arc<Mutex<Sender<String>>>,
+    signature: &str,
+) -> Result<StatusCode, ServerError> {
+    let result = match request {
+        GitHttpRequest::InfoRefs { repo_name, service } => {
+            let message = format!("Git info/refs request for repository: {}", repo_name);
+            log_message_with_signature(tx, signature, &message);
+            build_info_refs(root_directory, &repo_name, &service, principal)
+        }
+        GitHttpRequest::UploadPack {
+            repo_name,
+            mut body,
+            content_length,
+            chunked,
+        } => {
+            let message = format!("Git upload-pack request for repository: {}", repo_name);
+            log_message_with_signature(tx, signature, &message);
+            // Los chunks que no llegaron en la primera lectura se siguen leyendo de la conexión
+            let body = match chunked {
+                true => read_chunked_body(&mut Cursor::new(body).chain(&mut *stream)),
+                false => read_remaining_body(stream, &mut body, content_length).map(|_| body),
+            };
+            match body {
+                Ok(body) => build_upload_pack_result(root_directory, &repo_name, body, principal),
+                Err(e) => Err(StatusCode::BadRequest(e.to_string())),
+            }
+        }
+    };
+    match result {
+        Ok((content_type, body)) => {
+            send_git_http_response(stream, content_type, &body)?;
+            Ok(StatusCode::Ok(None))
+        }
+        Err(status_code) => {
+            send_response_http(stream, &status_code, APPLICATION_SERVER)?;
+            Ok(status_code)
+        }
+    }
+}
+
+/// Construye la respuesta de `info/refs`: el anuncio del servicio seguido de las
+/// referencias del repositorio.
+///
+/// # Argumentos
+///
+/// * `root_directory` - Directorio raíz de los repositorios.
+/// * `repo_name` - Nombre del repositorio solicitado.
+/// * `service` - Servicio solicitado por el cliente.
+/// * `principal` - Usuario autenticado, o `None` si la solicitud es anónima.
+///
+fn build_info_refs(
+    root_directory: &str,
+    repo_name: &str,
+    service: &str,
+    principal: Option<&str>,
+) -> Result<(&'static str, Vec<u8>), StatusCode> {
+    if service != GIT_UPLOAD_PACK {
+        return Err(StatusCode::Forbidden(format!(
+            "Servicio no soportado: {}",
+            service
+        )));
+    }
+    let path_repo = get_repository(root_directory, repo_name, principal)?;
+    let server = create_upload_pack_server(&path_repo).map_err(internal_error)?;
+
+    let announcement = format!("# service={}\n", GIT_UPLOAD_PACK);
+    let mut body = add_length_prefix(&announcement, announcement.len()).into_bytes();
+    body.extend_from_slice(FLUSH_PKT.as_bytes());
+    server
+        .send_advertisement(&mut body)
+        .map_err(internal_error)?;
+    Ok((GIT_UPLOAD_PACK_ADVERTISEMENT, body))
+}
+
+/// Realiza la negociación del `git-upload-pack` con los want/have recibidos en el
+/// cuerpo de la solicitud y devuelve el packfile resultante.
+///
+/// # Argumentos
+///
+/// * `root_directory` - Directorio raíz de los repositorios.
+/// * `repo_name` - Nombre del repositorio solicitado.
+/// * `body` - Cuerpo de la solicitud en formato pkt-line.
+/// * `principal` - Usuario autenticado, o `None` si la solicitud es anónima.
+///
+fn build_upload_pack_result(
+    root_directory: &str,
+    repo_name: &str,
+    body: Vec<u8>,
+    principal: Option<&str>,
+) -> Result<(&'static str, Vec<u8>), StatusCode> {
+    let path_repo = get_repository(root_directory, repo_name, principal)?;
+    let mut server = create_upload_pack_server(&path_repo).map_err(internal_error)?;
+    let mut stream = GitHttpStream {
+        input: Cursor::new(body),
+        output: Vec::new(),
+    };
+    upload_pack_negotiation(&mut stream, &mut server, &path_repo).map_err(internal_error)?;
+    Ok((GIT_UPLOAD_PACK_RESULT, stream.output))
+}
+
+/// Obtiene la ruta del repositorio solicitado, rechazando rutas fuera del directorio raíz y
+/// los repositorios que la política de acceso no permite leer. Si se rechaza una solicitud
+/// anónima se responde `401` para que el cliente pida credenciales.
+fn get_repository(
+    root_directory: &str,
+    repo_name: &str,
+    principal: Option<&str>,
+) -> Result<String, StatusCode> {
+    if repo_name.is_empty() || repo_name.split('/').any(|part| part == "..") {
+        return Err(StatusCode::Forbidden(format!(
+            "Repositorio inválido: {}",
+            repo_name
+        )));
+    }
+    let policy = AccessPolicy::load(root_directory);
+    match policy.check(repo_name, principal, Access::Read) {
+        Ok(()) => {}
+        Err(e) if principal.is_none() => return Err(StatusCode::Unauthorized(e.to_string())),
+        Err(e) => return Err(StatusCode::Forbidden(e.to_string())),
+    }
+    match get_path_repository(root_directory, repo_name) {
+        Ok(path_repo) => Ok(path_repo),
+        Err(e) => Err(StatusCode::ResourceNotFound(e.to_string())),
+    }
+}
+
+fn internal_error(error: UtilError) -> StatusCode {
+    StatusCode::InternalError(error.to_string())
+}
+
+/// Envía una respuesta exitosa del protocolo de Git con su cuerpo binario.
+///
+/// # Argumentos
+///
+/// * `writer` - Conexión con el cliente.
+/// * `content_type` - Tipo de contenido de la respuesta.
+/// * `body` - Cuerpo de la respuesta.
+///
+fn send_git_http_response(
+    writer: &mut dyn Write,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), ServerError> {
+    let headers = format!(
+        "{} {}{}{}: {}{}Cache-Control: no-cache{}{}: {}{}",
+        HTTP_VERSION,
+        StatusCode::Ok(None),
+        CRLF,
+        CONTENT_TYPE,
+        content_type,
+        CRLF,
+        CRLF,
+        CONTENT_LENGTH,
+        body.len(),
+        CRLF_DOUBLE
+    );
+    if writer.write_all(headers.as_bytes()).is_err() || writer.write_all(body).is_err() {
+        return Err(ServerError::SendResponse(headers));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::git_init;
+    use crate::consts::{ACCESS_POLICY_FILE, PR_FOLDER};
+    use crate::util::files::create_file_replace;
+    use std::fs;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_parse_git_http_request() {
+        let request =
+            "GET /repo/info/refs?service=git-upload-pack HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        assert_eq!(
+            GitHttpRequest::parse(request),
+            Some(GitHttpRequest::InfoRefs {
+                repo_name: "repo".to_string(),
+                service: "git-upload-pack".to_string(),
+            })
+        );
+
+        let request = "POST /repo/git-upload-pack HTTP/1.1\r\nContent-Length: 9\r\n\r\n0009done\n";
+        assert_eq!(
+            GitHttpRequest::parse(request),
+            Some(GitHttpRequest::UploadPack {
+                repo_name: "repo".to_string(),
+                body: b"0009done\n".to_vec(),
+                content_length: 9,
+                chunked: false,
+            })
+        );
+
+        let request = "POST /repo/git-upload-pack HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n9\r\n0009done\n\r\n0\r\n\r\n";
+        assert!(matches!(
+            GitHttpRequest::parse(request),
+            Some(GitHttpRequest::UploadPack { chunked: true, .. })
+        ));
+
+        let request = "GET /repos/repo/pulls HTTP/1.1\r\n\r\n";
+        assert_eq!(GitHttpRequest::parse(request), None);
+    }
+
+    #[test]
+    fn test_info_refs_empty_repository() {
+        let root = "./test_git_http_info_refs";
+        git_init(&format!("{}/repo", root)).expect("Falló al crear el repositorio");
+        let (tx, _rx) = mpsc::channel();
+        let tx = Arc::new(Mutex::new(tx));
+
+        let request = GitHttpRequest::InfoRefs {
+            repo_name: "repo".to_string(),
+            service: GIT_UPLOAD_PACK.to_string(),
+        };
+        let mut stream = Cursor::new(Vec::new());
+        let status = handle_git_http_request(&mut stream, request, root, None, &tx, "test");
+        let response = String::from_utf8_lossy(stream.get_ref()).to_string();
+
+        assert_eq!(status, Ok(StatusCode::Ok(None)));
+        assert!(response.contains(GIT_UPLOAD_PACK_ADVERTISEMENT));
+        assert!(response.contains("001e# service=git-upload-pack\n0000"));
+        assert!(response.contains("0000000000000000000000000000000000000000 capabilities^{}"));
+        assert!(response.ends_with(FLUSH_PKT));
+
+        let request = GitHttpRequest::InfoRefs {
+            repo_name: "../repo".to_string(),
+            service: GIT_UPLOAD_PACK.to_string(),
+        };
+        let mut stream = Cursor::new(Vec::new());
+        let status = handle_git_http_request(&mut stream, request, root, None, &tx, "test");
+        assert!(matches!(status, Ok(StatusCode::Forbidden(_))));
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_git_http_checks_access_policy() {
+        let root = "./test_git_http_access";
+        git_init(&format!("{}/privado", root)).expect("Falló al crear el repositorio");
+        create_file_replace(
+            &format!("{}/{}/{}", root, PR_FOLDER, ACCESS_POLICY_FILE),
+            "privado = none\nprivado:alice = read\n",
+        )
+        .expect("Falló al crear la política de acceso");
+        let (tx, _rx) = mpsc::channel();
+        let tx = Arc::new(Mutex::new(tx));
+
+        let info_refs = || GitHttpRequest::InfoRefs {
+            repo_name: "./privado/".to_string(),
+            service: GIT_UPLOAD_PACK.to_string(),
+        };
+        let upload_pack = || GitHttpRequest::UploadPack {
+            repo_name: "privado".to_string(),
+            body: b"0009done\n".to_vec(),
+            content_length: 9,
+            chunked: false,
+        };
+        let handle = |request: GitHttpRequest, principal: Option<&str>| {
+            let mut stream = Cursor::new(Vec::new());
+            handle_git_http_request(&mut stream, request, root, principal, &tx, "test")
+        };
+        let anonymous_refs = handle(info_refs(), None);
+        let anonymous_pack = handle(upload_pack(), None);
+        let bob_refs = handle(info_refs(), Some("bob"));
+        let bob_pack = handle(upload_pack(), Some("bob"));
+        let alice_refs = handle(info_refs(), Some("alice"));
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+
+        assert!(matches!(anonymous_refs, Ok(StatusCode::Unauthorized(_))));
+        assert!(matches!(anonymous_pack, Ok(StatusCode::Unauthorized(_))));
+        assert!(matches!(bob_refs, Ok(StatusCode::Forbidden(_))));
+        assert!(matches!(bob_pack, Ok(StatusCode::Forbidden(_))));
+        assert_eq!(alice_refs, Ok(StatusCode::Ok(None)));
+        assert_eq!(
+            get_request_header(
+                "GET / HTTP/1.1\r\nauthorization: Bearer x\r\n\r\nAuthorization: y",
+                "Authorization"
+            ),
+            Some("Bearer x")
+        );
+    }
+}
@@ -1,8 +1,12 @@
-use std::{collections::HashMap, fmt, fs::File, io::Write};
+use std::{collections::HashMap, fmt};
 
 use crate::{
-    consts::{APPLICATION_JSON, APPLICATION_XML, APPLICATION_YAML, TEXT_XML, TEXT_YAML},
+    consts::{
+        APPLICATION_JSON, APPLICATION_XML, APPLICATION_YAML, CONTENT_DISPOSITION, CONTENT_TYPE,
+        CRLF, CRLF_DOUBLE, MULTIPART_FORM_DATA, TEXT_XML, TEXT_YAML,
+    },
     servers::errors::ServerError,
+    util::files::write_file_atomic,
 };
 use serde_json::Value as JsonValue;
 use serde_xml_rs::to_string as xml_to_string;
@@ -12,21 +16,102 @@ use super::pr::PullRequest;
 
 /// Enum `HttpBody` que representa los diferentes tipos de cuerpos de solicitudes HTTP.
 ///
-/// Este enum puede contener un valor JSON, XML, YAML o texto plano.
+/// Este enum puede contener un valor JSON, XML, YAML, las partes de un formulario multipart o
+/// un cuerpo vacío.
 ///
 /// # Variantes
 /// - `Json(JsonValue)`: Contiene un valor JSON.
 /// - `Xml(JsonValue)`: Contiene un valor XML representado como `JsonValue`.
 /// - `Yaml(YamlValue)`: Contiene un valor YAML.
+/// - `Multipart(Vec<MultipartPart>)`: Contiene las partes de un cuerpo `multipart/form-data`.
 ///
 #[derive(Debug, PartialEq, Clone)]
 pub enum HttpBody {
     Json(JsonValue),
     Xml(JsonValue),
     Yaml(YamlValue),
+    Multipart(Vec<MultipartPart>),
     Empty,
 }
 
+/// Parte de un cuerpo `multipart/form-data`.
+///
+/// - `name`: Nombre del campo, tomado del encabezado `Content-Disposition`.
+/// - `filename`: Nombre del archivo adjunto, si la parte es un archivo.
+/// - `content_type`: Tipo de contenido de la parte, si se indicó.
+/// - `content`: Contenido de la parte, tal como llegó (puede ser binario).
+///
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultipartPart {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub content: Vec<u8>,
+}
+
+impl MultipartPart {
+    /// Analiza una parte de un cuerpo multipart: el fin de línea del delimitador, los
+    /// encabezados, una línea vacía y el contenido. El `\r\n` anterior al siguiente
+    /// delimitador ya no forma parte de la sección.
+    ///
+    /// # Parámetros
+    /// - `section`: Los bytes entre dos delimitadores del cuerpo.
+    ///
+    /// # Errores
+    /// - `ServerError::InvalidBody` si la parte no tiene encabezados, no tiene nombre o sus
+    ///   encabezados no son UTF-8.
+    ///
+    fn parse(section: &[u8]) -> Result<Self, ServerError> {
+        let section = section
+            .strip_prefix(CRLF.as_bytes())
+            .ok_or(ServerError::InvalidBody)?;
+        let headers_end =
+            find_bytes(section, CRLF_DOUBLE.as_bytes()).ok_or(ServerError::InvalidBody)?;
+        let headers =
+            std::str::from_utf8(&section[..headers_end]).map_err(|_| ServerError::InvalidBody)?;
+        let content = &section[headers_end + CRLF_DOUBLE.len()..];
+
+        let mut name = None;
+        let mut filename = None;
+        let mut content_type = None;
+        for header in headers.split(CRLF) {
+            let (key, value) = header.split_once(':').ok_or(ServerError::InvalidBody)?;
+            let key = key.trim();
+            if key.eq_ignore_ascii_case(CONTENT_DISPOSITION) {
+                for param in value.split(';').skip(1) {
+                    match param.trim().split_once('=') {
+                        Some(("name", value)) => name = Some(value.trim_matches('"').to_string()),
+                        Some(("filename", value)) => {
+                            filename = Some(value.trim_matches('"').to_string())
+                        }
+                        _ => (),
+                    }
+                }
+            } else if key.eq_ignore_ascii_case(CONTENT_TYPE) {
+                content_type = Some(value.trim().to_string());
+            }
+        }
+
+        Ok(MultipartPart {
+            name: name.ok_or(ServerError::InvalidBody)?,
+            filename,
+            content_type,
+            content: content.to_vec(),
+        })
+    }
+}
+
+/// Busca la primera aparición de `needle` dentro de `haystack`.
+///
+/// # Retorno
+/// La posición donde empieza, o `None` si no aparece.
+///
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
 /// Implementa el trait `fmt::Display` para `HttpBody`.
 ///
 /// Permite que el tipo `HttpBody` sea formateado como una cadena, dependiendo de su variante.
@@ -42,6 +127,7 @@ impl fmt::Display for HttpBody {
             HttpBody::Json(json) => write!(f, "{}", json),
             HttpBody::Xml(xml) => write!(f, "{:?}", xml),
             HttpBody::Yaml(yaml) => write!(f, "{:?}", yaml),
+            HttpBody::Multipart(parts) => write!(f, "{:?}", parts),
             HttpBody::Empty => write!(f, ""),
         }
     }
@@ -52,6 +138,7 @@ impl HttpBody {
     ///
     /// # Parámetros
     /// - `content_type`: El tipo de contenido de la solicitud, como `application/json`, `application/xml`, etc.
+    ///   Para `multipart/form-data` debe incluir el parámetro `boundary`.
     /// - `body`: El cuerpo de la solicitud como una cadena.
     ///
     /// # Retorno
@@ -62,13 +149,16 @@ impl HttpBody {
     /// - `ServerError::HttpParseJsonBody` si ocurre un error al analizar JSON.
     /// - `ServerError::HttpParseYamlBody` si ocurre un error al analizar YAML.
     /// - `ServerError::HttpParseXmlBody` si ocurre un error al analizar XML.
+    /// - `ServerError::InvalidBody` si el cuerpo multipart está mal formado.
     /// - `ServerError::UnsupportedMediaType` si el tipo de contenido no es soportado.
     ///
     pub fn parse(content_type: &str, body: &str) -> Result<Self, ServerError> {
         if body.is_empty() {
             return Ok(HttpBody::Empty);
         }
-        match content_type {
+        // Se ignoran los parámetros del tipo de contenido, como `charset` o `boundary`
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        match media_type {
             APPLICATION_JSON => serde_json::from_str(body)
                 .map(HttpBody::Json)
                 .map_err(|_| ServerError::HttpParseJsonBody),
@@ -78,10 +168,68 @@ impl HttpBody {
             APPLICATION_XML | TEXT_XML => serde_xml_rs::from_str(body)
                 .map(HttpBody::Xml)
                 .map_err(|_| ServerError::HttpParseXmlBody),
+            MULTIPART_FORM_DATA => HttpBody::parse_multipart(content_type, body.as_bytes()),
             _ => Err(ServerError::UnsupportedMediaType),
         }
     }
 
+    /// Analiza el cuerpo de la solicitud HTTP tal como llegó de la conexión. Los cuerpos
+    /// `multipart/form-data` se analizan sobre los bytes, para no alterar archivos binarios;
+    /// el resto se convierte a texto y se analiza con `parse`.
+    ///
+    /// # Parámetros
+    /// - `content_type`: El tipo de contenido de la solicitud.
+    /// - `body`: Los bytes del cuerpo de la solicitud.
+    ///
+    /// # Errores
+    /// Los mismos que `parse`.
+    ///
+    pub fn parse_bytes(content_type: &str, body: &[u8]) -> Result<Self, ServerError> {
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        if media_type == MULTIPART_FORM_DATA && !body.is_empty() {
+            return HttpBody::parse_multipart(content_type, body);
+        }
+        HttpBody::parse(content_type, &String::from_utf8_lossy(body))
+    }
+
+    /// Analiza un cuerpo `multipart/form-data`, separando las partes con el delimitador
+    /// indicado en el parámetro `boundary` del tipo de contenido. Como en la RFC 2046, el
+    /// delimitador sólo cuenta al comienzo de una línea (`\r\n--boundary`), así que el contenido
+    /// de una parte puede incluir el boundary. Se ignora el texto anterior al primer
+    /// delimitador, y el cuerpo debe terminar con el delimitador de cierre.
+    ///
+    /// # Parámetros
+    /// - `content_type`: El tipo de contenido de la solicitud, con el parámetro `boundary`.
+    /// - `body`: Los bytes del cuerpo de la solicitud.
+    ///
+    /// # Errores
+    /// - `ServerError::InvalidBody` si falta el `boundary`, si alguna parte está mal formada o
+    ///   si falta el delimitador de cierre.
+    ///
+    fn parse_multipart(content_type: &str, body: &[u8]) -> Result<Self, ServerError> {
+        let boundary = content_type
+            .split(';')
+            .skip(1)
+            .find_map(|param| param.trim().strip_prefix("boundary="))
+            .map(|boundary| boundary.trim_matches('"'))
+            .filter(|boundary| !boundary.is_empty())
+            .ok_or(ServerError::InvalidBody)?;
+        let delimiter = format!("{}--{}", CRLF, boundary);
+        let delimiter = delimiter.as_bytes();
+
+        // El primer delimitador puede estar al comienzo del cuerpo, sin `\r\n` previo
+        let body = [CRLF.as_bytes(), body].concat();
+        let start = find_bytes(&body, delimiter).ok_or(ServerError::InvalidBody)?;
+        let mut rest = &body[start + delimiter.len()..];
+        let mut parts = Vec::new();
+        while !rest.starts_with(b"--") {
+            let end = find_bytes(rest, delimiter).ok_or(ServerError::InvalidBody)?;
+            parts.push(MultipartPart::parse(&rest[..end])?);
+            rest = &rest[end + delimiter.len()..];
+        }
+        Ok(HttpBody::Multipart(parts))
+    }
+
     /// Obtiene el valor de un campo específico dentro del cuerpo de la solicitud que se espera que sea un String
     ///
     /// # Parámetros
@@ -116,10 +264,37 @@ impl HttpBody {
                 .as_str()
                 .ok_or_else(|| ServerError::HttpFieldNotFound(field.to_string()))
                 .map(|s| s.to_string()),
+            HttpBody::Multipart(parts) => parts
+                .iter()
+                .find(|part| part.name == field && part.filename.is_none())
+                .map(|part| String::from_utf8_lossy(&part.content).to_string())
+                .ok_or_else(|| ServerError::HttpFieldNotFound(field.to_string())),
             HttpBody::Empty => Err(ServerError::HttpFieldNotFound(field.to_string())),
         }
     }
 
+    /// Obtiene un archivo adjunto de un cuerpo `multipart/form-data`.
+    ///
+    /// # Parámetros
+    /// - `name`: El nombre del campo del archivo.
+    ///
+    /// # Retorno
+    /// Retorna la parte con el nombre indicado que tiene un `filename`.
+    ///
+    /// # Errores
+    /// - `ServerError::HttpFieldNotFound` si no hay un archivo con ese nombre o el cuerpo no
+    ///   es multipart.
+    ///
+    pub fn get_file_part(&self, name: &str) -> Result<&MultipartPart, ServerError> {
+        match self {
+            HttpBody::Multipart(parts) => parts
+                .iter()
+                .find(|part| part.name == name && part.filename.is_some())
+                .ok_or_else(|| ServerError::HttpFieldNotFound(name.to_string())),
+            _ => Err(ServerError::HttpFieldNotFound(name.to_string())),
+        }
+    }
+
     /// Obtiene el valor de un campo específico dentro del cuerpo de la solicitud que se espera que sea un array.
     ///
     /// # Parámetros
@@ -177,7 +352,9 @@ impl HttpBody {
                     Err(ServerError::HttpFieldNotFound(field.to_string()))
                 }
             }
-            HttpBody::Empty => Err(ServerError::HttpFieldNotFound(field.to_string())),
+            HttpBody::Multipart(_) | HttpBody::Empty => {
+                Err(ServerError::HttpFieldNotFound(field.to_string()))
+            }
         }
     }
 
@@ -287,6 +464,9 @@ impl HttpBody {
     /// * `file_path` - La ruta del archivo donde se guardará el cuerpo serializado.
     /// * `application` - El formato en el que se debe guardar el cuerpo: `application/json`, `application/xml`, o `application/yaml`.
     ///
+    /// El archivo se reemplaza de forma atómica, por lo que una escritura interrumpida no deja
+    /// el archivo truncado.
+    ///
     /// # Retornos
     ///
     /// Retorna `Ok(())` si el cuerpo se guarda correctamente en el archivo.
@@ -300,11 +480,6 @@ impl HttpBody {
     /// * `ServerError::InvalidFormat` - Si el formato especificado no es compatible.
     ///
     pub fn save_body_to_file(&self, file_path: &str, application: &str) -> Result<(), ServerError> {
-        let mut file = match File::create(file_path) {
-            Ok(file) => file,
-            Err(_) => return Err(ServerError::SavePr),
-        };
-
         // Convertimos el cuerpo actual al formato especificado por `application`
         let serialized = match application {
             APPLICATION_JSON => match self {
@@ -322,6 +497,9 @@ impl HttpBody {
                 }
                 HttpBody::Yaml(yaml) => serde_json::to_string_pretty(yaml)
                     .map_err(|e| ServerError::Serialization(e.to_string()))?,
+                HttpBody::Multipart(_) => {
+                    return Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => return Err(ServerError::EmptyBody),
             },
             APPLICATION_XML => match self {
@@ -341,6 +519,9 @@ impl HttpBody {
                     xml_to_string(&json_value)
                         .map_err(|e| ServerError::Serialization(e.to_string()))?
                 }
+                HttpBody::Multipart(_) => {
+                    return Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => return Err(ServerError::EmptyBody),
             },
             APPLICATION_YAML => match self {
@@ -357,6 +538,9 @@ impl HttpBody {
                 }
                 HttpBody::Yaml(yaml) => serde_yaml::to_string(yaml)
                     .map_err(|e| ServerError::Serialization(e.to_string()))?,
+                HttpBody::Multipart(_) => {
+                    return Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => return Err(ServerError::EmptyBody),
             },
             _ => {
@@ -366,11 +550,8 @@ impl HttpBody {
             }
         };
 
-        // Guardamos el contenido serializado en el archivo
-        if file.write_all(serialized.as_bytes()).is_err() {
-            return Err(ServerError::SavePr);
-        }
-        Ok(())
+        // Guardamos el contenido serializado en el archivo, de forma atómica
+        write_file_atomic(file_path, &serialized).map_err(|_| ServerError::SavePr)
     }
 
     pub fn from_string(application: &str, message: &str, key: &str) -> Result<Self, ServerError> {
@@ -422,6 +603,9 @@ impl HttpBody {
                         .map_err(|e| ServerError::Serialization(e.to_string()))?;
                     Ok(HttpBody::Json(json_value))
                 }
+                HttpBody::Multipart(_) => {
+                    Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => Ok(HttpBody::Empty),
             },
             APPLICATION_XML | TEXT_XML => match body {
@@ -438,6 +622,9 @@ impl HttpBody {
                         .map_err(|e| ServerError::Serialization(e.to_string()))?;
                     Ok(HttpBody::Xml(xml_value))
                 }
+                HttpBody::Multipart(_) => {
+                    Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => Ok(HttpBody::Empty),
             },
             APPLICATION_YAML | TEXT_YAML => match body {
@@ -460,6 +647,9 @@ impl HttpBody {
                     Ok(HttpBody::Yaml(yaml_value))
                 }
                 HttpBody::Yaml(_) => Ok(body), // Ya está en formato YAML
+                HttpBody::Multipart(_) => {
+                    Err(ServerError::InvalidFormat(MULTIPART_FORM_DATA.to_string()))
+                }
                 HttpBody::Empty => Ok(HttpBody::Empty),
             },
             _ => Err(ServerError::InvalidFormat(
@@ -480,7 +670,7 @@ impl HttpBody {
                     serde_json::from_value(serde_json::to_value(yaml).unwrap()).unwrap();
                 map[key].clone()
             }
-            HttpBody::Empty => JsonValue::Null,
+            HttpBody::Multipart(_) | HttpBody::Empty => JsonValue::Null,
         }
     }
 
@@ -526,3 +716,67 @@ impl HttpBody {
     //     Ok(result)
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multipart_form_data() {
+        let content_type = "multipart/form-data; boundary=limite";
+        let body = "preámbulo\r\n--limite\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nNuevo PR\r\n--limite\r\nContent-Disposition: form-data; name=\"adjunto\"; filename=\"notas.txt\"\r\nContent-Type: text/plain\r\n\r\nlínea 1\r\nlínea 2\r\n--limite--\r\n";
+        let body = HttpBody::parse(content_type, body).expect("Falló al parsear el cuerpo");
+
+        assert_eq!(body.get_field("title"), Ok("Nuevo PR".to_string()));
+        let file = body
+            .get_file_part("adjunto")
+            .expect("Falló al obtener el archivo");
+        assert_eq!(file.filename, Some("notas.txt".to_string()));
+        assert_eq!(file.content_type, Some("text/plain".to_string()));
+        assert_eq!(file.content, "línea 1\r\nlínea 2".as_bytes());
+        assert!(body.get_file_part("title").is_err());
+    }
+
+    #[test]
+    fn test_parse_multipart_binary_content_with_boundary() {
+        let content_type = "multipart/form-data; boundary=limite";
+        let mut body =
+            b"--limite\r\nContent-Disposition: form-data; name=\"bin\"; filename=\"a.bin\"\r\n\r\n"
+                .to_vec();
+        let content = [
+            0xff, 0x00, b'-', b'-', b'l', b'i', b'm', b'i', b't', b'e', 0xfe,
+        ];
+        body.extend_from_slice(&content);
+        body.extend_from_slice(b"\r\n--limite--\r\n");
+
+        let body = HttpBody::parse_bytes(content_type, &body).expect("Falló al parsear el cuerpo");
+        let file = body
+            .get_file_part("bin")
+            .expect("Falló al obtener el archivo");
+
+        assert_eq!(file.content, content);
+    }
+
+    #[test]
+    fn test_parse_malformed_multipart_form_data() {
+        let part = "--limite\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nNuevo PR\r\n";
+        // Sin delimitador de cierre
+        assert_eq!(
+            HttpBody::parse("multipart/form-data; boundary=limite", part),
+            Err(ServerError::InvalidBody)
+        );
+        // Sin boundary
+        assert_eq!(
+            HttpBody::parse("multipart/form-data", &format!("{}--limite--", part)),
+            Err(ServerError::InvalidBody)
+        );
+        // Parte sin nombre
+        assert_eq!(
+            HttpBody::parse(
+                "multipart/form-data; boundary=limite",
+                "--limite\r\nContent-Type: text/plain\r\n\r\nhola\r\n--limite--"
+            ),
+            Err(ServerError::InvalidBody)
+        );
+    }
+}
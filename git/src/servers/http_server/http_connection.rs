@@ -1,9 +1,16 @@
+use super::auth::authorize_credentials;
+use super::git_http::{get_request_header, handle_git_http_request, GitHttpRequest};
 use super::http_request::HttpRequest;
+use super::method::Method;
+use super::rate_limiter::check_rate_limit;
 use super::status_code::StatusCode;
-use super::utils::send_response_http;
-use crate::consts::APPLICATION_SERVER;
+use super::utils::{read_request, send_response_http, send_response_http_with_headers};
+use crate::consts::{APPLICATION_SERVER, AUTHORIZATION, ETAG};
 use crate::errors::GitError;
+use crate::servers::errors::ServerError;
+use crate::servers::server_config::ServerConfig;
 use crate::util::logger::log_message_with_signature;
+use std::io::Read;
 use std::net::TcpStream;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
@@ -18,7 +25,7 @@ use std::sync::{Arc, Mutex};
 ///
 /// * `stream` - Un mutable referencia a un `TcpStream` que representa la conexión con el cliente.
 /// * `tx` - Un `Arc<Mutex<Sender<String>>>` que se utiliza para enviar mensajes de log.
-/// * `root_directory` - Un `String` que representa el directorio raíz del servidor.
+/// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
 ///
 /// # Retornos
 ///
@@ -28,55 +35,120 @@ pub fn handle_client_http(
     stream: &mut TcpStream,
     signature: String,
     tx: &Arc<Mutex<Sender<String>>>,
-    root_directory: String,
+    config: Arc<ServerConfig>,
 ) -> Result<(), GitError> {
-    let (request, status_code) = _handle_client_http(stream, root_directory, tx, &signature);
+    let raw_request = match read_request(stream) {
+        Ok(raw_request) => raw_request,
+        Err(_) => {
+            let status_code = StatusCode::BadRequest(ServerError::ReadRequest.to_string());
+            send_response_http(stream, &status_code, APPLICATION_SERVER)?;
+            return log_status_code(tx, &signature, status_code);
+        }
+    };
+
+    // Limito la cantidad de solicitudes por cliente
+    if let Ok(address) = stream.peer_addr() {
+        if let Err(status_code) = check_rate_limit(address.ip()) {
+            send_response_http(stream, &status_code, APPLICATION_SERVER)?;
+            return log_status_code(tx, &signature, status_code);
+        }
+    }
+
+    // Solicitudes del protocolo HTTP de Git (clone/fetch sobre HTTP). Solo leen repositorios,
+    // por lo que sus credenciales se validan como las de un `GET`
+    if let Some(git_request) = GitHttpRequest::parse(&raw_request) {
+        let authorization = get_request_header(&raw_request, AUTHORIZATION);
+        let root = config.get_root();
+        let status_code = match authorize_credentials(&Method::Get, authorization, root) {
+            Ok(principal) => handle_git_http_request(
+                stream,
+                git_request,
+                root,
+                principal.as_deref(),
+                tx,
+                &signature,
+            )?,
+            Err(status_code) => {
+                send_response_http(stream, &status_code, APPLICATION_SERVER)?;
+                status_code
+            }
+        };
+        return log_status_code(tx, &signature, status_code);
+    }
+
+    let (request, status_code, etag) =
+        _handle_client_http(&raw_request, stream, &config, tx, &signature);
     let content_type = match request {
-        Some(request) => request.get_content_type(),
+        Some(request) => request.get_response_content_type(),
         None => APPLICATION_SERVER.to_string(),
     };
 
-    let message = format!("Response sent to client with status code: {}", status_code);
-    log_message_with_signature(tx, &signature, &message);
+    let headers = match (&status_code, etag) {
+        (StatusCode::Ok(_) | StatusCode::NotModified, Some(etag)) => vec![(ETAG, etag)],
+        _ => Vec::new(),
+    };
+    send_response_http_with_headers(stream, &status_code, &content_type, &headers)?;
+    log_status_code(tx, &signature, status_code)
+}
 
-    send_response_http(stream, &status_code, &content_type)?;
+/// Loggea el código de estado con el que se respondió al cliente.
+///
+/// # Retornos
+///
+/// Retorna `Ok(())` si la respuesta fue exitosa o un `GitError::RequestFailed` en caso contrario.
+///
+fn log_status_code(
+    tx: &Arc<Mutex<Sender<String>>>,
+    signature: &str,
+    status_code: StatusCode,
+) -> Result<(), GitError> {
+    let message = format!("Response sent to client with status code: {}", status_code);
+    log_message_with_signature(tx, signature, &message);
 
     match status_code {
-        StatusCode::Ok(_) => Ok(()),
+        StatusCode::Ok(_) | StatusCode::NotModified => Ok(()),
         _ => Err(GitError::RequestFailed(status_code.to_string())),
     }
 }
 
 /// Maneja las solicitudes HTTP internas del cliente.
 ///
-/// Esta función auxiliar se encarga de crear la solicitud HTTP a partir del flujo de entrada,
+/// Esta función auxiliar se encarga de crear la solicitud HTTP a partir de la solicitud leída,
 /// y manejar la solicitud procesándola y retornando el código de estado apropiado.
 ///
 /// # Argumentos
 ///
-/// * `stream` - Un mutable referencia a un `TcpStream` que representa la conexión con el cliente.
-/// * `root_directory` - Un `String` que representa el directorio raíz del servidor.
+/// * `raw_request` - La parte de la solicitud HTTP ya leída del cliente.
+/// * `stream` - Conexión con el cliente, de la que se lee el resto del cuerpo.
+/// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
 /// * `tx` - Una referencia a un `Arc<Mutex<Sender<String>>>` que se utiliza para enviar mensajes de log.
 /// * `signature` - Una referencia a un `String` que contiene la firma del cliente.
 ///
 /// # Retornos
 ///
-/// Retorna un `Result<StatusCode, GitError>` indicando si la operación fue exitosa o si ocurrió un error.
+/// Retorna la solicitud, el código de estado con el que se debe responder y el ETag del
+/// recurso solicitado, si corresponde.
 ///
 pub fn _handle_client_http(
-    stream: &mut TcpStream,
-    root_directory: String,
+    raw_request: &str,
+    stream: &mut dyn Read,
+    config: &ServerConfig,
     tx: &Arc<Mutex<Sender<String>>>,
     signature: &str,
-) -> (Option<HttpRequest>, StatusCode) {
+) -> (Option<HttpRequest>, StatusCode, Option<String>) {
     // Creo la solicitud HTTP
-    let request = match HttpRequest::new_from_reader(stream) {
+    let request = match HttpRequest::new_from_stream(raw_request, stream) {
         Ok(request) => request,
-        Err(e) => return (None, e),
+        Err(e) => return (None, e, None),
     };
+    let etag = request.get_etag(config.get_root());
     // Manejar la solicitud HTTP
-    match request.handle_http_request(&root_directory, tx, signature) {
-        Ok(status_code) => (Some(request), status_code),
-        Err(e) => (Some(request), StatusCode::InternalError(e.to_string())),
+    match request.handle_http_request(config, tx, signature, etag.as_deref()) {
+        Ok(status_code) => (Some(request), status_code, etag),
+        Err(e) => (
+            Some(request),
+            StatusCode::InternalError(e.to_string()),
+            None,
+        ),
     }
 }
@@ -1,11 +1,23 @@
-use super::{http_body::HttpBody, method::Method, status_code::StatusCode, utils::read_request};
+use super::{
+    auth::authorize,
+    features_pr::{get_etag_list_pull_request, get_etag_pull_request},
+    http_body::HttpBody,
+    method::{segment_path, Method},
+    status_code::StatusCode,
+    utils::{is_chunked, read_chunked_body, read_remaining_body, read_request},
+};
 use crate::{
-    consts::{APPLICATION_JSON, APPLICATION_SERVER, CONTENT_LENGTH, CONTENT_TYPE, HTTP_VERSION},
-    servers::errors::ServerError,
+    consts::{
+        ACCEPT, APPLICATION_JSON, APPLICATION_SERVER, APPLICATION_XML, APPLICATION_YAML,
+        CONTENT_LENGTH, CONTENT_TYPE, CRLF_DOUBLE, HTTP_VERSION, IF_NONE_MATCH, TEXT_PLAIN,
+        TEXT_XML, TEXT_YAML, TRANSFER_ENCODING,
+    },
+    servers::{errors::ServerError, server_config::ServerConfig},
     util::logger::log_message_with_signature,
 };
 use std::{
     collections::HashMap,
+    io::{self, Read},
     sync::{mpsc::Sender, Arc, Mutex},
 };
 
@@ -63,12 +75,42 @@ impl HttpRequest {
     ///
     /// Retorna una nueva instancia de `HttpRequest`.
     ///
-    pub fn new_from_reader(reader: &mut dyn std::io::Read) -> Result<Self, StatusCode> {
+    pub fn new_from_reader(reader: &mut dyn Read) -> Result<Self, StatusCode> {
         let request = match read_request(reader) {
             Ok(request) => request,
             Err(_) => return Err(StatusCode::BadRequest(ServerError::ReadRequest.to_string())),
         };
-        parse_http_request(&request)
+        parse_http_request(&request, reader)
+    }
+
+    /// Crea una nueva instancia de `HttpRequest` a partir de la parte de la solicitud ya leída.
+    /// Si el cuerpo no llegó entero en esa lectura, el resto se sigue leyendo de la conexión.
+    ///
+    /// # Argumentos
+    ///
+    /// * `request` - La parte de la solicitud HTTP ya leída.
+    /// * `stream` - Conexión con el cliente.
+    ///
+    /// # Errores
+    ///
+    /// Retorna un `StatusCode` si la solicitud no es válida, informando el error.
+    ///
+    pub fn new_from_stream(request: &str, stream: &mut dyn Read) -> Result<Self, StatusCode> {
+        parse_http_request(request, stream)
+    }
+
+    /// Crea una nueva instancia de `HttpRequest` a partir de una solicitud ya leída.
+    ///
+    /// # Argumentos
+    ///
+    /// * `request` - La solicitud HTTP completa.
+    ///
+    /// # Errores
+    ///
+    /// Retorna un `StatusCode` si la solicitud no es válida, informando el error.
+    ///
+    pub fn new_from_string(request: &str) -> Result<Self, StatusCode> {
+        parse_http_request(request, &mut io::empty())
     }
 
     /// Maneja la solicitud HTTP y ejecuta la acción correspondiente.
@@ -76,8 +118,10 @@ impl HttpRequest {
     /// # Argumentos
     ///
     /// * `tx` - Un transmisor sincronizado para enviar mensajes.
-    /// * source - Una referencia a la cadena que contiene el directorio fuente.
+    /// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
     /// * `signature` - La firma del cliente.
+    /// * `etag` - El ETag actual del recurso solicitado, si corresponde. Si coincide con
+    ///   el encabezado `If-None-Match` se responde `NotModified`.
     ///
     /// # Errores
     ///
@@ -88,9 +132,10 @@ impl HttpRequest {
     /// Retorna un `Result` que contiene la respuesta en caso de éxito, o un `ServerError` en caso de error.
     pub fn handle_http_request(
         &self,
-        source: &String,
+        config: &ServerConfig,
         tx: &Arc<Mutex<Sender<String>>>,
         signature: &str,
+        etag: Option<&str>,
     ) -> Result<StatusCode, ServerError> {
         // Manejar la solicitud HTTP
         let message = format!("{} request to path: {}", self.method, self.path);
@@ -101,7 +146,58 @@ impl HttpRequest {
             Err(_) => return Ok(StatusCode::MethodNotAllowed),
         };
 
-        method.handle_method(&self.path, &self.body, source, tx)
+        // Valido las credenciales antes de despachar la solicitud
+        let user = match authorize(&method, &self.headers, config.get_root()) {
+            Ok(user) => user,
+            Err(status_code) => {
+                log_message_with_signature(tx, signature, &status_code.to_string());
+                return Ok(status_code);
+            }
+        };
+
+        if let Some(etag) = etag {
+            if self.matches_if_none_match(etag) {
+                return Ok(StatusCode::NotModified);
+            }
+        }
+
+        method.handle_method(&self.path, &self.body, config, user.as_deref(), tx)
+    }
+
+    /// Calcula el ETag del recurso solicitado. Solo los endpoints de lectura de
+    /// pull requests tienen ETag.
+    ///
+    /// # Argumentos
+    ///
+    /// * `source` - El directorio fuente del servidor.
+    ///
+    pub fn get_etag(&self, source: &str) -> Option<String> {
+        if self.method != Method::Get.to_string() {
+            return None;
+        }
+        match segment_path(&self.path).as_slice() {
+            ["repos", repo_name, "pulls"] => get_etag_list_pull_request(repo_name, source),
+            ["repos", repo_name, "pulls", pull_number] => {
+                get_etag_pull_request(repo_name, pull_number, source)
+            }
+            _ => None,
+        }
+    }
+
+    /// Indica si alguno de los ETags del encabezado `If-None-Match` coincide con el indicado.
+    fn matches_if_none_match(&self, etag: &str) -> bool {
+        let if_none_match = self
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(IF_NONE_MATCH))
+            .map(|(_, value)| value.as_str());
+        match if_none_match {
+            Some(value) => value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag),
+            None => false,
+        }
     }
 
     /// Obtiene la ruta de la solicitud HTTP.
@@ -119,6 +215,38 @@ impl HttpRequest {
             .unwrap_or(&APPLICATION_SERVER.to_string())
             .to_string()
     }
+
+    /// Obtiene el tipo de contenido con el que se debe responder la solicitud.
+    ///
+    /// Se usa el primer tipo soportado del encabezado `Accept`, ignorando sus parámetros
+    /// (por ejemplo `;q=0.9`). Si no hay ninguno, se responde con el `Content-Type` de la
+    /// solicitud.
+    ///
+    /// # Retornos
+    ///
+    /// Devuelve el tipo de contenido de la respuesta.
+    pub fn get_response_content_type(&self) -> String {
+        let supported = [
+            APPLICATION_JSON,
+            APPLICATION_XML,
+            APPLICATION_YAML,
+            TEXT_XML,
+            TEXT_YAML,
+            TEXT_PLAIN,
+        ];
+        let accept = self
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(ACCEPT))
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default();
+        accept
+            .split(',')
+            .filter_map(|media_type| media_type.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase())
+            .find(|media_type| supported.contains(&media_type.as_str()))
+            .unwrap_or_else(|| self.get_content_type())
+    }
 }
 
 /// Parsea una solicitud HTTP en una instancia de `HttpRequest`.
@@ -143,7 +271,7 @@ impl HttpRequest {
 /// Retorna un `Result` que contiene una instancia de `HttpRequest` en caso de éxito, o un `StatusCode`
 /// en caso de error.
 ///
-fn parse_http_request(request: &str) -> Result<HttpRequest, StatusCode> {
+fn parse_http_request(request: &str, reader: &mut dyn Read) -> Result<HttpRequest, StatusCode> {
     let lines: Vec<&str> = request.lines().collect();
     if lines.is_empty() {
         return Err(StatusCode::BadRequest(
@@ -165,21 +293,25 @@ fn parse_http_request(request: &str) -> Result<HttpRequest, StatusCode> {
     let headers = parse_headers(&lines[1..header_end_index]);
 
     // Obtener el cuerpo de la solicitud
-    let body = parse_body(request, &headers)?;
+    let body = parse_body(request, &headers, reader)?;
 
     Ok(HttpRequest::new(method, path, body, headers))
 }
 
 /// Parsea el cuerpo de una solicitud HTTP basado en el tipo de contenido especificado en los encabezados.
 ///
+/// Si la solicitud se envió con `Transfer-Encoding: chunked`, primero se decodifican los chunks
+/// para obtener el cuerpo completo. Si no, el largo del cuerpo se toma de `Content-Length`.
+///
 /// Esta función toma una solicitud HTTP en forma de string y un `HashMap` de encabezados,
 /// y devuelve un `Result` que contiene un `HttpBody` en caso de éxito, o un `ServerError`
 /// en caso de error.
 ///
 /// # Argumentos
 ///
-/// * `request` - Una referencia a un string slice que contiene la solicitud HTTP completa.
+/// * `request` - Una referencia a un string slice que contiene la parte de la solicitud HTTP ya leída.
 /// * `headers` - Un `HashMap` que contiene los encabezados de la solicitud HTTP.
+/// * `reader` - Conexión de la que se lee el resto del cuerpo.
 ///
 /// # Retornos
 ///
@@ -192,20 +324,46 @@ fn parse_http_request(request: &str) -> Result<HttpRequest, StatusCode> {
 /// Esta función puede devolver un `ServerError` si:
 /// - El tipo de contenido especificado no es soportado.
 /// - Ocurre un error durante el parseo del cuerpo de la solicitud.
+/// - La codificación chunked del cuerpo es inválida.
 ///
-fn parse_body(request: &str, headers: &HashMap<String, String>) -> Result<HttpBody, StatusCode> {
+fn parse_body(
+    request: &str,
+    headers: &HashMap<String, String>,
+    reader: &mut dyn Read,
+) -> Result<HttpBody, StatusCode> {
     // Obtener el cuerpo de la solicitud
-    let finish = headers
-        .get(CONTENT_LENGTH)
-        .map(|v| v.parse::<usize>().unwrap_or(0))
-        .unwrap_or(0);
-    let body = &request[request.len() - finish..];
+    let chunked = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(TRANSFER_ENCODING))
+        .is_some_and(|(_, value)| is_chunked(value));
+    let (_, received) = request.split_once(CRLF_DOUBLE).unwrap_or((request, ""));
+    // Lo que no llegó en la primera lectura se sigue leyendo de la conexión
+    let body = if chunked {
+        read_chunked_body(&mut received.as_bytes().chain(reader))
+    } else {
+        let content_length = headers
+            .get(CONTENT_LENGTH)
+            .map(|v| v.parse::<usize>().unwrap_or(0))
+            .unwrap_or(0);
+        let mut body = received.as_bytes().to_vec();
+        read_remaining_body(reader, &mut body, content_length).map(|_| {
+            body.truncate(content_length);
+            body
+        })
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => return Err(StatusCode::BadRequest(e.to_string())),
+    };
 
     // Parsear el cuerpo de la solicitud
     let binding = APPLICATION_JSON.to_string();
     let content_type = headers.get(CONTENT_TYPE).unwrap_or(&binding);
-    match HttpBody::parse(content_type, body) {
+    match HttpBody::parse_bytes(content_type, &body) {
         Ok(body) => Ok(body),
+        Err(ServerError::InvalidBody) => {
+            Err(StatusCode::BadRequest(ServerError::InvalidBody.to_string()))
+        }
         Err(_) => Err(StatusCode::UnsupportedMediaType),
     }
 }
@@ -271,35 +429,100 @@ mod tests {
 
     #[test]
     fn test_parse_valid_request() {
-        let request_str = "POST /path HTTP/1.1\r\nContent-Length: 18\r\n\r\n{\"key\": \"value\"}";
+        let request_str = "POST /path HTTP/1.1\r\nContent-Length: 16\r\n\r\n{\"key\": \"value\"}";
         let expected_request = HttpRequest {
             method: "POST".to_string(),
             path: "/path".to_string(),
             body: HttpBody::Json(json!({"key": "value"})),
-            headers: [("Content-Length", "18")]
+            headers: [("Content-Length", "16")]
                 .iter()
                 .cloned()
                 .map(|(a, b)| (a.to_string(), b.to_string()))
                 .collect(),
         };
-        assert_eq!(parse_http_request(request_str).unwrap(), expected_request);
+        assert_eq!(
+            parse_http_request(request_str, &mut io::empty()).unwrap(),
+            expected_request
+        );
+    }
+
+    #[test]
+    fn test_parse_chunked_request() {
+        let request_str = "POST /repos/repo/pulls HTTP/1.1\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n10\r\n{\"title\": \"Nuevo\r\n18\r\n PR\", \"head\": \"feature\"}\r\n0\r\n\r\n";
+        let request = parse_http_request(request_str, &mut io::empty())
+            .expect("Falló al parsear la solicitud");
+        assert_eq!(
+            request.body,
+            HttpBody::Json(json!({"title": "Nuevo PR", "head": "feature"}))
+        );
+
+        let request_str = "POST /repos/repo/pulls HTTP/1.1\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\nxyz\r\n{}\r\n0\r\n\r\n";
+        assert!(matches!(
+            parse_http_request(request_str, &mut io::empty()),
+            Err(StatusCode::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_request_body_split_across_reads() {
+        // Solo el primer chunk llegó en la primera lectura, el resto sigue en la conexión
+        let request_str = "POST /repos/repo/pulls HTTP/1.1\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n10\r\n{\"title\": \"Nuevo\r\n";
+        let mut rest = "18\r\n PR\", \"head\": \"feature\"}\r\n0\r\n\r\n".as_bytes();
+        let request = HttpRequest::new_from_stream(request_str, &mut rest)
+            .expect("Falló al parsear la solicitud");
+        assert_eq!(
+            request.body,
+            HttpBody::Json(json!({"title": "Nuevo PR", "head": "feature"}))
+        );
+
+        let request_str = "POST /repos/repo/pulls HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 15\r\n\r\n{\"title\": ";
+        let mut rest = "\"PR\"}".as_bytes();
+        let request = HttpRequest::new_from_stream(request_str, &mut rest)
+            .expect("Falló al parsear la solicitud");
+        assert_eq!(request.body, HttpBody::Json(json!({"title": "PR"})));
+
+        // El cuerpo no llega entero antes de que se cierre la conexión
+        assert!(matches!(
+            HttpRequest::new_from_string(request_str),
+            Err(StatusCode::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_matches_if_none_match() {
+        let request_str = "GET /repos/repo/pulls HTTP/1.1\r\nIf-None-Match: \"a\", W/\"b\"\r\n\r\n";
+        let request = parse_http_request(request_str, &mut io::empty()).unwrap();
+        assert!(request.matches_if_none_match("\"a\""));
+        assert!(request.matches_if_none_match("\"b\""));
+        assert!(!request.matches_if_none_match("\"c\""));
+    }
+
+    #[test]
+    fn test_get_response_content_type() {
+        let request_str = "GET /repos/repo/pulls/1/files HTTP/1.1\r\nContent-Type: application/json\r\naccept: text/html, text/plain;q=0.9\r\n\r\n";
+        let request = parse_http_request(request_str, &mut io::empty()).unwrap();
+        assert_eq!(request.get_response_content_type(), TEXT_PLAIN);
+
+        let request_str = "GET /repos/repo/pulls/1/files HTTP/1.1\r\nContent-Type: application/xml\r\nAccept: */*\r\n\r\n";
+        let request = parse_http_request(request_str, &mut io::empty()).unwrap();
+        assert_eq!(request.get_response_content_type(), APPLICATION_XML);
     }
 
     #[test]
     fn test_parse_empty_request() {
         let request_str = "";
-        assert!(parse_http_request(request_str).is_err());
+        assert!(parse_http_request(request_str, &mut io::empty()).is_err());
     }
 
     #[test]
     fn test_parse_invalid_request_line() {
         let request_str = "GET";
-        assert!(parse_http_request(request_str).is_err());
+        assert!(parse_http_request(request_str, &mut io::empty()).is_err());
     }
 
     #[test]
     fn test_parse_invalid_json_body() {
         let request_str = "POST /path HTTP/1.1\r\nContent-Length: 18\r\n\r\n{\"key\": \"value\"";
-        assert!(parse_http_request(request_str).is_err());
+        assert!(parse_http_request(request_str, &mut io::empty()).is_err());
     }
 }
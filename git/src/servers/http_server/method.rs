@@ -1,11 +1,12 @@
-use crate::servers::errors::ServerError;
+use crate::servers::{errors::ServerError, server_config::ServerConfig};
 use std::fmt;
 use std::sync::{mpsc::Sender, Arc, Mutex};
 
 use super::{
     features_pr::{
-        create_pull_requests, delete_pull_request, get_pull_request, list_commits,
-        list_pull_request, merge_pull_request, modify_pull_request,
+        check_merge_pull_request, create_pull_requests, create_repository, delete_pull_request,
+        get_commit_graph, get_files_pull_request, get_pull_request, list_commits,
+        list_pull_request, list_repos, merge_pull_request, modify_pull_request,
     },
     http_body::HttpBody,
     status_code::StatusCode,
@@ -50,7 +51,8 @@ impl Method {
     ///
     /// * `path` - La ruta del recurso solicitado.
     /// * `http_body` - El cuerpo de la solicitud HTTP.
-    /// * `src` - La dirección de origen de la solicitud.
+    /// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
+    /// * `user` - El usuario autenticado de la solicitud, si lo hay.
     /// * `tx` - Un canal para enviar respuestas.
     ///
     /// # Retorna
@@ -62,13 +64,15 @@ impl Method {
         &self,
         path: &str,
         http_body: &HttpBody,
-        src: &String,
+        config: &ServerConfig,
+        user: Option<&str>,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
+        let src = config.get_root();
         match self {
             Method::Get => self.handle_get_request(path, src, tx),
-            Method::Post => self.handle_post_request(path, http_body, src, tx),
-            Method::Put => self.handle_put_request(path, src, tx),
+            Method::Post => self.handle_post_request(path, http_body, config, tx),
+            Method::Put => self.handle_put_request(path, src, user, tx),
             Method::Patch => self.handle_patch_request(path, http_body, src, tx),
             Method::Delete => self.handle_delete_request(path, src, tx),
         }
@@ -92,15 +96,38 @@ impl Method {
         src: &String,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
+        let (path, query) = match path.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (path, ""),
+        };
         let path_segments: Vec<&str> = segment_path(path);
         match path_segments.as_slice() {
-            ["repos", repo_name, "pulls"] => list_pull_request(repo_name, src, tx),
+            ["repos"] => list_repos(src, tx),
+            ["repos", repo_name, "commits", "graph"] => {
+                let branch = get_query_param(query, "branch");
+                let limit = get_query_param(query, "limit");
+                get_commit_graph(repo_name, branch.as_deref(), limit.as_deref(), src, tx)
+            }
+            ["repos", repo_name, "pulls"] => {
+                let label = get_query_param(query, "label");
+                list_pull_request(repo_name, src, label.as_deref(), tx)
+            }
             ["repos", repo_name, "pulls", pull_number] => {
-                get_pull_request(repo_name, pull_number, src, tx)
+                let include_stats = matches!(
+                    get_query_param(query, "include_stats").as_deref(),
+                    Some("true" | "1")
+                );
+                get_pull_request(repo_name, pull_number, src, include_stats, tx)
             }
             ["repos", repo_name, "pulls", pull_number, "commits"] => {
                 list_commits(repo_name, pull_number, src, tx)
             }
+            ["repos", repo_name, "pulls", pull_number, "files"] => {
+                get_files_pull_request(repo_name, pull_number, src, tx)
+            }
+            ["repos", repo_name, "pulls", pull_number, "merge-check"] => {
+                check_merge_pull_request(repo_name, pull_number, src, tx)
+            }
             _ => Ok(StatusCode::ResourceNotFound(
                 "The requested path was not found on the server.".to_string(),
             )),
@@ -112,7 +139,7 @@ impl Method {
     /// # Parámetros
     ///
     /// * `pr` - Una referencia a la estructura `PullRequest`.
-    /// * `src` - Una referencia a la cadena que contiene el directorio fuente.
+    /// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
     /// * `tx` - Un puntero compartido y seguro para subprocesos a un transmisor de mensajes.
     ///
     /// # Retornos
@@ -123,11 +150,19 @@ impl Method {
         &self,
         path: &str,
         http_body: &HttpBody,
-        src: &String,
+        config: &ServerConfig,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
+        let src = config.get_root();
         let path_segments: Vec<&str> = segment_path(path);
         match path_segments.as_slice() {
+            ["repos"] => {
+                let _tx_lock = match tx.lock() {
+                    Ok(lock) => lock,
+                    Err(_) => return Err(ServerError::BadRequest("Failed lock".to_string())),
+                };
+                create_repository(http_body, config, tx)
+            }
             ["repos", repo_name, "pulls"] => {
                 let _tx_lock = match tx.lock() {
                     Ok(lock) => lock,
@@ -156,17 +191,26 @@ impl Method {
     fn handle_put_request(
         &self,
         path: &str,
-        src: &String,
+        src: &str,
+        user: Option<&str>,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
         let path_segments: Vec<&str> = segment_path(path);
         match path_segments.as_slice() {
             ["repos", repo_name, "pulls", pull_number, "merge"] => {
+                let user = match user {
+                    Some(user) => user,
+                    None => {
+                        return Ok(StatusCode::Unauthorized(
+                            "Authentication required".to_string(),
+                        ))
+                    }
+                };
                 let _tx_lock = match tx.lock() {
                     Ok(lock) => lock,
                     Err(_) => return Err(ServerError::BadRequest("Failed lock".to_string())),
                 };
-                merge_pull_request(repo_name, pull_number, src, tx)
+                merge_pull_request(repo_name, pull_number, src, user, tx)
             }
             _ => Ok(StatusCode::ResourceNotFound(
                 "The requested path was not found on the server.".to_string(),
@@ -190,7 +234,7 @@ impl Method {
         &self,
         path: &str,
         http_body: &HttpBody,
-        src: &String,
+        src: &str,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
         let path_segments: Vec<&str> = segment_path(path);
@@ -211,7 +255,7 @@ impl Method {
     fn handle_delete_request(
         &self,
         path: &str,
-        src: &String,
+        src: &str,
         tx: &Arc<Mutex<Sender<String>>>,
     ) -> Result<StatusCode, ServerError> {
         let path_segments: Vec<&str> = segment_path(path);
@@ -243,6 +287,59 @@ impl fmt::Display for Method {
     }
 }
 
+/// Obtiene el valor de un parámetro de la query de una ruta (`clave=valor&...`).
+///
+/// # Argumentos
+///
+/// * `query` - La query de la ruta, sin el `?`.
+/// * `name` - El nombre del parámetro.
+///
+/// # Retorna
+///
+/// Retorna el valor decodificado del primer parámetro con ese nombre, o `None` si no está.
+///
+fn get_query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .find_map(|param| match param.split_once('=') {
+            Some((key, value)) if decode_query_component(key) == name => {
+                Some(decode_query_component(value))
+            }
+            _ => None,
+        })
+}
+
+/// Decodifica un componente de la query: las secuencias `%XX` se reemplazan por el byte que
+/// representan y los `+` por espacios. Las secuencias inválidas se dejan sin modificar.
+///
+/// # Argumentos
+///
+/// * `component` - La clave o el valor de un parámetro, tal como llega en la ruta.
+///
+fn decode_query_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match component
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
 /// Segmenta una ruta en partes separadas.
 ///
 /// Esta función toma una ruta de cadena y la divide en segmentos individuales,
@@ -264,3 +361,29 @@ pub fn segment_path(path: &str) -> Vec<&str> {
     }
     path.split('/').collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_query_param_decodes_values() {
+        let query = "label=good%20first+issue&branch=feature%2Fui&limit=5&bad=100%";
+
+        assert_eq!(
+            get_query_param(query, "label").as_deref(),
+            Some("good first issue")
+        );
+        assert_eq!(
+            get_query_param(query, "branch").as_deref(),
+            Some("feature/ui")
+        );
+        assert_eq!(get_query_param(query, "limit").as_deref(), Some("5"));
+        assert_eq!(get_query_param(query, "bad").as_deref(), Some("100%"));
+        assert_eq!(
+            get_query_param("label=espa%C3%B1ol", "label").as_deref(),
+            Some("español")
+        );
+        assert_eq!(get_query_param(query, "state"), None);
+    }
+}
@@ -1,6 +1,63 @@
-use crate::consts::{APPLICATION_JSON, APPLICATION_XML, APPLICATION_YAML, TEXT_XML, TEXT_YAML};
+use crate::consts::{
+    APPLICATION_JSON, APPLICATION_XML, APPLICATION_YAML, TEXT_PLAIN, TEXT_XML, TEXT_YAML,
+};
+use serde_json::json;
 
-use super::pr::{CommitsPr, PullRequest};
+use super::pr::{CommitsPr, FileStat, PullRequest};
+
+/// Resultado del análisis de mergeabilidad de un pull request.
+///
+/// - `mergeable`: si el pull request se puede mergear sin conflictos.
+/// - `conflicts`: paths de los archivos en conflicto.
+/// - `strategy`: `fast-forward`, `three-way` o `up-to-date`.
+#[derive(Debug, PartialEq)]
+pub struct MergeCheck {
+    pub mergeable: bool,
+    pub conflicts: Vec<String>,
+    pub strategy: String,
+}
+
+/// Diff de un archivo modificado por un pull request.
+///
+/// - `filename`: path del archivo.
+/// - `status`: `added`, `modified` o `removed`.
+/// - `binary`: si el archivo es binario; en ese caso no tiene hunks.
+/// - `hunks`: hunks del diff con el formato unificado, cada uno con su encabezado `@@`.
+/// - `truncated`: si se omitieron hunks por superar el límite de líneas del diff.
+#[derive(Debug, PartialEq)]
+pub struct FileDiff {
+    pub filename: String,
+    pub status: String,
+    pub binary: bool,
+    pub hunks: Vec<String>,
+    pub truncated: bool,
+}
+
+/// Diff de los archivos de un pull request.
+///
+/// - `files`: diff de cada archivo modificado, ordenados por path.
+/// - `truncated`: si se omitieron hunks de alguno de los archivos.
+#[derive(Debug, PartialEq)]
+pub struct Diff {
+    pub files: Vec<FileDiff>,
+    pub truncated: bool,
+}
+
+/// Commit del grafo de la historia de una branch.
+///
+/// - `hash`: hash del commit.
+/// - `parents`: hashes de todos sus parents; los merge commits tienen más de uno.
+/// - `author`: nombre del autor.
+/// - `date`: fecha del autor, o `unknown` si su timestamp no es válido.
+/// - `summary`: título del mensaje del commit.
+#[derive(Debug, PartialEq)]
+pub struct CommitNode {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub date: String,
+    pub summary: String,
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Model {
@@ -8,6 +65,11 @@ pub enum Model {
     // CommitsPr(HttpBody),
     ListPullRequest(Vec<PullRequest>),
     ListCommits(Vec<CommitsPr>),
+    ListRepositories(Vec<String>),
+    MergeConflict(Vec<String>),
+    MergeCheck(MergeCheck),
+    Diff(Diff),
+    CommitGraph(Vec<CommitNode>),
     Message(String),
     // Empty,
 }
@@ -17,6 +79,11 @@ impl Model {
         match self {
             Model::ListPullRequest(v) => list_pull_request_to_string(v, content_type),
             Model::ListCommits(v) => list_commits_to_string(v, content_type),
+            Model::ListRepositories(v) => list_repositories_to_string(v, content_type),
+            Model::MergeConflict(v) => merge_conflict_to_string(v, content_type),
+            Model::MergeCheck(c) => merge_check_to_string(c, content_type),
+            Model::Diff(d) => diff_to_string(d, content_type),
+            Model::CommitGraph(v) => commit_graph_to_string(v, content_type),
             Model::Message(s) => message_to_string(s, content_type),
         }
     }
@@ -32,14 +99,20 @@ fn pull_request_to_string(pr: &PullRequest, content_type: &str) -> String {
     let base = pr.base.clone().unwrap_or_default();
     let head = pr.head.clone().unwrap_or_default();
     let repo = pr.repo.clone().unwrap_or_default();
+    let labels = convert_vector_in_string(pr.labels.clone());
+    let assignees = convert_vector_in_string(pr.assignees.clone());
     let mergeable = pr.mergeable.clone().unwrap_or_default();
     let changed_files = convert_vector_in_string(pr.changed_files.clone().unwrap_or_default());
     let amount_commits = pr.amount_commits.unwrap_or_default();
     let commits = convert_vector_in_string(pr.commits.clone().unwrap_or_default());
+    let file_stats = match &pr.file_stats {
+        Some(stats) => file_stats_to_string(stats, content_type),
+        None => String::new(),
+    };
 
     match content_type {
         APPLICATION_JSON => {
-            result.push_str(&format!("{{\t\"id\": {},\n\t\"owner\": \"{}\",\n\t\"title\": \"{}\",\n\t\"body\": \"{}\",\n\t\"state\": \"{}\",\n\t\"base\": \"{}\",\n\t\"head\": \"{}\",\n\t\"repo\": \"{}\",\n\t\"mergeable\": {},\n\t\"changed_files\": {},\n\t\"amount_commits\": {},\n\t\"commits\": {}}}", id, owner, title, body, state, base, head, repo, mergeable, changed_files, amount_commits, commits));
+            result.push_str(&format!("{{\t\"id\": {},\n\t\"owner\": \"{}\",\n\t\"title\": \"{}\",\n\t\"body\": \"{}\",\n\t\"state\": \"{}\",\n\t\"base\": \"{}\",\n\t\"head\": \"{}\",\n\t\"repo\": \"{}\",\n\t\"labels\": {},\n\t\"assignees\": {},\n\t\"mergeable\": {},\n\t\"changed_files\": {},\n\t\"amount_commits\": {},\n\t\"commits\": {}{}}}", id, owner, title, body, state, base, head, repo, labels, assignees, mergeable, changed_files, amount_commits, commits, file_stats));
         }
         TEXT_XML | APPLICATION_XML => {
             result.push_str(&format!(
@@ -52,11 +125,13 @@ fn pull_request_to_string(pr: &PullRequest, content_type: &str) -> String {
                 \t<base>{}</base>\n\
                 \t<head>{}</head>\n\
                 \t<repo>{}</repo>\n\
+                \t<labels>{}</labels>\n\
+                \t<assignees>{}</assignees>\n\
                 \t<mergeable>{}</mergeable>\n\
                 \t<changed_files>{}</changed_files>\n\
                 \t<amount_commits>{}</amount_commits>\n\
                 \t<commits>{}</commits>\n\
-                </pull_request>",
+                {}</pull_request>",
                 id,
                 escape_xml(&owner),
                 escape_xml(&title),
@@ -65,10 +140,13 @@ fn pull_request_to_string(pr: &PullRequest, content_type: &str) -> String {
                 escape_xml(&base),
                 escape_xml(&head),
                 escape_xml(&repo),
+                escape_xml(&labels),
+                escape_xml(&assignees),
                 mergeable,
                 escape_xml(&changed_files),
                 amount_commits,
-                escape_xml(&commits)
+                escape_xml(&commits),
+                file_stats
             ));
         }
         TEXT_YAML | APPLICATION_YAML => {
@@ -81,10 +159,12 @@ fn pull_request_to_string(pr: &PullRequest, content_type: &str) -> String {
                 base: \"{}\"\n\
                 head: \"{}\"\n\
                 repo: \"{}\"\n\
+                labels: {}\n\
+                assignees: {}\n\
                 mergeable: {}\n\
                 changed_files: [{}]\n\
                 amount_commits: {}\n\
-                commits: [{}]",
+                commits: [{}]{}",
                 id,
                 owner,
                 title,
@@ -93,10 +173,13 @@ fn pull_request_to_string(pr: &PullRequest, content_type: &str) -> String {
                 base,
                 head,
                 repo,
+                labels,
+                assignees,
                 mergeable,
                 changed_files,
                 amount_commits,
-                commits
+                commits,
+                file_stats
             ));
         }
         _ => return "".to_string(),
@@ -174,6 +257,57 @@ fn commits_to_string(commit: &CommitsPr, content_type: &str) -> String {
     result
 }
 
+/// Formatea las estadísticas de los archivos de un pull request para agregarlas al final de su
+/// representación. Las cantidades de los archivos binarios se muestran como `-`.
+fn file_stats_to_string(stats: &[FileStat], content_type: &str) -> String {
+    let count = |value: Option<usize>| match value {
+        Some(value) => json!(value),
+        None => json!("-"),
+    };
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            let files: Vec<_> = stats
+                .iter()
+                .map(|stat| {
+                    json!({
+                        "filename": stat.filename,
+                        "additions": count(stat.additions),
+                        "deletions": count(stat.deletions),
+                    })
+                })
+                .collect();
+            let files = serde_json::to_string(&files).unwrap_or_default();
+            result.push_str(&format!(",\n\t\"file_stats\": {}", files));
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str("\t<file_stats>");
+            for stat in stats.iter() {
+                result.push_str(&format!(
+                    "<file><filename>{}</filename><additions>{}</additions><deletions>{}</deletions></file>",
+                    escape_xml(&stat.filename),
+                    stat.additions.map_or("-".to_string(), |value| value.to_string()),
+                    stat.deletions.map_or("-".to_string(), |value| value.to_string())
+                ));
+            }
+            result.push_str("</file_stats>\n");
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str("\nfile_stats:");
+            for stat in stats.iter() {
+                result.push_str(&format!(
+                    "\n  - filename: {}\n    additions: {}\n    deletions: {}",
+                    yaml_string(&stat.filename),
+                    count(stat.additions),
+                    count(stat.deletions)
+                ));
+            }
+        }
+        _ => {}
+    };
+    result
+}
+
 fn convert_vector_in_string(vec: Vec<String>) -> String {
     let mut result = String::new();
     result.push('[');
@@ -213,6 +347,28 @@ fn list_pull_request_to_string(prs: &[PullRequest], content_type: &str) -> Strin
                 result.push_str(&pull_request_to_string(pr, content_type));
             }
         }
+        TEXT_PLAIN => {
+            let rows = prs
+                .iter()
+                .map(|pr| {
+                    vec![
+                        format!("#{}", pr.id.unwrap_or_default()),
+                        pr.state.clone().unwrap_or_default(),
+                        format!(
+                            "{} <- {}",
+                            pr.base.clone().unwrap_or_default(),
+                            pr.head.clone().unwrap_or_default()
+                        ),
+                        pr.owner.clone().unwrap_or_default(),
+                        pr.title.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            result.push_str(&format_table(
+                &["ID", "STATE", "BRANCHES", "OWNER", "TITLE"],
+                rows,
+            ));
+        }
         _ => return "".to_string(),
     };
     result
@@ -244,6 +400,245 @@ fn list_commits_to_string(commits: &[CommitsPr], content_type: &str) -> String {
                 result.push_str(&commits_to_string(commit, content_type));
             }
         }
+        TEXT_PLAIN => {
+            let rows = commits
+                .iter()
+                .map(|commit| {
+                    vec![
+                        commit.sha_1.chars().take(7).collect(),
+                        commit.author_name.clone(),
+                        commit.date.clone(),
+                        commit
+                            .message
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                    ]
+                })
+                .collect();
+            result.push_str(&format_table(&["SHA", "AUTHOR", "DATE", "MESSAGE"], rows));
+        }
+        _ => return "".to_string(),
+    };
+    result
+}
+
+fn list_repositories_to_string(repositories: &[String], content_type: &str) -> String {
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            result.push_str(&format!(
+                "{{\n\t\"repos\": {}\n}}",
+                convert_vector_in_string(repositories.to_vec())
+            ));
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str("<repos>");
+            for repository in repositories.iter() {
+                result.push_str(&format!("<repo>{}</repo>", escape_xml(repository)));
+            }
+            result.push_str("</repos>");
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str("repos:\n");
+            for repository in repositories.iter() {
+                result.push_str(&format!("  - \"{}\"\n", repository));
+            }
+        }
+        _ => return "".to_string(),
+    };
+    result
+}
+
+fn merge_conflict_to_string(conflicts: &[String], content_type: &str) -> String {
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            result.push_str(&format!(
+                "{{\n\t\"mergeable\": false,\n\t\"conflicts\": {}\n}}",
+                convert_vector_in_string(conflicts.to_vec())
+            ));
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str("<merge><mergeable>false</mergeable><conflicts>");
+            for conflict in conflicts.iter() {
+                result.push_str(&format!("<file>{}</file>", escape_xml(conflict)));
+            }
+            result.push_str("</conflicts></merge>");
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str("mergeable: false\nconflicts:\n");
+            for conflict in conflicts.iter() {
+                result.push_str(&format!("  - \"{}\"\n", conflict));
+            }
+        }
+        _ => return "".to_string(),
+    };
+    result
+}
+
+fn merge_check_to_string(check: &MergeCheck, content_type: &str) -> String {
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            result.push_str(&format!(
+                "{{\n\t\"mergeable\": {},\n\t\"conflicts\": {},\n\t\"strategy\": \"{}\"\n}}",
+                check.mergeable,
+                convert_vector_in_string(check.conflicts.clone()),
+                check.strategy
+            ));
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str(&format!(
+                "<merge_check><mergeable>{}</mergeable><conflicts>",
+                check.mergeable
+            ));
+            for conflict in check.conflicts.iter() {
+                result.push_str(&format!("<file>{}</file>", escape_xml(conflict)));
+            }
+            result.push_str(&format!(
+                "</conflicts><strategy>{}</strategy></merge_check>",
+                check.strategy
+            ));
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str(&format!("mergeable: {}\nconflicts:\n", check.mergeable));
+            for conflict in check.conflicts.iter() {
+                result.push_str(&format!("  - \"{}\"\n", conflict));
+            }
+            result.push_str(&format!("strategy: {}\n", check.strategy));
+        }
+        _ => return "".to_string(),
+    };
+    result
+}
+
+/// Representa el diff de un pull request. Con `text/plain` se devuelve el patch con el formato
+/// de `git diff`; los archivos binarios se indican con `Binary files ... differ` y los
+/// truncados con una línea final.
+fn diff_to_string(diff: &Diff, content_type: &str) -> String {
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            let files: Vec<_> = diff
+                .files
+                .iter()
+                .map(|file| {
+                    json!({
+                        "filename": file.filename,
+                        "status": file.status,
+                        "binary": file.binary,
+                        "patch": file.hunks.concat(),
+                        "truncated": file.truncated,
+                    })
+                })
+                .collect();
+            let body = json!({ "files": files, "truncated": diff.truncated });
+            result.push_str(&serde_json::to_string_pretty(&body).unwrap_or_default());
+        }
+        TEXT_PLAIN => {
+            for file in diff.files.iter() {
+                result.push_str(&format!(
+                    "diff --git a/{} b/{}\n",
+                    file.filename, file.filename
+                ));
+                if file.binary {
+                    result.push_str(&format!(
+                        "Binary files a/{} and b/{} differ\n",
+                        file.filename, file.filename
+                    ));
+                    continue;
+                }
+                result.push_str(&file.hunks.concat());
+                if file.truncated {
+                    result.push_str("... diff truncated\n");
+                }
+            }
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str("<diff>");
+            for file in diff.files.iter() {
+                result.push_str(&format!(
+                    "<file><filename>{}</filename><status>{}</status><binary>{}</binary><patch>{}</patch><truncated>{}</truncated></file>",
+                    escape_xml(&file.filename),
+                    file.status,
+                    file.binary,
+                    escape_xml(&file.hunks.concat()),
+                    file.truncated
+                ));
+            }
+            result.push_str(&format!("<truncated>{}</truncated></diff>", diff.truncated));
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str("files:\n");
+            for file in diff.files.iter() {
+                result.push_str(&format!(
+                    "  - filename: \"{}\"\n    status: {}\n    binary: {}\n    truncated: {}\n    patch: |\n",
+                    file.filename, file.status, file.binary, file.truncated
+                ));
+                for line in file.hunks.concat().lines() {
+                    result.push_str(&format!("      {}\n", line));
+                }
+            }
+            result.push_str(&format!("truncated: {}\n", diff.truncated));
+        }
+        _ => return "".to_string(),
+    };
+    result
+}
+
+/// Representa el grafo de commits de una branch, del commit más reciente al más antiguo.
+fn commit_graph_to_string(commits: &[CommitNode], content_type: &str) -> String {
+    let mut result = String::new();
+    match content_type {
+        APPLICATION_JSON => {
+            let nodes: Vec<_> = commits
+                .iter()
+                .map(|commit| {
+                    json!({
+                        "hash": commit.hash,
+                        "parents": commit.parents,
+                        "author": commit.author,
+                        "date": commit.date,
+                        "summary": commit.summary,
+                    })
+                })
+                .collect();
+            let body = json!({ "commits": nodes });
+            result.push_str(&serde_json::to_string_pretty(&body).unwrap_or_default());
+        }
+        TEXT_XML | APPLICATION_XML => {
+            result.push_str("<commits>");
+            for commit in commits.iter() {
+                result.push_str(&format!("<commit><hash>{}</hash><parents>", commit.hash));
+                for parent in commit.parents.iter() {
+                    result.push_str(&format!("<parent>{}</parent>", parent));
+                }
+                result.push_str(&format!(
+                    "</parents><author>{}</author><date>{}</date><summary>{}</summary></commit>",
+                    escape_xml(&commit.author),
+                    escape_xml(&commit.date),
+                    escape_xml(&commit.summary)
+                ));
+            }
+            result.push_str("</commits>");
+        }
+        TEXT_YAML | APPLICATION_YAML => {
+            result.push_str("commits:\n");
+            for commit in commits.iter() {
+                result.push_str(&format!("  - hash: {}\n    parents:\n", commit.hash));
+                for parent in commit.parents.iter() {
+                    result.push_str(&format!("      - {}\n", parent));
+                }
+                result.push_str(&format!(
+                    "    author: {}\n    date: {}\n    summary: {}\n",
+                    yaml_string(&commit.author),
+                    yaml_string(&commit.date),
+                    yaml_string(&commit.summary)
+                ));
+            }
+        }
         _ => return "".to_string(),
     };
     result
@@ -261,14 +656,147 @@ fn message_to_string(message: &str, content_type: &str) -> String {
         TEXT_YAML | APPLICATION_YAML => {
             result.push_str(&format!("message: {}", message));
         }
+        TEXT_PLAIN => {
+            result.push_str(message);
+        }
         _ => return "".to_string(),
     };
     result
 }
 
+/// Formatea filas como una tabla de texto plano, con una línea de encabezado y las columnas
+/// alineadas a la izquierda. La última columna no se rellena para no dejar espacios al final.
+///
+/// # Argumentos
+///
+/// * `headers` - Los títulos de las columnas.
+/// * `rows` - Las filas de la tabla, con un valor por columna.
+///
+/// # Retorno
+///
+/// Retorna la tabla, con cada fila terminada en un salto de línea.
+fn format_table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows.iter() {
+        for (width, value) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    let headers = headers.iter().map(|header| header.to_string()).collect();
+    let mut result = String::new();
+    for row in std::iter::once(headers).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect();
+        result.push_str(line.join("  ").trim_end());
+        result.push('\n');
+    }
+    result
+}
+
+/// Escribe un valor como string de YAML entre comillas dobles. Las comillas, las barras
+/// invertidas y los caracteres de control se escapan con la misma sintaxis que en JSON, que es
+/// válida en YAML.
+fn yaml_string(input: &str) -> String {
+    serde_json::to_string(input).unwrap_or_default()
+}
+
 fn escape_xml(input: &str) -> String {
     input
         .replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff() -> Diff {
+        Diff {
+            files: vec![
+                FileDiff {
+                    filename: "a.txt".to_string(),
+                    status: "modified".to_string(),
+                    binary: false,
+                    hunks: vec!["@@ -1 +1 @@\n-uno\n+\"dos\"\n".to_string()],
+                    truncated: true,
+                },
+                FileDiff {
+                    filename: "imagen.bin".to_string(),
+                    status: "added".to_string(),
+                    binary: true,
+                    hunks: Vec::new(),
+                    truncated: false,
+                },
+            ],
+            truncated: true,
+        }
+    }
+
+    #[test]
+    fn test_diff_to_string_text() {
+        assert_eq!(
+            Model::Diff(diff()).to_string(TEXT_PLAIN),
+            "diff --git a/a.txt b/a.txt\n@@ -1 +1 @@\n-uno\n+\"dos\"\n... diff truncated\n\
+             diff --git a/imagen.bin b/imagen.bin\nBinary files a/imagen.bin and b/imagen.bin differ\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_to_string_json() {
+        let body: serde_json::Value =
+            serde_json::from_str(&Model::Diff(diff()).to_string(APPLICATION_JSON))
+                .expect("El diff no es un JSON válido");
+
+        assert_eq!(body["truncated"], true);
+        assert_eq!(body["files"][0]["patch"], "@@ -1 +1 @@\n-uno\n+\"dos\"\n");
+        assert_eq!(body["files"][0]["truncated"], true);
+        assert_eq!(body["files"][1]["binary"], true);
+        assert_eq!(body["files"][1]["status"], "added");
+    }
+
+    #[test]
+    fn test_file_stats_to_string_escapes_filenames() {
+        let stats = vec![FileStat {
+            filename: "dir\\\"raro\".txt".to_string(),
+            additions: None,
+            deletions: Some(3),
+        }];
+        let json = format!(
+            "{{\"id\": 1{}}}",
+            file_stats_to_string(&stats, APPLICATION_JSON)
+        );
+        let body: serde_json::Value =
+            serde_json::from_str(&json).expect("Las estadísticas no son un JSON válido");
+
+        assert_eq!(body["file_stats"][0]["filename"], "dir\\\"raro\".txt");
+        assert_eq!(body["file_stats"][0]["additions"], "-");
+        assert_eq!(body["file_stats"][0]["deletions"], 3);
+        assert!(file_stats_to_string(&stats, APPLICATION_YAML)
+            .contains("filename: \"dir\\\\\\\"raro\\\".txt\""));
+    }
+
+    #[test]
+    fn test_commit_graph_yaml_escapes_fields() {
+        let commits = vec![CommitNode {
+            hash: "a".repeat(40),
+            parents: vec!["b".repeat(40)],
+            author: "Juan \"el\" Pérez".to_string(),
+            date: "2024-01-01".to_string(),
+            summary: "fix: \\ raro\nsegunda línea".to_string(),
+        }];
+        let yaml = Model::CommitGraph(commits).to_string(APPLICATION_YAML);
+        let body: serde_yaml::Value =
+            serde_yaml::from_str(&yaml).expect("El grafo no es un YAML válido");
+
+        assert_eq!(body["commits"][0]["author"], "Juan \"el\" Pérez");
+        assert_eq!(body["commits"][0]["summary"], "fix: \\ raro\nsegunda línea");
+        assert_eq!(body["commits"][0]["parents"][0], "b".repeat(40).as_str());
+    }
+}
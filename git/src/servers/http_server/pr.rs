@@ -1,6 +1,9 @@
-use super::{http_body::HttpBody, utils::validate_branch_changes};
-use crate::consts::{APPLICATION_SERVER, OPEN};
+use super::{http_body::HttpBody, status_code::StatusCode, utils::validate_branch_changes};
+use crate::consts::{APPLICATION_SERVER, GIT_DIR, OPEN, REFS_HEADS};
+use crate::git_transport::references::read_packed_refs;
 use crate::servers::errors::ServerError;
+use crate::util::files::file_exists;
+use crate::util::validation::valid_ref_name;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -38,6 +41,16 @@ impl Default for CommitsPr {
     }
 }
 
+/// Cantidad de líneas agregadas y eliminadas de un archivo del pull request.
+///
+/// En los archivos binarios las cantidades son `None` y se muestran como `-`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct FileStat {
+    pub filename: String,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct PullRequest {
     pub id: Option<usize>,
@@ -48,6 +61,10 @@ pub struct PullRequest {
     pub head: Option<String>,
     pub base: Option<String>,
     pub state: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub assignees: Vec<String>,
 
     // Campos opcionales, estos no deben estar guardados en el archivo
     // del propio pr, solo se los completa por si se necesitan en algun
@@ -56,6 +73,8 @@ pub struct PullRequest {
     pub changed_files: Option<Vec<String>>,
     pub commits: Option<Vec<String>>,
     pub amount_commits: Option<usize>,
+    #[serde(skip)]
+    pub file_stats: Option<Vec<FileStat>>,
 }
 
 impl PullRequest {
@@ -79,6 +98,8 @@ impl PullRequest {
         let head = body.get_field("head").ok();
         let base = body.get_field("base").ok();
         let state = body.get_field("state").ok();
+        let labels = unique_values(body.get_array_field("labels").unwrap_or_default());
+        let assignees = unique_values(body.get_array_field("assignees").unwrap_or_default());
         let body = body.get_field("body").ok();
 
         Ok(PullRequest {
@@ -90,10 +111,13 @@ impl PullRequest {
             head,
             base,
             state,
+            labels,
+            assignees,
             mergeable: None,
             changed_files: None,
             commits: None,
             amount_commits: None,
+            file_stats: None,
         })
     }
 
@@ -152,6 +176,49 @@ impl PullRequest {
         Ok(has_changes)
     }
 
+    /// Verifica que las ramas `base` y `head` del pull request sean distintas y existan en el
+    /// repositorio.
+    ///
+    /// # Argumentos
+    ///
+    /// * `directory` - Ruta del repositorio del pull request.
+    ///
+    /// # Errores
+    ///
+    /// Retorna `StatusCode::ValidationFailed` si falta alguna de las ramas, si son la misma rama
+    /// o si alguna no existe en el repositorio.
+    ///
+    pub fn validate(&self, directory: &str) -> Result<(), StatusCode> {
+        let base = self.get_base().ok_or_else(|| {
+            StatusCode::ValidationFailed("The base branch is required.".to_string())
+        })?;
+        let head = self.get_head().ok_or_else(|| {
+            StatusCode::ValidationFailed("The head branch is required.".to_string())
+        })?;
+        if base == head {
+            return Err(StatusCode::ValidationFailed(
+                "The base and head branches must be different.".to_string(),
+            ));
+        }
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        let packed_refs = read_packed_refs(&git_dir);
+        for (kind, branch) in [("base", base), ("head", head)] {
+            let ref_path = format!("{}/{}", REFS_HEADS, branch);
+            // La branch puede estar suelta o empaquetada en packed-refs
+            let exists = file_exists(&format!("{}/{}", git_dir, ref_path))
+                || packed_refs
+                    .iter()
+                    .any(|packed| *packed.reference.get_ref_path() == ref_path);
+            if valid_ref_name(branch).is_err() || !exists {
+                return Err(StatusCode::ValidationFailed(format!(
+                    "The {} branch '{}' does not exist.",
+                    kind, branch
+                )));
+            }
+        }
+        Ok(())
+    }
+
     pub fn change_state(&mut self, new_state: &str) {
         self.state = Some(new_state.to_string());
     }
@@ -168,6 +235,9 @@ impl PullRequest {
     pub fn set_commits(&mut self, commits: Vec<String>) {
         self.commits = Some(commits);
     }
+    pub fn set_file_stats(&mut self, file_stats: Vec<FileStat>) {
+        self.file_stats = Some(file_stats);
+    }
 
     pub fn is_open(&self) -> bool {
         self.state.as_deref() == Some(OPEN)
@@ -207,6 +277,20 @@ impl PullRequest {
         self.base = Some(new_base.to_string());
     }
 
+    /// Reemplaza las etiquetas del pull request, descartando las repetidas.
+    pub fn set_labels(&mut self, labels: Vec<String>) {
+        self.labels = unique_values(labels);
+    }
+
+    /// Reemplaza los usuarios asignados al pull request, descartando los repetidos.
+    pub fn set_assignees(&mut self, assignees: Vec<String>) {
+        self.assignees = unique_values(assignees);
+    }
+
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l == label)
+    }
+
     pub fn get_id(&self) -> Option<usize> {
         self.id
     }
@@ -217,3 +301,99 @@ impl PullRequest {
         }
     }
 }
+
+/// Descarta los valores vacíos y repetidos, conservando el orden de aparición.
+fn unique_values(values: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for value in values {
+        if !value.is_empty() && !result.contains(&value) {
+            result.push(value);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        add::git_add, branch::git_branch_create, commit::git_commit, init::git_init,
+    };
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+    use std::fs;
+
+    fn pull_request(base: &str, head: &str) -> PullRequest {
+        PullRequest {
+            base: Some(base.to_string()),
+            head: Some(head.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn validation_message(result: Result<(), StatusCode>) -> String {
+        match result {
+            Err(StatusCode::ValidationFailed(message)) => message,
+            other => panic!("Se esperaba ValidationFailed: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_pull_request_branches() {
+        let directory = "./test_pr_validate";
+        git_init(directory).expect("Falló al crear el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "a.txt").expect("Falló al agregar el archivo");
+        let commit = test_commit("primero");
+        git_commit(directory, commit).expect("Falló al hacer el commit");
+        git_branch_create(directory, "feature").expect("Falló al crear la branch");
+        let hash = fs::read_to_string(format!("{}/.git/refs/heads/master", directory))
+            .expect("Falló al leer la branch");
+        fs::write(
+            format!("{}/.git/packed-refs", directory),
+            format!(
+                "# pack-refs with: peeled\n{} refs/heads/packed\n",
+                hash.trim()
+            ),
+        )
+        .expect("Falló al crear packed-refs");
+
+        let valid = pull_request("master", "feature").validate(directory);
+        let packed = pull_request("packed", "feature").validate(directory);
+        let same = pull_request("master", "master").validate(directory);
+        let missing_base = pull_request("develop", "feature").validate(directory);
+        let missing_head = pull_request("master", "fix").validate(directory);
+        let traversal = pull_request("../../HEAD", "feature").validate(directory);
+        let without_head = PullRequest {
+            base: Some("master".to_string()),
+            ..Default::default()
+        }
+        .validate(directory);
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(valid, Ok(()));
+        assert_eq!(packed, Ok(()));
+        assert_eq!(
+            validation_message(same),
+            "The base and head branches must be different."
+        );
+        assert_eq!(
+            validation_message(missing_base),
+            "The base branch 'develop' does not exist."
+        );
+        assert_eq!(
+            validation_message(missing_head),
+            "The head branch 'fix' does not exist."
+        );
+        assert_eq!(
+            validation_message(traversal),
+            "The base branch '../../HEAD' does not exist."
+        );
+        assert_eq!(
+            validation_message(without_head),
+            "The head branch is required."
+        );
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::servers::errors::ServerError;
+use crate::util::files::write_file_atomic;
 
 use super::http_body::HttpBody;
 
@@ -14,6 +15,8 @@ use super::http_body::HttpBody;
 /// especificado por `pr_map_path`. Esto se utiliza para almacenar de manera persistente el estado actual
 /// del mapa de pull requests, que mapea claves hash únicas a números de pull requests.
 ///
+/// La escritura es atómica: si se interrumpe, el mapa anterior sigue siendo legible.
+///
 /// # Argumentos
 ///
 /// * `pr_map_path` - La ruta del archivo donde se debe guardar el mapa de pull requests.
@@ -28,7 +31,7 @@ use super::http_body::HttpBody;
 pub fn save_pr_map(pr_map_path: &str, pr_map: &HashMap<String, usize>) -> Result<(), ServerError> {
     let file_content =
         serde_json::to_string_pretty(pr_map).map_err(|_| ServerError::SaveMapPrFile)?;
-    std::fs::write(pr_map_path, file_content).map_err(|_| ServerError::SaveMapPrFile)?;
+    write_file_atomic(pr_map_path, &file_content).map_err(|_| ServerError::SaveMapPrFile)?;
     Ok(())
 }
 
@@ -58,6 +61,8 @@ pub fn generate_head_base_hash(head: &str, base: &str) -> String {
 ///
 /// Esta función carga el contenido de un archivo JSON que contiene el mapa de pull requests, lo deserializa
 /// y lo devuelve como un `HashMap`. Si el archivo no existe o está vacío, se devuelve un mapa vacío.
+/// Solo se lee el archivo del mapa: un temporal que haya quedado de una escritura interrumpida
+/// se ignora.
 ///
 /// # Argumentos
 ///
@@ -193,3 +198,39 @@ pub fn delete_pr_map(
     save_pr_map(pr_map_path, pr_map)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::TEMP_FILE_SUFFIX;
+    use std::fs;
+
+    #[test]
+    fn test_pr_map_survives_interrupted_write() {
+        let directory = "./test_pr_map_atomic";
+        fs::create_dir_all(directory).expect("Falló al crear el directorio");
+        let pr_map_path = format!("{}/pr_map.json", directory);
+        let temp_path = format!("{}{}", pr_map_path, TEMP_FILE_SUFFIX);
+        let mut pr_map = HashMap::new();
+        update_pr_map(&mut pr_map, &pr_map_path, "abc".to_string(), 1)
+            .expect("Falló al guardar el mapa");
+
+        // Escritura interrumpida: el temporal queda a medio escribir y nunca se renombra
+        fs::write(&temp_path, "{\n  \"abc\": 1,\n  \"de").expect("Falló al escribir");
+        let read_after_crash = read_pr_map(&pr_map_path);
+
+        update_pr_map(&mut pr_map, &pr_map_path, "def".to_string(), 2)
+            .expect("Falló al guardar el mapa");
+        let read_after_save = read_pr_map(&pr_map_path);
+        let temp_exists = fs::metadata(&temp_path).is_ok();
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            read_after_crash,
+            Ok(HashMap::from([("abc".to_string(), 1)]))
+        );
+        assert_eq!(read_after_save, Ok(pr_map));
+        assert!(!temp_exists);
+    }
+}
@@ -0,0 +1,171 @@
+use super::status_code::StatusCode;
+use crate::consts::{
+    RATE_LIMIT_BURST_DEFAULT, RATE_LIMIT_PRUNE_INTERVAL, RATE_LIMIT_REFILL_DEFAULT,
+};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Limitador global de solicitudes del servidor HTTP, compartido por todas las conexiones.
+static RATE_LIMITER: OnceLock<Mutex<RateLimiter>> = OnceLock::new();
+
+/// Balde de tokens de un cliente.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Limitador de solicitudes por IP utilizando el algoritmo de token bucket.
+///
+/// Cada cliente cuenta con hasta `burst` tokens que se recargan a razón de
+/// `refill_rate` tokens por segundo; cada solicitud consume un token.
+///
+#[derive(Debug)]
+pub struct RateLimiter {
+    refill_rate: f64,
+    burst: f64,
+    buckets: HashMap<IpAddr, Bucket>,
+    last_prune: Instant,
+}
+
+impl RateLimiter {
+    /// Crea un nuevo limitador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `refill_rate` - Tokens que se recargan por segundo.
+    /// * `burst` - Cantidad máxima de tokens que puede acumular un cliente.
+    ///
+    pub fn new(refill_rate: u32, burst: u32) -> Self {
+        RateLimiter {
+            refill_rate: refill_rate.max(1) as f64,
+            burst: burst.max(1) as f64,
+            buckets: HashMap::new(),
+            last_prune: Instant::now(),
+        }
+    }
+
+    /// Consume un token del cliente indicado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ip` - Dirección IP del cliente.
+    /// * `now` - Instante de la solicitud.
+    ///
+    /// # Retornos
+    ///
+    /// Retorna `Ok(())` si la solicitud está permitida, o `Err` con los segundos que el
+    /// cliente debe esperar para volver a intentarlo.
+    ///
+    pub fn check(&mut self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        if now.duration_since(self.last_prune) >= Duration::from_secs(RATE_LIMIT_PRUNE_INTERVAL) {
+            self.prune(now);
+        }
+        let (refill_rate, burst) = (self.refill_rate, self.burst);
+        let bucket = self.buckets.entry(ip).or_insert(Bucket {
+            tokens: burst,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_rate).min(burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let wait = (1.0 - bucket.tokens) / refill_rate;
+        Err(wait.ceil().max(1.0) as u64)
+    }
+
+    /// Elimina los baldes de los clientes inactivos, es decir, aquellos que ya
+    /// habrían recargado todos sus tokens.
+    fn prune(&mut self, now: Instant) {
+        let full_refill = Duration::from_secs_f64(self.burst / self.refill_rate);
+        self.buckets
+            .retain(|_, bucket| now.duration_since(bucket.last_refill) < full_refill);
+        self.last_prune = now;
+    }
+}
+
+/// Configura el limitador global del servidor HTTP.
+///
+/// Debe llamarse antes de atender la primera solicitud; si no se llama se utilizan
+/// los valores por defecto.
+///
+/// # Argumentos
+///
+/// * `refill_rate` - Tokens que se recargan por segundo.
+/// * `burst` - Cantidad máxima de tokens que puede acumular un cliente.
+///
+pub fn init_rate_limiter(refill_rate: u32, burst: u32) {
+    let _ = RATE_LIMITER.set(Mutex::new(RateLimiter::new(refill_rate, burst)));
+}
+
+/// Verifica si el cliente puede realizar una nueva solicitud.
+///
+/// # Argumentos
+///
+/// * `ip` - Dirección IP del cliente.
+///
+/// # Retornos
+///
+/// Retorna `StatusCode::TooManyRequests` con los segundos de espera si se excedió el límite.
+///
+pub fn check_rate_limit(ip: IpAddr) -> Result<(), StatusCode> {
+    let limiter = RATE_LIMITER.get_or_init(|| {
+        Mutex::new(RateLimiter::new(
+            RATE_LIMIT_REFILL_DEFAULT,
+            RATE_LIMIT_BURST_DEFAULT,
+        ))
+    });
+    let mut limiter = match limiter.lock() {
+        Ok(limiter) => limiter,
+        Err(_) => return Err(StatusCode::InternalError("Failed lock".to_string())),
+    };
+    match limiter.check(ip, Instant::now()) {
+        Ok(_) => Ok(()),
+        Err(retry_after) => Err(StatusCode::TooManyRequests(retry_after)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_rate_limiter_burst_and_refill() {
+        let mut limiter = RateLimiter::new(1, 2);
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let now = Instant::now();
+
+        assert_eq!(limiter.check(ip, now), Ok(()));
+        assert_eq!(limiter.check(ip, now), Ok(()));
+        assert_eq!(limiter.check(ip, now), Err(1));
+
+        // Otro cliente tiene su propio balde
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(limiter.check(other, now), Ok(()));
+
+        assert_eq!(limiter.check(ip, now + Duration::from_secs(1)), Ok(()));
+    }
+
+    #[test]
+    fn test_rate_limiter_prunes_idle_clients() {
+        let mut limiter = RateLimiter::new(1, 2);
+        let now = Instant::now();
+        limiter
+            .check(IpAddr::V4(Ipv4Addr::LOCALHOST), now)
+            .expect("Falló al consumir un token");
+        assert_eq!(limiter.buckets.len(), 1);
+
+        let later = now + Duration::from_secs(RATE_LIMIT_PRUNE_INTERVAL);
+        limiter
+            .check(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), later)
+            .expect("Falló al consumir un token");
+        assert_eq!(limiter.buckets.len(), 1);
+    }
+}
@@ -19,10 +19,12 @@ pub enum StatusCode {
     ServiceUnavailable,
     MergeWasSuccessful,
     MethodNotAllowed,
-    Conflict(String),
+    Conflict(Model),
     BadRequest(String),
     UnsupportedMediaType,
     HttpVersionNotSupported,
+    Unauthorized(String),
+    TooManyRequests(u64),
 }
 
 impl fmt::Display for StatusCode {
@@ -50,6 +52,8 @@ impl fmt::Display for StatusCode {
             StatusCode::BadRequest(_) => "400 Bad Request",
             StatusCode::UnsupportedMediaType => "415 Unsupported Media Type",
             StatusCode::HttpVersionNotSupported => "505 HTTP Version Not Supported",
+            StatusCode::Unauthorized(_) => "401 Unauthorized",
+            StatusCode::TooManyRequests(_) => "429 Too Many Requests",
         };
         write!(f, "{}", s)
     }
@@ -77,6 +81,7 @@ impl From<ServerError> for StatusCode {
                 StatusCode::BadRequest(format!("Field not found: {}", e))
             }
             ServerError::EmptyBody => StatusCode::BadRequest("Empty body".to_string()),
+            ServerError::PathOutsideRoot(e) => StatusCode::Forbidden(e),
             _ => StatusCode::InternalError("Internal server error".to_string()),
         }
     }
@@ -2,16 +2,19 @@ use super::{
     features_pr::get_commits_pr, http_body::HttpBody, model::Model, status_code::StatusCode,
 };
 use crate::{
-    consts::{APPLICATION_SERVER, CRLF, CRLF_DOUBLE, HTTP_VERSION, PR_FILE_EXTENSION, PR_FOLDER},
-    servers::errors::ServerError,
+    consts::{
+        APPLICATION_SERVER, CHUNKED, CRLF, CRLF_DOUBLE, HTTP_VERSION, PR_FILE_EXTENSION, PR_FOLDER,
+    },
+    servers::{errors::ServerError, server_config::resolve_repo_path},
     util::{
         connections::send_message,
         errors::UtilError,
         files::{create_directory, folder_exists},
+        logger::log_debug,
     },
 };
 use std::{
-    fs::OpenOptions,
+    fs::{self, OpenOptions},
     io::{Read, Seek, SeekFrom, Write},
     num::ParseIntError,
     path::Path,
@@ -49,6 +52,112 @@ pub fn read_request(reader: &mut dyn Read) -> Result<String, ServerError> {
     Ok(String::from_utf8_lossy(&request).to_string())
 }
 
+/// Completa el cuerpo de la solicitud si no llegó entero en la primera lectura.
+///
+/// # Argumentos
+///
+/// * `reader` - Conexión con el cliente.
+/// * `body` - Parte del cuerpo ya leída.
+/// * `content_length` - Largo total del cuerpo indicado en los encabezados.
+///
+pub fn read_remaining_body(
+    reader: &mut dyn Read,
+    body: &mut Vec<u8>,
+    content_length: usize,
+) -> Result<(), ServerError> {
+    if body.len() >= content_length {
+        return Ok(());
+    }
+    let mut remaining = vec![0; content_length - body.len()];
+    if reader.read_exact(&mut remaining).is_err() {
+        return Err(ServerError::ReadHttpRequest);
+    }
+    body.extend_from_slice(&remaining);
+    Ok(())
+}
+
+/// Indica si el valor del encabezado `Transfer-Encoding` incluye la codificación `chunked`.
+///
+/// # Argumentos
+///
+/// * `transfer_encoding` - Valor del encabezado, con las codificaciones separadas por comas.
+///
+pub fn is_chunked(transfer_encoding: &str) -> bool {
+    transfer_encoding
+        .split(',')
+        .any(|encoding| encoding.trim().eq_ignore_ascii_case(CHUNKED))
+}
+
+/// Lee un cuerpo enviado con `Transfer-Encoding: chunked` y devuelve el cuerpo decodificado.
+///
+/// Cada chunk empieza con su tamaño en hexadecimal, opcionalmente seguido de extensiones
+/// (`;nombre=valor`) que se ignoran, y termina con `\r\n`. El cuerpo termina con un chunk de
+/// tamaño 0, seguido de encabezados finales opcionales que también se ignoran.
+///
+/// # Argumentos
+///
+/// * `reader` - Lector posicionado al inicio del primer chunk.
+///
+/// # Errores
+///
+/// Retorna `ServerError::InvalidChunkedBody` si un tamaño no es un número hexadecimal válido,
+/// si un chunk no termina con `\r\n` o si el cuerpo termina antes del último chunk.
+///
+pub fn read_chunked_body(reader: &mut dyn Read) -> Result<Vec<u8>, ServerError> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_chunk_line(reader)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        if size.is_empty() || !size.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ServerError::InvalidChunkedBody(format!(
+                "tamaño inválido '{}'",
+                line
+            )));
+        }
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| ServerError::InvalidChunkedBody(format!("tamaño inválido '{}'", line)))?;
+        if size == 0 {
+            break;
+        }
+        let read = reader
+            .take(size as u64)
+            .read_to_end(&mut body)
+            .map_err(|_| ServerError::ReadHttpRequest)?;
+        let mut end = [0; 2];
+        if read != size || reader.read_exact(&mut end).is_err() || end != *b"\r\n" {
+            return Err(ServerError::InvalidChunkedBody(format!(
+                "el chunk no tiene {} bytes",
+                size
+            )));
+        }
+    }
+    // Encabezados finales, hasta la línea vacía
+    while !read_chunk_line(reader)?.is_empty() {}
+    Ok(body)
+}
+
+/// Lee una línea de un cuerpo chunked, sin el `\r\n` final.
+fn read_chunk_line(reader: &mut dyn Read) -> Result<String, ServerError> {
+    let mut line = Vec::new();
+    let mut byte = [0; 1];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => {
+                return Err(ServerError::InvalidChunkedBody(
+                    "fin inesperado del cuerpo".to_string(),
+                ))
+            }
+            Ok(_) if byte[0] == b'\n' => break,
+            Ok(_) => line.push(byte[0]),
+            Err(_) => return Err(ServerError::ReadHttpRequest),
+        }
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(String::from_utf8_lossy(&line).to_string())
+}
+
 /// Crea una carpeta de pull request (PR) dentro del directorio fuente especificado.
 ///
 /// Esta función construye la ruta a la carpeta PR utilizando la ruta del directorio fuente proporcionada
@@ -95,7 +204,31 @@ pub fn send_response_http(
     status_code: &StatusCode,
     content_type: &str,
 ) -> Result<(), ServerError> {
-    let response = format!("{} {}{}", HTTP_VERSION, status_code, CRLF);
+    send_response_http_with_headers(writer, status_code, content_type, &[])
+}
+
+/// Envía una respuesta HTTP al cliente incluyendo encabezados adicionales, como el `ETag`.
+///
+/// # Argumentos
+///
+/// * `writer` - Un escritor que implementa el trait `Write` para enviar la respuesta.
+/// * `status_code` - El código de estado HTTP que se debe incluir en la respuesta.
+/// * `content_type` - El tipo de contenido del cuerpo de la respuesta.
+/// * `headers` - Encabezados adicionales, como pares `(nombre, valor)`.
+///
+/// # Errores
+///
+/// Retorna `ServerError::SendResponse` si falla al escribir la respuesta en el escritor.
+pub fn send_response_http_with_headers(
+    writer: &mut dyn Write,
+    status_code: &StatusCode,
+    content_type: &str,
+    headers: &[(&str, String)],
+) -> Result<(), ServerError> {
+    let mut response = format!("{} {}{}", HTTP_VERSION, status_code, CRLF);
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}{}", name, value, CRLF));
+    }
     let error = UtilError::UtilFromServer("Error sending response".to_string());
     match send_message(writer, &response, error) {
         Ok(_) => {}
@@ -106,16 +239,33 @@ pub fn send_response_http(
             // let body = HttpBody::convert_body_to_content_type(body.clone(), content_type)?;
             send_body_model(writer, body, content_type)
         }
+        StatusCode::Conflict(body) => send_body_model(writer, body, content_type),
         StatusCode::ValidationFailed(message)
         | StatusCode::InternalError(message)
         | StatusCode::ResourceNotFound(message)
         | StatusCode::Forbidden(message)
-        | StatusCode::Conflict(message)
+        | StatusCode::Unauthorized(message)
         | StatusCode::BadRequest(message) => {
             // let body = HttpBody::from_string(content_type, message, MESSAGE)?;
             let body = Model::Message(message.to_string());
             send_body_model(writer, &body, content_type)
         }
+        StatusCode::NotModified => {
+            let error = UtilError::UtilFromServer("Error sending response".to_string());
+            match send_message(writer, CRLF, error) {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ServerError::SendResponse(response)),
+            }
+        }
+        StatusCode::TooManyRequests(retry_after) => {
+            let header = format!("Retry-After: {}{}", retry_after, CRLF);
+            let error = UtilError::UtilFromServer("Error sending response".to_string());
+            if send_message(writer, &header, error).is_err() {
+                return Err(ServerError::SendResponse(header));
+            }
+            let body = Model::Message("Too many requests".to_string());
+            send_body_model(writer, &body, content_type)
+        }
         _ => Ok(()), // Deberia enviar un CRLF
     }
 }
@@ -126,7 +276,13 @@ fn send_body_model(
     content_type: &str,
 ) -> Result<(), ServerError> {
     // let (content_type, body_str) = body.get_content_type_and_body()?;
-    let body_str = model.to_string(content_type);
+    // Si el modelo no se puede representar con el tipo pedido, se responde con el del servidor
+    let (content_type, body_str) = match model.to_string(content_type) {
+        body_str if body_str.is_empty() => {
+            (APPLICATION_SERVER, model.to_string(APPLICATION_SERVER))
+        }
+        body_str => (content_type, body_str),
+    };
 
     let message = match body_str.len() {
         0 => CRLF.to_string(),
@@ -189,12 +345,13 @@ fn send_body_model(
 ///
 /// * `Ok(())` si el repositorio existe.
 /// * `Err(ServerError::ResourceNotFound)` si el repositorio o su carpeta `.git` no existen.
+/// * `Err(ServerError::PathOutsideRoot)` si la ruta del repositorio sale del directorio base.
 ///
 /// # Errores
 ///
 /// Esta función retornará `ServerError::ResourceNotFound` si el repositorio o su carpeta `.git` no existen.
-pub fn valid_repository(repo_name: &str, base_path: &String) -> Result<(), ServerError> {
-    let repo_directory = format!("{}/{}", base_path, repo_name);
+pub fn valid_repository(repo_name: &str, base_path: &str) -> Result<(), ServerError> {
+    let repo_directory = resolve_repo_path(base_path, repo_name)?;
     if !folder_exists(&repo_directory) {
         return Err(ServerError::ResourceNotFound(
             "The repository does not exist.".to_string(),
@@ -209,6 +366,62 @@ pub fn valid_repository(repo_name: &str, base_path: &String) -> Result<(), Serve
     Ok(())
 }
 
+/// Verifica que el nombre de un repositorio a crear sea seguro de usar como directorio dentro
+/// del directorio base del servidor.
+///
+/// Solo se aceptan letras, números, `-`, `_` y `.`, sin empezar con `.`. Así el nombre no
+/// puede salir del directorio base (`..`, `/`) ni pisar carpetas ocultas como la de los PRs.
+///
+/// # Argumentos
+///
+/// * `repo_name` - El nombre del repositorio.
+///
+/// # Retorno
+///
+/// `true` si el nombre es válido, `false` en caso contrario.
+///
+pub fn is_valid_repository_name(repo_name: &str) -> bool {
+    !repo_name.is_empty()
+        && !repo_name.starts_with('.')
+        && repo_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+}
+
+/// Lista los repositorios disponibles en el directorio base del servidor.
+///
+/// Un repositorio es un directorio que contiene una carpeta `.git`. Se omiten los directorios
+/// ocultos (como el de los pull requests) y los que no son repositorios.
+///
+/// # Argumentos
+///
+/// * `src` - El directorio base donde se encuentran los repositorios.
+///
+/// # Retorno
+///
+/// Retorna los nombres de los repositorios ordenados alfabéticamente, o un `ServerError` si no
+/// se pudo leer el directorio.
+///
+pub fn list_repositories(src: &String) -> Result<Vec<String>, ServerError> {
+    let entries = match fs::read_dir(src) {
+        Ok(entries) => entries,
+        Err(_) => return Err(UtilError::ReadDirError.into()),
+    };
+    let mut repositories = Vec::new();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => return Err(UtilError::DirEntryError.into()),
+        };
+        let repo_name = entry.file_name().to_string_lossy().to_string();
+        if !repo_name.starts_with('.') && valid_repository(&repo_name, src).is_ok() {
+            repositories.push(repo_name);
+        }
+    }
+    repositories.sort();
+    Ok(repositories)
+}
+
 /// Obtiene el número del próximo pull request a partir de un archivo.
 ///
 /// Si el archivo no existe, se crea y se inicializa en 1.
@@ -261,10 +474,10 @@ fn parse_next_pr_number(content: &str) -> Result<usize, ServerError> {
         .trim()
         .parse::<usize>()
         .map_err(|err: ParseIntError| {
-            println!(
+            log_debug(&format!(
                 "Failed to parse number from content: {}. Error: {:?}",
                 content, err
-            );
+            ));
             ServerError::ParseNumberPR("Failed to parse PR number".to_string())
         })
 }
@@ -290,7 +503,7 @@ pub fn validate_branch_changes(
     base: &str,
     head: &str,
 ) -> Result<bool, ServerError> {
-    let directory = format!("{}/{}", base_path, repo_name);
+    let directory = resolve_repo_path(base_path, repo_name)?;
     let result = get_commits_pr(&directory, base, head)?;
 
     Ok(!result.is_empty())
@@ -333,9 +546,10 @@ pub fn save_pr_to_file(body: &HttpBody, path: &str, pr_number: usize) -> Result<
 ///
 /// Devuelve `Ok(String)` con la ruta del directorio de pull requests si se crea o existe correctamente.
 /// Devuelve `Err(StatusCode::InternalError)` si ocurre un error al crear el directorio.
+/// Devuelve `Err(StatusCode::Forbidden)` si la ruta sale del directorio base.
 ///
-pub fn setup_pr_directory(repo_name: &str, src: &String) -> Result<String, StatusCode> {
-    let path = format!("{}/{}/{}", src, PR_FOLDER, repo_name);
+pub fn setup_pr_directory(repo_name: &str, src: &str) -> Result<String, StatusCode> {
+    let path = resolve_repo_path(src, &format!("{}/{}", PR_FOLDER, repo_name))?;
     let directory = Path::new(&path);
     if create_directory(directory).is_err() {
         return Err(StatusCode::InternalError(
@@ -348,6 +562,7 @@ pub fn setup_pr_directory(repo_name: &str, src: &String) -> Result<String, Statu
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::servers::http_server::{http_request::HttpRequest, pr::PullRequest};
     use std::io::{Cursor, Read};
 
     #[test]
@@ -409,4 +624,84 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_read_chunked_body() {
+        let mut reader: &[u8] = b"4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nTrailer: x\r\n\r\n";
+        assert_eq!(read_chunked_body(&mut reader), Ok(b"Wikipedia".to_vec()));
+
+        let mut reader: &[u8] = b"zz\r\nWiki\r\n0\r\n\r\n";
+        assert!(matches!(
+            read_chunked_body(&mut reader),
+            Err(ServerError::InvalidChunkedBody(_))
+        ));
+        let mut reader: &[u8] = b"5\r\nWiki\r\n0\r\n\r\n";
+        assert!(matches!(
+            read_chunked_body(&mut reader),
+            Err(ServerError::InvalidChunkedBody(_))
+        ));
+        let mut reader: &[u8] = b"4\r\nWiki\r\n";
+        assert!(matches!(
+            read_chunked_body(&mut reader),
+            Err(ServerError::InvalidChunkedBody(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_repositories() {
+        let src = "./test_list_repositories".to_string();
+        for directory in ["repo_b/.git", "repo_a/.git", "no_repo", ".pr/.git"] {
+            fs::create_dir_all(format!("{}/{}", src, directory))
+                .expect("Falló al crear el directorio");
+        }
+        fs::write(format!("{}/archivo.txt", src), "hola").expect("Falló al crear el archivo");
+
+        let result = list_repositories(&src);
+
+        fs::remove_dir_all(&src).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(result, Ok(vec!["repo_a".to_string(), "repo_b".to_string()]));
+        assert!(list_repositories(&"./test_list_repositories_missing".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_send_list_pull_request_text_plain() {
+        let pr = |id: usize, title: &str, head: &str| PullRequest {
+            id: Some(id),
+            owner: Some("valen".to_string()),
+            title: Some(title.to_string()),
+            head: Some(head.to_string()),
+            base: Some("master".to_string()),
+            state: Some("open".to_string()),
+            ..Default::default()
+        };
+        let status_code = StatusCode::Ok(Some(Model::ListPullRequest(vec![
+            pr(1, "Agrega el login", "login"),
+            pr(12, "Arregla el merge", "fix-merge"),
+        ])));
+        let send = |raw_request: &str| {
+            let request = HttpRequest::new_from_string(raw_request).expect("Solicitud inválida");
+            let mut writer = Vec::new();
+            send_response_http(
+                &mut writer,
+                &status_code,
+                &request.get_response_content_type(),
+            )
+            .expect("Falló al enviar la respuesta");
+            String::from_utf8(writer).expect("Respuesta inválida")
+        };
+
+        let response = send("GET /repos/repo/pulls HTTP/1.1\r\nAccept: text/plain\r\n\r\n");
+        let body = "ID   STATE  BRANCHES             OWNER  TITLE\n\
+                    #1   open   master <- login      valen  Agrega el login\n\
+                    #12  open   master <- fix-merge  valen  Arregla el merge\n";
+        assert!(response.contains("Content-Type: text/plain\r\n"));
+        assert!(response.ends_with(&format!("\r\n\r\n{}", body)));
+
+        for accept in ["Accept: */*\r\n", ""] {
+            let response = send(&format!("GET /repos/repo/pulls HTTP/1.1\r\n{}\r\n", accept));
+            assert!(response.contains("Content-Type: application/json\r\n"));
+            assert!(response.contains("\"title\": \"Arregla el merge\""));
+        }
+    }
 }
@@ -0,0 +1,249 @@
+use crate::consts::{WEBHOOK_MAX_ATTEMPTS, WEBHOOK_RETRY_DELAY, WEBHOOK_TIMEOUT};
+use crate::servers::errors::ServerError;
+use crate::util::logger::log_message;
+use serde_json::json;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{mpsc::Sender, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// URLs a las que se envían los eventos de los pull requests, configuradas al iniciar el servidor.
+static WEBHOOK_URLS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Nombre del evento que se envía ante cambios en un pull request.
+const PULL_REQUEST_EVENT: &str = "pull_request";
+
+/// Configura las URLs de los webhooks del servidor HTTP.
+///
+/// Debe llamarse antes de atender la primera solicitud; si no se llama no se envían eventos.
+///
+/// # Argumentos
+///
+/// * `urls` - URLs `http://` a las que se envían los eventos.
+///
+pub fn init_webhooks(urls: Vec<String>) {
+    let _ = WEBHOOK_URLS.set(urls);
+}
+
+/// Construye el cuerpo JSON de un evento de pull request.
+///
+/// # Argumentos
+///
+/// * `repo` - Nombre del repositorio.
+/// * `pull_number` - Número del pull request.
+/// * `action` - Acción realizada: `opened`, `merged` o `closed`.
+///
+/// # Retornos
+///
+/// Retorna el evento con el formato `{event, repo, pull_number, action}`.
+///
+pub fn build_pull_request_event(repo: &str, pull_number: usize, action: &str) -> String {
+    json!({
+        "event": PULL_REQUEST_EVENT,
+        "repo": repo,
+        "pull_number": pull_number,
+        "action": action,
+    })
+    .to_string()
+}
+
+/// Envía un evento de pull request a todos los webhooks configurados.
+///
+/// El envío se realiza en un hilo aparte para no demorar la respuesta HTTP. Cada envío se
+/// reintenta hasta `WEBHOOK_MAX_ATTEMPTS` veces y los fallos se registran en el log.
+///
+/// # Argumentos
+///
+/// * `tx` - Canal usado para comunicación con el archivo de log.
+/// * `repo` - Nombre del repositorio.
+/// * `pull_number` - Número del pull request.
+/// * `action` - Acción realizada: `opened`, `merged` o `closed`.
+///
+pub fn dispatch_pull_request_event(
+    tx: &Arc<Mutex<Sender<String>>>,
+    repo: &str,
+    pull_number: usize,
+    action: &str,
+) {
+    let urls = match WEBHOOK_URLS.get() {
+        Some(urls) if !urls.is_empty() => urls.clone(),
+        _ => return,
+    };
+    let payload = build_pull_request_event(repo, pull_number, action);
+    let tx = Arc::clone(tx);
+    thread::spawn(move || {
+        for url in urls {
+            send_with_retries(&url, &payload, &tx);
+        }
+    });
+}
+
+/// Envía el evento a un webhook, reintentando con una espera creciente si falla.
+///
+/// # Argumentos
+///
+/// * `url` - URL del webhook.
+/// * `payload` - Cuerpo JSON del evento.
+/// * `tx` - Canal usado para comunicación con el archivo de log.
+///
+fn send_with_retries(url: &str, payload: &str, tx: &Arc<Mutex<Sender<String>>>) {
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        match post_json(url, payload) {
+            Ok(_) => return,
+            Err(e) => {
+                let message = format!(
+                    "Webhook {}: intento {} de {} fallido: {}",
+                    url, attempt, WEBHOOK_MAX_ATTEMPTS, e
+                );
+                log_message(tx, &message);
+            }
+        }
+        if attempt < WEBHOOK_MAX_ATTEMPTS {
+            thread::sleep(Duration::from_secs(WEBHOOK_RETRY_DELAY * attempt as u64));
+        }
+    }
+}
+
+/// Separa una URL `http://host[:puerto][/path]` en la dirección a la que conectarse, el
+/// host y el path.
+///
+/// # Argumentos
+///
+/// * `url` - URL del webhook.
+///
+fn parse_webhook_url(url: &str) -> Option<(String, String, String)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(pos) => (&rest[..pos], &rest[pos..]),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return None;
+    }
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    Some((address, authority.to_string(), path.to_string()))
+}
+
+/// Envía un cuerpo JSON a una URL con una solicitud `POST` y verifica que la respuesta sea
+/// exitosa (`2xx`).
+///
+/// # Argumentos
+///
+/// * `url` - URL `http://` de destino.
+/// * `body` - Cuerpo JSON a enviar.
+///
+/// # Retornos
+///
+/// Retorna `Ok(())` si el destino respondió con un estado `2xx`, o un
+/// `ServerError::WebhookDelivery` en caso contrario.
+///
+pub fn post_json(url: &str, body: &str) -> Result<(), ServerError> {
+    let delivery_error = |message: &str| ServerError::WebhookDelivery(message.to_string());
+    let (address, host, path) = parse_webhook_url(url).ok_or_else(|| delivery_error(url))?;
+    let timeout = Duration::from_secs(WEBHOOK_TIMEOUT);
+
+    let socket_address = address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or_else(|| delivery_error("no se pudo resolver el host"))?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, timeout)
+        .map_err(|e| delivery_error(&e.to_string()))?;
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        host,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| delivery_error(&e.to_string()))?;
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(delivery_error(status_line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_build_pull_request_event() {
+        let payload = build_pull_request_event("repo", 3, "merged");
+        let event: serde_json::Value =
+            serde_json::from_str(&payload).expect("El evento no es un JSON válido");
+
+        assert_eq!(event["event"], "pull_request");
+        assert_eq!(event["repo"], "repo");
+        assert_eq!(event["pull_number"], 3);
+        assert_eq!(event["action"], "merged");
+        assert_eq!(event.as_object().map(|fields| fields.len()), Some(4));
+    }
+
+    /// Indica si ya se leyeron los encabezados y el cuerpo completo según `Content-Length`.
+    fn is_complete_request(request: &[u8]) -> bool {
+        let request = String::from_utf8_lossy(request);
+        let (headers, body) = match request.split_once("\r\n\r\n") {
+            Some(parts) => parts,
+            None => return false,
+        };
+        let length = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("Content-Length: "))
+            .and_then(|length| length.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        body.len() >= length
+    }
+
+    #[test]
+    fn test_post_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar el listener");
+        let port = listener
+            .local_addr()
+            .expect("Falló al leer el puerto")
+            .port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Falló al aceptar la conexión");
+            // La solicitud puede llegar en varios segmentos: se lee hasta completar el cuerpo
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !is_complete_request(&request) {
+                let read = stream
+                    .read(&mut buffer)
+                    .expect("Falló al leer la solicitud");
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .expect("Falló al responder");
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let url = format!("http://127.0.0.1:{}/hooks", port);
+        let result = post_json(&url, "{\"action\":\"opened\"}");
+        let request = handle.join().expect("Falló el hilo del listener");
+
+        assert_eq!(result, Ok(()));
+        assert!(request.starts_with("POST /hooks HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\n{\"action\":\"opened\"}"));
+        assert!(post_json("https://127.0.0.1/hooks", "{}").is_err());
+    }
+}
@@ -3,19 +3,26 @@ use std::net::{TcpListener, TcpStream};
 use crate::config::Config;
 use crate::errors::GitError;
 use crate::git_transport::git_request::GitRequest;
+use crate::util::connections::set_stream_timeout;
 use crate::util::logger::{
     get_client_signature, handle_log_file, log_client_connect, log_client_disconnection_error,
-    log_client_disconnection_success, log_message,
+    log_client_disconnection_success, log_level, log_message, set_log_channel, LogLevel,
 };
 use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
+use std::time::Duration;
 use std::{env, thread};
 
 use super::errors::ServerError;
+use super::server_config::ServerConfig;
 
-type Handler =
-    fn(&mut TcpStream, String, &Arc<Mutex<Sender<String>>>, String) -> Result<(), GitError>;
+type Handler = fn(
+    &mut TcpStream,
+    String,
+    &Arc<Mutex<Sender<String>>>,
+    Arc<ServerConfig>,
+) -> Result<(), GitError>;
 type LogResult = Result<(Arc<Mutex<Sender<String>>>, JoinHandle<()>), GitError>;
 
 /// Inicia un servidor en la dirección IP y puerto proporcionados.
@@ -112,8 +119,9 @@ pub fn process_request(
 ///
 /// * `listener` - Una referencia al escuchador de TCP.
 /// * `shared_tx` - Un `Arc<Mutex<Sender<String>>>` para enviar mensajes de registro.
-/// * `src` - Una cadena que representa el directorio fuente.
+/// * `config` - La configuración del servidor, compartida por todos los clientes.
 /// * `handler` - Una función que maneja la conexión del cliente.
+/// * `timeout` - Tiempo máximo de espera de cada lectura o escritura con el cliente.
 ///
 /// # Returns
 ///
@@ -123,8 +131,9 @@ pub fn receive_client(
     listener: &TcpListener,
     name_server: String,
     shared_tx: Arc<Mutex<Sender<String>>>,
-    src: &str,
+    config: &Arc<ServerConfig>,
     handler: Handler,
+    timeout: Duration,
 ) -> Result<Vec<JoinHandle<()>>, GitError> {
     // let shared_tx = Arc::new(Mutex::new(tx));
     let mut handles: Vec<JoinHandle<()>> = vec![];
@@ -132,20 +141,21 @@ pub fn receive_client(
         match stream {
             Ok(mut stream) => {
                 let tx = Arc::clone(&shared_tx);
-                println!("Nueva conexión: {:?}", stream.local_addr());
-                let root_directory = src.to_string().clone();
+                if let Err(e) = set_stream_timeout(&stream, timeout) {
+                    log_message(&tx, &format!("Error al configurar el timeout: {}", e));
+                }
+                let config = Arc::clone(config);
                 let server = name_server.clone();
                 let signature = get_client_signature(&stream, &server);
                 log_client_connect(&stream, &tx, &server);
                 handles.push(std::thread::spawn(move || {
-                    let result = handler(&mut stream, signature, &tx, root_directory);
+                    let result = handler(&mut stream, signature, &tx, config);
                     log_request_result(&stream, &server, &tx, result);
                 }));
             }
             Err(e) => {
                 let message = format!("Error al aceptar la conexión: {}", e);
-                log_message(&shared_tx, &message);
-                eprintln!("Error al aceptar la conexión: {}", e);
+                log_level(Some(&shared_tx), LogLevel::Error, &message);
             }
         }
     }
@@ -224,6 +234,8 @@ pub fn create_listener(ip: &str, port: &String) -> Result<TcpListener, GitError>
 pub fn start_logging(path_log: String) -> LogResult {
     let (tx, rx) = mpsc::channel();
     let shared_tx = Arc::new(Mutex::new(tx));
+    // El avance de los packfiles y los mensajes de debug se registran en el mismo archivo
+    set_log_channel(&shared_tx);
     let log_handle = thread::spawn(move || {
         let _ = handle_log_file(&path_log, rx);
     });
@@ -237,8 +249,10 @@ pub fn start_logging(path_log: String) -> LogResult {
 ///
 /// * `listener` - El `TcpListener` que acepta conexiones entrantes.
 /// * `shared_tx` - Un `Arc<Mutex<Sender<String>>>` para transmitir mensajes de log.
-/// * `src` - La ruta del directorio raíz para operaciones de servidor.
+/// * `config` - La configuración del servidor, con el directorio raíz de los repositorios.
 /// * `handler` - Una función que maneja cada conexión entrante.
+/// * `timeout` - Tiempo máximo de espera de cada lectura o escritura con un cliente, para que
+///   un cliente que deja de responder no bloquee su hilo indefinidamente.
 ///
 /// # Returns
 ///
@@ -255,11 +269,12 @@ pub fn start_server_thread(
     listener: TcpListener,
     name_server: String,
     shared_tx: Arc<Mutex<Sender<String>>>,
-    src: String,
+    config: Arc<ServerConfig>,
     handler: Handler,
+    timeout: Duration,
 ) -> Result<JoinHandle<()>, GitError> {
     let handle = thread::spawn(move || {
-        let _ = receive_client(&listener, name_server, shared_tx, &src, handler);
+        let _ = receive_client(&listener, name_server, shared_tx, &config, handler, timeout);
     });
     Ok(handle)
 }
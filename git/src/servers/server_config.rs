@@ -0,0 +1,128 @@
+use crate::config::Config;
+use crate::servers::errors::ServerError;
+use std::fs;
+use std::path::{Component, Path};
+
+/// Configuración compartida por los servidores git daemon y HTTP.
+///
+/// - `root`: Directorio raíz donde se encuentran los repositorios del servidor.
+/// - `max_repositories`: Cantidad máxima de repositorios que se pueden crear desde la API.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    root: String,
+    max_repositories: usize,
+}
+
+impl ServerConfig {
+    pub fn new(root: &str, max_repositories: usize) -> Self {
+        ServerConfig {
+            root: root.to_string(),
+            max_repositories,
+        }
+    }
+
+    /// Crea la configuración de los servidores a partir de la configuración del programa.
+    pub fn from_config(config: &Config) -> Self {
+        ServerConfig::new(&config.src, config.max_repositories)
+    }
+
+    pub fn get_root(&self) -> &String {
+        &self.root
+    }
+
+    pub fn get_max_repositories(&self) -> usize {
+        self.max_repositories
+    }
+}
+
+/// Obtiene la ruta de `name` dentro del directorio raíz, verificando que no salga de él.
+///
+/// Además de rechazar nombres absolutos o con componentes `..`, se canonicaliza la ruta para
+/// resolver los enlaces simbólicos: si la ruta todavía no existe se canonicaliza el ancestro
+/// existente más cercano. Un enlace simbólico roto se rechaza, ya que al crear la ruta se
+/// seguiría el enlace.
+///
+/// # Argumentos
+///
+/// * `root` - Directorio raíz del servidor. Debe existir.
+/// * `name` - Ruta relativa al directorio raíz, por ejemplo el nombre de un repositorio.
+///
+/// # Retorno
+///
+/// La ruta `<root>/<name>`, o `ServerError::PathOutsideRoot` si la ruta sale del directorio
+/// raíz.
+///
+pub fn resolve_repo_path(root: &str, name: &str) -> Result<String, ServerError> {
+    let outside = || ServerError::PathOutsideRoot(name.to_string());
+    let only_names = Path::new(name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if name.is_empty() || !only_names {
+        return Err(outside());
+    }
+    let canonical_root = match fs::canonicalize(root) {
+        Ok(canonical_root) => canonical_root,
+        Err(_) => return Err(ServerError::ResourceNotFound(root.to_string())),
+    };
+
+    let path = format!("{}/{}", root, name);
+    let mut existing = Path::new(&path);
+    while existing.symlink_metadata().is_err() {
+        existing = match existing.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+    match fs::canonicalize(existing) {
+        Ok(canonical) if canonical.starts_with(&canonical_root) => Ok(path),
+        _ => Err(outside()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_repo_path() {
+        let root = "./test_resolve_repo_path";
+        let outside = "./test_resolve_repo_path_outside";
+        fs::create_dir_all(format!("{}/repo", root)).expect("Falló al crear el directorio");
+        fs::create_dir_all(outside).expect("Falló al crear el directorio");
+
+        assert_eq!(
+            resolve_repo_path(root, "repo"),
+            Ok(format!("{}/repo", root))
+        );
+        assert_eq!(
+            resolve_repo_path(root, ".pr/repo/1.json"),
+            Ok(format!("{}/.pr/repo/1.json", root))
+        );
+        for name in ["", "../repo", "repo/../../x", "/tmp"] {
+            assert_eq!(
+                resolve_repo_path(root, name),
+                Err(ServerError::PathOutsideRoot(name.to_string()))
+            );
+        }
+
+        // Un enlace simbólico dentro del directorio raíz que apunta fuera de él
+        #[cfg(unix)]
+        {
+            let target = fs::canonicalize(outside).expect("Falló al canonicalizar");
+            std::os::unix::fs::symlink(&target, format!("{}/escape", root))
+                .expect("Falló al crear el enlace");
+            std::os::unix::fs::symlink("/no/existe", format!("{}/roto", root))
+                .expect("Falló al crear el enlace");
+            for name in ["escape", "escape/.git", "roto/repo"] {
+                assert_eq!(
+                    resolve_repo_path(root, name),
+                    Err(ServerError::PathOutsideRoot(name.to_string()))
+                );
+            }
+        }
+
+        fs::remove_dir_all(root).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(outside).expect("Falló al remover el directorio temporal");
+    }
+}
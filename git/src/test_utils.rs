@@ -0,0 +1,99 @@
+//! Funciones auxiliares compartidas por los tests del crate.
+
+use crate::commands::add::git_add;
+use crate::commands::commit::{git_commit, Commit};
+use crate::commands::rev_parse::git_rev_parse;
+use crate::consts::HEAD;
+use crate::git_transport::git_request::GitRequest;
+use crate::models::client::Client;
+use crate::util::errors::UtilError;
+use crate::util::files::create_file_replace;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+pub const TEST_NAME: &str = "Valen";
+
+pub const TEST_EMAIL: &str = "vlanzillotta@fi.uba.ar";
+
+/// Crea un commit con el mensaje indicado y la identidad de los tests como autor y committer.
+pub fn test_commit(message: &str) -> Commit {
+    Commit::new(
+        message.to_string(),
+        TEST_NAME.to_string(),
+        TEST_EMAIL.to_string(),
+        TEST_NAME.to_string(),
+        TEST_EMAIL.to_string(),
+    )
+}
+
+/// Crea un cliente con la identidad de los tests que trabaja sobre el repositorio indicado.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio local.
+/// * `port` - Puerto del servidor al que se conecta el cliente.
+///
+pub fn test_client(directory: &str, port: &str) -> Client {
+    Client::new(
+        TEST_NAME.to_string(),
+        TEST_EMAIL.to_string(),
+        "127.0.0.1".to_string(),
+        port.to_string(),
+        "localhost".to_string(),
+        directory.to_string(),
+        "master".to_string(),
+    )
+}
+
+/// Escribe el archivo, lo agrega al index y lo commitea con su contenido como mensaje.
+///
+/// # Retorno
+///
+/// El hash del commit creado.
+///
+pub fn commit_file(directory: &str, file: &str, content: &str) -> String {
+    create_file_replace(&format!("{}/{}", directory, file), content)
+        .expect("Falló al crear el archivo");
+    git_add(directory, file).expect("Falló al agregar el archivo");
+    commit_index(directory, test_commit(content))
+}
+
+/// Commitea el contenido actual del index con el commit indicado.
+///
+/// # Retorno
+///
+/// El hash del commit creado.
+///
+pub fn commit_index(directory: &str, commit: Commit) -> String {
+    git_commit(directory, commit).expect("Falló al hacer el commit");
+    git_rev_parse(directory, HEAD).expect("Falló al resolver HEAD")
+}
+
+/// Atiende una única solicitud git:// sobre el directorio raíz indicado.
+///
+/// # Retorno
+///
+/// El socket del cliente ya conectado, el puerto del servidor y el hilo del servidor, que
+/// devuelve el resultado de la solicitud.
+///
+pub fn serve_once(
+    root: &'static str,
+) -> (
+    TcpStream,
+    String,
+    thread::JoinHandle<Result<String, UtilError>>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Falló al iniciar el servidor");
+    let port = listener
+        .local_addr()
+        .expect("Falló al obtener el puerto")
+        .port()
+        .to_string();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("Falló al aceptar la conexión");
+        let request = GitRequest::read_git_request(&mut stream)?;
+        request.execute(&mut stream, root)
+    });
+    let socket = TcpStream::connect(format!("127.0.0.1:{}", port)).expect("Falló al conectar");
+    (socket, port, server)
+}
@@ -58,3 +58,9 @@ pub mod files;
 pub mod errors;
 
 pub mod index;
+
+pub mod diff;
+
+pub mod progress;
+
+pub mod object_format;
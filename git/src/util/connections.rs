@@ -1,17 +1,24 @@
-use crate::consts::FLUSH_PKT;
 use crate::consts::PKT_DONE;
-use crate::consts::WANT;
+use crate::consts::{FILTER, HAVE, WANT};
+use crate::consts::{
+    FLUSH_PKT, NETWORK_MAX_RETRIES, NETWORK_TIMEOUT_DEFAULT, PACKFILE_HEADER_SIZE,
+};
 use crate::git_server::GitServer;
 use crate::git_transport::negotiation::receive_nak;
-use crate::git_transport::negotiation::upload_request_type;
-use std::io::Read;
-use std::io::Write;
+use crate::git_transport::negotiation::{upload_request_hashes, upload_request_type};
+use std::io::{self, ErrorKind, Read, Write};
 use std::net::TcpStream;
+use std::time::Duration;
 
 use super::errors::UtilError;
+use super::logger::log_debug;
+use super::object_format::ObjectFormat;
 use super::objects::ObjectEntry;
-use super::packfile::read_packfile_data;
 use super::packfile::read_packfile_header;
+use super::packfile::{
+    read_packfile_data, read_packfile_data_partial, PackfileLimits, ReceivedPackfile,
+};
+use super::pkt_line::{self, add_length_prefix, read_pkt_line};
 
 /// Inicia una conexión de cliente con el servidor en la dirección IP proporcionada.
 ///
@@ -22,9 +29,125 @@ use super::packfile::read_packfile_header;
 /// Un Result que indica si la conexión de cliente se estableció con éxito (Ok) o si se
 /// produjo un error (Err) de UtilError, como un error de conexión.
 pub fn start_client(address: &str) -> Result<TcpStream, UtilError> {
-    match TcpStream::connect(address) {
-        Ok(socket) => Ok(socket),
-        Err(_) => Err(UtilError::ClientConnection),
+    let socket = match TcpStream::connect(address) {
+        Ok(socket) => socket,
+        Err(_) => return Err(UtilError::ClientConnection),
+    };
+    set_stream_timeout(&socket, Duration::from_secs(NETWORK_TIMEOUT_DEFAULT))?;
+    Ok(socket)
+}
+
+/// Configura el timeout de lectura y escritura de una conexión.
+///
+/// Sin timeout, una lectura sobre una conexión en la que la otra parte dejó de responder
+/// bloquea el hilo indefinidamente.
+///
+/// # Argumentos
+/// - `socket`: Conexión a configurar.
+/// - `timeout`: Tiempo máximo de espera de cada lectura o escritura.
+///
+pub fn set_stream_timeout(socket: &TcpStream, timeout: Duration) -> Result<(), UtilError> {
+    if socket.set_read_timeout(Some(timeout)).is_err()
+        || socket.set_write_timeout(Some(timeout)).is_err()
+    {
+        return Err(UtilError::ClientConnection);
+    }
+    Ok(())
+}
+
+/// Indica si un error de E/S es transitorio, es decir, si la operación puede reintentarse.
+fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::WouldBlock | ErrorKind::Interrupted | ErrorKind::TimedOut
+    )
+}
+
+/// Lee exactamente `buffer.len()` bytes, reintentando ante timeouts e interrupciones.
+///
+/// A diferencia de `Read::read_exact`, los bytes leídos antes de un timeout no se pierden.
+/// Se reintenta hasta `NETWORK_MAX_RETRIES` veces seguidas sin recibir datos.
+///
+/// # Argumentos
+/// - `reader`: Origen de los datos.
+/// - `buffer`: Destino de los datos leídos.
+/// - `error`: Error que se devuelve si la lectura falla por otro motivo.
+///
+/// # Retorno
+/// `Ok(())` si se completó el buffer, `Err(UtilError::NetworkTimeout)` si se agotaron los
+/// reintentos, o `error` si la conexión se cerró o falló.
+///
+pub fn read_exact_retry(
+    reader: &mut dyn Read,
+    buffer: &mut [u8],
+    error: UtilError,
+) -> Result<(), UtilError> {
+    let mut filled = 0;
+    let mut retries = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => return Err(error),
+            Ok(n) => {
+                filled += n;
+                retries = 0;
+            }
+            Err(e) if is_transient(&e) => {
+                retries += 1;
+                if retries > NETWORK_MAX_RETRIES {
+                    return Err(UtilError::NetworkTimeout);
+                }
+            }
+            Err(_) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+/// Escribe todos los bytes, reintentando ante timeouts e interrupciones.
+///
+/// # Argumentos
+/// - `writer`: Destino de los datos.
+/// - `bytes`: Datos a escribir.
+/// - `error`: Error que se devuelve si la escritura falla por otro motivo.
+///
+/// # Retorno
+/// `Ok(())` si se escribieron y enviaron todos los bytes, `Err(UtilError::NetworkTimeout)`
+/// si se agotaron los reintentos, o `error` si la conexión falló.
+///
+fn write_all_retry(
+    writer: &mut dyn Write,
+    bytes: &[u8],
+    error: UtilError,
+) -> Result<(), UtilError> {
+    let mut written = 0;
+    let mut retries = 0;
+    while written < bytes.len() {
+        match writer.write(&bytes[written..]) {
+            Ok(0) => return Err(error),
+            Ok(n) => {
+                written += n;
+                retries = 0;
+            }
+            Err(e) if is_transient(&e) => {
+                retries += 1;
+                if retries > NETWORK_MAX_RETRIES {
+                    return Err(UtilError::NetworkTimeout);
+                }
+            }
+            Err(_) => return Err(error),
+        }
+    }
+    loop {
+        match writer.flush() {
+            Ok(_) => return Ok(()),
+            Err(e) if is_transient(&e) => {
+                retries += 1;
+                if retries > NETWORK_MAX_RETRIES {
+                    return Err(UtilError::NetworkTimeout);
+                }
+            }
+            Err(_) => return Err(error),
+        }
     }
 }
 
@@ -34,6 +157,8 @@ pub fn start_client(address: &str) -> Result<TcpStream, UtilError> {
 /// # Argumentos
 /// - `socket`: Un TcpStream que representa la conexión con el servidor.
 /// - `advertised`: Un vector de AdvertisedRefLine que contiene las referencias anunciadas por el servidor.
+/// - `filter`: Filtro del clone parcial (por ejemplo `blob:none`), que se envía con una línea
+///   `filter <spec>` después de los `want`.
 ///
 /// # Retorno
 /// Un Result que indica si la negociación del paquete se realizó con éxito (Ok) o si se
@@ -41,19 +166,127 @@ pub fn start_client(address: &str) -> Result<TcpStream, UtilError> {
 pub fn packfile_negotiation(
     socket: &mut TcpStream,
     git_server: &GitServer,
+    filter: Option<&str>,
 ) -> Result<(), UtilError> {
     let refs = git_server.get_references();
-    upload_request_type(socket, refs, WANT)?;
+    match filter {
+        Some(filter) => {
+            for reference in refs {
+                let message = format!("{} {}\n", WANT, reference.get_hash());
+                let message = add_length_prefix(&message, message.len());
+                send_message(socket, &message, UtilError::UploadRequest)?;
+            }
+            let message = format!("{} {}\n", FILTER, filter);
+            let message = add_length_prefix(&message, message.len());
+            send_message(socket, &message, UtilError::UploadRequest)?;
+            send_flush(socket, UtilError::UploadRequestFlush)?;
+        }
+        None => upload_request_type(socket, refs, WANT)?,
+    }
     send_done(socket, UtilError::UploadRequestDone)?;
     receive_nak(socket)?;
     Ok(())
 }
 
-pub fn receive_packfile(socket: &mut TcpStream) -> Result<Vec<(ObjectEntry, Vec<u8>)>, UtilError> {
-    // read_pack_prueba(socket)?;
+/// Negocia el packfile de un clone que se retoma luego de interrumpirse. Además de pedir todas
+/// las referencias con `want`, se informan con `have` los commits que ya se recibieron
+/// completos, para que el servidor no los vuelva a enviar.
+///
+/// # Argumentos
+/// - `socket`: Conexión con el servidor.
+/// - `git_server`: Servidor con las referencias anunciadas.
+/// - `haves`: Commits recibidos en el intento anterior, con su tree y sus parents completos.
+///
+pub fn packfile_negotiation_resume(
+    socket: &mut TcpStream,
+    git_server: &GitServer,
+    haves: &[String],
+) -> Result<(), UtilError> {
+    upload_request_type(socket, git_server.get_references(), WANT)?;
+    upload_request_hashes(socket, haves, HAVE)?;
+    // El servidor confirma los commits en común con un ACK por cada uno y termina con un NAK
+    let acks = pkt_line::read(socket)?;
+    log_debug(&format!("ACKS: {}", acks.len().saturating_sub(1)));
+    send_done(socket, UtilError::UploadRequestDone)?;
+    // Último ACK, antes del packfile
+    read_pkt_line(socket)?;
+    Ok(())
+}
+
+/// Recibe un packfile del socket.
+///
+/// # Argumentos
+/// - `socket`: Un TcpStream que representa la conexión con la otra parte.
+/// - `ofs_delta`: Indica si se negoció la capacidad `ofs-delta`; si no, un objeto `OfsDelta`
+///   en el packfile es un error.
+/// - `object_format`: Formato de los objetos del repositorio remoto.
+///
+pub fn receive_packfile(
+    socket: &mut TcpStream,
+    ofs_delta: bool,
+    object_format: ObjectFormat,
+) -> Result<Vec<(ObjectEntry, Vec<u8>)>, UtilError> {
+    let limits = PackfileLimits::unlimited();
+    receive_packfile_with_limits(socket, ofs_delta, &limits, object_format)
+}
+
+/// Recibe un packfile de una parte no confiable, cortando la lectura en cuanto se supera
+/// alguno de los límites.
+///
+/// # Argumentos
+/// - `socket`: Canal con la otra parte.
+/// - `ofs_delta`: Indica si se negoció la capacidad `ofs-delta`.
+/// - `limits`: Límites de bytes, de objetos y de tamaño descomprimido de cada objeto.
+/// - `object_format`: Formato de los objetos, con el que se leen las bases de los `RefDelta`.
+///
+/// # Retorno
+/// Los objetos del packfile, o `UtilError::PackfileTooLarge` si se supera algún límite.
+///
+pub fn receive_packfile_with_limits(
+    socket: &mut dyn Read,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+) -> Result<Vec<(ObjectEntry, Vec<u8>)>, UtilError> {
     let objects = read_packfile_header(socket)?;
-    println!("Objects: {}", objects);
-    read_packfile_data(socket, objects as usize)
+    log_debug(&format!("Objects: {}", objects));
+    read_packfile_data(socket, objects as usize, ofs_delta, limits, object_format)
+}
+
+/// Recibe un packfile del socket conservando los objetos recibidos si la conexión se corta
+/// antes de terminar, para poder retomar la descarga en un nuevo intento.
+///
+/// # Argumentos
+/// - `socket`: Canal con la otra parte.
+/// - `ofs_delta`: Indica si se negoció la capacidad `ofs-delta`.
+/// - `limits`: Límites de bytes, de objetos y de tamaño descomprimido de cada objeto.
+/// - `object_format`: Formato de los objetos, con el que se verifica el checksum final.
+///
+/// # Retorno
+/// Los objetos recibidos completos y la cantidad anunciada en el encabezado; si la conexión se
+/// corta antes del encabezado no se recibió ningún objeto. Si el packfile está mal formado,
+/// supera los límites o su checksum no coincide, se devuelve el error.
+///
+pub fn receive_packfile_partial(
+    socket: &mut dyn Read,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+) -> Result<ReceivedPackfile, UtilError> {
+    let mut header = [0u8; PACKFILE_HEADER_SIZE];
+    if socket.read_exact(&mut header).is_err() {
+        return Ok(ReceivedPackfile::default());
+    }
+    let objects = read_packfile_header(&mut header.as_slice())?;
+    log_debug(&format!("Objects: {}", objects));
+    read_packfile_data_partial(
+        socket,
+        &header,
+        objects as usize,
+        ofs_delta,
+        limits,
+        object_format,
+    )
 }
 
 /// Envía un mensaje a través de un socket a un servidor.
@@ -77,25 +310,11 @@ pub fn send_message(
     message: &str,
     error: UtilError,
 ) -> Result<(), UtilError> {
-    if socket.write(message.as_bytes()).is_err() {
-        return Err(error);
-    };
-
-    if socket.flush().is_err() {
-        return Err(error);
-    };
-    Ok(())
+    write_all_retry(socket, message.as_bytes(), error)
 }
 
 pub fn send_bytes(writer: &mut dyn Write, bytes: &[u8], error: UtilError) -> Result<(), UtilError> {
-    if writer.write_all(bytes).is_err() {
-        return Err(error);
-    };
-
-    if writer.flush().is_err() {
-        return Err(error);
-    };
-    Ok(())
+    write_all_retry(writer, bytes, error)
 }
 
 /// Finaliza la conexión enviando un paquete de finalización al servidor.
@@ -153,7 +372,65 @@ pub fn received_message(
 mod tests {
     use super::*;
     use crate::consts::{FLUSH_PKT, PKT_NAK};
+    use crate::git_transport::references::reference_discovery;
+    use crate::util::objects::ObjectType;
+    use flate2::{write::ZlibEncoder, Compression};
     use std::io::Cursor;
+    use std::net::TcpListener;
+
+    /// Lector que falla con `WouldBlock` la cantidad de veces indicada antes de entregar los datos.
+    struct StallingReader {
+        stalls: usize,
+        data: Cursor<Vec<u8>>,
+    }
+
+    impl Read for StallingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.stalls > 0 {
+                self.stalls -= 1;
+                return Err(io::Error::from(ErrorKind::WouldBlock));
+            }
+            self.data.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_read_exact_retry_recovers_from_transient_errors() {
+        let mut reader = StallingReader {
+            stalls: NETWORK_MAX_RETRIES,
+            data: Cursor::new(b"0008NAK\n".to_vec()),
+        };
+        let mut buffer = [0u8; 4];
+        assert_eq!(
+            read_exact_retry(&mut reader, &mut buffer, UtilError::GenericError),
+            Ok(())
+        );
+        assert_eq!(&buffer, b"0008");
+
+        let mut reader = StallingReader {
+            stalls: NETWORK_MAX_RETRIES + 1,
+            data: Cursor::new(b"0008NAK\n".to_vec()),
+        };
+        assert_eq!(
+            read_exact_retry(&mut reader, &mut buffer, UtilError::GenericError),
+            Err(UtilError::NetworkTimeout)
+        );
+    }
+
+    #[test]
+    fn test_reference_discovery_times_out_on_stalled_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Falló al crear el listener");
+        let address = listener
+            .local_addr()
+            .expect("Falló al obtener la dirección");
+        let mut client = TcpStream::connect(address).expect("Falló al conectar");
+        let (_server, _) = listener.accept().expect("Falló al aceptar la conexión");
+        set_stream_timeout(&client, Duration::from_millis(20)).expect("Falló el timeout");
+
+        let message = "0032git-upload-pack /repo\0host=127.0.0.1\0".to_string();
+        let result = reference_discovery(&mut client, message, "repo", &[]);
+        assert_eq!(result.map(|_| ()), Err(UtilError::NetworkTimeout));
+    }
 
     #[test]
     fn send_message_sends_data_to_socket() {
@@ -209,4 +486,150 @@ mod tests {
         let result = received_message(&mut stream, message, UtilError::GenericError);
         assert!(result.is_err());
     }
+
+    /// Arma un packfile con un único blob que anuncia el tamaño indicado.
+    fn packfile_with_blob(content: &[u8], declared_length: usize) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).expect("Falló al comprimir");
+        let mut packfile = b"PACK".to_vec();
+        packfile.extend(2u32.to_be_bytes());
+        packfile.extend(1u32.to_be_bytes());
+        packfile.extend(ObjectEntry::new(ObjectType::Blob, declared_length).to_bytes());
+        packfile.extend(encoder.finish().expect("Falló al comprimir"));
+        packfile
+    }
+
+    #[test]
+    fn test_receive_packfile_partial() {
+        use sha1::{Digest, Sha1};
+
+        let mut packfile = b"PACK".to_vec();
+        packfile.extend(2u32.to_be_bytes());
+        packfile.extend(2u32.to_be_bytes());
+        let first_object = packfile.len();
+        for content in [b"hola".as_slice(), b"mundo"] {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(content).expect("Falló al comprimir");
+            packfile.extend(ObjectEntry::new(ObjectType::Blob, content.len()).to_bytes());
+            packfile.extend(encoder.finish().expect("Falló al comprimir"));
+        }
+        let trailer = Sha1::digest(&packfile);
+        packfile.extend(trailer);
+        let unlimited = PackfileLimits::unlimited();
+        let receive = |packfile: &[u8], limits: &PackfileLimits| {
+            receive_packfile_partial(&mut &packfile[..], false, limits, ObjectFormat::Sha1)
+        };
+
+        let received = receive(&packfile, &unlimited).expect("Falló al recibir el packfile");
+        assert!(received.is_complete());
+        assert_eq!(received.objects[1].1, b"mundo");
+
+        // Cortado en el segundo objeto o en el checksum: se conservan los objetos completos
+        let received =
+            receive(&packfile[..packfile.len() - 23], &unlimited).expect("Falló al recibir");
+        assert_eq!((received.objects.len(), received.expected), (1, 2));
+        assert!(!received.is_complete());
+        let received =
+            receive(&packfile[..packfile.len() - 5], &unlimited).expect("Falló al recibir");
+        assert_eq!(received.objects.len(), 2);
+        assert!(!received.is_complete());
+
+        // Un packfile corrupto no se toma como cortado
+        let mut corrupted = packfile.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert_eq!(
+            receive(&corrupted, &unlimited).map(|received| received.objects.len()),
+            Err(UtilError::PackfileChecksumMismatch)
+        );
+        let mut corrupted = packfile.clone();
+        corrupted[first_object + 3] ^= 0xff;
+        assert!(receive(&corrupted, &unlimited).is_err());
+
+        let limits = PackfileLimits {
+            max_objects: 1,
+            ..PackfileLimits::unlimited()
+        };
+        assert!(matches!(
+            receive(&packfile, &limits),
+            Err(UtilError::PackfileTooLarge(_))
+        ));
+        let limits = PackfileLimits {
+            max_bytes: 10,
+            ..PackfileLimits::unlimited()
+        };
+        assert!(matches!(
+            receive(&packfile, &limits),
+            Err(UtilError::PackfileTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_receive_packfile_with_limits() {
+        let content = vec![0u8; 100_000];
+        let limits = PackfileLimits {
+            max_bytes: 10_000,
+            max_object_size: 1_000,
+            max_objects: 10,
+        };
+        let too_large = UtilError::PackfileTooLarge("un objeto de más de 1000 bytes".to_string());
+
+        // El objeto anuncia su tamaño real
+        let packfile = packfile_with_blob(&content, content.len());
+        let received = receive_packfile_with_limits(
+            &mut Cursor::new(packfile),
+            false,
+            &limits,
+            ObjectFormat::Sha1,
+        );
+        assert_eq!(received, Err(too_large.clone()));
+
+        // El objeto miente sobre su tamaño: la descompresión se corta al superar el límite
+        let packfile = packfile_with_blob(&content, 10);
+        let received = receive_packfile_with_limits(
+            &mut Cursor::new(packfile),
+            false,
+            &limits,
+            ObjectFormat::Sha1,
+        );
+        assert_eq!(received, Err(too_large));
+
+        let limits = PackfileLimits {
+            max_bytes: 50,
+            ..PackfileLimits::unlimited()
+        };
+        let packfile = packfile_with_blob(&content, content.len());
+        let received = receive_packfile_with_limits(
+            &mut Cursor::new(packfile.clone()),
+            false,
+            &limits,
+            ObjectFormat::Sha1,
+        );
+        assert_eq!(
+            received,
+            Err(UtilError::PackfileTooLarge("más de 50 bytes".to_string()))
+        );
+
+        let limits = PackfileLimits {
+            max_objects: 0,
+            ..PackfileLimits::unlimited()
+        };
+        let received = receive_packfile_with_limits(
+            &mut Cursor::new(packfile.clone()),
+            false,
+            &limits,
+            ObjectFormat::Sha1,
+        );
+        assert!(matches!(received, Err(UtilError::PackfileTooLarge(_))));
+
+        let unlimited = PackfileLimits::unlimited();
+        let received = receive_packfile_with_limits(
+            &mut Cursor::new(packfile),
+            false,
+            &unlimited,
+            ObjectFormat::Sha1,
+        )
+        .expect("Falló al recibir el packfile");
+        assert_eq!(received[0].1, content);
+    }
 }
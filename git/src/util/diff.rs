@@ -0,0 +1,219 @@
+/// Cantidad de líneas de contexto que se muestran alrededor de cada cambio.
+const CONTEXT_LINES: usize = 3;
+
+/// Representa una línea del resultado de comparar dos contenidos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Equal(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Compara dos contenidos línea por línea utilizando la subsecuencia común más larga.
+/// Devuelve las líneas en orden, indicando si se mantienen, se agregan o se eliminan.
+/// ###Parametros:
+/// 'old': contenido original
+/// 'new': contenido nuevo
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j]: largo de la subsecuencia común más larga entre old[i..] y new[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            result.push(DiffLine::Equal(old_lines[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old_lines[i].to_string()));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    for line in &old_lines[i..] {
+        result.push(DiffLine::Removed(line.to_string()));
+    }
+    for line in &new_lines[j..] {
+        result.push(DiffLine::Added(line.to_string()));
+    }
+    result
+}
+
+/// Cuenta las líneas agregadas y eliminadas entre dos contenidos.
+/// Devuelve `(agregadas, eliminadas)`.
+/// ###Parametros:
+/// 'old': contenido original
+/// 'new': contenido nuevo
+pub fn count_changes(old: &str, new: &str) -> (usize, usize) {
+    diff_lines(old, new)
+        .iter()
+        .fold((0, 0), |(additions, deletions), line| match line {
+            DiffLine::Added(_) => (additions + 1, deletions),
+            DiffLine::Removed(_) => (additions, deletions + 1),
+            DiffLine::Equal(_) => (additions, deletions),
+        })
+}
+
+/// Indica si un contenido es binario. Como en git, se considera binario si contiene un byte nulo.
+/// ###Parametros:
+/// 'content': contenido a verificar
+pub fn is_binary(content: &str) -> bool {
+    content.contains('\0')
+}
+
+/// Genera el diff en formato unificado entre dos contenidos.
+/// Si los contenidos son iguales devuelve un string vacío.
+/// ###Parametros:
+/// 'old_path': path del archivo original (`/dev/null` si el archivo es nuevo)
+/// 'new_path': path del archivo nuevo (`/dev/null` si el archivo fue eliminado)
+/// 'old': contenido original
+/// 'new': contenido nuevo
+pub fn format_unified_diff(old_path: &str, new_path: &str, old: &str, new: &str) -> String {
+    let hunks = diff_hunks(old, new);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let mut formatted_result = format!("--- {}\n+++ {}\n", old_path, new_path);
+    for hunk in hunks {
+        formatted_result.push_str(&hunk);
+    }
+    formatted_result
+}
+
+/// Genera los hunks del diff entre dos contenidos, cada uno con su encabezado
+/// `@@ -inicio,largo +inicio,largo @@` y sus líneas de contexto.
+/// ###Parametros:
+/// 'old': contenido original
+/// 'new': contenido nuevo
+pub fn diff_hunks(old: &str, new: &str) -> Vec<String> {
+    let lines = diff_lines(old, new);
+    get_hunks(&lines)
+        .into_iter()
+        .map(|(start, end)| format_hunk(&lines, start, end))
+        .collect()
+}
+
+/// Agrupa los cambios en hunks, cada uno con sus líneas de contexto.
+/// Devuelve los rangos `[inicio, fin)` de cada hunk dentro de las líneas del diff.
+/// ###Parametros:
+/// 'lines': líneas del diff
+fn get_hunks(lines: &[DiffLine]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if let DiffLine::Equal(_) = line {
+            continue;
+        }
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + 1 + CONTEXT_LINES).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+/// Formatea un hunk con su encabezado `@@ -inicio,largo +inicio,largo @@`.
+/// ###Parametros:
+/// 'lines': líneas del diff
+/// 'start': índice de la primera línea del hunk
+/// 'end': índice siguiente a la última línea del hunk
+fn format_hunk(lines: &[DiffLine], start: usize, end: usize) -> String {
+    let count_old = |line: &DiffLine| !matches!(line, DiffLine::Added(_));
+    let count_new = |line: &DiffLine| !matches!(line, DiffLine::Removed(_));
+    let old_before = lines[..start].iter().filter(|l| count_old(l)).count();
+    let new_before = lines[..start].iter().filter(|l| count_new(l)).count();
+    let old_len = lines[start..end].iter().filter(|l| count_old(l)).count();
+    let new_len = lines[start..end].iter().filter(|l| count_new(l)).count();
+    let old_start = if old_len == 0 {
+        old_before
+    } else {
+        old_before + 1
+    };
+    let new_start = if new_len == 0 {
+        new_before
+    } else {
+        new_before + 1
+    };
+
+    let mut formatted_result = format!(
+        "@@ -{},{} +{},{} @@\n",
+        old_start, old_len, new_start, new_len
+    );
+    for line in &lines[start..end] {
+        let formatted_line = match line {
+            DiffLine::Equal(content) => format!(" {}\n", content),
+            DiffLine::Added(content) => format!("+{}\n", content),
+            DiffLine::Removed(content) => format!("-{}\n", content),
+        };
+        formatted_result.push_str(&formatted_line);
+    }
+    formatted_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let result = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(
+            result,
+            vec![
+                DiffLine::Equal("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Equal("c".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_changes() {
+        assert_eq!(count_changes("a\nb\nc\n", "a\nc\nd\ne\n"), (2, 1));
+        assert_eq!(count_changes("", "hola\n"), (1, 0));
+        assert_eq!(count_changes("igual\n", "igual\n"), (0, 0));
+        assert!(is_binary("PNG\0\u{1}"));
+        assert!(!is_binary("texto\n"));
+    }
+
+    #[test]
+    fn test_format_unified_diff_new_file() {
+        let result = format_unified_diff("/dev/null", "b/test.txt", "", "hola\nmundo\n");
+        assert_eq!(
+            result,
+            "--- /dev/null\n+++ b/test.txt\n@@ -0,0 +1,2 @@\n+hola\n+mundo\n"
+        );
+    }
+
+    #[test]
+    fn test_format_unified_diff_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\nuno\n3\n4\n5\n6\n7\n8\n9\n10\n11\ndoce\n";
+        let result = format_unified_diff("a/n.txt", "b/n.txt", old, new);
+        assert_eq!(
+            result,
+            "--- a/n.txt\n+++ b/n.txt\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+uno\n 3\n 4\n 5\n\
+             @@ -9,4 +9,4 @@\n 9\n 10\n 11\n-12\n+doce\n"
+        );
+        assert!(format_unified_diff("a/n.txt", "b/n.txt", old, old).is_empty());
+    }
+}
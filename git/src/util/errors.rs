@@ -77,6 +77,7 @@ pub enum UtilError {
     RemoveFileError,
     WriteFileError,
     CopyFileError,
+    RenameFileError,
     CreateDirError,
     VisitDirectoryError,
     DirEntryError,
@@ -89,6 +90,7 @@ pub enum UtilError {
     BranchNotFound(String),
     SendMessageReferenceUpdate,
     ObjectSerialization,
+    PackfileObjectCountMismatch(usize, usize),
     SendSha1Packfile,
     ReceiveReferenceUpdateRequest,
     InvalidReferenceUpdateRequest,
@@ -97,6 +99,26 @@ pub enum UtilError {
     SendStatusUpdateRequest,
     CloseConnection,
     NotDirectory,
+    ObjectNotFoundInAlternates(String),
+    ObjectNotFound(String),
+    ReferenceNotFound(String),
+    SymbolicRefTooDeep(String),
+    InvalidReferenceName(String),
+    OfsDeltaBaseNotFound(usize),
+    OfsDeltaNotNegotiated,
+    UnresolvedDeltas(usize),
+    InvalidDelta,
+    NetworkTimeout,
+    UploadArchiveRejected(String),
+    UnsupportedFilter(String),
+    MissingPromisorObject(String),
+    InvalidIndex(String),
+    PackfileTooLarge(String),
+    PackfileTruncated,
+    PackfileChecksumMismatch,
+    RemoteError(String),
+    InvalidObjectHeader(String),
+    AccessDenied(String),
 }
 
 fn format_error(error: &UtilError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -172,6 +194,7 @@ fn format_error(error: &UtilError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         UtilError::RemoveFileError => write!(f, "No se pudo eliminar el archivo"),
         UtilError::WriteFileError => write!(f, "Fallo al escribir en el archivo"),
         UtilError::CopyFileError => write!(f, "Fallo al copiar el archivo"),
+        UtilError::RenameFileError => write!(f, "No se pudo renombrar el archivo"),
         UtilError::CreateDirError => write!(f, "Fallo al crear el directorio"),
         UtilError::ReadDirError => write!(f, "Falló al leer el directorio"),
         UtilError::DirEntryError => write!(f, "Falló al obtener la entrada del directorio"),
@@ -186,6 +209,7 @@ fn format_error(error: &UtilError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         UtilError::BranchNotFound(s) => write!(f, "BranchNotFound: No se encontró la rama: {}", s),
         UtilError::SendMessageReferenceUpdate => write!(f, "SendMessageReferenceUpdate: Error al enviar el mensaje de actualización de referencia."),
         UtilError::ObjectSerialization => write!(f, "ObjectSerialization: Error al serializar el objeto."),
+        UtilError::PackfileObjectCountMismatch(expected, sent) => write!(f, "PackfileObjectCountMismatch: Se anunciaron {} objetos en el packfile pero se enviaron {}.", expected, sent),
         UtilError::SendSha1Packfile => write!(f, "SendSha1Packfile: Error al enviar el sha1 del packfile."),
         UtilError::ReceiveReferenceUpdateRequest => write!(f, "ReceiveReferenceUpdateRequest: Error al recibir la solicitud de actualización de referencia."),
         UtilError::InvalidReferenceUpdateRequest => write!(f, "InvalidReferenceUpdateRequest: Solicitud de actualización de referencia inválida."),
@@ -194,6 +218,26 @@ fn format_error(error: &UtilError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         UtilError::SendStatusUpdateRequest => write!(f, "SendStatusUpdateRequest: Error al enviar la solicitud de actualización de estado."),
         UtilError::CloseConnection => write!(f, "CloseConnection: Error al cerrar la conexión."),
         UtilError::NotDirectory => write!(f, "NotDirectory: No es un directorio."),
+        UtilError::ObjectNotFoundInAlternates(hash) => write!(f, "ObjectNotFoundInAlternates: No se encontró el objeto {} ni en el repositorio ni en sus alternates.", hash),
+        UtilError::ObjectNotFound(hash) => write!(f, "ObjectNotFound: No se encontró el objeto {} en el repositorio.", hash),
+        UtilError::ReferenceNotFound(reference) => write!(f, "ReferenceNotFound: No se encontró la referencia {}.", reference),
+        UtilError::SymbolicRefTooDeep(reference) => write!(f, "SymbolicRefTooDeep: La referencia {} tiene demasiados niveles de referencias simbólicas.", reference),
+        UtilError::UploadArchiveRejected(reason) => write!(f, "UploadArchiveRejected: {}", reason),
+        UtilError::NetworkTimeout => write!(f, "NetworkTimeout: La otra parte no respondió a tiempo."),
+        UtilError::OfsDeltaNotNegotiated => write!(f, "OfsDeltaNotNegotiated: El packfile contiene un delta por offset sin haberse negociado ofs-delta."),
+        UtilError::UnresolvedDeltas(count) => write!(f, "UnresolvedDeltas: El packfile contiene {} deltas cuyo objeto base no se encontró.", count),
+        UtilError::InvalidDelta => write!(f, "InvalidDelta: Las instrucciones del delta no corresponden a su objeto base."),
+        UtilError::OfsDeltaBaseNotFound(offset) => write!(f, "OfsDeltaBaseNotFound: No se encontró el objeto base del delta en el offset {} del packfile.", offset),
+        UtilError::InvalidReferenceName(name) => write!(f, "InvalidReferenceName: '{}' no es un nombre de referencia válido.", name),
+        UtilError::UnsupportedFilter(filter) => write!(f, "UnsupportedFilter: El filtro '{}' no está soportado.", filter),
+        UtilError::MissingPromisorObject(hash) => write!(f, "MissingPromisorObject: El objeto {} se omitió en el clone parcial y todavía no se descargó.", hash),
+        UtilError::InvalidIndex(reason) => write!(f, "InvalidIndex: El index binario es inválido: {}.", reason),
+        UtilError::RemoteError(message) => write!(f, "RemoteError: El servidor respondió con un error: {}", message),
+        UtilError::InvalidObjectHeader(path) => write!(f, "InvalidObjectHeaderError: El header del objeto {} no es válido.", path),
+        UtilError::PackfileTooLarge(limit) => write!(f, "PackfileTooLarge: El packfile supera el límite permitido: {}.", limit),
+        UtilError::PackfileTruncated => write!(f, "PackfileTruncated: El packfile terminó antes de completar un objeto."),
+        UtilError::PackfileChecksumMismatch => write!(f, "PackfileChecksumMismatch: El checksum final del packfile no coincide con su contenido."),
+        UtilError::AccessDenied(repo) => write!(f, "AccessDenied: No tiene permiso para realizar esta operación en el repositorio {}.", repo),
 
     }
 }
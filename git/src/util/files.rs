@@ -7,6 +7,10 @@ use std::path::Path;
 
 use super::errors::UtilError;
 use super::validation::join_paths_correctly;
+use crate::consts::{
+    COMMIT_EDITMSG, COMMONDIR_FILE, EXECUTABLE_FILE, FILE, GITDIR_PREFIX, GIT_DIR, HEAD, INDEX,
+    LOGS_DIR, SYMLINK, TEMP_FILE_SUFFIX,
+};
 
 /// Verifica si un directorio está vacío
 /// ###Parametros:
@@ -62,6 +66,39 @@ pub fn create_file_replace(file: &str, content: &str) -> Result<(), UtilError> {
     Ok(())
 }
 
+/// Reemplaza el contenido de un archivo de forma atómica: el contenido se escribe en un archivo
+/// temporal junto al destino, que luego se renombra sobre él. Si el proceso se interrumpe antes
+/// del renombre, el archivo original queda intacto y solo sobra el temporal, que se pisa en la
+/// próxima escritura.
+/// ###Parametros:
+/// 'file': archivo a reemplazar.
+/// 'content': contenido que se escribirá en el archivo.
+pub fn write_file_atomic(file: &str, content: &str) -> Result<(), UtilError> {
+    write_bytes_atomic(file, content.as_bytes())
+}
+
+/// Igual que `write_file_atomic`, pero con contenido binario. Si falla la escritura o el
+/// renombre se borra el archivo temporal, de modo que nunca queda un archivo a medio escribir
+/// con el nombre del destino.
+/// ###Parametros:
+/// 'file': archivo a reemplazar.
+/// 'content': bytes que se escribirán en el archivo.
+pub fn write_bytes_atomic(file: &str, content: &[u8]) -> Result<(), UtilError> {
+    let temp_file = format!("{}{}", file, TEMP_FILE_SUFFIX);
+    let mut temp = match fs::File::create(&temp_file) {
+        Ok(temp) => temp,
+        Err(_) => return Err(UtilError::CreateFileError),
+    };
+    let result = match temp.write_all(content).and_then(|_| temp.sync_all()) {
+        Ok(_) => fs::rename(&temp_file, file).map_err(|_| UtilError::RenameFileError),
+        Err(_) => Err(UtilError::WriteFileError),
+    };
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_file);
+    }
+    result
+}
+
 /// Crea un archivo si no existe.
 /// ###Parametros:
 /// 'file': archivo a crear.
@@ -143,7 +180,7 @@ pub fn delete_file(path_file: &str) -> Result<(), UtilError> {
 /// y el segundo es el nombre del repositorio.
 pub fn is_git_initialized(current_src: &str) -> (bool, String) {
     let mut result = (false, "".to_string());
-    let path_git = join_paths_correctly(current_src, ".git");
+    let path_git = follow_gitdir_link(&join_paths_correctly(current_src, GIT_DIR));
     if fs::read_dir(path_git).is_ok() {
         let parts: Vec<&str> = current_src.split('/').collect();
         let name = parts[parts.len() - 1];
@@ -223,6 +260,177 @@ pub fn file_exists(file_path: &str) -> bool {
     path.is_file()
 }
 
+/// Si `git_dir` es un archivo `.git` con una línea `gitdir: <ruta>`, como el de un working tree
+/// enlazado, devuelve la ruta a la que apunta. En otro caso devuelve `git_dir` sin cambios.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Ruta del directorio (o archivo) `.git`.
+///
+/// # Retorna
+///
+/// La ruta del directorio git. Las rutas relativas se interpretan respecto del directorio que
+/// contiene el archivo `.git`.
+///
+pub fn follow_gitdir_link(git_dir: &str) -> String {
+    let path = Path::new(git_dir);
+    if !path.is_file() {
+        return git_dir.to_string();
+    }
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return git_dir.to_string(),
+    };
+    match content.trim().strip_prefix(GITDIR_PREFIX) {
+        Some(target) => match path.parent() {
+            Some(parent) => parent.join(target.trim()).to_string_lossy().to_string(),
+            None => target.trim().to_string(),
+        },
+        None => git_dir.to_string(),
+    }
+}
+
+/// Devuelve el directorio git propio del working tree, siguiendo el archivo `.git` de un
+/// working tree enlazado. Allí se guardan HEAD y el index.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del working tree.
+///
+pub fn get_git_dir(directory: &str) -> String {
+    follow_gitdir_link(&format!("{}/{}", directory, GIT_DIR))
+}
+
+/// Devuelve el directorio git compartido por todos los working trees, donde se guardan los
+/// objetos y las referencias. En un working tree enlazado es el indicado por el archivo
+/// `commondir`; en el working tree principal es el mismo directorio git.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Ruta del directorio (o archivo) `.git`.
+///
+pub fn get_common_git_dir(git_dir: &str) -> String {
+    let git_dir = follow_gitdir_link(git_dir);
+    match fs::read_to_string(Path::new(&git_dir).join(COMMONDIR_FILE)) {
+        Ok(common_dir) => Path::new(&git_dir)
+            .join(common_dir.trim())
+            .to_string_lossy()
+            .to_string(),
+        Err(_) => git_dir,
+    }
+}
+
+/// Devuelve la ruta de un archivo del directorio git. HEAD, el index, `COMMIT_EDITMSG` y el
+/// reflog de HEAD son propios de cada working tree; el resto se busca en el directorio
+/// compartido.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del working tree.
+/// * `name` - Ruta del archivo relativa al directorio git, por ejemplo `refs/heads/master`.
+///
+pub fn get_git_path(directory: &str, name: &str) -> String {
+    let git_dir = get_git_dir(directory);
+    let per_worktree =
+        [HEAD, INDEX, COMMIT_EDITMSG].contains(&name) || name == format!("{}/{}", LOGS_DIR, HEAD);
+    if per_worktree {
+        return format!("{}/{}", git_dir, name);
+    }
+    format!("{}/{}", get_common_git_dir(&git_dir), name)
+}
+
+/// Devuelve el modo con el que git guarda un archivo del working directory: `120000` para los
+/// links simbólicos, `100755` para los ejecutables y `100644` para el resto.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo.
+///
+pub fn get_file_mode(path: &str) -> &'static str {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return FILE,
+    };
+    if metadata.file_type().is_symlink() {
+        return SYMLINK;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 != 0 {
+            return EXECUTABLE_FILE;
+        }
+    }
+    FILE
+}
+
+/// Lee el contenido con el que se guarda un archivo del working directory. De un link simbólico
+/// se guarda la ruta a la que apunta, no el contenido del archivo apuntado.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo.
+///
+pub fn read_working_file(path: &str) -> Result<Vec<u8>, UtilError> {
+    if get_file_mode(path) == SYMLINK {
+        return match fs::read_link(path) {
+            Ok(target) => Ok(target.to_string_lossy().as_bytes().to_vec()),
+            Err(_) => Err(UtilError::ReadFileError),
+        };
+    }
+    read_file(open_file(path)?)
+}
+
+/// Escribe un archivo del working directory con el modo con el que está guardado en el tree.
+///
+/// Los ejecutables quedan con permiso de ejecución y los links simbólicos se crean apuntando al
+/// contenido del blob. En sistemas que no son Unix los links se escriben como archivos regulares.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo.
+/// * `content` - Contenido del blob.
+/// * `mode` - Modo del archivo en el tree, por ejemplo `100755`.
+///
+pub fn write_file_with_mode(path: &str, content: &str, mode: &str) -> Result<(), UtilError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let is_symlink = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+        if is_symlink || mode == SYMLINK {
+            let _ = fs::remove_file(path);
+        }
+        if mode == SYMLINK {
+            return match std::os::unix::fs::symlink(content, path) {
+                Ok(_) => Ok(()),
+                Err(_) => Err(UtilError::CreateFileError),
+            };
+        }
+        create_file_replace(path, content)?;
+        let mut permissions = match fs::metadata(path) {
+            Ok(metadata) => metadata.permissions(),
+            Err(_) => return Err(UtilError::OpenFileError),
+        };
+        let current = permissions.mode();
+        let updated = match mode == EXECUTABLE_FILE {
+            true => current | ((current & 0o444) >> 2),
+            false => current & !0o111,
+        };
+        if updated != current {
+            permissions.set_mode(updated);
+            if fs::set_permissions(path, permissions).is_err() {
+                return Err(UtilError::WriteFileError);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mode;
+        create_file_replace(path, content)
+    }
+}
+
 /// Lista todos los archivos y carpetas en un directorio especificado.
 ///
 /// Esta función toma la ruta de un directorio como parámetro y devuelve un `Vec<String>`
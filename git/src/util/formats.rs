@@ -1,11 +1,15 @@
+use crate::consts::{
+    NULL, OBJECT_HEADER_MAX_LEN, OBJECT_INITIAL_CAPACITY_MAX, OBJECT_READ_BUFFER_SIZE, SPACE,
+};
 use crate::util::errors::UtilError;
 extern crate flate2;
+use flate2::bufread;
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use sha1::{Digest, Sha1};
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{self, BufReader, Read, Write};
 
 /// Dado un contenido en bytes, genera el valor hash
 /// ###Parametros:
@@ -141,6 +145,135 @@ pub fn decompression_object(path: &str) -> Result<Vec<u8>, UtilError> {
     Ok(uncompressed_content)
 }
 
+/// Lector de un objeto suelto que lo descomprime a medida que se lee, sin cargarlo entero en
+/// memoria.
+///
+/// Al crearlo se descomprime solo el header (`<tipo> <tamaño>\0`); luego, al leerlo con `Read`,
+/// se obtiene el contenido del objeto sin el header. Los bytes comprimidos se leen de a
+/// `OBJECT_READ_BUFFER_SIZE`.
+///
+/// - `object_type`: Tipo del objeto (`blob`, `tree`, `commit` o `tag`).
+/// - `size`: Tamaño del contenido del objeto, sin el header.
+/// - `decoder`: Descompresor posicionado al comienzo del contenido.
+///
+pub struct ObjectStream<R: Read> {
+    object_type: String,
+    size: usize,
+    decoder: bufread::ZlibDecoder<BufReader<R>>,
+}
+
+impl<R: Read> ObjectStream<R> {
+    /// Crea el lector a partir del contenido comprimido del objeto y lee su header.
+    ///
+    /// # Argumentos
+    ///
+    /// * `compressed` - Contenido comprimido del objeto, por ejemplo el archivo del objeto.
+    /// * `name` - Nombre del objeto para los mensajes de error, por ejemplo su ruta.
+    ///
+    /// # Errores
+    ///
+    /// Devuelve `UtilError::InvalidObjectHeader` si el header no termina dentro de los primeros
+    /// `OBJECT_HEADER_MAX_LEN` bytes o su tamaño no es un número, y `UtilError::ReadFileError`
+    /// si falla la descompresión.
+    ///
+    pub fn new(compressed: R, name: &str) -> Result<Self, UtilError> {
+        let reader = BufReader::with_capacity(OBJECT_READ_BUFFER_SIZE, compressed);
+        let mut decoder = bufread::ZlibDecoder::new(reader);
+        let invalid = || UtilError::InvalidObjectHeader(name.to_string());
+
+        let mut header = Vec::with_capacity(OBJECT_HEADER_MAX_LEN);
+        let mut byte = [0u8; 1];
+        loop {
+            match decoder.read(&mut byte) {
+                Ok(0) => return Err(invalid()),
+                Ok(_) if byte[0] == NULL => break,
+                Ok(_) if header.len() < OBJECT_HEADER_MAX_LEN => header.push(byte[0]),
+                Ok(_) => return Err(invalid()),
+                Err(_) => return Err(UtilError::ReadFileError),
+            }
+        }
+        let header = String::from_utf8_lossy(&header).to_string();
+        let (object_type, size) = header.split_once(SPACE as char).ok_or_else(invalid)?;
+        let size = size.parse::<usize>().map_err(|_| invalid())?;
+        Ok(ObjectStream {
+            object_type: object_type.to_string(),
+            size,
+            decoder,
+        })
+    }
+
+    pub fn get_type(&self) -> &str {
+        &self.object_type
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// Lee todo el contenido del objeto, sin el header, en un único buffer. El tamaño indicado
+    /// en el header solo se usa para reservar memoria hasta `OBJECT_INITIAL_CAPACITY_MAX`, ya
+    /// que un objeto corrupto puede declarar un tamaño arbitrario.
+    pub fn read_content(mut self) -> Result<Vec<u8>, UtilError> {
+        let mut content = Vec::with_capacity(self.size.min(OBJECT_INITIAL_CAPACITY_MAX));
+        if self.read_to_end(&mut content).is_err() {
+            return Err(UtilError::ReadFileError);
+        }
+        Ok(content)
+    }
+}
+
+impl<R: Read> Read for ObjectStream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.decoder.read(buf)
+    }
+}
+
+/// Abre un objeto suelto para leer su contenido a medida que se descomprime.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo del objeto.
+///
+/// # Errores
+///
+/// Devuelve `UtilError::OpenFileError` si no se puede abrir el archivo, o los errores de
+/// `ObjectStream::new` si su header no es válido.
+///
+pub fn open_object_stream(path: &str) -> Result<ObjectStream<File>, UtilError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return Err(UtilError::OpenFileError),
+    };
+    ObjectStream::new(file, path)
+}
+
+/// Descomprime el contenido de un objeto suelto sin su header. A diferencia de
+/// `decompression_object`, el contenido no se copia para quitarle el header.
+/// ###Parametros:
+/// 'path': ruta del archivo del objeto
+pub fn decompression_object_content(path: &str) -> Result<Vec<u8>, UtilError> {
+    open_object_stream(path)?.read_content()
+}
+
+/// Descomprime solo los primeros `limit` bytes de un objeto suelto, por ejemplo para leer su
+/// tipo y tamaño sin descomprimir el resto.
+/// ###Parametros:
+/// 'path': ruta del archivo del objeto
+/// 'limit': cantidad máxima de bytes descomprimidos que se devuelven
+pub fn decompression_object_prefix(path: &str, limit: usize) -> Result<Vec<u8>, UtilError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return Err(UtilError::OpenFileError),
+    };
+    let reader = BufReader::with_capacity(OBJECT_READ_BUFFER_SIZE, file);
+    let mut prefix = Vec::with_capacity(limit);
+    let decoder = bufread::ZlibDecoder::new(reader);
+    if decoder.take(limit as u64).read_to_end(&mut prefix).is_err() {
+        return Err(UtilError::ReadFileError);
+    }
+    Ok(prefix)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,4 +318,103 @@ mod tests {
         // Se borra el archivo de prueba
         std::fs::remove_file(test_file).expect("Falló al remover el archivo de prueba");
     }
+
+    /// Cuenta los bytes comprimidos que se leyeron.
+    struct CountingReader<'a> {
+        inner: &'a [u8],
+        read: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.read.set(self.read.get() + n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_object_stream_large_object() {
+        // Contenido pseudoaleatorio, para que comprimido siga ocupando varios megas
+        let size = 4 * 1024 * 1024;
+        let mut state: u32 = 2463534242;
+        let content: Vec<u8> = (0..size)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        let mut store = format!("blob {}\0", size).into_bytes();
+        store.extend_from_slice(&content);
+        let compressed = compressor_object_with_bytes_content(store).expect("Falló al comprimir");
+
+        let read = std::rc::Rc::new(std::cell::Cell::new(0));
+        let reader = CountingReader {
+            inner: &compressed,
+            read: read.clone(),
+        };
+        let mut stream = ObjectStream::new(reader, "blob").expect("Header inválido");
+        // Para el header solo se leyeron unos pocos bloques del archivo comprimido
+        let bound = 4 * OBJECT_READ_BUFFER_SIZE;
+        assert_eq!(stream.get_type(), "blob");
+        assert_eq!(stream.get_size(), size);
+        assert!(read.get() <= bound);
+
+        // El contenido se recorre de a bloques sin guardarlo entero en memoria
+        let mut first_block = [0u8; 16];
+        stream
+            .read_exact(&mut first_block)
+            .expect("Falló al leer el contenido");
+        assert_eq!(&first_block, &content[..16]);
+        assert!(read.get() <= bound);
+        let copied = io::copy(&mut stream, &mut io::sink()).expect("Falló al leer el contenido");
+        assert_eq!(copied as usize + 16, size);
+        assert_eq!(read.get(), compressed.len());
+
+        // El contenido completo se lee en un único buffer, sin una copia con el header
+        let test_file = "./test_object_stream_large";
+        std::fs::write(test_file, &compressed).expect("Falló al escribir el objeto");
+        let full = decompression_object_content(test_file);
+        let prefix = decompression_object_prefix(test_file, OBJECT_HEADER_MAX_LEN);
+        let invalid = decompression_object_content("./test_object_stream_no_existe");
+        std::fs::remove_file(test_file).expect("Falló al remover el archivo de prueba");
+
+        let full = full.expect("Falló al leer el contenido");
+        assert_eq!(full.len(), size);
+        assert!(full == content);
+        let prefix = prefix.expect("Falló al leer el header");
+        assert!(prefix.starts_with(format!("blob {}\0", size).as_bytes()));
+        assert_eq!(prefix.len(), OBJECT_HEADER_MAX_LEN);
+        assert_eq!(invalid, Err(UtilError::OpenFileError));
+    }
+
+    #[test]
+    fn test_object_stream_invalid_header() {
+        let compressed = compressor_object_with_bytes_content(b"blob sin fin".to_vec())
+            .expect("Falló al comprimir");
+        assert!(matches!(
+            ObjectStream::new(compressed.as_slice(), "objeto"),
+            Err(UtilError::InvalidObjectHeader(_))
+        ));
+        let compressed = compressor_object_with_bytes_content(b"blob abc\0hola".to_vec())
+            .expect("Falló al comprimir");
+        assert!(matches!(
+            ObjectStream::new(compressed.as_slice(), "objeto"),
+            Err(UtilError::InvalidObjectHeader(_))
+        ));
+    }
+
+    #[test]
+    fn test_object_stream_huge_declared_size() {
+        // Un header corrupto no hace reservar la memoria que declara
+        let store = format!("blob {}\0hola", usize::MAX).into_bytes();
+        let compressed = compressor_object_with_bytes_content(store).expect("Falló al comprimir");
+        let stream = ObjectStream::new(compressed.as_slice(), "blob").expect("Header inválido");
+        assert_eq!(stream.get_size(), usize::MAX);
+        let content = stream.read_content().expect("Falló al leer el contenido");
+        assert_eq!(content, b"hola");
+        assert!(content.capacity() <= OBJECT_INITIAL_CAPACITY_MAX);
+    }
 }
@@ -1,10 +1,252 @@
-use super::files::{create_file_replace, open_file, read_file_string};
-use crate::consts::{BLOB, DIRECTORY, FILE, GIT_DIR, INDEX};
+use super::files::{open_file, read_file};
+use crate::consts::{
+    BLOB, DIRECTORY, FILE, INDEX, INDEX_DEFAULT_MODE, INDEX_SIGNATURE, INDEX_VERSION,
+};
 use crate::util::errors::UtilError;
+use crate::util::files::get_git_dir;
+use crate::util::object_format::{get_object_format, ObjectFormat};
 use crate::util::objects::builder_object_tree;
+use crate::util::validation::normalize_path;
+use std::fs;
+
+/// Tamaño de los campos de una entrada del index binario anteriores al hash: diez campos de 32
+/// bits con los datos del archivo. Al hash le siguen los flags de 16 bits.
+const INDEX_ENTRY_STAT_SIZE: usize = 40;
+
+/// Tamaño del header del index binario: firma, versión y cantidad de entradas.
+const INDEX_HEADER_SIZE: usize = 12;
+
+/// Posición del modo del archivo entre los campos de 32 bits de una entrada.
+const INDEX_MODE_FIELD: usize = 6;
+
+/// Formato en el que está guardado el index.
+///
+/// - `Text`: una línea `<ruta> blob <hash>` por archivo, el formato que usaban las versiones
+///   anteriores de este cliente. Solo se lee: al guardarlo se convierte a binario.
+/// - `Binary`: el formato `DIRC` de git (versiones 2 y 3), con el que se guarda el index para
+///   que git lo pueda leer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFormat {
+    Text,
+    Binary,
+}
+
+/// Entrada del index.
+///
+/// - `path`: ruta del archivo, relativa al repositorio.
+/// - `hash`: hash del blob del archivo.
+/// - `stat`: datos del archivo guardados por git (fechas, dispositivo, inodo, modo, usuario,
+///   grupo y tamaño), en el orden del formato binario. En las entradas nuevas solo se completa
+///   el modo, por lo que git vuelve a comparar el contenido del archivo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub path: String,
+    pub hash: String,
+    pub stat: [u32; 10],
+}
+
+impl IndexEntry {
+    /// Crea una entrada para un archivo regular sin datos del archivo.
+    pub fn new(path: &str, hash: &str) -> IndexEntry {
+        let mut stat = [0; 10];
+        stat[INDEX_MODE_FIELD] = INDEX_DEFAULT_MODE;
+        IndexEntry {
+            path: path.to_string(),
+            hash: hash.to_string(),
+            stat,
+        }
+    }
+
+    /// Crea una entrada con el modo indicado en el formato de texto: `blob` para un archivo
+    /// regular o el modo en octal, por ejemplo `100755`. Un modo inválido se toma como regular.
+    pub fn with_mode(path: &str, hash: &str, mode: &str) -> IndexEntry {
+        let mut entry = IndexEntry::new(path, hash);
+        if mode != BLOB {
+            if let Ok(mode) = u32::from_str_radix(mode, 8) {
+                entry.stat[INDEX_MODE_FIELD] = mode;
+            }
+        }
+        entry
+    }
+
+    /// Devuelve el modo del archivo, por ejemplo `0o100644`.
+    pub fn get_mode(&self) -> u32 {
+        self.stat[INDEX_MODE_FIELD]
+    }
+
+    /// Devuelve el modo como se escribe en el formato de texto: `blob` para los archivos
+    /// regulares y el modo en octal para el resto.
+    pub fn get_text_mode(&self) -> String {
+        match self.get_mode() {
+            INDEX_DEFAULT_MODE => BLOB.to_string(),
+            mode => format!("{:o}", mode),
+        }
+    }
+}
+
+/// Index del repositorio, con las entradas en el formato en que está guardado.
+///
+/// Todos los comandos leen el index con `open_index`, que lo devuelve siempre como texto, y lo
+/// escriben con `write_index`, que lo guarda en el formato binario de git. Los hashes de las
+/// entradas y el checksum del archivo usan el formato de los objetos del repositorio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    pub format: IndexFormat,
+    pub entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Interpreta el contenido del archivo index, detectando el formato por la firma `DIRC`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content`: Contenido del archivo index.
+    /// * `object_format`: Formato de los objetos del repositorio.
+    ///
+    /// # Retorno
+    ///
+    /// Devuelve el index o un `UtilError::InvalidIndex` si el index binario es inválido.
+    ///
+    pub fn parse(content: &[u8], object_format: ObjectFormat) -> Result<Index, UtilError> {
+        if content.starts_with(INDEX_SIGNATURE) {
+            return Index::parse_binary(content, object_format);
+        }
+        let content = String::from_utf8_lossy(content);
+        Ok(Index::from_text(&content, IndexFormat::Text))
+    }
+
+    /// Crea el index a partir de las líneas `<ruta> <modo> <hash>` del formato de texto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content`: Contenido del index en formato de texto.
+    /// * `format`: Formato en el que se guardará el index.
+    ///
+    pub fn from_text(content: &str, format: IndexFormat) -> Index {
+        let entries = content
+            .lines()
+            .filter_map(parse_index_line)
+            .map(|(path, mode, hash)| IndexEntry::with_mode(&normalize_path(path), hash, mode))
+            .collect();
+        Index { format, entries }
+    }
+
+    /// Devuelve el index con el formato de texto, una línea `<ruta> <modo> <hash>` por entrada.
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{} {} {}", entry.path, entry.get_text_mode(), entry.hash))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Interpreta un index binario. Se verifica el checksum final y se ignoran las extensiones
+    /// (por ejemplo el cache de trees), que git vuelve a generar.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content`: Contenido del archivo index, que comienza con la firma `DIRC`.
+    /// * `object_format`: Formato de los objetos, que define el tamaño de los hashes.
+    ///
+    fn parse_binary(content: &[u8], object_format: ObjectFormat) -> Result<Index, UtilError> {
+        let invalid = |reason: &str| UtilError::InvalidIndex(reason.to_string());
+        let hash_len = object_format.raw_len();
+        let entry_fixed_size = INDEX_ENTRY_STAT_SIZE + hash_len + 2;
+        if content.len() < INDEX_HEADER_SIZE + hash_len {
+            return Err(invalid("el archivo es demasiado corto"));
+        }
+        let (body, checksum) = content.split_at(content.len() - hash_len);
+        if object_format.hash(body) != bytes_to_hex(checksum) {
+            return Err(invalid("el checksum no coincide"));
+        }
+        let version = read_u32(body, 4);
+        if version != 2 && version != 3 {
+            return Err(invalid(&format!("versión {} no soportada", version)));
+        }
+        let count = read_u32(body, 8) as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        let mut offset = INDEX_HEADER_SIZE;
+        for _ in 0..count {
+            if offset + entry_fixed_size > body.len() {
+                return Err(invalid("entrada incompleta"));
+            }
+            let mut stat = [0; 10];
+            for (i, field) in stat.iter_mut().enumerate() {
+                *field = read_u32(body, offset + i * 4);
+            }
+            let hash_start = offset + INDEX_ENTRY_STAT_SIZE;
+            let hash = bytes_to_hex(&body[hash_start..hash_start + hash_len]);
+            let flags =
+                u16::from_be_bytes([body[hash_start + hash_len], body[hash_start + hash_len + 1]]);
+            let mut path_start = offset + entry_fixed_size;
+            // En la versión 3 las entradas con el flag extendido tienen 16 bits más de flags
+            if version == 3 && flags & 0x4000 != 0 {
+                path_start += 2;
+            }
+            let path_end = match body[path_start.min(body.len())..]
+                .iter()
+                .position(|&byte| byte == 0)
+            {
+                Some(length) => path_start + length,
+                None => return Err(invalid("ruta sin terminar")),
+            };
+            let path = String::from_utf8_lossy(&body[path_start..path_end]).to_string();
+            // Las entradas se completan con entre 1 y 8 bytes nulos hasta un múltiplo de 8
+            let entry_size = path_end - offset;
+            offset += (entry_size + 8) & !7;
+            // Solo se cargan las entradas sin conflictos (stage 0)
+            if flags & 0x3000 == 0 {
+                entries.push(IndexEntry { path, hash, stat });
+            }
+        }
+        Ok(Index {
+            format: IndexFormat::Binary,
+            entries,
+        })
+    }
+
+    /// Devuelve el index en el formato binario de git (versión 2), con las entradas ordenadas por
+    /// ruta y el checksum al final.
+    ///
+    /// # Argumentos
+    ///
+    /// * `object_format`: Formato de los objetos, con el que se escriben los hashes y el checksum.
+    ///
+    pub fn to_binary(&self, object_format: ObjectFormat) -> Vec<u8> {
+        let mut entries: Vec<&IndexEntry> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.path.as_bytes().cmp(b.path.as_bytes()));
+
+        let mut content = Vec::new();
+        content.extend_from_slice(INDEX_SIGNATURE);
+        content.extend_from_slice(&INDEX_VERSION.to_be_bytes());
+        content.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for entry in entries {
+            let start = content.len();
+            for field in entry.stat {
+                content.extend_from_slice(&field.to_be_bytes());
+            }
+            // Un hash inválido se completa con ceros para no romper la estructura del archivo
+            let mut hash = hex_to_bytes(&entry.hash);
+            hash.resize(object_format.raw_len(), 0);
+            content.extend_from_slice(&hash);
+            let flags = entry.path.len().min(0xFFF) as u16;
+            content.extend_from_slice(&flags.to_be_bytes());
+            content.extend_from_slice(entry.path.as_bytes());
+            let entry_size = content.len() - start;
+            content.resize(start + ((entry_size + 8) & !7), 0);
+        }
+        let checksum = object_format.hash(&content);
+        content.extend_from_slice(&hex_to_bytes(&checksum));
+        content
+    }
+}
 
 /// Maneja el index del repositorio del cliente, lo abre y devuelve su contenido
 ///
+/// Si el index está guardado en el formato binario de git, se devuelve convertido al formato de
+/// texto, una línea `<ruta> blob <hash>` por archivo.
+///
 /// # Argumentos
 ///
 /// * `git_dir`: Contiene la dirección del repositorio.
@@ -16,8 +258,73 @@ use crate::util::objects::builder_object_tree;
 pub fn open_index(git_dir: &str) -> Result<String, UtilError> {
     let path_index = format!("{}/{}", git_dir, INDEX);
 
-    let index_file = open_file(&path_index)?;
-    read_file_string(index_file)
+    let content = read_file(open_file(&path_index)?)?;
+    if content.starts_with(INDEX_SIGNATURE) {
+        return Ok(Index::parse(&content, get_object_format(git_dir))?.to_text());
+    }
+    match String::from_utf8(content) {
+        Ok(content) => Ok(content),
+        Err(_) => Err(UtilError::ReadFileError),
+    }
+}
+
+/// Separa una línea del index en formato de texto en ruta, modo y hash. La ruta puede contener
+/// espacios, por lo que el modo y el hash se toman desde el final de la línea.
+///
+/// # Argumentos
+///
+/// * `line`: Línea `<ruta> <modo> <hash>` del index.
+///
+/// # Retorno
+///
+/// `(ruta, modo, hash)`, o `None` si a la línea le falta alguno de los campos.
+///
+pub fn parse_index_line(line: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = line.trim_end().rsplitn(3, ' ');
+    let hash = parts.next()?;
+    let mode = parts.next()?;
+    let path = parts.next()?;
+    if path.is_empty() || mode.is_empty() || hash.is_empty() {
+        return None;
+    }
+    Some((path, mode, hash))
+}
+
+/// Guarda el contenido del index, recibido en el formato de texto, en el formato binario de git.
+/// Si el index actual es binario se conservan los datos de los archivos cuyo blob no cambió; el
+/// modo es siempre el del contenido recibido.
+///
+/// # Argumentos
+///
+/// * `git_dir`: Contiene la dirección del repositorio.
+/// * `content`: Contenido del index en formato de texto.
+///
+/// # Retorno
+///
+/// Devuelve un error (UtilError) en caso de fallo.
+///
+pub fn write_index(git_dir: &str, content: &str) -> Result<(), UtilError> {
+    let path_index = format!("{}/{}", git_dir, INDEX);
+    let object_format = get_object_format(git_dir);
+    let previous = fs::read(&path_index).unwrap_or_default();
+    // Un index en el formato de texto no tiene datos de los archivos para conservar
+    let previous = match previous.starts_with(INDEX_SIGNATURE) {
+        true => Index::parse(&previous, object_format)?.entries,
+        false => Vec::new(),
+    };
+    let mut index = Index::from_text(content, IndexFormat::Binary);
+    for entry in index.entries.iter_mut() {
+        let old = previous.iter().find(|old| old.path == entry.path);
+        if let Some(old) = old.filter(|old| old.hash == entry.hash) {
+            let mode = entry.get_mode();
+            entry.stat = old.stat;
+            entry.stat[INDEX_MODE_FIELD] = mode;
+        }
+    }
+    match fs::write(&path_index, index.to_binary(object_format)) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(UtilError::WriteFileError),
+    }
 }
 
 /// Maneja el index del repositorio del cliente, vacía el contenido del mismo
@@ -31,9 +338,26 @@ pub fn open_index(git_dir: &str) -> Result<String, UtilError> {
 /// Devuelve un error (CommandsError) en caso de fallo.
 ///
 pub fn empty_index(directory: &str) -> Result<(), UtilError> {
-    let path_index = format!("{}/{}/{}", directory, GIT_DIR, INDEX);
-    create_file_replace(&path_index, "")?;
-    Ok(())
+    write_index(&get_git_dir(directory), "")
+}
+
+/// Lee un entero de 32 bits big endian en la posición indicada.
+fn read_u32(content: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&content[offset..offset + 4]);
+    u32::from_be_bytes(bytes)
+}
+
+/// Convierte bytes a su representación hexadecimal.
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Convierte un hash hexadecimal a sus bytes.
+fn hex_to_bytes(hash: &str) -> Vec<u8> {
+    (0..hash.len() / 2)
+        .filter_map(|i| u8::from_str_radix(&hash[i * 2..i * 2 + 2], 16).ok())
+        .collect()
 }
 
 /// Maneja el contenido del index del repositorio del cliente, creando los tree y sub tree correspondientes.
@@ -42,13 +366,18 @@ pub fn empty_index(directory: &str) -> Result<(), UtilError> {
 ///
 /// * `git_dir`: Contiene la dirección del repositorio.
 /// * `index_content`: Contenido del index.
+/// * `object_format`: Formato de los objetos del repositorio, con el que se calculan los hashes.
 ///
 /// # Retorno
 ///
 /// Devuelve un `Result` que contiene un (String) en caso de éxito o un error (UtilError) en caso de fallo.
 ///
-pub fn recovery_index(index_content: &str, git_dir: &str) -> Result<String, UtilError> {
-    let mut lines: Vec<String> = index_content.lines().map(String::from).collect();
+pub fn recovery_index(
+    index_content: &str,
+    git_dir: &str,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
+    let mut lines: Vec<String> = index_content.lines().map(normalize_path).collect();
     lines.sort();
 
     let mut tree = String::new();
@@ -56,12 +385,7 @@ pub fn recovery_index(index_content: &str, git_dir: &str) -> Result<String, Util
     let mut folder_name = String::new();
 
     for line in lines {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 3 {
-            let file_name = parts[0];
-            let mut mode = parts[1];
-            let hash = parts[2];
-
+        if let Some((file_name, mut mode, hash)) = parse_index_line(&line) {
             if mode == BLOB {
                 mode = FILE;
             }
@@ -71,28 +395,24 @@ pub fn recovery_index(index_content: &str, git_dir: &str) -> Result<String, Util
                     &mut tree,
                     &mut sub_tree,
                     &mut folder_name,
-                    file_name,
-                    mode,
-                    hash,
-                    git_dir,
+                    (file_name, mode, hash),
+                    (git_dir, object_format),
                 )?;
             } else {
                 handle_file_entry(
                     &mut tree,
                     &mut sub_tree,
                     &mut folder_name,
-                    file_name,
-                    mode,
-                    hash,
-                    git_dir,
+                    (file_name, mode, hash),
+                    (git_dir, object_format),
                 )?;
             }
         } else {
             return Err(UtilError::InvalidObjectLength);
         }
     }
-    handle_last_subtree(&mut tree, &sub_tree, &folder_name, git_dir)?;
-    let tree_hash = builder_object_tree(git_dir, &tree)?;
+    handle_last_subtree(&mut tree, &sub_tree, &folder_name, (git_dir, object_format))?;
+    let tree_hash = builder_object_tree(git_dir, &tree, object_format)?;
 
     Ok(tree_hash)
 }
@@ -104,6 +424,8 @@ pub fn recovery_index(index_content: &str, git_dir: &str) -> Result<String, Util
 /// * `tree`: Contiene la información del tree principal
 /// * `sub_tree`: Contiene la información del siguente sub-tree a crear
 /// * `folder_name`: Contiene el nombre de la carpeta actual para la iteración.
+/// * `entry`: Nombre, modo y hash de la entrada del index.
+/// * `repo`: Dirección del repositorio y formato de sus objetos.
 ///
 /// # Retorno
 ///
@@ -113,17 +435,15 @@ fn handle_folder_entry(
     tree: &mut String,
     sub_tree: &mut String,
     folder_name: &mut String,
-    file_name: &str,
-    mode: &str,
-    hash: &str,
-    git_dir: &str,
+    (file_name, mode, hash): (&str, &str, &str),
+    repo: (&str, ObjectFormat),
 ) -> Result<(), UtilError> {
     let path_parts: Vec<&str> = file_name.split('/').collect();
     let new_path: Vec<&str> = path_parts.clone().into_iter().skip(1).collect();
     let new_path_str = new_path.join("/");
 
     if folder_name != path_parts[0] {
-        handle_subtree(tree, sub_tree, folder_name, git_dir)?;
+        handle_subtree(tree, sub_tree, folder_name, repo)?;
         *folder_name = path_parts[0].to_string();
         let sub_blob = format!("{} {} {}\n", new_path_str, mode, hash);
         sub_tree.push_str(&sub_blob);
@@ -142,6 +462,8 @@ fn handle_folder_entry(
 /// * `tree`: Contiene la información del tree principal
 /// * `sub_tree`: Contiene la información del siguente sub-tree a crear
 /// * `folder_name`: Contiene el nombre de la carpeta actual para la iteración.
+/// * `entry`: Nombre, modo y hash de la entrada del index.
+/// * `repo`: Dirección del repositorio y formato de sus objetos.
 ///
 /// # Retorno
 ///
@@ -151,12 +473,10 @@ fn handle_file_entry(
     tree: &mut String,
     sub_tree: &mut String,
     folder_name: &mut str,
-    file_name: &str,
-    mode: &str,
-    hash: &str,
-    git_dir: &str,
+    (file_name, mode, hash): (&str, &str, &str),
+    repo: (&str, ObjectFormat),
 ) -> Result<(), UtilError> {
-    handle_subtree(tree, sub_tree, folder_name, git_dir)?;
+    handle_subtree(tree, sub_tree, folder_name, repo)?;
 
     let blob = format!("{} {} {}\n", mode, file_name, hash);
     tree.push_str(&blob);
@@ -171,7 +491,7 @@ fn handle_file_entry(
 /// * `tree`: Contiene la información del tree principal
 /// * `sub_tree`: Contiene la información del siguente sub-tree a crear
 /// * `folder_name`: Contiene el nombre de la carpeta actual para la iteración.
-/// * `git_dir`: Contiene la dirección del repositorio.
+/// * `repo`: Dirección del repositorio y formato de sus objetos.
 ///
 /// # Retorno
 ///
@@ -181,10 +501,10 @@ fn handle_subtree(
     tree: &mut String,
     sub_tree: &mut String,
     folder_name: &str,
-    git_dir: &str,
+    (git_dir, object_format): (&str, ObjectFormat),
 ) -> Result<(), UtilError> {
     if !sub_tree.is_empty() {
-        let hash_sub_tree = recovery_index(sub_tree, git_dir)?;
+        let hash_sub_tree = recovery_index(sub_tree, git_dir, object_format)?;
         let blob = format!("{} {} {}\n", DIRECTORY, folder_name, hash_sub_tree);
         tree.push_str(&blob);
         sub_tree.clear();
@@ -200,6 +520,7 @@ fn handle_subtree(
 /// * `tree`: Contiene la información del tree principal
 /// * `sub_tree`: Contiene la información del siguente sub-tree a crear
 /// * `folder_name`: Contiene el nombre de la carpeta actual para la iteración.
+/// * `repo`: Dirección del repositorio y formato de sus objetos.
 ///
 /// # Retorno
 ///
@@ -209,10 +530,10 @@ fn handle_last_subtree(
     tree: &mut String,
     sub_tree: &str,
     folder_name: &str,
-    git_dir: &str,
+    repo: (&str, ObjectFormat),
 ) -> Result<(), UtilError> {
     if !sub_tree.is_empty() {
-        handle_subtree(tree, &mut sub_tree.to_string(), folder_name, git_dir)?;
+        handle_subtree(tree, &mut sub_tree.to_string(), folder_name, repo)?;
     }
     Ok(())
 }
@@ -231,3 +552,134 @@ mod tests {
 
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        add::git_add, commit::git_commit, init::git_init, status::is_files_to_commit,
+    };
+    use crate::consts::GIT_DIR;
+    use crate::test_utils::test_commit;
+    use crate::util::files::create_file_replace;
+    use crate::util::objects::builder_object_blob;
+
+    #[test]
+    fn test_binary_index_round_trip() {
+        let mut executable = IndexEntry::new("src/run.sh", &"b".repeat(40));
+        executable.stat[INDEX_MODE_FIELD] = 0o100755;
+        let index = Index {
+            format: IndexFormat::Binary,
+            entries: vec![executable, IndexEntry::new("README", &"a".repeat(40))],
+        };
+        let content = index.to_binary(ObjectFormat::Sha1);
+
+        assert!(content.starts_with(b"DIRC\0\0\0\x02\0\0\0\x02"));
+        // Cada entrada ocupa un múltiplo de 8 bytes
+        assert_eq!(content.len(), 12 + 72 + 80 + 20);
+        let parsed = Index::parse(&content, ObjectFormat::Sha1).expect("Falló al leer el index");
+        assert_eq!(parsed.format, IndexFormat::Binary);
+        assert_eq!(parsed.entries[0].path, "README");
+        assert_eq!(parsed.entries[1].get_mode(), 0o100755);
+        assert_eq!(
+            parsed.to_text(),
+            format!(
+                "README blob {}\nsrc/run.sh 100755 {}",
+                "a".repeat(40),
+                "b".repeat(40)
+            )
+        );
+
+        let mut corrupted = content.clone();
+        corrupted[20] ^= 1;
+        assert!(matches!(
+            Index::parse(&corrupted, ObjectFormat::Sha1),
+            Err(UtilError::InvalidIndex(_))
+        ));
+
+        // En SHA-256 los hashes y el checksum ocupan 32 bytes
+        let index = Index {
+            format: IndexFormat::Binary,
+            entries: vec![IndexEntry::new("README", &"c".repeat(64))],
+        };
+        let content = index.to_binary(ObjectFormat::Sha256);
+        assert_eq!(content.len(), 12 + 88 + 32);
+        let parsed = Index::parse(&content, ObjectFormat::Sha256).expect("Falló al leer el index");
+        assert_eq!(parsed, index);
+    }
+
+    #[test]
+    fn test_commands_write_binary_index() {
+        let directory = "./test_index_write_binary";
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        git_init(directory).expect("Falló al crear el repositorio");
+        let empty = fs::read(format!("{}/{}", git_dir, INDEX)).expect("Falló al leer el index");
+        // Index de las versiones anteriores, en el formato de texto
+        let blob = builder_object_blob(b"uno".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        create_file_replace(
+            &format!("{}/{}", git_dir, INDEX),
+            &format!("a.txt blob {}", blob),
+        )
+        .expect("Falló al escribir el index");
+        create_file_replace(&format!("{}/b.txt", directory), "dos")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "b.txt").expect("Falló al agregar el archivo");
+
+        let content = fs::read(format!("{}/{}", git_dir, INDEX)).expect("Falló al leer el index");
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        assert_eq!(
+            empty,
+            Index::from_text("", IndexFormat::Binary).to_binary(ObjectFormat::Sha1)
+        );
+        let index = Index::parse(&content, ObjectFormat::Sha1).expect("Falló al leer el index");
+        assert_eq!(index.format, IndexFormat::Binary);
+        assert_eq!(index.entries.len(), 2);
+        assert_eq!(index.entries[0], IndexEntry::new("a.txt", &blob));
+        assert_eq!(index.entries[1].path, "b.txt");
+    }
+
+    #[test]
+    fn test_commands_keep_binary_index() {
+        let directory = "./test_index_binary";
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        git_init(directory).expect("Falló al crear el repositorio");
+        create_file_replace(&format!("{}/a.txt", directory), "uno")
+            .expect("Falló al crear el archivo");
+        let blob = builder_object_blob(b"uno".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        // Index escrito por git, con los datos del archivo
+        let mut entry = IndexEntry::new("a.txt", &blob);
+        entry.stat[0] = 1700000000;
+        let index = Index {
+            format: IndexFormat::Binary,
+            entries: vec![entry.clone()],
+        };
+        fs::write(
+            format!("{}/{}", git_dir, INDEX),
+            index.to_binary(ObjectFormat::Sha1),
+        )
+        .expect("Falló al escribir el index");
+
+        assert_eq!(open_index(&git_dir), Ok(format!("a.txt blob {}", blob)));
+        create_file_replace(&format!("{}/b.txt", directory), "dos")
+            .expect("Falló al crear el archivo");
+        git_add(directory, "b.txt").expect("Falló al agregar el archivo");
+        let commit = test_commit("primero");
+        git_commit(directory, commit).expect("Falló al hacer el commit");
+
+        let content = fs::read(format!("{}/{}", git_dir, INDEX)).expect("Falló al leer el index");
+        let index = Index::parse(&content, ObjectFormat::Sha1).expect("Falló al leer el index");
+        assert_eq!(index.format, IndexFormat::Binary);
+        assert_eq!(index.entries.len(), 2);
+        // Se conservan los datos de los archivos que no cambiaron
+        assert_eq!(index.entries[0], entry);
+        assert_eq!(index.entries[1].path, "b.txt");
+        assert_eq!(is_files_to_commit(directory), Ok(false));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
+}
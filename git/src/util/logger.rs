@@ -1,16 +1,46 @@
 use crate::consts::*;
 use crate::errors::GitError;
+use std::fmt;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::net::TcpStream;
 use std::path::Path;
 use std::sync::mpsc::Receiver;
-use std::sync::{mpsc::Sender, Arc, Mutex};
+use std::sync::{mpsc::Sender, Arc, Mutex, OnceLock};
 
 use super::errors::UtilError;
 use super::files::create_directory;
 use super::log_output::LogOutput;
 
+/// Indica si están habilitados los mensajes de nivel debug. Se lee una única vez de la
+/// variable de entorno `LOG_DEBUG_ENV`.
+static DEBUG_ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Canal de log del proceso, registrado al iniciar el logging. Los mensajes que se registran
+/// sin un canal propio, como el avance de la lectura de un packfile, se envían por él.
+static LOG_CHANNEL: OnceLock<Arc<Mutex<Sender<String>>>> = OnceLock::new();
+
+/// Nivel de un mensaje de log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        };
+        write!(f, "{}", level)
+    }
+}
+
 /// Envía un mensaje a través del canal con un transmisor protegido por Mutex.
 ///
 /// # Argumentos
@@ -79,6 +109,71 @@ pub fn log_message(tx: &Arc<Mutex<Sender<String>>>, message: &str) {
     };
 }
 
+/// Devuelve si están habilitados los mensajes de nivel debug, es decir, si la variable de
+/// entorno `LOG_DEBUG_ENV` vale `1` o `true`.
+pub fn debug_enabled() -> bool {
+    *DEBUG_ENABLED.get_or_init(|| {
+        std::env::var(LOG_DEBUG_ENV).is_ok_and(|value| value == "1" || value == "true")
+    })
+}
+
+/// Formatea un mensaje de log con su nivel, con el formato `[NIVEL] mensaje`.
+///
+/// # Argumentos
+///
+/// * `level`: Nivel del mensaje.
+/// * `message`: Mensaje a registrar.
+///
+pub fn format_log_level(level: LogLevel, message: &str) -> String {
+    format!("[{}] {}", level, message)
+}
+
+/// Registra el canal de log del proceso, por el que se envían los mensajes que se registran sin
+/// un canal propio. Solo se tiene en cuenta el primer canal registrado.
+///
+/// # Argumentos
+///
+/// * `tx`: Canal de log del proceso.
+///
+pub fn set_log_channel(tx: &Arc<Mutex<Sender<String>>>) {
+    let _ = LOG_CHANNEL.set(Arc::clone(tx));
+}
+
+/// Registra un mensaje con un nivel.
+///
+/// El mensaje se envía por el canal indicado o, si no se indica, por el canal de log del
+/// proceso (ver `set_log_channel`). Si no hay ninguno se escribe en la salida de error para no
+/// mezclarlo con la salida de los comandos. Los mensajes de nivel debug solo se registran si
+/// están habilitados (ver `debug_enabled`).
+///
+/// # Argumentos
+///
+/// * `tx`: Canal de log, si lo hay.
+/// * `level`: Nivel del mensaje.
+/// * `message`: Mensaje a registrar.
+///
+pub fn log_level(tx: Option<&Arc<Mutex<Sender<String>>>>, level: LogLevel, message: &str) {
+    if level == LogLevel::Debug && !debug_enabled() {
+        return;
+    }
+    let message = format_log_level(level, message);
+    match tx.or(LOG_CHANNEL.get()) {
+        Some(tx) => log_message(tx, &message),
+        None => eprintln!("{}", message),
+    }
+}
+
+/// Registra un mensaje de nivel debug sin canal de log. Solo se muestra si están habilitados
+/// los mensajes de debug.
+///
+/// # Argumentos
+///
+/// * `message`: Mensaje a registrar.
+///
+pub fn log_debug(message: &str) {
+    log_level(None, LogLevel::Debug, message);
+}
+
 /// Maneja el archivo de registro, escribiendo los datos recibidos del canal en el archivo de registro indicado.
 ///
 /// # Argumentos
@@ -236,6 +331,31 @@ mod tests {
         (Arc::new(Mutex::new(tx)), rx)
     }
 
+    #[test]
+    fn test_log_level() {
+        let (tx, rx) = setup();
+        log_level(Some(&tx), LogLevel::Warn, "Referencia inválida");
+        assert_eq!(rx.recv().unwrap(), "[WARN] Referencia inválida");
+
+        log_level(Some(&tx), LogLevel::Debug, "want");
+        if debug_enabled() {
+            assert_eq!(rx.try_recv().unwrap(), "[DEBUG] want");
+        } else {
+            assert!(rx.try_recv().is_err());
+        }
+    }
+
+    #[test]
+    fn test_log_level_process_channel() {
+        let (tx, rx) = setup();
+        set_log_channel(&tx);
+        log_level(None, LogLevel::Info, "Receiving objects: 100% (1/1), done.");
+        // Otros tests pueden registrar mensajes en el canal del proceso al mismo tiempo
+        assert!(rx
+            .try_iter()
+            .any(|message| message == "[INFO] Receiving objects: 100% (1/1), done."));
+    }
+
     #[test]
     fn test_log_client_disconnection() {
         let (tx, rx) = setup();
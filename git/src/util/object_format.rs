@@ -0,0 +1,197 @@
+use crate::commands::config::GitConfig;
+use crate::consts::{
+    CONFIG_FILE, OBJECT_FORMAT_CAPABILITY, OBJECT_FORMAT_KEY, SHA1_NAME, SHA256_NAME,
+};
+use crate::util::files::get_common_git_dir;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use std::fmt::Write;
+
+/// Algoritmo con el que se calculan los nombres de los objetos de un repositorio.
+///
+/// - `Sha1`: hashes de 20 bytes (40 caracteres hexadecimales), el formato por defecto.
+/// - `Sha256`: hashes de 32 bytes (64 caracteres hexadecimales), para los repositorios creados
+///   con `git init --object-format=sha256`.
+///
+/// El formato se guarda en la configuración del repositorio en `extensions.objectformat`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectFormat {
+    #[default]
+    Sha1,
+    Sha256,
+}
+
+impl ObjectFormat {
+    /// Devuelve el formato con el nombre indicado (`sha1` o `sha256`), o `None` si no existe.
+    pub fn from_name(name: &str) -> Option<ObjectFormat> {
+        match name.trim().to_lowercase().as_str() {
+            SHA1_NAME => Some(ObjectFormat::Sha1),
+            SHA256_NAME => Some(ObjectFormat::Sha256),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el formato anunciado con la capacidad `object-format=<nombre>`. Si no se anuncia,
+    /// o el nombre no es válido, es SHA-1, como en git.
+    pub fn from_capabilities(capabilities: &[impl AsRef<str>]) -> ObjectFormat {
+        capabilities
+            .iter()
+            .find_map(|capability| capability.as_ref().strip_prefix(OBJECT_FORMAT_CAPABILITY))
+            .and_then(ObjectFormat::from_name)
+            .unwrap_or_default()
+    }
+
+    /// Devuelve la capacidad con la que se anuncia el formato, `None` en SHA-1 porque es el
+    /// formato que se asume cuando no se anuncia.
+    pub fn capability(&self) -> Option<String> {
+        match self {
+            ObjectFormat::Sha1 => None,
+            ObjectFormat::Sha256 => Some(format!("{}{}", OBJECT_FORMAT_CAPABILITY, self.name())),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectFormat::Sha1 => SHA1_NAME,
+            ObjectFormat::Sha256 => SHA256_NAME,
+        }
+    }
+
+    /// Cantidad de bytes de un hash, por ejemplo dentro de un tree o al final de un packfile.
+    pub fn raw_len(&self) -> usize {
+        match self {
+            ObjectFormat::Sha1 => 20,
+            ObjectFormat::Sha256 => 32,
+        }
+    }
+
+    /// Cantidad de caracteres de un hash escrito en hexadecimal.
+    pub fn hex_len(&self) -> usize {
+        self.raw_len() * 2
+    }
+
+    /// Calcula el hash del contenido y lo devuelve en hexadecimal.
+    ///
+    /// # Argumentos
+    ///
+    /// * `content` - Contenido del que se calcula el hash, con el header del objeto.
+    ///
+    pub fn hash(&self, content: &[u8]) -> String {
+        let mut hasher = ObjectHasher::new(*self);
+        hasher.update(content);
+        bytes_to_hex(&hasher.finalize())
+    }
+}
+
+/// Calcula un hash de a partes con el algoritmo de un `ObjectFormat`, por ejemplo el checksum
+/// de un packfile mientras se envían los objetos.
+#[derive(Debug, Clone)]
+pub enum ObjectHasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl ObjectHasher {
+    pub fn new(object_format: ObjectFormat) -> ObjectHasher {
+        match object_format {
+            ObjectFormat::Sha1 => ObjectHasher::Sha1(Sha1::new()),
+            ObjectFormat::Sha256 => ObjectHasher::Sha256(Sha256::new()),
+        }
+    }
+
+    pub fn update(&mut self, content: impl AsRef<[u8]>) {
+        match self {
+            ObjectHasher::Sha1(hasher) => hasher.update(content),
+            ObjectHasher::Sha256(hasher) => hasher.update(content),
+        }
+    }
+
+    /// Devuelve los bytes del hash.
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            ObjectHasher::Sha1(hasher) => hasher.finalize().to_vec(),
+            ObjectHasher::Sha256(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+/// Devuelve el formato de los objetos del repositorio, leído de `extensions.objectformat` en
+/// su configuración. Si no está configurado se usa SHA-1.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Directorio git del repositorio.
+///
+pub fn get_object_format(git_dir: &str) -> ObjectFormat {
+    let config_path = format!("{}/{}", get_common_git_dir(git_dir), CONFIG_FILE);
+    match GitConfig::new_from_path(&config_path) {
+        Ok(config) => config.get_object_format(),
+        Err(_) => ObjectFormat::Sha1,
+    }
+}
+
+/// Devuelve el texto de configuración de un repositorio nuevo con el formato indicado. Los
+/// repositorios SHA-256 necesitan la versión 1 del formato del repositorio.
+pub fn object_format_config(object_format: ObjectFormat) -> String {
+    match object_format {
+        ObjectFormat::Sha1 => String::new(),
+        ObjectFormat::Sha256 => format!(
+            "[core]\n\trepositoryformatversion = 1\n[extensions]\n\t{} = {}\n",
+            OBJECT_FORMAT_KEY,
+            object_format.name()
+        ),
+    }
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_known_values() {
+        let sha256 = ObjectFormat::Sha256;
+        assert_eq!(
+            sha256.hash(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256.hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            sha256.hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        // Es lo mismo calcularlo de a partes que de una vez
+        let content = vec![b'a'; 1000];
+        let mut hasher = ObjectHasher::new(sha256);
+        for chunk in content.chunks(7) {
+            hasher.update(chunk);
+        }
+        assert_eq!(bytes_to_hex(&hasher.finalize()), sha256.hash(&content));
+    }
+
+    #[test]
+    fn test_object_format_lengths() {
+        let blob = b"blob 4\0hola";
+        assert_eq!(ObjectFormat::Sha1.hash(blob).len(), 40);
+        assert_eq!(ObjectFormat::Sha256.hash(blob).len(), 64);
+        assert_eq!(
+            ObjectFormat::Sha1.hash(blob),
+            crate::util::formats::hash_generate("blob 4\0hola")
+        );
+        assert_eq!(
+            ObjectFormat::from_name("SHA256"),
+            Some(ObjectFormat::Sha256)
+        );
+        assert_eq!(ObjectFormat::from_name("md5"), None);
+        assert_eq!(get_object_format("./no_existe/.git"), ObjectFormat::Sha1);
+    }
+}
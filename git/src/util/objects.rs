@@ -1,14 +1,14 @@
 use crate::consts::*;
 use crate::errors::GitError;
-use crate::util::files::create_directory;
-use crate::util::formats::{compressor_object, hash_generate};
+use crate::util::files::{create_directory, get_common_git_dir, write_bytes_atomic};
+use crate::util::formats::compressor_object_with_bytes_content;
+use crate::util::object_format::{get_object_format, ObjectFormat};
 use std::fmt::Write;
-use std::fs::File;
+use std::fs;
 use std::io::Read;
 use std::path::Path;
 
 use super::errors::UtilError;
-use super::formats::{compressor_object_with_bytes, hash_generate_with_bytes};
 
 /// Estructura que representa una entrada de objeto en el sistema de control de versiones Git.
 ///
@@ -103,7 +103,7 @@ pub fn encode_size_encoding(mut number: usize, offset: u8, result: &mut Vec<u8>)
 /// - `OfsDelta`: Objeto de tipo OfsDelta, que representa un objeto delta relativo a una posición en un paquete.
 /// - `RefDelta`: Objeto de tipo RefDelta, que representa un objeto delta referenciado en un paquete.
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ObjectType {
     Commit,
     Tree,
@@ -118,7 +118,6 @@ pub fn read_type_and_length(reader: &mut dyn Read) -> Result<ObjectEntry, GitErr
     if reader.read_exact(&mut buffer).is_err() {
         return Err(GitError::HeaderPackFileReadError);
     };
-    // println!("(read_type_and_length)Buffer: {:?}", buffer);
     let byte = buffer[0];
 
     let obj_type: ObjectType = create_object_bits(byte)?;
@@ -134,7 +133,10 @@ pub fn read_type_and_length_from_vec(
     data: &[u8],
     offset: &mut usize,
 ) -> Result<ObjectEntry, UtilError> {
-    let byte = data[*offset];
+    let byte = match data.get(*offset) {
+        Some(byte) => *byte,
+        None => return Err(UtilError::PackfileTruncated),
+    };
     *offset += 1;
     let obj_type: ObjectType = create_object_bits(byte)?;
     let length = read_size_encoded_length_from_vec(data, byte, offset)?;
@@ -157,7 +159,10 @@ fn read_size_encoded_length_from_vec(
     let mut shift: usize = 4;
 
     loop {
-        let byte = data[*offset];
+        let byte = match data.get(*offset) {
+            Some(byte) => *byte,
+            None => return Err(UtilError::PackfileTruncated),
+        };
         *offset += 1;
 
         let seven_bits = (byte & 0b01111111) as usize;
@@ -177,7 +182,6 @@ fn read_size_encoded_length(reader: &mut dyn Read, byte: u8) -> Result<usize, Gi
         return Ok(length_bits); // Se gasto un bit para el tipo
     }
 
-    // println!("(MSB)Length firts: {:?}", length_bits);
     let mut shift: usize = 4;
 
     loop {
@@ -185,16 +189,9 @@ fn read_size_encoded_length(reader: &mut dyn Read, byte: u8) -> Result<usize, Gi
         if reader.read_exact(&mut byte).is_err() {
             return Err(GitError::HeaderPackFileReadError);
         };
-        // println!("(MSB)Buffer: {:?}", byte);
 
         let seven_bits = (byte[0] & 0b01111111) as usize;
-        // print_u8_bits(byte[0] & 0b01111111);
-        // println!(
-        //     "(MSB)Unire:length seven: {:?} y length_bits: {}",
-        //     seven_bits, length_bits
-        // );
         length_bits |= seven_bits << shift;
-        // println!("(MSB)Length final: {:?}", length_bits);
         if (byte[0] & 0x80) == 0 {
             break;
         }
@@ -232,8 +229,22 @@ fn create_object(byte: u8) -> Result<ObjectType, UtilError> {
     }
 }
 
-/// Creará la carpeta con los 2 primeros digitos del hash del objeto commit, y el archivo con los ultimos 38 de nombre.
-pub fn builder_object(git_dir: &str, hash_object: &str) -> Result<File, UtilError> {
+/// Creará la carpeta con los 2 primeros digitos del hash del objeto, y el archivo con los ultimos 38 de nombre,
+/// con el contenido comprimido. Como el contenido de un objeto queda determinado por su hash, si
+/// el objeto ya existe en el repositorio o en sus alternates no se vuelve a escribir.
+///
+/// El objeto se escribe en un archivo temporal que luego se renombra, por lo que un proceso
+/// interrumpido nunca deja un objeto a medio escribir con el nombre de su hash.
+/// ###Parametros:
+/// 'git_dir': Directorio del git
+/// 'hash_object': hash del objeto
+/// 'store': contenido del objeto con su header, sin comprimir
+pub fn builder_object(git_dir: &str, hash_object: &str, store: &[u8]) -> Result<(), UtilError> {
+    if matches!(get_object_path(git_dir, hash_object), Ok(path) if Path::new(&path).is_file()) {
+        return Ok(());
+    }
+    // Los working trees enlazados comparten los objetos del repositorio principal
+    let git_dir = get_common_git_dir(git_dir);
     let objects_dir = format!(
         "{}/{}/{}/{}",
         &git_dir,
@@ -246,28 +257,28 @@ pub fn builder_object(git_dir: &str, hash_object: &str) -> Result<File, UtilErro
 
     create_directory(Path::new(&hash_object_path))?;
 
-    let file_object = match File::create(objects_dir) {
-        Ok(file_object) => file_object,
-        Err(_) => return Err(UtilError::CreateFileError),
-    };
-
-    Ok(file_object)
+    let compressed = compressor_object_with_bytes_content(store.to_vec())?;
+    write_bytes_atomic(&objects_dir, &compressed)
 }
 
 /// comprimirá el contenido y lo escribirá en el archivo
 /// ###Parametros:
 /// 'git_dir': Directorio del git
 /// 'content': contenido del archivo a comprimir
-pub fn builder_object_tag(content: &str, git_dir: &str) -> Result<String, UtilError> {
+/// 'object_format': formato de los objetos del repositorio, con el que se calcula el hash
+pub fn builder_object_tag(
+    content: &str,
+    git_dir: &str,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let content_bytes = content.as_bytes();
     let content_size = content_bytes.len().to_string();
     let header = format!("{} {}\0", TAG, content_size);
     let store = header + content;
 
-    let tag_hash = hash_generate(content);
+    let tag_hash = object_format.hash(content.as_bytes());
 
-    let file_object = builder_object(git_dir, &tag_hash)?;
-    compressor_object(store, file_object)?;
+    builder_object(git_dir, &tag_hash, store.as_bytes())?;
 
     Ok(tag_hash)
 }
@@ -276,15 +287,18 @@ pub fn builder_object_tag(content: &str, git_dir: &str) -> Result<String, UtilEr
 /// ###Parametros:
 /// 'git_dir': Directorio del git
 /// 'content': contenido del archivo a comprimir
-pub fn builder_object_blob(content: Vec<u8>, git_dir: &str) -> Result<String, UtilError> {
+/// 'object_format': formato de los objetos del repositorio, con el que se calcula el hash
+pub fn builder_object_blob(
+    content: Vec<u8>,
+    git_dir: &str,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let header = format!("{} {}\0", BLOB, content.len());
     let store = header + String::from_utf8_lossy(&content).as_ref();
 
-    let hash_blob = hash_generate(&store);
+    let hash_blob = object_format.hash(store.as_bytes());
 
-    let file_object = builder_object(git_dir, &hash_blob)?;
-
-    compressor_object(store, file_object)?;
+    builder_object(git_dir, &hash_blob, store.as_bytes())?;
 
     Ok(hash_blob)
 }
@@ -293,17 +307,21 @@ pub fn builder_object_blob(content: Vec<u8>, git_dir: &str) -> Result<String, Ut
 /// ###Parametros:
 /// 'git_dir': Directorio del git
 /// 'hash_commit': hash del objeto commit previamente generado
-pub fn builder_object_commit(content: &str, git_dir: &str) -> Result<String, UtilError> {
+/// 'object_format': formato de los objetos del repositorio, con el que se calcula el hash
+pub fn builder_object_commit(
+    content: &str,
+    git_dir: &str,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let content_bytes = content.as_bytes();
     let content_size = content_bytes.len().to_string();
     let header = format!("commit {}\0", content_size);
 
     let store = header + content;
 
-    let hash_commit = hash_generate(&store);
+    let hash_commit = object_format.hash(store.as_bytes());
 
-    let file = builder_object(git_dir, &hash_commit)?;
-    compressor_object(store, file)?;
+    builder_object(git_dir, &hash_commit, store.as_bytes())?;
 
     Ok(hash_commit)
 }
@@ -313,17 +331,17 @@ fn builder_format_tree(index_content: &str) -> Result<Vec<u8>, UtilError> {
     let mut format_tree = Vec::new();
 
     for line in index_content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() == 3 {
-            let file_name = parts[0];
-            let mut mode = parts[1];
-            let hash = parts[2];
-
+        if let Some((mut mode, name, hash)) = parse_tree_line(line) {
             if mode == BLOB {
                 mode = FILE;
             } else if mode == TREE {
                 mode = DIRECTORY;
             }
+            // Se respeta el orden de los campos de la línea recibida
+            let (first, second) = match line.split(' ').next().is_some_and(is_tree_mode) {
+                true => (mode, name),
+                false => (name, mode),
+            };
             let bytes = hash
                 .as_bytes()
                 .chunks(2)
@@ -333,9 +351,9 @@ fn builder_format_tree(index_content: &str) -> Result<Vec<u8>, UtilError> {
                 })
                 .collect::<Vec<u8>>();
 
-            format_tree.extend_from_slice(file_name.as_bytes());
+            format_tree.extend_from_slice(first.as_bytes());
             format_tree.push(SPACE);
-            format_tree.extend_from_slice(mode.as_bytes());
+            format_tree.extend_from_slice(second.as_bytes());
             format_tree.push(NULL);
             format_tree.extend_from_slice(&bytes);
         }
@@ -343,7 +361,11 @@ fn builder_format_tree(index_content: &str) -> Result<Vec<u8>, UtilError> {
     Ok(format_tree)
 }
 
-pub fn builder_object_tree(git_dir: &str, content: &str) -> Result<String, UtilError> {
+pub fn builder_object_tree(
+    git_dir: &str,
+    content: &str,
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let format_tree = builder_format_tree(content)?;
 
     let content_size = format_tree.len().to_string();
@@ -353,11 +375,9 @@ pub fn builder_object_tree(git_dir: &str, content: &str) -> Result<String, UtilE
     header.extend_from_slice(content_size.as_bytes());
     header.push(NULL);
     header.extend_from_slice(&format_tree);
-    let hash_tree = hash_generate_with_bytes(header.clone());
-
-    let file = builder_object(git_dir, &hash_tree)?;
+    let hash_tree = object_format.hash(&header);
 
-    compressor_object_with_bytes(header, file)?;
+    builder_object(git_dir, &hash_tree, &header)?;
 
     Ok(hash_tree)
 }
@@ -441,11 +461,14 @@ pub fn read_tag(decompressed_data: &[u8]) -> Result<String, UtilError> {
     }
 }
 
-/// Lee desde el contenido descomprimido el tipo de objeto de tipo tree.
+/// Lee desde el contenido descomprimido el tipo de objeto de tipo tree. Los hashes de las
+/// entradas tienen el largo del formato de objetos indicado, por ejemplo los 32 bytes de un
+/// repositorio SHA-256.
 ///
 /// # Argumentos
 ///
 /// * `decompressed_data`: El contenido de un objeto en bytes de tipo tree.
+/// * `object_format`: Formato de los objetos del repositorio del tree.
 ///
 /// # Retorno
 ///
@@ -453,7 +476,10 @@ pub fn read_tag(decompressed_data: &[u8]) -> Result<String, UtilError> {
 ///     del archivo y su hash
 /// * `Err(UtilError)`: .
 ///
-pub fn read_tree(decompressed_data: &[u8]) -> Result<String, UtilError> {
+pub fn read_tree(
+    decompressed_data: &[u8],
+    object_format: ObjectFormat,
+) -> Result<String, UtilError> {
     let content = decompressed_data;
 
     let mut index = 0;
@@ -474,6 +500,8 @@ pub fn read_tree(decompressed_data: &[u8]) -> Result<String, UtilError> {
             type_object.push(content[index]);
             index += 1;
         }
+        // Se saltea el espacio que separa el modo del nombre
+        index += 1;
         let mut file_name: Vec<u8> = Vec::new();
         while index < content.len() && content[index] != NULL {
             file_name.push(content[index]);
@@ -481,7 +509,7 @@ pub fn read_tree(decompressed_data: &[u8]) -> Result<String, UtilError> {
         }
         index += 1;
         let mut hash: Vec<u8> = Vec::new();
-        for _i in 0..20 {
+        for _i in 0..object_format.raw_len() {
             if index < content.len() {
                 hash.push(content[index]);
                 index += 1;
@@ -559,11 +587,291 @@ pub fn read_blob(decompressed_data: &[u8]) -> Result<String, UtilError> {
     }
 }
 
+/// Obtiene los hashes de todos los objetos sueltos del repositorio.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Directorio del git.
+///
+/// # Retorno
+///
+/// Un vector con los hashes de 40 caracteres de los objetos encontrados en `objects`.
+///
+pub fn get_object_hashes(git_dir: &str) -> Result<Vec<String>, UtilError> {
+    let objects_dir = format!("{}/{}", get_common_git_dir(git_dir), DIR_OBJECTS);
+    let entries = match fs::read_dir(objects_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Err(UtilError::ReadDirError),
+    };
+    let mut hashes = Vec::new();
+    for entry in entries.flatten() {
+        let prefix = entry.file_name().to_string_lossy().to_string();
+        if prefix.len() != 2 || !entry.path().is_dir() {
+            continue;
+        }
+        let objects = match fs::read_dir(entry.path()) {
+            Ok(objects) => objects,
+            Err(_) => return Err(UtilError::ReadDirError),
+        };
+        for object in objects.flatten() {
+            let name = object.file_name().to_string_lossy().to_string();
+            // Un objeto que se estaba escribiendo cuando se interrumpió el proceso
+            if name.ends_with(TEMP_FILE_SUFFIX) {
+                continue;
+            }
+            hashes.push(format!("{}{}", prefix, name));
+        }
+    }
+    Ok(hashes)
+}
+
+/// Abrevia un hash al prefijo más corto que no sea ambiguo entre los hashes dados.
+/// El prefijo nunca es menor a `ABBREV_MIN_LENGTH` caracteres.
+///
+/// # Argumentos
+///
+/// * `hash` - Hash a abreviar.
+/// * `hashes` - Hashes de todos los objetos del repositorio.
+///
+pub fn abbreviate_hash(hash: &str, hashes: &[String]) -> String {
+    let mut len = ABBREV_MIN_LENGTH.min(hash.len());
+    while len < hash.len() {
+        let prefix = &hash[..len];
+        let ambiguous = hashes
+            .iter()
+            .any(|other| other != hash && other.starts_with(prefix));
+        if !ambiguous {
+            break;
+        }
+        len += 1;
+    }
+    hash[..len].to_string()
+}
+
+/// Obtiene la ruta del objeto suelto con el hash indicado.
+///
+/// Si el objeto no se encuentra en el repositorio, se lo busca en cada uno de los
+/// directorios de objetos listados en `objects/info/alternates`. Las rutas relativas
+/// de ese archivo se interpretan respecto del directorio `objects` del repositorio.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Directorio del git.
+/// * `hash_object` - Hash de 40 caracteres del objeto.
+///
+/// # Retorno
+///
+/// La ruta del objeto. Si no hay alternates se devuelve la ruta local aunque el objeto no exista.
+/// Si hay alternates y el objeto no está en ninguno, se devuelve `UtilError::ObjectNotFoundInAlternates`.
+/// Si el hash tiene menos de 3 caracteres o alguno no es hexadecimal, se devuelve
+/// `UtilError::ObjectNotFound`.
+///
+pub fn get_object_path(git_dir: &str, hash_object: &str) -> Result<String, UtilError> {
+    if hash_object.len() < 3 || !hash_object.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(UtilError::ObjectNotFound(hash_object.to_string()));
+    }
+    let objects_dir = format!("{}/{}", get_common_git_dir(git_dir), DIR_OBJECTS);
+    let object_path = format!(
+        "{}/{}/{}",
+        objects_dir,
+        &hash_object[..2],
+        &hash_object[2..]
+    );
+    if Path::new(&object_path).is_file() {
+        return Ok(object_path);
+    }
+    let alternate_dirs = get_alternate_dirs(&objects_dir);
+    if alternate_dirs.is_empty() {
+        return Ok(object_path);
+    }
+    for alternate_dir in alternate_dirs {
+        let alternate_path = format!(
+            "{}/{}/{}",
+            alternate_dir,
+            &hash_object[..2],
+            &hash_object[2..]
+        );
+        if Path::new(&alternate_path).is_file() {
+            return Ok(alternate_path);
+        }
+    }
+    Err(UtilError::ObjectNotFoundInAlternates(
+        hash_object.to_string(),
+    ))
+}
+
+/// Obtiene los directorios de objetos listados en `objects/info/alternates`. Las rutas
+/// relativas se interpretan respecto del directorio `objects` del repositorio.
+///
+/// # Argumentos
+///
+/// * `objects_dir` - Directorio `objects` del repositorio.
+///
+/// # Retorno
+///
+/// Los directorios de los alternates, o un vector vacío si el archivo no existe.
+///
+fn get_alternate_dirs(objects_dir: &str) -> Vec<String> {
+    let alternates =
+        fs::read_to_string(format!("{}/{}", objects_dir, ALTERNATES)).unwrap_or_default();
+    alternates
+        .lines()
+        .map(str::trim)
+        .filter(|alternate| !alternate.is_empty() && !alternate.starts_with('#'))
+        .map(|alternate| match Path::new(alternate).is_absolute() {
+            true => alternate.to_string(),
+            false => format!("{}/{}", objects_dir, alternate),
+        })
+        .collect()
+}
+
+/// Obtiene los hashes de los objetos del repositorio y de sus alternates que comienzan con el
+/// prefijo dado. Solo se recorre el subdirectorio de `objects` correspondiente a los dos
+/// primeros caracteres del prefijo. Todos los objetos se guardan sueltos, por lo que no hay
+/// índices de packfiles que recorrer.
+///
+/// # Argumentos
+///
+/// * `git_dir` - Directorio del git.
+/// * `prefix` - Prefijo en minúsculas, de al menos dos caracteres.
+///
+/// # Retorno
+///
+/// Los hashes encontrados, ordenados y sin repetir.
+///
+pub fn find_object_hashes_with_prefix(git_dir: &str, prefix: &str) -> Vec<String> {
+    let objects_dir = format!("{}/{}", get_common_git_dir(git_dir), DIR_OBJECTS);
+    let (fan_out, rest) = prefix.split_at(2.min(prefix.len()));
+    let mut hashes: Vec<String> = std::iter::once(objects_dir.clone())
+        .chain(get_alternate_dirs(&objects_dir))
+        .filter_map(|dir| fs::read_dir(format!("{}/{}", dir, fan_out)).ok())
+        .flat_map(|objects| objects.flatten())
+        .map(|object| object.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(rest) && !name.ends_with(TEMP_FILE_SUFFIX))
+        .map(|name| format!("{}{}", fan_out, name))
+        .collect();
+    hashes.sort();
+    hashes.dedup();
+    hashes
+}
+
+/// Indica si el objeto con el hash indicado está en el repositorio o en sus alternates,
+/// sin descomprimirlo.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio.
+/// * `hash_object` - Hash del objeto, de 40 caracteres, o de 64 en un repositorio SHA-256.
+///
+/// # Retorno
+///
+/// `true` si el objeto existe, `false` si no existe o el hash no es válido.
+///
+pub fn object_exists(directory: &str, hash_object: &str) -> bool {
+    let git_dir = format!("{}/{}", directory, GIT_DIR);
+    if hash_object.len() != get_object_format(&git_dir).hex_len()
+        || !hash_object.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return false;
+    }
+    match get_object_path(&git_dir, hash_object) {
+        Ok(object_path) => Path::new(&object_path).is_file(),
+        Err(_) => false,
+    }
+}
+
+/// Indica si el repositorio es un clone parcial, es decir, si se clonó con un filtro y le
+/// pueden faltar objetos que el servidor promete enviar más adelante.
+///
+/// # Argumentos
+///
+/// * `directory` - Directorio del repositorio.
+///
+pub fn is_promisor_repository(directory: &str) -> bool {
+    let git_dir = get_common_git_dir(&format!("{}/{}", directory, GIT_DIR));
+    Path::new(&format!("{}/{}", git_dir, PROMISOR_FILE)).is_file()
+}
+
+/// Indica si el modo de una entrada de un tree corresponde a un blob: un archivo regular, un
+/// ejecutable o un link simbólico.
+///
+/// # Argumentos
+///
+/// * `mode` - Modo de la entrada, por ejemplo `100755`.
+///
+pub fn is_blob_mode(mode: &str) -> bool {
+    mode == FILE || mode == EXECUTABLE_FILE || mode == SYMLINK
+}
+
+/// Separa una línea de un tree en modo, nombre y hash. Acepta las líneas `<modo> <nombre> <hash>`
+/// que devuelve `read_tree` y las líneas `<nombre> <tipo> <hash>` del index, y el nombre puede
+/// contener espacios.
+///
+/// # Argumentos
+///
+/// * `line` - Línea del tree.
+///
+/// # Retorno
+///
+/// `(modo, nombre, hash)`, o `None` si a la línea le falta alguno de los campos.
+///
+pub fn parse_tree_line(line: &str) -> Option<(&str, &str, &str)> {
+    let (rest, hash) = line.trim_end_matches(['\n', '\r']).rsplit_once(' ')?;
+    let (first, name) = rest.split_once(' ')?;
+    let (mode, name) = match is_tree_mode(first) {
+        true => (first, name),
+        false => {
+            let (name, mode) = rest.rsplit_once(' ')?;
+            (mode, name)
+        }
+    };
+    if mode.is_empty() || name.is_empty() || hash.is_empty() {
+        return None;
+    }
+    Some((mode, name, hash))
+}
+
+/// Indica si el texto es un modo válido de una entrada de un tree, sea un blob o un sub-tree.
+///
+/// # Argumentos
+///
+/// * `mode` - Texto a verificar.
+///
+pub fn is_tree_mode(mode: &str) -> bool {
+    is_blob_mode(mode) || mode == DIRECTORY
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
     use super::*;
+    use crate::commands::init::git_init;
+    use crate::util::files::get_git_dir;
+
+    #[test]
+    fn test_builder_object_blob_skips_existing_object() {
+        let directory = "./test_builder_object_dedup";
+        git_init(directory).expect("Falló al crear el repositorio");
+        let git_dir = get_git_dir(directory);
+
+        let hash = builder_object_blob(b"contenido".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        let object_path =
+            get_object_path(&git_dir, &hash).expect("Falló al obtener la ruta del objeto");
+        // Se marca el archivo para detectar si se vuelve a escribir
+        fs::write(&object_path, "marca").expect("Falló al escribir el archivo");
+
+        let second_hash = builder_object_blob(b"contenido".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el blob");
+        assert_eq!(second_hash, hash);
+        assert_eq!(
+            fs::read_to_string(&object_path).ok(),
+            Some("marca".to_string())
+        );
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
 
     #[test]
     fn test_create_object_commit() {
@@ -729,9 +1037,10 @@ mod tests {
             9, 9, 45, 28, 226, 40, 36, 92, 101, 118, 100, 240, 105, 241, 130, 145, 202,
         ];
 
-        let tree = read_tree(&decompressed_data).expect("Error al leer el tipo");
+        let tree =
+            read_tree(&decompressed_data, ObjectFormat::Sha1).expect("Error al leer el tipo");
 
-        assert_eq!(tree, "100644  Cargo.lock e788d04fe9bbed573d32f0b024939cb2204c6a86\n40000  src 09328009092d1ce228245c657664f069f18291ca\n");
+        assert_eq!(tree, "100644 Cargo.lock e788d04fe9bbed573d32f0b024939cb2204c6a86\n40000 src 09328009092d1ce228245c657664f069f18291ca\n");
     }
     #[test]
     fn test_read_commit() {
@@ -818,4 +1127,88 @@ mod tests {
         assert_eq!(bytes[0], 0b10101100);
         assert_eq!(bytes[1], 0b00010010);
     }
+
+    #[test]
+    fn test_abbreviate_hash_unambiguous() {
+        let hashes = vec![
+            "1234567890abcdef1234567890abcdef12345678".to_string(),
+            "abcdef1234567890abcdef1234567890abcdef12".to_string(),
+        ];
+        assert_eq!(abbreviate_hash(&hashes[0], &hashes), "1234567");
+    }
+
+    #[test]
+    fn test_abbreviate_hash_ambiguous() {
+        let hashes = vec![
+            "1234567890abcdef1234567890abcdef12345678".to_string(),
+            "1234567891abcdef1234567890abcdef12345678".to_string(),
+        ];
+        assert_eq!(abbreviate_hash(&hashes[0], &hashes), "1234567890");
+        assert_eq!(abbreviate_hash(&hashes[1], &hashes), "1234567891");
+    }
+
+    #[test]
+    fn test_get_object_path_from_alternates() {
+        let base = "./test_alternates_base";
+        let primary = "./test_alternates_primary";
+        let base_git_dir = format!("{}/{}", base, GIT_DIR);
+        let primary_git_dir = format!("{}/{}", primary, GIT_DIR);
+        fs::create_dir_all(format!("{}/{}/info", primary_git_dir, DIR_OBJECTS))
+            .expect("Falló al crear el directorio temporal");
+        fs::create_dir_all(format!("{}/{}", base_git_dir, DIR_OBJECTS))
+            .expect("Falló al crear el directorio temporal");
+
+        let hash = builder_object_blob(b"compartido".to_vec(), &base_git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el objeto");
+        let missing = "0123456789abcdef0123456789abcdef01234567";
+
+        // Sin alternates se devuelve la ruta local
+        let result = get_object_path(&primary_git_dir, &hash).expect("Falló al buscar el objeto");
+        assert!(result.starts_with(&primary_git_dir));
+
+        // La ruta del alternate es relativa al directorio objects del repositorio
+        fs::write(
+            format!("{}/{}/{}", primary_git_dir, DIR_OBJECTS, ALTERNATES),
+            "../../../test_alternates_base/.git/objects\n",
+        )
+        .expect("Falló al escribir los alternates");
+        let result = get_object_path(&primary_git_dir, &hash).expect("Falló al buscar el objeto");
+        assert!(Path::new(&result).is_file());
+        assert_eq!(
+            get_object_path(&primary_git_dir, missing),
+            Err(UtilError::ObjectNotFoundInAlternates(missing.to_string()))
+        );
+
+        fs::remove_dir_all(base).expect("Falló al remover el directorio temporal");
+        fs::remove_dir_all(primary).expect("Falló al remover el directorio temporal");
+    }
+
+    #[test]
+    fn test_get_object_path_invalid_hash() {
+        for hash in ["", "ab", "ñandú", "../../config"] {
+            assert_eq!(
+                get_object_path("./.git", hash),
+                Err(UtilError::ObjectNotFound(hash.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_object_exists() {
+        let directory = "./test_object_exists";
+        let git_dir = format!("{}/{}", directory, GIT_DIR);
+        fs::create_dir_all(format!("{}/{}", git_dir, DIR_OBJECTS))
+            .expect("Falló al crear el directorio temporal");
+        let hash = builder_object_blob(b"presente".to_vec(), &git_dir, ObjectFormat::Sha1)
+            .expect("Falló al crear el objeto");
+
+        assert!(object_exists(directory, &hash));
+        assert!(!object_exists(
+            directory,
+            "0123456789abcdef0123456789abcdef01234567"
+        ));
+        assert!(!object_exists(directory, &hash[..10]));
+
+        fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+    }
 }
@@ -1,16 +1,27 @@
 use crate::{
-    consts::{BUFFER_SIZE, PACK_BYTES, PACK_SIGNATURE},
+    commands::config::GitConfig,
+    consts::{
+        BLOB, BUFFER_SIZE, COMMIT, LENGTH_PREFIX_SIZE, PACKFILE_MAX_BYTES, PACKFILE_MAX_OBJECTS,
+        PACKFILE_MAX_OBJECT_SIZE, PACK_BYTES, PACK_SIGNATURE, RECEIVE_MAX_INPUT_SIZE_KEY,
+        RECEIVE_MAX_OBJECTS_KEY, RECEIVE_MAX_OBJECT_SIZE_KEY, RECEIVE_SECTION, SIDE_BAND_DATA,
+        SIDE_BAND_MAX_DATA, SIDE_BAND_PROGRESS, TAG, TREE,
+    },
     git_server::GitServer,
-    util::objects::read_type_and_length_from_vec,
+    util::{
+        object_format::{ObjectFormat, ObjectHasher},
+        objects::read_type_and_length_from_vec,
+    },
 };
 use flate2::{bufread::ZlibEncoder, read::ZlibDecoder, Compression};
-use sha1::{Digest, Sha1};
-use std::io::{Read, Write};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
 
 use super::{
-    connections::send_bytes,
+    connections::{send_bytes, send_flush},
     errors::UtilError,
+    logger::log_debug,
     objects::{ObjectEntry, ObjectType},
+    progress::Progress,
 };
 
 pub fn read_packfile_header(reader: &mut dyn Read) -> Result<u32, UtilError> {
@@ -22,42 +33,648 @@ pub fn read_packfile_header(reader: &mut dyn Read) -> Result<u32, UtilError> {
     Ok(number_object)
 }
 
+/// Objetos completos de un packfile junto con la cantidad de deltas que no se pudieron resolver.
+type ResolvedObjects = (Vec<(ObjectEntry, Vec<u8>)>, usize);
+
+/// Objetos leídos de un packfile junto con la información necesaria para resolver los deltas.
+///
+/// - `objects`: Entradas en el orden del packfile; para los deltas, `data` contiene las
+///   instrucciones del delta ya descomprimidas.
+/// - `offsets`: Offset en el que comienza cada objeto, contado desde el primer objeto
+///   (sin el encabezado), asociado a su índice en `objects`.
+/// - `ofs_bases`: Para cada `OfsDelta`, el índice del objeto base en `objects`.
+/// - `ref_bases`: Para cada `RefDelta`, el hash del objeto base.
+///
+#[derive(Debug, Default)]
+pub struct PackfileObjects {
+    pub objects: Vec<(ObjectEntry, Vec<u8>)>,
+    pub offsets: HashMap<usize, usize>,
+    pub ofs_bases: HashMap<usize, usize>,
+    pub ref_bases: HashMap<usize, String>,
+}
+
+/// Límites que se aplican al recibir un packfile de otra parte, para no agotar la memoria con
+/// un packfile enorme o con objetos que al descomprimirse ocupan mucho más que en el packfile.
+///
+/// - `max_bytes`: Máximo de bytes de los datos del packfile.
+/// - `max_object_size`: Máximo tamaño descomprimido de cada objeto; en los deltas también se
+///   controla el tamaño del objeto resultante que declara el delta.
+/// - `max_objects`: Máximo de objetos que puede anunciar el encabezado.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackfileLimits {
+    pub max_bytes: usize,
+    pub max_object_size: usize,
+    pub max_objects: usize,
+}
+
+impl Default for PackfileLimits {
+    fn default() -> Self {
+        PackfileLimits {
+            max_bytes: PACKFILE_MAX_BYTES,
+            max_object_size: PACKFILE_MAX_OBJECT_SIZE,
+            max_objects: PACKFILE_MAX_OBJECTS,
+        }
+    }
+}
+
+impl PackfileLimits {
+    /// Límites que aceptan cualquier packfile.
+    pub fn unlimited() -> Self {
+        PackfileLimits {
+            max_bytes: usize::MAX,
+            max_object_size: usize::MAX,
+            max_objects: usize::MAX,
+        }
+    }
+
+    /// Límites del repositorio, tomados de las claves `receive.maxInputSize`,
+    /// `receive.maxObjectSize` y `receive.maxObjects` de su configuración. Las claves que no
+    /// están definidas o cuyo valor no es válido conservan el límite por defecto.
+    ///
+    /// Al igual que en git, los tamaños aceptan los sufijos `k`, `m` y `g`, y un valor de 0
+    /// indica que no hay límite.
+    ///
+    /// # Argumentos
+    ///
+    /// * `config` - Configuración del repositorio.
+    ///
+    pub fn from_config(config: &GitConfig) -> Self {
+        let limit = |key: &str, default: usize| match config
+            .get_value(RECEIVE_SECTION, key)
+            .and_then(parse_config_size)
+        {
+            Some(0) => usize::MAX,
+            Some(value) => value,
+            None => default,
+        };
+        PackfileLimits {
+            max_bytes: limit(RECEIVE_MAX_INPUT_SIZE_KEY, PACKFILE_MAX_BYTES),
+            max_object_size: limit(RECEIVE_MAX_OBJECT_SIZE_KEY, PACKFILE_MAX_OBJECT_SIZE),
+            max_objects: limit(RECEIVE_MAX_OBJECTS_KEY, PACKFILE_MAX_OBJECTS),
+        }
+    }
+
+    /// Límites del repositorio indicado, o los límites por defecto si no se puede leer su
+    /// configuración.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path_repo` - Ruta del repositorio.
+    ///
+    pub fn from_repository(path_repo: &str) -> Self {
+        match GitConfig::new_from_file(path_repo) {
+            Ok(config) => PackfileLimits::from_config(&config),
+            Err(_) => PackfileLimits::default(),
+        }
+    }
+}
+
+/// Interpreta un tamaño de la configuración, con un sufijo opcional `k`, `m` o `g`.
+fn parse_config_size(value: &str) -> Option<usize> {
+    let value = value.trim().to_lowercase();
+    let (number, unit) = match value.chars().last()? {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value.as_str(), 1),
+    };
+    number.parse::<usize>().ok()?.checked_mul(unit)
+}
+
+/// Lee los datos del packfile a continuación del encabezado y devuelve sus objetos.
+///
+/// # Argumentos
+///
+/// * `reader` - Canal del que se lee el packfile.
+/// * `objects` - Cantidad de objetos indicada en el encabezado.
+/// * `ofs_delta` - Indica si se negoció la capacidad `ofs-delta`.
+/// * `limits` - Límites de tamaño; la lectura se corta en cuanto se supera `max_bytes`.
+/// * `object_format` - Formato de los objetos, con el que se leen las bases de los `RefDelta`.
+///
+/// # Retorno
+///
+/// Los objetos del packfile, o `UtilError::PackfileTooLarge` si se supera alguno de los límites.
 pub fn read_packfile_data(
     reader: &mut dyn Read,
     objects: usize,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+) -> Result<Vec<(ObjectEntry, Vec<u8>)>, UtilError> {
+    if objects > limits.max_objects {
+        return Err(UtilError::PackfileTooLarge(format!(
+            "{} objetos (máximo {})",
+            objects, limits.max_objects
+        )));
+    }
+    let mut buffer: Vec<u8> = Vec::new();
+    let max_read = (limits.max_bytes as u64).saturating_add(1);
+    if reader.take(max_read).read_to_end(&mut buffer).is_err() {
+        return Err(UtilError::DataPackFiletReadObject);
+    }
+    if buffer.len() > limits.max_bytes {
+        return Err(UtilError::PackfileTooLarge(format!(
+            "más de {} bytes",
+            limits.max_bytes
+        )));
+    }
+    let mut progress = Progress::new("Receiving objects", objects);
+    let pack = read_packfile_objects(
+        &buffer,
+        objects,
+        ofs_delta,
+        limits,
+        object_format,
+        Some(&mut progress),
+    )?;
+    resolve_deltas(pack, object_format)
+}
+
+/// Aplica los deltas de un packfile sobre sus objetos base y devuelve todos los objetos
+/// completos, en el orden del packfile. Cada delta resuelto toma el tipo de su base.
+///
+/// # Argumentos
+///
+/// * `pack` - Objetos leídos del packfile.
+/// * `object_format` - Formato de los objetos, con el que se calculan los hashes de las bases
+///   de los `RefDelta`.
+///
+/// # Retorno
+///
+/// Los objetos completos, o `UtilError::UnresolvedDeltas` si algún delta tiene una base que no
+/// está en el packfile.
+pub fn resolve_deltas(
+    pack: PackfileObjects,
+    object_format: ObjectFormat,
 ) -> Result<Vec<(ObjectEntry, Vec<u8>)>, UtilError> {
-    let mut information: Vec<(ObjectEntry, Vec<u8>)> = Vec::new();
+    let (objects, unresolved) = resolve_available_deltas(pack, object_format)?;
+    if unresolved > 0 {
+        return Err(UtilError::UnresolvedDeltas(unresolved));
+    }
+    Ok(objects)
+}
+
+/// Aplica los deltas cuya base está en el packfile. Como la base de un delta puede ser otro
+/// delta, y la de un `RefDelta` puede aparecer después que él, se repiten las pasadas hasta
+/// que no se resuelve ningún delta más.
+///
+/// # Retorno
+///
+/// Los objetos completos en el orden del packfile, sin los deltas que no se pudieron resolver,
+/// y la cantidad de esos deltas.
+fn resolve_available_deltas(
+    pack: PackfileObjects,
+    object_format: ObjectFormat,
+) -> Result<ResolvedObjects, UtilError> {
+    let mut resolved: Vec<Option<(ObjectType, Vec<u8>)>> = Vec::new();
+    let mut hashes: HashMap<String, usize> = HashMap::new();
+    let mut deltas: Vec<(usize, Vec<u8>)> = Vec::new();
+    for (index, (entry, data)) in pack.objects.into_iter().enumerate() {
+        match entry.obj_type {
+            ObjectType::OfsDelta | ObjectType::RefDelta => {
+                resolved.push(None);
+                deltas.push((index, data));
+            }
+            obj_type => {
+                hashes.insert(object_hash(obj_type, &data, object_format), index);
+                resolved.push(Some((obj_type, data)));
+            }
+        }
+    }
+
+    while !deltas.is_empty() {
+        let mut pending = Vec::new();
+        let count = deltas.len();
+        for (index, delta) in deltas {
+            let base_index = match pack.ofs_bases.get(&index) {
+                Some(base_index) => Some(*base_index),
+                None => pack
+                    .ref_bases
+                    .get(&index)
+                    .and_then(|hash| hashes.get(hash).copied()),
+            };
+            let object = match base_index.and_then(|base_index| resolved[base_index].as_ref()) {
+                Some((obj_type, base)) => (*obj_type, apply_delta(base, &delta)?),
+                None => {
+                    pending.push((index, delta));
+                    continue;
+                }
+            };
+            hashes.insert(object_hash(object.0, &object.1, object_format), index);
+            resolved[index] = Some(object);
+        }
+        deltas = pending;
+        if deltas.len() == count {
+            break;
+        }
+    }
+
+    let objects = resolved
+        .into_iter()
+        .flatten()
+        .map(|(obj_type, data)| (ObjectEntry::new(obj_type, data.len()), data))
+        .collect();
+    Ok((objects, deltas.len()))
+}
+
+/// Calcula el hash de un objeto a partir de su tipo y su contenido.
+fn object_hash(obj_type: ObjectType, data: &[u8], object_format: ObjectFormat) -> String {
+    let name = match obj_type {
+        ObjectType::Commit => COMMIT,
+        ObjectType::Tree => TREE,
+        ObjectType::Blob => BLOB,
+        _ => TAG,
+    };
+    let mut store = format!("{} {}\0", name, data.len()).into_bytes();
+    store.extend_from_slice(data);
+    object_format.hash(&store)
+}
+
+/// Aplica las instrucciones de un delta sobre su objeto base.
+///
+/// Luego de los tamaños de la base y del resultado, cada instrucción con el bit más
+/// significativo en 1 copia un rango de la base, cuyo offset y tamaño se indican en los bytes
+/// siguientes según los bits 0-3 y 4-6; una instrucción con el bit en 0 inserta los siguientes
+/// `n` bytes del delta.
+///
+/// # Argumentos
+///
+/// * `base` - Contenido del objeto base.
+/// * `delta` - Instrucciones del delta ya descomprimidas.
+///
+fn apply_delta(base: &[u8], delta: &[u8]) -> Result<Vec<u8>, UtilError> {
+    let mut offset = 0;
+    if read_delta_size(delta, &mut offset)? != base.len() {
+        return Err(UtilError::InvalidDelta);
+    }
+    let target_size = read_delta_size(delta, &mut offset)?;
+    let mut target = Vec::with_capacity(target_size);
+    while let Some(&instruction) = delta.get(offset) {
+        offset += 1;
+        if instruction & 0b10000000 != 0 {
+            let mut read_argument = |bits: std::ops::Range<u8>| -> Result<usize, UtilError> {
+                let mut value = 0;
+                for bit in bits.clone() {
+                    if instruction & (1 << bit) != 0 {
+                        let byte = *delta.get(offset).ok_or(UtilError::InvalidDelta)?;
+                        offset += 1;
+                        value |= (byte as usize) << (8 * (bit - bits.start));
+                    }
+                }
+                Ok(value)
+            };
+            let copy_offset = read_argument(0..4)?;
+            let copy_size = match read_argument(4..7)? {
+                0 => 0x10000,
+                size => size,
+            };
+            match base.get(copy_offset..copy_offset.saturating_add(copy_size)) {
+                Some(bytes) => target.extend_from_slice(bytes),
+                None => return Err(UtilError::InvalidDelta),
+            }
+        } else if instruction != 0 {
+            match delta.get(offset..offset + instruction as usize) {
+                Some(bytes) => target.extend_from_slice(bytes),
+                None => return Err(UtilError::InvalidDelta),
+            }
+            offset += instruction as usize;
+        } else {
+            return Err(UtilError::InvalidDelta);
+        }
+    }
+    if target.len() != target_size {
+        return Err(UtilError::InvalidDelta);
+    }
+    Ok(target)
+}
+
+/// Lee los objetos de los datos de un packfile, registrando el offset de inicio de cada uno.
+///
+/// Los `OfsDelta` indican su base como una distancia negativa desde su propio inicio, por lo
+/// que la base se busca entre los objetos ya leídos en `this_offset - relative_offset`.
+///
+/// # Argumentos
+///
+/// * `buffer` - Datos del packfile a partir del primer objeto (sin el encabezado).
+/// * `objects` - Cantidad de objetos indicada en el encabezado.
+/// * `ofs_delta` - Indica si se negoció la capacidad `ofs-delta`.
+/// * `limits` - Límites de tamaño; ningún objeto se descomprime más allá de `max_object_size`.
+/// * `object_format` - Formato de los objetos, con el que se leen las bases de los `RefDelta`.
+/// * `progress` - Si se indica, se registra en el log el avance a medida que se leen los objetos.
+///
+/// # Retorno
+///
+/// Un `PackfileObjects` con los objetos leídos, o un `UtilError` si algún objeto está mal
+/// formado, si aparece un `OfsDelta` sin haberse negociado `ofs-delta`, si la base de un
+/// `OfsDelta` no corresponde a un objeto anterior o si un objeto supera `max_object_size`.
+pub fn read_packfile_objects(
+    buffer: &[u8],
+    objects: usize,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+    mut progress: Option<&mut Progress>,
+) -> Result<PackfileObjects, UtilError> {
+    let mut pack = PackfileObjects::default();
+    let mut offset: usize = 0;
+
+    for index in 0..objects {
+        read_packfile_object(
+            buffer,
+            &mut offset,
+            &mut pack,
+            ofs_delta,
+            limits,
+            object_format,
+        )?;
+        if let Some(progress) = progress.as_deref_mut() {
+            progress.log(index + 1);
+        }
+    }
+    Ok(pack)
+}
+
+/// Lee el objeto que comienza en `offset` y lo agrega a `pack`, avanzando el offset hasta el
+/// siguiente objeto. Si el objeto está incompleto o mal formado no se agrega.
+///
+/// # Argumentos
+///
+/// * `buffer` - Datos del packfile a partir del primer objeto (sin el encabezado).
+/// * `offset` - Posición del objeto a leer.
+/// * `pack` - Objetos ya leídos, donde se buscan las bases de los `OfsDelta`.
+/// * `ofs_delta` - Indica si se negoció la capacidad `ofs-delta`.
+/// * `limits` - Límites de tamaño del objeto.
+/// * `object_format` - Formato de los objetos, con el que se lee el hash de la base de un `RefDelta`.
+///
+fn read_packfile_object(
+    buffer: &[u8],
+    offset: &mut usize,
+    pack: &mut PackfileObjects,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+) -> Result<(), UtilError> {
+    let index = pack.objects.len();
+    let start = *offset;
+    let object_entry = read_type_and_length_from_vec(buffer, offset)?;
+    if object_entry.obj_length > limits.max_object_size {
+        return Err(object_too_large(limits.max_object_size));
+    }
+    match object_entry.obj_type {
+        ObjectType::OfsDelta => {
+            if !ofs_delta {
+                return Err(UtilError::OfsDeltaNotNegotiated);
+            }
+            let relative_offset = read_ofs_delta_offset(buffer, offset)?;
+            let base_offset = match start.checked_sub(relative_offset) {
+                Some(base_offset) if relative_offset != 0 => base_offset,
+                _ => return Err(UtilError::OfsDeltaBaseNotFound(start)),
+            };
+            match pack.offsets.get(&base_offset) {
+                Some(base_index) => pack.ofs_bases.insert(index, *base_index),
+                None => return Err(UtilError::OfsDeltaBaseNotFound(base_offset)),
+            };
+        }
+        ObjectType::RefDelta => {
+            let hash_len = object_format.raw_len();
+            let base_hash = match buffer.get(*offset..*offset + hash_len) {
+                Some(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+                None => return Err(UtilError::PackfileTruncated),
+            };
+            *offset += hash_len;
+            pack.ref_bases.insert(index, base_hash);
+        }
+        _ => {}
+    }
+    let data: Vec<u8> = read_object_data(
+        buffer,
+        offset,
+        limits.max_object_size,
+        object_entry.obj_length,
+    )?;
+
+    if data.len() != object_entry.obj_length {
+        return Err(UtilError::DataPackFiletReadObject);
+    }
+    if matches!(
+        object_entry.obj_type,
+        ObjectType::OfsDelta | ObjectType::RefDelta
+    ) && read_delta_target_size(&data)? > limits.max_object_size
+    {
+        return Err(object_too_large(limits.max_object_size));
+    }
+    pack.offsets.insert(start, index);
+    pack.objects.push((object_entry, data));
+    Ok(())
+}
+
+/// Objetos recibidos de un packfile que pudo cortarse antes de terminar, por ejemplo porque
+/// se cayó la conexión.
+///
+/// - `objects`: Objetos recibidos completos, en el orden del packfile.
+/// - `expected`: Cantidad de objetos indicada en el encabezado.
+/// - `verified`: Indica si se recibió el checksum final y coincide con el packfile.
+///
+#[derive(Debug, Default)]
+pub struct ReceivedPackfile {
+    pub objects: Vec<(ObjectEntry, Vec<u8>)>,
+    pub expected: usize,
+    pub verified: bool,
+}
+
+impl ReceivedPackfile {
+    /// Indica si se recibieron todos los objetos anunciados en el encabezado y el checksum
+    /// final del packfile.
+    pub fn is_complete(&self) -> bool {
+        self.objects.len() == self.expected && self.verified
+    }
+}
+
+/// Lee los datos del packfile a continuación del encabezado hasta que termina o se corta la
+/// conexión, y devuelve los objetos recibidos completos. Un objeto cortado a la mitad se
+/// descarta, al igual que todos los que le siguen.
+///
+/// Solo se toleran los datos cortados al final: un objeto mal formado, un packfile que supera
+/// los límites o un checksum final que no coincide se informan como error.
+///
+/// # Argumentos
+///
+/// * `reader` - Canal del que se lee el packfile.
+/// * `header` - Encabezado del packfile ya leído, que forma parte del checksum final.
+/// * `objects` - Cantidad de objetos indicada en el encabezado.
+/// * `ofs_delta` - Indica si se negoció la capacidad `ofs-delta`.
+/// * `limits` - Límites de tamaño; la lectura se corta en cuanto se supera `max_bytes`.
+/// * `object_format` - Formato de los objetos, con el que se calcula el checksum final.
+///
+pub fn read_packfile_data_partial(
+    reader: &mut dyn Read,
+    header: &[u8],
+    objects: usize,
+    ofs_delta: bool,
+    limits: &PackfileLimits,
+    object_format: ObjectFormat,
+) -> Result<ReceivedPackfile, UtilError> {
+    if objects > limits.max_objects {
+        return Err(UtilError::PackfileTooLarge(format!(
+            "{} objetos (máximo {})",
+            objects, limits.max_objects
+        )));
+    }
+    // Si la conexión se corta con un error, lo leído hasta ese momento queda en el buffer
     let mut buffer: Vec<u8> = Vec::new();
-    match reader.read_to_end(&mut buffer) // Necesita refactorizar, si el packfile es muy grande habra problema
-     {
-         Ok(buffer) => buffer,
-         Err(_) => return Err(UtilError::DataPackFiletReadObject),
-     };
-    //let buffer = read_data_packfile(reader)?;
+    let max_len = limits.max_bytes.saturating_add(object_format.raw_len());
+    let _ = reader
+        .take((max_len as u64).saturating_add(1))
+        .read_to_end(&mut buffer);
+    if buffer.len() > max_len {
+        return Err(UtilError::PackfileTooLarge(format!(
+            "más de {} bytes",
+            limits.max_bytes
+        )));
+    }
+
+    let mut pack = PackfileObjects::default();
     let mut offset: usize = 0;
+    let mut progress = Progress::new("Receiving objects", objects);
+    while pack.objects.len() < objects {
+        let read = read_packfile_object(
+            &buffer,
+            &mut offset,
+            &mut pack,
+            ofs_delta,
+            limits,
+            object_format,
+        );
+        match read {
+            Ok(()) => progress.log(pack.objects.len()),
+            Err(UtilError::PackfileTruncated) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    let verified = match buffer.get(offset..offset + object_format.raw_len()) {
+        Some(trailer) if pack.objects.len() == objects => {
+            let mut hasher = ObjectHasher::new(object_format);
+            hasher.update(header);
+            hasher.update(&buffer[..offset]);
+            if hasher.finalize() != trailer {
+                return Err(UtilError::PackfileChecksumMismatch);
+            }
+            true
+        }
+        _ => false,
+    };
+    // Los deltas cuya base no llegó antes del corte se descartan, como los objetos cortados
+    let (resolved, unresolved) = resolve_available_deltas(pack, object_format)?;
+    if verified && unresolved > 0 {
+        return Err(UtilError::UnresolvedDeltas(unresolved));
+    }
+    Ok(ReceivedPackfile {
+        objects: resolved,
+        expected: objects,
+        verified,
+    })
+}
 
-    for _ in 0..objects {
-        let object_entry = read_type_and_length_from_vec(&buffer, &mut offset)?;
-        // println!("Object entry: {:?}", object_entry);
-        println!("Object entry: {:?}", object_entry.obj_type);
-        let data: Vec<u8> = read_object_data(&buffer, &mut offset)?;
+/// Lee la distancia a la base de un `OfsDelta`.
+///
+/// Cada byte aporta 7 bits y el bit más significativo indica si sigue otro byte; a diferencia
+/// del tamaño de los objetos, se suma 1 antes de cada desplazamiento para que no haya dos
+/// codificaciones del mismo valor.
+///
+/// # Argumentos
+///
+/// * `data` - Datos del packfile.
+/// * `offset` - Posición del primer byte de la distancia; se avanza hasta el inicio de los datos.
+///
+fn read_ofs_delta_offset(data: &[u8], offset: &mut usize) -> Result<usize, UtilError> {
+    let mut byte = match data.get(*offset) {
+        Some(byte) => *byte,
+        None => return Err(UtilError::PackfileTruncated),
+    };
+    *offset += 1;
+    let mut relative_offset = (byte & 0b01111111) as usize;
+    while (byte & 0b10000000) != 0 {
+        byte = match data.get(*offset) {
+            Some(byte) => *byte,
+            None => return Err(UtilError::PackfileTruncated),
+        };
+        *offset += 1;
+        relative_offset = ((relative_offset + 1) << 7) | (byte & 0b01111111) as usize;
+    }
+    Ok(relative_offset)
+}
 
-        if data.len() != object_entry.obj_length {
-            return Err(UtilError::DataPackFiletReadObject);
+/// Lee el tamaño del objeto que resulta de aplicar un delta.
+///
+/// Las instrucciones del delta comienzan con el tamaño de la base y el del objeto resultante,
+/// cada uno con 7 bits por byte, empezando por los menos significativos, y el bit más
+/// significativo indicando si sigue otro byte. Un tamaño que no entra en un `usize` se
+/// considera demasiado grande.
+///
+/// # Argumentos
+///
+/// * `delta` - Instrucciones del delta ya descomprimidas.
+///
+fn read_delta_target_size(delta: &[u8]) -> Result<usize, UtilError> {
+    let mut offset = 0;
+    read_delta_size(delta, &mut offset)?;
+    read_delta_size(delta, &mut offset)
+}
+
+fn read_delta_size(delta: &[u8], offset: &mut usize) -> Result<usize, UtilError> {
+    let mut size: usize = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let byte = match delta.get(*offset) {
+            Some(byte) => *byte,
+            None => return Err(UtilError::DataPackFiletReadObject),
+        };
+        *offset += 1;
+        let bits = (byte & 0b01111111) as usize;
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return Err(object_too_large(usize::MAX));
         }
-        information.push((object_entry, data));
+        size |= bits << shift;
+        if (byte & 0b10000000) == 0 {
+            return Ok(size);
+        }
+        shift += 7;
     }
-    Ok(information)
 }
 
-fn read_object_data(data: &[u8], offset: &mut usize) -> Result<Vec<u8>, UtilError> {
+/// Descomprime el objeto que comienza en `offset`, avanzando el offset hasta el siguiente.
+///
+/// El encabezado del objeto puede mentir sobre su tamaño, por lo que la descompresión se
+/// corta en cuanto se supera `max_size` en lugar de descomprimir el objeto completo. Si los
+/// datos se terminan antes del final del objeto se devuelve `UtilError::PackfileTruncated`.
+fn read_object_data(
+    data: &[u8],
+    offset: &mut usize,
+    max_size: usize,
+    expected_size: usize,
+) -> Result<Vec<u8>, UtilError> {
     let mut decompressed_data: Vec<u8> = Vec::new();
 
+    let remaining = data.len().saturating_sub(*offset);
     let mut zlib_decoder: ZlibDecoder<&[u8]> = ZlibDecoder::new(&data[*offset..]);
-    let n = match zlib_decoder.read_to_end(&mut decompressed_data) {
+    let max_read = (max_size as u64).saturating_add(1);
+    let result = zlib_decoder
+        .by_ref()
+        .take(max_read)
+        .read_to_end(&mut decompressed_data);
+    // Se consumieron todos los datos sin completar el objeto: el packfile se cortó
+    let incomplete = result.is_err() || decompressed_data.len() < expected_size;
+    if incomplete && zlib_decoder.total_in() as usize >= remaining {
+        return Err(UtilError::PackfileTruncated);
+    }
+    let n = match result {
         Ok(n) => n,
         Err(_) => return Err(UtilError::ObjectDeserialization),
     };
+    if n > max_size {
+        return Err(object_too_large(max_size));
+    }
 
     if n == 0 {
         return Err(UtilError::EmptyDecompressionError);
@@ -67,6 +684,11 @@ fn read_object_data(data: &[u8], offset: &mut usize) -> Result<Vec<u8>, UtilErro
     Ok(decompressed_data)
 }
 
+/// Error de un objeto que supera el tamaño máximo permitido.
+fn object_too_large(max_size: usize) -> UtilError {
+    UtilError::PackfileTooLarge(format!("un objeto de más de {} bytes", max_size))
+}
+
 pub fn read_data_packfile(reader: &mut dyn Read) -> Result<Vec<u8>, UtilError> {
     let mut buffer = Vec::new();
     let mut temp_buffer = [0; BUFFER_SIZE];
@@ -78,11 +700,8 @@ pub fn read_data_packfile(reader: &mut dyn Read) -> Result<Vec<u8>, UtilError> {
 
         if bytes_read != BUFFER_SIZE {
             buffer.extend_from_slice(&temp_buffer[..bytes_read]);
-            println!("Len buffer: {}", buffer.len());
-            println!("Saliii");
             break;
         }
-        println!("Reaaaad!");
         buffer.extend_from_slice(&temp_buffer[..bytes_read]);
         temp_buffer = [0; BUFFER_SIZE];
     }
@@ -163,18 +782,139 @@ fn read_objects_contained(reader: &mut dyn Read) -> Result<u32, UtilError> {
     Ok(value)
 }
 
-pub fn send_packfile(
+/// Envía un packfile con los objetos recibidos. Los objetos se escriben a medida que el
+/// iterador los produce y el SHA-1 final se calcula de forma incremental, por lo que no es
+/// necesario tener todo el packfile en memoria.
+///
+/// # Argumentos
+///
+/// * `writer` - Canal por el que se envía el packfile.
+/// * `server` - Servidor con la versión del packfile.
+/// * `number_objects` - Cantidad de objetos que produce `objects`, que se envía en el header.
+/// * `objects` - Tipo y contenido de cada objeto.
+/// * `decoder` - Si es true, el contenido de los objetos ya está comprimido.
+///
+/// # Retorno
+///
+/// Retorna `UtilError::PackfileObjectCountMismatch` si `objects` no produce la cantidad de
+/// objetos anunciada.
+pub fn send_packfile<I>(
     writer: &mut dyn Write,
     server: &GitServer,
-    objects: Vec<(ObjectType, Vec<u8>)>,
+    number_objects: usize,
+    objects: I,
     decoder: bool,
-) -> Result<(), UtilError> {
-    println!("Send packfile");
-    let mut sha1 = Sha1::new();
+) -> Result<(), UtilError>
+where
+    I: IntoIterator<Item = Result<(ObjectType, Vec<u8>), UtilError>>,
+{
+    let mut writer = PackfileWriter {
+        writer,
+        side_band: false,
+        progress: None,
+    };
+    write_packfile(&mut writer, server, number_objects, objects, decoder)
+}
+
+/// Envía un packfile multiplexado con la capacidad `side-band-64k`: los datos del packfile
+/// viajan en líneas pkt-line de la banda 1 y, si se pide, el avance ("Writing objects") en la
+/// banda 2. El envío termina con un flush-pkt.
+///
+/// # Argumentos
+///
+/// * `writer` - Canal por el que se envía el packfile.
+/// * `server` - Servidor con la versión del packfile.
+/// * `number_objects` - Cantidad de objetos que produce `objects`, que se envía en el header.
+/// * `objects` - Tipo y contenido de cada objeto.
+/// * `decoder` - Si es true, el contenido de los objetos ya está comprimido.
+/// * `progress` - Indica si se envían mensajes de avance en la banda 2.
+///
+/// # Retorno
+///
+/// Los mismos errores que `send_packfile`.
+pub fn send_packfile_side_band<I>(
+    writer: &mut dyn Write,
+    server: &GitServer,
+    number_objects: usize,
+    objects: I,
+    decoder: bool,
+    progress: bool,
+) -> Result<(), UtilError>
+where
+    I: IntoIterator<Item = Result<(ObjectType, Vec<u8>), UtilError>>,
+{
+    let mut side_band = PackfileWriter {
+        writer: &mut *writer,
+        side_band: true,
+        progress: progress.then(|| Progress::new("Writing objects", number_objects)),
+    };
+    write_packfile(&mut side_band, server, number_objects, objects, decoder)?;
+    send_flush(writer, UtilError::SendObjectPackfile)
+}
+
+/// Canal por el que se escribe un packfile. Sin side-band los bytes se escriben tal cual; con
+/// side-band se envían en líneas pkt-line de la banda de datos.
+struct PackfileWriter<'a> {
+    writer: &'a mut dyn Write,
+    side_band: bool,
+    progress: Option<Progress>,
+}
+
+impl PackfileWriter<'_> {
+    /// Envía por la banda de progreso el avance, si corresponde reportarlo. Los mensajes
+    /// intermedios terminan en `\r` para que el cliente los sobrescriba en la misma línea.
+    fn report_progress(&mut self, sent_objects: usize) -> Result<(), UtilError> {
+        let message = match self.progress.as_mut().and_then(|p| p.update(sent_objects)) {
+            Some(message) => message,
+            None => return Ok(()),
+        };
+        let end = match self.progress.as_ref().is_some_and(Progress::is_done) {
+            true => '\n',
+            false => '\r',
+        };
+        let line = side_band_line(SIDE_BAND_PROGRESS, format!("{}{}", message, end).as_bytes());
+        send_bytes(self.writer, &line, UtilError::SendObjectPackfile)
+    }
+}
+
+impl Write for PackfileWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.side_band {
+            return self.writer.write(buf);
+        }
+        let buf = &buf[..buf.len().min(SIDE_BAND_MAX_DATA)];
+        self.writer
+            .write_all(&side_band_line(SIDE_BAND_DATA, buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Arma una línea pkt-line de la banda indicada.
+fn side_band_line(band: u8, data: &[u8]) -> Vec<u8> {
+    let mut line = format!("{:04x}", data.len() + LENGTH_PREFIX_SIZE + 1).into_bytes();
+    line.push(band);
+    line.extend_from_slice(data);
+    line
+}
+
+fn write_packfile<I>(
+    writer: &mut PackfileWriter,
+    server: &GitServer,
+    number_objects: usize,
+    objects: I,
+    decoder: bool,
+) -> Result<(), UtilError>
+where
+    I: IntoIterator<Item = Result<(ObjectType, Vec<u8>), UtilError>>,
+{
+    let mut sha1 = ObjectHasher::new(server.object_format);
     // Envio signature
     send_bytes(writer, &PACK_BYTES, UtilError::SendSignaturePackfile)?;
     sha1.update(PACK_BYTES);
-    println!("Signature: {:?}", PACK_BYTES);
 
     // Envio version
     send_bytes(
@@ -183,30 +923,36 @@ pub fn send_packfile(
         UtilError::SendSignaturePackfile,
     )?;
     sha1.update(server.version.to_be_bytes());
-    println!("Version: {}", server.version);
 
     // Envio numero de objetos
-    let number_objects = objects.len() as u32;
+    let expected_objects = number_objects;
+    let number_objects = number_objects as u32;
     send_bytes(
         writer,
         &number_objects.to_be_bytes(),
         UtilError::SendSignaturePackfile,
     )?;
-    println!("Number of objects: {}", number_objects);
+    log_debug(&format!("Number of objects: {}", number_objects));
 
     sha1.update(number_objects.to_be_bytes());
-    // println!("Number of objects: {}", number_objects);
 
-    if decoder {
-        // Envio de objetos
-        for (object_type, content) in objects {
+    // Envio de objetos
+    let mut sent_objects = 0;
+    for object in objects {
+        let (object_type, content) = object?;
+        if decoder {
             send_object(writer, object_type, content, &mut sha1)?;
-        }
-    } else {
-        // Envio de objetos
-        for (object_type, content) in objects {
+        } else {
             send_object_enconder(writer, object_type, content, &mut sha1)?;
         }
+        sent_objects += 1;
+        writer.report_progress(sent_objects)?;
+    }
+    if sent_objects != expected_objects {
+        return Err(UtilError::PackfileObjectCountMismatch(
+            expected_objects,
+            sent_objects,
+        ));
     }
     let result = sha1.finalize();
     send_bytes(writer, &result[..], UtilError::SendSha1Packfile)?; // Esto es nuevo, envio el sha1 del packfile
@@ -217,7 +963,7 @@ pub fn send_object(
     writer: &mut dyn Write,
     obj_type: ObjectType,
     content: Vec<u8>,
-    sha1: &mut Sha1,
+    sha1: &mut ObjectHasher,
 ) -> Result<(), UtilError> {
     let mut decompressed_data: Vec<u8> = Vec::new();
     let mut zlib_decoder: ZlibDecoder<&[u8]> = ZlibDecoder::new(&content);
@@ -240,7 +986,7 @@ pub fn send_object_enconder(
     writer: &mut dyn Write,
     obj_type: ObjectType,
     content: Vec<u8>,
-    sha1: &mut Sha1,
+    sha1: &mut ObjectHasher,
 ) -> Result<(), UtilError> {
     let object = ObjectEntry::new(obj_type, content.len());
     let mut bytes = object.to_bytes();
@@ -264,8 +1010,300 @@ pub fn send_object_enconder(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sha1::{Digest, Sha1};
     use std::io::{self, Cursor};
 
+    fn compress(content: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).expect("Falló al comprimir");
+        encoder.finish().expect("Falló al comprimir")
+    }
+
+    #[test]
+    fn test_send_packfile_from_iterator() {
+        let directory = "./test_send_packfile_iterator";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+        let server = GitServer::create_from_path(directory, 2, &[]).expect("Falló el servidor");
+        std::fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let objects = [b"hola".to_vec(), b"mundo".to_vec()];
+        let lazy_objects = objects
+            .iter()
+            .map(|content| Ok((ObjectType::Blob, compress(content))));
+        let mut packfile = Vec::new();
+        send_packfile(&mut packfile, &server, 2, lazy_objects, true).expect("Falló el envío");
+
+        let mut reader = Cursor::new(&packfile);
+        assert_eq!(read_packfile_header(&mut reader), Ok(2));
+        let (content, checksum) = packfile.split_at(packfile.len() - 20);
+        assert_eq!(checksum, &Sha1::digest(content)[..]);
+
+        // La cantidad anunciada en el header debe coincidir con los objetos enviados
+        let lazy_objects = objects
+            .iter()
+            .map(|content| Ok((ObjectType::Blob, compress(content))));
+        assert_eq!(
+            send_packfile(&mut Vec::new(), &server, 3, lazy_objects, true),
+            Err(UtilError::PackfileObjectCountMismatch(3, 2))
+        );
+    }
+
+    #[test]
+    fn test_send_packfile_side_band() {
+        let directory = "./test_send_packfile_side_band";
+        crate::commands::init::git_init(directory).expect("Falló al crear el repositorio");
+        let server = GitServer::create_from_path(directory, 2, &[]).expect("Falló el servidor");
+        std::fs::remove_dir_all(directory).expect("Falló al remover el directorio temporal");
+
+        let objects = [b"hola".to_vec(), b"mundo".to_vec()];
+        let lazy_objects = || {
+            objects
+                .iter()
+                .map(|content| Ok((ObjectType::Blob, compress(content))))
+        };
+        let mut expected = Vec::new();
+        send_packfile(&mut expected, &server, 2, lazy_objects(), true).expect("Falló el envío");
+        let mut sent = Vec::new();
+        send_packfile_side_band(&mut sent, &server, 2, lazy_objects(), true, true)
+            .expect("Falló el envío");
+
+        // Se separan las bandas de las líneas pkt-line hasta el flush-pkt
+        let (mut data, mut progress) = (Vec::new(), Vec::new());
+        let mut rest = &sent[..];
+        loop {
+            let length = std::str::from_utf8(&rest[..4]).expect("Largo inválido");
+            let length = usize::from_str_radix(length, 16).expect("Largo inválido");
+            if length == 0 {
+                break;
+            }
+            match rest[4] {
+                SIDE_BAND_DATA => data.extend_from_slice(&rest[5..length]),
+                SIDE_BAND_PROGRESS => progress.extend_from_slice(&rest[5..length]),
+                band => panic!("Banda inesperada: {}", band),
+            }
+            rest = &rest[length..];
+        }
+        assert_eq!(rest, b"0000");
+        assert_eq!(data, expected);
+        assert_eq!(
+            String::from_utf8(progress),
+            Ok("Writing objects: 100% (2/2), done.\n".to_string())
+        );
+
+        // Sin progreso solo se envía la banda de datos
+        let mut sent = Vec::new();
+        send_packfile_side_band(&mut sent, &server, 2, lazy_objects(), true, false)
+            .expect("Falló el envío");
+        assert!(!sent.windows(7).any(|window| window == b"Writing"));
+    }
+
+    #[test]
+    fn test_read_ofs_delta_offset() {
+        let mut offset = 0;
+        assert_eq!(read_ofs_delta_offset(&[0x05], &mut offset), Ok(5));
+        assert_eq!(offset, 1);
+
+        // 200 = ((0 + 1) << 7) | 72
+        let mut offset = 0;
+        assert_eq!(read_ofs_delta_offset(&[0x80, 72], &mut offset), Ok(200));
+        assert_eq!(offset, 2);
+
+        let mut offset = 0;
+        assert!(read_ofs_delta_offset(&[0x80], &mut offset).is_err());
+    }
+
+    #[test]
+    fn test_read_packfile_objects_ofs_delta() {
+        let base = b"Hola Mundo\n";
+        // Delta: tamaño base, tamaño resultado, copiar 11 bytes desde 0, insertar "!"
+        let delta: Vec<u8> = vec![11, 12, 0b10010000, 11, 1, b'!'];
+
+        let mut buffer = ObjectEntry::new(ObjectType::Blob, base.len()).to_bytes();
+        buffer.extend(compress(base));
+        let delta_start = buffer.len();
+        buffer.extend(ObjectEntry::new(ObjectType::OfsDelta, delta.len()).to_bytes());
+        buffer.push(delta_start as u8);
+        buffer.extend(compress(&delta));
+
+        let pack = read_packfile_objects(
+            &buffer,
+            2,
+            true,
+            &PackfileLimits::unlimited(),
+            ObjectFormat::Sha1,
+            None,
+        )
+        .expect("Falló al leer el packfile");
+
+        assert_eq!(pack.objects.len(), 2);
+        assert_eq!(pack.objects[0].1, base.to_vec());
+        assert_eq!(pack.objects[1].0.obj_type, ObjectType::OfsDelta);
+        assert_eq!(pack.objects[1].1, delta);
+        assert_eq!(pack.offsets.get(&0), Some(&0));
+        assert_eq!(pack.offsets.get(&delta_start), Some(&1));
+        assert_eq!(pack.ofs_bases.get(&1), Some(&0));
+
+        // Una distancia que no apunta al inicio de un objeto anterior es un error
+        let mut invalid = buffer[..delta_start].to_vec();
+        invalid.extend(ObjectEntry::new(ObjectType::OfsDelta, delta.len()).to_bytes());
+        invalid.push(delta_start as u8 - 1);
+        invalid.extend(compress(&delta));
+        assert_eq!(
+            read_packfile_objects(
+                &invalid,
+                2,
+                true,
+                &PackfileLimits::unlimited(),
+                ObjectFormat::Sha1,
+                None
+            )
+            .map(|pack| pack.objects.len()),
+            Err(UtilError::OfsDeltaBaseNotFound(1))
+        );
+
+        // Sin la capacidad ofs-delta no se aceptan deltas por offset
+        assert_eq!(
+            read_packfile_objects(
+                &buffer,
+                2,
+                false,
+                &PackfileLimits::unlimited(),
+                ObjectFormat::Sha1,
+                None
+            )
+            .map(|pack| pack.objects.len()),
+            Err(UtilError::OfsDeltaNotNegotiated)
+        );
+    }
+
+    #[test]
+    fn test_resolve_deltas() {
+        let base = b"Hola Mundo\n";
+        let base_hash = object_hash(ObjectType::Blob, base, ObjectFormat::Sha1);
+        // "Hola Mundo\n!" a partir de la base, y "Hola" a partir de ese resultado
+        let ofs_delta: Vec<u8> = vec![11, 12, 0b10010000, 11, 1, b'!'];
+        let ref_delta: Vec<u8> = vec![12, 4, 0b10010000, 4];
+        let ofs_hash = object_hash(ObjectType::Blob, b"Hola Mundo\n!", ObjectFormat::Sha1);
+        let hex_to_bytes = |hash: &str| -> Vec<u8> {
+            (0..hash.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hash[i..i + 2], 16).expect("Hash inválido"))
+                .collect()
+        };
+
+        // El RefDelta aparece antes que su base, que es a su vez un delta
+        let mut buffer = ObjectEntry::new(ObjectType::RefDelta, ref_delta.len()).to_bytes();
+        buffer.extend(hex_to_bytes(&ofs_hash));
+        buffer.extend(compress(&ref_delta));
+        let base_start = buffer.len();
+        buffer.extend(ObjectEntry::new(ObjectType::Blob, base.len()).to_bytes());
+        buffer.extend(compress(base));
+        let delta_start = buffer.len();
+        buffer.extend(ObjectEntry::new(ObjectType::OfsDelta, ofs_delta.len()).to_bytes());
+        buffer.push((delta_start - base_start) as u8);
+        buffer.extend(compress(&ofs_delta));
+
+        let limits = PackfileLimits::unlimited();
+        let pack = read_packfile_objects(&buffer, 3, true, &limits, ObjectFormat::Sha1, None)
+            .expect("Falló al leer el packfile");
+        let objects = resolve_deltas(pack, ObjectFormat::Sha1).expect("Falló al resolver");
+
+        let contents: Vec<&[u8]> = objects.iter().map(|(_, data)| data.as_slice()).collect();
+        assert_eq!(contents, vec![&b"Hola"[..], base, b"Hola Mundo\n!"]);
+        assert!(objects
+            .iter()
+            .all(|(entry, data)| entry.obj_type == ObjectType::Blob
+                && entry.obj_length == data.len()));
+
+        // Un RefDelta cuya base no está en el packfile no se puede resolver
+        let mut thin = ObjectEntry::new(ObjectType::RefDelta, ofs_delta.len()).to_bytes();
+        thin.extend(hex_to_bytes(&base_hash));
+        thin.extend(compress(&ofs_delta));
+        let pack = read_packfile_objects(&thin, 1, true, &limits, ObjectFormat::Sha1, None)
+            .expect("Falló al leer el packfile");
+        assert_eq!(
+            resolve_deltas(pack, ObjectFormat::Sha1),
+            Err(UtilError::UnresolvedDeltas(1))
+        );
+
+        // Instrucciones que no corresponden a la base
+        assert_eq!(
+            apply_delta(b"corto", &ofs_delta),
+            Err(UtilError::InvalidDelta)
+        );
+        assert_eq!(
+            apply_delta(base, &[11, 20, 0b10010000, 20]),
+            Err(UtilError::InvalidDelta)
+        );
+    }
+
+    #[test]
+    fn test_read_packfile_objects_delta_target_too_large() {
+        // Delta chico que declara un resultado de 10000 bytes: tamaño base 11, tamaño
+        // resultado 10000 (0x90 0x4e), insertar "!"
+        let delta: Vec<u8> = vec![11, 0x90, 0x4e, 1, b'!'];
+        let mut buffer = ObjectEntry::new(ObjectType::RefDelta, delta.len()).to_bytes();
+        buffer.extend([0xab; 20]);
+        buffer.extend(compress(&delta));
+
+        let limits = PackfileLimits {
+            max_object_size: 1000,
+            ..PackfileLimits::unlimited()
+        };
+        assert!(matches!(
+            read_packfile_objects(&buffer, 1, true, &limits, ObjectFormat::Sha1, None),
+            Err(UtilError::PackfileTooLarge(_))
+        ));
+        let pack = read_packfile_objects(
+            &buffer,
+            1,
+            true,
+            &PackfileLimits::unlimited(),
+            ObjectFormat::Sha1,
+            None,
+        )
+        .expect("Falló al leer el packfile");
+        assert_eq!(pack.objects[0].1, delta);
+        assert_eq!(read_delta_target_size(&delta), Ok(10000));
+    }
+
+    #[test]
+    fn test_read_packfile_objects_ref_delta_sha256() {
+        let delta: Vec<u8> = vec![11, 1, 1, b'!'];
+        let mut buffer = ObjectEntry::new(ObjectType::RefDelta, delta.len()).to_bytes();
+        buffer.extend([0xab; 32]);
+        buffer.extend(compress(&delta));
+
+        let limits = PackfileLimits::unlimited();
+        let pack = read_packfile_objects(&buffer, 1, true, &limits, ObjectFormat::Sha256, None)
+            .expect("Falló al leer el packfile");
+        assert_eq!(pack.ref_bases.get(&0), Some(&"ab".repeat(32)));
+        assert_eq!(pack.objects[0].1, delta);
+    }
+
+    #[test]
+    fn test_packfile_limits_from_config() {
+        let mut config = GitConfig::new();
+        config
+            .add_entry("maxInputSize", "10m", "receive")
+            .expect("Falló al agregar la entrada");
+        config
+            .add_entry("maxObjects", "0", "receive")
+            .expect("Falló al agregar la entrada");
+        config
+            .add_entry("maxObjectSize", "mucho", "receive")
+            .expect("Falló al agregar la entrada");
+
+        let limits = PackfileLimits::from_config(&config);
+        assert_eq!(limits.max_bytes, 10 * 1024 * 1024);
+        assert_eq!(limits.max_objects, usize::MAX);
+        assert_eq!(limits.max_object_size, PACKFILE_MAX_OBJECT_SIZE);
+        assert_eq!(
+            PackfileLimits::from_repository("./test_packfile_limits_missing"),
+            PackfileLimits::default()
+        );
+    }
+
     #[test]
     fn test_read_signature_valid_signature() -> Result<(), UtilError> {
         let data: [u8; 4] = [b'P', b'A', b'C', b'K']; // Firma válida "PACK"
@@ -2,7 +2,7 @@ use std::io::Read;
 
 use crate::consts::LENGTH_PREFIX_SIZE;
 
-use super::errors::UtilError;
+use super::{connections::read_exact_retry, errors::UtilError};
 
 /// Lee líneas de paquete del flujo de entrada proporcionado y las devuelve como un vector de vectores de bytes.
 ///
@@ -80,10 +80,11 @@ pub fn read(stream: &mut dyn Read) -> Result<Vec<Vec<u8>>, UtilError> {
 /// - `Result<Vec<u8>, UtilError>`: Un resultado que contiene el contenido de la línea de paquete o un error si ocurre alguno.
 pub fn read_pkt_line(socket: &mut dyn Read) -> Result<Vec<u8>, UtilError> {
     let mut length_buf = [0u8; 4];
-    if socket.read_exact(&mut length_buf).is_err() {
-        return Err(UtilError::InvalidPacketLineMissingLength);
-    };
-    println!("length_buf: {:?}", length_buf);
+    read_exact_retry(
+        socket,
+        &mut length_buf,
+        UtilError::InvalidPacketLineMissingLength,
+    )?;
     let length_hex = String::from_utf8_lossy(&length_buf);
     let length = match u32::from_str_radix(length_hex.trim(), 16) {
         Ok(l) => l,
@@ -97,15 +98,15 @@ pub fn read_pkt_line(socket: &mut dyn Read) -> Result<Vec<u8>, UtilError> {
 
     let length = length as usize - LENGTH_PREFIX_SIZE - 1; // 1 por el enter
     let mut content = vec![0u8; length];
-    if socket.read_exact(&mut content).is_err() {
-        return Err(UtilError::InvalidPacketLineReadData);
-    };
+    read_exact_retry(socket, &mut content, UtilError::InvalidPacketLineReadData)?;
 
     // Consume the newline character
     let mut newline_buf = [0u8; 1];
-    if socket.read_exact(&mut newline_buf).is_err() {
-        return Err(UtilError::InvalidPacketLineMissingNewline);
-    };
+    read_exact_retry(
+        socket,
+        &mut newline_buf,
+        UtilError::InvalidPacketLineMissingNewline,
+    )?;
 
     Ok(content)
 }
@@ -0,0 +1,124 @@
+use super::logger::{log_level, LogLevel};
+
+// Cantidad mínima de objetos entre dos reportes de avance
+pub const PROGRESS_MIN_OBJECTS: usize = 256;
+
+/// Avance de una operación sobre una cantidad conocida de objetos, como la recepción o la
+/// escritura de un packfile.
+///
+/// Para no saturar el canal de log, solo se genera un mensaje cuando el avance supera el 1% del
+/// total y al menos `PROGRESS_MIN_OBJECTS` objetos desde el último reporte. El último objeto
+/// siempre se reporta.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    title: String,
+    total: usize,
+    last_reported: Option<usize>,
+}
+
+impl Progress {
+    /// Crea el avance de una operación.
+    ///
+    /// # Argumentos
+    ///
+    /// * `title` - Nombre de la operación, por ejemplo "Receiving objects".
+    /// * `total` - Cantidad total de objetos.
+    ///
+    pub fn new(title: &str, total: usize) -> Self {
+        Progress {
+            title: title.to_string(),
+            total,
+            last_reported: None,
+        }
+    }
+
+    /// Actualiza el avance con la cantidad de objetos procesados.
+    ///
+    /// # Argumentos
+    ///
+    /// * `current` - Cantidad de objetos procesados hasta el momento.
+    ///
+    /// # Retorno
+    ///
+    /// El mensaje de avance, con el formato `<título>: <porcentaje>% (<actual>/<total>)`, o
+    /// `None` si todavía no corresponde reportarlo. Al completarse se agrega `, done.`.
+    pub fn update(&mut self, current: usize) -> Option<String> {
+        if self.total == 0 || current > self.total {
+            return None;
+        }
+        let step = (self.total / 100).max(PROGRESS_MIN_OBJECTS);
+        let done = current == self.total;
+        let due = match self.last_reported {
+            Some(last) => current >= last + step || (done && last != current),
+            None => current >= step || done,
+        };
+        if !due {
+            return None;
+        }
+        self.last_reported = Some(current);
+        let percent = current * 100 / self.total;
+        let mut message = format!(
+            "{}: {:3}% ({}/{})",
+            self.title, percent, current, self.total
+        );
+        if done {
+            message.push_str(", done.");
+        }
+        Some(message)
+    }
+
+    /// Actualiza el avance y, si corresponde, lo registra en el canal de log del proceso (ver
+    /// `set_log_channel`).
+    ///
+    /// # Argumentos
+    ///
+    /// * `current` - Cantidad de objetos procesados hasta el momento.
+    ///
+    pub fn log(&mut self, current: usize) {
+        if let Some(message) = self.update(current) {
+            log_level(None, LogLevel::Info, &message);
+        }
+    }
+
+    /// Indica si ya se reportó la última actualización de la operación.
+    pub fn is_done(&self) -> bool {
+        self.last_reported == Some(self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_throttled() {
+        let mut progress = Progress::new("Receiving objects", 1000);
+        let reported: Vec<String> = (1..=1000).filter_map(|i| progress.update(i)).collect();
+        assert_eq!(
+            reported,
+            vec![
+                "Receiving objects:  25% (256/1000)",
+                "Receiving objects:  51% (512/1000)",
+                "Receiving objects:  76% (768/1000)",
+                "Receiving objects: 100% (1000/1000), done.",
+            ]
+        );
+        assert!(progress.is_done());
+
+        // Con muchos objetos el paso es el 1% del total
+        let mut progress = Progress::new("Writing objects", 100_000);
+        assert_eq!(
+            (1..=100_000).filter_map(|i| progress.update(i)).count(),
+            100
+        );
+
+        let mut progress = Progress::new("Writing objects", 3);
+        assert_eq!(progress.update(1), None);
+        assert_eq!(
+            progress.update(3),
+            Some("Writing objects: 100% (3/3), done.".to_string())
+        );
+        assert_eq!(progress.update(3), None);
+        assert_eq!(Progress::new("Writing objects", 0).update(0), None);
+    }
+}
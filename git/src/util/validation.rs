@@ -1,6 +1,8 @@
 use std::{fs, path::Path};
 
-use crate::{consts::*, errors::GitError};
+use crate::{
+    consts::*, errors::GitError, util::errors::UtilError, util::object_format::ObjectFormat,
+};
 
 /// Valida una dirección IP.
 ///
@@ -93,6 +95,82 @@ pub fn valid_port(input: &str) -> Result<String, GitError> {
     Err(GitError::InvalidPortError)
 }
 
+/// Valida un valor del limitador de solicitudes del servidor HTTP
+/// (`rate_limit_refill` o `rate_limit_burst`).
+///
+/// # Retorno
+///
+/// * `Ok(num)`: Si el valor es un entero positivo.
+/// * `Err(GitError::InvalidRateLimitError)`: En caso contrario.
+///
+pub fn valid_rate_limit(input: &str) -> Result<u32, GitError> {
+    match input.parse::<u32>() {
+        Ok(num) if num > 0 => Ok(num),
+        _ => Err(GitError::InvalidRateLimitError),
+    }
+}
+
+/// Valida el timeout de red del servidor (`network_timeout`), expresado en segundos.
+///
+/// # Retorno
+///
+/// * `Ok(num)`: Si el valor es un entero positivo.
+/// * `Err(GitError::InvalidNetworkTimeoutError)`: En caso contrario.
+///
+pub fn valid_network_timeout(input: &str) -> Result<u64, GitError> {
+    match input.parse::<u64>() {
+        Ok(num) if num > 0 => Ok(num),
+        _ => Err(GitError::InvalidNetworkTimeoutError),
+    }
+}
+
+/// Valida la cantidad máxima de repositorios del servidor (`max_repositories`).
+///
+/// # Retorno
+///
+/// * `Ok(num)`: Si el valor es un entero positivo.
+/// * `Err(GitError::InvalidMaxRepositoriesError)`: En caso contrario.
+///
+pub fn valid_max_repositories(input: &str) -> Result<usize, GitError> {
+    match input.parse::<usize>() {
+        Ok(num) if num > 0 => Ok(num),
+        _ => Err(GitError::InvalidMaxRepositoriesError),
+    }
+}
+
+/// Valida la URL de un webhook del servidor HTTP (`webhook_url`). Solo se admiten URLs
+/// `http://` con un host, ya que los eventos se envían sin TLS.
+///
+/// # Retorno
+///
+/// * `Ok(url)`: Si la URL es válida.
+/// * `Err(GitError::InvalidWebhookUrlError)`: En caso contrario.
+///
+pub fn valid_webhook_url(input: &str) -> Result<String, GitError> {
+    match input.strip_prefix("http://") {
+        Some(rest) if !rest.is_empty() && !rest.starts_with(['/', ':']) => Ok(input.to_string()),
+        _ => Err(GitError::InvalidWebhookUrlError),
+    }
+}
+
+/// Valida el nombre de la branch por defecto de los repositorios nuevos.
+///
+/// # Argumentos
+///
+/// * `input`: Nombre de la branch.
+///
+/// # Retorno
+///
+/// * `Ok(branch)`: Si es un nombre de referencia válido.
+/// * `Err(GitError::InvalidDefaultBranchError)`: En caso contrario.
+///
+pub fn valid_default_branch(input: &str) -> Result<String, GitError> {
+    if is_valid_ref_name(input) {
+        return Ok(input.to_string());
+    }
+    Err(GitError::InvalidDefaultBranchError)
+}
+
 /// Valida un path de archivo.
 ///
 /// Esta función toma una cadena `input` que representa una ruta de archivo y valida si el
@@ -245,29 +323,33 @@ pub fn valid_email(input: &str) -> Result<String, GitError> {
 ///
 /// Un objeto ID válido debe cumplir con los siguientes criterios:
 ///
-/// 1. Debe tener una longitud de 40 caracteres, que es la longitud típica de un objeto ID en Git.
+/// 1. Debe tener la longitud de un hash del formato de objetos del repositorio: 40 caracteres
+///    en SHA-1 o 64 caracteres en SHA-256.
 /// 2. Debe contener caracteres válidos que son dígitos hexadecimales (0-9, a-f, A-F).
 ///
 /// # Argumentos
 ///
 /// * `obj_id`: Un objeto ID (hash) que se desea validar.
+/// * `object_format`: Formato de los objetos del repositorio.
 ///
 /// # Ejemplo
 ///
 /// ```
+/// use git::util::object_format::ObjectFormat;
 /// use git::util::validation::is_valid_obj_id;
 /// let obj_id_valido = "7217a7c7e582c46cec22a130adf4b9d7d950fba0";
 /// let obj_id_invalido = "invalid_hash";
 ///
-/// assert_eq!(is_valid_obj_id(obj_id_valido), true);
-/// assert_eq!(is_valid_obj_id(obj_id_invalido), false);
+/// assert_eq!(is_valid_obj_id(obj_id_valido, ObjectFormat::Sha1), true);
+/// assert_eq!(is_valid_obj_id(obj_id_valido, ObjectFormat::Sha256), false);
+/// assert_eq!(is_valid_obj_id(obj_id_invalido, ObjectFormat::Sha1), false);
 /// ```
 ///
 /// # Retorno
 ///
 /// `true` si el objeto ID es válido, `false` en caso contrario.
-pub fn is_valid_obj_id(obj_id: &str) -> bool {
-    if obj_id.len() != 40 {
+pub fn is_valid_obj_id(obj_id: &str, object_format: ObjectFormat) -> bool {
+    if obj_id.len() != object_format.hex_len() {
         return false;
     }
 
@@ -280,6 +362,32 @@ pub fn is_valid_obj_id(obj_id: &str) -> bool {
     true
 }
 
+/// Verifica si un objeto ID es un hash completo de alguno de los formatos de objetos.
+///
+/// Se usa al interpretar archivos sin leer la configuración del repositorio, como HEAD o el
+/// reflog; en el resto de los casos se valida con `is_valid_obj_id` y el formato del repositorio.
+///
+/// # Argumentos
+///
+/// * `obj_id`: Un objeto ID (hash) que se desea validar.
+///
+pub fn is_valid_obj_id_any_format(obj_id: &str) -> bool {
+    [ObjectFormat::Sha1, ObjectFormat::Sha256]
+        .into_iter()
+        .any(|object_format| is_valid_obj_id(obj_id, object_format))
+}
+
+/// Verifica si un objeto ID es el hash nulo (todos ceros) de alguno de los formatos de objetos,
+/// con el que se indica en un push que una referencia se crea o se borra.
+///
+/// # Argumentos
+///
+/// * `obj_id`: Un objeto ID (hash) que se desea validar.
+///
+pub fn is_null_obj_id(obj_id: &str) -> bool {
+    is_valid_obj_id_any_format(obj_id) && obj_id.bytes().all(|byte| byte == b'0')
+}
+
 /// Comprueba si la cadena de entrada es una dirección IPv4 válida.
 ///
 /// La función verifica si la cadena de entrada contiene cuatro segmentos separados
@@ -493,6 +601,88 @@ pub fn join_paths_correctly(first_path: &str, second_path: &str) -> String {
     format!("{}{}", first_path, second_path)
 }
 
+/// Convierte los separadores de una ruta a `/`, el separador que utiliza git en el index y en
+/// los objetos tree, para que un mismo repositorio se comporte igual en cualquier plataforma.
+///
+/// # Argumentos
+///
+/// * `path` - La ruta a normalizar.
+///
+/// # Ejemplo
+///
+/// ```
+/// use git::util::validation::normalize_path;
+/// assert_eq!(normalize_path(".\\repo\\src\\main.rs"), "./repo/src/main.rs");
+/// ```
+///
+/// # Retorno
+///
+/// Un `String` con la ruta utilizando `/` como separador.
+pub fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Verifica si un nombre de referencia cumple las reglas de `git check-ref-format`.
+///
+/// Un nombre es inválido si:
+///
+/// 1. Está vacío, es `@`, empieza o termina con `/`, o contiene `//`.
+/// 2. Algún componente empieza con `.` o termina con `.lock`.
+/// 3. Contiene `..` o `@{`, o termina con `.`.
+/// 4. Contiene caracteres de control, espacios o alguno de `~ ^ : ? * [ \`.
+///
+/// Se admiten nombres de un solo nivel (`master`), como en `git check-ref-format --allow-onelevel`.
+///
+/// # Argumentos
+///
+/// * `name` - Nombre de la referencia, por ejemplo `feature/login` o `refs/tags/v1.0`.
+///
+/// # Ejemplo
+///
+/// ```
+/// use git::util::validation::is_valid_ref_name;
+/// assert!(is_valid_ref_name("feature/login"));
+/// assert!(!is_valid_ref_name("../../config"));
+/// ```
+///
+/// # Retorno
+///
+/// `true` si el nombre es válido, `false` en caso contrario.
+pub fn is_valid_ref_name(name: &str) -> bool {
+    if name.is_empty() || name == "@" || name.ends_with('.') {
+        return false;
+    }
+    if name.contains("..") || name.contains("@{") {
+        return false;
+    }
+    if name
+        .chars()
+        .any(|c| c.is_ascii_control() || " ~^:?*[\\".contains(c))
+    {
+        return false;
+    }
+    name.split('/').all(|component| {
+        !component.is_empty() && !component.starts_with('.') && !component.ends_with(".lock")
+    })
+}
+
+/// Valida un nombre de referencia antes de escribirla en el repositorio.
+///
+/// # Argumentos
+///
+/// * `name` - Nombre de la referencia.
+///
+/// # Retorno
+///
+/// * `Ok(())`: Si el nombre es válido según `is_valid_ref_name`.
+/// * `Err(UtilError::InvalidReferenceName(name))`: Si el nombre no es válido.
+pub fn valid_ref_name(name: &str) -> Result<(), UtilError> {
+    if is_valid_ref_name(name) {
+        return Ok(());
+    }
+    Err(UtilError::InvalidReferenceName(name.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -529,6 +719,49 @@ mod tests {
         assert_eq!(result.unwrap(), port);
     }
 
+    #[test]
+    fn test_valid_rate_limit() {
+        assert_eq!(valid_rate_limit("10"), Ok(10));
+        assert_eq!(valid_rate_limit("0"), Err(GitError::InvalidRateLimitError));
+        assert_eq!(valid_rate_limit("-1"), Err(GitError::InvalidRateLimitError));
+    }
+
+    #[test]
+    fn test_valid_webhook_url() {
+        let url = "http://127.0.0.1:9000/hooks";
+        assert_eq!(valid_webhook_url(url), Ok(url.to_string()));
+        assert_eq!(
+            valid_webhook_url("https://ci.example.com"),
+            Err(GitError::InvalidWebhookUrlError)
+        );
+        assert_eq!(
+            valid_webhook_url("http://"),
+            Err(GitError::InvalidWebhookUrlError)
+        );
+    }
+
+    #[test]
+    fn test_valid_max_repositories() {
+        assert_eq!(valid_max_repositories("10"), Ok(10));
+        assert_eq!(
+            valid_max_repositories("0"),
+            Err(GitError::InvalidMaxRepositoriesError)
+        );
+    }
+
+    #[test]
+    fn test_valid_network_timeout() {
+        assert_eq!(valid_network_timeout("5"), Ok(5));
+        assert_eq!(
+            valid_network_timeout("0"),
+            Err(GitError::InvalidNetworkTimeoutError)
+        );
+        assert_eq!(
+            valid_network_timeout("abc"),
+            Err(GitError::InvalidNetworkTimeoutError)
+        );
+    }
+
     #[test]
     fn test_invalid_port_minimum_range() {
         let invalid_port_low = "10";
@@ -588,13 +821,24 @@ mod tests {
     #[test]
     fn test_valid_obj_id() {
         let valid_obj_id = "0123456789abcdef0123456789abcdef01234567";
-        assert!(is_valid_obj_id(valid_obj_id));
+        assert!(is_valid_obj_id(valid_obj_id, ObjectFormat::Sha1));
+        assert!(!is_valid_obj_id(valid_obj_id, ObjectFormat::Sha256));
+        assert!(is_valid_obj_id(&"a".repeat(64), ObjectFormat::Sha256));
+        assert!(!is_valid_obj_id(&"a".repeat(64), ObjectFormat::Sha1));
     }
 
     #[test]
     fn test_invalid_obj_id() {
         let short_obj_id = "0123456789abcdef0123456789abcdef0123456";
-        assert!(!is_valid_obj_id(short_obj_id));
+        assert!(!is_valid_obj_id(short_obj_id, ObjectFormat::Sha1));
+    }
+
+    #[test]
+    fn test_is_null_obj_id() {
+        assert!(is_null_obj_id(&"0".repeat(40)));
+        assert!(is_null_obj_id(&"0".repeat(64)));
+        assert!(!is_null_obj_id(&"0".repeat(39)));
+        assert!(!is_null_obj_id(&format!("{}1", "0".repeat(39))));
     }
 
     #[test]
@@ -800,4 +1044,45 @@ mod tests {
         let result = join_paths_correctly("/path/to", "file.txt");
         assert_eq!(result, "/path/to/file.txt".to_string());
     }
+
+    #[test]
+    fn test_is_valid_ref_name() {
+        let cases = [
+            ("master", true),
+            ("feature/login", true),
+            ("refs/heads/master", true),
+            ("refs/tags/v1.0", true),
+            ("fix-123_abc", true),
+            ("a.b", true),
+            ("", false),
+            ("@", false),
+            ("foo..bar", false),
+            ("../../config", false),
+            ("foo~1", false),
+            ("foo^", false),
+            ("foo:bar", false),
+            ("foo bar", false),
+            ("foo?", false),
+            ("foo*", false),
+            ("foo[bar", false),
+            ("foo\\bar", false),
+            ("foo\tbar", false),
+            ("foo@{1}", false),
+            ("foo.lock", false),
+            ("foo.lock/bar", false),
+            ("foo/.bar", false),
+            (".foo", false),
+            ("foo.", false),
+            ("/foo", false),
+            ("foo/", false),
+            ("foo//bar", false),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(is_valid_ref_name(name), expected, "{}", name);
+        }
+        assert_eq!(
+            valid_ref_name("foo..bar"),
+            Err(UtilError::InvalidReferenceName("foo..bar".to_string()))
+        );
+    }
 }
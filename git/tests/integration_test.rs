@@ -8,6 +8,7 @@ mod tests {
     use git::commands::rm::git_rm;
     use git::commands::status::{get_index_content, git_status};
     use git::util::files::{open_file, read_file};
+    use git::util::object_format::ObjectFormat;
     use git::util::objects::builder_object_blob;
     use std::fs;
     use std::io::Write;
@@ -38,7 +39,10 @@ mod tests {
         let current_branch = get_current_branch(directory).expect("Error al encontrar la branch");
 
         let status_before_add = git_status(directory).expect("Error al obtener el status");
-        let status_msg = format!("On branch {}\nYour branch is up to date with 'origin/{}'.\n\nnothing to commit, working tree clean\n", current_branch, current_branch);
+        let status_msg = format!(
+            "On branch {}\nnothing to commit, working tree clean\n",
+            current_branch
+        );
 
         assert_eq!(status_before_add, status_msg);
 
@@ -71,8 +75,8 @@ mod tests {
         let file = open_file(&file_path).expect("Error al abrir el archivo");
         let content = read_file(file).expect("Error al leer el archivo");
         let git_dir = format!("{}/{}", directory, ".git");
-        let hash_object =
-            builder_object_blob(content, &git_dir).expect("Error al crear el objeto blob");
+        let hash_object = builder_object_blob(content, &git_dir, ObjectFormat::Sha1)
+            .expect("Error al crear el objeto blob");
         let index_content_msg = format!("toremove.txt blob {}", hash_object);
 
         let index_content = get_index_content(&git_dir).expect("Error al leer el index");